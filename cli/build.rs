@@ -5,6 +5,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_build::configure()
         .out_dir("src/api")
         .file_descriptor_set_path("src/api/rockbox_descriptor.bin")
+        // JSON for the scripting bridge.
+        .message_attribute(
+            ".",
+            "#[derive(serde::Serialize, serde::Deserialize)] #[serde(default, rename_all = \"camelCase\")]",
+        )
         .compile_protos(
             &[
                 "proto/rockbox/v1alpha1/bluetooth.proto",
@@ -15,6 +20,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/rockbox/v1alpha1/playback.proto",
                 "proto/rockbox/v1alpha1/playlist.proto",
                 "proto/rockbox/v1alpha1/settings.proto",
+                "proto/rockbox/v1alpha1/smart_playlist.proto",
                 "proto/rockbox/v1alpha1/sound.proto",
                 "proto/rockbox/v1alpha1/system.proto",
             ],
@@ -1,19 +1,14 @@
-use std::env;
-
 use anyhow::Error;
 use owo_colors::OwoColorize;
-use rockbox::api::rockbox::v1alpha1::{
-    bluetooth_service_client::BluetoothServiceClient, BluetoothDevice,
-    ConnectBluetoothDeviceRequest, DisconnectBluetoothDeviceRequest, GetBluetoothDevicesRequest,
-    ScanBluetoothRequest,
+use rockbox::{
+    api::rockbox::v1alpha1::{
+        bluetooth_service_client::BluetoothServiceClient, BluetoothDevice,
+        ConnectBluetoothDeviceRequest, DisconnectBluetoothDeviceRequest,
+        GetBluetoothDevicesRequest, ScanBluetoothRequest,
+    },
+    grpc_channel,
 };
 
-fn grpc_url() -> String {
-    let host = env::var("ROCKBOX_HOST").unwrap_or_else(|_| "localhost".to_string());
-    let port = env::var("ROCKBOX_PORT").unwrap_or_else(|_| "6061".to_string());
-    format!("tcp://{}:{}", host, port)
-}
-
 fn print_devices(devices: &[BluetoothDevice]) {
     if devices.is_empty() {
         println!("No devices found.");
@@ -54,7 +49,7 @@ fn print_devices(devices: &[BluetoothDevice]) {
 }
 
 pub async fn scan(timeout_secs: u64) -> Result<(), Error> {
-    let mut client = BluetoothServiceClient::connect(grpc_url()).await?;
+    let mut client = BluetoothServiceClient::new(grpc_channel().await?);
     let devices = client
         .scan(tonic::Request::new(ScanBluetoothRequest {
             timeout_secs: timeout_secs as u32,
@@ -67,7 +62,7 @@ pub async fn scan(timeout_secs: u64) -> Result<(), Error> {
 }
 
 pub async fn devices() -> Result<(), Error> {
-    let mut client = BluetoothServiceClient::connect(grpc_url()).await?;
+    let mut client = BluetoothServiceClient::new(grpc_channel().await?);
     let devices = client
         .get_devices(tonic::Request::new(GetBluetoothDevicesRequest {}))
         .await?
@@ -78,7 +73,7 @@ pub async fn devices() -> Result<(), Error> {
 }
 
 pub async fn connect(address: &str) -> Result<(), Error> {
-    let mut client = BluetoothServiceClient::connect(grpc_url()).await?;
+    let mut client = BluetoothServiceClient::new(grpc_channel().await?);
     client
         .connect_device(tonic::Request::new(ConnectBluetoothDeviceRequest {
             address: address.to_string(),
@@ -89,7 +84,7 @@ pub async fn connect(address: &str) -> Result<(), Error> {
 }
 
 pub async fn disconnect(address: &str) -> Result<(), Error> {
-    let mut client = BluetoothServiceClient::connect(grpc_url()).await?;
+    let mut client = BluetoothServiceClient::new(grpc_channel().await?);
     client
         .disconnect(tonic::Request::new(DisconnectBluetoothDeviceRequest {
             address: address.to_string(),
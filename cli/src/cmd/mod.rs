@@ -7,6 +7,7 @@ pub mod open;
 pub mod repl;
 pub mod run;
 pub mod scan;
+pub mod scripts;
 pub mod service;
 pub mod setup;
 pub mod start;
@@ -3,7 +3,7 @@ use std::{env, path::Path, thread};
 use anyhow::{anyhow, Error};
 use rockbox::{
     api::rockbox::v1alpha1::{playback_service_client::PlaybackServiceClient, PlayTrackRequest},
-    install_rockboxd, wait_for_rockboxd,
+    grpc_channel, install_rockboxd, wait_for_rockboxd,
 };
 
 use super::start::start;
@@ -18,12 +18,11 @@ pub async fn open(path_or_url: &str) -> Result<(), Error> {
         }
     });
 
-    let host = env::var("ROCKBOX_HOST").unwrap_or_else(|_| "localhost".to_string());
     let port = env::var("ROCKBOX_PORT").unwrap_or_else(|_| "6061".to_string());
 
     wait_for_rockboxd(port.parse()?, None)?;
 
-    let mut client = PlaybackServiceClient::connect(format!("tcp://{}:{}", host, port)).await?;
+    let mut client = PlaybackServiceClient::new(grpc_channel().await?);
     client
         .play_track(tonic::Request::new(PlayTrackRequest {
             path: normalize_path_or_url(path_or_url)?,
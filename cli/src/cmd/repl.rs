@@ -1,13 +1,20 @@
-use std::thread;
+use std::{ffi::OsString, thread};
+
+use anyhow::Error;
+use rockbox::scripting;
 
 macro_rules! svec {
     ($($x:expr),*) => (vec![$($x.to_string().into()), *])
 }
 
-pub fn repl() {
-    let handle = thread::spawn(|| match deno::cli(svec!["deno", "repl", "-A"]) {
+pub fn repl() -> Result<(), Error> {
+    let mut args: Vec<OsString> = svec!["deno", "repl", "-A"];
+    args.extend(scripting::deno_flags()?);
+    args.push("--eval=globalThis.rockbox = await import(\"rockbox\");".into());
+    let handle = thread::spawn(|| match deno::cli(args) {
         Ok(_) => {}
         Err(_) => {}
     });
     handle.join().unwrap();
+    Ok(())
 }
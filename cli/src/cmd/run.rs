@@ -1,9 +1,15 @@
 use std::{ffi::OsString, thread};
 
-pub fn run(args: Vec<OsString>) {
+use anyhow::Error;
+use rockbox::scripting;
+
+pub fn run(mut args: Vec<OsString>) -> Result<(), Error> {
+    // `deno run [OPTIONS] <SCRIPT_ARG>...`: ours go first.
+    args.splice(2..2, scripting::deno_flags()?);
     let handle = thread::spawn(move || match deno::cli(args) {
         Ok(_) => {}
         Err(_) => {}
     });
     handle.join().unwrap();
+    Ok(())
 }
@@ -3,7 +3,7 @@ use std::{env, thread};
 use anyhow::Error;
 use rockbox::{
     api::rockbox::v1alpha1::{library_service_client::LibraryServiceClient, ScanLibraryRequest},
-    install_rockboxd, wait_for_rockboxd,
+    grpc_channel, install_rockboxd, wait_for_rockboxd,
 };
 
 use super::start::*;
@@ -17,13 +17,11 @@ pub async fn scan(path: Option<String>, rebuild_index: Option<bool>) -> Result<(
         }
     });
 
-    let host = env::var("ROCKBOX_HOST").unwrap_or_else(|_| "localhost".to_string());
     let port = env::var("ROCKBOX_PORT").unwrap_or_else(|_| "6061".to_string());
 
     wait_for_rockboxd(port.parse()?, None)?;
 
-    let url = format!("tcp://{}:{}", host, port);
-    let mut client = LibraryServiceClient::connect(url).await?;
+    let mut client = LibraryServiceClient::new(grpc_channel().await?);
     let request = tonic::Request::new(ScanLibraryRequest {
         path,
         rebuild_index,
@@ -0,0 +1,21 @@
+use std::thread;
+
+use anyhow::Error;
+use rockbox::scripting;
+
+/// Load every script in the scripts directory into one runtime and keep it
+/// running for their hooks.
+pub fn scripts() -> Result<(), Error> {
+    let scripts = scripting::scripts()?;
+    if scripts.is_empty() {
+        println!("No scripts in {}", scripting::scripts_dir().display());
+        return Ok(());
+    }
+    let args = scripting::host_args(&scripts)?;
+    let handle = thread::spawn(move || match deno::cli(args) {
+        Ok(_) => {}
+        Err(_) => {}
+    });
+    handle.join().unwrap();
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::{env, process::Command};
 
 use anyhow::Error;
-use rockbox::{install_rockboxd, wait_for_rockboxd};
+use rockbox::{install_rockboxd, scripting, wait_for_rockboxd};
 
 pub fn start(with_ui: bool) -> Result<(), Error> {
     let video_driver = std::env::var("SDL_VIDEODRIVER").unwrap_or_else(|_| "dummy".to_string());
@@ -35,7 +35,17 @@ pub fn start(with_ui: bool) -> Result<(), Error> {
                 )
                 .spawn()?;
 
+            // The hooks reconnect on their own once rockboxd is up.
+            let mut hooks = match scripting::scripts()?.is_empty() {
+                true => None,
+                false => Some(Command::new(env::current_exe()?).arg("scripts").spawn()?),
+            };
+
             child.wait()?;
+
+            if let Some(hooks) = hooks.as_mut() {
+                hooks.kill()?;
+            }
         }
     };
     Ok(())
@@ -4,6 +4,12 @@ use std::{
 };
 
 use anyhow::Error;
+use tonic::{
+    codegen::InterceptedService,
+    service::Interceptor,
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Uri},
+    Request, Status,
+};
 
 pub mod api {
     #[path = ""]
@@ -14,6 +20,8 @@ pub mod api {
     }
 }
 
+pub mod scripting;
+
 pub fn install_rockboxd() -> Result<(), Error> {
     let mut child = Command::new("sh")
         .arg("-c")
@@ -48,6 +56,72 @@ pub fn wait_for_rockboxd(port: u32, timeout: Option<u32>) -> Result<(), Error> {
     Ok(())
 }
 
+/// gRPC URL from `ROCKBOX_GRPC_URL`, or `tcp://$ROCKBOX_HOST:$ROCKBOX_PORT`.
+/// Use `https://host:7061` to go through rockboxd's TLS listener.
+pub fn grpc_url() -> String {
+    if let Ok(url) = env::var("ROCKBOX_GRPC_URL") {
+        return url;
+    }
+    let host = env::var("ROCKBOX_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = env::var("ROCKBOX_PORT").unwrap_or_else(|_| "6061".to_string());
+    format!("tcp://{}:{}", host, port)
+}
+
+/// API token sent with every gRPC call: `ROCKBOX_TOKEN`, or else the one
+/// the local rockboxd writes to `~/.config/rockbox.org/local-token` when it
+/// starts.
+pub fn api_token() -> Option<String> {
+    env::var("ROCKBOX_TOKEN")
+        .ok()
+        .or_else(|| {
+            let home = env::var("HOME").ok()?;
+            std::fs::read_to_string(format!("{}/.config/rockbox.org/local-token", home)).ok()
+        })
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Adds `authorization: Bearer <token>` to every call when there is a token.
+/// [`api_token`] is read again for each call, so a channel kept open (the
+/// scripting bridge's) follows rockboxd restarts, which change its token.
+#[derive(Clone)]
+pub struct Authorize;
+
+impl Interceptor for Authorize {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = api_token().and_then(|token| format!("Bearer {}", token).parse().ok());
+        if let Some(value) = token {
+            request.metadata_mut().insert("authorization", value);
+        }
+        Ok(request)
+    }
+}
+
+pub type GrpcChannel = InterceptedService<Channel, Authorize>;
+
+/// Channel to the gRPC server at [`grpc_url`], authenticated with
+/// [`api_token`]. `https://` trusts the system roots plus `ROCKBOX_TLS_CA`,
+/// or the self-signed certificate rockboxd generates when that variable is
+/// unset.
+pub async fn grpc_channel() -> Result<GrpcChannel, Error> {
+    let uri: Uri = grpc_url().parse()?;
+    let mut endpoint = Endpoint::from(uri.clone());
+    if uri.scheme_str() == Some("https") {
+        let mut config = ClientTlsConfig::new().with_native_roots();
+        let ca = env::var("ROCKBOX_TLS_CA").or_else(|_| {
+            env::var("HOME").map(|home| format!("{}/.config/rockbox.org/tls/cert.pem", home))
+        });
+        if let Some(pem) = ca.ok().and_then(|path| std::fs::read(path).ok()) {
+            config = config.ca_certificate(Certificate::from_pem(pem));
+        }
+        endpoint = endpoint.tls_config(config)?;
+    }
+    Ok(InterceptedService::new(
+        endpoint.connect().await?,
+        Authorize,
+    ))
+}
+
 pub fn setup_pkgx() -> Result<(), Error> {
     let path = format!(
         "{}:{}",
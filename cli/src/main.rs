@@ -5,8 +5,8 @@ use clap::{arg, Command};
 use owo_colors::OwoColorize;
 
 use cmd::{
-    clear::*, community::*, login::*, open::*, repl::*, run::*, scan::*, service, start::*,
-    webui::*, whoami::*,
+    clear::*, community::*, login::*, open::*, repl::*, run::*, scan::*, scripts::*, service,
+    start::*, webui::*, whoami::*,
 };
 
 use crate::cmd::setup;
@@ -65,6 +65,9 @@ fn cli() -> Command {
                 .about("Run a JavaScript or TypeScript program")
                 .visible_alias("x"),
         )
+        .subcommand(
+            Command::new("scripts").about("Run the hook scripts in ~/.config/rockbox.org/scripts"),
+        )
         .subcommand(
             Command::new("open")
                 .arg(arg!(<PATH_OR_URL> "Local file path or HTTP URL to play"))
@@ -157,7 +160,7 @@ async fn main() -> Result<(), Error> {
             })
             .collect::<Vec<OsString>>();
 
-        run(_args);
+        run(_args)?;
         return Ok(());
     }
 
@@ -182,7 +185,10 @@ async fn main() -> Result<(), Error> {
             webui()?;
         }
         Some(("repl", _)) => {
-            repl();
+            repl()?;
+        }
+        Some(("scripts", _)) => {
+            scripts()?;
         }
         Some(("open", args)) => {
             let path_or_url = args.get_one::<String>("PATH_OR_URL").unwrap();
@@ -0,0 +1,391 @@
+//! Loopback HTTP front for the gRPC clients, which is what the `rockbox`
+//! module talks to from inside Deno.
+//!
+//! `POST /rpc/{Service}/{Method}` takes the request message as JSON
+//! (camelCase fields, anything left out defaults like in protobuf) and
+//! answers with the response message. `GET /events/{topic}` relays one of
+//! the server streams as server-sent events. Every request must carry the
+//! bridge's token as a bearer token.
+
+use std::{convert::Infallible, net::SocketAddr};
+
+use anyhow::{anyhow, Error};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+use tonic::codegen::tokio_stream::{self, StreamExt};
+use uuid::Uuid;
+use warp::{http::StatusCode, sse::Event, Filter, Reply};
+
+use crate::{
+    api::rockbox::v1alpha1::{
+        library_service_client::LibraryServiceClient,
+        playback_service_client::PlaybackServiceClient,
+        playlist_service_client::PlaylistServiceClient,
+        settings_service_client::SettingsServiceClient,
+        smart_playlist_service_client::SmartPlaylistServiceClient,
+        sound_service_client::SoundServiceClient, *,
+    },
+    grpc_channel, GrpcChannel,
+};
+
+static CHANNEL: OnceCell<GrpcChannel> = OnceCell::const_new();
+
+pub struct Bridge {
+    pub addr: SocketAddr,
+    pub token: String,
+}
+
+/// Bind the bridge to an ephemeral loopback port and serve it on the current
+/// runtime. rockboxd doesn't have to be up yet: the gRPC channel is opened
+/// on the first call.
+pub fn serve() -> Result<Bridge, Error> {
+    let token = token();
+    let (addr, server) = warp::serve(routes(&token)).try_bind_ephemeral(([127, 0, 0, 1], 0))?;
+    tokio::spawn(server);
+
+    Ok(Bridge { addr, token })
+}
+
+/// `/rpc` and `/events`, answering only requests that carry `token`.
+fn routes(token: &str) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let auth = warp::header::exact("authorization", leak(format!("Bearer {}", token)));
+
+    let rpc = warp::post()
+        .and(warp::path!("rpc" / String / String))
+        .and(warp::body::json())
+        .and_then(|service: String, method: String, body: Value| async move {
+            let reply = match call(&service, &method, body).await {
+                Ok(response) => {
+                    warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)
+                }
+                Err(e) => {
+                    let upstream = e.is::<tonic::Status>() || e.is::<tonic::transport::Error>();
+                    let status = match upstream {
+                        true => StatusCode::BAD_GATEWAY,
+                        false => StatusCode::BAD_REQUEST,
+                    };
+                    let body = serde_json::json!({ "error": e.to_string() });
+                    warp::reply::with_status(warp::reply::json(&body), status)
+                }
+            };
+            Ok::<_, Infallible>(reply)
+        });
+
+    let events =
+        warp::get()
+            .and(warp::path!("events" / String))
+            .and_then(|topic: String| async move {
+                let reply = match subscribe(&topic).await {
+                    Ok(events) => {
+                        warp::sse::reply(warp::sse::keep_alive().stream(events)).into_response()
+                    }
+                    Err(e) => {
+                        let body = serde_json::json!({ "error": e.to_string() });
+                        warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_GATEWAY)
+                            .into_response()
+                    }
+                };
+                Ok::<_, Infallible>(reply)
+            });
+
+    auth.and(rpc.or(events))
+}
+
+async fn channel() -> Result<GrpcChannel, Error> {
+    let channel = CHANNEL.get_or_try_init(grpc_channel).await?;
+    Ok(channel.clone())
+}
+
+/// Forward a JSON request to `service`'s `method`. The request is decoded
+/// before rockboxd is contacted, so bad input fails without a connection.
+async fn call(service: &str, method: &str, body: Value) -> Result<Value, Error> {
+    macro_rules! rpc {
+        ($client:ident, $method:ident, $request:ty) => {{
+            let request: $request = serde_json::from_value(body)?;
+            let response = $client::new(channel().await?).$method(request).await?;
+            serde_json::to_value(response.into_inner())?
+        }};
+    }
+
+    let response = match (service, method) {
+        ("PlaybackService", "Play") => rpc!(PlaybackServiceClient, play, PlayRequest),
+        ("PlaybackService", "Pause") => rpc!(PlaybackServiceClient, pause, PauseRequest),
+        ("PlaybackService", "PlayOrPause") => {
+            rpc!(PlaybackServiceClient, play_or_pause, PlayOrPauseRequest)
+        }
+        ("PlaybackService", "Resume") => rpc!(PlaybackServiceClient, resume, ResumeRequest),
+        ("PlaybackService", "Next") => rpc!(PlaybackServiceClient, next, NextRequest),
+        ("PlaybackService", "Previous") => rpc!(PlaybackServiceClient, previous, PreviousRequest),
+        ("PlaybackService", "FastForwardRewind") => rpc!(
+            PlaybackServiceClient,
+            fast_forward_rewind,
+            FastForwardRewindRequest
+        ),
+        ("PlaybackService", "Status") => rpc!(PlaybackServiceClient, status, StatusRequest),
+        ("PlaybackService", "CurrentTrack") => {
+            rpc!(PlaybackServiceClient, current_track, CurrentTrackRequest)
+        }
+        ("PlaybackService", "NextTrack") => {
+            rpc!(PlaybackServiceClient, next_track, NextTrackRequest)
+        }
+        ("PlaybackService", "HardStop") => rpc!(PlaybackServiceClient, hard_stop, HardStopRequest),
+        ("PlaybackService", "PlayTrack") => {
+            rpc!(PlaybackServiceClient, play_track, PlayTrackRequest)
+        }
+        ("PlaybackService", "PlayAlbum") => {
+            rpc!(PlaybackServiceClient, play_album, PlayAlbumRequest)
+        }
+        ("PlaybackService", "PlayArtistTracks") => rpc!(
+            PlaybackServiceClient,
+            play_artist_tracks,
+            PlayArtistTracksRequest
+        ),
+        ("PlaybackService", "PlayPlaylist") => {
+            rpc!(PlaybackServiceClient, play_playlist, PlayPlaylistRequest)
+        }
+        ("PlaybackService", "PlayDirectory") => {
+            rpc!(PlaybackServiceClient, play_directory, PlayDirectoryRequest)
+        }
+        ("PlaybackService", "PlayLikedTracks") => rpc!(
+            PlaybackServiceClient,
+            play_liked_tracks,
+            PlayLikedTracksRequest
+        ),
+        ("PlaybackService", "PlayAllTracks") => {
+            rpc!(PlaybackServiceClient, play_all_tracks, PlayAllTracksRequest)
+        }
+        ("PlaylistService", "GetCurrent") => {
+            rpc!(PlaylistServiceClient, get_current, GetCurrentRequest)
+        }
+        ("PlaylistService", "Amount") => rpc!(PlaylistServiceClient, amount, AmountRequest),
+        ("PlaylistService", "Start") => rpc!(PlaylistServiceClient, start, StartRequest),
+        ("PlaylistService", "InsertTracks") => {
+            rpc!(PlaylistServiceClient, insert_tracks, InsertTracksRequest)
+        }
+        ("PlaylistService", "InsertDirectory") => rpc!(
+            PlaylistServiceClient,
+            insert_directory,
+            InsertDirectoryRequest
+        ),
+        ("PlaylistService", "InsertAlbum") => {
+            rpc!(PlaylistServiceClient, insert_album, InsertAlbumRequest)
+        }
+        ("PlaylistService", "InsertArtistTracks") => rpc!(
+            PlaylistServiceClient,
+            insert_artist_tracks,
+            InsertArtistTracksRequest
+        ),
+        ("PlaylistService", "RemoveTracks") => {
+            rpc!(PlaylistServiceClient, remove_tracks, RemoveTracksRequest)
+        }
+        ("PlaylistService", "RemoveAllTracks") => rpc!(
+            PlaylistServiceClient,
+            remove_all_tracks,
+            RemoveAllTracksRequest
+        ),
+        ("PlaylistService", "ShufflePlaylist") => rpc!(
+            PlaylistServiceClient,
+            shuffle_playlist,
+            ShufflePlaylistRequest
+        ),
+        ("LibraryService", "GetAlbums") => rpc!(LibraryServiceClient, get_albums, GetAlbumsRequest),
+        ("LibraryService", "GetArtists") => {
+            rpc!(LibraryServiceClient, get_artists, GetArtistsRequest)
+        }
+        ("LibraryService", "GetTracks") => rpc!(LibraryServiceClient, get_tracks, GetTracksRequest),
+        ("LibraryService", "GetAlbum") => rpc!(LibraryServiceClient, get_album, GetAlbumRequest),
+        ("LibraryService", "GetArtist") => rpc!(LibraryServiceClient, get_artist, GetArtistRequest),
+        ("LibraryService", "GetTrack") => rpc!(LibraryServiceClient, get_track, GetTrackRequest),
+        ("LibraryService", "LikeTrack") => rpc!(LibraryServiceClient, like_track, LikeTrackRequest),
+        ("LibraryService", "UnlikeTrack") => {
+            rpc!(LibraryServiceClient, unlike_track, UnlikeTrackRequest)
+        }
+        ("LibraryService", "GetLikedTracks") => {
+            rpc!(
+                LibraryServiceClient,
+                get_liked_tracks,
+                GetLikedTracksRequest
+            )
+        }
+        ("LibraryService", "Search") => rpc!(LibraryServiceClient, search, SearchRequest),
+        ("LibraryService", "GetLyrics") => rpc!(LibraryServiceClient, get_lyrics, GetLyricsRequest),
+        ("LibraryService", "GetRating") => rpc!(LibraryServiceClient, get_rating, GetRatingRequest),
+        ("LibraryService", "SetRating") => rpc!(LibraryServiceClient, set_rating, SetRatingRequest),
+        ("SmartPlaylistService", "GetSmartPlaylists") => rpc!(
+            SmartPlaylistServiceClient,
+            get_smart_playlists,
+            GetSmartPlaylistsRequest
+        ),
+        ("SmartPlaylistService", "GetSmartPlaylist") => rpc!(
+            SmartPlaylistServiceClient,
+            get_smart_playlist,
+            GetSmartPlaylistRequest
+        ),
+        ("SmartPlaylistService", "CreateSmartPlaylist") => rpc!(
+            SmartPlaylistServiceClient,
+            create_smart_playlist,
+            CreateSmartPlaylistRequest
+        ),
+        ("SmartPlaylistService", "UpdateSmartPlaylist") => rpc!(
+            SmartPlaylistServiceClient,
+            update_smart_playlist,
+            UpdateSmartPlaylistRequest
+        ),
+        ("SmartPlaylistService", "DeleteSmartPlaylist") => rpc!(
+            SmartPlaylistServiceClient,
+            delete_smart_playlist,
+            DeleteSmartPlaylistRequest
+        ),
+        ("SmartPlaylistService", "GetSmartPlaylistTracks") => rpc!(
+            SmartPlaylistServiceClient,
+            get_smart_playlist_tracks,
+            GetSmartPlaylistTracksRequest
+        ),
+        ("SmartPlaylistService", "PlaySmartPlaylist") => rpc!(
+            SmartPlaylistServiceClient,
+            play_smart_playlist,
+            PlaySmartPlaylistRequest
+        ),
+        ("SmartPlaylistService", "GetTrackStats") => rpc!(
+            SmartPlaylistServiceClient,
+            get_track_stats,
+            GetTrackStatsRequest
+        ),
+        ("SoundService", "AdjustVolume") => {
+            rpc!(SoundServiceClient, adjust_volume, AdjustVolumeRequest)
+        }
+        ("SoundService", "GetPitch") => rpc!(SoundServiceClient, get_pitch, GetPitchRequest),
+        ("SoundService", "SetPitch") => rpc!(SoundServiceClient, set_pitch, SetPitchRequest),
+        ("SettingsService", "GetGlobalSettings") => rpc!(
+            SettingsServiceClient,
+            get_global_settings,
+            GetGlobalSettingsRequest
+        ),
+        ("SettingsService", "SaveSettings") => {
+            rpc!(SettingsServiceClient, save_settings, SaveSettingsRequest)
+        }
+        _ => return Err(anyhow!("unknown method {}/{}", service, method)),
+    };
+    Ok(response)
+}
+
+type Events = std::pin::Pin<Box<dyn tokio_stream::Stream<Item = Result<Event, Infallible>> + Send>>;
+
+/// Open the server stream behind `topic`. The SSE stream ends when the gRPC
+/// one does; the module reconnects.
+async fn subscribe(topic: &str) -> Result<Events, Error> {
+    macro_rules! relay {
+        ($client:ident, $method:ident, $request:expr) => {{
+            let stream = $client::new(channel().await?)
+                .$method($request)
+                .await?
+                .into_inner();
+            Box::pin(stream.map_while(|message| {
+                let message = message.ok()?;
+                let data = serde_json::to_string(&message).ok()?;
+                Some(Ok(Event::default().data(data)))
+            })) as Events
+        }};
+    }
+
+    let events = match topic {
+        "currentTrack" => relay!(
+            PlaybackServiceClient,
+            stream_current_track,
+            StreamCurrentTrackRequest {}
+        ),
+        "status" => relay!(PlaybackServiceClient, stream_status, StreamStatusRequest {}),
+        "playlist" => relay!(
+            PlaybackServiceClient,
+            stream_playlist,
+            StreamPlaylistRequest {}
+        ),
+        "smartPlaylists" => relay!(
+            SmartPlaylistServiceClient,
+            stream_smart_playlist_changes,
+            StreamSmartPlaylistChangesRequest { id: None }
+        ),
+        _ => return Err(anyhow!("unknown topic {}", topic)),
+    };
+    Ok(events)
+}
+
+/// Two v4 UUIDs, 244 bits from the OS random number generator, to keep
+/// other local users and web pages off the bridge.
+fn token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// `warp::header::exact` wants a `&'static str`; there is one bridge per
+/// process.
+fn leak(value: String) -> &'static str {
+    Box::leak(value.into_boxed_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn requests_decode_from_camel_case_json_with_defaults() {
+        let request: InsertTracksRequest = serde_json::from_value(json!({
+            "playlistId": "p1",
+            "tracks": ["/music/a.flac", "/music/b.flac"],
+        }))
+        .unwrap();
+        assert_eq!(request.playlist_id.as_deref(), Some("p1"));
+        assert_eq!(request.tracks, ["/music/a.flac", "/music/b.flac"]);
+        assert_eq!(request.position, 0);
+        assert_eq!(request.shuffle, None);
+
+        let request: FastForwardRewindRequest =
+            serde_json::from_value(json!({ "newTime": 90000 })).unwrap();
+        assert_eq!(request.new_time, 90000);
+        let request: SetPitchRequest = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(request.value, 0);
+
+        assert!(serde_json::from_value::<SetPitchRequest>(json!({ "value": "fast" })).is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_methods_and_topics_are_errors() {
+        let error = call("PlaybackService", "Rewind", json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "unknown method PlaybackService/Rewind");
+        let error = call("RadioService", "Play", json!({})).await.unwrap_err();
+        assert_eq!(error.to_string(), "unknown method RadioService/Play");
+
+        let error = subscribe("volume").await.err().unwrap();
+        assert_eq!(error.to_string(), "unknown topic volume");
+    }
+
+    #[tokio::test]
+    async fn requests_need_the_bridge_token() {
+        let routes = routes("secret");
+        let request = |authorization: Option<&str>| {
+            let request = warp::test::request()
+                .method("POST")
+                .path("/rpc/PlaybackService/Rewind")
+                .json(&json!({}));
+            match authorization {
+                Some(value) => request.header("authorization", value),
+                None => request,
+            }
+        };
+
+        for authorization in [None, Some("Bearer guess"), Some("secret")] {
+            let response = request(authorization).reply(&routes).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = String::from_utf8_lossy(response.body()).to_string();
+            assert!(body.contains("authorization"), "{}", body);
+        }
+
+        // Past the token check the request reaches the method lookup.
+        let response = request(Some("Bearer secret")).reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["error"], "unknown method PlaybackService/Rewind");
+    }
+}
@@ -0,0 +1,11 @@
+// Loads the hook scripts given as arguments into one runtime. A script that
+// fails to load is reported and skipped; the others keep running.
+
+for (const path of Deno.args) {
+  try {
+    await import(new URL(path, "file:///").href);
+    console.log(`rockbox: loaded ${path}`);
+  } catch (e) {
+    console.error(`rockbox: ${path}:`, e);
+  }
+}
@@ -0,0 +1,105 @@
+//! Scripting on top of the embedded Deno runtime.
+//!
+//! Programs run with `rockbox run`, the REPL and the hooks in
+//! `~/.config/rockbox.org/scripts` can `import ... from "rockbox"`: the
+//! module is written next to an import map under
+//! `~/.config/rockbox.org/deno`, and reaches rockboxd through a
+//! [`bridge`] started in the CLI process.
+
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Error;
+
+pub mod bridge;
+
+const MODULE: &str = include_str!("rockbox.ts");
+const HOST: &str = include_str!("host.ts");
+
+fn config_dir() -> PathBuf {
+    let mut dir = dirs::home_dir().unwrap();
+    dir.push(".config/rockbox.org");
+    dir
+}
+
+/// Where hook scripts are picked up from.
+pub fn scripts_dir() -> PathBuf {
+    config_dir().join("scripts")
+}
+
+/// `.ts` and `.js` files in [`scripts_dir`], in name order.
+pub fn scripts() -> Result<Vec<PathBuf>, Error> {
+    let dir = scripts_dir();
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut scripts = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("ts" | "js" | "mts" | "mjs")
+                )
+        })
+        .collect::<Vec<_>>();
+    scripts.sort();
+    Ok(scripts)
+}
+
+/// Write `contents` to `path` unless it's already there.
+fn write_if_changed(path: &Path, contents: &str) -> Result<(), Error> {
+    if fs::read_to_string(path).ok().as_deref() != Some(contents) {
+        fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+/// Write the module, the hook loader and the import map, returning the
+/// directory they're in.
+fn install() -> Result<PathBuf, Error> {
+    let dir = config_dir().join("deno");
+    fs::create_dir_all(&dir)?;
+    let module = dir.join("rockbox.ts");
+    write_if_changed(&module, MODULE)?;
+    write_if_changed(&dir.join("host.ts"), HOST)?;
+    let import_map = serde_json::json!({
+        "imports": { "rockbox": format!("file://{}", module.display()) }
+    });
+    write_if_changed(
+        &dir.join("import_map.json"),
+        &serde_json::to_string_pretty(&import_map)?,
+    )?;
+    Ok(dir)
+}
+
+/// Start the bridge and return the flags `deno run` or `deno repl` need for
+/// `rockbox` to be importable and reach it. Must be called on a Tokio
+/// runtime, which has to outlive the Deno program.
+pub fn deno_flags() -> Result<Vec<OsString>, Error> {
+    let dir = install()?;
+    let bridge = bridge::serve()?;
+    // Deno runs in this process and reads the environment from here.
+    env::set_var("ROCKBOX_SCRIPT_BRIDGE", format!("http://{}", bridge.addr));
+    env::set_var("ROCKBOX_SCRIPT_TOKEN", &bridge.token);
+
+    Ok(vec![
+        format!("--import-map={}", dir.join("import_map.json").display()).into(),
+        format!("--allow-net={}", bridge.addr).into(),
+        "--allow-env=ROCKBOX_SCRIPT_BRIDGE,ROCKBOX_SCRIPT_TOKEN".into(),
+    ])
+}
+
+/// Arguments for a `deno run` of the hook loader over [`scripts`]. Hooks are
+/// the user's own code, so they get every permission, like the REPL.
+pub fn host_args(scripts: &[PathBuf]) -> Result<Vec<OsString>, Error> {
+    let mut args: Vec<OsString> = vec!["deno".into(), "run".into(), "-A".into()];
+    args.extend(deno_flags()?);
+    args.push(config_dir().join("deno/host.ts").into());
+    args.extend(scripts.iter().map(|path| path.into()));
+    Ok(args)
+}
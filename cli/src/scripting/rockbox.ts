@@ -0,0 +1,453 @@
+// The `rockbox` module for scripts run with `rockbox run`, the REPL and the
+// hooks in ~/.config/rockbox.org/scripts.
+//
+//   import { library, on, playback, sound } from "rockbox";
+//
+//   on("trackChange", async (track) => {
+//     await sound.setSpeed(track.genre === "Podcast" ? 1.25 : 1);
+//   });
+//
+// Calls go to rockboxd's gRPC API through a loopback bridge the CLI starts
+// next to the runtime; its address and token are in the environment.
+
+const BRIDGE = Deno.env.get("ROCKBOX_SCRIPT_BRIDGE");
+const TOKEN = Deno.env.get("ROCKBOX_SCRIPT_TOKEN");
+
+export interface Track {
+  id: string;
+  path: string;
+  title: string;
+  artist: string;
+  album: string;
+  albumArtist: string;
+  bitrate: number;
+  composer: string;
+  discNumber: number;
+  filesize: number;
+  frequency: number;
+  length: number;
+  trackNumber: number;
+  year: number;
+  yearString: string;
+  genre: string;
+  md5: string;
+  albumArt?: string;
+  artistId?: string;
+  albumId?: string;
+  genreId?: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface Album {
+  id: string;
+  title: string;
+  artist: string;
+  year: number;
+  yearString: string;
+  albumArt?: string;
+  md5: string;
+  artistId: string;
+  label?: string;
+  copyrightMessage?: string;
+  tracks: Track[];
+}
+
+export interface Artist {
+  id: string;
+  name: string;
+  bio?: string;
+  image?: string;
+  albums: Album[];
+  tracks: Track[];
+  genres?: string;
+}
+
+/** The track being played, as reported by the playback engine. */
+export interface CurrentTrack {
+  id: string;
+  title: string;
+  artist: string;
+  album: string;
+  genre: string;
+  disc: string;
+  trackString: string;
+  yearString: string;
+  composer: string;
+  comment: string;
+  albumArtist: string;
+  grouping: string;
+  discnum: number;
+  tracknum: number;
+  layer: number;
+  year: number;
+  bitrate: number;
+  frequency: number;
+  filesize: number;
+  /** Milliseconds. */
+  length: number;
+  /** Milliseconds. */
+  elapsed: number;
+  path: string;
+  albumArt?: string;
+  albumId: string;
+  artistId: string;
+}
+
+export type Status = "stopped" | "playing" | "paused";
+
+export interface Queue {
+  index: number;
+  amount: number;
+  tracks: CurrentTrack[];
+}
+
+export interface SearchResults {
+  tracks: Track[];
+  albums: Album[];
+  artists: Artist[];
+  playlists: {
+    id: string;
+    name: string;
+    description?: string;
+    image?: string;
+    isSmart: boolean;
+    trackCount: number;
+  }[];
+}
+
+export interface Lyrics {
+  found: boolean;
+  synced: boolean;
+  source: string;
+  lines: { startMs?: number; text: string }[];
+}
+
+export interface RuleCondition {
+  field: string;
+  operator: string;
+  value?: string;
+  value2?: string;
+  unit?: string;
+}
+
+export interface RuleCriteria {
+  matchType: "all" | "any";
+  conditions: RuleCondition[];
+  limit?: number;
+  sortBy?: string;
+  sortOrder?: "asc" | "desc";
+}
+
+export interface SmartPlaylist {
+  id: string;
+  name: string;
+  description?: string;
+  image?: string;
+  folderId?: string;
+  isSystem: boolean;
+  rules?: RuleCriteria;
+  createdAt: number;
+  updatedAt: number;
+  trackCount: number;
+}
+
+export interface SmartPlaylistChange {
+  playlistId: string;
+  added: string[];
+  removed: string[];
+}
+
+export interface TrackStats {
+  trackId: string;
+  playCount: number;
+  skipCount: number;
+  lastPlayed?: number;
+  lastSkipped?: number;
+  updatedAt: number;
+}
+
+/** Where `queue.add*` puts things; same values as Rockbox's playlist.h. */
+export const Position = {
+  Prepend: -1,
+  /** After the current track, or after what was last inserted there. */
+  Insert: -2,
+  Last: -3,
+  /** Right after the current track. */
+  Next: -4,
+  Shuffled: -5,
+  Replace: -6,
+  LastShuffled: -7,
+} as const;
+
+export class RockboxError extends Error {
+  constructor(readonly method: string, message: string, readonly status: number) {
+    super(`${method}: ${message}`);
+    this.name = "RockboxError";
+  }
+}
+
+function bridge(): string {
+  if (!BRIDGE) {
+    throw new Error("rockbox: run this with `rockbox run`, `rockbox repl` or from the scripts directory");
+  }
+  return BRIDGE;
+}
+
+const headers = () => ({
+  authorization: `Bearer ${TOKEN}`,
+  "content-type": "application/json",
+});
+
+/**
+ * Call any method the bridge exposes, e.g.
+ * `call("PlaybackService", "FastForwardRewind", { newTime: 30_000 })`.
+ * Fields are the proto ones in camelCase.
+ */
+export async function call<T = Record<string, unknown>>(
+  service: string,
+  method: string,
+  request: Record<string, unknown> = {},
+): Promise<T> {
+  const res = await fetch(`${bridge()}/rpc/${service}/${method}`, {
+    method: "POST",
+    headers: headers(),
+    body: JSON.stringify(request),
+  });
+  const body = await res.json();
+  if (!res.ok) {
+    throw new RockboxError(`${service}/${method}`, body.error ?? res.statusText, res.status);
+  }
+  return body as T;
+}
+
+const STATUS: Record<number, Status> = { 0: "stopped", 1: "playing", 3: "paused" };
+
+export const playback = {
+  play: (elapsed = 0, offset = 0) => call("PlaybackService", "Play", { elapsed, offset }).then(() => {}),
+  pause: () => call("PlaybackService", "Pause").then(() => {}),
+  resume: () => call("PlaybackService", "Resume").then(() => {}),
+  playOrPause: () => call("PlaybackService", "PlayOrPause").then(() => {}),
+  next: () => call("PlaybackService", "Next").then(() => {}),
+  previous: () => call("PlaybackService", "Previous").then(() => {}),
+  stop: () => call("PlaybackService", "HardStop").then(() => {}),
+  /** Jump to `ms` into the current track. */
+  seek: (ms: number) =>
+    call("PlaybackService", "FastForwardRewind", { newTime: Math.round(ms) }).then(() => {}),
+  status: async (): Promise<Status> => {
+    const { status } = await call<{ status: number }>("PlaybackService", "Status");
+    return STATUS[status] ?? "stopped";
+  },
+  currentTrack: () => call<CurrentTrack>("PlaybackService", "CurrentTrack"),
+  nextTrack: () => call<CurrentTrack>("PlaybackService", "NextTrack"),
+  /** Play a file path or an http(s) URL. */
+  playTrack: (path: string) => call("PlaybackService", "PlayTrack", { path }).then(() => {}),
+  playAlbum: (albumId: string, shuffle?: boolean) =>
+    call("PlaybackService", "PlayAlbum", { albumId, shuffle }).then(() => {}),
+  playArtist: (artistId: string, shuffle?: boolean) =>
+    call("PlaybackService", "PlayArtistTracks", { artistId, shuffle }).then(() => {}),
+  playPlaylist: (playlistId: string, shuffle?: boolean) =>
+    call("PlaybackService", "PlayPlaylist", { playlistId, shuffle }).then(() => {}),
+  playDirectory: (path: string, options: { shuffle?: boolean; recurse?: boolean } = {}) =>
+    call("PlaybackService", "PlayDirectory", { path, ...options }).then(() => {}),
+  playLikedTracks: (shuffle?: boolean) =>
+    call("PlaybackService", "PlayLikedTracks", { shuffle }).then(() => {}),
+  playAllTracks: (shuffle?: boolean) =>
+    call("PlaybackService", "PlayAllTracks", { shuffle }).then(() => {}),
+};
+
+export const queue = {
+  get: () => call<Queue>("PlaylistService", "GetCurrent"),
+  length: async () => (await call<{ amount: number }>("PlaylistService", "Amount")).amount,
+  /** Start playing the queue from `index`. */
+  start: (index = 0) => call("PlaylistService", "Start", { startIndex: index }).then(() => {}),
+  /** Add file paths or URLs. */
+  add: (tracks: string[], position: number = Position.Last, shuffle?: boolean) =>
+    call("PlaylistService", "InsertTracks", { tracks, position, shuffle }).then(() => {}),
+  addDirectory: (directory: string, position: number = Position.Last, recurse = true) =>
+    call("PlaylistService", "InsertDirectory", { directory, position, recurse }).then(() => {}),
+  addAlbum: (albumId: string, position: number = Position.Last, shuffle?: boolean) =>
+    call("PlaylistService", "InsertAlbum", { albumId, position, shuffle }).then(() => {}),
+  addArtist: (artistId: string, position: number = Position.Last, shuffle?: boolean) =>
+    call("PlaylistService", "InsertArtistTracks", { artistId, position, shuffle }).then(() => {}),
+  remove: (positions: number[]) =>
+    call("PlaylistService", "RemoveTracks", { positions }).then(() => {}),
+  clear: () => call("PlaylistService", "RemoveAllTracks").then(() => {}),
+  /** `mode` is "tracks", "album", "artist_spread" or "smart". */
+  shuffle: (mode?: string, startIndex = 0) =>
+    call("PlaylistService", "ShufflePlaylist", { startIndex, mode }).then(() => {}),
+};
+
+export const library = {
+  albums: async () => (await call<{ albums: Album[] }>("LibraryService", "GetAlbums")).albums,
+  artists: async () => (await call<{ artists: Artist[] }>("LibraryService", "GetArtists")).artists,
+  tracks: async () => (await call<{ tracks: Track[] }>("LibraryService", "GetTracks")).tracks,
+  album: async (id: string) =>
+    (await call<{ album?: Album }>("LibraryService", "GetAlbum", { id })).album,
+  artist: async (id: string) =>
+    (await call<{ artist?: Artist }>("LibraryService", "GetArtist", { id })).artist,
+  track: async (id: string) =>
+    (await call<{ track?: Track }>("LibraryService", "GetTrack", { id })).track,
+  search: (term: string) => call<SearchResults>("LibraryService", "Search", { term }),
+  like: (id: string) => call("LibraryService", "LikeTrack", { id }).then(() => {}),
+  unlike: (id: string) => call("LibraryService", "UnlikeTrack", { id }).then(() => {}),
+  likedTracks: async () =>
+    (await call<{ tracks: Track[] }>("LibraryService", "GetLikedTracks")).tracks,
+  lyrics: (trackId: string) => call<Lyrics>("LibraryService", "GetLyrics", { trackId }),
+  /** 1 to 5 stars, 0 when unrated. */
+  rating: async (kind: "track" | "album" | "artist", id: string) =>
+    (await call<{ rating: number }>("LibraryService", "GetRating", { kind, id })).rating,
+  rate: (kind: "track" | "album" | "artist", id: string, rating: number) =>
+    call("LibraryService", "SetRating", { kind, id, rating }).then(() => {}),
+};
+
+export const smartPlaylists = {
+  list: async () =>
+    (await call<{ playlists: SmartPlaylist[] }>("SmartPlaylistService", "GetSmartPlaylists"))
+      .playlists,
+  get: async (id: string) =>
+    (await call<{ playlist?: SmartPlaylist }>("SmartPlaylistService", "GetSmartPlaylist", { id }))
+      .playlist,
+  /** Ids of the tracks the rules currently match. */
+  tracks: async (id: string) =>
+    (await call<{ trackIds: string[] }>("SmartPlaylistService", "GetSmartPlaylistTracks", { id }))
+      .trackIds,
+  play: (id: string) => call("SmartPlaylistService", "PlaySmartPlaylist", { id }).then(() => {}),
+  create: async (playlist: { name: string; description?: string; rules: RuleCriteria }) =>
+    (await call<{ playlist: SmartPlaylist }>(
+      "SmartPlaylistService",
+      "CreateSmartPlaylist",
+      playlist,
+    )).playlist,
+  update: (id: string, playlist: { name: string; description?: string; rules: RuleCriteria }) =>
+    call("SmartPlaylistService", "UpdateSmartPlaylist", { id, ...playlist }).then(() => {}),
+  delete: (id: string) =>
+    call("SmartPlaylistService", "DeleteSmartPlaylist", { id }).then(() => {}),
+  stats: async (trackId: string) =>
+    (await call<{ stats?: TrackStats }>("SmartPlaylistService", "GetTrackStats", { trackId }))
+      .stats,
+};
+
+export const sound = {
+  /** Relative, in volume steps. */
+  adjustVolume: (steps: number) =>
+    call("SoundService", "AdjustVolume", { steps }).then(() => {}),
+  /** Playback speed as a ratio, 1 being normal. */
+  speed: async () =>
+    (await call<{ value: number }>("SoundService", "GetPitch")).value / 10_000,
+  /**
+   * Set the playback speed (0.5 to 2). This is Rockbox's pitch setting, so
+   * the pitch moves with it.
+   */
+  setSpeed: (ratio: number) =>
+    call("SoundService", "SetPitch", {
+      value: Math.round(Math.min(Math.max(ratio, 0.5), 2) * 10_000),
+    }).then(() => {}),
+};
+
+export const settings = {
+  get: () => call("SettingsService", "GetGlobalSettings"),
+  /** Only the fields given are changed. */
+  save: (changes: Record<string, unknown>) =>
+    call("SettingsService", "SaveSettings", changes).then(() => {}),
+};
+
+export interface Events {
+  /** The track changed; also fires once with the current track on subscribe. */
+  trackChange: CurrentTrack;
+  /** Every position update of the current track. */
+  progress: CurrentTrack;
+  status: Status;
+  queueChange: Queue;
+  smartPlaylistChange: SmartPlaylistChange;
+}
+
+const TOPICS: Record<keyof Events, string> = {
+  trackChange: "currentTrack",
+  progress: "currentTrack",
+  status: "status",
+  queueChange: "playlist",
+  smartPlaylistChange: "smartPlaylists",
+};
+
+/** Messages on `topic` until `signal` aborts, reconnecting as needed. */
+async function* subscribe(topic: string, signal: AbortSignal): AsyncGenerator<unknown> {
+  while (!signal.aborted) {
+    try {
+      const res = await fetch(`${bridge()}/events/${topic}`, { headers: headers(), signal });
+      if (res.ok && res.body) {
+        let buffered = "";
+        for await (const chunk of res.body.pipeThrough(new TextDecoderStream())) {
+          buffered += chunk;
+          const events = buffered.split("\n\n");
+          buffered = events.pop()!;
+          for (const event of events) {
+            const data = event
+              .split("\n")
+              .filter((line) => line.startsWith("data:"))
+              .map((line) => line.slice(5).trimStart())
+              .join("\n");
+            if (data) yield JSON.parse(data);
+          }
+        }
+      } else {
+        await res.body?.cancel();
+      }
+    } catch (e) {
+      if (signal.aborted) return;
+      if (!(e instanceof TypeError)) throw e;
+    }
+    // rockboxd is down or restarting.
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+  }
+}
+
+/** Turns stream messages into `event` payloads, dropping repeats. */
+function changes<E extends keyof Events>(event: E): (message: unknown) => Events[E] | undefined {
+  let last: unknown;
+  switch (event) {
+    case "trackChange":
+      return (message) => {
+        const track = message as CurrentTrack;
+        const key = track.id || track.path;
+        if (!key || key === last) return undefined;
+        last = key;
+        return track as Events[E];
+      };
+    case "status":
+      return (message) => {
+        const status = STATUS[(message as { status: number }).status] ?? "stopped";
+        if (status === last) return undefined;
+        last = status;
+        return status as Events[E];
+      };
+    default:
+      return (message) => message as Events[E];
+  }
+}
+
+/**
+ * Call `handler` on every `event`. Returns a function that unsubscribes.
+ * An open subscription keeps the script running.
+ */
+export function on<E extends keyof Events>(
+  event: E,
+  handler: (payload: Events[E]) => unknown,
+): () => void {
+  const controller = new AbortController();
+  const filter = changes(event);
+
+  (async () => {
+    for await (const message of subscribe(TOPICS[event], controller.signal)) {
+      const payload = filter(message);
+      if (payload === undefined) continue;
+      try {
+        await handler(payload);
+      } catch (e) {
+        console.error(`rockbox: ${event} handler failed:`, e);
+      }
+    }
+  })();
+
+  return () => controller.abort();
+}
@@ -0,0 +1,436 @@
+// DSF / DFF readers and the DoP (DSD over PCM) packer.
+//
+// Both readers hand out the same stream: one byte per channel per step,
+// channels interleaved, oldest bit in the MSB. DoP then packs two of those
+// bytes per channel into a 24-bit PCM sample under an alternating
+// 0x05 / 0xFA marker, at a sixteenth of the DSD bit rate — a DoP-capable
+// DAC recognises the markers and plays the bits as DSD. The container must
+// reach the DAC unchanged, so DoP only works on an exclusive, non-resampled
+// device.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::format::{put_24, SampleFormat};
+
+/// DSD idle pattern: equal ones and zeros, i.e. silence.
+pub const DSD_SILENCE: u8 = 0x69;
+
+/// Markers DoP frames alternate between.
+pub const DOP_MARKERS: [u8; 2] = [0x05, 0xfa];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DsdInfo {
+    /// DSD bit rate per channel, e.g. 2 822 400 for DSD64.
+    pub rate: u32,
+    pub channels: u32,
+}
+
+impl DsdInfo {
+    /// PCM rate the DoP stream runs at.
+    pub fn dop_rate(&self) -> u32 {
+        self.rate / 16
+    }
+}
+
+/// Whether `path` names a DSF or DFF file.
+pub fn is_dsd_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dsf") || ext.eq_ignore_ascii_case("dff"))
+}
+
+enum Layout {
+    /// Per-channel blocks of `block_size` bytes; `lsb_first` when the file
+    /// stores the oldest bit in the LSB.
+    Dsf { block_size: usize, lsb_first: bool },
+    /// Already byte-interleaved, MSB first.
+    Dff,
+}
+
+pub struct DsdReader<R> {
+    reader: R,
+    info: DsdInfo,
+    layout: Layout,
+    /// Sample bytes left per channel.
+    remaining: u64,
+    /// Current DSF block group and the per-channel read position in it.
+    block: Vec<u8>,
+    block_pos: usize,
+    block_len: usize,
+}
+
+impl DsdReader<BufReader<File>> {
+    pub fn open(path: &str) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        if path.to_ascii_lowercase().ends_with(".dff") {
+            Self::dff(reader)
+        } else {
+            Self::dsf(reader)
+        }
+    }
+}
+
+impl<R: Read> DsdReader<R> {
+    pub fn dsf(mut reader: R) -> io::Result<Self> {
+        let header = read_array::<28>(&mut reader)?;
+        if &header[..4] != b"DSD " {
+            return Err(invalid("not a DSF file"));
+        }
+        let fmt = read_array::<52>(&mut reader)?;
+        if &fmt[..4] != b"fmt " {
+            return Err(invalid("DSF fmt chunk missing"));
+        }
+        if le_u32(&fmt[16..]) != 0 {
+            return Err(invalid("DSF data isn't raw DSD"));
+        }
+        let channels = le_u32(&fmt[24..]);
+        let rate = le_u32(&fmt[28..]);
+        let lsb_first = match le_u32(&fmt[32..]) {
+            1 => true,
+            8 => false,
+            bits => return Err(invalid(&format!("DSF: {bits} bits per sample"))),
+        };
+        let samples = u64::from(le_u32(&fmt[36..])) | u64::from(le_u32(&fmt[40..])) << 32;
+        let block_size = le_u32(&fmt[44..]) as usize;
+        // fmt chunks are 52 bytes in every DSF version so far; skip any more.
+        skip(&mut reader, le_u64(&fmt[4..]).saturating_sub(52))?;
+
+        let data = read_array::<12>(&mut reader)?;
+        if &data[..4] != b"data" {
+            return Err(invalid("DSF data chunk missing"));
+        }
+        if channels == 0 || rate == 0 || block_size == 0 {
+            return Err(invalid("DSF fmt chunk is empty"));
+        }
+        Ok(Self {
+            reader,
+            info: DsdInfo { rate, channels },
+            layout: Layout::Dsf {
+                block_size,
+                lsb_first,
+            },
+            remaining: samples.div_ceil(8),
+            block: vec![0; block_size * channels as usize],
+            block_pos: 0,
+            block_len: 0,
+        })
+    }
+
+    pub fn dff(mut reader: R) -> io::Result<Self> {
+        let form = read_array::<16>(&mut reader)?;
+        if &form[..4] != b"FRM8" || &form[12..] != b"DSD " {
+            return Err(invalid("not a DSDIFF file"));
+        }
+        let (mut rate, mut channels) = (0, 0);
+        loop {
+            let chunk = read_array::<12>(&mut reader)?;
+            let size = be_u64(&chunk[4..]);
+            match &chunk[..4] {
+                b"PROP" => {
+                    let mut prop = (&mut reader).take(size);
+                    if &read_array::<4>(&mut prop)? != b"SND " {
+                        return Err(invalid("DSDIFF PROP chunk isn't SND"));
+                    }
+                    while prop.limit() > 0 {
+                        let sub = read_array::<12>(&mut prop)?;
+                        let sub_size = be_u64(&sub[4..]);
+                        match &sub[..4] {
+                            b"FS  " => {
+                                rate = be_u32(&read_array::<4>(&mut prop)?);
+                                skip(&mut prop, sub_size.saturating_sub(4))?;
+                            }
+                            b"CHNL" => {
+                                let count = read_array::<2>(&mut prop)?;
+                                channels = u32::from(u16::from_be_bytes(count));
+                                skip(&mut prop, sub_size.saturating_sub(2))?;
+                            }
+                            b"CMPR" => {
+                                if &read_array::<4>(&mut prop)? != b"DSD " {
+                                    return Err(invalid("compressed (DST) DSDIFF isn't supported"));
+                                }
+                                skip(&mut prop, sub_size.saturating_sub(4))?;
+                            }
+                            _ => skip(&mut prop, sub_size)?,
+                        }
+                        skip(&mut prop, sub_size & 1)?;
+                    }
+                }
+                b"DSD " => {
+                    if channels == 0 || rate == 0 {
+                        return Err(invalid("DSDIFF sound properties missing"));
+                    }
+                    return Ok(Self {
+                        reader,
+                        info: DsdInfo { rate, channels },
+                        layout: Layout::Dff,
+                        remaining: size / u64::from(channels),
+                        block: Vec::new(),
+                        block_pos: 0,
+                        block_len: 0,
+                    });
+                }
+                b"DST " => return Err(invalid("compressed (DST) DSDIFF isn't supported")),
+                _ => skip(&mut reader, size + (size & 1))?,
+            }
+        }
+    }
+
+    pub fn info(&self) -> DsdInfo {
+        self.info
+    }
+
+    /// Append up to `bytes` bytes per channel to `out`, interleaved and MSB
+    /// first. Returns the bytes per channel appended; 0 at the end.
+    pub fn read(&mut self, bytes: usize, out: &mut Vec<u8>) -> io::Result<usize> {
+        let channels = self.info.channels as usize;
+        match self.layout {
+            Layout::Dff => {
+                let want = bytes.min(self.remaining as usize);
+                let start = out.len();
+                out.resize(start + want * channels, 0);
+                let got = read_full(&mut self.reader, &mut out[start..])? / channels;
+                out.truncate(start + got * channels);
+                self.remaining -= got as u64;
+                Ok(got)
+            }
+            Layout::Dsf {
+                block_size,
+                lsb_first,
+            } => {
+                let mut done = 0;
+                while done < bytes {
+                    if self.block_pos == self.block_len {
+                        if self.remaining == 0 {
+                            break;
+                        }
+                        // The last block group is zero-padded to full size.
+                        let got = read_full(&mut self.reader, &mut self.block)?;
+                        if got == 0 {
+                            break;
+                        }
+                        self.block_pos = 0;
+                        self.block_len = (got / channels)
+                            .min(block_size)
+                            .min(self.remaining as usize);
+                        self.remaining -= self.block_len as u64;
+                    }
+                    let n = (bytes - done).min(self.block_len - self.block_pos);
+                    for i in self.block_pos..self.block_pos + n {
+                        for ch in 0..channels {
+                            let byte = self.block[ch * block_size + i];
+                            out.push(if lsb_first { byte.reverse_bits() } else { byte });
+                        }
+                    }
+                    self.block_pos += n;
+                    done += n;
+                }
+                Ok(done)
+            }
+        }
+    }
+}
+
+/// Packs the readers' interleaved DSD bytes into DoP frames.
+#[derive(Default)]
+pub struct DopEncoder {
+    marker: usize,
+}
+
+impl DopEncoder {
+    /// Append DoP frames for `dsd` to `out`. Each frame takes two bytes per
+    /// channel; a short final frame is padded with silence.
+    pub fn encode(&mut self, dsd: &[u8], channels: usize, format: SampleFormat, out: &mut Vec<u8>) {
+        for frame in dsd.chunks(2 * channels) {
+            let marker = u32::from(DOP_MARKERS[self.marker]);
+            for ch in 0..channels {
+                let older = frame.get(ch).copied().unwrap_or(DSD_SILENCE);
+                let newer = frame.get(channels + ch).copied().unwrap_or(DSD_SILENCE);
+                put_24(
+                    marker << 16 | u32::from(older) << 8 | u32::from(newer),
+                    format,
+                    out,
+                );
+            }
+            self.marker ^= 1;
+        }
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Read until `buf` is full or the reader ends.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn skip(reader: &mut impl Read, bytes: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(bytes), &mut io::sink())?;
+    if skipped < bytes {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn le_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn be_u64(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn dsf(channels: u32, block_size: u32, samples: u64, data: &[u8]) -> Vec<u8> {
+        let mut file = Vec::new();
+        file.extend_from_slice(b"DSD ");
+        file.extend_from_slice(&28u64.to_le_bytes());
+        file.extend_from_slice(&(28 + 52 + 12 + data.len() as u64).to_le_bytes());
+        file.extend_from_slice(&0u64.to_le_bytes());
+        file.extend_from_slice(b"fmt ");
+        file.extend_from_slice(&52u64.to_le_bytes());
+        for field in [1, 0, 2, channels, 2_822_400, 1] {
+            file.extend_from_slice(&field.to_le_bytes());
+        }
+        file.extend_from_slice(&samples.to_le_bytes());
+        file.extend_from_slice(&block_size.to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes());
+        file.extend_from_slice(b"data");
+        file.extend_from_slice(&(12 + data.len() as u64).to_le_bytes());
+        file.extend_from_slice(data);
+        file
+    }
+
+    #[test]
+    fn dsf_blocks_are_interleaved_and_bit_reversed() {
+        // Two block groups of 4 bytes per channel, 6 bytes of real data.
+        let data = [
+            0x01, 0x02, 0x03, 0x04, 0x80, 0x40, 0x20, 0x10, // group 1: L, R
+            0x05, 0x06, 0x00, 0x00, 0x08, 0x0c, 0x00, 0x00, // group 2: padded
+        ];
+        let file = dsf(2, 4, 6 * 8, &data);
+        let mut reader = DsdReader::dsf(Cursor::new(file)).unwrap();
+        assert_eq!(
+            reader.info(),
+            DsdInfo {
+                rate: 2_822_400,
+                channels: 2
+            }
+        );
+        assert_eq!(reader.info().dop_rate(), 176_400);
+
+        let mut out = Vec::new();
+        assert_eq!(reader.read(5, &mut out).unwrap(), 5);
+        assert_eq!(reader.read(5, &mut out).unwrap(), 1);
+        assert_eq!(reader.read(5, &mut out).unwrap(), 0);
+        assert_eq!(
+            out,
+            [0x80, 0x01, 0x40, 0x02, 0xc0, 0x04, 0x20, 0x08, 0xa0, 0x10, 0x60, 0x30]
+        );
+    }
+
+    #[test]
+    fn dff_sample_data_is_read_as_is() {
+        let mut file = Vec::new();
+        file.extend_from_slice(b"FRM8");
+        file.extend_from_slice(&0u64.to_be_bytes());
+        file.extend_from_slice(b"DSD ");
+        file.extend_from_slice(b"FVER");
+        file.extend_from_slice(&4u64.to_be_bytes());
+        file.extend_from_slice(&0x0105_0000u32.to_be_bytes());
+        let mut prop = Vec::new();
+        prop.extend_from_slice(b"SND ");
+        prop.extend_from_slice(b"FS  ");
+        prop.extend_from_slice(&4u64.to_be_bytes());
+        prop.extend_from_slice(&5_644_800u32.to_be_bytes());
+        prop.extend_from_slice(b"CHNL");
+        prop.extend_from_slice(&10u64.to_be_bytes());
+        prop.extend_from_slice(&2u16.to_be_bytes());
+        prop.extend_from_slice(b"SLFTSRGT");
+        prop.extend_from_slice(b"CMPR");
+        prop.extend_from_slice(&19u64.to_be_bytes());
+        prop.extend_from_slice(b"DSD \x0enot compressed\0");
+        file.extend_from_slice(b"PROP");
+        file.extend_from_slice(&(prop.len() as u64).to_be_bytes());
+        file.extend_from_slice(&prop);
+        file.extend_from_slice(b"DSD ");
+        file.extend_from_slice(&4u64.to_be_bytes());
+        file.extend_from_slice(&[0x11, 0x22, 0x33, 0x44]);
+
+        let mut reader = DsdReader::dff(Cursor::new(file)).unwrap();
+        assert_eq!(
+            reader.info(),
+            DsdInfo {
+                rate: 5_644_800,
+                channels: 2
+            }
+        );
+        let mut out = Vec::new();
+        assert_eq!(reader.read(8, &mut out).unwrap(), 2);
+        assert_eq!(out, [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(reader.read(8, &mut out).unwrap(), 0);
+    }
+
+    #[test]
+    fn compressed_dff_is_refused() {
+        let mut file = Vec::new();
+        file.extend_from_slice(b"FRM8");
+        file.extend_from_slice(&0u64.to_be_bytes());
+        file.extend_from_slice(b"DSD ");
+        file.extend_from_slice(b"DST ");
+        file.extend_from_slice(&0u64.to_be_bytes());
+        assert!(DsdReader::dff(Cursor::new(file)).is_err());
+    }
+
+    #[test]
+    fn dop_frames_alternate_markers() {
+        let dsd = [0xaa, 0xbb, 0xcc, 0xdd, 0x11, 0x22];
+        let mut out = Vec::new();
+        DopEncoder::default().encode(&dsd, 2, SampleFormat::S24_3, &mut out);
+        assert_eq!(
+            out,
+            [
+                0xcc, 0xaa, 0x05, 0xdd, 0xbb, 0x05, // L: aa cc, R: bb dd
+                0x69, 0x11, 0xfa, 0x69, 0x22, 0xfa, // padded with silence
+            ]
+        );
+    }
+
+    #[test]
+    fn dsd_paths() {
+        assert!(is_dsd_path("/music/a.dsf"));
+        assert!(is_dsd_path("/music/b.DFF"));
+        assert!(!is_dsd_path("/music/c.flac"));
+    }
+}
@@ -0,0 +1,187 @@
+// Sample formats the exclusive path can hand to a DAC. The firmware mixes
+// S16LE stereo; widening it to a 24- or 32-bit container only pads the low
+// bits with zeros, so the DAC receives exactly the values the firmware
+// produced — nothing is scaled or dithered on the way. rockbox-mixer is the
+// exception: ReplayGain scales the S16 samples as they are pushed, and
+// software volume below 0 dB makes the writer scale into the full container
+// width (`put_sample`), dithering at that width rather than at 16 bits.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// 16-bit little-endian.
+    S16,
+    /// 24-bit little-endian packed in 3 bytes (ALSA `S24_3LE`).
+    S24_3,
+    /// 24-bit little-endian in the low bits of 4 bytes (ALSA `S24_LE`).
+    S24,
+    /// 32-bit little-endian.
+    S32,
+}
+
+impl SampleFormat {
+    /// Formats exclusive mode asks the device for, best first.
+    pub const PREFERENCE: [SampleFormat; 4] = [
+        SampleFormat::S32,
+        SampleFormat::S24_3,
+        SampleFormat::S24,
+        SampleFormat::S16,
+    ];
+
+    /// Bytes per sample in the container.
+    pub fn bytes(self) -> usize {
+        match self {
+            SampleFormat::S16 => 2,
+            SampleFormat::S24_3 => 3,
+            SampleFormat::S24 | SampleFormat::S32 => 4,
+        }
+    }
+
+    /// Significant bits per sample.
+    pub fn bits(self) -> u32 {
+        match self {
+            SampleFormat::S16 => 16,
+            SampleFormat::S24_3 | SampleFormat::S24 => 24,
+            SampleFormat::S32 => 32,
+        }
+    }
+
+    /// DoP needs 24 significant bits: an 8-bit marker over 16 DSD bits.
+    pub fn carries_dop(self) -> bool {
+        self.bits() >= 24
+    }
+
+    /// ALSA's name for the format, as `aplay --dump-hw-params` prints it.
+    pub fn name(self) -> &'static str {
+        match self {
+            SampleFormat::S16 => "S16_LE",
+            SampleFormat::S24_3 => "S24_3LE",
+            SampleFormat::S24 => "S24_LE",
+            SampleFormat::S32 => "S32_LE",
+        }
+    }
+}
+
+/// Append interleaved S16LE `input` to `out` in `format`, the 16 bits
+/// left-justified in the container.
+pub fn widen_s16(input: &[u8], format: SampleFormat, out: &mut Vec<u8>) {
+    out.reserve(input.len() / 2 * format.bytes());
+    for sample in input.chunks_exact(2) {
+        let (lo, hi) = (sample[0], sample[1]);
+        match format {
+            SampleFormat::S16 => out.extend_from_slice(&[lo, hi]),
+            SampleFormat::S24_3 => out.extend_from_slice(&[0, lo, hi]),
+            // Sign-extend into the unused top byte.
+            SampleFormat::S24 => out.extend_from_slice(&[0, lo, hi, sign_byte(hi)]),
+            SampleFormat::S32 => out.extend_from_slice(&[0, 0, lo, hi]),
+        }
+    }
+}
+
+/// Append the low 24 bits of `word` to `out` as one sample in `format`.
+/// `format` must carry 24 bits.
+pub fn put_24(word: u32, format: SampleFormat, out: &mut Vec<u8>) {
+    let [b0, b1, b2, _] = word.to_le_bytes();
+    match format {
+        SampleFormat::S24_3 => out.extend_from_slice(&[b0, b1, b2]),
+        SampleFormat::S24 => out.extend_from_slice(&[b0, b1, b2, sign_byte(b2)]),
+        SampleFormat::S32 => out.extend_from_slice(&[0, b0, b1, b2]),
+        SampleFormat::S16 => debug_assert!(false, "S16 can't carry 24-bit samples"),
+    }
+}
+
+/// Append `value`, a signed integer `format.bits()` wide, to `out`.
+pub fn put_sample(value: i32, format: SampleFormat, out: &mut Vec<u8>) {
+    match format {
+        SampleFormat::S16 => out.extend_from_slice(&(value as i16).to_le_bytes()),
+        SampleFormat::S24_3 | SampleFormat::S24 => put_24(value as u32, format, out),
+        SampleFormat::S32 => out.extend_from_slice(&value.to_le_bytes()),
+    }
+}
+
+fn sign_byte(top: u8) -> u8 {
+    if top & 0x80 != 0 {
+        0xff
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widening_keeps_the_sample_values() {
+        // 0x1234, -2 (0xfffe)
+        let input = [0x34, 0x12, 0xfe, 0xff];
+        let mut out = Vec::new();
+        widen_s16(&input, SampleFormat::S32, &mut out);
+        let samples: Vec<i32> = out
+            .chunks_exact(4)
+            .map(|s| i32::from_le_bytes(s.try_into().unwrap()))
+            .collect();
+        assert_eq!(samples, [0x1234 << 16, -2 << 16]);
+
+        out.clear();
+        widen_s16(&input, SampleFormat::S24, &mut out);
+        let samples: Vec<i32> = out
+            .chunks_exact(4)
+            .map(|s| i32::from_le_bytes(s.try_into().unwrap()))
+            .collect();
+        assert_eq!(samples, [0x1234 << 8, -2 << 8]);
+
+        out.clear();
+        widen_s16(&input, SampleFormat::S24_3, &mut out);
+        assert_eq!(out, [0, 0x34, 0x12, 0, 0xfe, 0xff]);
+
+        out.clear();
+        widen_s16(&input, SampleFormat::S16, &mut out);
+        assert_eq!(out, input);
+    }
+
+    #[test]
+    fn twenty_four_bit_words_fill_the_top_of_the_container() {
+        let mut out = Vec::new();
+        put_24(0xfa_69_96, SampleFormat::S24_3, &mut out);
+        assert_eq!(out, [0x96, 0x69, 0xfa]);
+
+        out.clear();
+        put_24(0xfa_69_96, SampleFormat::S24, &mut out);
+        assert_eq!(out, [0x96, 0x69, 0xfa, 0xff]);
+
+        out.clear();
+        put_24(0x05_69_96, SampleFormat::S32, &mut out);
+        assert_eq!(out, [0, 0x96, 0x69, 0x05]);
+    }
+
+    #[test]
+    fn samples_fill_the_container_width() {
+        let mut out = Vec::new();
+        put_sample(-2, SampleFormat::S16, &mut out);
+        assert_eq!(out, [0xfe, 0xff]);
+
+        out.clear();
+        put_sample(-2, SampleFormat::S24, &mut out);
+        assert_eq!(out, [0xfe, 0xff, 0xff, 0xff]);
+
+        out.clear();
+        put_sample(0x12_3456, SampleFormat::S24_3, &mut out);
+        assert_eq!(out, [0x56, 0x34, 0x12]);
+
+        out.clear();
+        put_sample(-0x1234_5678, SampleFormat::S32, &mut out);
+        assert_eq!(out, (-0x1234_5678_i32).to_le_bytes());
+    }
+
+    #[test]
+    fn only_24_bit_formats_carry_dop() {
+        let dop: Vec<_> = SampleFormat::PREFERENCE
+            .iter()
+            .filter(|f| f.carries_dop())
+            .collect();
+        assert_eq!(
+            dop,
+            [&SampleFormat::S32, &SampleFormat::S24_3, &SampleFormat::S24]
+        );
+    }
+}
@@ -11,50 +11,268 @@
 // Data flow:
 //   firmware DMA thread
 //     → pcm_alsa_push(data, size)   (blocks on back-pressure)
+//       – ReplayGain from rockbox-mixer, so it switches with the track
 //       → ring buffer (VecDeque)
 //         ← writer thread drains via snd_pcm_writei when running=true
+//           – software volume from rockbox-mixer, scaled and dithered at
+//             the output format's width (unity in hardware / fixed mode)
+//
+// Output modes (see `AlsaConfig`):
+//   shared    — the "default" PCM at S16LE, letting ALSA convert and mix.
+//   exclusive — the hw: device itself, at the source rate with resampling
+//               off and the widest sample format it takes. If the device
+//               refuses the rate, the matching plughw: device is used and
+//               `status()` stops reporting the path as bit-perfect.
+//   DoP       — DSF / DFF files streamed as DSD over PCM by the writer
+//               thread itself (`play_dsd`), with the firmware bypassed.
+//
+// The C ABI is behind the `ffi` feature. rockbox-server enables it, so the
+// writer, the settings that configure it and the status endpoint all share
+// one copy of this crate's state.
+
+pub mod dsd;
+pub mod format;
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use dsd::{DopEncoder, DsdInfo, DsdReader};
+use format::SampleFormat;
 
-/// Force-linkage sentinel. Always present so crates/cli can reference it
+/// Force-linkage sentinel. Always present so crates/server can reference it
 /// with #[cfg(feature = "alsa-sink")] without needing a cfg(target_os) guard.
 pub fn _link_alsa_sink() {}
 
+// ── Output mode ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlsaConfig {
+    /// Hardware device exclusive mode opens, e.g. "hw:0,0" or "hw:CARD=DAC".
+    pub device: String,
+    /// Open `device` directly instead of the shared "default" PCM.
+    pub exclusive: bool,
+    /// Allow DSF / DFF playback as DoP. Needs `exclusive`.
+    pub dop: bool,
+}
+
+impl Default for AlsaConfig {
+    fn default() -> Self {
+        Self {
+            device: "hw:0,0".to_string(),
+            exclusive: false,
+            dop: false,
+        }
+    }
+}
+
+/// What the writer thread currently has open.
+#[derive(Debug, Clone, Default)]
+pub struct Status {
+    pub open: bool,
+    /// PCM name actually opened.
+    pub device: String,
+    pub format: Option<SampleFormat>,
+    /// Rate the device runs at.
+    pub rate: u32,
+    /// Rate of the stream written to it.
+    pub source_rate: u32,
+    pub channels: u32,
+    /// Whether `device` is the hardware itself rather than a plugin.
+    pub exclusive: bool,
+    /// DSD bit rate while a DoP stream is playing.
+    pub dsd: Option<u32>,
+}
+
+impl Status {
+    /// Why the device side of the path isn't bit-perfect; empty when it is.
+    /// Firmware DSP and volume are for the caller to check.
+    pub fn reasons(&self) -> Vec<String> {
+        if !self.open {
+            return vec!["the output isn't open".to_string()];
+        }
+        let mut reasons = Vec::new();
+        if !self.exclusive {
+            reasons.push(format!(
+                "'{}' is shared and may convert or mix the stream",
+                self.device
+            ));
+        }
+        if self.rate != self.source_rate {
+            reasons.push(format!(
+                "{} Hz source played at {} Hz",
+                self.source_rate, self.rate
+            ));
+        }
+        reasons
+    }
+}
+
+static CONFIG: OnceLock<Mutex<AlsaConfig>> = OnceLock::new();
+// Bumped on every config change; the writer reopens when it sees a new value.
+static CONFIG_GENERATION: AtomicU64 = AtomicU64::new(0);
+static STATUS: OnceLock<Mutex<Status>> = OnceLock::new();
+
+fn config_lock() -> &'static Mutex<AlsaConfig> {
+    CONFIG.get_or_init(|| Mutex::new(AlsaConfig::default()))
+}
+
+fn status_lock() -> &'static Mutex<Status> {
+    STATUS.get_or_init(|| Mutex::new(Status::default()))
+}
+
+/// Set the output mode. Takes effect on the next write: the device is
+/// reopened if anything changed.
+pub fn configure(config: AlsaConfig) {
+    let mut current = config_lock().lock().unwrap();
+    if *current != config {
+        tracing::info!(
+            "pcm-alsa: {} mode, device '{}', DoP {}",
+            if config.exclusive {
+                "exclusive"
+            } else {
+                "shared"
+            },
+            config.device,
+            if config.dop { "on" } else { "off" }
+        );
+        *current = config;
+        CONFIG_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+pub fn config() -> AlsaConfig {
+    config_lock().lock().unwrap().clone()
+}
+
+pub fn status() -> Status {
+    status_lock().lock().unwrap().clone()
+}
+
+// ── DSD playback ──────────────────────────────────────────────────────────────
+
+// Only the writer thread reads it.
+#[cfg_attr(not(all(target_os = "linux", feature = "ffi")), allow(dead_code))]
+struct DsdPlayback {
+    path: String,
+    reader: DsdReader<std::io::BufReader<std::fs::File>>,
+    encoder: DopEncoder,
+}
+
+static DSD: OnceLock<Mutex<Option<DsdPlayback>>> = OnceLock::new();
+// A DSD file is loaded and not paused: the writer streams it instead of the ring.
+static DSD_PLAYING: AtomicBool = AtomicBool::new(false);
+
+fn dsd_slot() -> &'static Mutex<Option<DsdPlayback>> {
+    DSD.get_or_init(|| Mutex::new(None))
+}
+
+/// Start streaming a DSF or DFF file as DoP, replacing any DSD file already
+/// playing. Firmware audio is dropped until it ends or [`stop_dsd`] is
+/// called, so the caller should stop the firmware first.
+pub fn play_dsd(path: &str) -> Result<DsdInfo, String> {
+    let config = config();
+    if !config.exclusive || !config.dop {
+        return Err("DSD playback needs alsa_exclusive and alsa_dop".to_string());
+    }
+    if !writer_alive() {
+        return Err("the ALSA output isn't running".to_string());
+    }
+    let reader = DsdReader::open(path).map_err(|e| format!("{path}: {e}"))?;
+    let info = reader.info();
+    tracing::info!(
+        "pcm-alsa: DoP {path} (DSD{} {}ch)",
+        info.rate / 44_100,
+        info.channels
+    );
+    *dsd_slot().lock().unwrap() = Some(DsdPlayback {
+        path: path.to_string(),
+        reader,
+        encoder: DopEncoder::default(),
+    });
+    set_dsd_playing(true);
+    Ok(info)
+}
+
+/// Pause or resume the DSD file playing, if any.
+pub fn set_dsd_paused(paused: bool) {
+    if dsd_active() {
+        set_dsd_playing(!paused);
+    }
+}
+
+/// Stop the DSD file playing. Returns whether there was one.
+pub fn stop_dsd() -> bool {
+    let was = dsd_slot().lock().unwrap().take().is_some();
+    set_dsd_playing(false);
+    was
+}
+
+/// Whether a DSD file is loaded, playing or paused.
+pub fn dsd_active() -> bool {
+    dsd_slot().lock().unwrap().is_some()
+}
+
+pub fn dsd_paused() -> bool {
+    dsd_active() && !DSD_PLAYING.load(Ordering::SeqCst)
+}
+
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn writer_alive() -> bool {
+    WRITER_ALIVE.load(Ordering::Relaxed)
+}
+
+#[cfg(not(all(target_os = "linux", feature = "ffi")))]
+fn writer_alive() -> bool {
+    false
+}
+
+fn set_dsd_playing(playing: bool) {
+    DSD_PLAYING.store(playing, Ordering::SeqCst);
+    // Wake the writer under the ring lock so the change can't slip in
+    // between its check and its wait.
+    #[cfg(all(target_os = "linux", feature = "ffi"))]
+    {
+        let (lock, cvar) = ring();
+        let _r = lock.lock().unwrap();
+        cvar.notify_all();
+    }
+}
+
 // ── Linux-only implementation ─────────────────────────────────────────────────
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 use alsa::pcm::{Access, Format, HwParams, PCM};
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 use alsa::{Direction, ValueOr};
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 use std::collections::VecDeque;
-#[cfg(target_os = "linux")]
-use std::sync::atomic::{AtomicBool, Ordering};
-#[cfg(target_os = "linux")]
-use std::sync::{Condvar, Mutex, OnceLock};
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+use std::sync::Condvar;
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 use std::thread::JoinHandle;
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 use std::time::Duration;
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 const RING_CAPACITY: usize = 512 * 1024; // 512 KB ≈ 3 s at 44.1 kHz stereo S16LE
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 const PERIOD_FRAMES: alsa::pcm::Frames = 1024; // ~23 ms @ 44.1 kHz
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 const BUFFER_FRAMES: alsa::pcm::Frames = 8192; // ~185 ms
 
 // ── Ring buffer ───────────────────────────────────────────────────────────────
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 struct Ring {
     buf: VecDeque<u8>,
     running: bool,
     shutdown: bool, // set once at daemon shutdown to exit the writer thread
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 static RING: OnceLock<(Mutex<Ring>, Condvar)> = OnceLock::new();
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 fn ring() -> &'static (Mutex<Ring>, Condvar) {
     RING.get_or_init(|| {
         (
@@ -68,161 +286,373 @@ fn ring() -> &'static (Mutex<Ring>, Condvar) {
     })
 }
 
+// Dither state for ReplayGain scaling in pcm_alsa_push(). Only the firmware
+// DMA thread pushes, so the lock is never contended.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+static PUSH_DITHER: Mutex<Option<rockbox_mixer::Dither>> = Mutex::new(None);
+
 // ── Writer thread state ───────────────────────────────────────────────────────
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 static CURRENT_RATE: OnceLock<Mutex<u32>> = OnceLock::new();
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 static WRITER_HANDLE: OnceLock<Mutex<Option<JoinHandle<()>>>> = OnceLock::new();
 // Set to true while the writer thread is alive so pcm_alsa_postinit is idempotent.
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 static WRITER_ALIVE: AtomicBool = AtomicBool::new(false);
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 fn current_rate() -> &'static Mutex<u32> {
     CURRENT_RATE.get_or_init(|| Mutex::new(44100))
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 fn writer_handle() -> &'static Mutex<Option<JoinHandle<()>>> {
     WRITER_HANDLE.get_or_init(|| Mutex::new(None))
 }
 
 // ── ALSA helpers ──────────────────────────────────────────────────────────────
 
-#[cfg(target_os = "linux")]
-fn open_pcm(rate: u32) -> Option<PCM> {
-    let pcm = match PCM::new("default", Direction::Playback, false) {
-        Ok(p) => p,
-        Err(e) => {
-            tracing::error!("pcm-alsa: open 'default' failed: {e}");
-            return None;
-        }
-    };
-    {
-        let hwp = match HwParams::any(&pcm) {
-            Ok(h) => h,
-            Err(e) => {
-                tracing::error!("pcm-alsa: HwParams::any failed: {e}");
-                return None;
-            }
+/// The stream the device has to be opened for.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Want {
+    rate: u32,
+    channels: u32,
+    /// DSD bit rate when the stream is DoP.
+    dsd: Option<u32>,
+}
+
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+struct Output {
+    pcm: PCM,
+    format: SampleFormat,
+    want: Want,
+    generation: u64,
+}
+
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn alsa_format(format: SampleFormat) -> Format {
+    match format {
+        SampleFormat::S16 => Format::S16LE,
+        SampleFormat::S24_3 => Format::S243LE,
+        SampleFormat::S24 => Format::S24LE,
+        SampleFormat::S32 => Format::S32LE,
+    }
+}
+
+/// Open `device` for `want`. With `exact`, ALSA's rate conversion is off,
+/// the rate must be supported as-is and the widest format is picked;
+/// otherwise the device gets S16LE at the nearest rate.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn open_pcm(device: &str, want: Want, exact: bool) -> Result<(PCM, SampleFormat, u32), String> {
+    let pcm = PCM::new(device, Direction::Playback, false).map_err(|e| format!("open: {e}"))?;
+    let format = {
+        let hwp = HwParams::any(&pcm).map_err(|e| format!("HwParams::any: {e}"))?;
+        hwp.set_access(Access::RWInterleaved)
+            .map_err(|e| format!("interleaved access: {e}"))?;
+        hwp.set_channels(want.channels)
+            .map_err(|e| format!("{} channels: {e}", want.channels))?;
+        let formats: &[SampleFormat] = if exact {
+            &SampleFormat::PREFERENCE
+        } else {
+            &[SampleFormat::S16]
         };
-        if hwp.set_access(Access::RWInterleaved).is_err()
-            || hwp.set_format(Format::s16()).is_err()
-            || hwp.set_channels(2).is_err()
-            || hwp.set_rate(rate, ValueOr::Nearest).is_err()
-        {
-            tracing::error!("pcm-alsa: HwParams setup failed for {rate} Hz");
-            return None;
+        let format = formats
+            .iter()
+            .copied()
+            .filter(|f| want.dsd.is_none() || f.carries_dop())
+            .find(|f| hwp.test_format(alsa_format(*f)).is_ok())
+            .ok_or_else(|| "no usable sample format".to_string())?;
+        hwp.set_format(alsa_format(format))
+            .map_err(|e| format!("{}: {e}", format.name()))?;
+        if exact {
+            hwp.set_rate_resample(false)
+                .map_err(|e| format!("disabling resampling: {e}"))?;
         }
+        hwp.set_rate(want.rate, ValueOr::Nearest)
+            .map_err(|e| format!("{} Hz: {e}", want.rate))?;
         let _ = hwp.set_buffer_size_near(BUFFER_FRAMES);
         let _ = hwp.set_period_size_near(PERIOD_FRAMES, ValueOr::Nearest);
-        if let Err(e) = pcm.hw_params(&hwp) {
-            tracing::error!("pcm-alsa: hw_params apply failed: {e}");
-            return None;
+        pcm.hw_params(&hwp)
+            .map_err(|e| format!("hw_params apply: {e}"))?;
+        format
+    };
+    let rate = pcm
+        .hw_params_current()
+        .and_then(|hwp| hwp.get_rate())
+        .unwrap_or(want.rate);
+    pcm.prepare().map_err(|e| format!("prepare: {e}"))?;
+    Ok((pcm, format, rate))
+}
+
+/// The plug device converting for `device`, used when exclusive mode can't
+/// play a stream as-is.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn plug_device(device: &str) -> String {
+    match device.strip_prefix("hw:") {
+        Some(rest) => format!("plughw:{rest}"),
+        None => "default".to_string(),
+    }
+}
+
+/// Open the device for `want` in the configured mode and publish the result
+/// in [`status`].
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn open_output(want: Want) -> Option<Output> {
+    let generation = CONFIG_GENERATION.load(Ordering::SeqCst);
+    let config = config();
+
+    let mut attempts = Vec::new();
+    if config.exclusive {
+        attempts.push((config.device.clone(), true));
+        // DoP has to reach the DAC untouched; a plug device would mangle it.
+        if want.dsd.is_none() {
+            attempts.push((plug_device(&config.device), false));
         }
+    } else {
+        attempts.push(("default".to_string(), false));
     }
-    if let Err(e) = pcm.prepare() {
-        tracing::error!("pcm-alsa: prepare failed: {e}");
-        return None;
+
+    for (device, exact) in attempts {
+        match open_pcm(&device, want, exact) {
+            Ok((pcm, format, rate)) => {
+                tracing::info!(
+                    "pcm-alsa: opened '{device}' at {rate} Hz {}ch {}{}",
+                    want.channels,
+                    format.name(),
+                    if want.dsd.is_some() { " (DoP)" } else { "" }
+                );
+                *status_lock().lock().unwrap() = Status {
+                    open: true,
+                    device,
+                    format: Some(format),
+                    rate,
+                    source_rate: want.rate,
+                    channels: want.channels,
+                    exclusive: exact,
+                    dsd: want.dsd,
+                };
+                return Some(Output {
+                    pcm,
+                    format,
+                    want,
+                    generation,
+                });
+            }
+            Err(e) => tracing::error!("pcm-alsa: '{device}' at {} Hz: {e}", want.rate),
+        }
+    }
+    close_output(None);
+    None
+}
+
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn close_output(output: Option<Output>) {
+    if let Some(output) = output {
+        let _ = output.pcm.drain();
+    }
+    status_lock().lock().unwrap().open = false;
+}
+
+/// Make sure `output` is open for `want` under the current config.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn ensure_output(output: &mut Option<Output>, want: Want) {
+    let current = output.as_ref().is_some_and(|o| {
+        o.want == want && o.generation == CONFIG_GENERATION.load(Ordering::SeqCst)
+    });
+    if !current {
+        close_output(output.take());
+        *output = open_output(want);
+    }
+}
+
+/// Write whole frames of `bytes`, already in the output's format. Returns
+/// false when the device needs reopening.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn write_frames(output: &Output, bytes: &[u8]) -> bool {
+    let frame = output.format.bytes() * output.want.channels as usize;
+    let io = output.pcm.io_bytes();
+    let mut offset = 0usize;
+    while offset + frame <= bytes.len() {
+        match io.writei(&bytes[offset..]) {
+            Ok(n) => offset += n * frame,
+            Err(e) => {
+                tracing::warn!(
+                    "pcm-alsa: writei at frame {}: {e}, recovering",
+                    offset / frame
+                );
+                if let Err(re) = output.pcm.try_recover(e, true) {
+                    tracing::error!("pcm-alsa: recover failed: {re}");
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Stream one period of the loaded DSD file as DoP.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn write_dsd_period(output: &mut Option<Output>) {
+    let mut slot = dsd_slot().lock().unwrap();
+    let Some(playback) = slot.as_mut() else {
+        DSD_PLAYING.store(false, Ordering::SeqCst);
+        return;
+    };
+    let info = playback.reader.info();
+    ensure_output(
+        output,
+        Want {
+            rate: info.dop_rate(),
+            channels: info.channels,
+            dsd: Some(info.rate),
+        },
+    );
+    let Some(out) = output.as_ref() else {
+        tracing::error!(
+            "pcm-alsa: no DoP-capable output, stopping {}",
+            playback.path
+        );
+        *slot = None;
+        DSD_PLAYING.store(false, Ordering::SeqCst);
+        return;
+    };
+
+    // Two DSD bytes per channel make one DoP frame.
+    let mut raw = Vec::with_capacity(PERIOD_FRAMES as usize * 2 * info.channels as usize);
+    let read = playback
+        .reader
+        .read(PERIOD_FRAMES as usize * 2, &mut raw)
+        .unwrap_or_else(|e| {
+            tracing::error!("pcm-alsa: reading {}: {e}", playback.path);
+            0
+        });
+    if read == 0 {
+        tracing::info!("pcm-alsa: finished {}", playback.path);
+        *slot = None;
+        DSD_PLAYING.store(false, Ordering::SeqCst);
+        drop(slot);
+        // Reopen for PCM on the next firmware write.
+        close_output(output.take());
+        return;
+    }
+    let mut frames = Vec::with_capacity(raw.len() / 2 * out.format.bytes());
+    playback
+        .encoder
+        .encode(&raw, info.channels as usize, out.format, &mut frames);
+    drop(slot);
+
+    if !write_frames(out, &frames) {
+        close_output(output.take());
     }
-    tracing::info!("pcm-alsa: opened 'default' at {rate} Hz stereo S16LE");
-    Some(pcm)
 }
 
 // ── Writer thread — lives for the daemon lifetime ─────────────────────────────
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+enum Work {
+    Pcm(Vec<u8>),
+    Dsd,
+}
+
+// Software volume for one chunk: scale each S16LE sample straight into the
+// output format, so 24- and 32-bit DACs keep the resolution 16 bits lose.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+fn scale_chunk(
+    chunk: &[u8],
+    format: SampleFormat,
+    gains: (f32, f32),
+    dither: &mut rockbox_mixer::Dither,
+    out: &mut Vec<u8>,
+) {
+    let dithered = rockbox_mixer::dither();
+    out.reserve(chunk.len() / 2 * format.bytes());
+    for (i, sample) in chunk.chunks_exact(2).enumerate() {
+        let gain = if i % 2 == 0 { gains.0 } else { gains.1 };
+        let value = i16::from_le_bytes([sample[0], sample[1]]);
+        let scaled = dither.scale(value, gain, format.bits(), dithered);
+        format::put_sample(scaled, format, out);
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 fn run_writer(initial_rate: u32) {
-    let mut pcm: Option<PCM> = open_pcm(initial_rate);
-    let mut rate = initial_rate;
+    let pcm_want = |rate| Want {
+        rate,
+        channels: 2,
+        dsd: None,
+    };
+    let mut output: Option<Output> = open_output(pcm_want(initial_rate));
+    let mut dither = rockbox_mixer::Dither::new();
     tracing::info!("pcm-alsa: writer thread started");
 
     loop {
-        // Wait until running=true with data, OR until shutdown.
-        let chunk: Vec<u8> = {
+        // Wait until running=true with data, a DSD file to play, OR shutdown.
+        let work = {
             let (lock, cvar) = ring();
             let mut r = lock.lock().unwrap();
             loop {
                 if r.shutdown {
-                    if let Some(p) = pcm.take() {
-                        let _ = p.drain();
-                    }
+                    close_output(output.take());
                     tracing::info!("pcm-alsa: writer thread shutting down");
                     WRITER_ALIVE.store(false, Ordering::Relaxed);
                     return;
                 }
+                if DSD_PLAYING.load(Ordering::SeqCst) {
+                    break Work::Dsd;
+                }
                 if r.running && r.buf.len() >= 4 {
-                    break;
+                    let n = r.buf.len().min(PERIOD_FRAMES as usize * 4 * 4);
+                    break Work::Pcm(r.buf.drain(..n).collect());
                 }
                 // Pause: running=false or buffer empty — wait for more data or
                 // a start() / shutdown() signal. ALSA stays open; any underrun
                 // is recovered transparently by try_recover on next write.
                 r = cvar.wait(r).unwrap();
             }
-            let n = r.buf.len().min(PERIOD_FRAMES as usize * 4 * 4);
-            r.buf.drain(..n).collect()
         };
-        ring().1.notify_all(); // signal push() that ring space freed
 
-        if chunk.is_empty() {
-            continue;
-        }
-
-        // Reopen ALSA if the sample rate changed (rare; no gap for same rate).
-        let new_rate = *current_rate().lock().unwrap();
-        if new_rate != rate || pcm.is_none() {
-            if let Some(old) = pcm.take() {
-                let _ = old.drain();
+        let chunk = match work {
+            Work::Dsd => {
+                write_dsd_period(&mut output);
+                continue;
             }
-            rate = new_rate;
-            pcm = open_pcm(rate);
-        }
-
-        let p = match pcm.as_ref() {
-            Some(p) => p,
-            None => continue,
+            Work::Pcm(chunk) => chunk,
         };
+        ring().1.notify_all(); // signal push() that ring space freed
 
-        let mut reopen = false;
-        match p.io_i16() {
-            Ok(io) => {
-                let samples_i16 = unsafe {
-                    std::slice::from_raw_parts(chunk.as_ptr() as *const i16, chunk.len() / 2)
-                };
-                let frames_total = samples_i16.len() / 2;
-                let mut offset = 0usize;
-                while offset < frames_total {
-                    match io.writei(&samples_i16[offset * 2..]) {
-                        Ok(n) => offset += n,
-                        Err(e) => {
-                            tracing::warn!("pcm-alsa: writei at frame {offset}: {e}, recovering");
-                            if let Err(re) = p.try_recover(e, true) {
-                                tracing::error!("pcm-alsa: recover failed: {re}");
-                                reopen = true;
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::error!("pcm-alsa: io_i16: {e}");
-                reopen = true;
-            }
-        }
+        // Reopen ALSA if the sample rate or the config changed (rare; no gap
+        // for the same rate).
+        ensure_output(&mut output, pcm_want(*current_rate().lock().unwrap()));
+        let Some(out) = output.as_ref() else {
+            continue;
+        };
 
-        if reopen {
-            pcm = None;
+        let gains = rockbox_mixer::volume_gains();
+        let written = if gains != (1.0, 1.0) {
+            let mut scaled = Vec::new();
+            scale_chunk(&chunk, out.format, gains, &mut dither, &mut scaled);
+            write_frames(out, &scaled)
+        } else if out.format == SampleFormat::S16 {
+            write_frames(out, &chunk)
+        } else {
+            let mut wide = Vec::with_capacity(chunk.len() / 2 * out.format.bytes());
+            format::widen_s16(&chunk, out.format, &mut wide);
+            write_frames(out, &wide)
+        };
+        if !written {
+            close_output(output.take());
         }
     }
 }
 
 // ── C ABI — called from firmware/target/hosted/headless/pcm-alsa.c ───────────
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub extern "C" fn pcm_alsa_init() {
+    rockbox_mixer::register_sink("alsa");
     let _ = ring();
     let _ = current_rate();
     let _ = writer_handle();
@@ -230,7 +660,7 @@ pub extern "C" fn pcm_alsa_init() {
 
 /// Open ALSA and start the persistent writer thread. Called once after
 /// kernel init; subsequent calls are no-ops if the thread is already alive.
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub extern "C" fn pcm_alsa_postinit() {
     if WRITER_ALIVE.swap(true, Ordering::Relaxed) {
@@ -246,7 +676,7 @@ pub extern "C" fn pcm_alsa_postinit() {
     );
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub extern "C" fn pcm_alsa_set_sample_rate(rate_hz: u32) {
     *current_rate().lock().unwrap() = rate_hz;
@@ -254,13 +684,17 @@ pub extern "C" fn pcm_alsa_set_sample_rate(rate_hz: u32) {
 
 /// Push `size` bytes of S16LE stereo PCM from the firmware DMA thread.
 /// Blocks when the ring is full (back-pressure). Returns immediately if
-/// the ring is stopped (running=false) so the DMA thread can exit cleanly.
+/// the ring is stopped (running=false) so the DMA thread can exit cleanly,
+/// and drops the data while a DSD file owns the device.
 ///
 /// # Safety
 /// `addr` must be valid for `size` bytes for the duration of this call.
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub unsafe extern "C" fn pcm_alsa_push(addr: *const u8, size: usize) {
+    if DSD_PLAYING.load(Ordering::SeqCst) {
+        return;
+    }
     let data = unsafe { std::slice::from_raw_parts(addr, size) };
     let (lock, cvar) = ring();
     let mut r = lock.lock().unwrap();
@@ -281,13 +715,34 @@ pub unsafe extern "C" fn pcm_alsa_push(addr: *const u8, size: usize) {
     if !r.running {
         return;
     }
-    r.buf.extend(data.iter().copied());
+    let gain = rockbox_mixer::replaygain_gain();
+    if gain == 1.0 {
+        r.buf.extend(data.iter().copied());
+    } else {
+        let mut scaled = data.to_vec();
+        PUSH_DITHER
+            .lock()
+            .unwrap()
+            .get_or_insert_with(rockbox_mixer::Dither::new)
+            .scale_s16(&mut scaled, (gain, gain), rockbox_mixer::dither());
+        r.buf.extend(scaled);
+    }
     cvar.notify_all();
 }
 
+/// Set per-channel volume in tenths of a dB (INT_MIN mutes). Called from
+/// audiohw_set_volume when the ALSA sink is active; rockbox-mixer decides
+/// whether the writer scales, the card's mixer element follows, or the
+/// volume is ignored.
+#[cfg(all(target_os = "linux", feature = "ffi"))]
+#[no_mangle]
+pub extern "C" fn pcm_alsa_set_volume(vol_l: i32, vol_r: i32) {
+    rockbox_mixer::set_volume(vol_l, vol_r);
+}
+
 /// Arm the ring for playback. The persistent writer thread wakes up and
 /// starts draining immediately — no ALSA re-open, no thread creation.
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub extern "C" fn pcm_alsa_start() {
     let (lock, cvar) = ring();
@@ -299,7 +754,7 @@ pub extern "C" fn pcm_alsa_start() {
 /// Pause the ring. The writer thread sees running=false and blocks on the
 /// condvar; ALSA stays open. Any underrun on next write is recovered by
 /// try_recover, typically transparent to the listener.
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub extern "C" fn pcm_alsa_stop() {
     let (lock, cvar) = ring();
@@ -308,7 +763,7 @@ pub extern "C" fn pcm_alsa_stop() {
     cvar.notify_all();
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub extern "C" fn pcm_alsa_flush() {
     let (lock, cvar) = ring();
@@ -317,7 +772,7 @@ pub extern "C" fn pcm_alsa_flush() {
     cvar.notify_all();
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "ffi"))]
 #[no_mangle]
 pub extern "C" fn pcm_alsa_is_running() -> bool {
     ring().0.lock().unwrap().running
@@ -0,0 +1,414 @@
+pub mod scope;
+
+use std::{fmt, io, net::IpAddr, path::PathBuf, sync::OnceLock};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use tracing::warn;
+use uuid::Uuid;
+
+pub use scope::{grpc_scope, mpd_scope, rest_scope, Scope};
+
+/// Prefix of every token, so a leaked one is easy to recognise in logs and
+/// secret scanners.
+pub const TOKEN_PREFIX: &str = "rbx_";
+
+/// Query parameter accepted instead of an `Authorization` header, for
+/// clients that cannot set headers (`<audio>` tags, WebSockets).
+pub const TOKEN_QUERY_PARAM: &str = "access_token";
+
+/// `last_used_at` is only rewritten when older than this, so a busy client
+/// does not turn every read into a write.
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scope: Scope,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+/// A freshly minted token. `token` is the only time the secret is available;
+/// only its hash is stored.
+#[derive(Debug, Clone, Serialize)]
+pub struct NewToken {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AuthConfig {
+    /// `Some(false)` turns enforcement off, `Some(true)` forces it on even
+    /// with no tokens. `None` enforces as soon as one token exists.
+    pub enabled: Option<bool>,
+    /// Grant admin to every loopback peer without a token. Off by default:
+    /// behind a reverse proxy on the same host, outside traffic arrives
+    /// from 127.0.0.1 too. The servers' own calls to each other carry
+    /// [`internal_token`] instead.
+    pub trust_localhost: bool,
+}
+
+impl AuthConfig {
+    /// From the `auth_enabled` / `auth_trust_localhost` settings.
+    pub fn new(enabled: Option<bool>, trust_localhost: Option<bool>) -> Self {
+        Self {
+            enabled,
+            trust_localhost: trust_localhost.unwrap_or(false),
+        }
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+/// Why a request was refused.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Denied {
+    /// No token, or one that does not exist (any more).
+    Unauthenticated,
+    /// A valid token whose scope is too narrow.
+    Forbidden { granted: Scope, required: Scope },
+    /// The token store could not be read.
+    Internal(String),
+}
+
+impl fmt::Display for Denied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Denied::Unauthenticated => f.write_str("a valid API token is required"),
+            Denied::Forbidden { granted, required } => write!(
+                f,
+                "this token has the {} scope; {} is required",
+                granted, required
+            ),
+            Denied::Internal(e) => write!(f, "could not check API token: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Denied {}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// 64 hex characters from two v4 UUIDs (244 random bits) after the prefix.
+fn generate_token() -> String {
+    format!(
+        "{}{}{}",
+        TOKEN_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// Admin token the GraphQL, gRPC and MPD servers present when they call the
+/// REST and gRPC APIs of this same process. Generated at startup and never
+/// printed; the only copy outside the process is [`write_local_token`]'s.
+pub fn internal_token() -> &'static str {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    TOKEN.get_or_init(generate_token)
+}
+
+/// `Authorization` header value carrying [`internal_token`].
+pub fn internal_authorization() -> String {
+    format!("Bearer {}", internal_token())
+}
+
+/// Where rockboxd leaves [`internal_token`] for the command-line tools of
+/// the same user: `~/.config/rockbox.org/local-token`.
+pub fn local_token_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".config/rockbox.org/local-token"))
+}
+
+/// Write [`internal_token`] to [`local_token_path`], readable by the
+/// owner only. Rewritten at every start, as the token changes.
+pub fn write_local_token() -> io::Result<()> {
+    let path = local_token_path().ok_or_else(|| io::Error::other("HOME is not set"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // `mode` only applies to a file created here.
+        if path.exists() {
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    io::Write::write_all(&mut options.open(&path)?, internal_token().as_bytes())
+}
+
+/// Token from an `Authorization: Bearer` header value, falling back to the
+/// `access_token` query parameter.
+pub fn request_token(authorization: Option<&str>, query: &str) -> Option<String> {
+    if let Some(token) = authorization
+        .and_then(|v| {
+            v.strip_prefix("Bearer ")
+                .or_else(|| v.strip_prefix("bearer "))
+        })
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        return Some(token.to_string());
+    }
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == TOKEN_QUERY_PARAM)
+        .map(|(_, value)| value.to_string())
+        .filter(|t| !t.is_empty())
+}
+
+const TOKEN_COLUMNS: &str = "id, name, scope, created_at, last_used_at";
+
+fn token_from_row(r: SqliteRow) -> ApiToken {
+    let scope: String = r.get(2);
+    ApiToken {
+        id: r.get(0),
+        name: r.get(1),
+        scope: scope.parse().unwrap_or(Scope::Read),
+        created_at: r.get(3),
+        last_used_at: r.get(4),
+    }
+}
+
+#[derive(Clone)]
+pub struct Auth {
+    pool: Pool<Sqlite>,
+    config: AuthConfig,
+}
+
+impl Auth {
+    pub fn new(pool: Pool<Sqlite>, config: AuthConfig) -> Self {
+        Self { pool, config }
+    }
+
+    pub async fn list(&self) -> Result<Vec<ApiToken>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TOKEN_COLUMNS} FROM api_tokens ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(token_from_row).collect())
+    }
+
+    pub async fn create(&self, name: &str, scope: Scope) -> Result<NewToken> {
+        let token = generate_token();
+        let api_token = ApiToken {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            scope,
+            created_at: Utc::now().timestamp(),
+            last_used_at: None,
+        };
+        sqlx::query(
+            "INSERT INTO api_tokens (id, name, scope, token_hash, created_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&api_token.id)
+        .bind(&api_token.name)
+        .bind(scope.as_str())
+        .bind(hash_token(&token))
+        .bind(api_token.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(NewToken { api_token, token })
+    }
+
+    /// Revoke by id, or by name when no id matches. Returns how many tokens
+    /// were removed.
+    pub async fn revoke(&self, id_or_name: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM api_tokens WHERE id = $1")
+            .bind(id_or_name)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() > 0 {
+            return Ok(result.rows_affected());
+        }
+        let result = sqlx::query("DELETE FROM api_tokens WHERE name = $1")
+            .bind(id_or_name)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// The token matching `token`, recording that it was used.
+    pub async fn verify(&self, token: &str) -> Result<Option<ApiToken>> {
+        if !token.starts_with(TOKEN_PREFIX) {
+            return Ok(None);
+        }
+        let row = sqlx::query(&format!(
+            "SELECT {TOKEN_COLUMNS} FROM api_tokens WHERE token_hash = $1"
+        ))
+        .bind(hash_token(token))
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(api_token) = row.map(token_from_row) else {
+            return Ok(None);
+        };
+        let now = Utc::now().timestamp();
+        if api_token
+            .last_used_at
+            .is_none_or(|t| now - t >= LAST_USED_RESOLUTION_SECS)
+        {
+            if let Err(e) = sqlx::query("UPDATE api_tokens SET last_used_at = $1 WHERE id = $2")
+                .bind(now)
+                .bind(&api_token.id)
+                .execute(&self.pool)
+                .await
+            {
+                warn!("failed to record API token use: {}", e);
+            }
+        }
+        Ok(Some(api_token))
+    }
+
+    /// Whether requests have to present a token at all.
+    pub async fn active(&self) -> Result<bool> {
+        if let Some(enabled) = self.config.enabled {
+            return Ok(enabled);
+        }
+        let exists: i64 = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM api_tokens)")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(exists != 0)
+    }
+
+    /// The scope a caller has: admin when auth is off, the token is the
+    /// internal one or the peer is a trusted loopback address, the token's
+    /// scope otherwise, `None` when the token is missing or unknown.
+    pub async fn resolve(
+        &self,
+        peer: Option<IpAddr>,
+        token: Option<&str>,
+    ) -> Result<Option<Scope>> {
+        if token == Some(internal_token()) {
+            return Ok(Some(Scope::Admin));
+        }
+        if self.config.trust_localhost && peer.is_some_and(|ip| ip.is_loopback()) {
+            return Ok(Some(Scope::Admin));
+        }
+        if !self.active().await? {
+            return Ok(Some(Scope::Admin));
+        }
+        match token {
+            Some(token) => Ok(self.verify(token).await?.map(|t| t.scope)),
+            None => Ok(None),
+        }
+    }
+
+    /// [`Auth::resolve`], failing unless the caller has at least `required`.
+    pub async fn authorize(
+        &self,
+        peer: Option<IpAddr>,
+        token: Option<&str>,
+        required: Scope,
+    ) -> Result<Scope, Denied> {
+        match self.resolve(peer, token).await {
+            Ok(Some(granted)) if granted >= required => Ok(granted),
+            Ok(Some(granted)) => Err(Denied::Forbidden { granted, required }),
+            Ok(None) => Err(Denied::Unauthenticated),
+            Err(e) => Err(Denied::Internal(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_prefixed_and_unique() {
+        let a = generate_token();
+        let b = generate_token();
+        assert!(a.starts_with(TOKEN_PREFIX));
+        assert_eq!(a.len(), TOKEN_PREFIX.len() + 64);
+        assert_ne!(a, b);
+        assert_eq!(hash_token(&a), hash_token(&a));
+        assert_ne!(hash_token(&a), hash_token(&b));
+    }
+
+    #[test]
+    fn token_is_read_from_header_then_query() {
+        assert_eq!(
+            request_token(Some("Bearer rbx_abc"), "access_token=rbx_def"),
+            Some("rbx_abc".to_string())
+        );
+        assert_eq!(
+            request_token(None, "id=1&access_token=rbx_def"),
+            Some("rbx_def".to_string())
+        );
+        assert_eq!(request_token(Some("Basic Zm9v"), ""), None);
+        assert_eq!(request_token(None, "access_token="), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn local_token_is_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let home = std::env::temp_dir().join(format!("rockbox-auth-{}", Uuid::new_v4()));
+        std::env::set_var("HOME", &home);
+        write_local_token().unwrap();
+        let path = local_token_path().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), internal_token());
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_dir_all(home).unwrap();
+    }
+
+    #[tokio::test]
+    async fn loopback_needs_a_token_unless_trusted() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::Executor::execute(
+            &pool,
+            include_str!("../../library/migrations/20261019000600_add_api_tokens.sql"),
+        )
+        .await
+        .unwrap();
+        let localhost = Some(IpAddr::from([127, 0, 0, 1]));
+
+        let auth = Auth::new(pool.clone(), AuthConfig::default());
+        let read = auth.create("tablet", Scope::Read).await.unwrap().token;
+        assert_eq!(auth.resolve(localhost, None).await.unwrap(), None);
+        assert_eq!(
+            auth.resolve(localhost, Some(&read)).await.unwrap(),
+            Some(Scope::Read)
+        );
+        assert_eq!(
+            auth.resolve(None, Some(internal_token())).await.unwrap(),
+            Some(Scope::Admin)
+        );
+
+        let trusted = Auth::new(pool, AuthConfig::new(None, Some(true)));
+        assert_eq!(
+            trusted.resolve(localhost, None).await.unwrap(),
+            Some(Scope::Admin)
+        );
+        assert_eq!(
+            trusted
+                .resolve(Some(IpAddr::from([192, 168, 1, 2])), None)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Which scope each API call needs. Kept free of any web framework so the
+//! REST, gRPC and MPD front ends classify requests the same way.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+/// What a token may do. Each scope includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Browse the library and observe playback.
+    Read,
+    /// Everything a remote does: playback, queue, volume, playlists.
+    Control,
+    /// Settings, outputs, library scans, remote sources, webhooks and tokens.
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Control => "control",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Scope::Read),
+            "control" => Ok(Scope::Control),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(anyhow!(
+                "unknown scope: {} (expected read, control or admin)",
+                s
+            )),
+        }
+    }
+}
+
+/// Scope needed for a REST call on port 6063.
+pub fn rest_scope(method: &str, path: &str) -> Scope {
+    let read_only = method == "GET" || method == "HEAD";
+    if path.starts_with("/tokens") || path.starts_with("/webhooks") || path.starts_with("/sources")
+    {
+        return Scope::Admin;
+    }
+    if read_only {
+        return Scope::Read;
+    }
+    let admin = path == "/settings"
+        || path == "/scan-library"
+        || path.starts_with("/library/health/")
+        || path.starts_with("/bluetooth/")
+        || (path.starts_with("/devices/")
+            && (path.ends_with("/connect") || path.ends_with("/disconnect")));
+    match admin {
+        true => Scope::Admin,
+        false => Scope::Control,
+    }
+}
+
+/// gRPC methods that only read, beyond the `Get*`/`Stream*`/... prefixes.
+const GRPC_READ_METHODS: [&str; 10] = [
+    "CurrentTrack",
+    "NextTrack",
+    "Status",
+    "Amount",
+    "SoundCurrent",
+    "SoundDefault",
+    "SoundMax",
+    "SoundMin",
+    "SoundUnit",
+    "SoundVal",
+];
+
+/// Scope needed for a gRPC call, from its `/package.Service/Method` path.
+pub fn grpc_scope(path: &str) -> Scope {
+    let mut parts = path.trim_start_matches('/').splitn(2, '/');
+    let service = parts.next().unwrap_or_default();
+    let method = parts.next().unwrap_or_default();
+    let service = service.rsplit('.').next().unwrap_or_default();
+
+    if service == "ServerReflection" {
+        return Scope::Read;
+    }
+    if ["Get", "Stream", "Search", "Filter", "TreeGet"]
+        .iter()
+        .any(|prefix| method.starts_with(prefix))
+        || GRPC_READ_METHODS.contains(&method)
+    {
+        return Scope::Read;
+    }
+    match (service, method) {
+        ("SettingsService", _)
+        | ("BluetoothService", _)
+        | ("DeviceService", "ConnectDevice" | "DisconnectDevice")
+        | ("LibraryService", "ScanLibrary") => Scope::Admin,
+        _ => Scope::Control,
+    }
+}
+
+/// MPD commands anyone may send, so clients can authenticate and discover
+/// what they are allowed to do.
+const MPD_OPEN_COMMANDS: [&str; 7] = [
+    "password",
+    "ping",
+    "close",
+    "commands",
+    "notcommands",
+    "binarylimit",
+    "tagtypes",
+];
+
+const MPD_READ_COMMANDS: [&str; 30] = [
+    "status",
+    "currentsong",
+    "stats",
+    "idle",
+    "noidle",
+    "getvol",
+    "playlistinfo",
+    "playlistid",
+    "plchanges",
+    "list",
+    "find",
+    "search",
+    "count",
+    "lsinfo",
+    "listall",
+    "listallinfo",
+    "listfiles",
+    "albumart",
+    "readpicture",
+    "readcomments",
+    "listplaylists",
+    "listplaylistinfo",
+    "outputs",
+    "decoders",
+    "urlhandlers",
+    "command_list_begin",
+    "command_list_ok_begin",
+    "command_list_end",
+    "replay_gain_status",
+    "channels",
+];
+
+/// MPD permission level needed for `command`, or `None` when it needs none.
+/// MPD's "add" and "control" levels both map to [`Scope::Control`].
+pub fn mpd_scope(command: &str) -> Option<Scope> {
+    let mut words = command.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    if MPD_OPEN_COMMANDS.contains(&name) {
+        return None;
+    }
+    if MPD_READ_COMMANDS.contains(&name) {
+        return Some(Scope::Read);
+    }
+    // `sticker get|list|find` only read; `sticker set|delete` write.
+    if name == "sticker" && matches!(words.next(), Some("get" | "list" | "find")) {
+        return Some(Scope::Read);
+    }
+    match name {
+        "config" | "update" | "rescan" | "enableoutput" | "disableoutput" | "toggleoutput" => {
+            Some(Scope::Admin)
+        }
+        _ => Some(Scope::Control),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_are_ordered_and_round_trip() {
+        assert!(Scope::Read < Scope::Control && Scope::Control < Scope::Admin);
+        for scope in [Scope::Read, Scope::Control, Scope::Admin] {
+            assert_eq!(scope.as_str().parse::<Scope>().unwrap(), scope);
+        }
+        assert!("root".parse::<Scope>().is_err());
+    }
+
+    #[test]
+    fn rest_routes_are_classified() {
+        assert_eq!(rest_scope("GET", "/tracks"), Scope::Read);
+        assert_eq!(rest_scope("GET", "/settings"), Scope::Read);
+        assert_eq!(rest_scope("PUT", "/player/pause"), Scope::Control);
+        assert_eq!(rest_scope("POST", "/playlists"), Scope::Control);
+        assert_eq!(rest_scope("PUT", "/settings"), Scope::Admin);
+        assert_eq!(rest_scope("PUT", "/scan-library"), Scope::Admin);
+        assert_eq!(rest_scope("GET", "/library/health"), Scope::Read);
+        assert_eq!(
+            rest_scope("POST", "/library/health/orphans/remove"),
+            Scope::Admin
+        );
+        assert_eq!(rest_scope("PUT", "/tracks/abc/hidden"), Scope::Control);
+        assert_eq!(rest_scope("PUT", "/devices/abc/connect"), Scope::Admin);
+        assert_eq!(rest_scope("GET", "/webhooks"), Scope::Admin);
+        assert_eq!(rest_scope("GET", "/sources"), Scope::Admin);
+        assert_eq!(rest_scope("POST", "/sources/abc/sync"), Scope::Admin);
+        assert_eq!(rest_scope("DELETE", "/tokens/abc"), Scope::Admin);
+    }
+
+    #[test]
+    fn grpc_methods_are_classified() {
+        let path = |method: &str| format!("/rockbox.v1alpha1.{}", method);
+        assert_eq!(grpc_scope(&path("LibraryService/GetTracks")), Scope::Read);
+        assert_eq!(
+            grpc_scope(&path("PlaybackService/StreamStatus")),
+            Scope::Read
+        );
+        assert_eq!(grpc_scope(&path("PlaybackService/Status")), Scope::Read);
+        assert_eq!(grpc_scope(&path("PlaybackService/Pause")), Scope::Control);
+        assert_eq!(
+            grpc_scope(&path("SoundService/AdjustVolume")),
+            Scope::Control
+        );
+        assert_eq!(
+            grpc_scope(&path("SettingsService/GetGlobalSettings")),
+            Scope::Read
+        );
+        assert_eq!(
+            grpc_scope(&path("SettingsService/SaveSettings")),
+            Scope::Admin
+        );
+        assert_eq!(
+            grpc_scope(&path("LibraryService/ScanLibrary")),
+            Scope::Admin
+        );
+        assert_eq!(grpc_scope(&path("BluetoothService/Scan")), Scope::Admin);
+        assert_eq!(
+            grpc_scope("/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo"),
+            Scope::Read
+        );
+    }
+
+    #[test]
+    fn mpd_commands_are_classified() {
+        assert_eq!(mpd_scope("password"), None);
+        assert_eq!(mpd_scope("ping"), None);
+        assert_eq!(mpd_scope("status"), Some(Scope::Read));
+        assert_eq!(mpd_scope("list album"), Some(Scope::Read));
+        assert_eq!(mpd_scope("find artist"), Some(Scope::Read));
+        assert_eq!(mpd_scope("add"), Some(Scope::Control));
+        assert_eq!(mpd_scope("setvol"), Some(Scope::Control));
+        assert_eq!(
+            mpd_scope("sticker get song a.flac rating"),
+            Some(Scope::Read)
+        );
+        assert_eq!(
+            mpd_scope("sticker set song a.flac rating 8"),
+            Some(Scope::Control)
+        );
+        assert_eq!(mpd_scope("update"), Some(Scope::Admin));
+        assert_eq!(mpd_scope("enableoutput"), Some(Scope::Admin));
+    }
+}
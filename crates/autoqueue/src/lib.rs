@@ -0,0 +1,311 @@
+//! Endless "radio" mode.
+//!
+//! When auto-queue is on, the broker tops the play queue up as it is about
+//! to run out, with tracks drawn from a smart playlist, an instant mix
+//! around a seed, or a shuffle of the library weighted against tracks
+//! played lately. The mode is kept in settings.toml so it survives restarts.
+
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::RwLock,
+};
+
+use anyhow::{anyhow, Error, Result};
+use chrono::Utc;
+use rand::Rng;
+use rockbox_library::{entity::track::Track, repo};
+use rockbox_playlists::{resolver, PlaylistStore};
+use rockbox_similarity::SimilarityStore;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+
+/// The queue is topped up once at most this many tracks are left after the
+/// one playing.
+pub const LOW_WATER: i32 = 2;
+
+/// Tracks appended per top-up.
+pub const BATCH: usize = 10;
+
+/// Tracks played this recently are never picked.
+const RECENT_SECS: i64 = 4 * 3600;
+
+/// A track last played this long ago is as likely to be picked as one
+/// never played; in between its chance grows linearly.
+const FULL_WEIGHT_SECS: i64 = 7 * 86400;
+
+/// Tracks the end of the queue contributes as seeds to an instant mix.
+const SEED_TRACKS: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    /// The library, weighted against recently played tracks.
+    #[default]
+    Shuffle,
+    /// The tracks that sound most like the seed.
+    InstantMix,
+    /// The tracks matching a smart playlist's rules.
+    SmartPlaylist,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Source::Shuffle => "shuffle",
+            Source::InstantMix => "instant_mix",
+            Source::SmartPlaylist => "smart_playlist",
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "shuffle" => Ok(Source::Shuffle),
+            "instant_mix" => Ok(Source::InstantMix),
+            "smart_playlist" => Ok(Source::SmartPlaylist),
+            _ => Err(anyhow!("unknown auto-queue source: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AutoQueue {
+    pub enabled: bool,
+    pub source: Source,
+    /// Smart playlist the `smart_playlist` source draws from.
+    pub smart_playlist_id: Option<String>,
+    /// Track the `instant_mix` source centres on; when unset it follows the
+    /// end of the queue, so the mix drifts as it plays.
+    pub seed_track_id: Option<String>,
+}
+
+static CONFIG: RwLock<Option<AutoQueue>> = RwLock::new(None);
+
+fn load() -> AutoQueue {
+    let settings = rockbox_settings::read_settings().unwrap_or_default();
+    AutoQueue {
+        enabled: settings.auto_queue.unwrap_or_default(),
+        source: settings
+            .auto_queue_source
+            .as_deref()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default(),
+        smart_playlist_id: settings.auto_queue_smart_playlist,
+        seed_track_id: settings.auto_queue_seed,
+    }
+}
+
+/// The current auto-queue mode, read from settings.toml on first use.
+pub fn config() -> AutoQueue {
+    if let Some(config) = CONFIG.read().unwrap().as_ref() {
+        return config.clone();
+    }
+    let mut guard = CONFIG.write().unwrap();
+    guard.get_or_insert_with(load).clone()
+}
+
+/// Switch to `config` and save it to settings.toml.
+pub fn set_config(config: AutoQueue) -> Result<()> {
+    if config.source == Source::SmartPlaylist && config.smart_playlist_id.is_none() {
+        return Err(anyhow!(
+            "the smart_playlist source needs a smart_playlist_id"
+        ));
+    }
+    let mut settings = rockbox_settings::read_settings().unwrap_or_default();
+    settings.auto_queue = Some(config.enabled);
+    settings.auto_queue_source = Some(config.source.as_str().to_string());
+    settings.auto_queue_smart_playlist = config.smart_playlist_id.clone();
+    settings.auto_queue_seed = config.seed_track_id.clone();
+    rockbox_settings::save_settings_to_file(&settings)?;
+    *CONFIG.write().unwrap() = Some(config);
+    Ok(())
+}
+
+/// A change to the auto-queue mode; unset fields keep their value and an
+/// empty id clears it.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AutoQueueUpdate {
+    pub enabled: Option<bool>,
+    pub source: Option<Source>,
+    pub smart_playlist_id: Option<String>,
+    pub seed_track_id: Option<String>,
+}
+
+/// Apply `update` to the current mode, save it and return the result.
+pub fn update(update: AutoQueueUpdate) -> Result<AutoQueue> {
+    let mut config = config();
+    if let Some(enabled) = update.enabled {
+        config.enabled = enabled;
+    }
+    if let Some(source) = update.source {
+        config.source = source;
+    }
+    if let Some(id) = update.smart_playlist_id {
+        config.smart_playlist_id = Some(id).filter(|id| !id.is_empty());
+    }
+    if let Some(id) = update.seed_track_id {
+        config.seed_track_id = Some(id).filter(|id| !id.is_empty());
+    }
+    set_config(config.clone())?;
+    Ok(config)
+}
+
+/// Chance of a track last played at `last_played` being picked, relative
+/// to one never played.
+pub fn weight(last_played: Option<i64>, now: i64) -> f64 {
+    match last_played {
+        None => 1.0,
+        Some(at) if now - at < RECENT_SECS => 0.0,
+        Some(at) => ((now - at) as f64 / FULL_WEIGHT_SECS as f64).min(1.0),
+    }
+}
+
+/// Up to `count` items drawn without replacement, each with a chance
+/// proportional to its weight. Items weighing nothing are never drawn.
+pub fn weighted_sample<T, R: Rng>(items: Vec<(T, f64)>, count: usize, rng: &mut R) -> Vec<T> {
+    // Efraimidis–Spirakis: keep the largest u^(1/w).
+    let mut keyed: Vec<(f64, T)> = items
+        .into_iter()
+        .filter(|(_, w)| *w > 0.0)
+        .map(|(item, w)| (rng.gen::<f64>().powf(1.0 / w), item))
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed
+        .into_iter()
+        .take(count)
+        .map(|(_, item)| item)
+        .collect()
+}
+
+/// Up to `count` tracks to append to a queue holding `queued` (paths), by
+/// the rules of `config`. Tracks already queued, audiobooks and tracks
+/// played within the last few hours are left out. A smart playlist or
+/// instant mix that has nothing left falls back to the shuffle.
+pub async fn next_tracks(
+    pool: &Pool<Sqlite>,
+    config: &AutoQueue,
+    queued: &[String],
+    count: usize,
+) -> Result<Vec<Track>> {
+    let playlists = PlaylistStore::new(pool.clone());
+    let now = Utc::now().timestamp();
+    let last_played: HashMap<String, i64> = playlists
+        .get_all_track_stats()
+        .await?
+        .into_iter()
+        .filter_map(|s| Some((s.track_id, s.last_played?)))
+        .collect();
+    let in_queue: HashSet<&str> = queued.iter().map(String::as_str).collect();
+    let weighted = |tracks: Vec<Track>| -> Vec<(Track, f64)> {
+        tracks
+            .into_iter()
+            .filter(|t| !t.is_book() && !in_queue.contains(t.path.as_str()))
+            .map(|t| {
+                let w = weight(last_played.get(&t.id).copied(), now);
+                (t, w)
+            })
+            .collect()
+    };
+
+    let picked = match config.source {
+        Source::Shuffle => Vec::new(),
+        Source::SmartPlaylist => {
+            let id = config.smart_playlist_id.as_deref().unwrap_or_default();
+            match playlists.get_smart_playlist(id).await? {
+                Some(playlist) => {
+                    let tracks = resolver::resolve_tracks(pool, &playlist.rules).await?;
+                    weighted_sample(weighted(tracks), count, &mut rand::thread_rng())
+                }
+                None => Vec::new(),
+            }
+        }
+        Source::InstantMix => {
+            let seeds = match &config.seed_track_id {
+                Some(id) => vec![id.clone()],
+                None => {
+                    let mut seeds = Vec::new();
+                    for path in queued.iter().rev().take(SEED_TRACKS) {
+                        if let Some(track) = repo::track::find_by_path(pool.clone(), path).await? {
+                            seeds.push(track.id);
+                        }
+                    }
+                    seeds
+                }
+            };
+            // Ask for enough neighbours that some survive the filtering;
+            // they stay in similarity order.
+            let similar = SimilarityStore::new(pool.clone())
+                .similar_tracks(&seeds, queued.len() + count * 4)
+                .await?;
+            weighted(similar)
+                .into_iter()
+                .filter(|(_, w)| *w > 0.0)
+                .map(|(t, _)| t)
+                .take(count)
+                .collect()
+        }
+    };
+    if !picked.is_empty() {
+        return Ok(picked);
+    }
+
+    let library = repo::track::all(pool.clone()).await?;
+    Ok(weighted_sample(
+        weighted(library),
+        count,
+        &mut rand::thread_rng(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn recently_played_tracks_weigh_less() {
+        let now = 1_000_000_000;
+        assert_eq!(weight(None, now), 1.0);
+        assert_eq!(weight(Some(now - 60), now), 0.0);
+        let day = weight(Some(now - 86400), now);
+        let week = weight(Some(now - 7 * 86400), now);
+        assert!(day > 0.0 && day < week);
+        assert_eq!(week, 1.0);
+        assert_eq!(weight(Some(now - 30 * 86400), now), 1.0);
+    }
+
+    #[test]
+    fn weighted_sample_skips_weightless_items() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let items = vec![("a", 1.0), ("b", 0.0), ("c", 0.5), ("d", 1.0)];
+        let picked = weighted_sample(items.clone(), 10, &mut rng);
+        assert_eq!(picked.len(), 3);
+        assert!(!picked.contains(&"b"));
+        assert_eq!(weighted_sample(items, 2, &mut rng).len(), 2);
+    }
+
+    #[test]
+    fn weighted_sample_favours_heavier_items() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut heavy = 0;
+        for _ in 0..1000 {
+            let picked = weighted_sample(vec![("light", 0.1), ("heavy", 1.0)], 1, &mut rng);
+            if picked == ["heavy"] {
+                heavy += 1;
+            }
+        }
+        assert!(heavy > 850, "heavy picked {} times", heavy);
+    }
+
+    #[test]
+    fn sources_round_trip() {
+        for source in [Source::Shuffle, Source::InstantMix, Source::SmartPlaylist] {
+            assert_eq!(source.as_str().parse::<Source>().unwrap(), source);
+        }
+        assert!("party".parse::<Source>().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 pub mod pcm;
 
 use std::{
+    collections::HashMap,
     future::Future,
     pin::Pin,
     str::FromStr,
@@ -21,6 +22,7 @@ use chromecast::{
     },
     CastDevice,
 };
+use rockbox_traits::types::chapter::{self, Chapter};
 use rockbox_traits::types::playback::Playback;
 use rockbox_traits::types::track::Track;
 use rockbox_traits::Player;
@@ -42,6 +44,9 @@ pub struct Chromecast<'a> {
     cast_player: Option<CastPlayer>,
     current_playback: Arc<Mutex<CurrentPlayback>>,
     cmd_tx: Option<mpsc::UnboundedSender<CastPlayerCommand>>,
+    // Chapters of the queued tracks, keyed by content id — the cast status
+    // only echoes the media metadata back, so they are kept on this side.
+    chapters: Arc<Mutex<HashMap<String, Vec<Chapter>>>>,
 }
 
 impl<'a> Chromecast<'a> {
@@ -55,9 +60,29 @@ impl<'a> Chromecast<'a> {
             cast_player: None,
             current_playback: Arc::new(Mutex::new(CurrentPlayback::new())),
             cmd_tx: None,
+            chapters: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    fn remember_chapters(&self, track: &Track) {
+        if !track.chapters.is_empty() {
+            self.chapters
+                .lock()
+                .unwrap()
+                .insert(track.uri.clone(), track.chapters.clone());
+        }
+    }
+
+    /// Chapters of the track being cast and the current position in it.
+    async fn current_chapters(&mut self) -> Result<(Vec<Chapter>, u64), Error> {
+        let playback = self.get_current_playback().await?;
+        let chapters = playback
+            .current_track
+            .and_then(|track| self.chapters.lock().unwrap().get(&track.uri).cloned())
+            .unwrap_or_default();
+        Ok((chapters, playback.position_ms as u64))
+    }
+
     pub fn connect(device: Device) -> Result<Option<Box<dyn Player + Send + 'a>>, Error> {
         let mut player: Self = device.clone().into();
 
@@ -243,6 +268,9 @@ impl<'a> Player for Chromecast<'a> {
     }
 
     async fn load_tracks(&self, tracks: Vec<Track>, start_index: Option<i32>) -> Result<(), Error> {
+        tracks
+            .iter()
+            .for_each(|track| self.remember_chapters(track));
         let media = tracks
             .iter()
             .map(|track| Media {
@@ -288,6 +316,7 @@ impl<'a> Player for Chromecast<'a> {
 
     async fn play_next(&self, track: Track) -> Result<(), Error> {
         if let Some(cmd_tx) = &self.cmd_tx {
+            self.remember_chapters(&track);
             cmd_tx.send(CastPlayerCommand::PlayNext(track)).unwrap();
             return Ok(());
         }
@@ -295,6 +324,7 @@ impl<'a> Player for Chromecast<'a> {
     }
 
     async fn load(&mut self, track: Track) -> Result<(), Error> {
+        self.remember_chapters(&track);
         let (cast_device, transport_id, _, session_id) = self.current_app_session()?;
 
         cast_device.media.load(
@@ -347,6 +377,22 @@ impl<'a> Player for Chromecast<'a> {
             .send(CastPlayerCommand::Disconnect)?;
         Ok(())
     }
+
+    async fn next_chapter(&mut self) -> Result<(), Error> {
+        let (chapters, position_ms) = self.current_chapters().await?;
+        match chapter::next_chapter_start(&chapters, position_ms) {
+            Some(start_ms) => self.seek((start_ms / 1000) as i32).await,
+            None => self.next().await,
+        }
+    }
+
+    async fn previous_chapter(&mut self) -> Result<(), Error> {
+        let (chapters, position_ms) = self.current_chapters().await?;
+        match chapter::previous_chapter_start(&chapters, position_ms) {
+            Some(start_ms) => self.seek((start_ms / 1000) as i32).await,
+            None => self.previous().await,
+        }
+    }
 }
 
 impl<'a> From<Device> for Chromecast<'a> {
@@ -1,7 +1,9 @@
 use anyhow::Error;
 
 pub mod login;
+pub mod schedule;
 pub mod settings;
+pub mod token;
 pub mod whoami;
 
 // Force rockbox-airplay and rockbox-slim symbols into librockbox_cli.a
@@ -9,16 +11,10 @@ use clap::Command;
 use owo_colors::OwoColorize;
 #[allow(unused_imports)]
 use rockbox_airplay::_link_airplay as _;
-#[cfg(feature = "alsa-sink")]
-#[allow(unused_imports)]
-use rockbox_alsa_sink::_link_alsa_sink as _;
 #[allow(unused_imports)]
 use rockbox_chromecast::_link_chromecast as _;
 #[allow(unused_imports)]
 use rockbox_cmaf::_link_cmaf as _;
-#[cfg(feature = "cpal-sink")]
-#[allow(unused_imports)]
-use rockbox_cpal_sink::_link_cpal_sink as _;
 #[allow(unused_imports)]
 use rockbox_hls::_link_hls as _;
 use rockbox_library::audio_scan::{save_audio_metadata, scan_audio_files};
@@ -192,7 +188,126 @@ pub extern "C" fn parse_args(argc: usize, argv: *const *const u8) -> i32 {
                         .help("Your Bluesky handle"),
                 ),
         )
-        .subcommand(Command::new("whoami").about("Show the currently logged-in Rocksky user"));
+        .subcommand(Command::new("whoami").about("Show the currently logged-in Rocksky user"))
+        .subcommand(
+            Command::new("token")
+                .about("Manage API tokens for the HTTP, GraphQL, gRPC and MPD servers")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("create")
+                        .about("Mint a token; it is printed once")
+                        .arg(
+                            clap::Arg::new("name")
+                                .required(true)
+                                .help("What the token is for, e.g. \"living room tablet\""),
+                        )
+                        .arg(
+                            clap::Arg::new("scope")
+                                .long("scope")
+                                .short('s')
+                                .value_parser(["read", "control", "admin"])
+                                .default_value("control")
+                                .help("What the token may do"),
+                        ),
+                )
+                .subcommand(Command::new("list").about("List API tokens"))
+                .subcommand(
+                    Command::new("revoke")
+                        .about("Revoke a token by id or name")
+                        .arg(clap::Arg::new("id").required(true).help("Token id or name")),
+                ),
+        )
+        .subcommand(
+            Command::new("schedule")
+                .about("Manage alarms and scheduled playback")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(Command::new("list").about("List schedules, soonest first"))
+                .subcommand(
+                    Command::new("add")
+                        .about("Add a one-shot (--at) or recurring (--cron) schedule")
+                        .arg(
+                            clap::Arg::new("action")
+                                .required(true)
+                                .value_parser(rockbox_scheduler::action::ALL.to_vec())
+                                .help("What to do when the schedule runs"),
+                        )
+                        .arg(
+                            clap::Arg::new("target")
+                                .help("Saved playlist or radio station id, for play_playlist and play_radio"),
+                        )
+                        .arg(
+                            clap::Arg::new("cron")
+                                .long("cron")
+                                .value_name("EXPR")
+                                .conflicts_with("at")
+                                .required_unless_present("at")
+                                .help("Five-field cron expression in local time, e.g. \"30 7 * * 1-5\""),
+                        )
+                        .arg(
+                            clap::Arg::new("at")
+                                .long("at")
+                                .value_name("TIME")
+                                .help("HH:MM or \"YYYY-MM-DD HH:MM\", local time"),
+                        )
+                        .arg(clap::Arg::new("name").long("name").help("Defaults to the action"))
+                        .arg(
+                            clap::Arg::new("volume")
+                                .long("volume")
+                                .value_name("DB")
+                                .allow_negative_numbers(true)
+                                .value_parser(clap::value_parser!(i32))
+                                .help("Volume to ramp up to; the current volume by default"),
+                        )
+                        .arg(
+                            clap::Arg::new("ramp")
+                                .long("ramp")
+                                .value_name("SECS")
+                                .value_parser(clap::value_parser!(i64))
+                                .help("Ramp the volume up over this many seconds, or fade out for sleep"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("remove")
+                        .about("Remove a schedule")
+                        .arg(clap::Arg::new("id").required(true).help("Schedule id")),
+                )
+                .subcommand(
+                    Command::new("enable")
+                        .about("Enable a schedule")
+                        .arg(clap::Arg::new("id").required(true).help("Schedule id")),
+                )
+                .subcommand(
+                    Command::new("disable")
+                        .about("Disable a schedule without removing it")
+                        .arg(clap::Arg::new("id").required(true).help("Schedule id")),
+                ),
+        )
+        .subcommand(
+            Command::new("sleep")
+                .about("Set, show or cancel the sleep timer")
+                .arg(
+                    clap::Arg::new("minutes")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .help("Pause playback after this many minutes"),
+                )
+                .arg(
+                    clap::Arg::new("fade")
+                        .long("fade")
+                        .value_name("SECS")
+                        .value_parser(clap::value_parser!(i64))
+                        .default_value("0")
+                        .help("Fade out over the last SECS seconds"),
+                )
+                .arg(
+                    clap::Arg::new("cancel")
+                        .long("cancel")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("minutes")
+                        .help("Cancel the running sleep timer"),
+                ),
+        );
 
     let matches = cli.get_matches_from(args);
 
@@ -237,6 +352,74 @@ pub extern "C" fn parse_args(argc: usize, argv: *const *const u8) -> i32 {
                 std::process::exit(1);
             }
         },
+        Some(("token", sub_m)) => {
+            let result = match sub_m.subcommand() {
+                Some(("create", m)) => {
+                    let name = m.get_one::<String>("name").unwrap();
+                    let scope = m.get_one::<String>("scope").unwrap();
+                    rt.block_on(token::create(name, scope))
+                }
+                Some(("list", _)) => rt.block_on(token::list()),
+                Some(("revoke", m)) => {
+                    rt.block_on(token::revoke(m.get_one::<String>("id").unwrap()))
+                }
+                _ => unreachable!(),
+            };
+            match result {
+                Ok(_) => std::process::exit(0),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(("schedule", sub_m)) => {
+            let result = match sub_m.subcommand() {
+                Some(("list", _)) => rt.block_on(schedule::list()),
+                Some(("add", m)) => rt.block_on(schedule::add(
+                    m.get_one::<String>("action").unwrap(),
+                    m.get_one::<String>("target").cloned(),
+                    m.get_one::<String>("cron").cloned(),
+                    m.get_one::<String>("at").map(|s| s.as_str()),
+                    m.get_one::<String>("name").cloned(),
+                    m.get_one::<i32>("volume").copied(),
+                    m.get_one::<i64>("ramp").copied(),
+                )),
+                Some(("remove", m)) => {
+                    rt.block_on(schedule::remove(m.get_one::<String>("id").unwrap()))
+                }
+                Some(("enable", m)) => rt.block_on(schedule::set_enabled(
+                    m.get_one::<String>("id").unwrap(),
+                    true,
+                )),
+                Some(("disable", m)) => rt.block_on(schedule::set_enabled(
+                    m.get_one::<String>("id").unwrap(),
+                    false,
+                )),
+                _ => unreachable!(),
+            };
+            match result {
+                Ok(_) => std::process::exit(0),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(("sleep", sub_m)) => {
+            let result = rt.block_on(schedule::sleep(
+                sub_m.get_one::<u32>("minutes").copied(),
+                *sub_m.get_one::<i64>("fade").unwrap(),
+                sub_m.get_flag("cancel"),
+            ));
+            match result {
+                Ok(_) => std::process::exit(0),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
         _ => {} // Fall through to starting the Rockbox server
     }
 
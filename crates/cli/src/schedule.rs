@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Error};
+use chrono::{Local, NaiveDateTime, NaiveTime, TimeZone};
+use rockbox_library::create_connection_pool;
+use rockbox_scheduler::{NewSchedule, ScheduleUpdate, SchedulerStore};
+
+async fn store() -> Result<SchedulerStore, Error> {
+    let pool = create_connection_pool().await?;
+    Ok(SchedulerStore::new(pool))
+}
+
+fn format_time(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// `HH:MM` (the next time the clock shows it) or `YYYY-MM-DD HH:MM`, local.
+fn parse_at(at: &str) -> Result<i64, Error> {
+    let now = Local::now();
+    let naive = match NaiveTime::parse_from_str(at, "%H:%M") {
+        Ok(time) => {
+            let today = now.date_naive().and_time(time);
+            match today > now.naive_local() {
+                true => today,
+                false => today + chrono::Duration::days(1),
+            }
+        }
+        Err(_) => NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M")
+            .map_err(|_| anyhow!("expected HH:MM or \"YYYY-MM-DD HH:MM\", got \"{}\"", at))?,
+    };
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.timestamp())
+        .ok_or_else(|| anyhow!("{} does not exist in the local time zone", at))
+}
+
+pub async fn list() -> Result<(), Error> {
+    let schedules = store().await?.list().await?;
+    if schedules.is_empty() {
+        println!("No schedules.");
+        return Ok(());
+    }
+    println!(
+        "{:<36}  {:<13}  {:<16}  {:<16}  NAME",
+        "ID", "ACTION", "WHEN", "NEXT RUN"
+    );
+    for schedule in schedules {
+        let when = match (&schedule.cron, schedule.run_at) {
+            (Some(cron), _) => cron.clone(),
+            (None, Some(run_at)) => format_time(run_at),
+            (None, None) => String::new(),
+        };
+        let next_run = match schedule.enabled {
+            true => schedule
+                .next_run
+                .map(format_time)
+                .unwrap_or_else(|| "never".to_string()),
+            false => "disabled".to_string(),
+        };
+        println!(
+            "{:<36}  {:<13}  {:<16}  {:<16}  {}",
+            schedule.id, schedule.action, when, next_run, schedule.name
+        );
+    }
+    Ok(())
+}
+
+pub async fn add(
+    action: &str,
+    target: Option<String>,
+    cron: Option<String>,
+    at: Option<&str>,
+    name: Option<String>,
+    volume: Option<i32>,
+    ramp_secs: Option<i64>,
+) -> Result<(), Error> {
+    let schedule = store()
+        .await?
+        .create(NewSchedule {
+            name: name.unwrap_or_default(),
+            action: action.to_string(),
+            target,
+            cron,
+            run_at: at.map(parse_at).transpose()?,
+            volume,
+            ramp_secs,
+            enabled: None,
+        })
+        .await?;
+    println!("Added schedule \"{}\" ({})", schedule.name, schedule.id);
+    if let Some(next_run) = schedule.next_run {
+        println!("Next run: {}", format_time(next_run));
+    }
+    Ok(())
+}
+
+pub async fn remove(id: &str) -> Result<(), Error> {
+    match store().await?.delete(id).await? {
+        true => {
+            println!("Removed schedule {}", id);
+            Ok(())
+        }
+        false => Err(anyhow!("no schedule with id \"{}\"", id)),
+    }
+}
+
+pub async fn set_enabled(id: &str, enabled: bool) -> Result<(), Error> {
+    let update = ScheduleUpdate {
+        enabled: Some(enabled),
+        ..Default::default()
+    };
+    let schedule = store()
+        .await?
+        .update(id, update)
+        .await?
+        .ok_or_else(|| anyhow!("no schedule with id \"{}\"", id))?;
+    match (enabled, schedule.next_run) {
+        (true, Some(next_run)) => println!(
+            "Enabled \"{}\", next run {}",
+            schedule.name,
+            format_time(next_run)
+        ),
+        (true, None) => println!("Enabled \"{}\"", schedule.name),
+        (false, _) => println!("Disabled \"{}\"", schedule.name),
+    }
+    Ok(())
+}
+
+pub async fn sleep(minutes: Option<u32>, fade_secs: i64, cancel: bool) -> Result<(), Error> {
+    let store = store().await?;
+    if cancel {
+        return match store.cancel_sleep_timer().await? {
+            true => {
+                println!("Sleep timer cancelled");
+                Ok(())
+            }
+            false => Err(anyhow!("no sleep timer running")),
+        };
+    }
+    let timer = match minutes {
+        Some(minutes) => store.set_sleep_timer(minutes, fade_secs).await?,
+        None => match store.sleep_timer().await? {
+            Some(timer) => timer,
+            None => {
+                println!("No sleep timer running.");
+                return Ok(());
+            }
+        },
+    };
+    println!(
+        "Pausing at {} ({} min left{})",
+        format_time(timer.ends_at),
+        (timer.remaining_secs + 59) / 60,
+        match timer.fade_secs {
+            0 => String::new(),
+            secs => format!(", {}s fade", secs),
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Error};
+use chrono::{TimeZone, Utc};
+use rockbox_auth::{Auth, AuthConfig, Scope};
+use rockbox_library::create_connection_pool;
+
+async fn auth() -> Result<Auth, Error> {
+    let pool = create_connection_pool().await?;
+    Ok(Auth::new(pool, AuthConfig::default()))
+}
+
+fn format_time(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+pub async fn create(name: &str, scope: &str) -> Result<(), Error> {
+    let scope = scope.parse::<Scope>()?;
+    let token = auth().await?.create(name, scope).await?;
+    println!(
+        "Created {} token \"{}\" ({})",
+        scope, name, token.api_token.id
+    );
+    println!();
+    println!("    {}", token.token);
+    println!();
+    println!("Store it now; it cannot be shown again.");
+    Ok(())
+}
+
+pub async fn list() -> Result<(), Error> {
+    let tokens = auth().await?.list().await?;
+    if tokens.is_empty() {
+        println!("No API tokens. The APIs are open to the network until one is created.");
+        return Ok(());
+    }
+    println!(
+        "{:<36}  {:<8}  {:<16}  {:<16}  NAME",
+        "ID", "SCOPE", "CREATED", "LAST USED"
+    );
+    for token in tokens {
+        println!(
+            "{:<36}  {:<8}  {:<16}  {:<16}  {}",
+            token.id,
+            token.scope,
+            format_time(token.created_at),
+            token
+                .last_used_at
+                .map(format_time)
+                .unwrap_or_else(|| "never".to_string()),
+            token.name
+        );
+    }
+    Ok(())
+}
+
+pub async fn revoke(id_or_name: &str) -> Result<(), Error> {
+    match auth().await?.revoke(id_or_name).await? {
+        0 => Err(anyhow!("no token with id or name \"{}\"", id_or_name)),
+        n => {
+            println!("Revoked {} token(s)", n);
+            Ok(())
+        }
+    }
+}
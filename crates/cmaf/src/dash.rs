@@ -3,26 +3,37 @@
 use std::fmt::Write;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::{AAC_FRAME_SAMPLES, FRAMES_PER_SEGMENT, SAMPLE_RATE, SEGMENT_WINDOW};
+use crate::{
+    mp4, part_seconds,
+    rendition::{Codec, Rendition},
+    segment_seconds, Playlist, SAMPLE_RATE, SEGMENT_SAMPLES,
+};
 
-/// `start_unix_ms` is the wall-clock time when the encoder pushed the
-/// init segment (i.e. when segment seq=1 became available).
-pub(crate) fn manifest_mpd(start_unix_ms: u64, latest_seq: u64) -> String {
-    let availability_start = format_unix_ms_as_iso8601(start_unix_ms);
+/// AAC renditions share one AdaptationSet so players switch between them
+/// freely; the lossless rendition gets its own, as DASH doesn't allow
+/// mixing codecs within a set.
+///
+/// With `low_latency` segments are announced one part before they end
+/// (`availabilityTimeOffset`) — the HTTP server sends a segment still being
+/// produced with chunked transfer encoding — and a latency target plus the
+/// server clock are included for dash.js' low-latency mode.
+pub(crate) fn manifest_mpd(playlist: &Playlist, low_latency: bool) -> String {
+    let availability_start = format_unix_ms_as_iso8601(playlist.start_unix_ms);
     let publish_time = format_unix_ms_as_iso8601(now_unix_ms());
 
     let timescale = SAMPLE_RATE;
-    let seg_duration = (FRAMES_PER_SEGMENT * AAC_FRAME_SAMPLES) as u32;
-    let seg_seconds = (seg_duration as f64) / (timescale as f64);
-    let time_shift = (SEGMENT_WINDOW as f64 * seg_seconds).max(seg_seconds);
+    let seg_seconds = segment_seconds();
+    let time_shift = (playlist.window as f64 * seg_seconds).max(seg_seconds);
     let mup = seg_seconds; // minimumUpdatePeriod
     let suggested_pres_delay = seg_seconds * 3.0;
-    let bitrate = crate::bitrate_bps();
-
-    // startNumber should match the absolute segment number that will be
-    // visible at availabilityStartTime + 0. Use 1 (we always reset seqs
-    // when the stream starts).
-    let _ = latest_seq;
+    let availability = if low_latency {
+        format!(
+            " availabilityTimeOffset=\"{:.3}\" availabilityTimeComplete=\"false\"",
+            seg_seconds - part_seconds()
+        )
+    } else {
+        String::new()
+    };
 
     let mut s = String::new();
     s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
@@ -40,36 +51,80 @@ minBufferTime=\"PT{seg_seconds:.3}S\">"
     )
     .unwrap();
 
+    if low_latency {
+        let part_ms = (part_seconds() * 1000.0) as u32;
+        let seg_ms = (seg_seconds * 1000.0) as u32;
+        s.push_str("  <ServiceDescription id=\"0\">\n");
+        writeln!(
+            s,
+            "    <Latency target=\"{seg_ms}\" min=\"{}\" max=\"{}\"/>",
+            part_ms * 2,
+            seg_ms * 3
+        )
+        .unwrap();
+        s.push_str("  </ServiceDescription>\n");
+    }
     s.push_str("  <Period id=\"0\" start=\"PT0S\">\n");
-    writeln!(
-        s,
-        "    <AdaptationSet id=\"0\" contentType=\"audio\" mimeType=\"audio/mp4\" \
-codecs=\"mp4a.40.2\" segmentAlignment=\"true\" startWithSAP=\"1\">"
-    )
-    .unwrap();
-    writeln!(
-        s,
-        "      <AudioChannelConfiguration \
+    let aac: Vec<usize> = (0..playlist.renditions.len())
+        .filter(|&i| matches!(playlist.renditions[i].codec, Codec::Aac { .. }))
+        .collect();
+    let flac: Vec<usize> = (0..playlist.renditions.len())
+        .filter(|&i| playlist.renditions[i].codec == Codec::Flac)
+        .collect();
+    for (set_id, set) in [aac, flac].iter().filter(|set| !set.is_empty()).enumerate() {
+        let codecs = playlist.renditions[set[0]].dash_codecs();
+        writeln!(
+            s,
+            "    <AdaptationSet id=\"{set_id}\" contentType=\"audio\" mimeType=\"audio/mp4\" \
+codecs=\"{codecs}\" segmentAlignment=\"true\" startWithSAP=\"1\">"
+        )
+        .unwrap();
+        writeln!(
+            s,
+            "      <AudioChannelConfiguration \
 schemeIdUri=\"urn:mpeg:dash:23003:3:audio_channel_configuration:2011\" value=\"2\"/>"
-    )
-    .unwrap();
-    writeln!(
-        s,
-        "      <Representation id=\"audio0\" bandwidth=\"{bitrate}\" \
-audioSamplingRate=\"{SAMPLE_RATE}\">"
-    )
-    .unwrap();
+        )
+        .unwrap();
+        writeln!(
+            s,
+            "      <InbandEventStream schemeIdUri=\"{}\"/>",
+            mp4::ID3_SCHEME
+        )
+        .unwrap();
+        // startNumber is 1: sequence numbers restart with the stream, and
+        // segment 1 starts at availabilityStartTime.
+        writeln!(
+            s,
+            "      <SegmentTemplate timescale=\"{timescale}\" duration=\"{SEGMENT_SAMPLES}\" \
+startNumber=\"1\" initialization=\"/$RepresentationID$/init.mp4\" \
+media=\"/$RepresentationID$/seg/$Number$.m4s\"{availability}/>"
+        )
+        .unwrap();
+        for &i in set {
+            write_representation(&mut s, &playlist.renditions[i], playlist.bandwidth[i].0);
+        }
+        s.push_str("    </AdaptationSet>\n");
+    }
+    s.push_str("  </Period>\n");
+    if low_latency {
+        writeln!(
+            s,
+            "  <UTCTiming schemeIdUri=\"urn:mpeg:dash:utc:direct:2014\" value=\"{publish_time}\"/>"
+        )
+        .unwrap();
+    }
+    s.push_str("</MPD>\n");
+    s
+}
+
+fn write_representation(s: &mut String, rendition: &Rendition, bandwidth: u32) {
     writeln!(
         s,
-        "        <SegmentTemplate timescale=\"{timescale}\" duration=\"{seg_duration}\" \
-startNumber=\"1\" initialization=\"/init.mp4\" media=\"/seg/$Number$.m4s\"/>"
+        "      <Representation id=\"{}\" bandwidth=\"{bandwidth}\" \
+audioSamplingRate=\"{SAMPLE_RATE}\"/>",
+        rendition.id
     )
     .unwrap();
-    s.push_str("      </Representation>\n");
-    s.push_str("    </AdaptationSet>\n");
-    s.push_str("  </Period>\n");
-    s.push_str("</MPD>\n");
-    s
 }
 
 fn now_unix_ms() -> u64 {
@@ -82,7 +137,7 @@ fn now_unix_ms() -> u64 {
 /// Minimal ISO-8601 in UTC ("YYYY-MM-DDTHH:MM:SS.mmmZ") without bringing in
 /// chrono. Uses the proleptic-Gregorian / civil-from-days algorithm
 /// (Howard Hinnant's date library).
-fn format_unix_ms_as_iso8601(ms: u64) -> String {
+pub(crate) fn format_unix_ms_as_iso8601(ms: u64) -> String {
     let secs = (ms / 1000) as i64;
     let millis = (ms % 1000) as u32;
 
@@ -1,7 +1,8 @@
-//! Encoder loop: pull raw S16LE stereo PCM from the intake, feed it to
-//! fdk-aac one 1024-sample frame at a time, accumulate
-//! [`crate::FRAMES_PER_SEGMENT`] frames into a complete fMP4 segment, and
-//! publish to the segment store.
+//! Encoder loop: pull raw S16LE stereo PCM from the intake, feed it one
+//! 1024-sample frame at a time to every rendition's encoder (fdk-aac or
+//! [`crate::flac`]), wrap every [`crate::PART_FRAMES`] frames in a CMAF
+//! chunk, and publish each part to the segment store once every rendition
+//! has finished it. [`crate::FRAMES_PER_SEGMENT`] frames make a segment.
 //!
 //! The encoder is **dumb**: it reads whatever the intake gives it and
 //! encodes it. It never injects silence into the middle of real audio —
@@ -15,14 +16,17 @@
 //! of silence at a time — small enough that any real-audio resume picks
 //! up within ~one frame's worth of latency.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use fdk_aac::enc::{AudioObjectType, BitRate, ChannelMode, Encoder, EncoderParams, Transport};
 
 use crate::{
-    intake, mp4, PcmIntake, Segment, SegmentStore, AAC_FRAME_SAMPLES, CHANNELS, FRAMES_PER_SEGMENT,
-    SAMPLE_RATE, SEGMENT_WINDOW,
+    flac, id3, intake, metadata, mp4,
+    rendition::{Codec, Rendition},
+    PcmIntake, SegmentStore, AAC_FRAME_SAMPLES, CHANNELS, FRAMES_PER_SEGMENT, PARTS_PER_SEGMENT,
+    PART_FRAMES, SAMPLE_RATE, SEGMENT_SAMPLES, SEGMENT_WINDOW,
 };
 
 /// 1024 samples × 2 channels × 2 bytes/sample.
@@ -37,43 +41,195 @@ const IDLE_BEFORE_PACER: Duration = Duration::from_millis(1500);
 /// How often the pacer wakes up to check whether the intake needs silence.
 const PACER_TICK: Duration = Duration::from_millis(100);
 
+enum FrameEncoder {
+    Aac(Encoder),
+    Flac(flac::Encoder),
+}
+
+/// One rendition's encoder and the frames of the part it is building.
+/// Segment numbers count the rendition's *output* frames, so an encoder
+/// that holds back its first frames (fdk-aac's priming) still stamps each
+/// segment with the same decode time as the others.
+struct Track {
+    encoder: FrameEncoder,
+    output: Vec<u8>,
+    frames: Vec<Vec<u8>>,
+    /// Frames of the current segment already emitted in earlier parts.
+    segment_frames: usize,
+    part: usize,
+    next_seq: u64,
+}
+
+/// One rendition's chunk for part `part` of segment `seq`.
+struct Part {
+    seq: u64,
+    part: usize,
+    /// In mdhd timescale.
+    duration: u32,
+    bytes: Vec<u8>,
+    last: bool,
+}
+
+impl Track {
+    fn new(rendition: &Rendition) -> Result<Self, String> {
+        let encoder = match rendition.codec {
+            Codec::Aac { bitrate_bps } => FrameEncoder::Aac(
+                Encoder::new(EncoderParams {
+                    bit_rate: BitRate::Cbr(bitrate_bps),
+                    sample_rate: SAMPLE_RATE,
+                    transport: Transport::Raw,
+                    channels: ChannelMode::Stereo,
+                    audio_object_type: AudioObjectType::Mpeg4LowComplexity,
+                })
+                .map_err(|e| format!("fdk-aac ({}): {e:?}", rendition.id))?,
+            ),
+            Codec::Flac => FrameEncoder::Flac(flac::Encoder::new()),
+        };
+        Ok(Track {
+            encoder,
+            output: vec![0u8; 8192],
+            frames: Vec::with_capacity(PART_FRAMES),
+            segment_frames: 0,
+            part: 0,
+            next_seq: 1,
+        })
+    }
+
+    /// Encode one frame of interleaved samples. Returns the finished part
+    /// when this frame completed one. The first part of a segment starts
+    /// with the segment header (styp + timed ID3), so the parts of a
+    /// segment concatenate to the whole segment.
+    fn encode(&mut self, samples: &[i16]) -> Result<Option<Part>, String> {
+        match &mut self.encoder {
+            FrameEncoder::Aac(encoder) => {
+                let info = encoder
+                    .encode(samples, &mut self.output)
+                    .map_err(|e| format!("fdk-aac encode: {e:?}"))?;
+                if info.output_size == 0 {
+                    return Ok(None);
+                }
+                self.frames.push(self.output[..info.output_size].to_vec());
+            }
+            FrameEncoder::Flac(encoder) => self.frames.push(encoder.encode(samples)),
+        }
+        let last = self.segment_frames + self.frames.len() == FRAMES_PER_SEGMENT;
+        if self.frames.len() < PART_FRAMES && !last {
+            return Ok(None);
+        }
+
+        let seq = self.next_seq;
+        let segment_start = (seq - 1) * SEGMENT_SAMPLES as u64;
+        let mut bytes = Vec::new();
+        if self.part == 0 {
+            let meta = metadata();
+            let tag = (!meta.title.is_empty()).then(|| id3::tag(&meta.title, &meta.artist));
+            bytes = mp4::write_segment_header(
+                segment_start,
+                tag.as_deref().map(|tag| mp4::TimedId3 { id: meta.id, tag }),
+            );
+        }
+        bytes.extend(mp4::write_chunk(
+            ((seq - 1) as usize * PARTS_PER_SEGMENT + self.part + 1) as u32,
+            segment_start + (self.segment_frames * AAC_FRAME_SAMPLES) as u64,
+            AAC_FRAME_SAMPLES as u32,
+            &self.frames,
+        ));
+        let part = Part {
+            seq,
+            part: self.part,
+            duration: (self.frames.len() * AAC_FRAME_SAMPLES) as u32,
+            bytes,
+            last,
+        };
+
+        self.segment_frames += self.frames.len();
+        self.frames.clear();
+        self.part += 1;
+        if last {
+            self.segment_frames = 0;
+            self.part = 0;
+            self.next_seq += 1;
+        }
+        Ok(Some(part))
+    }
+}
+
+/// Collects each rendition's chunk of a part and publishes the part once
+/// all of them are in. Renditions run at most a frame or two apart, so
+/// parts complete — and reach the store — in order.
+struct Assembler {
+    renditions: usize,
+    pending: BTreeMap<(u64, usize), Vec<Option<Vec<u8>>>>,
+    published: usize,
+}
+
+impl Assembler {
+    fn new(renditions: usize) -> Self {
+        Assembler {
+            renditions,
+            pending: BTreeMap::new(),
+            published: 0,
+        }
+    }
+
+    fn add(&mut self, store: &SegmentStore, rendition: usize, part: Part) {
+        let key = (part.seq, part.part);
+        let slots = self
+            .pending
+            .entry(key)
+            .or_insert_with(|| vec![None; self.renditions]);
+        slots[rendition] = Some(part.bytes);
+        if slots.iter().any(Option::is_none) {
+            return;
+        }
+        let Some(slots) = self.pending.remove(&key) else {
+            return;
+        };
+        let chunks: Vec<Vec<u8>> = slots.into_iter().flatten().collect();
+        store.push_part(part.seq, part.duration, chunks, part.last);
+        if part.last {
+            tracing::debug!("cmaf: emit seg {}", part.seq);
+            self.published += 1;
+        }
+    }
+}
+
+fn encode_frame(
+    tracks: &mut [Track],
+    assembler: &mut Assembler,
+    store: &SegmentStore,
+    samples: &[i16],
+) -> Result<(), String> {
+    for (i, track) in tracks.iter_mut().enumerate() {
+        if let Some(part) = track.encode(samples)? {
+            assembler.add(store, i, part);
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn run(
     _intake: Arc<PcmIntake>,
     store: Arc<SegmentStore>,
-    bitrate_bps: u32,
+    renditions: Vec<Rendition>,
 ) -> Result<(), String> {
-    let encoder = Encoder::new(EncoderParams {
-        bit_rate: BitRate::Cbr(bitrate_bps),
-        sample_rate: SAMPLE_RATE,
-        transport: Transport::Raw,
-        channels: ChannelMode::Stereo,
-        audio_object_type: AudioObjectType::Mpeg4LowComplexity,
-    })
-    .map_err(|e| format!("fdk-aac: {e:?}"))?;
-
-    let init_seg = mp4::write_init_segment();
-    store.set_init(init_seg);
-
-    let mut output = vec![0u8; 8192];
-    let mut frames_in_seg: Vec<Vec<u8>> = Vec::with_capacity(FRAMES_PER_SEGMENT);
-    let mut next_seq: u64 = 1;
-    let mut base_media_decode_time: u64 = 0;
+    let mut tracks = renditions
+        .iter()
+        .map(Track::new)
+        .collect::<Result<Vec<_>, _>>()?;
+    store.set_init(renditions.iter().map(mp4::write_init_segment).collect());
+
+    let mut assembler = Assembler::new(tracks.len());
 
     // ----- Bootstrap: pre-fill the segment window with silence -----
     // hls.js / dash.js refuse to start if the very first manifest they see
     // has zero segments. Produce `SEGMENT_WINDOW` silence segments before
     // entering the main loop so clients connecting to a freshly-booted
-    // rockboxd hit a healthy stream.
+    // rockboxd hit a healthy stream. Every rendition gets the same frames,
+    // so the ones without priming delay run a frame or two ahead.
     let silence_samples = vec![0i16; AAC_FRAME_SAMPLES * (CHANNELS as usize)];
-    for _ in 0..SEGMENT_WINDOW {
-        emit_silence_segment(
-            &encoder,
-            &mut output,
-            &silence_samples,
-            &store,
-            &mut next_seq,
-            &mut base_media_decode_time,
-        )?;
+    while assembler.published < SEGMENT_WINDOW {
+        encode_frame(&mut tracks, &mut assembler, &store, &silence_samples)?;
     }
 
     let intake = intake();
@@ -90,6 +246,7 @@ pub(crate) fn run(
     }
 
     let mut pending: Vec<u8> = Vec::with_capacity(FRAME_BYTES_PCM * 4);
+    let mut samples_i16 = vec![0i16; AAC_FRAME_SAMPLES * CHANNELS as usize];
 
     loop {
         // Plain blocking pull. The pacer handles "broadcaster is idle" by
@@ -107,89 +264,15 @@ pub(crate) fn run(
         }
 
         let frame_bytes = &pending[..FRAME_BYTES_PCM];
-        let mut samples_i16 = vec![0i16; AAC_FRAME_SAMPLES * CHANNELS as usize];
         for (i, chunk) in frame_bytes.chunks_exact(2).enumerate() {
             samples_i16[i] = i16::from_le_bytes([chunk[0], chunk[1]]);
         }
         pending.drain(..FRAME_BYTES_PCM);
 
-        let info = encoder
-            .encode(&samples_i16, &mut output)
-            .map_err(|e| format!("fdk-aac encode: {e:?}"))?;
-
-        if info.output_size == 0 {
-            continue;
-        }
-
-        frames_in_seg.push(output[..info.output_size].to_vec());
-
-        if frames_in_seg.len() == FRAMES_PER_SEGMENT {
-            let duration_samples = (FRAMES_PER_SEGMENT * AAC_FRAME_SAMPLES) as u32;
-            let bytes = mp4::write_media_segment(
-                next_seq as u32,
-                base_media_decode_time,
-                AAC_FRAME_SAMPLES as u32,
-                &frames_in_seg,
-            );
-
-            tracing::debug!(
-                "cmaf: emit seg {} ({} bytes, {} frames, base_dts {})",
-                next_seq,
-                bytes.len(),
-                FRAMES_PER_SEGMENT,
-                base_media_decode_time
-            );
-
-            store.push(Segment {
-                seq: next_seq,
-                duration: duration_samples,
-                bytes: Arc::new(bytes),
-            });
-
-            base_media_decode_time += duration_samples as u64;
-            next_seq += 1;
-            frames_in_seg.clear();
-        }
+        encode_frame(&mut tracks, &mut assembler, &store, &samples_i16)?;
     }
 }
 
-/// Encode `FRAMES_PER_SEGMENT` silence frames synchronously and push the
-/// resulting fMP4 segment to the store. Used for the startup bootstrap
-/// only — the steady-state pacer goes through the intake instead.
-fn emit_silence_segment(
-    encoder: &Encoder,
-    output: &mut [u8],
-    silence_samples: &[i16],
-    store: &Arc<SegmentStore>,
-    next_seq: &mut u64,
-    base_media_decode_time: &mut u64,
-) -> Result<(), String> {
-    let mut frames: Vec<Vec<u8>> = Vec::with_capacity(FRAMES_PER_SEGMENT);
-    while frames.len() < FRAMES_PER_SEGMENT {
-        let info = encoder
-            .encode(silence_samples, output)
-            .map_err(|e| format!("fdk-aac silence encode: {e:?}"))?;
-        if info.output_size > 0 {
-            frames.push(output[..info.output_size].to_vec());
-        }
-    }
-    let duration_samples = (FRAMES_PER_SEGMENT * AAC_FRAME_SAMPLES) as u32;
-    let bytes = mp4::write_media_segment(
-        *next_seq as u32,
-        *base_media_decode_time,
-        AAC_FRAME_SAMPLES as u32,
-        &frames,
-    );
-    store.push(Segment {
-        seq: *next_seq,
-        duration: duration_samples,
-        bytes: Arc::new(bytes),
-    });
-    *base_media_decode_time += duration_samples as u64;
-    *next_seq += 1;
-    Ok(())
-}
-
 /// Silence pacer — runs forever, injects one frame of silence at a time
 /// when the broadcaster has been idle and the intake has drained. Keeps
 /// the encoder fed (so segments keep being produced) without ever
@@ -0,0 +1,488 @@
+//! Minimal FLAC encoder for the lossless rendition.
+//!
+//! Encodes one frame per [`crate::AAC_FRAME_SAMPLES`] block of S16 stereo,
+//! so the FLAC rendition is cut into segments on exactly the same sample
+//! boundaries as the AAC ones. Each channel pair is tried as left/right,
+//! left/side, side/right and mid/side; each channel is coded with the best
+//! fixed predictor (order 0–4) and partitioned Rice residuals, falling back
+//! to verbatim or constant subframes. No LPC — it compresses a few percent
+//! worse than `flac -5` but keeps the encoder small and allocation-light.
+//!
+//! Frames are bare (no `fLaC` marker or metadata blocks); the STREAMINFO
+//! block lives in the init segment's `dfLa` box instead (see
+//! [`stream_info`]).
+
+use crate::{AAC_FRAME_SAMPLES, CHANNELS, SAMPLE_RATE};
+
+const BITS_PER_SAMPLE: u32 = 16;
+/// Highest Rice partition order tried; 1024 / 2^4 = 64 samples per partition.
+const MAX_PARTITION_ORDER: u32 = 4;
+/// Largest Rice parameter a 4-bit field can hold (15 is the escape code).
+const MAX_RICE_PARAM: u32 = 14;
+
+/// Frame header code for 1024-sample blocks: 256 * 2^(10 - 8).
+const BLOCK_SIZE_CODE: u8 = 0b1010;
+/// Frame header code for 44.1 kHz.
+const SAMPLE_RATE_CODE: u8 = 0b1001;
+/// Frame header code for 16 bits per sample.
+const SAMPLE_SIZE_CODE: u8 = 0b100;
+
+/// The 34-byte STREAMINFO metadata block body for the stream this encoder
+/// produces. Frame sizes, total samples and the MD5 are left unknown (zero),
+/// as a live stream can't know them.
+pub(crate) fn stream_info() -> [u8; 34] {
+    let mut w = BitWriter::default();
+    w.write(AAC_FRAME_SAMPLES as u64, 16); // min block size
+    w.write(AAC_FRAME_SAMPLES as u64, 16); // max block size
+    w.write(0, 24); // min frame size
+    w.write(0, 24); // max frame size
+    w.write(SAMPLE_RATE as u64, 20);
+    w.write(CHANNELS as u64 - 1, 3);
+    w.write(BITS_PER_SAMPLE as u64 - 1, 5);
+    w.write(0, 36); // total samples
+    w.write(0, 64); // MD5, high half
+    w.write(0, 64); // MD5, low half
+    let mut out = [0u8; 34];
+    out.copy_from_slice(&w.into_bytes());
+    out
+}
+
+#[derive(Clone, Copy)]
+enum Stereo {
+    Independent,
+    LeftSide,
+    SideRight,
+    MidSide,
+}
+
+impl Stereo {
+    fn code(self) -> u8 {
+        match self {
+            Stereo::Independent => 0b0001,
+            Stereo::LeftSide => 0b1000,
+            Stereo::SideRight => 0b1001,
+            Stereo::MidSide => 0b1010,
+        }
+    }
+}
+
+pub(crate) struct Encoder {
+    frame_number: u32,
+    left: Vec<i32>,
+    right: Vec<i32>,
+    mid: Vec<i32>,
+    side: Vec<i32>,
+    residual: Vec<i32>,
+}
+
+impl Encoder {
+    pub(crate) fn new() -> Self {
+        Encoder {
+            frame_number: 0,
+            left: Vec::with_capacity(AAC_FRAME_SAMPLES),
+            right: Vec::with_capacity(AAC_FRAME_SAMPLES),
+            mid: Vec::with_capacity(AAC_FRAME_SAMPLES),
+            side: Vec::with_capacity(AAC_FRAME_SAMPLES),
+            residual: Vec::with_capacity(AAC_FRAME_SAMPLES),
+        }
+    }
+
+    /// Encode one block of interleaved stereo samples
+    /// ([`AAC_FRAME_SAMPLES`] per channel) as a FLAC frame.
+    pub(crate) fn encode(&mut self, interleaved: &[i16]) -> Vec<u8> {
+        debug_assert_eq!(interleaved.len(), AAC_FRAME_SAMPLES * CHANNELS as usize);
+        self.left.clear();
+        self.right.clear();
+        self.mid.clear();
+        self.side.clear();
+        for pair in interleaved.chunks_exact(2) {
+            let (l, r) = (pair[0] as i32, pair[1] as i32);
+            self.left.push(l);
+            self.right.push(r);
+            self.mid.push((l + r) >> 1);
+            self.side.push(l - r);
+        }
+
+        let cost_l = estimate(&self.left);
+        let cost_r = estimate(&self.right);
+        let cost_m = estimate(&self.mid);
+        let cost_s = estimate(&self.side);
+        let stereo = [
+            (cost_l + cost_r, Stereo::Independent),
+            (cost_l + cost_s, Stereo::LeftSide),
+            (cost_s + cost_r, Stereo::SideRight),
+            (cost_m + cost_s, Stereo::MidSide),
+        ]
+        .into_iter()
+        .min_by_key(|(cost, _)| *cost)
+        .map(|(_, stereo)| stereo)
+        .unwrap_or(Stereo::Independent);
+
+        let mut w = BitWriter::default();
+        w.write(0xfff8, 16); // sync code, fixed block size
+        w.write(BLOCK_SIZE_CODE as u64, 4);
+        w.write(SAMPLE_RATE_CODE as u64, 4);
+        w.write(stereo.code() as u64, 4);
+        w.write(SAMPLE_SIZE_CODE as u64, 3);
+        w.write(0, 1);
+        write_utf8(&mut w, self.frame_number);
+        let crc = crc8(w.bytes());
+        w.write(crc as u64, 8);
+
+        let mut residual = std::mem::take(&mut self.residual);
+        let (first, second, first_bits, second_bits) = match stereo {
+            Stereo::Independent => (&self.left, &self.right, 16, 16),
+            Stereo::LeftSide => (&self.left, &self.side, 16, 17),
+            Stereo::SideRight => (&self.side, &self.right, 17, 16),
+            Stereo::MidSide => (&self.mid, &self.side, 16, 17),
+        };
+        write_subframe(&mut w, first, first_bits, &mut residual);
+        write_subframe(&mut w, second, second_bits, &mut residual);
+        self.residual = residual;
+
+        w.align();
+        let crc = crc16(w.bytes());
+        w.write(crc as u64, 16);
+
+        self.frame_number = self.frame_number.wrapping_add(1) & 0x7fff_ffff;
+        w.into_bytes()
+    }
+}
+
+/// Rough cost of a channel: the smallest sum of absolute residuals over
+/// the fixed predictors. Only used to rank the stereo modes.
+fn estimate(samples: &[i32]) -> u64 {
+    (0..=4)
+        .map(|order| {
+            (order..samples.len())
+                .map(|i| fixed_residual(samples, order, i).unsigned_abs() as u64)
+                .sum::<u64>()
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+fn fixed_residual(s: &[i32], order: usize, i: usize) -> i32 {
+    match order {
+        0 => s[i],
+        1 => s[i] - s[i - 1],
+        2 => s[i] - 2 * s[i - 1] + s[i - 2],
+        3 => s[i] - 3 * s[i - 1] + 3 * s[i - 2] - s[i - 3],
+        _ => s[i] - 4 * s[i - 1] + 6 * s[i - 2] - 4 * s[i - 3] + s[i - 4],
+    }
+}
+
+fn write_subframe(w: &mut BitWriter, samples: &[i32], bits: u32, residual: &mut Vec<i32>) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        w.write(0, 1);
+        w.write(0b000000, 6);
+        w.write(0, 1);
+        w.write_signed(samples[0], bits);
+        return;
+    }
+
+    // Best fixed predictor by exact Rice cost.
+    let mut best: Option<(u64, usize, u32)> = None;
+    for order in 0..=4 {
+        residual.clear();
+        residual.extend((order..samples.len()).map(|i| fixed_residual(samples, order, i)));
+        let (cost, partition_order) = rice_cost(residual, samples.len(), order);
+        let cost = cost + order as u64 * bits as u64;
+        if best.is_none_or(|(c, _, _)| cost < c) {
+            best = Some((cost, order, partition_order));
+        }
+    }
+    let verbatim = samples.len() as u64 * bits as u64;
+    let Some((_, order, partition_order)) = best.filter(|(c, _, _)| *c < verbatim) else {
+        w.write(0, 1);
+        w.write(0b000001, 6);
+        w.write(0, 1);
+        for &s in samples {
+            w.write_signed(s, bits);
+        }
+        return;
+    };
+
+    w.write(0, 1);
+    w.write(0b001000 | order as u64, 6);
+    w.write(0, 1);
+    for &s in &samples[..order] {
+        w.write_signed(s, bits);
+    }
+    residual.clear();
+    residual.extend((order..samples.len()).map(|i| fixed_residual(samples, order, i)));
+    w.write(0b00, 2); // Rice coding, 4-bit parameters
+    w.write(partition_order as u64, 4);
+    for part in partitions(residual, samples.len(), order, partition_order) {
+        let param = best_param(part).0;
+        w.write(param as u64, 4);
+        for &r in part {
+            let u = zigzag(r);
+            let q = u >> param;
+            w.write_unary(q);
+            w.write((u & ((1 << param) - 1)) as u64, param);
+        }
+    }
+}
+
+/// Split `residual` into the 2^`partition_order` Rice partitions; the first
+/// is `order` samples short because of the warm-up samples.
+fn partitions(
+    residual: &[i32],
+    block: usize,
+    order: usize,
+    partition_order: u32,
+) -> impl Iterator<Item = &[i32]> {
+    let per = block >> partition_order;
+    let mut start = 0;
+    (0..1usize << partition_order).map(move |p| {
+        let len = if p == 0 { per - order } else { per };
+        let part = &residual[start..start + len];
+        start += len;
+        part
+    })
+}
+
+/// Cheapest (bits, partition order) for `residual`.
+fn rice_cost(residual: &[i32], block: usize, order: usize) -> (u64, u32) {
+    (0..=MAX_PARTITION_ORDER)
+        .filter(|&p| (block >> p) > order)
+        .map(|p| {
+            let bits = 6 + partitions(residual, block, order, p)
+                .map(|part| 4 + best_param(part).1)
+                .sum::<u64>();
+            (bits, p)
+        })
+        .min_by_key(|(bits, _)| *bits)
+        .unwrap_or((u64::MAX, 0))
+}
+
+/// Best Rice parameter for one partition and the bits it takes. The
+/// optimum sits next to log2 of the mean folded residual, so only the
+/// parameters around it are costed.
+fn best_param(part: &[i32]) -> (u32, u64) {
+    let sum: u64 = part.iter().map(|&r| zigzag(r) as u64).sum();
+    let mean = sum / part.len().max(1) as u64;
+    let guess = (64 - mean.leading_zeros()).min(MAX_RICE_PARAM);
+    (guess.saturating_sub(1)..=(guess + 1).min(MAX_RICE_PARAM))
+        .map(|k| {
+            let bits = part
+                .iter()
+                .map(|&r| (zigzag(r) >> k) as u64 + 1 + k as u64)
+                .sum::<u64>();
+            (k, bits)
+        })
+        .min_by_key(|(_, bits)| *bits)
+        .unwrap_or((0, 0))
+}
+
+fn zigzag(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+/// FLAC's UTF-8-style variable-length frame number.
+fn write_utf8(w: &mut BitWriter, v: u32) {
+    if v < 0x80 {
+        w.write(v as u64, 8);
+        return;
+    }
+    let continuation = match v {
+        0..=0x7ff => 1,
+        0x800..=0xffff => 2,
+        0x1_0000..=0x1f_ffff => 3,
+        0x20_0000..=0x3ff_ffff => 4,
+        _ => 5,
+    };
+    let lead_marker: u64 = (0xff00 >> (continuation + 1)) & 0xff;
+    w.write(lead_marker | (v >> (6 * continuation)) as u64, 8);
+    for i in (0..continuation).rev() {
+        w.write(0x80 | ((v >> (6 * i)) & 0x3f) as u64, 8);
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &b in data {
+        crc ^= b;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &b in data {
+        crc ^= (b as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// MSB-first bit writer.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    /// Append the low `n` bits of `value` (`n` ≤ 32 per call).
+    fn write(&mut self, value: u64, n: u32) {
+        if n > 32 {
+            self.write(value >> 32, n - 32);
+            self.write(value & 0xffff_ffff, 32);
+            return;
+        }
+        if n == 0 {
+            return;
+        }
+        self.acc = (self.acc << n) | (value & ((1u64 << n) - 1));
+        self.bits += n;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+        self.acc &= (1u64 << self.bits) - 1;
+    }
+
+    fn write_signed(&mut self, value: i32, n: u32) {
+        self.write(value as u32 as u64 & ((1u64 << n) - 1), n);
+    }
+
+    /// `q` zero bits then a one.
+    fn write_unary(&mut self, mut q: u32) {
+        while q >= 32 {
+            self.write(0, 32);
+            q -= 32;
+        }
+        self.write(1, q + 1);
+    }
+
+    /// Zero-pad to the next byte boundary.
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+
+    /// Whole bytes written so far.
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Uniform noise in `-amplitude..=amplitude` from a fixed-seed LCG.
+    fn noise(seed: u32, amplitude: i32) -> impl Iterator<Item = i32> {
+        let mut state = seed;
+        std::iter::repeat_with(move || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 16) as i32 % (2 * amplitude + 1) - amplitude
+        })
+    }
+
+    fn sine(amplitude: f64) -> impl Iterator<Item = i32> {
+        (0..).map(move |i: i32| {
+            (amplitude * (i as f64 * std::f64::consts::TAU / 1024.0).sin()).round() as i32
+        })
+    }
+
+    fn interleave(left: impl Iterator<Item = i32>, right: impl Iterator<Item = i32>) -> Vec<i16> {
+        left.zip(right)
+            .take(AAC_FRAME_SAMPLES)
+            .flat_map(|(l, r)| [l as i16, r as i16])
+            .collect()
+    }
+
+    #[test]
+    fn crcs_match_the_check_values() {
+        // CRC-8/SMBUS and CRC-16/UMTS, the variants FLAC uses.
+        assert_eq!(crc8(b"123456789"), 0xf4);
+        assert_eq!(crc16(b"123456789"), 0xfee8);
+        assert_eq!(crc8(&[]), 0);
+        assert_eq!(crc16(&[]), 0);
+    }
+
+    #[test]
+    fn frames_in_every_stereo_mode_decode_to_the_input() {
+        let blocks = [
+            // Unrelated channels.
+            (
+                Stereo::Independent,
+                interleave(noise(1, 1000), sine(10_000.0)),
+            ),
+            // Right is left plus noise.
+            (
+                Stereo::LeftSide,
+                interleave(
+                    sine(10_000.0),
+                    sine(10_000.0).zip(noise(2, 100)).map(|(s, n)| s + n),
+                ),
+            ),
+            // Left is right plus noise.
+            (
+                Stereo::SideRight,
+                interleave(
+                    sine(10_000.0).zip(noise(3, 100)).map(|(s, n)| s + n),
+                    sine(10_000.0),
+                ),
+            ),
+            // Shared signal with opposite noise on each side.
+            (
+                Stereo::MidSide,
+                interleave(
+                    sine(10_000.0).zip(noise(4, 100)).map(|(s, n)| s + n),
+                    sine(10_000.0).zip(noise(4, 100)).map(|(s, n)| s - n),
+                ),
+            ),
+        ];
+
+        let mut stream = b"fLaC".to_vec();
+        stream.extend_from_slice(&[0x80, 0, 0, 34]); // last block, STREAMINFO
+        stream.extend_from_slice(&stream_info());
+        let mut encoder = Encoder::new();
+        for (stereo, block) in &blocks {
+            let frame = encoder.encode(block);
+            assert_eq!(frame[3] >> 4, stereo.code(), "channel assignment");
+            stream.extend_from_slice(&frame);
+        }
+
+        let mut reader = claxon::FlacReader::new(std::io::Cursor::new(stream)).unwrap();
+        assert_eq!(reader.streaminfo().sample_rate, SAMPLE_RATE);
+        assert_eq!(reader.streaminfo().channels, CHANNELS as u32);
+        let mut frames = reader.blocks();
+        let mut buffer = Vec::new();
+        for (_, block) in &blocks {
+            let decoded = frames.read_next_or_eof(buffer).unwrap().unwrap();
+            assert_eq!(decoded.duration(), AAC_FRAME_SAMPLES as u32);
+            let samples: Vec<i16> = decoded
+                .stereo_samples()
+                .flat_map(|(l, r)| [l as i16, r as i16])
+                .collect();
+            assert_eq!(&samples, block);
+            buffer = decoded.into_buffer();
+        }
+        assert!(frames.read_next_or_eof(buffer).unwrap().is_none());
+    }
+}
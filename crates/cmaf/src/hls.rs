@@ -1,42 +1,231 @@
-//! HLS manifest writers — master.m3u8 + audio media playlist.
+//! HLS manifest writers — master.m3u8 + one media playlist per rendition.
 
 use std::fmt::Write;
 
-use crate::{Segment, AAC_FRAME_SAMPLES, FRAMES_PER_SEGMENT, SAMPLE_RATE};
+use crate::{
+    dash::format_unix_ms_as_iso8601, part_seconds, segment_seconds, Playlist, SAMPLE_RATE,
+};
 
-/// Master playlist with a single audio rendition.
-pub(crate) fn master_m3u8() -> String {
-    let bitrate = crate::bitrate_bps();
+/// Master playlist listing every rendition as a variant stream.
+pub(crate) fn master_m3u8(playlist: &Playlist) -> String {
     let mut s = String::new();
     s.push_str("#EXTM3U\n");
     s.push_str("#EXT-X-VERSION:7\n");
     s.push_str("#EXT-X-INDEPENDENT-SEGMENTS\n");
-    writeln!(
-        s,
-        "#EXT-X-STREAM-INF:BANDWIDTH={bitrate},CODECS=\"mp4a.40.2\",AUDIO=\"audio\""
-    )
-    .unwrap();
-    s.push_str("audio.m3u8\n");
+    for (rendition, (peak, average)) in playlist.renditions.iter().zip(&playlist.bandwidth) {
+        writeln!(
+            s,
+            "#EXT-X-STREAM-INF:BANDWIDTH={peak},AVERAGE-BANDWIDTH={average},CODECS=\"{}\"",
+            rendition.hls_codecs()
+        )
+        .unwrap();
+        writeln!(s, "{}.m3u8", rendition.id).unwrap();
+    }
     s
 }
 
-/// Media playlist showing the sliding window of recent segments.
-pub(crate) fn audio_m3u8(media_sequence: u64, segments: &[Segment]) -> String {
-    let target = segment_duration_seconds().ceil() as u32;
+/// Media playlist for rendition `index`, showing the window of recent
+/// segments. Every segment carries its `EXT-X-PROGRAM-DATE-TIME` so players
+/// can seek within the DVR window by wall-clock time.
+///
+/// With `low_latency` the playlist is LL-HLS: the parts of the newest
+/// segments and of the one being produced are listed, followed by a preload
+/// hint for the next part and a report of where the other renditions are.
+/// The on-disk mirror leaves it off, as parts are only served over HTTP.
+pub(crate) fn media_m3u8(playlist: &Playlist, index: usize, low_latency: bool) -> String {
+    let id = &playlist.renditions[index].id;
+    let target = segment_seconds().ceil() as u32;
+    let part_target = part_seconds();
     let mut s = String::new();
     s.push_str("#EXTM3U\n");
     s.push_str("#EXT-X-VERSION:7\n");
     writeln!(s, "#EXT-X-TARGETDURATION:{target}").unwrap();
-    writeln!(s, "#EXT-X-MEDIA-SEQUENCE:{media_sequence}").unwrap();
-    s.push_str("#EXT-X-MAP:URI=\"/init.mp4\"\n");
-    for seg in segments {
+    if low_latency {
+        writeln!(
+            s,
+            "#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,PART-HOLD-BACK={:.3}",
+            part_target * 3.0
+        )
+        .unwrap();
+        writeln!(s, "#EXT-X-PART-INF:PART-TARGET={part_target:.3}").unwrap();
+    }
+    writeln!(s, "#EXT-X-MEDIA-SEQUENCE:{}", playlist.media_sequence).unwrap();
+    writeln!(s, "#EXT-X-MAP:URI=\"/{id}/init.mp4\"").unwrap();
+    for seg in &playlist.segments {
+        if low_latency {
+            write_parts(&mut s, playlist, id, seg.seq);
+        }
+        let pdt = format_unix_ms_as_iso8601(seg.program_time_ms(playlist.start_unix_ms));
         let dur = (seg.duration as f64) / (SAMPLE_RATE as f64);
+        writeln!(s, "#EXT-X-PROGRAM-DATE-TIME:{pdt}").unwrap();
         writeln!(s, "#EXTINF:{dur:.3},").unwrap();
-        writeln!(s, "/seg/{}.m4s", seg.seq).unwrap();
+        writeln!(s, "/{id}/seg/{}.m4s", seg.seq).unwrap();
+    }
+    if !low_latency {
+        return s;
+    }
+
+    // The segment still being produced has parts but no EXTINF yet.
+    let (next_seq, next_part) = playlist.next_part;
+    if next_part > 0 {
+        write_parts(&mut s, playlist, id, next_seq);
+    }
+    writeln!(
+        s,
+        "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"/{id}/part/{next_seq}.{next_part}.m4s\""
+    )
+    .unwrap();
+
+    // Every rendition is cut at the same points, so they all stand where
+    // this one does.
+    let last = match next_part {
+        0 => next_seq
+            .checked_sub(1)
+            .and_then(|seq| playlist.parts.iter().find(|p| p.seq == seq))
+            .map(|p| (p.seq, p.durations.len() - 1)),
+        part => Some((next_seq, part - 1)),
+    };
+    if let Some((last_msn, last_part)) = last {
+        for other in playlist.renditions.iter().filter(|r| r.id != *id) {
+            writeln!(
+                s,
+                "#EXT-X-RENDITION-REPORT:URI=\"{}.m3u8\",LAST-MSN={last_msn},LAST-PART={last_part}",
+                other.id
+            )
+            .unwrap();
+        }
     }
     s
 }
 
-fn segment_duration_seconds() -> f64 {
-    (FRAMES_PER_SEGMENT * AAC_FRAME_SAMPLES) as f64 / SAMPLE_RATE as f64
+fn write_parts(s: &mut String, playlist: &Playlist, id: &str, seq: u64) {
+    let Some(parts) = playlist.parts.iter().find(|p| p.seq == seq) else {
+        return;
+    };
+    for (i, duration) in parts.durations.iter().enumerate() {
+        let dur = (*duration as f64) / (SAMPLE_RATE as f64);
+        writeln!(
+            s,
+            "#EXT-X-PART:DURATION={dur:.3},URI=\"/{id}/part/{seq}.{i}.m4s\",INDEPENDENT=YES"
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rendition::Rendition, SegmentStore, AAC_FRAME_SAMPLES, PART_FRAMES, SEGMENT_SAMPLES,
+        SEGMENT_WINDOW,
+    };
+
+    fn store() -> SegmentStore {
+        let store = SegmentStore::new();
+        store.reset(
+            vec![Rendition::aac(128_000), Rendition::flac()],
+            SEGMENT_WINDOW,
+        );
+        store.set_init(vec![Vec::new(), Vec::new()]);
+        store
+    }
+
+    #[test]
+    fn master_playlist_advertises_nominal_bitrates_before_any_segment() {
+        assert_eq!(
+            master_m3u8(&store().playlist()),
+            "#EXTM3U\n\
+             #EXT-X-VERSION:7\n\
+             #EXT-X-INDEPENDENT-SEGMENTS\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=128000,AVERAGE-BANDWIDTH=128000,CODECS=\"mp4a.40.2\"\n\
+             aac-128.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1411200,AVERAGE-BANDWIDTH=1411200,CODECS=\"fLaC\"\n\
+             flac.m3u8\n"
+        );
+    }
+
+    #[test]
+    fn master_playlist_advertises_measured_bitrates() {
+        let store = store();
+        for (seq, aac, flac) in [(1, 32_000, 300_000), (2, 30_000, 280_000)] {
+            store.push_part(
+                seq,
+                SEGMENT_SAMPLES,
+                vec![vec![0; aac], vec![0; flac]],
+                true,
+            );
+        }
+        assert_eq!(
+            master_m3u8(&store.playlist()),
+            "#EXTM3U\n\
+             #EXT-X-VERSION:7\n\
+             #EXT-X-INDEPENDENT-SEGMENTS\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=128197,AVERAGE-BANDWIDTH=124191,CODECS=\"mp4a.40.2\"\n\
+             aac-128.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1201853,AVERAGE-BANDWIDTH=1161791,CODECS=\"fLaC\"\n\
+             flac.m3u8\n"
+        );
+    }
+
+    /// Publish part `part` of segment `seq` for both renditions.
+    fn push_part(store: &SegmentStore, seq: u64, part: usize) {
+        let last = part == 3;
+        let frames = if last { 20 } else { PART_FRAMES };
+        let duration = (frames * AAC_FRAME_SAMPLES) as u32;
+        store.push_part(seq, duration, vec![vec![0; 8], vec![0; 8]], last);
+    }
+
+    /// The LL-HLS lines of the first rendition's media playlist.
+    fn low_latency_lines(store: &SegmentStore) -> Vec<String> {
+        media_m3u8(&store.playlist(), 0, true)
+            .lines()
+            .filter(|l| {
+                l.starts_with("#EXT-X-PART:")
+                    || l.starts_with("#EXT-X-PRELOAD-HINT:")
+                    || l.starts_with("#EXT-X-RENDITION-REPORT:")
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn media_playlist_lists_parts_preload_hint_and_rendition_reports() {
+        let store = store();
+        for part in 0..4 {
+            push_part(&store, 1, part);
+        }
+        for part in 0..2 {
+            push_part(&store, 2, part);
+        }
+        assert_eq!(
+            low_latency_lines(&store),
+            [
+                "#EXT-X-PART:DURATION=0.511,URI=\"/aac-128/part/1.0.m4s\",INDEPENDENT=YES",
+                "#EXT-X-PART:DURATION=0.511,URI=\"/aac-128/part/1.1.m4s\",INDEPENDENT=YES",
+                "#EXT-X-PART:DURATION=0.511,URI=\"/aac-128/part/1.2.m4s\",INDEPENDENT=YES",
+                "#EXT-X-PART:DURATION=0.464,URI=\"/aac-128/part/1.3.m4s\",INDEPENDENT=YES",
+                "#EXT-X-PART:DURATION=0.511,URI=\"/aac-128/part/2.0.m4s\",INDEPENDENT=YES",
+                "#EXT-X-PART:DURATION=0.511,URI=\"/aac-128/part/2.1.m4s\",INDEPENDENT=YES",
+                "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"/aac-128/part/2.2.m4s\"",
+                "#EXT-X-RENDITION-REPORT:URI=\"flac.m3u8\",LAST-MSN=2,LAST-PART=1",
+            ]
+        );
+
+        // Right after a segment completes, the hint points at the next one
+        // and the report at the last part of the one just finished.
+        for part in 2..4 {
+            push_part(&store, 2, part);
+        }
+        let lines = low_latency_lines(&store);
+        assert_eq!(
+            lines[lines.len() - 2..],
+            [
+                "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"/aac-128/part/3.0.m4s\"",
+                "#EXT-X-RENDITION-REPORT:URI=\"flac.m3u8\",LAST-MSN=2,LAST-PART=3",
+            ]
+        );
+        assert!(media_m3u8(&store.playlist(), 0, false)
+            .lines()
+            .all(|l| !l.starts_with("#EXT-X-PART")));
+    }
 }
@@ -2,11 +2,22 @@
 //!
 //! Routes:
 //!   GET /                         → 302 → /hls/master.m3u8 (handy default)
-//!   GET /hls/master.m3u8          → master playlist
-//!   GET /hls/audio.m3u8           → media playlist (sliding window)
+//!   GET /hls/master.m3u8          → master playlist (one variant per rendition)
+//!   GET /hls/{rendition}.m3u8     → LL-HLS media playlist (DVR window);
+//!                                   `_HLS_msn` / `_HLS_part` block until
+//!                                   that segment / part is out
+//!   GET /hls/audio.m3u8           → media playlist of the first rendition
 //!   GET /dash/manifest.mpd        → DASH MPD
-//!   GET /init.mp4                 → init segment
-//!   GET /seg/{n}.m4s              → media segment {n}
+//!   GET /{rendition}/init.mp4     → init segment
+//!   GET /{rendition}/seg/{n}.m4s  → media segment {n}; the one being
+//!                                   produced is sent chunked as its parts
+//!                                   arrive (low-latency DASH)
+//!   GET /{rendition}/part/{n}.{p}.m4s → LL-HLS part {p} of segment {n};
+//!                                   blocks for the part about to come
+//!   GET /init.mp4, /seg/{n}.m4s   → the same for the first rendition
+//!
+//! With TLS configured (see `rockbox-tls`) the same routes are also served
+//! over HTTPS on the plain port plus `tls_port_offset`.
 //!
 //! Runs on a dedicated single-thread Tokio runtime so it can coexist with
 //! the actix-web runtime used by `crates/server` without fighting over the
@@ -14,17 +25,19 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     body::Body,
-    extract::{Path, State},
+    extract::{Path, RawQuery, State},
     http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Redirect, Response},
     routing::get,
     Router,
 };
+use hyper_util::{rt::TokioIo, service::TowerToHyperService};
 
-use crate::{dash, hls, SegmentStore};
+use crate::{dash, hls, segment_seconds, SegmentStore};
 
 #[derive(Clone)]
 struct AppState {
@@ -46,72 +59,253 @@ pub(crate) fn serve(port: u16, store: Arc<SegmentStore>) {
     rt.block_on(async move {
         let app = Router::new()
             .route("/", get(redirect_root))
-            .route("/hls/master.m3u8", get(master_m3u8))
-            .route("/hls/audio.m3u8", get(audio_m3u8))
+            .route("/hls/:playlist", get(hls_playlist))
             .route("/dash/manifest.mpd", get(dash_mpd))
-            .route("/init.mp4", get(init_mp4))
-            .route("/seg/:name", get(segment))
+            .route("/init.mp4", get(default_init_mp4))
+            .route("/seg/:name", get(default_segment))
+            .route("/:rendition/init.mp4", get(init_mp4))
+            .route("/:rendition/seg/:name", get(segment))
+            .route("/:rendition/part/:name", get(part))
             .with_state(AppState { store });
 
-        let addr = SocketAddr::from(([0, 0, 0, 0], port));
-        let listener = match tokio::net::TcpListener::bind(addr).await {
+        if let Some(tls) = rockbox_tls::tls() {
+            match tls.port(port) {
+                Ok(tls_port) => serve_tls(tls, tls_port, app.clone()).await,
+                Err(e) => tracing::error!("cmaf/http: {e}"),
+            }
+        }
+
+        let addr = rockbox_tls::plain_addr(&SocketAddr::from(([0, 0, 0, 0], port)).to_string());
+        let listener = match tokio::net::TcpListener::bind(&addr).await {
             Ok(l) => l,
             Err(e) => {
-                tracing::error!("cmaf/http: bind :{port} failed: {e}");
+                tracing::error!("cmaf/http: bind {addr} failed: {e}");
                 return;
             }
         };
-        tracing::info!("cmaf/http: listening on :{port}");
+        tracing::info!("cmaf/http: listening on {addr}");
         if let Err(e) = axum::serve(listener, app).await {
             tracing::error!("cmaf/http: serve error: {e}");
         }
     });
 }
 
+/// Spawns the TLS twin of the plain listener. axum 0.7's `serve` only takes
+/// a plain `TcpListener`, so TLS connections are driven through hyper here.
+async fn serve_tls(tls: &rockbox_tls::Tls, port: u16, app: Router) {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("cmaf/http: bind :{port} (TLS) failed: {e}");
+            return;
+        }
+    };
+    tracing::info!("cmaf/http: listening on :{port} (TLS)");
+    let mut incoming = tls.incoming(listener, rockbox_tls::ALPN_HTTP1);
+    tokio::spawn(async move {
+        while let Some(stream) = incoming.recv().await {
+            let service = TowerToHyperService::new(app.clone());
+            tokio::spawn(async move {
+                if let Err(e) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    tracing::debug!("cmaf/http: TLS connection error: {e}");
+                }
+            });
+        }
+    });
+}
+
 async fn redirect_root() -> impl IntoResponse {
     Redirect::to("/hls/master.m3u8")
 }
 
-async fn master_m3u8() -> impl IntoResponse {
-    text_response(hls::master_m3u8(), "application/vnd.apple.mpegurl")
-}
+async fn hls_playlist(
+    State(s): State<AppState>,
+    Path(name): Path<String>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let Some(id) = name.strip_suffix(".m3u8") else {
+        return (StatusCode::NOT_FOUND, "not found").into_response();
+    };
 
-async fn audio_m3u8(State(s): State<AppState>) -> impl IntoResponse {
-    let (media_seq, segs, _) = s.store.snapshot();
-    text_response(
-        hls::audio_m3u8(media_seq, &segs),
-        "application/vnd.apple.mpegurl",
-    )
+    // Blocking playlist reload: hold the request until the segment (or
+    // part) the client asks for is out.
+    let query = query.as_deref();
+    let msn = query_param(query, "_HLS_msn");
+    let part = query_param(query, "_HLS_part");
+    if msn.is_some() || part.is_some() {
+        let (Some(Ok(msn)), Ok(part)) = (
+            msn.map(str::parse::<u64>),
+            part.map(str::parse::<usize>).transpose(),
+        ) else {
+            return (StatusCode::BAD_REQUEST, "invalid _HLS_msn / _HLS_part").into_response();
+        };
+        if msn > s.store.last_seq().unwrap_or(0) + 2 {
+            return (StatusCode::BAD_REQUEST, "_HLS_msn too far ahead").into_response();
+        }
+        if !wait_for(&s.store, |store| store.has(msn, part)).await {
+            return (StatusCode::SERVICE_UNAVAILABLE, "timed out").into_response();
+        }
+    }
+
+    let playlist = s.store.playlist();
+    let body = match id {
+        "master" => hls::master_m3u8(&playlist),
+        "audio" if !playlist.renditions.is_empty() => hls::media_m3u8(&playlist, 0, true),
+        _ => match playlist.renditions.iter().position(|r| r.id == id) {
+            Some(i) => hls::media_m3u8(&playlist, i, true),
+            None => return (StatusCode::NOT_FOUND, "unknown rendition").into_response(),
+        },
+    };
+    text_response(body, "application/vnd.apple.mpegurl")
 }
 
 async fn dash_mpd(State(s): State<AppState>) -> impl IntoResponse {
-    let (media_seq, _, start_ms) = s.store.snapshot();
     text_response(
-        dash::manifest_mpd(start_ms, media_seq),
+        dash::manifest_mpd(&s.store.playlist(), true),
         "application/dash+xml",
     )
 }
 
-async fn init_mp4(State(s): State<AppState>) -> Response {
-    match s.store.init() {
+async fn default_init_mp4(State(s): State<AppState>) -> Response {
+    init_response(&s.store, 0)
+}
+
+async fn init_mp4(State(s): State<AppState>, Path(rendition): Path<String>) -> Response {
+    match s.store.rendition_index(&rendition) {
+        Some(i) => init_response(&s.store, i),
+        None => (StatusCode::NOT_FOUND, "unknown rendition").into_response(),
+    }
+}
+
+fn init_response(store: &SegmentStore, rendition: usize) -> Response {
+    match store.init(rendition) {
         Some(init) => binary_response(StatusCode::OK, (*init).clone(), "audio/mp4"),
         None => (StatusCode::SERVICE_UNAVAILABLE, "init not ready").into_response(),
     }
 }
 
-async fn segment(State(s): State<AppState>, Path(name): Path<String>) -> Response {
+async fn default_segment(State(s): State<AppState>, Path(name): Path<String>) -> Response {
+    segment_response(s.store, 0, &name)
+}
+
+async fn segment(
+    State(s): State<AppState>,
+    Path((rendition, name)): Path<(String, String)>,
+) -> Response {
+    match s.store.rendition_index(&rendition) {
+        Some(i) => segment_response(s.store, i, &name),
+        None => (StatusCode::NOT_FOUND, "unknown rendition").into_response(),
+    }
+}
+
+fn segment_response(store: Arc<SegmentStore>, rendition: usize, name: &str) -> Response {
     let Some(num_str) = name.strip_suffix(".m4s") else {
         return (StatusCode::NOT_FOUND, "not found").into_response();
     };
     let Ok(n) = num_str.parse::<u64>() else {
         return (StatusCode::BAD_REQUEST, "invalid segment number").into_response();
     };
-    match s.store.get(n) {
-        Some(seg) => binary_response(StatusCode::OK, (*seg).clone(), "audio/mp4"),
-        None => (StatusCode::NOT_FOUND, "segment not available").into_response(),
+    if let Some(seg) = store.get(rendition, n) {
+        return binary_response(StatusCode::OK, (*seg).clone(), "audio/mp4");
+    }
+    if store.read_from(rendition, n, 0).is_none() {
+        return (StatusCode::NOT_FOUND, "segment not available").into_response();
+    }
+    chunked_segment(store, rendition, n)
+}
+
+/// Send segment `seq`, still being produced, as its parts come out. The
+/// body has no length, so hyper uses chunked transfer encoding.
+fn chunked_segment(store: Arc<SegmentStore>, rendition: usize, seq: u64) -> Response {
+    let chunks = futures_util::stream::unfold(Some((store, 0)), move |state| async move {
+        let (store, offset) = state?;
+        let mut published = store.subscribe();
+        let deadline = tokio::time::Instant::now() + blocking_timeout();
+        loop {
+            match store.read_from(rendition, seq, offset) {
+                Some((bytes, complete)) if complete || !bytes.is_empty() => {
+                    let next = (!complete).then(|| (store, offset + bytes.len()));
+                    return Some((Ok(bytes), next));
+                }
+                Some(_) => {}
+                None => {
+                    let e = std::io::Error::other(format!("segment {seq} dropped"));
+                    return Some((Err(e), None));
+                }
+            }
+            if !matches!(
+                tokio::time::timeout_at(deadline, published.changed()).await,
+                Ok(Ok(()))
+            ) {
+                let e = std::io::Error::other(format!("segment {seq} stalled"));
+                return Some((Err(e), None));
+            }
+        }
+    });
+    let mut resp = binary_response(StatusCode::OK, Vec::new(), "audio/mp4");
+    *resp.body_mut() = Body::from_stream(chunks);
+    resp
+}
+
+async fn part(
+    State(s): State<AppState>,
+    Path((rendition, name)): Path<(String, String)>,
+) -> Response {
+    let Some(i) = s.store.rendition_index(&rendition) else {
+        return (StatusCode::NOT_FOUND, "unknown rendition").into_response();
+    };
+    let parsed = name.strip_suffix(".m4s").and_then(|name| {
+        let (seq, part) = name.split_once('.')?;
+        Some((seq.parse::<u64>().ok()?, part.parse::<usize>().ok()?))
+    });
+    let Some((seq, part)) = parsed else {
+        return (StatusCode::NOT_FOUND, "not found").into_response();
+    };
+
+    // A preload hint names the part before it exists: hold the request
+    // until it does.
+    if seq <= s.store.last_seq().unwrap_or(0) + 2 {
+        wait_for(&s.store, |store| store.has(seq, Some(part))).await;
+    }
+    match s.store.part(i, seq, part) {
+        Some(bytes) => binary_response(StatusCode::OK, bytes, "audio/mp4"),
+        None => (StatusCode::NOT_FOUND, "part not available").into_response(),
     }
 }
 
+/// How long a blocking request waits — three target durations, as LL-HLS
+/// suggests.
+fn blocking_timeout() -> Duration {
+    Duration::from_secs_f64(segment_seconds().ceil() * 3.0)
+}
+
+/// Wait until `ready` holds, giving up after [`blocking_timeout`].
+async fn wait_for(store: &SegmentStore, ready: impl Fn(&SegmentStore) -> bool) -> bool {
+    let mut published = store.subscribe();
+    let deadline = tokio::time::Instant::now() + blocking_timeout();
+    loop {
+        if ready(store) {
+            return true;
+        }
+        match tokio::time::timeout_at(deadline, published.changed()).await {
+            Ok(Ok(())) => {}
+            _ => return ready(store),
+        }
+    }
+}
+
+fn query_param<'a>(query: Option<&'a str>, key: &str) -> Option<&'a str> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
 fn text_response(body: String, content_type: &'static str) -> Response {
     let mut resp = Response::new(Body::from(body));
     *resp.status_mut() = StatusCode::OK;
@@ -143,3 +337,119 @@ fn binary_response(status: StatusCode, bytes: Vec<u8>, content_type: &'static st
     );
     resp
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rendition::Rendition, AAC_FRAME_SAMPLES, PART_FRAMES, SEGMENT_WINDOW};
+
+    fn state() -> AppState {
+        let store = SegmentStore::new();
+        store.reset(vec![Rendition::aac(128_000)], SEGMENT_WINDOW);
+        store.set_init(vec![b"init".to_vec()]);
+        AppState {
+            store: Arc::new(store),
+        }
+    }
+
+    /// Publish part `part` of segment `seq`, its payload `[seq, part]`
+    /// repeated.
+    fn push_part(store: &SegmentStore, seq: u64, part: usize) {
+        let last = part == 3;
+        let frames = if last { 20 } else { PART_FRAMES };
+        let chunk = [seq as u8, part as u8].repeat(4);
+        store.push_part(seq, (frames * AAC_FRAME_SAMPLES) as u32, vec![chunk], last);
+    }
+
+    async fn playlist(s: &AppState, query: &str) -> Response {
+        hls_playlist(
+            State(s.clone()),
+            Path("aac-128.m3u8".to_string()),
+            RawQuery(Some(query.to_string())),
+        )
+        .await
+    }
+
+    async fn body(resp: Response) -> Vec<u8> {
+        axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    #[test]
+    fn query_params_are_found_by_name() {
+        let query = Some("_HLS_msn=12&_HLS_part=3&x");
+        assert_eq!(query_param(query, "_HLS_msn"), Some("12"));
+        assert_eq!(query_param(query, "_HLS_part"), Some("3"));
+        assert_eq!(query_param(query, "_HLS_skip"), None);
+        assert_eq!(query_param(None, "_HLS_msn"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn malformed_or_far_ahead_blocking_requests_are_rejected() {
+        let s = state();
+        push_part(&s.store, 1, 0);
+        for query in [
+            "_HLS_msn=one",
+            "_HLS_msn=1&_HLS_part=-1",
+            "_HLS_part=1",
+            "_HLS_msn=3",
+        ] {
+            assert_eq!(
+                playlist(&s, query).await.status(),
+                StatusCode::BAD_REQUEST,
+                "{query}"
+            );
+        }
+        assert_eq!(
+            playlist(&s, "_HLS_msn=1&_HLS_part=0").await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocking_reload_returns_once_the_part_is_published() {
+        let s = state();
+        push_part(&s.store, 1, 0);
+        let request = tokio::spawn({
+            let s = s.clone();
+            async move { playlist(&s, "_HLS_msn=1&_HLS_part=1").await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!request.is_finished());
+
+        push_part(&s.store, 1, 1);
+        let resp = request.await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(body(resp).await).unwrap();
+        assert!(body.contains("URI=\"/aac-128/part/1.1.m4s\""));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocking_reload_times_out() {
+        let s = state();
+        push_part(&s.store, 1, 0);
+        let started = tokio::time::Instant::now();
+        let resp = playlist(&s, "_HLS_msn=2").await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(started.elapsed(), blocking_timeout());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn open_segment_is_sent_chunked_as_it_is_produced() {
+        let s = state();
+        push_part(&s.store, 1, 0);
+        let resp = segment_response(s.store.clone(), 0, "1.m4s");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let request = tokio::spawn(body(resp));
+        for part in 1..4 {
+            tokio::task::yield_now().await;
+            push_part(&s.store, 1, part);
+        }
+
+        let complete = s.store.get(0, 1).unwrap();
+        assert_eq!(request.await.unwrap(), *complete);
+        assert_eq!(complete.len(), 4 * 8);
+    }
+}
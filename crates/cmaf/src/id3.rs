@@ -0,0 +1,37 @@
+//! ID3v2.4 tags for the timed metadata carried in `emsg` boxes.
+
+/// An ID3v2.4 tag holding `title` (TIT2) and, when not empty, `artist`
+/// (TPE1), both UTF-8.
+pub(crate) fn tag(title: &str, artist: &str) -> Vec<u8> {
+    let mut frames = Vec::new();
+    text_frame(&mut frames, b"TIT2", title);
+    if !artist.is_empty() {
+        text_frame(&mut frames, b"TPE1", artist);
+    }
+
+    let mut out = Vec::with_capacity(10 + frames.len());
+    out.extend_from_slice(b"ID3");
+    out.extend_from_slice(&[4, 0]); // v2.4.0
+    out.push(0); // flags
+    out.extend_from_slice(&synchsafe(frames.len() as u32));
+    out.extend_from_slice(&frames);
+    out
+}
+
+fn text_frame(out: &mut Vec<u8>, id: &[u8; 4], text: &str) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&synchsafe(text.len() as u32 + 1));
+    out.extend_from_slice(&[0, 0]); // flags
+    out.push(3); // UTF-8
+    out.extend_from_slice(text.as_bytes());
+}
+
+/// 28-bit size as four 7-bit bytes.
+fn synchsafe(n: u32) -> [u8; 4] {
+    [
+        ((n >> 21) & 0x7f) as u8,
+        ((n >> 14) & 0x7f) as u8,
+        ((n >> 7) & 0x7f) as u8,
+        (n & 0x7f) as u8,
+    ]
+}
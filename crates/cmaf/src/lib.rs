@@ -1,16 +1,31 @@
-//! CMAF (fragmented MP4) PCM sink — AAC-LC encoded audio served as both
-//! HLS and DASH from the same in-memory segment ring buffer.
+//! CMAF (fragmented MP4) PCM sink — AAC-LC and FLAC renditions served as
+//! both HLS and DASH from the same segment store.
 //!
 //! Pipeline:
 //!
-//!     pcm_cmaf_write(PCM)
-//!         → PcmIntake (Mutex<VecDeque<u8>> + Condvar)
-//!             → encoder thread: fdk-aac → AAC frames
-//!                 → segmenter: accumulate N frames into one fMP4 segment
-//!                     → SegmentStore (ring buffer, last K segments)
-//!                         → HTTP server: /init.mp4, /seg/{n}.m4s,
-//!                           /hls/master.m3u8, /hls/audio.m3u8,
-//!                           /dash/manifest.mpd
+//! ```text
+//! pcm_cmaf_write(PCM)
+//!     → PcmIntake (Mutex<VecDeque<u8>> + Condvar)
+//!         → encoder thread: one encoder per rendition
+//!           (fdk-aac at each AAC bitrate, `flac` for lossless)
+//!             → segmenter: accumulate N frames into one fMP4 segment
+//!               per rendition, published a part (CMAF chunk) at a
+//!               time and joined on sequence number
+//!                 → SegmentStore (DVR window; recent segments in
+//!                   memory, older ones read back from the DiskMirror)
+//!                     → HTTP server: /{rendition}/init.mp4,
+//!                       /{rendition}/seg/{n}.m4s,
+//!                       /{rendition}/part/{n}.{p}.m4s, /hls/master.m3u8,
+//!                       /hls/{rendition}.m3u8, /dash/manifest.mpd
+//! ```
+//!
+//! Low latency: media playlists are LL-HLS (EXT-X-PART, EXT-X-PRELOAD-HINT,
+//! blocking reload), and a segment still being produced is sent with chunked
+//! transfer encoding as its parts arrive, which is what low-latency DASH
+//! (`availabilityTimeOffset`) relies on.
+//!
+//! The title of the track playing rides along as a timed ID3 tag in an
+//! `emsg` box at the start of every segment (`pcm_cmaf_set_metadata`).
 //!
 //! NOTE on licensing: libfdk-aac ships under the "Software License for The
 //! Fraunhofer FDK AAC Codec Library for Android" — it is OSS but is *not*
@@ -20,9 +35,12 @@
 
 mod dash;
 mod encoder;
+mod flac;
 mod hls;
 mod http;
+mod id3;
 mod mp4;
+mod rendition;
 
 use std::collections::VecDeque;
 use std::fs;
@@ -30,6 +48,10 @@ use std::path::PathBuf;
 use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use tokio::sync::watch;
+
+use rendition::Rendition;
+
 // Called from rockbox-cli to force this crate's symbols into librockbox_cli.a.
 #[doc(hidden)]
 pub fn _link_cmaf() {}
@@ -45,11 +67,36 @@ pub(crate) const CHANNELS: u16 = 2;
 pub(crate) const AAC_FRAME_SAMPLES: usize = 1024;
 /// AAC frames per fMP4 segment — 86 × 1024 / 44100 = 1.997 s.
 pub(crate) const FRAMES_PER_SEGMENT: usize = 86;
-/// Sliding-window size for both HLS playlist and DASH timeShiftBufferDepth.
+/// Samples per segment, in the mdhd timescale.
+pub(crate) const SEGMENT_SAMPLES: u32 = (FRAMES_PER_SEGMENT * AAC_FRAME_SAMPLES) as u32;
+/// Smallest sliding window for both HLS playlist and DASH
+/// timeShiftBufferDepth; `cmaf_dvr_window` can make it longer.
 pub(crate) const SEGMENT_WINDOW: usize = 6;
 /// Maximum segments retained in memory (window + a little slack so a slow
-/// client can still pull the oldest in-window segment).
+/// client can still pull the oldest in-window segment). Longer DVR windows
+/// are served from the DiskMirror.
 pub(crate) const SEGMENT_CAPACITY: usize = 12;
+/// Segments kept past the start of the window.
+const SEGMENT_SLACK: usize = SEGMENT_CAPACITY - SEGMENT_WINDOW;
+
+/// AAC frames per LL-HLS part — 22 × 1024 / 44100 = 0.511 s, so a segment
+/// is published in four parts (the last one 20 frames long).
+pub(crate) const PART_FRAMES: usize = 22;
+/// Parts per segment.
+pub(crate) const PARTS_PER_SEGMENT: usize = FRAMES_PER_SEGMENT.div_ceil(PART_FRAMES);
+/// Complete segments whose parts are still listed in LL-HLS playlists —
+/// about three target durations' worth, as the spec asks.
+pub(crate) const PART_HOLD_SEGMENTS: usize = 3;
+
+/// Seconds per segment.
+pub(crate) fn segment_seconds() -> f64 {
+    SEGMENT_SAMPLES as f64 / SAMPLE_RATE as f64
+}
+
+/// Longest part, in seconds — the LL-HLS `PART-TARGET`.
+pub(crate) fn part_seconds() -> f64 {
+    (PART_FRAMES * AAC_FRAME_SAMPLES) as f64 / SAMPLE_RATE as f64
+}
 
 // ---------------------------------------------------------------------------
 // PCM intake — single-producer, single-consumer byte queue.
@@ -151,19 +198,21 @@ impl PcmIntake {
 // ---------------------------------------------------------------------------
 // DiskMirror — optional side-car that writes the same artefacts to a
 // directory. All I/O is best-effort: failures are logged at warn level and
-// never abort the encoder thread, since the in-memory ring is authoritative.
+// never abort the encoder thread.
 //
 // Layout written under `dir`:
 //
-//     init.mp4
-//     seg/{N}.m4s
+//     {rendition}/init.mp4
+//     {rendition}/seg/{N}.m4s
 //     hls/master.m3u8
-//     hls/audio.m3u8        (re-written every segment)
+//     hls/{rendition}.m3u8  (re-written every segment)
 //     dash/manifest.mpd     (re-written every segment)
 //
 // External HTTP servers (nginx etc.) can be pointed at `dir` and the paths
 // inside the manifests resolve correctly because they are root-relative
-// (`/init.mp4`, `/seg/{n}.m4s`).
+// (`/{rendition}/init.mp4`, `/{rendition}/seg/{n}.m4s`). The mirror also
+// backs DVR windows longer than the store keeps in memory: segments that
+// have left memory are read back from it.
 // ---------------------------------------------------------------------------
 
 pub(crate) struct DiskMirror {
@@ -171,35 +220,50 @@ pub(crate) struct DiskMirror {
 }
 
 impl DiskMirror {
-    fn new(dir: PathBuf) -> std::io::Result<Self> {
-        fs::create_dir_all(&dir)?;
-        fs::create_dir_all(dir.join("seg"))?;
+    fn new(dir: PathBuf, renditions: &[Rendition]) -> std::io::Result<Self> {
         fs::create_dir_all(dir.join("hls"))?;
         fs::create_dir_all(dir.join("dash"))?;
+        for rendition in renditions {
+            fs::create_dir_all(dir.join(&rendition.id).join("seg"))?;
+        }
         tracing::info!("cmaf: mirroring segments to {}", dir.display());
         Ok(DiskMirror { dir })
     }
 
-    fn write_init(&self, init: &[u8]) {
-        let p = self.dir.join("init.mp4");
-        if let Err(e) = fs::write(&p, init) {
-            tracing::warn!("cmaf mirror: write {} failed: {e}", p.display());
-        }
-        let master = self.dir.join("hls").join("master.m3u8");
-        if let Err(e) = fs::write(&master, hls::master_m3u8()) {
-            tracing::warn!("cmaf mirror: write {} failed: {e}", master.display());
+    fn write(&self, path: PathBuf, bytes: &[u8]) {
+        if let Err(e) = fs::write(&path, bytes) {
+            tracing::warn!("cmaf mirror: write {} failed: {e}", path.display());
         }
     }
 
-    fn write_segment(&self, seq: u64, bytes: &[u8]) {
-        let p = self.dir.join("seg").join(format!("{seq}.m4s"));
-        if let Err(e) = fs::write(&p, bytes) {
-            tracing::warn!("cmaf mirror: write {} failed: {e}", p.display());
+    fn segment_path(&self, rendition: &Rendition, seq: u64) -> PathBuf {
+        self.dir
+            .join(&rendition.id)
+            .join("seg")
+            .join(format!("{seq}.m4s"))
+    }
+
+    fn write_init(&self, rendition: &Rendition, init: &[u8]) {
+        self.write(self.dir.join(&rendition.id).join("init.mp4"), init);
+    }
+
+    fn write_segment(&self, rendition: &Rendition, seq: u64, bytes: &[u8]) {
+        self.write(self.segment_path(rendition, seq), bytes);
+    }
+
+    fn read_segment(&self, rendition: &Rendition, seq: u64) -> Option<Vec<u8>> {
+        let p = self.segment_path(rendition, seq);
+        match fs::read(&p) {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                tracing::warn!("cmaf mirror: read {} failed: {e}", p.display());
+                None
+            }
         }
     }
 
-    fn remove_segment(&self, seq: u64) {
-        let p = self.dir.join("seg").join(format!("{seq}.m4s"));
+    fn remove_segment(&self, rendition: &Rendition, seq: u64) {
+        let p = self.segment_path(rendition, seq);
         if let Err(e) = fs::remove_file(&p) {
             if e.kind() != std::io::ErrorKind::NotFound {
                 tracing::warn!("cmaf mirror: rm {} failed: {e}", p.display());
@@ -207,70 +271,249 @@ impl DiskMirror {
         }
     }
 
-    fn write_manifests(&self, media_seq: u64, segs: &[Segment], start_ms: u64) {
-        let audio = self.dir.join("hls").join("audio.m3u8");
-        if let Err(e) = fs::write(&audio, hls::audio_m3u8(media_seq, segs)) {
-            tracing::warn!("cmaf mirror: write {} failed: {e}", audio.display());
-        }
-        let mpd = self.dir.join("dash").join("manifest.mpd");
-        if let Err(e) = fs::write(&mpd, dash::manifest_mpd(start_ms, media_seq)) {
-            tracing::warn!("cmaf mirror: write {} failed: {e}", mpd.display());
+    fn write_manifests(&self, playlist: &Playlist) {
+        let hls_dir = self.dir.join("hls");
+        self.write(
+            hls_dir.join("master.m3u8"),
+            hls::master_m3u8(playlist).as_bytes(),
+        );
+        for (i, rendition) in playlist.renditions.iter().enumerate() {
+            self.write(
+                hls_dir.join(format!("{}.m3u8", rendition.id)),
+                hls::media_m3u8(playlist, i, false).as_bytes(),
+            );
         }
+        self.write(
+            self.dir.join("dash").join("manifest.mpd"),
+            dash::manifest_mpd(playlist, false).as_bytes(),
+        );
     }
 }
 
 // ---------------------------------------------------------------------------
-// SegmentStore — sliding ring buffer of completed fMP4 segments.
+// SegmentStore — the DVR window of completed fMP4 segments, one payload per
+// rendition, plus the segment being built, published part by part for
+// LL-HLS. The newest SEGMENT_CAPACITY segments stay in memory; older ones
+// are dropped from memory once the mirror has them.
 // ---------------------------------------------------------------------------
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub(crate) struct Segment {
     /// Sequence number; first segment after a stream start is `start_seq`.
     pub seq: u64,
-    /// Sample count (in mdhd timescale = SAMPLE_RATE) for this segment.
+    /// Decode time of the first sample, in mdhd timescale (= SAMPLE_RATE)
+    /// since the stream started.
+    pub start: u64,
+    /// Sample count (in mdhd timescale) for this segment.
     pub duration: u32,
-    /// fMP4 payload (styp + moof + mdat).
-    pub bytes: Arc<Vec<u8>>,
+}
+
+impl Segment {
+    /// Wall-clock time of the segment's first sample, for
+    /// `EXT-X-PROGRAM-DATE-TIME`. Derived from the media timeline like the
+    /// DASH availability times, so both manifests agree.
+    pub(crate) fn program_time_ms(&self, start_unix_ms: u64) -> u64 {
+        start_unix_ms + self.start * 1000 / SAMPLE_RATE as u64
+    }
+}
+
+/// Where a segment's parts end: `part_ends[rendition][part]` is the byte
+/// offset one past the end of that part in the rendition's payload.
+#[derive(Clone, Default)]
+struct Parts {
+    durations: Vec<u32>,
+    part_ends: Vec<Vec<usize>>,
+}
+
+impl Parts {
+    fn push(&mut self, duration: u32, ends: impl Iterator<Item = usize>) {
+        self.durations.push(duration);
+        for (rendition, end) in ends.enumerate() {
+            if rendition == self.part_ends.len() {
+                self.part_ends.push(Vec::new());
+            }
+            self.part_ends[rendition].push(end);
+        }
+    }
+
+    fn range(&self, rendition: usize, part: usize) -> Option<std::ops::Range<usize>> {
+        let ends = self.part_ends.get(rendition)?;
+        let end = *ends.get(part)?;
+        let start = if part == 0 { 0 } else { ends[part - 1] };
+        Some(start..end)
+    }
+}
+
+struct StoredSegment {
+    segment: Segment,
+    /// fMP4 payloads (styp + emsg + moof + mdat…), in rendition order.
+    /// Emptied once the segment has left memory; it is on disk then.
+    bytes: Vec<Arc<Vec<u8>>>,
+    /// Payload sizes in bytes, kept for the bandwidth figures.
+    sizes: Vec<usize>,
+    parts: Parts,
+    /// Whether the mirror has this segment.
+    on_disk: bool,
+}
+
+/// The segment the encoder is still producing. Its payloads grow by one
+/// chunk per part.
+struct OpenSegment {
+    segment: Segment,
+    bytes: Vec<Vec<u8>>,
+    parts: Parts,
+}
+
+/// Parts of one segment, as listed in an LL-HLS playlist.
+pub(crate) struct PartList {
+    pub seq: u64,
+    /// Part durations, in mdhd timescale.
+    pub durations: Vec<u32>,
+}
+
+/// Everything the manifest writers need, taken in one go under the store
+/// lock.
+pub(crate) struct Playlist {
+    pub renditions: Vec<Rendition>,
+    /// Segments in the window, oldest first.
+    pub segments: Vec<Segment>,
+    pub media_sequence: u64,
+    pub start_unix_ms: u64,
+    /// Window length in segments.
+    pub window: usize,
+    /// (peak, average) bits/sec per rendition, measured over the segments
+    /// in memory; the nominal bitrate before any exist.
+    pub bandwidth: Vec<(u32, u32)>,
+    /// Parts of the last PART_HOLD_SEGMENTS complete segments, then of the
+    /// open segment (which has no entry in `segments`), oldest first.
+    pub parts: Vec<PartList>,
+    /// The next part the encoder will publish, as (segment, part).
+    pub next_part: (u64, usize),
 }
 
 pub(crate) struct SegmentStore {
     inner: Mutex<SegmentStoreInner>,
-    cv: Condvar,
+    /// Bumped whenever a part is published, for requests that wait on one
+    /// (blocking playlist reload, preload hints, chunked segments).
+    published: watch::Sender<()>,
     /// Optional on-disk mirror — when Some, every segment + manifest is also
     /// written to this directory so external HTTP servers (nginx, Caddy, a
-    /// CDN origin) can serve them directly. The in-memory ring is always the
-    /// source of truth; disk is purely a side-car.
+    /// CDN origin) can serve them directly, and segments past the in-memory
+    /// capacity are served from it.
     mirror: Mutex<Option<Arc<DiskMirror>>>,
 }
 
 struct SegmentStoreInner {
-    init: Option<Arc<Vec<u8>>>,
-    segments: VecDeque<Segment>,
+    renditions: Vec<Rendition>,
+    /// Init segments, in rendition order; empty until the encoder starts.
+    init: Vec<Arc<Vec<u8>>>,
+    segments: VecDeque<StoredSegment>,
+    open: Option<OpenSegment>,
+    /// Segments listed in the playlists.
+    window: usize,
     /// Sequence number of the first segment ever produced for this stream.
     start_seq: u64,
     /// availabilityStartTime equivalent — wall-clock when the producer started.
     start_unix_ms: u64,
 }
 
+impl SegmentStoreInner {
+    fn playlist(&self) -> Playlist {
+        let n = self.segments.len();
+        let take = n.min(self.window);
+        let segments: Vec<Segment> = self
+            .segments
+            .iter()
+            .skip(n - take)
+            .map(|s| s.segment)
+            .collect();
+        let media_sequence = segments.first().map(|s| s.seq).unwrap_or(self.start_seq);
+
+        let seconds = segment_seconds();
+        let bandwidth = self
+            .renditions
+            .iter()
+            .enumerate()
+            .map(|(i, rendition)| {
+                let bps: Vec<u32> = self
+                    .segments
+                    .iter()
+                    .rev()
+                    .take(SEGMENT_CAPACITY)
+                    .map(|s| (s.sizes[i] as f64 * 8.0 / seconds) as u32)
+                    .collect();
+                match bps.iter().max() {
+                    Some(&peak) => (peak, bps.iter().sum::<u32>() / bps.len() as u32),
+                    None => (rendition.nominal_bps(), rendition.nominal_bps()),
+                }
+            })
+            .collect();
+
+        let mut parts: Vec<PartList> = self
+            .segments
+            .iter()
+            .skip(n - take.min(PART_HOLD_SEGMENTS))
+            .map(|s| PartList {
+                seq: s.segment.seq,
+                durations: s.parts.durations.clone(),
+            })
+            .collect();
+        parts.extend(self.open.as_ref().map(|open| PartList {
+            seq: open.segment.seq,
+            durations: open.parts.durations.clone(),
+        }));
+
+        Playlist {
+            renditions: self.renditions.clone(),
+            segments,
+            media_sequence,
+            start_unix_ms: self.start_unix_ms,
+            window: self.window,
+            bandwidth,
+            parts,
+            next_part: self.next_part(),
+        }
+    }
+
+    fn next_part(&self) -> (u64, usize) {
+        match (&self.open, self.segments.back()) {
+            (Some(open), _) => (open.segment.seq, open.parts.durations.len()),
+            (None, Some(last)) => (last.segment.seq + 1, 0),
+            (None, None) => (self.start_seq, 0),
+        }
+    }
+
+    fn stored(&self, seq: u64) -> Option<&StoredSegment> {
+        self.segments.iter().find(|s| s.segment.seq == seq)
+    }
+}
+
 impl SegmentStore {
     fn new() -> Self {
         SegmentStore {
             inner: Mutex::new(SegmentStoreInner {
-                init: None,
+                renditions: Vec::new(),
+                init: Vec::new(),
                 segments: VecDeque::with_capacity(SEGMENT_CAPACITY),
+                open: None,
+                window: SEGMENT_WINDOW,
                 start_seq: 1,
                 start_unix_ms: 0,
             }),
-            cv: Condvar::new(),
+            published: watch::Sender::new(()),
             mirror: Mutex::new(None),
         }
     }
 
     /// Install or remove the on-disk mirror. Pass `None` to disable.
     fn set_mirror(&self, dir: Option<PathBuf>) {
+        let (renditions, init) = {
+            let g = self.inner.lock().unwrap();
+            (g.renditions.clone(), g.init.clone())
+        };
         let new = match dir {
             None => None,
-            Some(d) => match DiskMirror::new(d) {
+            Some(d) => match DiskMirror::new(d, &renditions) {
                 Ok(m) => Some(Arc::new(m)),
                 Err(e) => {
                     tracing::warn!("cmaf: disk mirror setup failed: {e}");
@@ -278,6 +521,11 @@ impl SegmentStore {
                 }
             },
         };
+        if let Some(m) = &new {
+            for (rendition, init) in renditions.iter().zip(&init) {
+                m.write_init(rendition, init);
+            }
+        }
         *self.mirror.lock().unwrap() = new;
     }
 
@@ -285,79 +533,224 @@ impl SegmentStore {
         self.mirror.lock().unwrap().clone()
     }
 
-    fn set_init(&self, init: Vec<u8>) {
-        let init_arc = Arc::new(init);
-        {
+    /// Change how many segments the playlists list. Takes effect as new
+    /// segments arrive.
+    fn set_window(&self, window: usize) {
+        self.inner.lock().unwrap().window = window;
+    }
+
+    /// Init segments, in rendition order.
+    fn set_init(&self, init: Vec<Vec<u8>>) {
+        let (renditions, init) = {
             let mut g = self.inner.lock().unwrap();
-            g.init = Some(init_arc.clone());
+            g.init = init.into_iter().map(Arc::new).collect();
             g.start_unix_ms = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .map(|d| d.as_millis() as u64)
                 .unwrap_or(0);
-            self.cv.notify_all();
-        }
+            (g.renditions.clone(), g.init.clone())
+        };
+        self.published.send_replace(());
         if let Some(m) = self.mirror_snapshot() {
-            m.write_init(&init_arc);
+            for (rendition, init) in renditions.iter().zip(&init) {
+                m.write_init(rendition, init);
+            }
         }
     }
 
-    fn push(&self, seg: Segment) {
-        let (evicted, mirror_snapshot) = {
+    /// Publish the next part of segment `seq`; `chunks` holds one chunk per
+    /// rendition, `duration` is in mdhd timescale. The segment is complete
+    /// once its `last` part is in.
+    fn push_part(&self, seq: u64, duration: u32, chunks: Vec<Vec<u8>>, last: bool) {
+        let completed = {
             let mut g = self.inner.lock().unwrap();
-            g.segments.push_back(seg.clone());
-            let evicted = if g.segments.len() > SEGMENT_CAPACITY {
-                g.segments.pop_front().map(|s| s.seq)
+            let open = g.open.get_or_insert_with(|| OpenSegment {
+                segment: Segment {
+                    seq,
+                    start: (seq - 1) * SEGMENT_SAMPLES as u64,
+                    duration: 0,
+                },
+                bytes: vec![Vec::new(); chunks.len()],
+                parts: Parts::default(),
+            });
+            for (bytes, chunk) in open.bytes.iter_mut().zip(&chunks) {
+                bytes.extend_from_slice(chunk);
+            }
+            open.segment.duration += duration;
+            let ends: Vec<usize> = open.bytes.iter().map(Vec::len).collect();
+            open.parts.push(duration, ends.into_iter());
+            if last {
+                g.open.take()
             } else {
                 None
-            };
-            let n = g.segments.len();
-            let take = n.min(SEGMENT_WINDOW);
-            let start = n - take;
-            let segs: Vec<Segment> = g.segments.iter().skip(start).cloned().collect();
-            let media_sequence = segs.first().map(|s| s.seq).unwrap_or(g.start_seq);
-            let start_unix_ms = g.start_unix_ms;
-            self.cv.notify_all();
-            (evicted, (segs, media_sequence, start_unix_ms))
+            }
         };
-        if let Some(m) = self.mirror_snapshot() {
-            m.write_segment(seg.seq, &seg.bytes);
-            if let Some(e) = evicted {
-                m.remove_segment(e);
+        match completed {
+            Some(open) => self.push(open),
+            None => {
+                self.published.send_replace(());
+            }
+        }
+    }
+
+    /// Move a completed segment into the window.
+    fn push(&self, open: OpenSegment) {
+        let OpenSegment {
+            segment,
+            bytes,
+            parts,
+        } = open;
+        let mirror = self.mirror_snapshot();
+        let renditions = self.inner.lock().unwrap().renditions.clone();
+        if let Some(m) = &mirror {
+            for (rendition, bytes) in renditions.iter().zip(&bytes) {
+                m.write_segment(rendition, segment.seq, bytes);
+            }
+        }
+
+        let (evicted, playlist) = {
+            let mut g = self.inner.lock().unwrap();
+            g.segments.push_back(StoredSegment {
+                segment,
+                sizes: bytes.iter().map(Vec::len).collect(),
+                bytes: bytes.into_iter().map(Arc::new).collect(),
+                parts,
+                on_disk: mirror.is_some(),
+            });
+            let mut evicted = Vec::new();
+            while g.segments.len() > g.window + SEGMENT_SLACK {
+                if let Some(s) = g.segments.pop_front() {
+                    evicted.push(s.segment.seq);
+                }
+            }
+            for s in g.segments.iter_mut().rev().skip(SEGMENT_CAPACITY) {
+                if s.bytes.is_empty() {
+                    break;
+                }
+                if s.on_disk {
+                    s.bytes.clear();
+                }
+            }
+            (evicted, g.playlist())
+        };
+        self.published.send_replace(());
+
+        if let Some(m) = mirror {
+            for seq in evicted {
+                for rendition in &renditions {
+                    m.remove_segment(rendition, seq);
+                }
             }
-            let (segs, media_seq, start_ms) = mirror_snapshot;
-            m.write_manifests(media_seq, &segs, start_ms);
+            m.write_manifests(&playlist);
+        }
+    }
+
+    pub(crate) fn rendition_index(&self, id: &str) -> Option<usize> {
+        let g = self.inner.lock().unwrap();
+        g.renditions.iter().position(|r| r.id == id)
+    }
+
+    pub(crate) fn init(&self, rendition: usize) -> Option<Arc<Vec<u8>>> {
+        self.inner.lock().unwrap().init.get(rendition).cloned()
+    }
+
+    /// A complete segment.
+    pub(crate) fn get(&self, rendition: usize, seq: u64) -> Option<Arc<Vec<u8>>> {
+        let (rendition, in_memory) = {
+            let g = self.inner.lock().unwrap();
+            let stored = g.stored(seq)?;
+            (
+                g.renditions.get(rendition)?.clone(),
+                stored.bytes.get(rendition).cloned(),
+            )
+        };
+        if in_memory.is_some() {
+            return in_memory;
         }
+        let mirror = self.mirror_snapshot()?;
+        mirror.read_segment(&rendition, seq).map(Arc::new)
+    }
+
+    /// One LL-HLS part, from the open segment or a complete one.
+    pub(crate) fn part(&self, rendition: usize, seq: u64, part: usize) -> Option<Vec<u8>> {
+        let range = {
+            let g = self.inner.lock().unwrap();
+            if let Some(open) = g.open.as_ref().filter(|o| o.segment.seq == seq) {
+                let range = open.parts.range(rendition, part)?;
+                return Some(open.bytes[rendition][range].to_vec());
+            }
+            g.stored(seq)?.parts.range(rendition, part)?
+        };
+        let segment = self.get(rendition, seq)?;
+        segment.get(range).map(<[u8]>::to_vec)
     }
 
-    pub(crate) fn init(&self) -> Option<Arc<Vec<u8>>> {
-        self.inner.lock().unwrap().init.clone()
+    /// Bytes of segment `seq` from `offset` on, and whether the segment is
+    /// complete. A segment the encoder is still producing — or is about to
+    /// start — reads as what has been published so far, so it can be sent
+    /// with chunked transfer encoding as its parts arrive.
+    pub(crate) fn read_from(
+        &self,
+        rendition: usize,
+        seq: u64,
+        offset: usize,
+    ) -> Option<(Vec<u8>, bool)> {
+        {
+            let g = self.inner.lock().unwrap();
+            if g.stored(seq).is_none() {
+                if let Some(open) = g.open.as_ref().filter(|o| o.segment.seq == seq) {
+                    let bytes = open.bytes.get(rendition)?;
+                    return Some((bytes.get(offset..).unwrap_or_default().to_vec(), false));
+                }
+                // Players whose clock runs a little ahead ask for the next
+                // segment before it has started.
+                let (next_seq, _) = g.next_part();
+                let upcoming = (next_seq..=next_seq + 1).contains(&seq) && !g.init.is_empty();
+                return upcoming.then(|| (Vec::new(), false));
+            }
+        }
+        let segment = self.get(rendition, seq)?;
+        Some((segment.get(offset..).unwrap_or_default().to_vec(), true))
     }
 
-    pub(crate) fn get(&self, seq: u64) -> Option<Arc<Vec<u8>>> {
+    /// Whether segment `seq` is complete or, with `part`, whether that part
+    /// of it has been published.
+    pub(crate) fn has(&self, seq: u64, part: Option<usize>) -> bool {
         let g = self.inner.lock().unwrap();
-        g.segments
-            .iter()
-            .find(|s| s.seq == seq)
-            .map(|s| s.bytes.clone())
+        if g.segments.back().is_some_and(|s| s.segment.seq >= seq) {
+            return true;
+        }
+        match (part, &g.open) {
+            (Some(part), Some(open)) => {
+                open.segment.seq == seq && open.parts.durations.len() > part
+            }
+            _ => false,
+        }
     }
 
-    /// Snapshot of the current playlist window. Returns
-    /// `(media_sequence, segments_in_order, start_unix_ms)` where
-    /// `segments_in_order` is at most SEGMENT_WINDOW most-recent segments.
-    pub(crate) fn snapshot(&self) -> (u64, Vec<Segment>, u64) {
+    /// Sequence number of the newest complete segment.
+    pub(crate) fn last_seq(&self) -> Option<u64> {
         let g = self.inner.lock().unwrap();
-        let n = g.segments.len();
-        let take = n.min(SEGMENT_WINDOW);
-        let start = n - take;
-        let segs: Vec<Segment> = g.segments.iter().skip(start).cloned().collect();
-        let media_sequence = segs.first().map(|s| s.seq).unwrap_or(g.start_seq);
-        (media_sequence, segs, g.start_unix_ms)
+        g.segments.back().map(|s| s.segment.seq)
     }
 
-    fn reset(&self) {
+    /// Notified whenever a part or segment is published.
+    pub(crate) fn subscribe(&self) -> watch::Receiver<()> {
+        self.published.subscribe()
+    }
+
+    /// Snapshot of the current playlist window.
+    pub(crate) fn playlist(&self) -> Playlist {
+        self.inner.lock().unwrap().playlist()
+    }
+
+    fn reset(&self, renditions: Vec<Rendition>, window: usize) {
         let mut g = self.inner.lock().unwrap();
-        g.init = None;
+        g.renditions = renditions;
+        g.init.clear();
         g.segments.clear();
+        g.open = None;
+        g.window = window;
         g.start_seq = 1;
         g.start_unix_ms = 0;
     }
@@ -373,16 +766,40 @@ static STARTED: Mutex<bool> = Mutex::new(false);
 
 struct CmafConfig {
     http_port: u16,
+    /// Bitrate of the single AAC rendition used when `renditions` is empty.
     bitrate_bps: u32,
     /// When Some, segments + manifests are mirrored to this directory.
-    /// None = in-memory only (default).
+    /// None = in-memory only (default), unless the DVR window needs disk.
     segment_dir: Option<PathBuf>,
+    /// Rendition specs ("aac-128", "flac", …), in the order they are listed
+    /// in the manifests.
+    renditions: Vec<String>,
+    /// How far back listeners can rewind, in seconds. Anything up to
+    /// SEGMENT_WINDOW segments is the plain live window.
+    dvr_window_secs: u32,
 }
 
 static CONFIG: Mutex<CmafConfig> = Mutex::new(CmafConfig {
     http_port: 7882,
     bitrate_bps: 128_000,
     segment_dir: None,
+    renditions: Vec::new(),
+    dvr_window_secs: 0,
+});
+
+/// Track metadata carried in the stream as timed ID3. `id` changes with
+/// every new title so players can tell it from a repeat.
+#[derive(Clone)]
+pub(crate) struct Metadata {
+    pub id: u32,
+    pub title: String,
+    pub artist: String,
+}
+
+static METADATA: Mutex<Metadata> = Mutex::new(Metadata {
+    id: 0,
+    title: String::new(),
+    artist: String::new(),
 });
 
 pub(crate) fn intake() -> Arc<PcmIntake> {
@@ -393,8 +810,34 @@ pub(crate) fn store() -> Arc<SegmentStore> {
     STORE.get_or_init(|| Arc::new(SegmentStore::new())).clone()
 }
 
-pub(crate) fn bitrate_bps() -> u32 {
-    CONFIG.lock().unwrap().bitrate_bps
+pub(crate) fn metadata() -> Metadata {
+    METADATA.lock().unwrap().clone()
+}
+
+/// Playlist window, in segments, for a DVR window of `secs` seconds.
+fn window_segments(secs: u32) -> usize {
+    ((secs as f64 / segment_seconds()).ceil() as usize).max(SEGMENT_WINDOW)
+}
+
+/// Where to mirror segments: `segment_dir` when set, otherwise a cache
+/// directory whenever the DVR window is longer than memory holds.
+fn mirror_dir(cfg: &CmafConfig) -> Option<PathBuf> {
+    cfg.segment_dir.clone().or_else(|| {
+        (window_segments(cfg.dvr_window_secs) > SEGMENT_WINDOW).then(|| {
+            dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".cache/rockbox/cmaf")
+        })
+    })
+}
+
+#[cfg(feature = "ffi")]
+fn c_string(ptr: *const std::os::raw::c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let s = unsafe { std::ffi::CStr::from_ptr(ptr) };
+    s.to_str().ok().map(str::to_string)
 }
 
 // ---------------------------------------------------------------------------
@@ -421,17 +864,63 @@ pub extern "C" fn pcm_cmaf_set_bitrate(bps: u32) {
 #[cfg(feature = "ffi")]
 #[no_mangle]
 pub extern "C" fn pcm_cmaf_set_segment_dir(path: *const std::os::raw::c_char) {
-    let dir: Option<PathBuf> = if path.is_null() {
-        None
-    } else {
-        let s = unsafe { std::ffi::CStr::from_ptr(path) };
-        match s.to_str() {
-            Ok(p) if !p.is_empty() => Some(PathBuf::from(p)),
-            _ => None,
-        }
-    };
-    CONFIG.lock().unwrap().segment_dir = dir.clone();
-    store().set_mirror(dir);
+    let dir = c_string(path).filter(|p| !p.is_empty()).map(PathBuf::from);
+    let mut cfg = CONFIG.lock().unwrap();
+    cfg.segment_dir = dir;
+    store().set_mirror(mirror_dir(&cfg));
+}
+
+/// Forget the configured renditions; the next `pcm_cmaf_start` falls back
+/// to one AAC rendition at the `pcm_cmaf_set_bitrate` bitrate unless
+/// renditions are added again.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn pcm_cmaf_clear_renditions() {
+    CONFIG.lock().unwrap().renditions.clear();
+}
+
+/// Add a rendition — `"aac-<kbps>"` or `"flac"`. Renditions are fixed once
+/// the encoder runs; they apply from the next `pcm_cmaf_start`.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn pcm_cmaf_add_rendition(spec: *const std::os::raw::c_char) {
+    if let Some(spec) = c_string(spec) {
+        CONFIG.lock().unwrap().renditions.push(spec);
+    }
+}
+
+/// Set how many seconds back listeners can rewind. Applies to the live
+/// store immediately; windows longer than memory holds are backed by the
+/// segment directory (or `~/.cache/rockbox/cmaf` when none is set).
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn pcm_cmaf_set_dvr_window(secs: u32) {
+    let mut cfg = CONFIG.lock().unwrap();
+    let changed = cfg.dvr_window_secs != secs;
+    cfg.dvr_window_secs = secs;
+    if changed {
+        let store = store();
+        store.set_window(window_segments(secs));
+        store.set_mirror(mirror_dir(&cfg));
+    }
+}
+
+/// Set the title / artist carried in the stream's timed ID3 metadata.
+/// NULL or empty title clears it.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn pcm_cmaf_set_metadata(
+    title: *const std::os::raw::c_char,
+    artist: *const std::os::raw::c_char,
+) {
+    let title = c_string(title).unwrap_or_default();
+    let artist = c_string(artist).unwrap_or_default();
+    let mut m = METADATA.lock().unwrap();
+    if m.title != title || m.artist != artist {
+        m.id = m.id.wrapping_add(1);
+        m.title = title;
+        m.artist = artist;
+    }
 }
 
 /// Start the encoder + HTTP server. Idempotent.
@@ -445,20 +934,22 @@ pub extern "C" fn pcm_cmaf_start() -> std::os::raw::c_int {
 
     let cfg = CONFIG.lock().unwrap();
     let http_port = cfg.http_port;
-    let bitrate = cfg.bitrate_bps;
-    let segment_dir = cfg.segment_dir.clone();
+    let renditions = rendition::parse_all(&cfg.renditions, cfg.bitrate_bps);
+    let window = window_segments(cfg.dvr_window_secs);
+    let mirror = mirror_dir(&cfg);
     drop(cfg);
 
     let intake = intake();
     let store = store();
     intake.reset();
-    store.reset();
-    store.set_mirror(segment_dir);
+    store.reset(renditions.clone(), window);
+    store.set_mirror(mirror);
 
     let intake_enc = intake.clone();
     let store_enc = store.clone();
+    let renditions_enc = renditions.clone();
     std::thread::spawn(move || {
-        if let Err(e) = encoder::run(intake_enc, store_enc, bitrate) {
+        if let Err(e) = encoder::run(intake_enc, store_enc, renditions_enc) {
             tracing::error!("cmaf encoder thread exited: {e}");
         }
     });
@@ -467,11 +958,13 @@ pub extern "C" fn pcm_cmaf_start() -> std::os::raw::c_int {
     std::thread::spawn(move || http::serve(http_port, store_http));
 
     *started = true;
+    let ids: Vec<&str> = renditions.iter().map(|r| r.id.as_str()).collect();
     tracing::info!(
         "cmaf sink: HLS at http://localhost:{http_port}/hls/master.m3u8, \
          DASH at http://localhost:{http_port}/dash/manifest.mpd \
-         (AAC-LC {} kbps)",
-        bitrate / 1000
+         ({}; {:.0} s window)",
+        ids.join(", "),
+        window as f64 * segment_seconds()
     );
     0
 }
@@ -501,3 +994,66 @@ pub extern "C" fn pcm_cmaf_close() {
     intake().close();
     *started = false;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Publish segment `seq` in one part, each rendition's payload being
+    /// `seq` repeated.
+    fn push_segment(store: &SegmentStore, seq: u64) {
+        let renditions = store.inner.lock().unwrap().renditions.len();
+        store.push_part(
+            seq,
+            SEGMENT_SAMPLES,
+            vec![vec![seq as u8; 16]; renditions],
+            true,
+        );
+    }
+
+    #[test]
+    fn segments_past_the_memory_capacity_are_served_from_disk() {
+        let dir = std::env::temp_dir().join(format!("rockbox-cmaf-{}", std::process::id()));
+        let window = window_segments(40);
+        let store = SegmentStore::new();
+        store.reset(vec![Rendition::aac(128_000)], window);
+        store.set_mirror(Some(dir.clone()));
+        store.set_init(vec![b"init".to_vec()]);
+        let last = (window + SEGMENT_SLACK + 4) as u64;
+        for seq in 1..=last {
+            push_segment(&store, seq);
+        }
+
+        let first = last + 1 - (window + SEGMENT_SLACK) as u64;
+        {
+            let g = store.inner.lock().unwrap();
+            assert_eq!(g.segments.front().map(|s| s.segment.seq), Some(first));
+            let in_memory: Vec<u64> = g
+                .segments
+                .iter()
+                .filter(|s| !s.bytes.is_empty())
+                .map(|s| s.segment.seq)
+                .collect();
+            assert_eq!(in_memory.len(), SEGMENT_CAPACITY);
+            assert_eq!(
+                in_memory.first(),
+                Some(&(last + 1 - SEGMENT_CAPACITY as u64))
+            );
+        }
+        assert_eq!(store.playlist().segments.len(), window);
+
+        // Out of memory, read back from the mirror.
+        assert_eq!(store.get(0, first).as_deref(), Some(&vec![first as u8; 16]));
+        assert_eq!(store.part(0, first, 0), Some(vec![first as u8; 16]));
+        assert_eq!(store.get(0, last).as_deref(), Some(&vec![last as u8; 16]));
+
+        // Out of the window altogether, and gone from disk.
+        let seg = dir.join("aac-128").join("seg");
+        assert!(store.get(0, first - 1).is_none());
+        assert!(!seg.join(format!("{}.m4s", first - 1)).exists());
+        assert!(seg.join(format!("{first}.m4s")).exists());
+        assert!(dir.join("aac-128").join("init.mp4").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
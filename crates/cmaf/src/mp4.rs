@@ -1,11 +1,16 @@
 //! Hand-rolled fragmented-MP4 (CMAF audio profile) writer.
 //!
 //! Produces a single init segment (ftyp + moov) and any number of media
-//! segments (styp + moof + mdat). All boxes are written big-endian per
-//! ISO/IEC 14496-12. Only what is needed for AAC-LC stereo @ 44.1 kHz is
-//! implemented; sample rate / channels are baked in via constants.
+//! segments (styp + emsg, then one moof + mdat chunk per LL-HLS part). All boxes are written big-endian
+//! per ISO/IEC 14496-12. Only what is needed for AAC-LC or FLAC stereo @
+//! 44.1 kHz is implemented; sample rate / channels are baked in via
+//! constants.
 
-use crate::{CHANNELS, SAMPLE_RATE};
+use crate::{
+    flac,
+    rendition::{Codec, Rendition},
+    CHANNELS, SAMPLE_RATE,
+};
 
 const TRACK_ID: u32 = 1;
 
@@ -93,10 +98,10 @@ const AUDIO_SPECIFIC_CONFIG: [u8; 2] = [0x12, 0x10];
 // Init segment (ftyp + moov)
 // ---------------------------------------------------------------------------
 
-pub(crate) fn write_init_segment() -> Vec<u8> {
+pub(crate) fn write_init_segment(rendition: &Rendition) -> Vec<u8> {
     let mut w = Writer::new();
     write_ftyp(&mut w);
-    write_moov(&mut w);
+    write_moov(&mut w, rendition.codec);
     w.into_vec()
 }
 
@@ -110,10 +115,10 @@ fn write_ftyp(w: &mut Writer) {
     w.end_box(s);
 }
 
-fn write_moov(w: &mut Writer) {
+fn write_moov(w: &mut Writer, codec: Codec) {
     let s = w.begin_box(b"moov");
     write_mvhd(w);
-    write_trak(w);
+    write_trak(w, codec);
     write_mvex(w);
     w.end_box(s);
 }
@@ -146,10 +151,10 @@ fn write_unity_matrix(w: &mut Writer) {
     }
 }
 
-fn write_trak(w: &mut Writer) {
+fn write_trak(w: &mut Writer, codec: Codec) {
     let s = w.begin_box(b"trak");
     write_tkhd(w);
-    write_mdia(w);
+    write_mdia(w, codec);
     w.end_box(s);
 }
 
@@ -173,11 +178,11 @@ fn write_tkhd(w: &mut Writer) {
     w.end_box(s);
 }
 
-fn write_mdia(w: &mut Writer) {
+fn write_mdia(w: &mut Writer, codec: Codec) {
     let s = w.begin_box(b"mdia");
     write_mdhd(w);
     write_hdlr(w);
-    write_minf(w);
+    write_minf(w, codec);
     w.end_box(s);
 }
 
@@ -204,11 +209,11 @@ fn write_hdlr(w: &mut Writer) {
     w.end_box(s);
 }
 
-fn write_minf(w: &mut Writer) {
+fn write_minf(w: &mut Writer, codec: Codec) {
     let s = w.begin_box(b"minf");
     write_smhd(w);
     write_dinf(w);
-    write_stbl(w);
+    write_stbl(w, codec);
     w.end_box(s);
 }
 
@@ -230,9 +235,9 @@ fn write_dinf(w: &mut Writer) {
     w.end_box(s);
 }
 
-fn write_stbl(w: &mut Writer) {
+fn write_stbl(w: &mut Writer, codec: Codec) {
     let s = w.begin_box(b"stbl");
-    write_stsd(w);
+    write_stsd(w, codec);
     // Empty sample tables — all real samples live in moof/trun.
     let stts = w.begin_full_box(b"stts", 0, 0);
     w.u32(0);
@@ -250,16 +255,42 @@ fn write_stbl(w: &mut Writer) {
     w.end_box(s);
 }
 
-fn write_stsd(w: &mut Writer) {
+fn write_stsd(w: &mut Writer, codec: Codec) {
     let s = w.begin_full_box(b"stsd", 0, 0);
     w.u32(1); // entry_count
-    write_mp4a(w);
+    match codec {
+        Codec::Aac { bitrate_bps } => write_mp4a(w, bitrate_bps),
+        Codec::Flac => write_flac(w),
+    }
     w.end_box(s);
 }
 
-fn write_mp4a(w: &mut Writer) {
+fn write_mp4a(w: &mut Writer, bitrate_bps: u32) {
     // AudioSampleEntry / mp4a
     let s = w.begin_box(b"mp4a");
+    write_audio_sample_entry(w);
+    write_esds(w, bitrate_bps);
+    w.end_box(s);
+}
+
+/// FLAC in ISO-BMFF ("Encapsulation of FLAC in ISO Base Media File
+/// Format"): an `fLaC` sample entry whose `dfLa` box carries the
+/// STREAMINFO block.
+fn write_flac(w: &mut Writer) {
+    let s = w.begin_box(b"fLaC");
+    write_audio_sample_entry(w);
+    let dfla = w.begin_full_box(b"dfLa", 0, 0);
+    let info = flac::stream_info();
+    // METADATA_BLOCK_HEADER: last-block flag, type 0 (STREAMINFO), length.
+    w.u8(0x80);
+    w.u24(info.len() as u32);
+    w.bytes(&info);
+    w.end_box(dfla);
+    w.end_box(s);
+}
+
+/// SampleEntry + AudioSampleEntry fields shared by `mp4a` and `fLaC`.
+fn write_audio_sample_entry(w: &mut Writer) {
     // SampleEntry header
     for _ in 0..6 {
         w.u8(0);
@@ -273,11 +304,9 @@ fn write_mp4a(w: &mut Writer) {
     w.u16(0); // pre_defined
     w.u16(0); // reserved
     w.u32(SAMPLE_RATE << 16); // samplerate (16.16 fixed)
-    write_esds(w);
-    w.end_box(s);
 }
 
-fn write_esds(w: &mut Writer) {
+fn write_esds(w: &mut Writer, bitrate_bps: u32) {
     let s = w.begin_full_box(b"esds", 0, 0);
 
     // ES_Descriptor (tag 0x03). We compute its inner-payload size first.
@@ -315,8 +344,8 @@ fn write_esds(w: &mut Writer) {
                 // streamType=5 (Audio), upStream=0, reserved=1  →  (5<<2) | 1 = 0x15
     w.u8(0x15);
     w.u24(0); // bufferSizeDB
-    w.u32(crate::bitrate_bps()); // maxBitrate
-    w.u32(crate::bitrate_bps()); // avgBitrate
+    w.u32(bitrate_bps); // maxBitrate
+    w.u32(bitrate_bps); // avgBitrate
 
     // DecoderSpecificInfo
     w.u8(0x05);
@@ -345,18 +374,25 @@ fn write_mvex(w: &mut Writer) {
 }
 
 // ---------------------------------------------------------------------------
-// Media segment (styp + moof + mdat)
+// Media segment (styp + emsg, then moof + mdat per chunk)
 // ---------------------------------------------------------------------------
 
-/// Build one fMP4 media segment containing `frames` AAC payloads, each
-/// `sample_duration` samples long (typically 1024 for AAC-LC) and starting
-/// at `base_media_decode_time` (cumulative sample count from stream start).
-pub(crate) fn write_media_segment(
-    seq: u32,
-    base_media_decode_time: u64,
-    sample_duration: u32,
-    frames: &[Vec<u8>],
-) -> Vec<u8> {
+/// Scheme for ID3 tags in `emsg` boxes ("Carriage of ID3 Timed Metadata in
+/// the Common Media Application Format"); hls.js and dash.js surface them
+/// as timed metadata cues.
+pub(crate) const ID3_SCHEME: &str = "https://aomedia.org/emsg/ID3";
+
+/// A timed ID3 tag to place at the start of a segment. Events with the same
+/// `id` are the same event, so players that see it repeated in every
+/// segment only fire it once.
+pub(crate) struct TimedId3<'a> {
+    pub id: u32,
+    pub tag: &'a [u8],
+}
+
+/// Start of an fMP4 media segment: `styp`, plus the timed ID3 `emsg` when
+/// there is one. The segment's chunks ([`write_chunk`]) follow it.
+pub(crate) fn write_segment_header(base_media_decode_time: u64, id3: Option<TimedId3>) -> Vec<u8> {
     let mut w = Writer::new();
 
     // styp
@@ -367,6 +403,26 @@ pub(crate) fn write_media_segment(
     w.fourcc(b"msix");
     w.end_box(styp);
 
+    if let Some(id3) = id3 {
+        write_emsg(&mut w, base_media_decode_time, id3);
+    }
+
+    w.into_vec()
+}
+
+/// Build one CMAF chunk (moof + mdat) containing `frames` (AAC or FLAC)
+/// payloads, each `sample_duration` samples long (1024 for both) and
+/// starting at `base_media_decode_time` (cumulative sample count from
+/// stream start). A segment is its header followed by one chunk per
+/// LL-HLS part; `sequence_number` counts chunks, not segments.
+pub(crate) fn write_chunk(
+    sequence_number: u32,
+    base_media_decode_time: u64,
+    sample_duration: u32,
+    frames: &[Vec<u8>],
+) -> Vec<u8> {
+    let mut w = Writer::new();
+
     // We need the moof size in advance because trun's data_offset is
     // relative to the start of the moof and must point at the first
     // mdat sample. We build the trun with a placeholder data_offset,
@@ -375,7 +431,7 @@ pub(crate) fn write_media_segment(
 
     // mfhd
     let mfhd = w.begin_full_box(b"mfhd", 0, 0);
-    w.u32(seq);
+    w.u32(sequence_number);
     w.end_box(mfhd);
 
     // traf
@@ -424,3 +480,18 @@ pub(crate) fn write_media_segment(
 
     w.into_vec()
 }
+
+/// Version 1 `emsg`: the presentation time is absolute (in the media
+/// timescale) rather than relative to the segment.
+fn write_emsg(w: &mut Writer, presentation_time: u64, id3: TimedId3) {
+    let s = w.begin_full_box(b"emsg", 1, 0);
+    w.u32(SAMPLE_RATE); // timescale
+    w.u64(presentation_time);
+    w.u32(0xFFFF_FFFF); // event_duration: unknown
+    w.u32(id3.id);
+    w.bytes(ID3_SCHEME.as_bytes());
+    w.u8(0);
+    w.u8(0); // value: empty
+    w.bytes(id3.tag);
+    w.end_box(s);
+}
@@ -0,0 +1,111 @@
+//! Renditions — the encodings the sink publishes side by side. Every
+//! rendition is cut into segments on the same sample boundaries and with
+//! the same sequence numbers, so a player can switch between them at any
+//! segment.
+
+use std::str::FromStr;
+
+/// Bitrate bounds for an AAC-LC rendition, in bits/sec.
+const AAC_MIN_BPS: u32 = 32_000;
+const AAC_MAX_BPS: u32 = 320_000;
+
+/// Upper bound for the lossless rendition: 16-bit stereo PCM at 44.1 kHz.
+/// FLAC frames never grow much past it, so it is a safe peak to advertise
+/// before any segment has been measured.
+const FLAC_NOMINAL_BPS: u32 = 1_411_200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Codec {
+    Aac { bitrate_bps: u32 },
+    Flac,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Rendition {
+    /// "aac-128", "flac", … — used in URLs, playlist names and as the DASH
+    /// `Representation@id`.
+    pub id: String,
+    pub codec: Codec,
+}
+
+impl Rendition {
+    pub(crate) fn aac(bitrate_bps: u32) -> Self {
+        let bitrate_bps = bitrate_bps.clamp(AAC_MIN_BPS, AAC_MAX_BPS);
+        Rendition {
+            id: format!("aac-{}", bitrate_bps / 1000),
+            codec: Codec::Aac { bitrate_bps },
+        }
+    }
+
+    pub(crate) fn flac() -> Self {
+        Rendition {
+            id: "flac".to_string(),
+            codec: Codec::Flac,
+        }
+    }
+
+    /// RFC 6381 codec string, as HLS `CODECS` spells it.
+    pub(crate) fn hls_codecs(&self) -> &'static str {
+        match self.codec {
+            Codec::Aac { .. } => "mp4a.40.2",
+            Codec::Flac => "fLaC",
+        }
+    }
+
+    /// Codec string for DASH `@codecs`.
+    pub(crate) fn dash_codecs(&self) -> &'static str {
+        match self.codec {
+            Codec::Aac { .. } => "mp4a.40.2",
+            Codec::Flac => "flac",
+        }
+    }
+
+    /// Bitrate to advertise before segments have been measured.
+    pub(crate) fn nominal_bps(&self) -> u32 {
+        match self.codec {
+            Codec::Aac { bitrate_bps } => bitrate_bps,
+            Codec::Flac => FLAC_NOMINAL_BPS,
+        }
+    }
+}
+
+/// Parses `"aac-<kbps>"` (e.g. `"aac-128"`) or `"flac"`.
+impl FromStr for Rendition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        if s == "flac" {
+            return Ok(Rendition::flac());
+        }
+        let kbps = s
+            .strip_prefix("aac-")
+            .and_then(|kbps| kbps.parse::<u32>().ok())
+            .ok_or_else(|| {
+                format!("unknown rendition {s:?}, expected \"aac-<kbps>\" or \"flac\"")
+            })?;
+        let bps = kbps.saturating_mul(1000);
+        if !(AAC_MIN_BPS..=AAC_MAX_BPS).contains(&bps) {
+            return Err(format!("rendition {s:?}: AAC bitrate must be 32–320 kbps"));
+        }
+        Ok(Rendition::aac(bps))
+    }
+}
+
+/// Parse `specs` into renditions, skipping (and logging) the ones that
+/// don't parse and any repeats. Falls back to a single AAC rendition at
+/// `default_bps` when nothing usable is left.
+pub(crate) fn parse_all(specs: &[String], default_bps: u32) -> Vec<Rendition> {
+    let mut renditions: Vec<Rendition> = Vec::new();
+    for spec in specs {
+        match spec.parse::<Rendition>() {
+            Ok(r) if !renditions.contains(&r) => renditions.push(r),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("cmaf: {e}"),
+        }
+    }
+    if renditions.is_empty() {
+        renditions.push(Rendition::aac(default_bps));
+    }
+    renditions
+}
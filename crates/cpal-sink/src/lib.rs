@@ -12,8 +12,10 @@
  *
  *   firmware DMA thread
  *     → pcm_cpal_push(data, size)    (blocks on back-pressure)
+ *       – ReplayGain from rockbox-mixer, so it switches with the track
  *       → ring buffer (512 KB, S16LE stereo)
  *         ← cpal audio callback drains at device rate
+ *           – software volume from rockbox-mixer (unity in hardware / fixed)
  *           – linear-interpolation resample if in_rate ≠ out_rate
  *           – converts i16 → f32 if the device requires f32
  *
@@ -25,8 +27,9 @@
  */
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rockbox_mixer::Dither;
 use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Condvar, Mutex, OnceLock};
 use std::time::Duration;
 
@@ -37,19 +40,9 @@ use std::time::Duration;
 // it to finish instead of doing the expensive work twice.
 static OPEN_STREAM_MTX: Mutex<()> = Mutex::new(());
 
-// Per-channel linear volume multiplier stored as f32 bits in an atomic so the
-// cpal callback can read without a mutex.  Initialised to 1.0 (0 dB).
-static VOLUME_L: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0f32
-static VOLUME_R: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0f32
-
-fn tenth_db_to_linear(tenth_db: i32) -> f32 {
-    if tenth_db == i32::MIN {
-        return 0.0; // PCM_MUTE_LEVEL
-    }
-    // tenth_db is in 1/10 dB units; amplitude = 10^(tenth_db / 200)
-    let linear = 10.0_f32.powf(tenth_db as f32 / 200.0);
-    linear.min(1.0) // clamp: we don't boost above unity
-}
+// Dither state for ReplayGain scaling in pcm_cpal_push(). Only the firmware
+// DMA thread pushes, so the lock is never contended.
+static PUSH_DITHER: Mutex<Option<Dither>> = Mutex::new(None);
 
 const RING_CAPACITY: usize = 512 * 1024;
 
@@ -152,8 +145,7 @@ fn fill_output_f32(output: &mut [f32], ring: &mut Ring, rs: &mut ResamplerState,
     let frames = output.len() / 2; // output.len() is always even (stereo)
     let mut wrote = 0usize;
 
-    let vol_l = f32::from_bits(VOLUME_L.load(Ordering::Relaxed));
-    let vol_r = f32::from_bits(VOLUME_R.load(Ordering::Relaxed));
+    let (vol_l, vol_r) = rockbox_mixer::volume_gains();
 
     // Ensure we have a "current" frame loaded.
     if !rs.cur_valid {
@@ -311,6 +303,7 @@ fn open_stream(rate: u32) {
     };
 
     let stream_result = if fmt == cpal::SampleFormat::I16 && out_rate == rate && channels == 2 {
+        let mut dither = Dither::new();
         device.build_output_stream(
             &config,
             move |output: &mut [i16], _| {
@@ -324,8 +317,8 @@ fn open_stream(rate: u32) {
                     output.fill(0);
                     return;
                 }
-                let vol_l = f32::from_bits(VOLUME_L.load(Ordering::Relaxed));
-                let vol_r = f32::from_bits(VOLUME_R.load(Ordering::Relaxed));
+                let (vol_l, vol_r) = rockbox_mixer::volume_gains();
+                let dithered = rockbox_mixer::dither();
                 let need_bytes = output.len() * 2;
                 let have = r.buf.len().min(need_bytes);
                 for (i, chunk) in r
@@ -336,9 +329,9 @@ fn open_stream(rate: u32) {
                     .enumerate()
                 {
                     if chunk.len() == 2 {
-                        let sample = i16::from_le_bytes([chunk[0], chunk[1]]) as f32;
+                        let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
                         let vol = if i % 2 == 0 { vol_l } else { vol_r };
-                        output[i] = (sample * vol).clamp(-32768.0, 32767.0) as i16;
+                        output[i] = dither.scale(sample, vol, 16, dithered) as i16;
                     }
                 }
                 let filled = have / 2;
@@ -412,6 +405,7 @@ fn open_stream(rate: u32) {
 
 #[no_mangle]
 pub extern "C" fn pcm_cpal_init() {
+    rockbox_mixer::register_sink("cpal");
     let _ = ring();
     let _ = stream_cell();
     let _ = current_rate();
@@ -490,16 +484,27 @@ pub unsafe extern "C" fn pcm_cpal_push(addr: *const u8, size: usize) {
     if !r.running {
         return;
     }
-    r.buf.extend(data.iter().copied());
+    let gain = rockbox_mixer::replaygain_gain();
+    if gain == 1.0 {
+        r.buf.extend(data.iter().copied());
+    } else {
+        let mut scaled = data.to_vec();
+        PUSH_DITHER
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Dither::new)
+            .scale_s16(&mut scaled, (gain, gain), rockbox_mixer::dither());
+        r.buf.extend(scaled);
+    }
 }
 
 /// Set per-channel volume. `vol_l` and `vol_r` are in tenth-decibel units
 /// (the Rockbox "centibel" convention: 0 = 0 dB, -740 = -74 dB, INT_MIN = mute).
-/// Called from audiohw_set_volume in audiohw-noop.c.
+/// Called from audiohw_set_volume in audiohw-noop.c. rockbox-mixer decides
+/// whether it scales the samples, goes to the hardware mixer or is ignored.
 #[no_mangle]
 pub extern "C" fn pcm_cpal_set_volume(vol_l: i32, vol_r: i32) {
-    VOLUME_L.store(tenth_db_to_linear(vol_l).to_bits(), Ordering::Relaxed);
-    VOLUME_R.store(tenth_db_to_linear(vol_r).to_bits(), Ordering::Relaxed);
+    rockbox_mixer::set_volume(vol_l, vol_r);
 }
 
 #[no_mangle]
@@ -571,6 +576,8 @@ pub extern "C" fn pcm_cpal_is_running() -> bool {
     ring().0.lock().unwrap().running
 }
 
-/// Force-linkage sentinel. crates/cli pulls this in so that the cpal-sink
-/// symbols are included in librockbox_cli.a even with --gc-sections.
+/// Force-linkage sentinel. crates/server pulls this in so that the cpal-sink
+/// symbols are included in librockbox_server.a even with --gc-sections.
+/// It has to be the server staticlib: the mixer state the sink reads is
+/// configured by the settings and handlers linked there.
 pub fn _link_cpal_sink() {}
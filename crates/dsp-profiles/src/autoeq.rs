@@ -0,0 +1,227 @@
+//! AutoEQ / EqualizerAPO `ParametricEQ.txt` import.
+//!
+//! ```text
+//! Preamp: -6.4 dB
+//! Filter 1: ON LSC Fc 105 Hz Gain 6.5 dB Q 0.70
+//! Filter 2: ON PK Fc 3345 Hz Gain 3.6 dB Q 2.28
+//! Filter 10: ON HSC Fc 10000 Hz Gain -2.1 dB Q 0.70
+//! ```
+//!
+//! Rockbox's equalizer has a fixed layout: a low shelf, eight peaking
+//! filters and a high shelf. The first low and high shelf go to the ends,
+//! peaking filters fill the middle in frequency order, and anything that
+//! does not fit is reported back rather than silently lost. When there are
+//! more than eight peaking filters the ones with the least gain are dropped.
+
+use anyhow::{anyhow, Error};
+
+use crate::{EqBand, EQ_DEFAULT_BANDS, EQ_NUM_BANDS};
+
+const PEAK_BANDS: usize = EQ_NUM_BANDS - 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParametricEq {
+    /// Tenths of a dB of attenuation, as `eq_precut`.
+    pub precut: i32,
+    pub bands: Vec<EqBand>,
+    /// Filters that were switched off, unsupported or did not fit.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    LowShelf,
+    Peak,
+    HighShelf,
+}
+
+fn value_after(words: &[&str], key: &str) -> Option<f64> {
+    let i = words.iter().position(|w| w.eq_ignore_ascii_case(key))?;
+    words.get(i + 1)?.parse().ok()
+}
+
+/// Rockbox units: Hz, Q and gain in tenths, within the equalizer's range.
+fn band(fc: f64, gain: f64, q: f64) -> EqBand {
+    EqBand {
+        cutoff: (fc.round() as i32).clamp(20, 22040),
+        q: ((q * 10.0).round() as i32).clamp(1, 64),
+        gain: ((gain * 10.0).round() as i32).clamp(-240, 240),
+    }
+}
+
+pub fn parse(content: &str) -> Result<ParametricEq, Error> {
+    let mut precut = 0;
+    let mut low_shelf = None;
+    let mut high_shelf = None;
+    let mut peaks = Vec::new();
+    let mut skipped = Vec::new();
+    let mut filters = 0;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, rest) = match line.split_once(':') {
+            Some((key, rest)) => (key.trim(), rest.trim()),
+            None => continue,
+        };
+        if key.eq_ignore_ascii_case("preamp") {
+            let db: f64 = rest
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| anyhow!("invalid preamp \"{}\"", line))?;
+            precut = ((-db * 10.0).round() as i32).clamp(0, 240);
+            continue;
+        }
+        if !key.to_ascii_lowercase().starts_with("filter") {
+            continue;
+        }
+        filters += 1;
+        let words: Vec<&str> = rest.split_whitespace().collect();
+        if words.first().map(|w| w.eq_ignore_ascii_case("on")) != Some(true) {
+            skipped.push(format!("{} (off)", line));
+            continue;
+        }
+        let shape = match words.get(1).map(|w| w.to_ascii_uppercase()).as_deref() {
+            Some("PK") | Some("PEQ") => Shape::Peak,
+            Some("LS") | Some("LSC") | Some("LSQ") => Shape::LowShelf,
+            Some("HS") | Some("HSC") | Some("HSQ") => Shape::HighShelf,
+            _ => {
+                skipped.push(format!("{} (unsupported filter type)", line));
+                continue;
+            }
+        };
+        let (fc, gain) = match (value_after(&words, "fc"), value_after(&words, "gain")) {
+            (Some(fc), Some(gain)) => (fc, gain),
+            _ => return Err(anyhow!("filter without Fc or Gain: \"{}\"", line)),
+        };
+        let q = value_after(&words, "q").unwrap_or(match shape {
+            Shape::Peak => 1.0,
+            _ => 0.7,
+        });
+        let slot = match shape {
+            Shape::LowShelf => &mut low_shelf,
+            Shape::HighShelf => &mut high_shelf,
+            Shape::Peak => {
+                peaks.push((band(fc, gain, q), line.to_string()));
+                continue;
+            }
+        };
+        match slot {
+            Some(_) => skipped.push(format!("{} (only one shelf of each kind fits)", line)),
+            None => *slot = Some(band(fc, gain, q)),
+        }
+    }
+
+    if filters == 0 {
+        return Err(anyhow!(
+            "no filters found; expected AutoEQ ParametricEQ.txt"
+        ));
+    }
+
+    if peaks.len() > PEAK_BANDS {
+        peaks.sort_by_key(|(band, _)| -band.gain.abs());
+        for (_, line) in peaks.drain(PEAK_BANDS..) {
+            skipped.push(format!(
+                "{} (more than {} peaking filters)",
+                line, PEAK_BANDS
+            ));
+        }
+    }
+    peaks.sort_by_key(|(band, _)| band.cutoff);
+
+    let mut bands = EQ_DEFAULT_BANDS.to_vec();
+    if let Some(shelf) = low_shelf {
+        bands[0] = shelf;
+    }
+    for (i, (peak, _)) in peaks.into_iter().enumerate() {
+        bands[i + 1] = peak;
+    }
+    if let Some(shelf) = high_shelf {
+        bands[EQ_NUM_BANDS - 1] = shelf;
+    }
+
+    Ok(ParametricEq {
+        precut,
+        bands,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENNHEISER_HD_600: &str = "\
+Preamp: -6.4 dB
+Filter 1: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70
+Filter 2: ON PK Fc 185 Hz Gain -3.0 dB Q 0.51
+Filter 3: ON PK Fc 1407 Hz Gain 1.9 dB Q 2.23
+Filter 4: ON PK Fc 3193 Hz Gain -2.4 dB Q 3.72
+Filter 5: ON PK Fc 6271 Hz Gain 3.7 dB Q 3.09
+Filter 6: ON PK Fc 4489 Hz Gain 0.2 dB Q 3.11
+Filter 7: ON PK Fc 43 Hz Gain 0.4 dB Q 1.62
+Filter 8: ON PK Fc 2246 Hz Gain -0.5 dB Q 4.59
+Filter 9: ON PK Fc 8611 Hz Gain -1.5 dB Q 2.23
+Filter 10: ON HSC Fc 10000 Hz Gain -3.8 dB Q 0.70
+";
+
+    #[test]
+    fn maps_shelves_to_the_ends_and_peaks_in_frequency_order() {
+        let eq = parse(SENNHEISER_HD_600).unwrap();
+        assert_eq!(eq.precut, 64);
+        assert!(eq.skipped.is_empty());
+        assert_eq!(
+            eq.bands[0],
+            EqBand {
+                cutoff: 105,
+                q: 7,
+                gain: 55
+            }
+        );
+        assert_eq!(
+            eq.bands[9],
+            EqBand {
+                cutoff: 10000,
+                q: 7,
+                gain: -38
+            }
+        );
+        let cutoffs: Vec<i32> = eq.bands[1..9].iter().map(|b| b.cutoff).collect();
+        assert_eq!(cutoffs, [43, 185, 1407, 2246, 3193, 4489, 6271, 8611]);
+        assert_eq!(eq.bands[4].q, 46);
+        assert_eq!(eq.bands[4].gain, -5);
+    }
+
+    #[test]
+    fn reports_what_does_not_fit() {
+        let mut content = String::from("Preamp: -3 dB\nFilter 1: ON LP Fc 20000 Hz\n");
+        content.push_str("Filter 2: OFF PK Fc 100 Hz Gain 1 dB Q 1\n");
+        for (i, gain) in [1.0, -2.0, 3.0, -4.0, 5.0, -6.0, 7.0, -8.0, 0.5]
+            .iter()
+            .enumerate()
+        {
+            content.push_str(&format!(
+                "Filter {}: ON PK Fc {} Hz Gain {} dB Q 1.41\n",
+                i + 3,
+                (i + 1) * 1000,
+                gain
+            ));
+        }
+        let eq = parse(&content).unwrap();
+        assert_eq!(eq.skipped.len(), 3);
+        assert!(eq.skipped[2].contains("Gain 0.5 dB"));
+        // The shelves keep Rockbox's defaults, flat.
+        assert_eq!(eq.bands[0], EQ_DEFAULT_BANDS[0]);
+        assert_eq!(eq.bands[9], EQ_DEFAULT_BANDS[9]);
+        assert_eq!(eq.bands[1].cutoff, 1000);
+        assert_eq!(eq.bands[8].cutoff, 8000);
+    }
+
+    #[test]
+    fn rejects_files_without_filters() {
+        assert!(parse("Preamp: -3 dB\n").is_err());
+        assert!(parse("Filter 1: ON PK Fc 100 Hz Q 1\n").is_err());
+    }
+}
@@ -0,0 +1,516 @@
+//! Named DSP profiles.
+//!
+//! A profile holds the equalizer (precut and the ten bands), crossfeed,
+//! perceptual bass enhancement, compressor and dither settings, in the
+//! firmware's own units, so applying one is a straight copy into
+//! `global_settings`. Profiles can be imported from AutoEQ's
+//! `ParametricEQ.txt` (see [`autoeq`]) and bound to an output: when the
+//! server switches to that output it applies the bound profile. Outputs are
+//! keyed by device id (`builtin`, an AirPlay or Chromecast device id, ...)
+//! and Bluetooth headsets by [`bluetooth_output`].
+//!
+//! Applying is left to the server, which owns the firmware.
+
+pub mod autoeq;
+
+use std::ops::RangeInclusive;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use uuid::Uuid;
+
+pub const EQ_NUM_BANDS: usize = 10;
+
+/// The firmware's default bands (`eq_defaults`), all flat.
+pub const EQ_DEFAULT_BANDS: [EqBand; EQ_NUM_BANDS] = [
+    EqBand::flat(32, 7),
+    EqBand::flat(64, 10),
+    EqBand::flat(125, 10),
+    EqBand::flat(250, 10),
+    EqBand::flat(500, 10),
+    EqBand::flat(1000, 10),
+    EqBand::flat(2000, 10),
+    EqBand::flat(4000, 10),
+    EqBand::flat(8000, 10),
+    EqBand::flat(16000, 7),
+];
+
+/// Output key of the built-in (local) sink.
+pub const BUILTIN_OUTPUT: &str = "builtin";
+
+/// Output key of a Bluetooth headset, which plays through the built-in sink.
+pub fn bluetooth_output(address: &str) -> String {
+    format!("bluetooth:{}", address.to_uppercase())
+}
+
+/// One equalizer band. Band 0 is a low shelf, band 9 a high shelf and the
+/// rest are peaking filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EqBand {
+    /// Hz.
+    pub cutoff: i32,
+    /// Tenths.
+    pub q: i32,
+    /// Tenths of a dB.
+    pub gain: i32,
+}
+
+impl EqBand {
+    const fn flat(cutoff: i32, q: i32) -> Self {
+        Self { cutoff, q, gain: 0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Compressor {
+    /// dB, 0 (off) to -24.
+    pub threshold: i32,
+    /// 0 off, 1 auto.
+    pub makeup_gain: i32,
+    /// 0-4: 2:1, 4:1, 6:1, 10:1, limit.
+    pub ratio: i32,
+    /// 0 hard, 1 soft.
+    pub knee: i32,
+    /// ms.
+    pub release_time: i32,
+    /// ms.
+    pub attack_time: i32,
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Self {
+            threshold: 0,
+            makeup_gain: 1,
+            ratio: 1,
+            knee: 1,
+            release_time: 500,
+            attack_time: 5,
+        }
+    }
+}
+
+/// Everything a profile sets. Missing fields take the firmware defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DspSettings {
+    pub eq_enabled: bool,
+    /// Tenths of a dB of attenuation, 0-240.
+    pub eq_precut: i32,
+    pub eq_bands: Vec<EqBand>,
+    /// 0 off, 1 Meier, 2 custom.
+    pub crossfeed: i32,
+    /// Tenths of a dB.
+    pub crossfeed_direct_gain: i32,
+    pub crossfeed_cross_gain: i32,
+    pub crossfeed_hf_attenuation: i32,
+    /// Hz.
+    pub crossfeed_hf_cutoff: i32,
+    /// Percent, 0 off.
+    pub pbe: i32,
+    /// Tenths of a dB.
+    pub pbe_precut: i32,
+    pub compressor: Compressor,
+    pub dithering_enabled: bool,
+}
+
+impl Default for DspSettings {
+    fn default() -> Self {
+        Self {
+            eq_enabled: false,
+            eq_precut: 0,
+            eq_bands: EQ_DEFAULT_BANDS.to_vec(),
+            crossfeed: 0,
+            crossfeed_direct_gain: -15,
+            crossfeed_cross_gain: -60,
+            crossfeed_hf_attenuation: -160,
+            crossfeed_hf_cutoff: 700,
+            pbe: 0,
+            pbe_precut: -25,
+            compressor: Compressor::default(),
+            dithering_enabled: false,
+        }
+    }
+}
+
+fn check(name: &str, value: i32, range: RangeInclusive<i32>) -> Result<()> {
+    match range.contains(&value) {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "{} must be between {} and {}, got {}",
+            name,
+            range.start(),
+            range.end(),
+            value
+        )),
+    }
+}
+
+impl DspSettings {
+    /// Check every value against the range the firmware accepts.
+    pub fn validate(&self) -> Result<()> {
+        if self.eq_bands.len() != EQ_NUM_BANDS {
+            return Err(anyhow!(
+                "eq_bands needs {} bands, got {}",
+                EQ_NUM_BANDS,
+                self.eq_bands.len()
+            ));
+        }
+        check("eq_precut", self.eq_precut, 0..=240)?;
+        for band in &self.eq_bands {
+            check("eq band cutoff", band.cutoff, 20..=22040)?;
+            check("eq band q", band.q, 1..=64)?;
+            check("eq band gain", band.gain, -240..=240)?;
+        }
+        check("crossfeed", self.crossfeed, 0..=2)?;
+        check("crossfeed_direct_gain", self.crossfeed_direct_gain, -60..=0)?;
+        check(
+            "crossfeed_cross_gain",
+            self.crossfeed_cross_gain,
+            -120..=-30,
+        )?;
+        check(
+            "crossfeed_hf_attenuation",
+            self.crossfeed_hf_attenuation,
+            -240..=-60,
+        )?;
+        check("crossfeed_hf_cutoff", self.crossfeed_hf_cutoff, 500..=2000)?;
+        check("pbe", self.pbe, 0..=100)?;
+        check("pbe_precut", self.pbe_precut, -45..=0)?;
+        let c = &self.compressor;
+        check("compressor threshold", c.threshold, -24..=0)?;
+        check("compressor makeup_gain", c.makeup_gain, 0..=1)?;
+        check("compressor ratio", c.ratio, 0..=4)?;
+        check("compressor knee", c.knee, 0..=1)?;
+        check("compressor release_time", c.release_time, 100..=1000)?;
+        check("compressor attack_time", c.attack_time, 0..=30)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DspProfile {
+    pub id: String,
+    pub name: String,
+    pub settings: DspSettings,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Fields to change on a profile; `None` keeps the current value.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DspProfileUpdate {
+    pub name: Option<String>,
+    pub settings: Option<DspSettings>,
+}
+
+/// A profile imported from AutoEQ, with the filters that did not make it.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoEqImport {
+    pub profile: DspProfile,
+    pub skipped: Vec<String>,
+}
+
+/// The profile an output switches to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputProfile {
+    pub output: String,
+    pub profile_id: String,
+    pub profile_name: String,
+}
+
+const PROFILE_COLUMNS: &str = "id, name, settings, created_at, updated_at";
+
+fn profile_from_row(r: SqliteRow) -> Result<DspProfile> {
+    Ok(DspProfile {
+        id: r.get(0),
+        name: r.get(1),
+        settings: serde_json::from_str(r.get::<&str, _>(2))?,
+        created_at: r.get(3),
+        updated_at: r.get(4),
+    })
+}
+
+fn check_name(name: &str) -> Result<&str> {
+    match name.trim() {
+        "" => Err(anyhow!("a profile needs a name")),
+        name => Ok(name),
+    }
+}
+
+#[derive(Clone)]
+pub struct DspProfileStore {
+    pool: Pool<Sqlite>,
+}
+
+impl DspProfileStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<DspProfile>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {PROFILE_COLUMNS} FROM dsp_profile ORDER BY name COLLATE NOCASE"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(profile_from_row).collect()
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<DspProfile>> {
+        let row = sqlx::query(&format!(
+            "SELECT {PROFILE_COLUMNS} FROM dsp_profile WHERE id = ?"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(profile_from_row).transpose()
+    }
+
+    async fn name_taken(&self, name: &str, except: &str) -> Result<bool> {
+        let row = sqlx::query("SELECT 1 FROM dsp_profile WHERE name = ? AND id != ?")
+            .bind(name)
+            .bind(except)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.is_some())
+    }
+
+    pub async fn create(&self, name: &str, settings: DspSettings) -> Result<DspProfile> {
+        let name = check_name(name)?;
+        settings.validate()?;
+        if self.name_taken(name, "").await? {
+            return Err(anyhow!("a profile named \"{}\" already exists", name));
+        }
+        let now = Utc::now().timestamp();
+        let profile = DspProfile {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            settings,
+            created_at: now,
+            updated_at: now,
+        };
+        sqlx::query(
+            "INSERT INTO dsp_profile (id, name, settings, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&profile.id)
+        .bind(&profile.name)
+        .bind(serde_json::to_string(&profile.settings)?)
+        .bind(profile.created_at)
+        .bind(profile.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(profile)
+    }
+
+    pub async fn update(&self, id: &str, update: DspProfileUpdate) -> Result<Option<DspProfile>> {
+        let mut profile = match self.get(id).await? {
+            Some(profile) => profile,
+            None => return Ok(None),
+        };
+        if let Some(name) = update.name {
+            let name = check_name(&name)?;
+            if self.name_taken(name, id).await? {
+                return Err(anyhow!("a profile named \"{}\" already exists", name));
+            }
+            profile.name = name.to_string();
+        }
+        if let Some(settings) = update.settings {
+            settings.validate()?;
+            profile.settings = settings;
+        }
+        profile.updated_at = Utc::now().timestamp();
+        sqlx::query("UPDATE dsp_profile SET name = ?, settings = ?, updated_at = ? WHERE id = ?")
+            .bind(&profile.name)
+            .bind(serde_json::to_string(&profile.settings)?)
+            .bind(profile.updated_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(Some(profile))
+    }
+
+    /// Delete a profile and unbind it from every output.
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM dsp_profile_output WHERE profile_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM dsp_profile WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Create a profile from an AutoEQ `ParametricEQ.txt`. Only the
+    /// equalizer comes from the file; everything else is left at the
+    /// defaults, crossfeed and compressor off.
+    pub async fn import_autoeq(&self, name: &str, content: &str) -> Result<AutoEqImport> {
+        let eq = autoeq::parse(content)?;
+        let settings = DspSettings {
+            eq_enabled: true,
+            eq_precut: eq.precut,
+            eq_bands: eq.bands,
+            ..Default::default()
+        };
+        Ok(AutoEqImport {
+            profile: self.create(name, settings).await?,
+            skipped: eq.skipped,
+        })
+    }
+
+    pub async fn outputs(&self) -> Result<Vec<OutputProfile>> {
+        let rows = sqlx::query(
+            "SELECT o.output, o.profile_id, p.name FROM dsp_profile_output o \
+             JOIN dsp_profile p ON p.id = o.profile_id ORDER BY o.output",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| OutputProfile {
+                output: r.get(0),
+                profile_id: r.get(1),
+                profile_name: r.get(2),
+            })
+            .collect())
+    }
+
+    /// Bind `output` to a profile, replacing any earlier binding. Returns
+    /// `false` if there is no such profile.
+    pub async fn set_output(&self, output: &str, profile_id: &str) -> Result<bool> {
+        if self.get(profile_id).await?.is_none() {
+            return Ok(false);
+        }
+        sqlx::query(
+            "INSERT INTO dsp_profile_output (output, profile_id) VALUES (?, ?) \
+             ON CONFLICT(output) DO UPDATE SET profile_id = excluded.profile_id",
+        )
+        .bind(output)
+        .bind(profile_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(true)
+    }
+
+    pub async fn clear_output(&self, output: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM dsp_profile_output WHERE output = ?")
+            .bind(output)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The profile bound to `output`, if any.
+    pub async fn for_output(&self, output: &str) -> Result<Option<DspProfile>> {
+        let row = sqlx::query(&format!(
+            "SELECT {PROFILE_COLUMNS} FROM dsp_profile \
+             WHERE id = (SELECT profile_id FROM dsp_profile_output WHERE output = ?)"
+        ))
+        .bind(output)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(profile_from_row).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    async fn store() -> DspProfileStore {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        pool.execute(include_str!(
+            "../../library/migrations/20261019001400_add_dsp_profiles.sql"
+        ))
+        .await
+        .unwrap();
+        DspProfileStore::new(pool)
+    }
+
+    #[tokio::test]
+    async fn profiles_round_trip_and_validate() {
+        let store = store().await;
+        let mut settings = DspSettings {
+            crossfeed: 1,
+            ..Default::default()
+        };
+        let profile = store.create("Desk", settings.clone()).await.unwrap();
+        assert_eq!(store.get(&profile.id).await.unwrap().unwrap(), profile);
+        assert!(store.create(" Desk ", settings.clone()).await.is_err());
+
+        settings.eq_bands[3].gain = 300;
+        assert!(store
+            .update(
+                &profile.id,
+                DspProfileUpdate {
+                    settings: Some(settings.clone()),
+                    ..Default::default()
+                }
+            )
+            .await
+            .is_err());
+        settings.eq_bands[3].gain = -30;
+        let updated = store
+            .update(
+                &profile.id,
+                DspProfileUpdate {
+                    name: Some("Desk speakers".to_string()),
+                    settings: Some(settings),
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.name, "Desk speakers");
+        assert_eq!(updated.settings.eq_bands[3].gain, -30);
+
+        // Partial JSON fills in the defaults.
+        let partial: DspSettings = serde_json::from_str(r#"{"eq_enabled": true}"#).unwrap();
+        assert_eq!(partial.eq_bands, EQ_DEFAULT_BANDS);
+        assert!(partial.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn outputs_follow_their_profile() {
+        let store = store().await;
+        let imported = store
+            .import_autoeq(
+                "HD 600",
+                "Preamp: -2.5 dB\nFilter 1: ON PK Fc 1000 Hz Gain 2.5 dB Q 1.41\n",
+            )
+            .await
+            .unwrap();
+        let profile = imported.profile;
+        assert!(profile.settings.eq_enabled);
+        assert_eq!(profile.settings.eq_precut, 25);
+
+        let headset = bluetooth_output("aa:bb:cc:dd:ee:ff");
+        assert!(store.set_output(&headset, &profile.id).await.unwrap());
+        assert!(!store.set_output(BUILTIN_OUTPUT, "missing").await.unwrap());
+        assert_eq!(
+            store
+                .for_output("bluetooth:AA:BB:CC:DD:EE:FF")
+                .await
+                .unwrap(),
+            Some(profile.clone())
+        );
+        assert_eq!(store.outputs().await.unwrap()[0].profile_name, "HD 600");
+
+        assert!(store.delete(&profile.id).await.unwrap());
+        assert!(store.outputs().await.unwrap().is_empty());
+        assert_eq!(store.for_output(&headset).await.unwrap(), None);
+    }
+}
@@ -60,7 +60,8 @@ pub async fn search_tracks(pool: Pool<Sqlite>, query: &str) -> Result<Option<Tra
         FROM track t
         JOIN track_fts f ON f.id = t.id
         WHERE track_fts MATCH ?
-          AND t.is_remote = 0
+          AND (t.is_remote = 0 OR t.source_id IS NOT NULL)
+          AND t.hidden = 0
         ORDER BY rank
         LIMIT ?
         "#,
@@ -0,0 +1,41 @@
+use async_graphql::{
+    parser::{parse_query, types::OperationType},
+    Context, Guard, Result,
+};
+use rockbox_auth::Scope;
+
+/// Field guard for operations that need more than the operation-level
+/// check in `server.rs` gives them, e.g. `#[graphql(guard = "ScopeGuard(Scope::Admin)")]`.
+/// The caller's scope is put into the request data by the HTTP handlers;
+/// a request without one is refused.
+pub struct ScopeGuard(pub Scope);
+
+impl Guard for ScopeGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        match ctx.data_opt::<Scope>() {
+            Some(granted) if *granted >= self.0 => Ok(()),
+            Some(granted) => Err(format!(
+                "this token has the {} scope; {} is required",
+                granted, self.0
+            )
+            .into()),
+            None => Err("a valid API token is required".into()),
+        }
+    }
+}
+
+/// Scope a whole document needs: control if it contains a mutation, read
+/// otherwise. Unparseable documents are left for the executor to reject.
+pub fn operation_scope(query: &str) -> Scope {
+    let Ok(document) = parse_query(query) else {
+        return Scope::Read;
+    };
+    match document
+        .operations
+        .iter()
+        .any(|(_, op)| op.node.ty == OperationType::Mutation)
+    {
+        true => Scope::Control,
+        false => Scope::Read,
+    }
+}
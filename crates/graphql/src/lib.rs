@@ -4,6 +4,7 @@ use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
 use schema::{Mutation, Query, Subscription};
 use tokio::fs;
 
+pub mod auth;
 pub mod schema;
 pub mod server;
 pub mod simplebroker;
@@ -21,6 +22,22 @@ pub fn rockbox_url() -> String {
     format!("http://127.0.0.1:{}", port)
 }
 
+/// HTTP client for calls to `rockbox_url()`. It carries the per-process
+/// internal token, so these hops keep working once API tokens are enforced.
+/// Never point it at another host.
+pub fn internal_client() -> reqwest::Client {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Ok(value) =
+        reqwest::header::HeaderValue::from_str(&rockbox_auth::internal_authorization())
+    {
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap_or_default()
+}
+
 pub fn read_files(path: String) -> BoxFuture<'static, Result<Vec<String>, Error>> {
     Box::pin(async move {
         if path.starts_with("upnp://") {
@@ -1,4 +1,7 @@
 use async_graphql::*;
+use rockbox_auth::Scope;
+
+use crate::auth::ScopeGuard;
 
 use super::objects::bluetooth_device::BluetoothDevice;
 
@@ -33,6 +36,7 @@ pub struct BluetoothMutation;
 
 #[Object]
 impl BluetoothMutation {
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
     async fn bluetooth_scan(
         &self,
         _ctx: &Context<'_>,
@@ -58,6 +62,7 @@ impl BluetoothMutation {
         Err(Error::new("Bluetooth is only supported on Linux"))
     }
 
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
     async fn bluetooth_connect(&self, _ctx: &Context<'_>, address: String) -> Result<bool, Error> {
         #[cfg(target_os = "linux")]
         {
@@ -68,6 +73,7 @@ impl BluetoothMutation {
         Err(Error::new("Bluetooth is only supported on Linux"))
     }
 
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
     async fn bluetooth_disconnect(
         &self,
         _ctx: &Context<'_>,
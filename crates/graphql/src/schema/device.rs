@@ -1,6 +1,7 @@
 use async_graphql::*;
+use rockbox_auth::Scope;
 
-use crate::rockbox_url;
+use crate::{auth::ScopeGuard, rockbox_url};
 
 use super::objects::device::Device;
 
@@ -10,7 +11,7 @@ pub struct DeviceQuery;
 #[Object]
 impl DeviceQuery {
     async fn devices(&self, _ctx: &Context<'_>) -> Result<Vec<Device>, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/devices", rockbox_url());
         let response = client.get(&url).send().await?;
         let response = response.json::<Vec<Device>>().await?;
@@ -18,7 +19,7 @@ impl DeviceQuery {
     }
 
     async fn device(&self, _ctx: &Context<'_>, id: String) -> Result<Option<Device>, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/devices/{}", rockbox_url(), id);
         let response = client.get(&url).send().await?;
 
@@ -36,15 +37,17 @@ pub struct DeviceMutation;
 
 #[Object]
 impl DeviceMutation {
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
     async fn connect(&self, _ctx: &Context<'_>, id: String) -> Result<bool, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/devices/{}/connect", rockbox_url(), id);
         client.put(&url).send().await?;
         Ok(true)
     }
 
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
     async fn disconnect(&self, _ctx: &Context<'_>, id: String) -> Result<bool, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/devices/{}/disconnect", rockbox_url(), id);
         client.put(&url).send().await?;
         Ok(true)
@@ -0,0 +1,140 @@
+use async_graphql::*;
+use rockbox_dsp_profiles::{DspProfile as RsDspProfile, DspProfileStore, DspProfileUpdate};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    rockbox_url,
+    schema::objects::dsp_profile::{AutoEqImport, DspProfile, DspSettingsInput, OutputDspProfile},
+};
+
+async fn check(response: reqwest::Response) -> Result<reqwest::Response, Error> {
+    match response.status().is_success() {
+        true => Ok(response),
+        false => Err(Error::new(response.text().await?)),
+    }
+}
+
+#[derive(Default)]
+pub struct DspProfileQuery;
+
+#[Object]
+impl DspProfileQuery {
+    async fn dsp_profiles(&self, ctx: &Context<'_>) -> Result<Vec<DspProfile>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let profiles = DspProfileStore::new(pool.clone()).list().await?;
+        Ok(profiles.into_iter().map(DspProfile::from).collect())
+    }
+
+    async fn dsp_profile(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<Option<DspProfile>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(DspProfileStore::new(pool.clone())
+            .get(&id)
+            .await?
+            .map(DspProfile::from))
+    }
+
+    /// Outputs that switch to a profile when selected.
+    async fn dsp_outputs(&self, ctx: &Context<'_>) -> Result<Vec<OutputDspProfile>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let outputs = DspProfileStore::new(pool.clone()).outputs().await?;
+        Ok(outputs.into_iter().map(OutputDspProfile::from).collect())
+    }
+}
+
+#[derive(Default)]
+pub struct DspProfileMutation;
+
+#[Object]
+impl DspProfileMutation {
+    /// Save a profile. Without `settings`, the settings in effect now are
+    /// saved.
+    async fn save_dsp_profile(
+        &self,
+        _ctx: &Context<'_>,
+        name: String,
+        settings: Option<DspSettingsInput>,
+    ) -> Result<DspProfile, Error> {
+        let client = crate::internal_client();
+        let url = format!("{}/dsp/profiles", rockbox_url());
+        let body = serde_json::json!({
+            "name": name,
+            "settings": settings.map(rockbox_dsp_profiles::DspSettings::from),
+        });
+        let response = check(client.post(&url).json(&body).send().await?).await?;
+        Ok(response.json::<RsDspProfile>().await?.into())
+    }
+
+    async fn update_dsp_profile(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        name: Option<String>,
+        settings: Option<DspSettingsInput>,
+    ) -> Result<Option<DspProfile>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let update = DspProfileUpdate {
+            name,
+            settings: settings.map(Into::into),
+        };
+        let profile = DspProfileStore::new(pool.clone())
+            .update(&id, update)
+            .await?;
+        Ok(profile.map(DspProfile::from))
+    }
+
+    /// Create a profile from the contents of an AutoEQ `ParametricEQ.txt`.
+    async fn import_auto_eq(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        content: String,
+    ) -> Result<AutoEqImport, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let imported = DspProfileStore::new(pool.clone())
+            .import_autoeq(&name, &content)
+            .await?;
+        Ok(imported.into())
+    }
+
+    async fn delete_dsp_profile(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(DspProfileStore::new(pool.clone()).delete(&id).await?)
+    }
+
+    async fn apply_dsp_profile(&self, _ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let client = crate::internal_client();
+        let url = format!("{}/dsp/profiles/{}/apply", rockbox_url(), id);
+        let response = client.post(&url).send().await?;
+        Ok(response.status().is_success())
+    }
+
+    /// Switch to `profileId` whenever `output` is selected; applied now if it
+    /// is the current device.
+    async fn set_output_dsp_profile(
+        &self,
+        _ctx: &Context<'_>,
+        output: String,
+        profile_id: String,
+    ) -> Result<bool, Error> {
+        let client = crate::internal_client();
+        let url = format!("{}/dsp/outputs/{}", rockbox_url(), output);
+        let body = serde_json::json!({ "profile_id": profile_id });
+        let response = client.put(&url).json(&body).send().await?;
+        Ok(response.status().is_success())
+    }
+
+    async fn clear_output_dsp_profile(
+        &self,
+        ctx: &Context<'_>,
+        output: String,
+    ) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(DspProfileStore::new(pool.clone())
+            .clear_output(&output)
+            .await?)
+    }
+}
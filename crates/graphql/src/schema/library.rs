@@ -1,11 +1,22 @@
 use async_graphql::*;
-use rockbox_library::{entity::favourites::Favourites, repo};
-use rockbox_playlists::{resolver, rules::RuleCriteria, PlaylistStore};
+use rockbox_auth::Scope;
+use rockbox_health::{DuplicateReason, HealthStore};
+use rockbox_library::{
+    entity::{
+        favourites::Favourites,
+        rating::{self, KIND_ALBUM, KIND_ARTIST, KIND_TRACK, MAX_RATING},
+    },
+    ratings, repo,
+};
+use rockbox_playlists::{resolver, rules::RuleCriteria};
 use sqlx::{Pool, Sqlite};
 
-use crate::{rockbox_url, schema::objects::track::Track};
+use crate::{auth::ScopeGuard, rockbox_url, schema::objects::track::Track};
 
-use super::objects::{album::Album, artist::Artist, genre::Genre, search::SearchResults};
+use super::objects::{
+    album::Album, artist::Artist, genre::Genre, health::HealthReport, lyrics::Lyrics,
+    rating::Rating, search::SearchResults,
+};
 
 #[derive(Default)]
 pub struct LibraryQuery;
@@ -62,6 +73,15 @@ impl LibraryQuery {
         Ok(results.map(Into::into))
     }
 
+    async fn lyrics(&self, ctx: &Context<'_>, track_id: String) -> Result<Option<Lyrics>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let Some(track) = repo::track::find(pool.clone(), &track_id).await? else {
+            return Ok(None);
+        };
+        let lyrics = rockbox_library::lyrics::get_lyrics(pool.clone(), &track).await?;
+        Ok(lyrics.map(Into::into))
+    }
+
     async fn genres(&self, ctx: &Context<'_>) -> Result<Vec<Genre>, Error> {
         let pool = ctx.data::<Pool<Sqlite>>()?;
         let genres = repo::genre::all(pool.clone()).await?;
@@ -118,18 +138,16 @@ impl LibraryQuery {
     /// `rules` is a JSON-encoded RuleCriteria (same shape used by smart playlists).
     async fn filter_albums(&self, ctx: &Context<'_>, rules: String) -> Result<Vec<Album>, Error> {
         let pool = ctx.data::<Pool<Sqlite>>()?;
-        let store = ctx.data::<PlaylistStore>()?;
         let criteria: RuleCriteria = serde_json::from_str(&rules)?;
-        let albums = resolver::filter_albums(store, pool, &criteria).await?;
+        let albums = resolver::filter_albums(pool, &criteria).await?;
         Ok(albums.into_iter().map(Into::into).collect())
     }
 
     /// Same as `filter_albums` but returns matching artists.
     async fn filter_artists(&self, ctx: &Context<'_>, rules: String) -> Result<Vec<Artist>, Error> {
         let pool = ctx.data::<Pool<Sqlite>>()?;
-        let store = ctx.data::<PlaylistStore>()?;
         let criteria: RuleCriteria = serde_json::from_str(&rules)?;
-        let artists = resolver::filter_artists(store, pool, &criteria).await?;
+        let artists = resolver::filter_artists(pool, &criteria).await?;
         Ok(artists.into_iter().map(Into::into).collect())
     }
 
@@ -145,6 +163,50 @@ impl LibraryQuery {
         Ok(results.into_iter().map(Into::into).collect())
     }
 
+    /// An item's 1–5 star rating, 0 when unrated. `kind` is `track`,
+    /// `album` or `artist`.
+    async fn rating(&self, ctx: &Context<'_>, kind: String, id: String) -> Result<i32, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        check_kind(&kind)?;
+        let rating = repo::rating::find(pool.clone(), &kind, &id).await?;
+        Ok(rating.unwrap_or(0) as i32)
+    }
+
+    /// Rated items, best first, optionally of one kind only.
+    async fn ratings(&self, ctx: &Context<'_>, kind: Option<String>) -> Result<Vec<Rating>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let kinds = match kind {
+            Some(kind) => {
+                check_kind(&kind)?;
+                vec![kind]
+            }
+            None => [KIND_TRACK, KIND_ALBUM, KIND_ARTIST]
+                .map(String::from)
+                .to_vec(),
+        };
+        let mut results = vec![];
+        for kind in kinds {
+            results.extend(repo::rating::all(pool.clone(), &kind).await?);
+        }
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    /// The last library health report; `refresh` checks the library again
+    /// first.
+    async fn library_health(
+        &self,
+        ctx: &Context<'_>,
+        refresh: Option<bool>,
+    ) -> Result<HealthReport, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let store = HealthStore::new(pool.clone());
+        let report = match refresh.unwrap_or(false) {
+            true => store.scan().await?,
+            false => store.report().await?,
+        };
+        Ok(report.into())
+    }
+
     async fn search(&self, ctx: &Context<'_>, term: String) -> Result<SearchResults, Error> {
         #[cfg(not(feature = "fts5"))]
         let (tracks, albums, artists) = {
@@ -266,6 +328,63 @@ impl LibraryMutation {
         Ok(0)
     }
 
+    /// Rate a track from 1 to 5 stars; 0 clears the rating.
+    async fn rate_track(&self, ctx: &Context<'_>, id: String, rating: i32) -> Result<i32, Error> {
+        rate(ctx, KIND_TRACK, &id, rating).await
+    }
+
+    async fn rate_album(&self, ctx: &Context<'_>, id: String, rating: i32) -> Result<i32, Error> {
+        rate(ctx, KIND_ALBUM, &id, rating).await
+    }
+
+    async fn rate_artist(&self, ctx: &Context<'_>, id: String, rating: i32) -> Result<i32, Error> {
+        rate(ctx, KIND_ARTIST, &id, rating).await
+    }
+
+    /// Hide a track from listings, search and smart playlists.
+    async fn hide_track(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(HealthStore::new(pool.clone()).set_hidden(&id, true).await?)
+    }
+
+    async fn unhide_track(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(HealthStore::new(pool.clone())
+            .set_hidden(&id, false)
+            .await?)
+    }
+
+    /// Hide all but the best copy of every duplicate, optionally only of
+    /// those found by `reason` (`metadata` or `fingerprint`). Returns the
+    /// ids of the hidden tracks.
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn hide_duplicates(
+        &self,
+        ctx: &Context<'_>,
+        reason: Option<String>,
+    ) -> Result<Vec<String>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let reason = match reason.as_deref() {
+            None => None,
+            Some("metadata") => Some(DuplicateReason::Metadata),
+            Some("fingerprint") => Some(DuplicateReason::Fingerprint),
+            Some(reason) => return Err(Error::new(format!("unknown reason: {}", reason))),
+        };
+        Ok(HealthStore::new(pool.clone())
+            .hide_duplicates(reason)
+            .await?)
+    }
+
+    /// Delete album and artist rows no track points at. Returns how many
+    /// rows were removed.
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn remove_orphans(&self, ctx: &Context<'_>) -> Result<i32, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let (albums, artists) = HealthStore::new(pool.clone()).remove_orphans().await?;
+        Ok((albums + artists) as i32)
+    }
+
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
     async fn scan_library(&self, ctx: &Context<'_>) -> Result<i32, Error> {
         let client = ctx.data::<reqwest::Client>().unwrap();
         let url = format!("{}/scan-library", rockbox_url());
@@ -273,3 +392,22 @@ impl LibraryMutation {
         Ok(0)
     }
 }
+
+fn check_kind(kind: &str) -> Result<(), Error> {
+    match rating::is_kind(kind) {
+        true => Ok(()),
+        false => Err(Error::new(format!("unknown kind: {}", kind))),
+    }
+}
+
+async fn rate(ctx: &Context<'_>, kind: &str, id: &str, stars: i32) -> Result<i32, Error> {
+    let pool = ctx.data::<Pool<Sqlite>>()?;
+    if !(0..=MAX_RATING as i32).contains(&stars) {
+        return Err(Error::new(format!(
+            "rating must be between 0 and {}",
+            MAX_RATING
+        )));
+    }
+    ratings::set(pool.clone(), kind, id, stars as u8).await?;
+    Ok(0)
+}
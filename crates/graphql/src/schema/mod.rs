@@ -2,27 +2,37 @@ use async_graphql::{MergedObject, MergedSubscription};
 use bluetooth::{BluetoothMutation, BluetoothQuery};
 use browse::BrowseQuery;
 use device::{DeviceMutation, DeviceQuery};
+use dsp_profile::{DspProfileMutation, DspProfileQuery};
 use library::{LibraryMutation, LibraryQuery};
 use playback::{PlaybackMutation, PlaybackQuery, PlaybackSubscription};
 use playlist::{PlaylistMutation, PlaylistQuery, PlaylistSubscription};
+use podcast::{PodcastMutation, PodcastQuery};
+use radio::{RadioMutation, RadioQuery};
 use saved_playlist::{SavedPlaylistMutation, SavedPlaylistQuery};
+use schedule::{ScheduleMutation, ScheduleQuery};
 use settings::{SettingsMutation, SettingsQuery};
-use smart_playlist::{SmartPlaylistMutation, SmartPlaylistQuery};
+use smart_playlist::{SmartPlaylistMutation, SmartPlaylistQuery, SmartPlaylistSubscription};
 use sound::{SoundMutation, SoundQuery};
+use source::{SourceMutation, SourceQuery};
 use system::SystemQuery;
 
 pub mod bluetooth;
 pub mod browse;
 pub mod device;
+pub mod dsp_profile;
 pub mod library;
 pub mod metadata;
 pub mod objects;
 pub mod playback;
 pub mod playlist;
+pub mod podcast;
+pub mod radio;
 pub mod saved_playlist;
+pub mod schedule;
 pub mod settings;
 pub mod smart_playlist;
 pub mod sound;
+pub mod source;
 pub mod system;
 
 #[derive(MergedObject, Default)]
@@ -30,12 +40,17 @@ pub struct Query(
     BluetoothQuery,
     BrowseQuery,
     DeviceQuery,
+    DspProfileQuery,
     LibraryQuery,
     PlaybackQuery,
     PlaylistQuery,
+    PodcastQuery,
+    RadioQuery,
     SavedPlaylistQuery,
     SmartPlaylistQuery,
+    ScheduleQuery,
     SoundQuery,
+    SourceQuery,
     SettingsQuery,
     SystemQuery,
 );
@@ -44,17 +59,26 @@ pub struct Query(
 pub struct Mutation(
     BluetoothMutation,
     DeviceMutation,
+    DspProfileMutation,
     PlaybackMutation,
     PlaylistMutation,
+    PodcastMutation,
+    RadioMutation,
     SavedPlaylistMutation,
     SmartPlaylistMutation,
+    ScheduleMutation,
     SoundMutation,
+    SourceMutation,
     LibraryMutation,
     SettingsMutation,
 );
 
 #[derive(MergedSubscription, Default)]
-pub struct Subscription(PlaybackSubscription, PlaylistSubscription);
+pub struct Subscription(
+    PlaybackSubscription,
+    PlaylistSubscription,
+    SmartPlaylistSubscription,
+);
 
 #[macro_export]
 macro_rules! check_and_load_player {
@@ -74,7 +98,7 @@ macro_rules! check_and_load_player {
         // and exits before the playlist is built — see `is_cast_device` in
         // rpc/src/lib.rs for the matching check.
         if player.is_cast_device {
-            let client = reqwest::Client::new();
+            let client = $crate::internal_client();
             let body = serde_json::json!({
                 "tracks": $tracks,
                 "shuffle": $shuffle,
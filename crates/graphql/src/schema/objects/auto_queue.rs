@@ -0,0 +1,23 @@
+use async_graphql::*;
+use rockbox_autoqueue::AutoQueue as RsAutoQueue;
+use serde::Serialize;
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct AutoQueue {
+    pub enabled: bool,
+    /// "shuffle", "instant_mix" or "smart_playlist".
+    pub source: String,
+    pub smart_playlist_id: Option<String>,
+    pub seed_track_id: Option<String>,
+}
+
+impl From<RsAutoQueue> for AutoQueue {
+    fn from(config: RsAutoQueue) -> Self {
+        Self {
+            enabled: config.enabled,
+            source: config.source.as_str().to_string(),
+            smart_playlist_id: config.smart_playlist_id,
+            seed_track_id: config.seed_track_id,
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use async_graphql::*;
+use rockbox_dsp_profiles::{
+    AutoEqImport as RsAutoEqImport, Compressor, DspProfile as RsDspProfile,
+    DspSettings as RsDspSettings, EqBand, OutputProfile as RsOutputProfile,
+};
+
+use super::{
+    compressor_settings::{CompressorSettings, CompressorSettingsInput},
+    eq_band_setting::{EqBandSetting, EqBandSettingInput},
+};
+
+/// DSP settings in the firmware's units (tenths of a dB, Hz, tenths of Q).
+#[derive(Default, Clone, SimpleObject)]
+pub struct DspSettings {
+    pub eq_enabled: bool,
+    pub eq_precut: i32,
+    pub eq_bands: Vec<EqBandSetting>,
+    pub crossfeed: i32,
+    pub crossfeed_direct_gain: i32,
+    pub crossfeed_cross_gain: i32,
+    pub crossfeed_hf_attenuation: i32,
+    pub crossfeed_hf_cutoff: i32,
+    pub pbe: i32,
+    pub pbe_precut: i32,
+    pub compressor: CompressorSettings,
+    pub dithering_enabled: bool,
+}
+
+impl From<RsDspSettings> for DspSettings {
+    fn from(s: RsDspSettings) -> Self {
+        Self {
+            eq_enabled: s.eq_enabled,
+            eq_precut: s.eq_precut,
+            eq_bands: s
+                .eq_bands
+                .into_iter()
+                .map(|b| EqBandSetting {
+                    cutoff: b.cutoff,
+                    q: b.q,
+                    gain: b.gain,
+                })
+                .collect(),
+            crossfeed: s.crossfeed,
+            crossfeed_direct_gain: s.crossfeed_direct_gain,
+            crossfeed_cross_gain: s.crossfeed_cross_gain,
+            crossfeed_hf_attenuation: s.crossfeed_hf_attenuation,
+            crossfeed_hf_cutoff: s.crossfeed_hf_cutoff,
+            pbe: s.pbe,
+            pbe_precut: s.pbe_precut,
+            compressor: CompressorSettings {
+                threshold: s.compressor.threshold,
+                makeup_gain: s.compressor.makeup_gain,
+                ratio: s.compressor.ratio,
+                knee: s.compressor.knee,
+                release_time: s.compressor.release_time,
+                attack_time: s.compressor.attack_time,
+            },
+            dithering_enabled: s.dithering_enabled,
+        }
+    }
+}
+
+#[derive(Default, Clone, SimpleObject)]
+pub struct DspProfile {
+    pub id: String,
+    pub name: String,
+    pub settings: DspSettings,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<RsDspProfile> for DspProfile {
+    fn from(p: RsDspProfile) -> Self {
+        Self {
+            id: p.id,
+            name: p.name,
+            settings: p.settings.into(),
+            created_at: p.created_at,
+            updated_at: p.updated_at,
+        }
+    }
+}
+
+#[derive(Default, Clone, SimpleObject)]
+pub struct AutoEqImport {
+    pub profile: DspProfile,
+    /// Filters that were switched off, unsupported or did not fit.
+    pub skipped: Vec<String>,
+}
+
+impl From<RsAutoEqImport> for AutoEqImport {
+    fn from(i: RsAutoEqImport) -> Self {
+        Self {
+            profile: i.profile.into(),
+            skipped: i.skipped,
+        }
+    }
+}
+
+/// The profile applied when the server switches to `output`.
+#[derive(Default, Clone, SimpleObject)]
+pub struct OutputDspProfile {
+    /// Device id, or `bluetooth:<address>` for a Bluetooth headset.
+    pub output: String,
+    pub profile_id: String,
+    pub profile_name: String,
+}
+
+impl From<RsOutputProfile> for OutputDspProfile {
+    fn from(o: RsOutputProfile) -> Self {
+        Self {
+            output: o.output,
+            profile_id: o.profile_id,
+            profile_name: o.profile_name,
+        }
+    }
+}
+
+/// Fields left out take the firmware defaults.
+#[derive(Default, InputObject)]
+pub struct DspSettingsInput {
+    pub eq_enabled: Option<bool>,
+    pub eq_precut: Option<i32>,
+    pub eq_bands: Option<Vec<EqBandSettingInput>>,
+    pub crossfeed: Option<i32>,
+    pub crossfeed_direct_gain: Option<i32>,
+    pub crossfeed_cross_gain: Option<i32>,
+    pub crossfeed_hf_attenuation: Option<i32>,
+    pub crossfeed_hf_cutoff: Option<i32>,
+    pub pbe: Option<i32>,
+    pub pbe_precut: Option<i32>,
+    pub compressor: Option<CompressorSettingsInput>,
+    pub dithering_enabled: Option<bool>,
+}
+
+impl From<DspSettingsInput> for RsDspSettings {
+    fn from(input: DspSettingsInput) -> Self {
+        let d = RsDspSettings::default();
+        Self {
+            eq_enabled: input.eq_enabled.unwrap_or(d.eq_enabled),
+            eq_precut: input.eq_precut.unwrap_or(d.eq_precut),
+            eq_bands: match input.eq_bands {
+                Some(bands) => bands
+                    .into_iter()
+                    .map(|b| EqBand {
+                        cutoff: b.cutoff,
+                        q: b.q,
+                        gain: b.gain,
+                    })
+                    .collect(),
+                None => d.eq_bands,
+            },
+            crossfeed: input.crossfeed.unwrap_or(d.crossfeed),
+            crossfeed_direct_gain: input
+                .crossfeed_direct_gain
+                .unwrap_or(d.crossfeed_direct_gain),
+            crossfeed_cross_gain: input.crossfeed_cross_gain.unwrap_or(d.crossfeed_cross_gain),
+            crossfeed_hf_attenuation: input
+                .crossfeed_hf_attenuation
+                .unwrap_or(d.crossfeed_hf_attenuation),
+            crossfeed_hf_cutoff: input.crossfeed_hf_cutoff.unwrap_or(d.crossfeed_hf_cutoff),
+            pbe: input.pbe.unwrap_or(d.pbe),
+            pbe_precut: input.pbe_precut.unwrap_or(d.pbe_precut),
+            compressor: match input.compressor {
+                Some(c) => Compressor {
+                    threshold: c.threshold,
+                    makeup_gain: c.makeup_gain,
+                    ratio: c.ratio,
+                    knee: c.knee,
+                    release_time: c.release_time,
+                    attack_time: c.attack_time,
+                },
+                None => d.compressor,
+            },
+            dithering_enabled: input.dithering_enabled.unwrap_or(d.dithering_enabled),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use async_graphql::*;
+use rockbox_health::{
+    DuplicateGroup as RsDuplicateGroup, DuplicateReason, HealthReport as RsHealthReport,
+    TrackIssue as RsTrackIssue,
+};
+
+use super::{album::Album, artist::Artist, track::Track};
+
+#[derive(Default, Clone, SimpleObject)]
+pub struct DuplicateGroup {
+    /// `metadata` or `fingerprint`.
+    pub reason: String,
+    /// Best copy first.
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Default, Clone, SimpleObject)]
+pub struct TrackIssue {
+    pub track: Track,
+    pub problems: Vec<String>,
+}
+
+/// Result of the last library health check.
+#[derive(Default, Clone, SimpleObject)]
+pub struct HealthReport {
+    pub generated_at: i64,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub missing_tags: Vec<TrackIssue>,
+    pub missing_cover_art: Vec<Album>,
+    pub broken_files: Vec<TrackIssue>,
+    pub orphan_albums: Vec<Album>,
+    pub orphan_artists: Vec<Artist>,
+    pub hidden_tracks: Vec<Track>,
+}
+
+impl From<RsDuplicateGroup> for DuplicateGroup {
+    fn from(group: RsDuplicateGroup) -> Self {
+        Self {
+            reason: match group.reason {
+                DuplicateReason::Metadata => "metadata".to_string(),
+                DuplicateReason::Fingerprint => "fingerprint".to_string(),
+            },
+            tracks: group.tracks.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<RsTrackIssue> for TrackIssue {
+    fn from(issue: RsTrackIssue) -> Self {
+        Self {
+            track: issue.track.into(),
+            problems: issue.problems,
+        }
+    }
+}
+
+impl From<RsHealthReport> for HealthReport {
+    fn from(report: RsHealthReport) -> Self {
+        Self {
+            generated_at: report.generated_at.timestamp(),
+            duplicates: report.duplicates.into_iter().map(Into::into).collect(),
+            missing_tags: report.missing_tags.into_iter().map(Into::into).collect(),
+            missing_cover_art: report
+                .missing_cover_art
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            broken_files: report.broken_files.into_iter().map(Into::into).collect(),
+            orphan_albums: report.orphan_albums.into_iter().map(Into::into).collect(),
+            orphan_artists: report.orphan_artists.into_iter().map(Into::into).collect(),
+            hidden_tracks: report.hidden_tracks.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use async_graphql::*;
+use rockbox_library::lyrics::{LyricLine as RsLyricLine, Lyrics as RsLyrics};
+use serde::Serialize;
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct LyricLine {
+    pub start_ms: Option<u64>,
+    pub text: String,
+}
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct Lyrics {
+    pub synced: bool,
+    pub source: String,
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub album: Option<String>,
+    pub lines: Vec<LyricLine>,
+}
+
+/// The synced line being sung, published by the playback broker whenever
+/// it changes.
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct CurrentLyricLine {
+    pub track_id: String,
+    pub index: usize,
+    pub start_ms: u64,
+    /// Start of the following line, when there is one.
+    pub next_start_ms: Option<u64>,
+    pub text: String,
+}
+
+impl From<RsLyricLine> for LyricLine {
+    fn from(l: RsLyricLine) -> Self {
+        Self {
+            start_ms: l.start_ms,
+            text: l.text,
+        }
+    }
+}
+
+impl From<RsLyrics> for Lyrics {
+    fn from(l: RsLyrics) -> Self {
+        Self {
+            synced: l.synced,
+            source: l.source,
+            artist: l.artist,
+            title: l.title,
+            album: l.album,
+            lines: l.lines.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -1,19 +1,28 @@
 pub mod album;
 pub mod artist;
 pub mod audio_status;
+pub mod auto_queue;
 pub mod bluetooth_device;
 pub mod compressor_settings;
 pub mod device;
+pub mod dsp_profile;
 pub mod entry;
 pub mod eq_band_setting;
 pub mod genre;
+pub mod health;
+pub mod lyrics;
 pub mod new_global_settings;
 pub mod playlist;
+pub mod podcast;
+pub mod radio_station;
+pub mod rating;
 pub mod replaygain_settings;
 pub mod saved_playlist;
+pub mod schedule;
 pub mod search;
 pub mod settings_list;
 pub mod smart_playlist;
+pub mod source;
 pub mod system_status;
 pub mod track;
 pub mod user_settings;
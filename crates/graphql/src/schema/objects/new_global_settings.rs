@@ -48,4 +48,10 @@ pub struct NewGlobalSettings {
     pub afr_enabled: Option<i32>,
     pub pbe: Option<i32>,
     pub pbe_precut: Option<i32>,
+    pub shuffle_mode: Option<String>,
+    pub playlists_dir: Option<String>,
+    pub stream_crossfade_secs: Option<f32>,
+    pub stream_crossfade_curve: Option<String>,
+    pub mixramp_db: Option<f32>,
+    pub mixramp_delay: Option<f32>,
 }
@@ -0,0 +1,85 @@
+use async_graphql::*;
+use rockbox_podcasts::{PodcastChannel as RsPodcastChannel, PodcastEpisode as RsPodcastEpisode};
+use serde::Serialize;
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct PodcastChannel {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub link: Option<String>,
+    pub image_url: Option<String>,
+    pub auto_download: bool,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub last_refreshed: Option<i64>,
+    pub episode_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<RsPodcastChannel> for PodcastChannel {
+    fn from(c: RsPodcastChannel) -> Self {
+        Self {
+            id: c.id,
+            url: c.url,
+            title: c.title,
+            description: c.description,
+            author: c.author,
+            link: c.link,
+            image_url: c.image_url,
+            auto_download: c.auto_download,
+            status: c.status,
+            error_message: c.error_message,
+            last_refreshed: c.last_refreshed,
+            episode_count: c.episode_count,
+            created_at: c.created_at,
+            updated_at: c.updated_at,
+        }
+    }
+}
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct PodcastEpisode {
+    pub id: String,
+    pub channel_id: String,
+    pub guid: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub audio_url: String,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub duration_secs: Option<i64>,
+    pub published_at: Option<i64>,
+    pub status: String,
+    pub local_path: Option<String>,
+    pub position_ms: i64,
+    pub played: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<RsPodcastEpisode> for PodcastEpisode {
+    fn from(e: RsPodcastEpisode) -> Self {
+        Self {
+            id: e.id,
+            channel_id: e.channel_id,
+            guid: e.guid,
+            title: e.title,
+            description: e.description,
+            audio_url: e.audio_url,
+            mime_type: e.mime_type,
+            file_size: e.file_size,
+            duration_secs: e.duration_secs,
+            published_at: e.published_at,
+            status: e.status,
+            local_path: e.local_path,
+            position_ms: e.position_ms,
+            played: e.played,
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+        }
+    }
+}
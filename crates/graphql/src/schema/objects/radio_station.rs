@@ -0,0 +1,36 @@
+use async_graphql::*;
+use rockbox_library::entity::radio_station::RadioStation as RsRadioStation;
+use serde::Serialize;
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct RadioStation {
+    pub id: String,
+    pub name: String,
+    pub stream_url: String,
+    pub homepage_url: Option<String>,
+    pub genre: Option<String>,
+    pub country: Option<String>,
+    pub codec: Option<String>,
+    pub bitrate: Option<u32>,
+    pub favicon: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<RsRadioStation> for RadioStation {
+    fn from(s: RsRadioStation) -> Self {
+        Self {
+            id: s.id,
+            name: s.name,
+            stream_url: s.stream_url,
+            homepage_url: s.homepage_url,
+            genre: s.genre,
+            country: s.country,
+            codec: s.codec,
+            bitrate: s.bitrate,
+            favicon: s.favicon,
+            created_at: s.created_at.timestamp(),
+            updated_at: s.updated_at.timestamp(),
+        }
+    }
+}
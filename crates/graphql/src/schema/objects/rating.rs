@@ -0,0 +1,24 @@
+use async_graphql::*;
+use rockbox_library::entity::rating::Rating as RsRating;
+use serde::Serialize;
+
+/// A 1–5 star rating of a track, album or artist.
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct Rating {
+    /// `track`, `album` or `artist`.
+    pub kind: String,
+    pub item_id: String,
+    pub rating: i32,
+    pub updated_at: i64,
+}
+
+impl From<RsRating> for Rating {
+    fn from(r: RsRating) -> Self {
+        Self {
+            kind: r.kind,
+            item_id: r.item_id,
+            rating: r.rating as i32,
+            updated_at: r.updated_at.timestamp(),
+        }
+    }
+}
@@ -90,3 +90,42 @@ impl From<rockbox_playlists::PlaylistFolder> for SavedPlaylistFolder {
         }
     }
 }
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct PlaylistFileEntry {
+    pub location: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+impl From<rockbox_playlists::formats::Entry> for PlaylistFileEntry {
+    fn from(e: rockbox_playlists::formats::Entry) -> Self {
+        Self {
+            location: e.location,
+            title: e.title,
+            artist: e.artist,
+            album: e.album,
+            duration_ms: e.duration_ms,
+        }
+    }
+}
+
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct PlaylistImport {
+    pub playlist: SavedPlaylist,
+    pub matched: i32,
+    /// Entries that match no library track.
+    pub unmatched: Vec<PlaylistFileEntry>,
+}
+
+impl From<rockbox_playlists::transfer::ImportReport> for PlaylistImport {
+    fn from(r: rockbox_playlists::transfer::ImportReport) -> Self {
+        Self {
+            playlist: r.playlist.into(),
+            matched: r.matched as i32,
+            unmatched: r.unmatched.into_iter().map(Into::into).collect(),
+        }
+    }
+}
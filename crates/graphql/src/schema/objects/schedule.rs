@@ -0,0 +1,117 @@
+use async_graphql::*;
+use rockbox_scheduler::{
+    NewSchedule, Schedule as RsSchedule, ScheduleUpdate, SleepTimer as RsSleepTimer,
+};
+
+/// An alarm, a recurring playback schedule or the sleep timer.
+#[derive(Default, Clone, SimpleObject)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    /// `play_playlist`, `play_radio`, `resume` or `sleep`.
+    pub action: String,
+    /// Saved playlist or radio station id.
+    pub target: Option<String>,
+    /// Five-field cron expression, in the server's local time.
+    pub cron: Option<String>,
+    pub run_at: Option<i64>,
+    pub volume: Option<i32>,
+    pub ramp_secs: i64,
+    pub enabled: bool,
+    pub next_run: Option<i64>,
+    pub last_run: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<RsSchedule> for Schedule {
+    fn from(s: RsSchedule) -> Self {
+        Self {
+            id: s.id,
+            name: s.name,
+            action: s.action,
+            target: s.target,
+            cron: s.cron,
+            run_at: s.run_at,
+            volume: s.volume,
+            ramp_secs: s.ramp_secs,
+            enabled: s.enabled,
+            next_run: s.next_run,
+            last_run: s.last_run,
+            created_at: s.created_at,
+            updated_at: s.updated_at,
+        }
+    }
+}
+
+#[derive(Default, Clone, SimpleObject)]
+pub struct SleepTimer {
+    pub ends_at: i64,
+    pub remaining_secs: i64,
+    pub fade_secs: i64,
+}
+
+impl From<RsSleepTimer> for SleepTimer {
+    fn from(t: RsSleepTimer) -> Self {
+        Self {
+            ends_at: t.ends_at,
+            remaining_secs: t.remaining_secs,
+            fade_secs: t.fade_secs,
+        }
+    }
+}
+
+/// Exactly one of `cron` and `runAt` is required.
+#[derive(Default, InputObject)]
+pub struct NewScheduleInput {
+    pub name: Option<String>,
+    pub action: String,
+    pub target: Option<String>,
+    pub cron: Option<String>,
+    pub run_at: Option<i64>,
+    pub volume: Option<i32>,
+    pub ramp_secs: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+impl From<NewScheduleInput> for NewSchedule {
+    fn from(input: NewScheduleInput) -> Self {
+        Self {
+            name: input.name.unwrap_or_default(),
+            action: input.action,
+            target: input.target,
+            cron: input.cron,
+            run_at: input.run_at,
+            volume: input.volume,
+            ramp_secs: input.ramp_secs,
+            enabled: input.enabled,
+        }
+    }
+}
+
+#[derive(Default, InputObject)]
+pub struct ScheduleUpdateInput {
+    pub name: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub cron: Option<String>,
+    pub run_at: Option<i64>,
+    pub volume: Option<i32>,
+    pub ramp_secs: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+impl From<ScheduleUpdateInput> for ScheduleUpdate {
+    fn from(input: ScheduleUpdateInput) -> Self {
+        Self {
+            name: input.name,
+            action: input.action,
+            target: input.target,
+            cron: input.cron,
+            run_at: input.run_at,
+            volume: input.volume,
+            ramp_secs: input.ramp_secs,
+            enabled: input.enabled,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use async_graphql::*;
-use rockbox_playlists::{SmartPlaylist as RsSmartPlaylist, TrackStats as RsTrackStats};
+use rockbox_playlists::{
+    SmartPlaylist as RsSmartPlaylist, SmartPlaylistDelta, TrackStats as RsTrackStats,
+};
 use serde::Serialize;
 
 #[derive(Default, Clone, Serialize, SimpleObject)]
@@ -26,6 +28,14 @@ pub struct TrackStats {
     pub updated_at: i64,
 }
 
+/// Tracks that joined or left a smart playlist.
+#[derive(Default, Clone, Serialize, SimpleObject)]
+pub struct SmartPlaylistChange {
+    pub playlist_id: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
 impl From<RsSmartPlaylist> for SmartPlaylist {
     fn from(p: RsSmartPlaylist) -> Self {
         Self {
@@ -55,3 +65,13 @@ impl From<RsTrackStats> for TrackStats {
         }
     }
 }
+
+impl From<SmartPlaylistDelta> for SmartPlaylistChange {
+    fn from(d: SmartPlaylistDelta) -> Self {
+        Self {
+            playlist_id: d.playlist_id,
+            added: d.added,
+            removed: d.removed,
+        }
+    }
+}
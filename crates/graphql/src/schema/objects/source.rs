@@ -0,0 +1,112 @@
+use async_graphql::*;
+use rockbox_sources::{
+    NewSource, RemoteSource as RsRemoteSource, SourceUpdate, SyncSummary as RsSyncSummary,
+};
+
+/// A remote server whose catalogue is mirrored into the library.
+#[derive(Default, Clone, SimpleObject)]
+pub struct RemoteSource {
+    pub id: String,
+    /// `subsonic`, `jellyfin`, `plex`, `kodi` or `upnp`.
+    pub kind: String,
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    pub enabled: bool,
+    pub prefer_local: bool,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub last_synced: Option<i64>,
+    pub track_count: i64,
+    pub conflict_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<RsRemoteSource> for RemoteSource {
+    fn from(s: RsRemoteSource) -> Self {
+        Self {
+            id: s.id,
+            kind: s.kind,
+            name: s.name,
+            url: s.url,
+            username: s.username,
+            enabled: s.enabled,
+            prefer_local: s.prefer_local,
+            status: s.status,
+            error_message: s.error_message,
+            last_synced: s.last_synced,
+            track_count: s.track_count,
+            conflict_count: s.conflict_count,
+            created_at: s.created_at,
+            updated_at: s.updated_at,
+        }
+    }
+}
+
+#[derive(Default, Clone, SimpleObject)]
+pub struct SyncSummary {
+    pub added: i32,
+    pub updated: i32,
+    pub removed: i32,
+    pub conflicts: i32,
+}
+
+impl From<RsSyncSummary> for SyncSummary {
+    fn from(s: RsSyncSummary) -> Self {
+        Self {
+            added: s.added as i32,
+            updated: s.updated as i32,
+            removed: s.removed as i32,
+            conflicts: s.conflicts as i32,
+        }
+    }
+}
+
+#[derive(Default, InputObject)]
+pub struct NewSourceInput {
+    /// `subsonic`, `jellyfin`, `plex`, `kodi` or `upnp`.
+    pub kind: String,
+    pub url: String,
+    pub name: Option<String>,
+    pub username: Option<String>,
+    /// Password, API key or token, depending on the kind.
+    pub password: Option<String>,
+    pub prefer_local: Option<bool>,
+}
+
+impl From<NewSourceInput> for NewSource {
+    fn from(input: NewSourceInput) -> Self {
+        Self {
+            kind: input.kind,
+            name: input.name.unwrap_or_default(),
+            url: input.url,
+            username: input.username,
+            password: input.password,
+            prefer_local: input.prefer_local,
+        }
+    }
+}
+
+#[derive(Default, InputObject)]
+pub struct SourceUpdateInput {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub enabled: Option<bool>,
+    pub prefer_local: Option<bool>,
+}
+
+impl From<SourceUpdateInput> for SourceUpdate {
+    fn from(input: SourceUpdateInput) -> Self {
+        Self {
+            name: input.name,
+            url: input.url,
+            username: input.username,
+            password: input.password,
+            enabled: input.enabled,
+            prefer_local: input.prefer_local,
+        }
+    }
+}
@@ -3,14 +3,20 @@ use std::fs;
 use crate::{check_and_load_player, read_files, schema::objects, AUDIO_EXTENSIONS};
 use async_graphql::*;
 use futures_util::Stream;
+use rockbox_autoqueue::AutoQueueUpdate;
 use rockbox_library::repo;
+use rockbox_similarity::SimilarityStore;
 use rockbox_sys::types::{
     audio_status::AudioStatus, file_position::FilePosition, mp3_entry::Mp3Entry,
 };
 use rockbox_types::device::Device;
 use sqlx::{Pool, Sqlite};
 
-use crate::{rockbox_url, schema::objects::track::Track, simplebroker::SimpleBroker};
+use crate::{
+    rockbox_url,
+    schema::objects::{auto_queue::AutoQueue, track::Track},
+    simplebroker::SimpleBroker,
+};
 
 #[derive(Default)]
 pub struct PlaybackQuery;
@@ -18,7 +24,7 @@ pub struct PlaybackQuery;
 #[Object]
 impl PlaybackQuery {
     async fn status(&self) -> Result<i32, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/player/status", rockbox_url());
         let response = client.get(&url).send().await?;
         let response = response.json::<AudioStatus>().await?;
@@ -72,6 +78,10 @@ impl PlaybackQuery {
         let response = response.json::<FilePosition>().await?;
         Ok(response.position)
     }
+
+    async fn auto_queue(&self) -> Result<AutoQueue, Error> {
+        Ok(rockbox_autoqueue::config().into())
+    }
 }
 
 #[derive(Default)]
@@ -248,6 +258,59 @@ impl PlaybackMutation {
         Ok(0)
     }
 
+    /// Keep the queue going when it is about to run out. Unset arguments
+    /// keep their value; an empty id clears it.
+    async fn set_auto_queue(
+        &self,
+        enabled: Option<bool>,
+        source: Option<String>,
+        smart_playlist_id: Option<String>,
+        seed_track_id: Option<String>,
+    ) -> Result<AutoQueue, Error> {
+        let source = source
+            .map(|s| s.parse::<rockbox_autoqueue::Source>())
+            .transpose()?;
+        let config = rockbox_autoqueue::update(AutoQueueUpdate {
+            enabled,
+            source,
+            smart_playlist_id,
+            seed_track_id,
+        })?;
+        Ok(config.into())
+    }
+
+    /// Start a radio from `track_id`: the track, then the ones that sound
+    /// most like it.
+    async fn play_track_radio(
+        &self,
+        ctx: &Context<'_>,
+        track_id: String,
+        limit: Option<i32>,
+    ) -> Result<i32, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let client = ctx.data::<reqwest::Client>().unwrap();
+        let limit = limit
+            .map(|l| l.max(1) as usize)
+            .unwrap_or(rockbox_similarity::DEFAULT_LIMIT);
+        let tracks = SimilarityStore::new(pool.clone())
+            .radio(&track_id, limit)
+            .await?;
+        let tracks = tracks.into_iter().map(|t| t.path).collect::<Vec<String>>();
+        let body = serde_json::json!({
+            "tracks": tracks,
+        });
+
+        check_and_load_player!(client, tracks, false);
+
+        let url = format!("{}/playlists", rockbox_url());
+        client.post(&url).json(&body).send().await?;
+
+        let url = format!("{}/playlists/start", rockbox_url());
+        client.put(&url).send().await?;
+
+        Ok(0)
+    }
+
     async fn play_playlist(
         &self,
         _ctx: &Context<'_>,
@@ -334,12 +397,12 @@ impl PlaybackMutation {
 
         check_and_load_player!(client, vec![path], false);
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
 
         let url = format!("{}/playlists", rockbox_url());
         client.post(&url).json(&body).send().await?;
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/playlists/start", rockbox_url());
         client.put(&url).send().await?;
 
@@ -435,4 +498,8 @@ impl PlaybackSubscription {
     async fn playback_status(&self) -> impl Stream<Item = objects::audio_status::AudioStatus> {
         SimpleBroker::<objects::audio_status::AudioStatus>::subscribe()
     }
+
+    async fn current_lyric_line(&self) -> impl Stream<Item = objects::lyrics::CurrentLyricLine> {
+        SimpleBroker::<objects::lyrics::CurrentLyricLine>::subscribe()
+    }
 }
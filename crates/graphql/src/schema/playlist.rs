@@ -17,7 +17,7 @@ pub struct PlaylistQuery;
 #[Object]
 impl PlaylistQuery {
     async fn playlist_get_current(&self, _ctx: &Context<'_>) -> Result<Playlist, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/playlists/current", rockbox_url());
         let response = client.get(&url).send().await?;
         let response = response.json::<PlaylistInfo>().await?;
@@ -50,7 +50,7 @@ impl PlaylistQuery {
     }
 
     async fn playlist_amount(&self, _ctx: &Context<'_>) -> Result<i32, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/playlists/amount", rockbox_url());
         let response = client.get(&url).send().await?;
         let response = response.json::<PlaylistAmount>().await?;
@@ -64,7 +64,7 @@ pub struct PlaylistMutation;
 #[Object]
 impl PlaylistMutation {
     async fn playlist_resume(&self, _ctx: &Context<'_>) -> Result<i32, Error> {
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/playlists/resume", rockbox_url());
         let response = client.put(&url).send().await?;
         let response = response.json::<StatusCode>().await?;
@@ -246,10 +246,25 @@ impl PlaylistMutation {
         Ok(start_index)
     }
 
-    async fn shuffle_playlist(&self, ctx: &Context<'_>) -> Result<i32, Error> {
+    async fn shuffle_playlist(
+        &self,
+        ctx: &Context<'_>,
+        mode: Option<String>,
+        seed: Option<u64>,
+    ) -> Result<i32, Error> {
         let client = ctx.data::<reqwest::Client>().unwrap();
         let url = format!("{}/playlists/shuffle", rockbox_url());
-        let response = client.put(&url).send().await?;
+        let mut query = Vec::new();
+        if let Some(mode) = mode {
+            query.push(("mode", mode));
+        }
+        if let Some(seed) = seed {
+            query.push(("seed", seed.to_string()));
+        }
+        let response = client.put(&url).query(&query).send().await?;
+        if response.status() == 400 {
+            return Err(Error::new(response.text().await?));
+        }
         let ret = response.text().await?.parse()?;
         Ok(ret)
     }
@@ -0,0 +1,147 @@
+use async_graphql::*;
+use rockbox_podcasts::PodcastStore;
+
+use crate::{
+    rockbox_url,
+    schema::objects::podcast::{PodcastChannel, PodcastEpisode},
+};
+
+#[derive(Default)]
+pub struct PodcastQuery;
+
+#[Object]
+impl PodcastQuery {
+    async fn podcasts(&self, ctx: &Context<'_>) -> Result<Vec<PodcastChannel>, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        let channels = store.list_channels().await?;
+        Ok(channels.into_iter().map(PodcastChannel::from).collect())
+    }
+
+    async fn podcast(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<Option<PodcastChannel>, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.get_channel(&id).await?.map(PodcastChannel::from))
+    }
+
+    async fn podcast_episodes(
+        &self,
+        ctx: &Context<'_>,
+        channel_id: String,
+    ) -> Result<Vec<PodcastEpisode>, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        let episodes = store.list_episodes(&channel_id).await?;
+        Ok(episodes.into_iter().map(PodcastEpisode::from).collect())
+    }
+
+    async fn podcast_episode(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<Option<PodcastEpisode>, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.get_episode(&id).await?.map(PodcastEpisode::from))
+    }
+
+    async fn newest_podcast_episodes(
+        &self,
+        ctx: &Context<'_>,
+        count: Option<i64>,
+    ) -> Result<Vec<PodcastEpisode>, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        let episodes = store.newest_episodes(count.unwrap_or(20)).await?;
+        Ok(episodes.into_iter().map(PodcastEpisode::from).collect())
+    }
+}
+
+#[derive(Default)]
+pub struct PodcastMutation;
+
+#[Object]
+impl PodcastMutation {
+    async fn subscribe_podcast(
+        &self,
+        ctx: &Context<'_>,
+        url: String,
+        auto_download: Option<bool>,
+    ) -> Result<PodcastChannel, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        let channel = store
+            .subscribe(&url, auto_download.unwrap_or(false))
+            .await?;
+        Ok(channel.into())
+    }
+
+    async fn unsubscribe_podcast(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.delete_channel(&id).await?)
+    }
+
+    async fn set_podcast_auto_download(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        auto_download: bool,
+    ) -> Result<bool, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.set_auto_download(&id, auto_download).await?)
+    }
+
+    /// Refresh one channel, or every channel when `id` is omitted. Returns
+    /// the number of new episodes.
+    async fn refresh_podcasts(&self, ctx: &Context<'_>, id: Option<String>) -> Result<i32, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        let added = match id {
+            Some(id) => store.refresh_channel(&id).await?.len(),
+            None => store.refresh_all().await?,
+        };
+        Ok(added as i32)
+    }
+
+    async fn download_podcast_episode(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<PodcastEpisode, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.download_episode(&id).await?.into())
+    }
+
+    async fn delete_podcast_episode_download(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<bool, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.delete_download(&id).await?)
+    }
+
+    async fn set_podcast_episode_position(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        position_ms: i64,
+    ) -> Result<bool, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.set_position(&id, position_ms).await?)
+    }
+
+    async fn set_podcast_episode_played(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        played: bool,
+    ) -> Result<bool, Error> {
+        let store = ctx.data::<PodcastStore>()?;
+        Ok(store.set_played(&id, played).await?)
+    }
+
+    async fn play_podcast_episode(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let client = ctx.data::<reqwest::Client>()?;
+        let url = format!("{}/podcast-episodes/{}/play", rockbox_url(), id);
+        client.put(&url).send().await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,127 @@
+use async_graphql::*;
+use rockbox_library::{
+    radio::{self, StationInput},
+    repo,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{rockbox_url, schema::objects::radio_station::RadioStation};
+
+#[derive(Default)]
+pub struct RadioQuery;
+
+#[Object]
+impl RadioQuery {
+    async fn radio_stations(&self, ctx: &Context<'_>) -> Result<Vec<RadioStation>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let stations = repo::radio_station::all(pool.clone()).await?;
+        Ok(stations.into_iter().map(RadioStation::from).collect())
+    }
+
+    async fn radio_station(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<Option<RadioStation>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let station = repo::radio_station::find(pool.clone(), &id).await?;
+        Ok(station.map(RadioStation::from))
+    }
+}
+
+#[derive(Default)]
+pub struct RadioMutation;
+
+#[Object]
+impl RadioMutation {
+    async fn create_radio_station(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        stream_url: String,
+        homepage_url: Option<String>,
+        genre: Option<String>,
+        country: Option<String>,
+        codec: Option<String>,
+        bitrate: Option<u32>,
+        favicon: Option<String>,
+    ) -> Result<RadioStation, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let input = StationInput {
+            name,
+            stream_url,
+            homepage_url,
+            genre,
+            country,
+            codec,
+            bitrate,
+            favicon,
+        };
+        if !input.is_valid() {
+            return Err(Error::new(
+                "a station needs a name and an http(s) stream URL",
+            ));
+        }
+        radio::create_station(pool.clone(), input)
+            .await?
+            .map(RadioStation::from)
+            .ok_or_else(|| Error::new("station was not saved"))
+    }
+
+    async fn update_radio_station(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        name: String,
+        stream_url: String,
+        homepage_url: Option<String>,
+        genre: Option<String>,
+        country: Option<String>,
+        codec: Option<String>,
+        bitrate: Option<u32>,
+        favicon: Option<String>,
+    ) -> Result<Option<RadioStation>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let input = StationInput {
+            name,
+            stream_url,
+            homepage_url,
+            genre,
+            country,
+            codec,
+            bitrate,
+            favicon,
+        };
+        if !input.is_valid() {
+            return Err(Error::new(
+                "a station needs a name and an http(s) stream URL",
+            ));
+        }
+        let station = radio::update_station(pool.clone(), &id, input).await?;
+        Ok(station.map(RadioStation::from))
+    }
+
+    async fn delete_radio_station(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(repo::radio_station::delete(pool.clone(), &id).await?)
+    }
+
+    /// Import every station from an M3U or PLS station list.
+    async fn import_radio_stations(
+        &self,
+        ctx: &Context<'_>,
+        content: String,
+        genre: Option<String>,
+    ) -> Result<Vec<RadioStation>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let stations = radio::import_stations(pool.clone(), &content, genre.as_deref()).await?;
+        Ok(stations.into_iter().map(RadioStation::from).collect())
+    }
+
+    async fn play_radio_station(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let client = ctx.data::<reqwest::Client>()?;
+        let url = format!("{}/radio/stations/{}/play", rockbox_url(), id);
+        client.put(&url).send().await?;
+        Ok(true)
+    }
+}
@@ -1,12 +1,15 @@
 use async_graphql::*;
 use rockbox_library::repo;
-use rockbox_playlists::PlaylistStore;
+use rockbox_playlists::{
+    formats::{self, Format},
+    PlaylistStore,
+};
 use sqlx::{Pool, Sqlite};
 
 use crate::{
     rockbox_url,
     schema::objects::{
-        saved_playlist::{SavedPlaylist, SavedPlaylistFolder},
+        saved_playlist::{PlaylistImport, SavedPlaylist, SavedPlaylistFolder},
         track::Track,
     },
 };
@@ -71,6 +74,21 @@ impl SavedPlaylistQuery {
         let folders = store.list_folders().await?;
         Ok(folders.into_iter().map(SavedPlaylistFolder::from).collect())
     }
+
+    /// A saved playlist as an m3u (default), pls, xspf or jspf file.
+    async fn export_saved_playlist(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        format: Option<String>,
+    ) -> Result<Option<String>, Error> {
+        let store = ctx.data::<PlaylistStore>()?;
+        let format = match format {
+            Some(format) => format.parse::<Format>()?,
+            None => Format::M3u,
+        };
+        Ok(store.export_playlist(&id, format, None).await?)
+    }
 }
 
 #[derive(Default)]
@@ -120,6 +138,28 @@ impl SavedPlaylistMutation {
         Ok(SavedPlaylist::from(updated))
     }
 
+    /// Create a saved playlist from the content of an M3U, PLS, XSPF or
+    /// JSPF file; the format is guessed when not given.
+    async fn import_saved_playlist(
+        &self,
+        ctx: &Context<'_>,
+        content: String,
+        format: Option<String>,
+        name: Option<String>,
+        folder_id: Option<String>,
+    ) -> Result<PlaylistImport, Error> {
+        let store = ctx.data::<PlaylistStore>()?;
+        let format = match format {
+            Some(format) => format.parse::<Format>()?,
+            None => Format::sniff(&content),
+        };
+        let file = formats::parse(format, &content)?;
+        let report = store
+            .import_playlist(file, name.as_deref(), folder_id.as_deref(), None)
+            .await?;
+        Ok(report.into())
+    }
+
     async fn update_saved_playlist(
         &self,
         ctx: &Context<'_>,
@@ -0,0 +1,101 @@
+use async_graphql::*;
+use rockbox_scheduler::SchedulerStore;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    rockbox_url,
+    schema::objects::schedule::{NewScheduleInput, Schedule, ScheduleUpdateInput, SleepTimer},
+};
+
+#[derive(Default)]
+pub struct ScheduleQuery;
+
+#[Object]
+impl ScheduleQuery {
+    async fn schedules(&self, ctx: &Context<'_>) -> Result<Vec<Schedule>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let schedules = SchedulerStore::new(pool.clone()).list().await?;
+        Ok(schedules.into_iter().map(Schedule::from).collect())
+    }
+
+    async fn schedule(&self, ctx: &Context<'_>, id: String) -> Result<Option<Schedule>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(SchedulerStore::new(pool.clone())
+            .get(&id)
+            .await?
+            .map(Schedule::from))
+    }
+
+    async fn sleep_timer(&self, ctx: &Context<'_>) -> Result<Option<SleepTimer>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(SchedulerStore::new(pool.clone())
+            .sleep_timer()
+            .await?
+            .map(SleepTimer::from))
+    }
+}
+
+#[derive(Default)]
+pub struct ScheduleMutation;
+
+#[Object]
+impl ScheduleMutation {
+    async fn create_schedule(
+        &self,
+        ctx: &Context<'_>,
+        input: NewScheduleInput,
+    ) -> Result<Schedule, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let schedule = SchedulerStore::new(pool.clone())
+            .create(input.into())
+            .await?;
+        Ok(schedule.into())
+    }
+
+    async fn update_schedule(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        input: ScheduleUpdateInput,
+    ) -> Result<Option<Schedule>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let schedule = SchedulerStore::new(pool.clone())
+            .update(&id, input.into())
+            .await?;
+        Ok(schedule.map(Schedule::from))
+    }
+
+    async fn delete_schedule(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(SchedulerStore::new(pool.clone()).delete(&id).await?)
+    }
+
+    /// Run a schedule's action now, whether or not it is due or enabled.
+    async fn run_schedule(&self, _ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let client = crate::internal_client();
+        let url = format!("{}/schedules/{}/run", rockbox_url(), id);
+        let response = client.post(&url).send().await?;
+        Ok(response.status().is_success())
+    }
+
+    /// Pause playback in `minutes`, fading out over the last `fadeSecs`.
+    async fn set_sleep_timer(
+        &self,
+        ctx: &Context<'_>,
+        minutes: u32,
+        fade_secs: Option<i64>,
+    ) -> Result<SleepTimer, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let timer = SchedulerStore::new(pool.clone())
+            .set_sleep_timer(minutes, fade_secs.unwrap_or_default())
+            .await?;
+        Ok(timer.into())
+    }
+
+    async fn cancel_sleep_timer(&self, ctx: &Context<'_>) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(SchedulerStore::new(pool.clone())
+            .cancel_sleep_timer()
+            .await?)
+    }
+}
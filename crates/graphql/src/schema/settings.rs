@@ -1,6 +1,7 @@
 use async_graphql::*;
 
-use crate::{rockbox_url, schema::objects::user_settings::UserSettings};
+use crate::{auth::ScopeGuard, rockbox_url, schema::objects::user_settings::UserSettings};
+use rockbox_auth::Scope;
 use rockbox_sys as rb;
 
 use super::objects::new_global_settings::NewGlobalSettings;
@@ -35,6 +36,7 @@ pub struct SettingsMutation;
 
 #[Object]
 impl SettingsMutation {
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
     async fn save_settings(
         &self,
         ctx: &Context<'_>,
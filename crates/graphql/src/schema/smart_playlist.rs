@@ -1,13 +1,15 @@
 use async_graphql::*;
+use futures_util::{future, Stream, StreamExt};
 use rockbox_playlists::{resolver, PlaylistStore};
 use sqlx::{Pool, Sqlite};
 
 use crate::{
     rockbox_url,
     schema::objects::{
-        smart_playlist::{SmartPlaylist, TrackStats},
+        smart_playlist::{SmartPlaylist, SmartPlaylistChange, TrackStats},
         track::Track,
     },
+    simplebroker::SimpleBroker,
 };
 
 /// Resolve the tracks for a smart playlist directly against the SQLite DB,
@@ -21,7 +23,7 @@ async fn resolve_smart_playlist_tracks(
         Some(p) => p.rules,
         None => return Ok(vec![]),
     };
-    let tracks = resolver::resolve_tracks(store, pool, &criteria).await?;
+    let tracks = resolver::resolve_tracks(pool, &criteria).await?;
     Ok(tracks.into_iter().map(Track::from).collect())
 }
 
@@ -59,7 +61,7 @@ impl SmartPlaylistQuery {
         let playlist = store.get_smart_playlist(&id).await?;
         let result = match playlist {
             Some(p) => {
-                let track_count = resolver::count_tracks(store, pool, &p.rules).await?;
+                let track_count = resolver::count_tracks(pool, &p.rules).await?;
                 let mut sp: SmartPlaylist = p.into();
                 sp.track_count = track_count;
                 Some(sp)
@@ -129,7 +131,7 @@ impl SmartPlaylistMutation {
                 &criteria,
             )
             .await?;
-        let track_count = resolver::count_tracks(store, pool, &playlist.rules).await?;
+        let track_count = resolver::count_tracks(pool, &playlist.rules).await?;
         let mut sp: SmartPlaylist = playlist.into();
         sp.track_count = track_count;
         Ok(sp)
@@ -195,3 +197,19 @@ impl SmartPlaylistMutation {
         Ok(true)
     }
 }
+
+#[derive(Default)]
+pub struct SmartPlaylistSubscription;
+
+#[Subscription]
+impl SmartPlaylistSubscription {
+    /// Tracks joining or leaving smart playlists, or only the one with `id`.
+    async fn smart_playlist_changed(
+        &self,
+        id: Option<String>,
+    ) -> impl Stream<Item = SmartPlaylistChange> {
+        SimpleBroker::<SmartPlaylistChange>::subscribe().filter(move |change| {
+            future::ready(id.as_ref().is_none_or(|id| *id == change.playlist_id))
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use async_graphql::*;
+use rockbox_auth::Scope;
+use rockbox_sources::SourceStore;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    auth::ScopeGuard,
+    schema::objects::source::{NewSourceInput, RemoteSource, SourceUpdateInput, SyncSummary},
+};
+
+#[derive(Default)]
+pub struct SourceQuery;
+
+#[Object]
+impl SourceQuery {
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn sources(&self, ctx: &Context<'_>) -> Result<Vec<RemoteSource>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let sources = SourceStore::new(pool.clone()).list().await?;
+        Ok(sources.into_iter().map(RemoteSource::from).collect())
+    }
+
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn source(&self, ctx: &Context<'_>, id: String) -> Result<Option<RemoteSource>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(SourceStore::new(pool.clone())
+            .get(&id)
+            .await?
+            .map(RemoteSource::from))
+    }
+}
+
+#[derive(Default)]
+pub struct SourceMutation;
+
+#[Object]
+impl SourceMutation {
+    /// Add a remote server. Nothing is synced until `syncSource` runs or
+    /// the periodic sync comes round.
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn add_source(
+        &self,
+        ctx: &Context<'_>,
+        input: NewSourceInput,
+    ) -> Result<RemoteSource, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let source = SourceStore::new(pool.clone()).create(input.into()).await?;
+        Ok(source.into())
+    }
+
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn update_source(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        input: SourceUpdateInput,
+    ) -> Result<Option<RemoteSource>, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        let source = SourceStore::new(pool.clone())
+            .update(&id, input.into())
+            .await?;
+        Ok(source.map(RemoteSource::from))
+    }
+
+    /// Remove a source along with every track synced from it.
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn remove_source(&self, ctx: &Context<'_>, id: String) -> Result<bool, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(SourceStore::new(pool.clone()).delete(&id).await?)
+    }
+
+    /// Sync a source now and wait for it to finish.
+    #[graphql(guard = "ScopeGuard(Scope::Admin)")]
+    async fn sync_source(&self, ctx: &Context<'_>, id: String) -> Result<SyncSummary, Error> {
+        let pool = ctx.data::<Pool<Sqlite>>()?;
+        Ok(SourceStore::new(pool.clone()).sync(&id).await?.into())
+    }
+}
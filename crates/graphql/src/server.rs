@@ -3,36 +3,71 @@ use std::path::PathBuf;
 use actix_cors::Cors;
 use actix_files::{self as fs, NamedFile};
 use actix_web::{
-    error::ErrorNotFound,
+    error::{ErrorForbidden, ErrorInternalServerError, ErrorNotFound, ErrorUnauthorized},
     guard,
-    http::header::{ContentDisposition, DispositionType, HOST},
+    http::header::{ContentDisposition, DispositionType, AUTHORIZATION, HOST},
     web::{self, Data},
     App, HttpRequest, HttpResponse, HttpServer, Result,
 };
 use anyhow::Error;
 use async_graphql::{http::GraphiQLSource, Schema};
 use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+use rockbox_auth::{request_token, Auth, AuthConfig, Denied, Scope};
 use rockbox_library::{create_connection_pool, repo};
 use rockbox_playlists::PlaylistStore;
+use rockbox_podcasts::PodcastStore;
 use rockbox_webui::{dist, index, index_spa};
 use sqlx::{Pool, Sqlite};
 
 use crate::{
+    auth::operation_scope,
     schema::{Mutation, Query, Subscription},
     RockboxSchema,
 };
 
+/// Scope of the caller behind `req`, or the HTTP error refusing it.
+async fn authorize(req: &HttpRequest, required: Scope) -> Result<Scope> {
+    let auth = req.app_data::<Data<Auth>>().unwrap();
+    let peer = req.peer_addr().map(|addr| addr.ip());
+    let token = request_token(
+        req.headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok()),
+        req.query_string(),
+    );
+    auth.authorize(peer, token.as_deref(), required)
+        .await
+        .map_err(|denied| match denied {
+            Denied::Unauthenticated => ErrorUnauthorized(denied),
+            Denied::Forbidden { .. } => ErrorForbidden(denied),
+            Denied::Internal(_) => ErrorInternalServerError(denied),
+        })
+}
+
 async fn index_ws(
     schema: web::Data<RockboxSchema>,
     req: HttpRequest,
     payload: web::Payload,
 ) -> Result<HttpResponse> {
-    GraphQLSubscription::new(Schema::clone(&*schema)).start(&req, payload)
+    // Subscriptions only observe, and browsers cannot set headers on a
+    // WebSocket, so `?access_token=` with a read token is enough.
+    let scope = authorize(&req, Scope::Read).await?;
+    let mut data = async_graphql::Data::default();
+    data.insert(scope);
+    GraphQLSubscription::new(Schema::clone(&*schema))
+        .with_data(data)
+        .start(&req, payload)
 }
 
 #[actix_web::post("/graphql")]
-async fn index_graphql(schema: web::Data<RockboxSchema>, req: GraphQLRequest) -> GraphQLResponse {
-    schema.execute(req.into_inner()).await.into()
+async fn index_graphql(
+    schema: web::Data<RockboxSchema>,
+    http_req: HttpRequest,
+    req: GraphQLRequest,
+) -> Result<GraphQLResponse> {
+    let req = req.into_inner();
+    let scope = authorize(&http_req, operation_scope(&req.query)).await?;
+    Ok(schema.execute(req.data(scope)).await.into())
 }
 
 #[actix_web::get("/graphiql")]
@@ -48,8 +83,21 @@ async fn index_graphiql(req: HttpRequest) -> Result<HttpResponse> {
         .unwrap();
 
     let http_port = std::env::var("ROCKBOX_GRAPHQL_PORT").unwrap_or("6062".to_string());
-    let graphql_endpoint = format!("http://{}:{}/graphql", host, http_port);
-    let ws_endpoint = format!("ws://{}:{}/graphql", host, http_port);
+    // Served from the TLS listener: point GraphiQL at it too.
+    let (graphql_endpoint, ws_endpoint) = match rockbox_tls::tls() {
+        Some(tls) if req.app_config().secure() => {
+            let port = http_port.parse::<u16>().map_err(ErrorInternalServerError)?;
+            let port = tls.port(port).map_err(ErrorInternalServerError)?;
+            (
+                format!("https://{}:{}/graphql", host, port),
+                format!("wss://{}:{}/graphql", host, port),
+            )
+        }
+        _ => (
+            format!("http://{}:{}/graphql", host, http_port),
+            format!("ws://{}:{}/graphql", host, http_port),
+        ),
+    };
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(
@@ -85,9 +133,15 @@ async fn index_file(req: HttpRequest) -> Result<NamedFile, actix_web::Error> {
 }
 
 pub async fn start() -> Result<(), Error> {
-    let client = reqwest::Client::new();
+    let client = crate::internal_client();
     let pool = create_connection_pool().await?;
     let playlist_store = PlaylistStore::new(pool.clone());
+    let podcast_store = PodcastStore::new(pool.clone());
+    let settings = rockbox_settings::read_settings().unwrap_or_default();
+    let auth = Data::new(Auth::new(
+        pool.clone(),
+        AuthConfig::new(settings.auth_enabled, settings.auth_trust_localhost),
+    ));
 
     let schema = Schema::build(
         Query::default(),
@@ -97,12 +151,13 @@ pub async fn start() -> Result<(), Error> {
     .data(client)
     .data(pool.clone())
     .data(playlist_store)
+    .data(podcast_store)
     .finish();
 
     let graphql_port = std::env::var("ROCKBOX_GRAPHQL_PORT").unwrap_or("6062".to_string());
     let addr = format!("{}:{}", "0.0.0.0", graphql_port);
 
-    HttpServer::new(move || {
+    let mut server = HttpServer::new(move || {
         let home = std::env::var("HOME").unwrap();
         let rockbox_data_dir = format!("{}/.config/rockbox.org", home);
         let covers_path = format!("{}/covers", rockbox_data_dir);
@@ -113,6 +168,7 @@ pub async fn start() -> Result<(), Error> {
         App::new()
             .app_data(pool.clone())
             .app_data(Data::new(schema.clone()))
+            .app_data(auth.clone())
             .wrap(cors)
             .service(index_graphql)
             .service(index_graphiql)
@@ -138,8 +194,9 @@ pub async fn start() -> Result<(), Error> {
             .route("/tracks/{id}", web::head().to(index_file))
             .service(dist)
     })
-    .bind(addr)?
-    .run()
-    .await
-    .map_err(Error::new)
+    .bind(rockbox_tls::plain_addr(&addr))?;
+    if let Some(tls) = rockbox_tls::tls() {
+        server = server.bind_rustls_0_23(tls.addr(&addr)?, tls.server_config())?;
+    }
+    server.run().await.map_err(Error::new)
 }
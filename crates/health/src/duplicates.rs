@@ -0,0 +1,274 @@
+//! Grouping of tracks that are the same recording: by tags, or by how they
+//! sound when the tags disagree.
+
+use std::{cmp::Reverse, collections::HashMap, path::Path};
+
+use rockbox_library::entity::track::Track;
+use rockbox_similarity::{analysis, DIMENSIONS};
+
+/// Copies of one recording may differ this much in length (ms): encoders
+/// pad and trim a few frames, rips differ in pregap handling.
+pub const DURATION_TOLERANCE_MS: u32 = 2000;
+
+/// Feature vectors closer than this are taken to be the same audio. Two
+/// encodes of one master land well below it; different recordings of a
+/// song, even by the same band, well above.
+pub const FINGERPRINT_DISTANCE: f32 = 0.05;
+
+/// Extensions of lossless formats, preferred over any lossy copy.
+const LOSSLESS: &[&str] = &["flac", "wav", "aif", "aiff", "ape", "wv", "dsf", "dff"];
+
+/// Lower-cased letters and digits of `s`, single-spaced, with bracketed
+/// parts such as "(Remastered 2011)" and a leading "the" dropped.
+pub fn normalize(s: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    for c in s.chars().flat_map(char::to_lowercase) {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = (depth - 1).max(0),
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() => out.push(c),
+            c if c.is_whitespace() && !out.is_empty() && !out.ends_with(' ') => out.push(' '),
+            _ => {}
+        }
+    }
+    let out = out.trim_end();
+    out.strip_prefix("the ").unwrap_or(out).to_string()
+}
+
+fn is_lossless(track: &Track) -> bool {
+    Path::new(&track.path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| LOSSLESS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Sort copies of a recording best first: lossless, then by bitrate,
+/// sample rate and file size.
+pub fn rank(tracks: &mut [Track]) {
+    tracks.sort_by_key(|t| {
+        (
+            Reverse(is_lossless(t)),
+            Reverse(t.bitrate),
+            Reverse(t.frequency),
+            Reverse(t.filesize),
+            t.path.clone(),
+        )
+    });
+}
+
+/// Split tracks sorted by length wherever two neighbours are further apart
+/// than [`DURATION_TOLERANCE_MS`], keeping runs of two or more.
+fn split_by_length(mut tracks: Vec<Track>) -> Vec<Vec<Track>> {
+    tracks.sort_by_key(|t| t.length);
+    let mut groups: Vec<Vec<Track>> = Vec::new();
+    let mut run: Vec<Track> = Vec::new();
+    for track in tracks {
+        if run
+            .last()
+            .is_some_and(|last| track.length - last.length > DURATION_TOLERANCE_MS)
+        {
+            groups.push(std::mem::take(&mut run));
+        }
+        run.push(track);
+    }
+    groups.push(run);
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+/// Tracks with the same normalised artist and title and about the same
+/// length, each group ranked best first. Untagged tracks are left out.
+pub fn metadata_groups(tracks: &[Track]) -> Vec<Vec<Track>> {
+    let mut by_key: HashMap<(String, String), Vec<Track>> = HashMap::new();
+    for track in tracks {
+        let key = (normalize(&track.artist), normalize(&track.title));
+        if key.0.is_empty() || key.1.is_empty() {
+            continue;
+        }
+        by_key.entry(key).or_default().push(track.clone());
+    }
+    let mut groups: Vec<Vec<Track>> = by_key
+        .into_values()
+        .flat_map(split_by_length)
+        .map(|mut group| {
+            rank(&mut group);
+            group
+        })
+        .collect();
+    groups.sort_by(|a, b| a[0].path.cmp(&b[0].path));
+    groups
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Tracks of about the same length whose feature vectors are within
+/// [`FINGERPRINT_DISTANCE`] of each other, each group ranked best first.
+/// Groups that [`metadata_groups`] already reports whole are left out.
+pub fn fingerprint_groups(
+    tracks: &[Track],
+    vectors: &HashMap<String, [f32; DIMENSIONS]>,
+    known: &[Vec<Track>],
+) -> Vec<Vec<Track>> {
+    let mut analysed: Vec<(&Track, &[f32; DIMENSIONS])> = tracks
+        .iter()
+        .filter_map(|t| Some((t, vectors.get(&t.id)?)))
+        .collect();
+    analysed.sort_by_key(|(t, _)| t.length);
+
+    let mut parent: Vec<usize> = (0..analysed.len()).collect();
+    for (i, (track, vector)) in analysed.iter().enumerate() {
+        for (j, (other, other_vector)) in analysed.iter().enumerate().skip(i + 1) {
+            if other.length - track.length > DURATION_TOLERANCE_MS {
+                break;
+            }
+            if analysis::distance(*vector, *other_vector) < FINGERPRINT_DISTANCE {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut by_root: HashMap<usize, Vec<Track>> = HashMap::new();
+    for (i, (track, _)) in analysed.iter().enumerate() {
+        let root = find(&mut parent, i);
+        by_root.entry(root).or_default().push((*track).clone());
+    }
+    let known_group: HashMap<&str, usize> = known
+        .iter()
+        .enumerate()
+        .flat_map(|(n, group)| group.iter().map(move |t| (t.id.as_str(), n)))
+        .collect();
+    let mut groups: Vec<Vec<Track>> = by_root
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .filter(|group| {
+            let first = known_group.get(group[0].id.as_str());
+            first.is_none()
+                || group
+                    .iter()
+                    .any(|t| known_group.get(t.id.as_str()) != first)
+        })
+        .map(|mut group| {
+            rank(&mut group);
+            group
+        })
+        .collect();
+    groups.sort_by(|a, b| a[0].path.cmp(&b[0].path));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: &str, path: &str, artist: &str, title: &str, length: u32, bitrate: u32) -> Track {
+        Track {
+            id: id.to_string(),
+            path: path.to_string(),
+            artist: artist.to_string(),
+            title: title.to_string(),
+            length,
+            bitrate,
+            ..Default::default()
+        }
+    }
+
+    fn ids(groups: &[Vec<Track>]) -> Vec<Vec<&str>> {
+        groups
+            .iter()
+            .map(|g| g.iter().map(|t| t.id.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn normalize_ignores_case_punctuation_and_brackets() {
+        assert_eq!(normalize("The Beatles"), "beatles");
+        assert_eq!(normalize("Let It Be (Remastered 2009)"), "let it be");
+        assert_eq!(normalize("  Don't  Stop [Live]"), "dont stop");
+        assert_eq!(normalize("Björk"), "björk");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn lossless_copies_rank_first() {
+        let mut tracks = vec![
+            track("mp3", "/m/a.mp3", "A", "B", 1000, 320),
+            track("ogg", "/m/a.ogg", "A", "B", 1000, 192),
+            track("flac", "/m/a.flac", "A", "B", 1000, 900),
+        ];
+        rank(&mut tracks);
+        let ids: Vec<&str> = tracks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["flac", "mp3", "ogg"]);
+    }
+
+    #[test]
+    fn metadata_duplicates_need_matching_tags_and_length() {
+        let tracks = vec![
+            track("1", "/m/1.mp3", "The Beatles", "Let It Be", 243_000, 320),
+            track(
+                "2",
+                "/m/2.flac",
+                "Beatles",
+                "Let It Be (Remastered)",
+                244_500,
+                900,
+            ),
+            // Same song, but a much longer live take.
+            track("3", "/m/3.mp3", "The Beatles", "Let It Be", 290_000, 320),
+            track("4", "/m/4.mp3", "Beatles", "Help!", 140_000, 320),
+            track("5", "/m/5.mp3", "", "", 140_000, 320),
+            track("6", "/m/6.mp3", "", "", 140_000, 320),
+        ];
+        assert_eq!(ids(&metadata_groups(&tracks)), [["2", "1"]]);
+    }
+
+    #[test]
+    fn fingerprint_duplicates_catch_mistagged_copies() {
+        let tracks = vec![
+            track("1", "/m/1.mp3", "Miles Davis", "So What", 562_000, 320),
+            track("2", "/m/2.mp3", "Unknown", "Track 01", 562_400, 128),
+            track(
+                "3",
+                "/m/3.mp3",
+                "Miles Davis",
+                "Freddie Freeloader",
+                562_000,
+                320,
+            ),
+            track("4", "/m/4.mp3", "Miles Davis", "So What", 561_000, 256),
+        ];
+        let mut a = [0.0; DIMENSIONS];
+        a[4] = 3.0;
+        let mut b = a;
+        b[0] = 0.01;
+        let mut c = [0.0; DIMENSIONS];
+        c[9] = 3.0;
+        let vectors: HashMap<String, [f32; DIMENSIONS]> = [
+            ("1".to_string(), a),
+            ("2".to_string(), b),
+            ("3".to_string(), c),
+            ("4".to_string(), a),
+        ]
+        .into();
+
+        let known = metadata_groups(&tracks);
+        assert_eq!(ids(&known), [["1", "4"]]);
+        assert_eq!(
+            ids(&fingerprint_groups(&tracks, &vectors, &known)),
+            [["1", "4", "2"]]
+        );
+
+        // Nothing new when the only match is already reported by tags.
+        let vectors: HashMap<String, [f32; DIMENSIONS]> =
+            [("1".to_string(), a), ("4".to_string(), a)].into();
+        assert!(fingerprint_groups(&tracks, &vectors, &known).is_empty());
+    }
+}
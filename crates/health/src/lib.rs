@@ -0,0 +1,281 @@
+//! Library health.
+//!
+//! Tracks are keyed on the path, so a song ripped twice, or kept as both
+//! FLAC and MP3, is two tracks. A periodic pass finds such duplicates by
+//! their tags and by their acoustic features, along with tracks missing
+//! tags, albums missing cover art, files that are gone or do not decode,
+//! and album/artist rows no track points at. The last report is kept in
+//! memory; bulk actions hide the lesser copies or remove orphan rows.
+
+pub mod duplicates;
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{OnceLock, RwLock},
+    time::Duration,
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rockbox_library::{
+    changes,
+    entity::{album::Album, artist::Artist, track::Track},
+    repo,
+};
+use rockbox_similarity::SimilarityStore;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tracing::{error, info};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateReason {
+    /// Same normalised artist and title, about the same length.
+    Metadata,
+    /// Sounds the same, whatever the tags say.
+    Fingerprint,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub reason: DuplicateReason,
+    /// Best copy first; [`HealthStore::hide_duplicates`] hides the rest.
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TrackIssue {
+    pub track: Track,
+    /// What is wrong, e.g. "missing title" or "file not found".
+    pub problems: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub generated_at: DateTime<Utc>,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub missing_tags: Vec<TrackIssue>,
+    pub missing_cover_art: Vec<Album>,
+    pub broken_files: Vec<TrackIssue>,
+    pub orphan_albums: Vec<Album>,
+    pub orphan_artists: Vec<Artist>,
+    pub hidden_tracks: Vec<Track>,
+}
+
+/// The last report, shared by every [`HealthStore`].
+fn last_report() -> &'static RwLock<Option<HealthReport>> {
+    static LAST: OnceLock<RwLock<Option<HealthReport>>> = OnceLock::new();
+    LAST.get_or_init(|| RwLock::new(None))
+}
+
+fn tag_problems(track: &Track) -> Vec<String> {
+    [
+        ("title", &track.title),
+        ("artist", &track.artist),
+        ("album", &track.album),
+    ]
+    .into_iter()
+    .filter(|(_, value)| value.trim().is_empty())
+    .map(|(tag, _)| format!("missing {}", tag))
+    .collect()
+}
+
+#[derive(Clone)]
+pub struct HealthStore {
+    pool: Pool<Sqlite>,
+}
+
+impl HealthStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Every local track, hidden ones included.
+    async fn local_tracks(&self) -> Result<Vec<Track>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM track WHERE is_remote = 0 ORDER BY path ASC")
+                .fetch_all(&self.pool)
+                .await?,
+        )
+    }
+
+    /// Duplicate groups among the visible music tracks: first those the tags
+    /// give away, then those only the acoustic features do.
+    async fn duplicates(&self, tracks: &[Track]) -> Result<Vec<DuplicateGroup>> {
+        let music: Vec<Track> = tracks
+            .iter()
+            .filter(|t| !t.hidden && !t.is_book())
+            .cloned()
+            .collect();
+        let vectors: HashMap<_, _> = SimilarityStore::new(self.pool.clone())
+            .vectors()
+            .await?
+            .into_iter()
+            .collect();
+        let by_tags = duplicates::metadata_groups(&music);
+        let by_sound = duplicates::fingerprint_groups(&music, &vectors, &by_tags);
+        Ok(by_tags
+            .into_iter()
+            .map(|tracks| DuplicateGroup {
+                reason: DuplicateReason::Metadata,
+                tracks,
+            })
+            .chain(by_sound.into_iter().map(|tracks| DuplicateGroup {
+                reason: DuplicateReason::Fingerprint,
+                tracks,
+            }))
+            .collect())
+    }
+
+    /// Files that are gone, empty, or that the similarity analysis could
+    /// not make sense of and that fail to decode again now. Files it has not
+    /// looked at yet are only checked for existence.
+    async fn broken_files(&self, tracks: &[Track]) -> Result<Vec<TrackIssue>> {
+        let unanalysable: HashSet<String> = SimilarityStore::new(self.pool.clone())
+            .unanalysable()
+            .await?
+            .into_iter()
+            .collect();
+        let mut broken = Vec::new();
+        for track in tracks {
+            let problem = match std::fs::metadata(&track.path) {
+                Err(_) => Some("file not found".to_string()),
+                Ok(metadata) if metadata.len() == 0 => Some("empty file".to_string()),
+                Ok(_) if unanalysable.contains(&track.id) => {
+                    let path = track.path.clone();
+                    tokio::task::spawn_blocking(move || {
+                        rockbox_similarity::analyze_file(Path::new(&path))
+                    })
+                    .await?
+                    .err()
+                    .map(|e| format!("cannot decode: {}", e))
+                }
+                Ok(_) => None,
+            };
+            if let Some(problem) = problem {
+                broken.push(TrackIssue {
+                    track: track.clone(),
+                    problems: vec![problem],
+                });
+            }
+        }
+        Ok(broken)
+    }
+
+    /// Check the whole library and keep the result as the last report.
+    pub async fn scan(&self) -> Result<HealthReport> {
+        let tracks = self.local_tracks().await?;
+        let missing_tags = tracks
+            .iter()
+            .filter_map(|track| {
+                let problems = tag_problems(track);
+                (!problems.is_empty()).then(|| TrackIssue {
+                    track: track.clone(),
+                    problems,
+                })
+            })
+            .collect();
+        let missing_cover_art = repo::album::all(self.pool.clone())
+            .await?
+            .into_iter()
+            .filter(|a| a.album_art.as_deref().unwrap_or("").is_empty())
+            .collect();
+
+        let report = HealthReport {
+            generated_at: Utc::now(),
+            duplicates: self.duplicates(&tracks).await?,
+            missing_tags,
+            missing_cover_art,
+            broken_files: self.broken_files(&tracks).await?,
+            orphan_albums: repo::album::orphans(self.pool.clone()).await?,
+            orphan_artists: repo::artist::orphans(self.pool.clone()).await?,
+            hidden_tracks: tracks.into_iter().filter(|t| t.hidden).collect(),
+        };
+        *last_report().write().unwrap() = Some(report.clone());
+        Ok(report)
+    }
+
+    /// The last report, or a fresh one when there is none yet or the
+    /// library was changed through this store since.
+    pub async fn report(&self) -> Result<HealthReport> {
+        if let Some(report) = last_report().read().unwrap().clone() {
+            return Ok(report);
+        }
+        self.scan().await
+    }
+
+    fn invalidate(&self) {
+        *last_report().write().unwrap() = None;
+    }
+
+    /// Hide or unhide a track. Returns whether it exists.
+    pub async fn set_hidden(&self, track_id: &str, hidden: bool) -> Result<bool> {
+        let found = repo::track::set_hidden(self.pool.clone(), track_id, hidden).await?;
+        if found {
+            changes::track_changed(track_id);
+            self.invalidate();
+        }
+        Ok(found)
+    }
+
+    /// Hide all but the best copy in every duplicate group, or only in
+    /// groups found for `reason`. Returns the ids of the hidden tracks.
+    pub async fn hide_duplicates(&self, reason: Option<DuplicateReason>) -> Result<Vec<String>> {
+        let tracks = self.local_tracks().await?;
+        let mut hidden = Vec::new();
+        for group in self.duplicates(&tracks).await? {
+            if reason.is_some_and(|reason| reason != group.reason) {
+                continue;
+            }
+            for track in group.tracks.into_iter().skip(1) {
+                if self.set_hidden(&track.id, true).await? {
+                    hidden.push(track.id);
+                }
+            }
+        }
+        self.invalidate();
+        Ok(hidden)
+    }
+
+    /// Delete album and artist rows no track points at. Returns how many
+    /// albums and artists were removed.
+    pub async fn remove_orphans(&self) -> Result<(u64, u64)> {
+        // Albums first: an artist may only be held by an orphan album.
+        let albums = repo::album::delete_orphans(self.pool.clone()).await?;
+        let artists = repo::artist::delete_orphans(self.pool.clone()).await?;
+        self.invalidate();
+        Ok((albums, artists))
+    }
+}
+
+/// Check the library ten minutes after startup, then every
+/// `ROCKBOX_HEALTH_INTERVAL_SECS` seconds (default `86400`, `0` disables).
+pub fn start_health_task(store: HealthStore) {
+    let secs: u64 = std::env::var("ROCKBOX_HEALTH_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(86400);
+    if secs == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        let start = tokio::time::Instant::now() + Duration::from_secs(600);
+        let mut interval = tokio::time::interval_at(start, Duration::from_secs(secs));
+        loop {
+            interval.tick().await;
+            match store.scan().await {
+                Ok(report) => info!(
+                    "health: {} duplicate groups, {} untagged, {} broken, {} orphan albums, {} orphan artists",
+                    report.duplicates.len(),
+                    report.missing_tags.len(),
+                    report.broken_files.len(),
+                    report.orphan_albums.len(),
+                    report.orphan_artists.len()
+                ),
+                Err(e) => error!("health: scan failed: {}", e),
+            }
+        }
+    });
+}
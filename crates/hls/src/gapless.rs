@@ -0,0 +1,162 @@
+//! Encoder delay and padding, for gapless joins.
+//!
+//! Lossy encoders prepend priming samples and pad the last frame, so a
+//! track decoded as-is starts with a few ms of silence and ends with some
+//! more. The amounts are recorded in the file:
+//!
+//! - iTunes / AAC: the `iTunSMPB` tag (an MP4 `----` atom or an ID3 comment)
+//!   holds the delay, the padding and the original length in hex.
+//! - LAME / MP3: the LAME extension of the first frame's Xing/Info header
+//!   packs the delay and padding into 3 bytes. MP3 decoders add another 529
+//!   samples of delay on top of the encoder's.
+//!
+//! Both are looked up in the init segment first, then the first media
+//! segment, which is where an MPEG-TS or packed-audio stream carries them.
+
+/// Samples the MP3 synthesis filter delays its output by.
+const MP3_DECODER_DELAY: usize = 529;
+
+/// Frames to drop from the start and end of a decoded stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Trim {
+    pub delay: usize,
+    pub padding: usize,
+}
+
+/// Look for a gapless tag in the stream's first bytes.
+pub fn find(init: Option<&[u8]>, first_segment: &[u8]) -> Option<Trim> {
+    init.and_then(itunsmpb)
+        .or_else(|| itunsmpb(first_segment))
+        .or_else(|| lame(first_segment))
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// `iTunSMPB`: ` 00000000 00000840 000001CA 00000000003F31F6 ...`, the
+/// second and third fields being delay and padding.
+pub fn itunsmpb(data: &[u8]) -> Option<Trim> {
+    let at = find_bytes(data, b"iTunSMPB")? + 8;
+    let window = &data[at..data.len().min(at + 256)];
+    // The value follows the `data` atom header or the ID3 comment's NUL:
+    // the first run of hex digits and spaces that holds the fields.
+    window
+        .split(|b| *b != b' ' && !b.is_ascii_hexdigit())
+        .find_map(|run| {
+            let text = std::str::from_utf8(run).ok()?;
+            let fields: Vec<&str> = text.split_whitespace().collect();
+            if fields.len() < 4 || fields[1].len() != 8 || fields[2].len() != 8 {
+                return None;
+            }
+            Some(Trim {
+                delay: usize::from_str_radix(fields[1], 16).ok()?,
+                padding: usize::from_str_radix(fields[2], 16).ok()?,
+            })
+        })
+}
+
+/// Offset of the Xing/Info header in an MPEG audio frame, after the 4-byte
+/// frame header and the side info.
+fn xing_offset(header: &[u8]) -> Option<usize> {
+    if header.len() < 4 || header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    let mpeg1 = header[1] & 0x18 == 0x18;
+    let mono = header[3] >> 6 == 3;
+    Some(match (mpeg1, mono) {
+        (true, false) => 36,
+        (true, true) => 21,
+        (false, false) => 21,
+        (false, true) => 13,
+    })
+}
+
+/// The LAME extension of the first frame's Xing/Info header.
+pub fn lame(data: &[u8]) -> Option<Trim> {
+    // Skip an ID3v2 tag, then find the first frame sync.
+    let mut pos = 0;
+    if data.len() > 10 && &data[..3] == b"ID3" {
+        let size = data[6..10]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize);
+        pos = 10 + size;
+    }
+    let frame = data.get(pos..)?;
+    let sync = frame
+        .windows(2)
+        .take(4096)
+        .position(|w| w[0] == 0xFF && w[1] & 0xE0 == 0xE0)?;
+    let frame = &frame[sync..];
+    let xing = xing_offset(frame)?;
+    let tag = frame.get(xing..xing + 8)?;
+    if &tag[..4] != b"Xing" && &tag[..4] != b"Info" {
+        return None;
+    }
+    let flags = u32::from_be_bytes([tag[4], tag[5], tag[6], tag[7]]);
+    let mut lame = xing + 8;
+    for (bit, len) in [(1, 4), (2, 4), (4, 100), (8, 4)] {
+        if flags & bit != 0 {
+            lame += len;
+        }
+    }
+    // 9-byte encoder string, then 12 bytes of VBR / replaygain / flags
+    // before the delay and padding.
+    let ext = frame.get(lame..lame + 24)?;
+    if !ext[..4].iter().all(|b| b.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let b = &ext[21..24];
+    let delay = ((b[0] as usize) << 4) | (b[1] as usize >> 4);
+    let padding = (((b[1] & 0x0F) as usize) << 8) | b[2] as usize;
+    Some(Trim {
+        delay: delay + MP3_DECODER_DELAY,
+        padding: padding.saturating_sub(MP3_DECODER_DELAY),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_itunsmpb_from_an_mp4_data_atom() {
+        let mut atom = b"\0\0\0\x1cmean\0\0\0\0com.apple.iTunes\0\0\0\x14name\0\0\0\0iTunSMPB\0\0\0\x84data\0\0\0\x01\0\0\0\0".to_vec();
+        atom.extend_from_slice(b" 00000000 00000840 000001CA 00000000003F31F6 00000000 00000000");
+        assert_eq!(
+            itunsmpb(&atom),
+            Some(Trim {
+                delay: 2112,
+                padding: 458
+            })
+        );
+        assert_eq!(itunsmpb(b"iTunSMPB\0\0\0\0"), None);
+    }
+
+    #[test]
+    fn reads_the_lame_tag() {
+        // MPEG-1 layer III, 128 kbps, 44.1 kHz, joint stereo.
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x64];
+        frame.resize(36, 0);
+        frame.extend_from_slice(b"Info");
+        frame.extend_from_slice(&0x0Fu32.to_be_bytes());
+        frame.resize(36 + 8 + 4 + 4 + 100 + 4, 0);
+        let mut ext = b"LAME3.100".to_vec();
+        ext.resize(21, 0);
+        // delay 576, padding 1248
+        ext.extend_from_slice(&[0x24, 0x04, 0xE0]);
+        frame.extend_from_slice(&ext);
+        frame.resize(417, 0);
+
+        assert_eq!(
+            lame(&frame),
+            Some(Trim {
+                delay: 576 + 529,
+                padding: 1248 - 529
+            })
+        );
+        assert_eq!(find(None, &frame), lame(&frame));
+        frame[36..40].copy_from_slice(b"Nope");
+        assert_eq!(lame(&frame), None);
+    }
+}
@@ -3,32 +3,43 @@
 //! Fully isolated from Rockbox's playback engine: no pcmbuf, no codec
 //! dispatcher, no kernel threads. The pipeline is pure Rust:
 //!
-//!     URL (.m3u8 / .mpd)
-//!         → manifest parser (m3u8-rs / dash-mpd)
-//!             → segment fetcher (reqwest, concurrent prefetch)
-//!                 → demuxer (symphonia: fMP4 + MPEG-TS → AAC frames)
-//!                     → decoder (fdk-aac → S16LE PCM)
-//!                         → output: pcm_external_write(addr, size)
-//!                                   → forwards to sinks[cur_sink]->ops.play()
+//! ```text
+//! URL (.m3u8 / .mpd)
+//!     → manifest parser (m3u8-rs / dash-mpd)
+//!         → segment fetcher (reqwest, concurrent prefetch)
+//!             → demuxer (symphonia: fMP4 + MPEG-TS → AAC frames)
+//!                 → decoder (fdk-aac → S16LE PCM)
+//!                     → output: pcm_external_write(addr, size)
+//!                               → forwards to sinks[cur_sink]->ops.play()
+//! ```
 //!
 //! The output stage doesn't touch Rockbox's audio buffer at all — it pushes
 //! decoded PCM straight into whichever PCM sink the user has currently
 //! selected (`audio_output = "cmaf" | "airplay" | "snapcast_tcp" | …`). That
 //! makes "phone A broadcasts CMAF / phone B consumes it" trivially symmetric,
 //! and lets the same HLS player re-broadcast as CMAF, AirPlay-cast, etc.
+//!
+//! Consecutive VOD streams are joined in PCM (`transition`): encoder delay
+//! and padding are trimmed so they play gapless, or they are crossfaded.
+//! Live streams are kept on disk for a while (`timeshift`), so they can be
+//! paused, rewound and brought back to the live edge.
 
 mod decoder;
 mod demux;
 mod fetcher;
+mod gapless;
 mod manifest;
 mod output;
 mod player;
+pub mod timeshift;
+pub mod transition;
 
 pub use manifest::{is_hls_or_dash_url, ManifestKind};
 pub use player::{
-    is_active as player_is_active, pause as player_pause, play as player_play,
-    remote_api_base as player_remote_api_base, resume as player_resume,
-    status_json as player_status_json, stop as player_stop, Player, PlayerState,
+    go_live as player_go_live, is_active as player_is_active, pause as player_pause,
+    play as player_play, queue as player_queue, remote_api_base as player_remote_api_base,
+    resume as player_resume, seek as player_seek, status_json as player_status_json,
+    stop as player_stop, Player, PlayerState,
 };
 
 /// Force this crate's symbols into the surrounding staticlib.
@@ -20,6 +20,11 @@ pub struct SegmentRef {
     /// Nominal duration of the segment, in seconds. Used for buffering
     /// heuristics and live tail latency. Best-effort.
     pub duration: f64,
+    /// Wall-clock time the segment starts at, in Unix milliseconds: from
+    /// `EXT-X-PROGRAM-DATE-TIME` for HLS, from availabilityStartTime for
+    /// DASH. Live streams that carry neither are anchored to now at the
+    /// live edge. None for VOD without program time.
+    pub program_time_ms: Option<i64>,
     /// Container hint — guides demuxer selection. We tell from the manifest
     /// when possible (DASH carries explicit mimeType), otherwise we sniff at
     /// fetch time via the first few bytes.
@@ -149,9 +154,11 @@ fn parse_hls_media(media: m3u8_rs::MediaPlaylist, base: Url) -> Result<ManifestS
             seq: media.media_sequence + i as u64,
             url: abs.to_string(),
             duration: s.duration as f64,
+            program_time_ms: s.program_date_time.map(|t| t.timestamp_millis()),
             container,
         });
     }
+    fill_program_times(&mut segs, is_live);
 
     let refresh_interval = if is_live {
         // Live: refresh every (target_duration / 2). Defaults to 3 s if the
@@ -181,6 +188,14 @@ fn parse_hls_media(media: m3u8_rs::MediaPlaylist, base: Url) -> Result<ManifestS
 // DASH
 // ---------------------------------------------------------------------------
 
+/// Segments listed for a live DASH stream without a deeper
+/// timeShiftBufferDepth.
+const DASH_LIVE_WINDOW: u64 = 6;
+
+/// Most segments listed for a live DASH stream, however deep its
+/// timeShiftBufferDepth.
+const DASH_MAX_LIVE_WINDOW: u64 = 4096;
+
 fn parse_dash(body: &str, base: Url) -> Result<ManifestSnapshot> {
     let mpd: dash_mpd::MPD = dash_mpd::parse(body).map_err(|e| anyhow!("DASH parse: {e}"))?;
     let is_live = matches!(mpd.mpdtype.as_deref(), Some("dynamic"));
@@ -222,11 +237,15 @@ fn parse_dash(body: &str, base: Url) -> Result<ManifestSnapshot> {
         base.join(&s).map(|u| u.to_string()).unwrap_or(s)
     });
 
-    // Live: build a reasonable window of recent segments based on
-    // availabilityStartTime / now / segment duration. For first cut we
-    // simply emit a fixed window of the most recent segments — refresher
-    // will keep it current.
-    let window = 6_u64;
+    // Live: list the segments still available, from availabilityStartTime /
+    // now / segment duration. timeShiftBufferDepth says how far back that
+    // goes; without it, a fixed window of the most recent segments. The
+    // refresher keeps it current.
+    let window = match mpd.timeShiftBufferDepth {
+        Some(depth) if is_live => ((depth.as_secs_f64() / seg_duration).floor() as u64)
+            .clamp(DASH_LIVE_WINDOW, DASH_MAX_LIVE_WINDOW),
+        _ => DASH_LIVE_WINDOW,
+    };
     let mut segs = Vec::with_capacity(window as usize);
     let highest = if is_live {
         // Approximate "now" segment number from publishTime - availabilityStartTime
@@ -251,6 +270,7 @@ fn parse_dash(body: &str, base: Url) -> Result<ManifestSnapshot> {
     } else {
         start_number
     };
+    let ast_ms = mpd.availabilityStartTime.map(|t| t.timestamp_millis());
     for n in first..=highest {
         let url = expand_template(
             media_tmpl,
@@ -259,13 +279,16 @@ fn parse_dash(body: &str, base: Url) -> Result<ManifestSnapshot> {
             representation.bandwidth,
         );
         let abs = base.join(&url).map(|u| u.to_string()).unwrap_or(url);
+        let offset = (n - start_number) as f64 * seg_duration;
         segs.push(SegmentRef {
             seq: n,
             url: abs,
             duration: seg_duration,
+            program_time_ms: ast_ms.map(|ast| ast + (offset * 1000.0) as i64),
             container: ContainerHint::Fmp4,
         });
     }
+    fill_program_times(&mut segs, is_live);
 
     let refresh_interval = if is_live {
         mpd.minimumUpdatePeriod
@@ -311,12 +334,41 @@ fn expand_template(
     s
 }
 
+/// Number of the newest segment that is complete by now.
 fn compute_live_head(mpd: &dash_mpd::MPD, start_number: u64, seg_duration: f64) -> Option<u64> {
     let ast = mpd.availabilityStartTime?;
     let now = chrono_like_now()?;
     let elapsed = (now - ast.timestamp_millis() as f64 / 1000.0).max(0.0);
-    let n = (elapsed / seg_duration).floor() as u64;
-    Some(start_number + n)
+    let complete = (elapsed / seg_duration).floor() as u64;
+    Some(start_number + complete.saturating_sub(1))
+}
+
+/// Give every segment a program time. HLS only needs
+/// `EXT-X-PROGRAM-DATE-TIME` on the first segment after a discontinuity,
+/// the rest follow on by duration; segments ahead of the first tag count
+/// back from it. A live stream with no program time at all ends now.
+fn fill_program_times(segs: &mut [SegmentRef], is_live: bool) {
+    let anchor = match segs.iter().position(|s| s.program_time_ms.is_some()) {
+        Some(i) => i,
+        None if is_live && !segs.is_empty() => {
+            let now_ms = (chrono_like_now().unwrap_or(0.0) * 1000.0) as i64;
+            let last = segs.len() - 1;
+            segs[last].program_time_ms = Some(now_ms - (segs[last].duration * 1000.0) as i64);
+            last
+        }
+        None => return,
+    };
+    for i in (0..anchor).rev() {
+        let next = segs[i + 1].program_time_ms.unwrap_or_default();
+        segs[i].program_time_ms = Some(next - (segs[i].duration * 1000.0) as i64);
+    }
+    for i in anchor + 1..segs.len() {
+        if segs[i].program_time_ms.is_none() {
+            let prev = &segs[i - 1];
+            segs[i].program_time_ms =
+                Some(prev.program_time_ms.unwrap_or_default() + (prev.duration * 1000.0) as i64);
+        }
+    }
 }
 
 fn chrono_like_now() -> Option<f64> {
@@ -326,3 +378,49 @@ fn chrono_like_now() -> Option<f64> {
         .ok()
         .map(|d| d.as_secs_f64())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(seq: u64, program_time_ms: Option<i64>) -> SegmentRef {
+        SegmentRef {
+            seq,
+            url: format!("{seq}.m4s"),
+            duration: 2.0,
+            program_time_ms,
+            container: ContainerHint::Fmp4,
+        }
+    }
+
+    #[test]
+    fn program_times_follow_on_from_the_tags() {
+        let mut segs = vec![
+            segment(1, None),
+            segment(2, Some(10_000)),
+            segment(3, None),
+            segment(4, Some(20_000)),
+        ];
+        fill_program_times(&mut segs, false);
+        let times: Vec<_> = segs.iter().map(|s| s.program_time_ms).collect();
+        assert_eq!(
+            times,
+            [Some(8_000), Some(10_000), Some(12_000), Some(20_000)]
+        );
+    }
+
+    #[test]
+    fn untagged_live_streams_end_now() {
+        let mut segs = vec![segment(1, None), segment(2, None)];
+        fill_program_times(&mut segs, false);
+        assert!(segs.iter().all(|s| s.program_time_ms.is_none()));
+
+        fill_program_times(&mut segs, true);
+        let now_ms = (chrono_like_now().unwrap() * 1000.0) as i64;
+        assert!((segs[1].program_time_ms.unwrap() + 2_000 - now_ms).abs() < 1_000);
+        assert_eq!(
+            segs[0].program_time_ms.unwrap() + 2_000,
+            segs[1].program_time_ms.unwrap()
+        );
+    }
+}
@@ -4,6 +4,14 @@
 //! one playing at a time, so the FFI surface treats the player as a global
 //! singleton: `rb_hls_play(url)` stops any current player and starts a new
 //! one. Status is queryable via `rb_hls_status_json`.
+//!
+//! `rb_hls_queue(url)` lines up the next stream: it is opened while the
+//! current one plays its last segment and joined onto its held-back end by
+//! `transition`, so VOD tracks follow each other without a gap.
+//!
+//! Live streams keep fetching into the `timeshift` buffer while paused, so
+//! resuming carries on from the same point. `rb_hls_seek` moves within the
+//! buffer by program time and `rb_hls_go_live` jumps back to the edge.
 
 use anyhow::{anyhow, Result};
 use bytes::Bytes;
@@ -16,11 +24,14 @@ use std::time::Duration;
 use tokio::runtime::Runtime;
 use tokio::task::JoinHandle;
 
-use crate::decoder::decode_segment;
+use crate::decoder::{decode_segment, DecodedSegment};
 use crate::demux;
 use crate::fetcher::{self, SegmentCache};
-use crate::manifest::{self, ManifestKind};
+use crate::gapless::{self, Trim};
+use crate::manifest::{self, ManifestKind, SegmentRef};
 use crate::output;
+use crate::timeshift::TimeShift;
+use crate::transition;
 
 /// Shared tokio runtime for all HLS player work. Multi-threaded so segment
 /// prefetch can overlap with decoding.
@@ -36,6 +47,16 @@ static RT: Lazy<Runtime> = Lazy::new(|| {
 /// Singleton player. Replacing it via `rb_hls_play` cancels any prior task.
 static PLAYER: Lazy<Mutex<Option<Arc<Player>>>> = Lazy::new(|| Mutex::new(None));
 
+/// `program_time_ms` before anything with a program time has been heard.
+const NO_PROGRAM_TIME: i64 = i64::MIN;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeekTarget {
+    /// Wall-clock time, in Unix milliseconds.
+    ProgramTime(i64),
+    LiveEdge,
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerState {
@@ -47,13 +68,16 @@ pub enum PlayerState {
 }
 
 pub struct Player {
-    url: String,
+    url: Mutex<String>,
     /// Base URL of the broadcaster's gRPC endpoint, derived from the
     /// HLS/DASH URL host. Used by the gRPC layer to proxy
     /// seek / next / previous / current_track / status to the upstream
     /// Rockbox so the consumer behaves as a thin remote control. Default
     /// gRPC port is 6061 (same as `ROCKBOX_PORT` on the broadcaster).
-    remote_api_base: String,
+    remote_api_base: Mutex<String>,
+    /// URL to join onto the end of the current VOD stream. Taken when the
+    /// current stream reaches its last segment and opened ahead of time.
+    next_url: Mutex<Option<String>>,
     state: AtomicU8,
     paused: AtomicBool,
     stop_flag: Arc<AtomicBool>,
@@ -62,6 +86,12 @@ pub struct Player {
     /// Total duration in ms (or -1 for live).
     duration_ms: AtomicI64,
     is_live: AtomicBool,
+    /// Wall-clock time of what is being heard, for live streams.
+    program_time_ms: AtomicI64,
+    /// Segments of the live stream playing, still listed or on disk.
+    timeline: Mutex<Option<Arc<Mutex<VecDeque<SegmentRef>>>>>,
+    /// Seek for the play loop to carry out.
+    seek_to: Mutex<Option<SeekTarget>>,
     task: Mutex<Option<JoinHandle<()>>>,
     last_error: Mutex<Option<String>>,
 }
@@ -70,21 +100,25 @@ impl Player {
     fn new(url: String) -> Arc<Self> {
         let remote_api_base = derive_remote_api_base(&url);
         Arc::new(Player {
-            url,
-            remote_api_base,
+            url: Mutex::new(url),
+            remote_api_base: Mutex::new(remote_api_base),
+            next_url: Mutex::new(None),
             state: AtomicU8::new(PlayerState::Stopped as u8),
             paused: AtomicBool::new(false),
             stop_flag: Arc::new(AtomicBool::new(false)),
             position_ms: AtomicI64::new(0),
             duration_ms: AtomicI64::new(-1),
             is_live: AtomicBool::new(false),
+            program_time_ms: AtomicI64::new(NO_PROGRAM_TIME),
+            timeline: Mutex::new(None),
+            seek_to: Mutex::new(None),
             task: Mutex::new(None),
             last_error: Mutex::new(None),
         })
     }
 
-    pub fn remote_api_base(&self) -> &str {
-        &self.remote_api_base
+    pub fn remote_api_base(&self) -> String {
+        self.remote_api_base.lock().unwrap().clone()
     }
 
     fn set_state(&self, s: PlayerState) {
@@ -107,12 +141,475 @@ impl Player {
         self.set_state(PlayerState::Errored);
     }
 
+    /// True when the PCM being written is no longer wanted.
+    fn interrupted(&self) -> bool {
+        self.stop_flag.load(Ordering::SeqCst) || self.seek_to.lock().unwrap().is_some()
+    }
+
     fn cancel(&self) {
         self.stop_flag.store(true, Ordering::SeqCst);
         if let Some(h) = self.task.lock().unwrap().take() {
             h.abort();
         }
     }
+
+    /// Point the status at `stream`, `head_start_ms` before it is heard.
+    fn start_stream(&self, stream: &Stream, head_start_ms: i64) {
+        *self.url.lock().unwrap() = stream.url.clone();
+        *self.remote_api_base.lock().unwrap() = derive_remote_api_base(&stream.url);
+        self.is_live.store(stream.is_live, Ordering::SeqCst);
+        self.duration_ms.store(
+            stream.duration.map(|d| (d * 1000.0) as i64).unwrap_or(-1),
+            Ordering::SeqCst,
+        );
+        self.position_ms.store(-head_start_ms, Ordering::SeqCst);
+        self.program_time_ms
+            .store(NO_PROGRAM_TIME, Ordering::SeqCst);
+        *self.timeline.lock().unwrap() = stream.is_live.then(|| stream.known.clone());
+    }
+}
+
+/// Frames VOD streams hold back even without a crossfade, enough to cover
+/// the encoder padding trimmed off their end.
+const GAPLESS_HOLDBACK_FRAMES: usize = 8192;
+
+/// Where live playback starts: three segments back from the newest, the
+/// usual hold-back.
+fn live_start<'a, I>(segments: I) -> Option<&'a SegmentRef>
+where
+    I: IntoIterator<Item = &'a SegmentRef>,
+    I::IntoIter: DoubleEndedIterator,
+{
+    segments.into_iter().rev().take(3).last()
+}
+
+/// Fetch `refs` and keep them in the time-shift buffer, a few at a time so
+/// none are evicted from the cache before they are stored.
+async fn buffer(
+    client: &Arc<reqwest::Client>,
+    cache: &Arc<SegmentCache>,
+    timeshift: &TimeShift,
+    refs: Vec<SegmentRef>,
+) {
+    let refs: Vec<_> = refs
+        .into_iter()
+        .filter(|s| !timeshift.contains(s.seq))
+        .collect();
+    for chunk in refs.chunks(4) {
+        fetcher::prefetch(client.clone(), cache.clone(), chunk.to_vec()).await;
+        for s in chunk {
+            if let Some(bytes) = cache.get(s.seq).await {
+                timeshift.put(s.seq, s.duration, &bytes).await;
+            }
+        }
+    }
+}
+
+/// One manifest being played: its segment list, kept current by a refresher
+/// task for live streams, and the cache the segments are prefetched into.
+/// Live streams also keep their segments in a time-shift buffer on disk.
+struct Stream {
+    url: String,
+    is_live: bool,
+    duration: Option<f64>,
+    init: Option<Bytes>,
+    /// Sample rate from the init segment, known before the first decode.
+    init_sample_rate: Option<u32>,
+    known: Arc<Mutex<VecDeque<SegmentRef>>>,
+    cache: Arc<SegmentCache>,
+    timeshift: Option<Arc<TimeShift>>,
+    /// Sequence number of the next segment we will play out.
+    next_seq: u64,
+    /// Milliseconds to drop from the start of the next segment, after a seek.
+    skip_ms: i64,
+    /// Program time the last segment returned starts at.
+    program_time_ms: Option<i64>,
+    /// Encoder delay and padding, read from the first segment.
+    trim: Trim,
+    started: bool,
+    refresher: Option<JoinHandle<()>>,
+}
+
+impl Stream {
+    async fn open(
+        client: &Arc<reqwest::Client>,
+        url: &str,
+        stop: &Arc<AtomicBool>,
+    ) -> Result<Stream> {
+        // Resolve master → media playlist once if needed (single redirect hop).
+        let kind = manifest::is_hls_or_dash_url(url)
+            .ok_or_else(|| anyhow!("URL does not look like HLS or DASH: {url}"))?;
+        let snap = match manifest::fetch_and_parse(client, url, kind).await {
+            Ok(s) => s,
+            Err(e) if e.to_string().contains("re-fetch variant") => {
+                // Extract variant URL from the error and re-fetch.
+                let s = e.to_string();
+                let variant = s
+                    .rsplit(' ')
+                    .next()
+                    .ok_or_else(|| anyhow!("variant url parse: {e}"))?
+                    .to_string();
+                manifest::fetch_and_parse(client, &variant, ManifestKind::Hls).await?
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Init segment (fMP4 only — MPEG-TS has its own self-describing headers).
+        let cache = Arc::new(SegmentCache::default());
+        let mut init = None;
+        let mut init_sample_rate = None;
+        if let Some(init_url) = snap.init_url.clone() {
+            let bytes = fetcher::fetch_bytes(client, &init_url).await?;
+            match demux::parse_init(&bytes) {
+                Ok(h) => init_sample_rate = h.sample_rate,
+                Err(e) => {
+                    tracing::warn!("hls: parse init failed ({e}); decoder will probe per-segment")
+                }
+            }
+            init = Some(bytes);
+        }
+
+        let mut next_seq = snap
+            .segments
+            .first()
+            .map(|s| s.seq)
+            .ok_or_else(|| anyhow!("manifest has no segments"))?;
+        // For live, jump near the live edge.
+        if snap.is_live {
+            next_seq = live_start(&snap.segments).map_or(next_seq, |s| s.seq);
+        }
+        let timeshift = match snap.is_live {
+            true => TimeShift::create().map(Arc::new),
+            false => None,
+        };
+
+        // Initial prefetch of next few segments.
+        let initial: Vec<_> = snap
+            .segments
+            .iter()
+            .filter(|s| s.seq >= next_seq)
+            .take(3)
+            .cloned()
+            .collect();
+        match &timeshift {
+            Some(ts) => buffer(client, &cache, ts, initial).await,
+            None => fetcher::prefetch(client.clone(), cache.clone(), initial).await,
+        }
+
+        // Track of the last "known" segment list so refresher can append.
+        let known: Arc<Mutex<VecDeque<SegmentRef>>> =
+            Arc::new(Mutex::new(snap.segments.iter().cloned().collect()));
+
+        // Spawn refresher for live streams.
+        let refresher = if snap.is_live {
+            let client = client.clone();
+            let known = known.clone();
+            let cache = cache.clone();
+            let timeshift = timeshift.clone();
+            let stop = stop.clone();
+            let interval = snap.refresh_interval;
+            let url = url.to_string();
+            let kind = snap.kind;
+            Some(tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    match manifest::fetch_and_parse(&client, &url, kind).await {
+                        Ok(new_snap) => {
+                            let listed_first = new_snap.segments.first().map(|s| s.seq);
+                            let (fresh, snapshot): (Vec<_>, Vec<_>) = {
+                                let mut g = known.lock().unwrap();
+                                let last_seen = g.back().map(|s| s.seq).unwrap_or(0);
+                                let fresh: Vec<_> = new_snap
+                                    .segments
+                                    .into_iter()
+                                    .filter(|s| s.seq > last_seen)
+                                    .collect();
+                                g.extend(fresh.iter().cloned());
+                                // Keep the deque from growing unboundedly, but
+                                // hold on to whatever can still be played:
+                                // listed by the manifest or on disk.
+                                let keep_from =
+                                    [listed_first, timeshift.as_ref().and_then(|t| t.first_seq())]
+                                        .into_iter()
+                                        .flatten()
+                                        .min()
+                                        .unwrap_or(u64::MAX);
+                                while g.len() > 64 && g.front().is_some_and(|s| s.seq < keep_from) {
+                                    g.pop_front();
+                                }
+                                (fresh, g.iter().rev().take(3).cloned().collect())
+                            };
+                            match &timeshift {
+                                // Everything new, paused or not, so there
+                                // is something to come back to.
+                                Some(ts) => buffer(&client, &cache, ts, fresh).await,
+                                None => {
+                                    fetcher::prefetch(client.clone(), cache.clone(), snapshot).await
+                                }
+                            }
+                        }
+                        Err(e) => tracing::warn!("hls refresh: {e}"),
+                    }
+                }
+            }))
+        } else {
+            None
+        };
+
+        Ok(Stream {
+            url: url.to_string(),
+            is_live: snap.is_live,
+            duration: snap.duration,
+            init,
+            init_sample_rate,
+            known,
+            cache,
+            timeshift,
+            next_seq,
+            skip_ms: 0,
+            program_time_ms: None,
+            trim: Trim::default(),
+            started: false,
+            refresher,
+        })
+    }
+
+    /// True when the next segment is the last one of a VOD stream.
+    fn on_last_segment(&self) -> bool {
+        !self.is_live
+            && !self
+                .known
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|s| s.seq > self.next_seq)
+    }
+
+    /// Fetch and decode the next segment, skipping any that fail. The first
+    /// one comes back with the encoder delay already trimmed. None once a
+    /// VOD stream has ended or the player is stopped.
+    async fn next_segment(
+        &mut self,
+        client: &Arc<reqwest::Client>,
+        stop: &AtomicBool,
+    ) -> Option<DecodedSegment> {
+        loop {
+            if stop.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            // Find the segment we need next.
+            let seg = {
+                let g = self.known.lock().unwrap();
+                g.iter().find(|s| s.seq == self.next_seq).cloned()
+            };
+            let Some(seg) = seg else {
+                if self.is_live {
+                    // Paused for longer than the time-shift window: carry on
+                    // from the oldest segment left.
+                    let first = self.known.lock().unwrap().front().map(|s| s.seq);
+                    if let Some(first) = first.filter(|first| *first > self.next_seq) {
+                        tracing::warn!(
+                            "hls: seg {} left the time-shift window; resuming at {first}",
+                            self.next_seq
+                        );
+                        self.next_seq = first;
+                        self.skip_ms = 0;
+                        continue;
+                    }
+                    // No segment with this seq exists yet.
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    continue;
+                }
+                // VOD: end of stream.
+                return None;
+            };
+
+            // Pull segment bytes from cache, the time-shift buffer or fetch.
+            let stored = match self.cache.get(seg.seq).await {
+                Some(b) => Some(b),
+                None => match &self.timeshift {
+                    Some(ts) => ts.get(seg.seq).await,
+                    None => None,
+                },
+            };
+            let bytes = match stored {
+                Some(b) => b,
+                None => match fetcher::fetch_bytes(client, &seg.url).await {
+                    Ok(b) => {
+                        self.cache.put(seg.seq, b.clone()).await;
+                        if let Some(ts) = &self.timeshift {
+                            ts.put(seg.seq, seg.duration, &b).await;
+                        }
+                        b
+                    }
+                    Err(e) => {
+                        tracing::warn!("hls: fetch seg {} failed: {e}; skipping", seg.seq);
+                        self.next_seq += 1;
+                        self.skip_ms = 0;
+                        continue;
+                    }
+                },
+            };
+
+            // Symphonia: demux + decode in one pass.
+            let mut decoded = match decode_segment(self.init.as_deref(), &bytes) {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::warn!("hls: decode seg {} failed: {e}; skipping", seg.seq);
+                    self.next_seq += 1;
+                    self.skip_ms = 0;
+                    continue;
+                }
+            };
+
+            if !self.started {
+                self.started = true;
+                self.trim = gapless::find(self.init.as_deref(), &bytes).unwrap_or_default();
+                let ch = decoded.channels.max(1) as usize;
+                let delay = (self.trim.delay * ch).min(decoded.samples.len());
+                decoded.samples.drain(..delay);
+            }
+
+            // A seek lands part way into its segment.
+            let skip_ms = std::mem::take(&mut self.skip_ms);
+            if skip_ms > 0 {
+                let ch = decoded.channels.max(1) as usize;
+                let frames = skip_ms as usize * decoded.sample_rate as usize / 1000;
+                let skip = (frames * ch).min(decoded.samples.len());
+                decoded.samples.drain(..skip);
+            }
+            self.program_time_ms = seg.program_time_ms.map(|t| t + skip_ms);
+
+            // Prefetch the next-next segment so we stay ahead.
+            let upcoming: Vec<_> = {
+                let g = self.known.lock().unwrap();
+                g.iter()
+                    .filter(|s| s.seq > self.next_seq && s.seq <= self.next_seq + 3)
+                    .cloned()
+                    .collect()
+            };
+            fetcher::prefetch(client.clone(), self.cache.clone(), upcoming).await;
+
+            self.next_seq += 1;
+            return Some(decoded);
+        }
+    }
+
+    /// Move playback of a live stream to `target`, within the segments
+    /// still listed or on disk; times past the live edge land on it.
+    /// Returns the program time playback picks up at.
+    fn seek(&mut self, target: SeekTarget) -> Option<i64> {
+        let (seq, skip_ms, program_time) = {
+            let g = self.known.lock().unwrap();
+            let edge = live_start(g.iter())?;
+            let seg = match target {
+                SeekTarget::ProgramTime(t) => g
+                    .iter()
+                    .rev()
+                    .find(|s| s.program_time_ms.is_some_and(|start| start <= t))
+                    .or(g.front())?,
+                SeekTarget::LiveEdge => edge,
+            };
+            let (seg, skip_ms) = match (target, seg.program_time_ms) {
+                _ if seg.seq >= edge.seq => (edge, 0),
+                (SeekTarget::ProgramTime(t), Some(start)) => {
+                    (seg, (t - start).clamp(0, (seg.duration * 1000.0) as i64))
+                }
+                _ => (seg, 0),
+            };
+            (seg.seq, skip_ms, seg.program_time_ms.map(|t| t + skip_ms))
+        };
+        self.next_seq = seq;
+        self.skip_ms = skip_ms;
+        program_time
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        if let Some(h) = self.refresher.take() {
+            h.abort();
+        }
+    }
+}
+
+/// A queued stream being opened, with its first segment.
+type NextStream = JoinHandle<Result<(Stream, Option<DecodedSegment>)>>;
+
+/// Open a queued stream and decode its first segment, ready to be joined on.
+async fn open_next(
+    client: Arc<reqwest::Client>,
+    url: String,
+    stop: Arc<AtomicBool>,
+) -> Result<(Stream, Option<DecodedSegment>)> {
+    let mut stream = Stream::open(&client, &url, &stop).await?;
+    let head = stream.next_segment(&client, &stop).await;
+    Ok((stream, head))
+}
+
+/// Decoded PCM on its way to the sink. VOD streams keep their last few
+/// seconds here so the next stream can be joined onto them.
+#[derive(Default)]
+struct Holdback {
+    sample_rate: u32,
+    channels: usize,
+    samples: Vec<i16>,
+}
+
+impl Holdback {
+    /// Queue `decoded`, writing out whatever doesn't fit in `frames` frames.
+    async fn push(&mut self, player: &Player, decoded: DecodedSegment, frames: usize) {
+        let ch = decoded.channels.max(1) as usize;
+        let rate_changed = decoded.sample_rate != self.sample_rate && decoded.sample_rate != 0;
+        if rate_changed || ch != self.channels {
+            self.flush(player).await;
+            if rate_changed {
+                self.sample_rate = decoded.sample_rate;
+                output::set_sample_rate(decoded.sample_rate);
+            }
+            self.channels = ch;
+        }
+        self.samples.extend_from_slice(&decoded.samples);
+        let keep = frames * self.channels;
+        if self.samples.len() > keep {
+            let out: Vec<i16> = self.samples.drain(..self.samples.len() - keep).collect();
+            self.write(player, &out).await;
+        }
+    }
+
+    async fn flush(&mut self, player: &Player) {
+        let out = std::mem::take(&mut self.samples);
+        self.write(player, &out).await;
+    }
+
+    /// Pause-honoring write: chunk the PCM so we can check paused/stop
+    /// between writes without holding up sink callbacks indefinitely.
+    async fn write(&self, player: &Player, samples: &[i16]) {
+        let ch = self.channels.max(1);
+        let chunk_frames = self.sample_rate.max(1) as usize / 20; // ~50 ms
+        let chunk_samples = chunk_frames * ch;
+        for window in samples.chunks(chunk_samples.max(ch)) {
+            while player.paused.load(Ordering::SeqCst) && !player.interrupted() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            if player.interrupted() {
+                break;
+            }
+            output::write_pcm(window);
+            let frames_pushed = window.len() / ch;
+            let ms = (frames_pushed as i64 * 1000) / self.sample_rate.max(1) as i64;
+            player.position_ms.fetch_add(ms, Ordering::SeqCst);
+            let _ = player
+                .program_time_ms
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |t| {
+                    (t != NO_PROGRAM_TIME).then_some(t + ms)
+                });
+        }
+    }
 }
 
 /// The actual playback loop. Runs on the shared tokio runtime.
@@ -133,210 +630,125 @@ async fn run_player_inner(player: &Arc<Player>) -> Result<()> {
     );
     player.set_state(PlayerState::Buffering);
 
-    // Resolve master → media playlist once if needed (single redirect hop).
-    let url = player.url.clone();
-    let kind = manifest::is_hls_or_dash_url(&url)
-        .ok_or_else(|| anyhow!("URL does not look like HLS or DASH: {url}"))?;
-    let mut snap = match manifest::fetch_and_parse(&client, &url, kind).await {
-        Ok(s) => s,
-        Err(e) if e.to_string().contains("re-fetch variant") => {
-            // Extract variant URL from the error and re-fetch.
-            let s = e.to_string();
-            let variant = s
-                .rsplit(' ')
-                .next()
-                .ok_or_else(|| anyhow!("variant url parse: {e}"))?
-                .to_string();
-            manifest::fetch_and_parse(&client, &variant, ManifestKind::Hls).await?
-        }
-        Err(e) => return Err(e),
-    };
-    player.is_live.store(snap.is_live, Ordering::SeqCst);
-    player.duration_ms.store(
-        snap.duration.map(|d| (d * 1000.0) as i64).unwrap_or(-1),
-        Ordering::SeqCst,
-    );
-
-    // Init segment (fMP4 only — MPEG-TS has its own self-describing headers).
-    let cache = Arc::new(SegmentCache::default());
-    let mut init_bytes: Option<Bytes> = None;
-    let mut current_sample_rate: u32 = 0;
-    if let Some(init_url) = snap.init_url.clone() {
-        let init = fetcher::fetch_bytes(&client, &init_url).await?;
-        match demux::parse_init(&init) {
-            Ok(h) => {
-                if let Some(sr) = h.sample_rate {
-                    current_sample_rate = sr;
-                    output::set_sample_rate(sr);
-                }
-            }
-            Err(e) => {
-                tracing::warn!("hls: parse init failed ({e}); decoder will probe per-segment")
-            }
-        }
-        init_bytes = Some(init);
-    }
-
-    // Index of the next segment we will play out.
-    let mut next_play_seq = snap
-        .segments
-        .first()
-        .map(|s| s.seq)
-        .ok_or_else(|| anyhow!("manifest has no segments"))?;
-    // For live, jump near the live edge.
-    if snap.is_live {
-        let n = snap.segments.len();
-        if n > 3 {
-            next_play_seq = snap.segments[n - 3].seq;
-        }
+    let url = player.url.lock().unwrap().clone();
+    let mut stream = Stream::open(&client, &url, &player.stop_flag).await?;
+    player.start_stream(&stream, 0);
+    let mut holdback = Holdback::default();
+    if let Some(sr) = stream.init_sample_rate {
+        holdback.sample_rate = sr;
+        output::set_sample_rate(sr);
     }
 
-    // Initial prefetch of next few segments.
-    let initial: Vec<_> = snap
-        .segments
-        .iter()
-        .filter(|s| s.seq >= next_play_seq)
-        .take(3)
-        .cloned()
-        .collect();
-    fetcher::prefetch(client.clone(), cache.clone(), initial).await;
-
     player.set_state(PlayerState::Playing);
 
-    // Track of the last "known" segment list so refresher can append.
-    let known: Arc<Mutex<VecDeque<crate::manifest::SegmentRef>>> =
-        Arc::new(Mutex::new(snap.segments.iter().cloned().collect()));
-
-    // Spawn refresher for live streams.
-    let refresher = if snap.is_live {
-        let client = client.clone();
-        let known = known.clone();
-        let cache = cache.clone();
-        let stop = player.stop_flag.clone();
-        let interval = snap.refresh_interval;
-        let url = url.clone();
-        let kind = snap.kind;
-        Some(tokio::spawn(async move {
-            loop {
-                tokio::time::sleep(interval).await;
-                if stop.load(Ordering::SeqCst) {
-                    break;
-                }
-                match manifest::fetch_and_parse(&client, &url, kind).await {
-                    Ok(new_snap) => {
-                        let snapshot: Vec<_> = {
-                            let mut g = known.lock().unwrap();
-                            let last_seen = g.back().map(|s| s.seq).unwrap_or(0);
-                            for s in new_snap.segments {
-                                if s.seq > last_seen {
-                                    g.push_back(s);
-                                }
-                            }
-                            // Keep the deque from growing unboundedly.
-                            while g.len() > 64 {
-                                g.pop_front();
-                            }
-                            g.iter().rev().take(3).cloned().collect()
-                        };
-                        fetcher::prefetch(client.clone(), cache.clone(), snapshot).await;
-                    }
-                    Err(e) => tracing::warn!("hls refresh: {e}"),
-                }
-            }
-        }))
-    } else {
-        None
-    };
+    // The queued stream, opened while the current one plays its last segment.
+    let mut next: Option<NextStream> = None;
 
     // Main play loop.
     loop {
         if player.stop_flag.load(Ordering::SeqCst) {
             break;
         }
+        let seek = player.seek_to.lock().unwrap().take();
+        if let Some(target) = seek {
+            let program_time = stream.seek(target);
+            player
+                .program_time_ms
+                .store(program_time.unwrap_or(NO_PROGRAM_TIME), Ordering::SeqCst);
+        }
         if player.paused.load(Ordering::SeqCst) {
             tokio::time::sleep(Duration::from_millis(100)).await;
             continue;
         }
 
-        // Find the segment we need next.
-        let seg = {
-            let g = known.lock().unwrap();
-            g.iter().find(|s| s.seq == next_play_seq).cloned()
-        };
-        let Some(seg) = seg else {
-            // No segment with this seq exists yet.
-            if snap.is_live {
-                tokio::time::sleep(Duration::from_millis(200)).await;
-                continue;
+        if next.is_none() && stream.on_last_segment() {
+            if let Some(url) = player.next_url.lock().unwrap().take() {
+                next = Some(tokio::spawn(open_next(
+                    client.clone(),
+                    url,
+                    player.stop_flag.clone(),
+                )));
             }
-            // VOD: end of stream.
-            break;
-        };
+        }
 
-        // Pull segment bytes from cache or fetch.
-        let bytes = match cache.get(seg.seq).await {
-            Some(b) => b,
-            None => match fetcher::fetch_bytes(&client, &seg.url).await {
-                Ok(b) => {
-                    cache.put(seg.seq, b.clone()).await;
-                    b
-                }
-                Err(e) => {
-                    tracing::warn!("hls: fetch seg {} failed: {e}; skipping", seg.seq);
-                    next_play_seq += 1;
-                    continue;
-                }
-            },
-        };
+        if let Some(decoded) = stream.next_segment(&client, &player.stop_flag).await {
+            if let Some(t) = stream.program_time_ms.filter(|_| stream.is_live) {
+                player.program_time_ms.store(t, Ordering::SeqCst);
+            }
+            let frames = match stream.is_live {
+                true => 0,
+                false => transition::config()
+                    .holdback_frames(holdback.sample_rate)
+                    .max(GAPLESS_HOLDBACK_FRAMES),
+            };
+            holdback.push(player, decoded, frames).await;
+            continue;
+        }
+        if player.stop_flag.load(Ordering::SeqCst) {
+            break;
+        }
 
-        // Symphonia: demux + decode in one pass.
-        let decoded = match decode_segment(init_bytes.as_deref(), &bytes) {
-            Ok(d) => d,
+        // End of a VOD stream: join the queued one on, if there is one.
+        let pending = next.take().or_else(|| {
+            let url = player.next_url.lock().unwrap().take()?;
+            Some(tokio::spawn(open_next(
+                client.clone(),
+                url,
+                player.stop_flag.clone(),
+            )))
+        });
+        let Some(pending) = pending else { break };
+        let padding = (stream.trim.padding * holdback.channels).min(holdback.samples.len());
+        holdback.samples.truncate(holdback.samples.len() - padding);
+        let (next_stream, head) = match pending.await {
+            Ok(Ok(opened)) => opened,
+            Ok(Err(e)) => {
+                tracing::warn!("hls: open next stream failed: {e:#}");
+                break;
+            }
             Err(e) => {
-                tracing::warn!("hls: decode seg {} failed: {e}; skipping", seg.seq);
-                next_play_seq += 1;
-                continue;
+                tracing::warn!("hls: open next stream: {e}");
+                break;
             }
         };
+        stream = next_stream;
 
-        // Pause-honoring write: chunk the PCM so we can check paused/stop
-        // between writes without holding up sink callbacks indefinitely.
-        if decoded.sample_rate != current_sample_rate && decoded.sample_rate != 0 {
-            current_sample_rate = decoded.sample_rate;
-            output::set_sample_rate(decoded.sample_rate);
-        }
-        let ch = decoded.channels.max(1) as usize;
-        let chunk_frames = decoded.sample_rate.max(1) as usize / 20; // ~50 ms
-        let chunk_samples = chunk_frames * ch;
-        for window in decoded.samples.chunks(chunk_samples.max(ch)) {
-            if player.stop_flag.load(Ordering::SeqCst) {
-                break;
+        match head {
+            Some(head)
+                if head.sample_rate == holdback.sample_rate
+                    && head.channels.max(1) as usize == holdback.channels =>
+            {
+                let tail = std::mem::take(&mut holdback.samples);
+                let ch = holdback.channels;
+                let joined = transition::join(
+                    &tail,
+                    &head.samples,
+                    ch,
+                    holdback.sample_rate,
+                    &transition::config(),
+                );
+                let head_start = (joined.len() - head.samples.len()) / ch;
+                player.start_stream(
+                    &stream,
+                    head_start as i64 * 1000 / holdback.sample_rate.max(1) as i64,
+                );
+                holdback.samples = joined;
             }
-            while player.paused.load(Ordering::SeqCst) && !player.stop_flag.load(Ordering::SeqCst) {
-                tokio::time::sleep(Duration::from_millis(50)).await;
+            head => {
+                // Different format (or nothing decodable): play back to back.
+                holdback.flush(player).await;
+                player.start_stream(&stream, 0);
+                if let Some(head) = head {
+                    holdback.push(player, head, GAPLESS_HOLDBACK_FRAMES).await;
+                }
             }
-            output::write_pcm(window);
-            let frames_pushed = window.len() / ch;
-            let ms = (frames_pushed as i64 * 1000) / decoded.sample_rate.max(1) as i64;
-            player.position_ms.fetch_add(ms, Ordering::SeqCst);
         }
-
-        // Prefetch the next-next segment so we stay ahead.
-        let upcoming: Vec<_> = {
-            let g = known.lock().unwrap();
-            g.iter()
-                .filter(|s| s.seq > next_play_seq && s.seq <= next_play_seq + 3)
-                .cloned()
-                .collect()
-        };
-        fetcher::prefetch(client.clone(), cache.clone(), upcoming).await;
-
-        next_play_seq += 1;
     }
 
-    if let Some(h) = refresher {
+    if let Some(h) = next {
         h.abort();
     }
+    holdback.flush(player).await;
     Ok(())
 }
 
@@ -372,7 +784,7 @@ pub fn is_active() -> bool {
 /// gRPC base URL of the broadcaster that owns the currently-playing HLS/DASH
 /// stream (e.g. `http://192.168.1.42:6061`). None if no active session.
 pub fn remote_api_base() -> Option<String> {
-    current().map(|p| p.remote_api_base.clone())
+    current().map(|p| p.remote_api_base())
 }
 
 /// Derive `http://<host>:6061` from any HLS / DASH URL. Falls back to
@@ -404,6 +816,20 @@ pub fn play(url: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Queue an HLS or DASH URL to follow the current VOD stream, gapless or
+/// crossfaded as `transition::config()` says. Replaces anything queued
+/// before. Errs if nothing is playing, so the caller can `play` instead.
+pub fn queue(url: &str) -> Result<(), String> {
+    if manifest::is_hls_or_dash_url(url).is_none() {
+        return Err(format!("not an HLS or DASH URL: {url}"));
+    }
+    let p = current()
+        .filter(|_| is_active())
+        .ok_or_else(|| "no HLS/DASH stream is playing".to_string())?;
+    *p.next_url.lock().unwrap() = Some(url.to_string());
+    Ok(())
+}
+
 pub fn pause() -> bool {
     if let Some(p) = current() {
         p.paused.store(true, Ordering::SeqCst);
@@ -424,6 +850,39 @@ pub fn resume() -> bool {
     }
 }
 
+/// Seek a live stream to `position_ms` into its time-shift window, 0 being
+/// the oldest segment still listed or on disk (`timeshift_start_ms` in the
+/// status). Positions past the live edge land on it. Errs for VOD streams,
+/// which can't seek yet.
+pub fn seek(position_ms: i64) -> Result<(), String> {
+    let p = current().ok_or_else(|| "no HLS/DASH stream is playing".to_string())?;
+    let timeline = p
+        .timeline
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "only live streams can seek".to_string())?;
+    let start = timeline
+        .lock()
+        .unwrap()
+        .front()
+        .and_then(|s| s.program_time_ms)
+        .ok_or_else(|| "live stream has no program time yet".to_string())?;
+    *p.seek_to.lock().unwrap() = Some(SeekTarget::ProgramTime(start + position_ms.max(0)));
+    Ok(())
+}
+
+/// Jump a live stream back to the live edge. Leaves it paused if it was.
+pub fn go_live() -> bool {
+    match current() {
+        Some(p) if p.is_live.load(Ordering::SeqCst) => {
+            *p.seek_to.lock().unwrap() = Some(SeekTarget::LiveEdge);
+            true
+        }
+        _ => false,
+    }
+}
+
 pub fn stop() -> bool {
     let mut g = PLAYER.lock().unwrap();
     if let Some(p) = g.take() {
@@ -447,7 +906,13 @@ pub fn status_json() -> String {
     };
     obj.insert("state".into(), serde_json::Value::String(state_str.into()));
     if let Some(p) = p.as_ref() {
-        obj.insert("url".into(), serde_json::Value::String(p.url.clone()));
+        obj.insert(
+            "url".into(),
+            serde_json::Value::String(p.url.lock().unwrap().clone()),
+        );
+        if let Some(next) = p.next_url.lock().unwrap().clone() {
+            obj.insert("next_url".into(), serde_json::Value::String(next));
+        }
         obj.insert(
             "position_ms".into(),
             serde_json::Value::Number(p.position_ms.load(Ordering::SeqCst).into()),
@@ -460,6 +925,9 @@ pub fn status_json() -> String {
             "is_live".into(),
             serde_json::Value::Bool(p.is_live.load(Ordering::SeqCst)),
         );
+        if let Some(timeline) = p.timeline.lock().unwrap().clone() {
+            insert_live_status(&mut obj, p, &timeline.lock().unwrap());
+        }
         if let Some(e) = p.last_error.lock().unwrap().clone() {
             obj.insert("error".into(), serde_json::Value::String(e));
         }
@@ -467,6 +935,36 @@ pub fn status_json() -> String {
     serde_json::to_string(&obj).unwrap_or_else(|_| "{}".to_string())
 }
 
+/// Where a live stream's time-shift window starts and ends, in Unix ms, and
+/// where in it playback is.
+fn insert_live_status(
+    obj: &mut serde_json::Map<String, serde_json::Value>,
+    p: &Player,
+    timeline: &VecDeque<SegmentRef>,
+) {
+    let start = timeline.front().and_then(|s| s.program_time_ms);
+    let edge = timeline
+        .back()
+        .and_then(|s| Some(s.program_time_ms? + (s.duration * 1000.0) as i64));
+    let (Some(start), Some(edge)) = (start, edge) else {
+        return;
+    };
+    obj.insert("timeshift_start_ms".into(), start.into());
+    obj.insert("live_edge_ms".into(), edge.into());
+    let at = p.program_time_ms.load(Ordering::SeqCst);
+    if at == NO_PROGRAM_TIME {
+        return;
+    }
+    // Allow a segment's slack for the manifest refresh.
+    let slack = timeline.back().map_or(0, |s| (s.duration * 1000.0) as i64);
+    let at_live_edge = live_start(timeline)
+        .and_then(|s| s.program_time_ms)
+        .is_some_and(|live| at >= live - slack);
+    obj.insert("program_time_ms".into(), at.into());
+    obj.insert("behind_live_ms".into(), (edge - at).max(0).into());
+    obj.insert("at_live_edge".into(), at_live_edge.into());
+}
+
 // ---------------------------------------------------------------------------
 // FFI surface — thin wrappers over the Rust API above.
 // ---------------------------------------------------------------------------
@@ -489,6 +987,25 @@ pub extern "C" fn rb_hls_play(url: *const std::os::raw::c_char) -> std::os::raw:
     }
 }
 
+/// Queue a URL to follow the current stream. Returns 0 on success, -3 if
+/// nothing is playing or the URL isn't a manifest.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn rb_hls_queue(url: *const std::os::raw::c_char) -> std::os::raw::c_int {
+    if url.is_null() {
+        return -1;
+    }
+    let url = unsafe { CStr::from_ptr(url) };
+    let url = match url.to_str() {
+        Ok(s) => s,
+        Err(_) => return -2,
+    };
+    match queue(url) {
+        Ok(()) => 0,
+        Err(_) => -3,
+    }
+}
+
 #[cfg(feature = "ffi")]
 #[no_mangle]
 pub extern "C" fn rb_hls_pause() -> std::os::raw::c_int {
@@ -519,21 +1036,38 @@ pub extern "C" fn rb_hls_stop() -> std::os::raw::c_int {
     }
 }
 
-/// VOD seek by milliseconds. Live streams ignore the seek and return -2.
+/// Seek a live stream to `position_ms` into its time-shift window, see
+/// `seek`. Returns -2 while the stream has no program time yet, and -3 for
+/// VOD: not implemented yet, so the caller can surface "seek not yet
+/// supported" rather than silently failing.
 #[cfg(feature = "ffi")]
 #[no_mangle]
-pub extern "C" fn rb_hls_seek(_position_ms: i64) -> std::os::raw::c_int {
+pub extern "C" fn rb_hls_seek(position_ms: i64) -> std::os::raw::c_int {
     let Some(p) = current() else { return -1 };
-    if p.is_live.load(Ordering::SeqCst) {
-        return -2;
+    if !p.is_live.load(Ordering::SeqCst) {
+        return -3;
+    }
+    match seek(position_ms) {
+        Ok(()) => 0,
+        Err(_) => -2,
+    }
+}
+
+/// Jump a live stream back to the live edge. Returns -1 when no live stream
+/// is playing.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn rb_hls_go_live() -> std::os::raw::c_int {
+    if go_live() {
+        0
+    } else {
+        -1
     }
-    // First-cut: not implemented. Returning -3 lets the caller surface a
-    // "seek not yet supported" rather than silently failing. Once we wire
-    // seek-by-segment-index this becomes the normal entry point.
-    -3
 }
 
-/// Returns a JSON status blob: `{state, position_ms, duration_ms, is_live, url, error?}`.
+/// Returns a JSON status blob: `{state, position_ms, duration_ms, is_live, url, error?}`,
+/// plus `{timeshift_start_ms, live_edge_ms, program_time_ms, behind_live_ms,
+/// at_live_edge}` for live streams, times in Unix ms.
 /// Caller MUST `rb_hls_free_string` the result.
 #[cfg(feature = "ffi")]
 #[no_mangle]
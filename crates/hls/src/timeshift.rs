@@ -0,0 +1,147 @@
+//! On-disk time-shift buffer for live streams.
+//!
+//! Every segment a live stream publishes is written to disk as soon as the
+//! refresher sees it, whether or not it has been played yet, and kept for
+//! `window_secs()`. That is what lets a paused stream resume where it was
+//! and a listener seek back further than the manifest itself still lists.
+//! Each stream gets its own directory under `~/.cache/rockbox/hls-timeshift`,
+//! removed again when the stream is dropped.
+
+use bytes::Bytes;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Seconds of each live stream kept unless configured otherwise.
+pub const DEFAULT_WINDOW_SECS: u32 = 1800;
+
+static WINDOW_SECS: AtomicU32 = AtomicU32::new(DEFAULT_WINDOW_SECS);
+
+/// Tells apart the directories of streams open at the same time.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+pub fn window_secs() -> u32 {
+    WINDOW_SECS.load(Ordering::SeqCst)
+}
+
+/// Keep `secs` seconds of the live streams opened from now on. 0 turns the
+/// buffer off: pausing then holds only what is cached in memory.
+pub fn set_window_secs(secs: u32) {
+    WINDOW_SECS.store(secs, Ordering::SeqCst);
+}
+
+pub(crate) struct TimeShift {
+    dir: PathBuf,
+    window: f64,
+    /// Sequence number and duration of each stored segment, oldest first.
+    stored: Mutex<VecDeque<(u64, f64)>>,
+}
+
+impl TimeShift {
+    /// A buffer for a newly opened stream, or None when it is turned off or
+    /// its directory can't be created.
+    pub(crate) fn create() -> Option<TimeShift> {
+        let window = window_secs();
+        if window == 0 {
+            return None;
+        }
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("rockbox")
+            .join("hls-timeshift")
+            .join(format!("{}-{id}", std::process::id()));
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            tracing::warn!("hls: time-shift dir {}: {e}", dir.display());
+            return None;
+        }
+        Some(TimeShift {
+            dir,
+            window: window as f64,
+            stored: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    fn path(&self, seq: u64) -> PathBuf {
+        self.dir.join(format!("{seq}.seg"))
+    }
+
+    pub(crate) fn contains(&self, seq: u64) -> bool {
+        self.stored.lock().unwrap().iter().any(|(s, _)| *s == seq)
+    }
+
+    /// Sequence number of the oldest segment still stored.
+    pub(crate) fn first_seq(&self) -> Option<u64> {
+        self.stored.lock().unwrap().front().map(|(s, _)| *s)
+    }
+
+    /// Store segment `seq`, dropping the oldest ones past the window. The
+    /// files are written and removed outside the lock.
+    pub(crate) async fn put(&self, seq: u64, duration: f64, bytes: &[u8]) {
+        if self.contains(seq) {
+            return;
+        }
+        if let Err(e) = tokio::fs::write(self.path(seq), bytes).await {
+            tracing::warn!("hls: time-shift write seg {seq}: {e}");
+            return;
+        }
+        let evicted = {
+            let mut stored = self.stored.lock().unwrap();
+            if stored.iter().any(|(s, _)| *s == seq) {
+                return;
+            }
+            // Segments fetched on a seek back can land behind newer ones.
+            let at = stored.partition_point(|(s, _)| *s < seq);
+            stored.insert(at, (seq, duration));
+            let mut total: f64 = stored.iter().map(|(_, d)| d).sum();
+            let mut evicted = Vec::new();
+            while total > self.window && stored.len() > 1 {
+                let (old, d) = stored.pop_front().unwrap();
+                total -= d;
+                evicted.push(old);
+            }
+            evicted
+        };
+        for old in evicted {
+            let _ = tokio::fs::remove_file(self.path(old)).await;
+        }
+    }
+
+    pub(crate) async fn get(&self, seq: u64) -> Option<Bytes> {
+        if !self.contains(seq) {
+            return None;
+        }
+        tokio::fs::read(self.path(seq)).await.ok().map(Bytes::from)
+    }
+}
+
+impl Drop for TimeShift {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn keeps_the_newest_segments_within_the_window() {
+        set_window_secs(5);
+        let ts = TimeShift::create().unwrap();
+        set_window_secs(DEFAULT_WINDOW_SECS);
+        // 12 arrives late, as after a seek back.
+        for seq in [10u64, 11, 13, 12] {
+            ts.put(seq, 2.0, &[seq as u8; 4]).await;
+        }
+        assert_eq!(ts.first_seq(), Some(12));
+        assert_eq!(ts.get(13).await.as_deref(), Some(&[13u8; 4][..]));
+        assert!(ts.get(10).await.is_none());
+        assert!(!ts.path(10).exists());
+
+        let dir = ts.dir.clone();
+        drop(ts);
+        assert!(!dir.exists());
+    }
+}
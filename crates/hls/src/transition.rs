@@ -0,0 +1,308 @@
+//! Transitions between consecutive streams.
+//!
+//! The player holds back the end of the stream it is playing, and when the
+//! next one is ready joins the two here: back to back (gapless, after the
+//! encoder delay and padding have been trimmed), crossfaded over a fixed
+//! time with one of a few gain curves, or overlapped MixRamp-style.
+//!
+//! MixRamp follows MPD: the incoming stream starts so that the point where
+//! it gets louder than `mixramp_db` lines up with the point where the
+//! outgoing stream drops below it, less `mixramp_delay` seconds of overlap.
+//! MPD reads those points from tags; here they are measured on the PCM, in
+//! 50 ms blocks. The two are summed without fading, since both ends are
+//! quiet by construction.
+
+use std::{f32::consts::FRAC_PI_2, str::FromStr, sync::RwLock};
+
+/// Longest crossfade accepted.
+pub const MAX_CROSSFADE_SECS: f32 = 30.0;
+
+/// How much of the outgoing stream MixRamp looks at.
+pub const MIXRAMP_WINDOW_SECS: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Curve {
+    /// Constant power: cos / sin. Keeps the loudness steady across the fade.
+    #[default]
+    EqualPower,
+    /// Straight lines; dips in the middle with uncorrelated material.
+    Linear,
+    /// Slow at both ends, quick through the middle.
+    SCurve,
+}
+
+impl Curve {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Curve::EqualPower => "equal_power",
+            Curve::Linear => "linear",
+            Curve::SCurve => "s_curve",
+        }
+    }
+
+    /// Gains of the outgoing and incoming stream `t` (0 to 1) of the way
+    /// through the fade.
+    fn gains(&self, t: f32) -> (f32, f32) {
+        match self {
+            Curve::EqualPower => ((t * FRAC_PI_2).cos(), (t * FRAC_PI_2).sin()),
+            Curve::Linear => (1.0 - t, t),
+            Curve::SCurve => {
+                let fade_in = (1.0 - (t * std::f32::consts::PI).cos()) / 2.0;
+                (1.0 - fade_in, fade_in)
+            }
+        }
+    }
+}
+
+impl FromStr for Curve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "equal_power" => Ok(Curve::EqualPower),
+            "linear" => Ok(Curve::Linear),
+            "s_curve" => Ok(Curve::SCurve),
+            _ => Err(format!("unknown crossfade curve: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransitionConfig {
+    /// Seconds the streams overlap; 0 plays them back to back.
+    pub crossfade_secs: f32,
+    pub curve: Curve,
+    /// Loudness, in dBFS, MixRamp lines the streams up on.
+    pub mixramp_db: f32,
+    /// Seconds taken off the MixRamp overlap. Negative turns MixRamp off,
+    /// like MPD's `nan`.
+    pub mixramp_delay: f32,
+}
+
+const DEFAULT_CONFIG: TransitionConfig = TransitionConfig {
+    crossfade_secs: 0.0,
+    curve: Curve::EqualPower,
+    mixramp_db: 0.0,
+    mixramp_delay: -1.0,
+};
+
+impl Default for TransitionConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+impl TransitionConfig {
+    pub fn mixramp(&self) -> bool {
+        self.mixramp_delay >= 0.0
+    }
+
+    /// Frames of the outgoing stream a join at `rate` can reach into.
+    pub fn holdback_frames(&self, rate: u32) -> usize {
+        let secs = match self.mixramp() {
+            true => MIXRAMP_WINDOW_SECS,
+            false => self.crossfade_secs,
+        };
+        (secs * rate as f32) as usize
+    }
+}
+
+static CONFIG: RwLock<TransitionConfig> = RwLock::new(DEFAULT_CONFIG);
+
+pub fn config() -> TransitionConfig {
+    *CONFIG.read().unwrap()
+}
+
+/// Switch to `config` from the next transition on. The crossfade is clamped
+/// to `0..=MAX_CROSSFADE_SECS`.
+pub fn set_config(mut config: TransitionConfig) {
+    config.crossfade_secs = match config.crossfade_secs.is_finite() {
+        true => config.crossfade_secs.clamp(0.0, MAX_CROSSFADE_SECS),
+        false => 0.0,
+    };
+    if !config.mixramp_delay.is_finite() {
+        config.mixramp_delay = -1.0;
+    }
+    *CONFIG.write().unwrap() = config;
+}
+
+/// Join the held-back end of one stream to the start of the next, both
+/// interleaved S16 with the same rate and channel count. Returns the PCM to
+/// play in their place.
+pub fn join(
+    tail: &[i16],
+    head: &[i16],
+    channels: usize,
+    rate: u32,
+    config: &TransitionConfig,
+) -> Vec<i16> {
+    let channels = channels.max(1);
+    let tail_frames = tail.len() / channels;
+    let head_frames = head.len() / channels;
+    if config.mixramp() {
+        let start = mixramp_start(tail, head, channels, rate, config);
+        return overlap(tail, head, channels, start, |_| (1.0, 1.0));
+    }
+    let fade = ((config.crossfade_secs * rate as f32) as usize)
+        .min(tail_frames)
+        .min(head_frames);
+    let curve = config.curve;
+    overlap(tail, head, channels, tail_frames - fade, |t| curve.gains(t))
+}
+
+/// Start `head` `start` frames into `tail`, mixing where they overlap with
+/// the gains `gains(t)` returns `t` of the way through the overlap.
+fn overlap(
+    tail: &[i16],
+    head: &[i16],
+    channels: usize,
+    start: usize,
+    gains: impl Fn(f32) -> (f32, f32),
+) -> Vec<i16> {
+    let tail_frames = tail.len() / channels;
+    let head_frames = head.len() / channels;
+    let frames = (tail_frames - start).min(head_frames);
+    let mut out = Vec::with_capacity(tail.len() + head.len() - frames * channels);
+    out.extend_from_slice(&tail[..start * channels]);
+    for f in 0..frames {
+        let (out_gain, in_gain) = gains(f as f32 / frames as f32);
+        for c in 0..channels {
+            let a = tail[(start + f) * channels + c] as f32;
+            let b = head[f * channels + c] as f32;
+            let mixed = a * out_gain + b * in_gain;
+            out.push(mixed.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16);
+        }
+    }
+    // A head shorter than the overlap leaves the rest of the tail to play.
+    out.extend_from_slice(&tail[(start + frames) * channels..tail_frames * channels]);
+    out.extend_from_slice(&head[frames * channels..head_frames * channels]);
+    out
+}
+
+/// Loudness of `frames` frames from `frame` on, in dBFS.
+fn loudness(pcm: &[i16], channels: usize, frame: usize, frames: usize) -> f32 {
+    let block = &pcm[frame * channels..((frame + frames) * channels).min(pcm.len())];
+    if block.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let power = block
+        .iter()
+        .map(|&s| (s as f32 / 32768.0).powi(2))
+        .sum::<f32>()
+        / block.len() as f32;
+    10.0 * power.log10()
+}
+
+/// Frame of `tail` the MixRamp-aligned `head` starts at.
+fn mixramp_start(
+    tail: &[i16],
+    head: &[i16],
+    channels: usize,
+    rate: u32,
+    config: &TransitionConfig,
+) -> usize {
+    let block = (rate as usize / 20).max(1);
+    let tail_frames = tail.len() / channels;
+    let head_frames = head.len() / channels;
+    // Where the tail last is at least as loud as the threshold...
+    let tail_point = (0..tail_frames.div_ceil(block))
+        .rev()
+        .find(|b| loudness(tail, channels, b * block, block) >= config.mixramp_db)
+        .map(|b| ((b + 1) * block).min(tail_frames))
+        .unwrap_or(0);
+    // ...and where the head first gets there.
+    let head_point = (0..head_frames.div_ceil(block))
+        .find(|b| loudness(head, channels, b * block, block) >= config.mixramp_db)
+        .map(|b| b * block)
+        .unwrap_or(0);
+    let delay = (config.mixramp_delay * rate as f32) as usize;
+    (tail_point.saturating_sub(head_point) + delay).min(tail_frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crossfade(crossfade_secs: f32, curve: Curve) -> TransitionConfig {
+        TransitionConfig {
+            crossfade_secs,
+            curve,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn back_to_back_without_a_crossfade() {
+        let tail = [1, -1, 2, -2];
+        let head = [3, -3];
+        assert_eq!(
+            join(&tail, &head, 2, 10, &crossfade(0.0, Curve::Linear)),
+            [1, -1, 2, -2, 3, -3]
+        );
+    }
+
+    #[test]
+    fn crossfades_over_the_configured_time() {
+        // 1 s at 100 Hz, mono.
+        let tail = vec![10_000i16; 100];
+        let head = vec![-10_000i16; 100];
+        let out = join(&tail, &head, 1, 100, &crossfade(0.5, Curve::Linear));
+        assert_eq!(out.len(), 150);
+        assert_eq!(out[49], 10_000);
+        assert_eq!(out[50], 10_000);
+        assert_eq!(out[75], 0);
+        assert!(out[99] < -9_000);
+        assert_eq!(out[100], -10_000);
+
+        // Equal power keeps two uncorrelated streams' level constant, which
+        // for identical ones shows up as a bump of up to 3 dB.
+        let out = join(&tail, &tail, 1, 100, &crossfade(0.5, Curve::EqualPower));
+        assert_eq!(out[75], 14_142);
+
+        // A crossfade longer than either stream is limited to the shorter.
+        let out = join(&tail[..20], &head, 1, 100, &crossfade(5.0, Curve::SCurve));
+        assert_eq!(out.len(), 100);
+    }
+
+    #[test]
+    fn mixramp_lines_the_threshold_crossings_up() {
+        let rate = 100;
+        // Loud for 3 s, then 1 s of near silence.
+        let mut tail = vec![16_000i16; 300];
+        tail.extend(vec![10i16; 100]);
+        // 0.5 s of near silence, then loud.
+        let mut head = vec![10i16; 50];
+        head.extend(vec![16_000i16; 100]);
+
+        let mixramp = TransitionConfig {
+            mixramp_db: -20.0,
+            mixramp_delay: 0.0,
+            ..Default::default()
+        };
+        let out = join(&tail, &head, 1, rate, &mixramp);
+        // The head starts at 2.5 s, so its loud part starts at 3 s.
+        assert_eq!(out.len(), 250 + 150);
+        assert_eq!(out[249], 16_000);
+        assert_eq!(out[250], 16_010);
+        assert_eq!(out[300], 16_010);
+
+        let delayed = TransitionConfig {
+            mixramp_delay: 0.2,
+            ..mixramp
+        };
+        assert_eq!(join(&tail, &head, 1, rate, &delayed).len(), 270 + 150);
+    }
+
+    #[test]
+    fn config_is_clamped() {
+        set_config(TransitionConfig {
+            crossfade_secs: 120.0,
+            mixramp_delay: f32::NAN,
+            ..Default::default()
+        });
+        let config = config();
+        assert_eq!(config.crossfade_secs, MAX_CROSSFADE_SECS);
+        assert!(!config.mixramp());
+        set_config(TransitionConfig::default());
+    }
+}
@@ -22,6 +22,23 @@ pub struct JellyfinEntry {
     pub stream_url: Option<String>,
 }
 
+/// An audio item with the tags needed to index it.
+#[derive(Debug, Clone)]
+pub struct JellyfinTrack {
+    pub id: String,
+    pub title: String,
+    pub artist: String,
+    pub album_artist: String,
+    pub album: String,
+    pub year: Option<u32>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    /// Length in milliseconds.
+    pub duration: u32,
+    pub genre: Option<String>,
+    pub stream_url: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 struct DiscoveryResponse {
@@ -63,6 +80,33 @@ struct JellyfinItem {
     item_type: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AudioItemsResponse {
+    items: Vec<AudioItem>,
+    #[serde(default)]
+    total_record_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AudioItem {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    artists: Vec<String>,
+    album_artist: Option<String>,
+    album: Option<String>,
+    production_year: Option<u32>,
+    index_number: Option<u32>,
+    parent_index_number: Option<u32>,
+    /// 100 ns units.
+    run_time_ticks: Option<u64>,
+    #[serde(default)]
+    genres: Vec<String>,
+}
+
 /// Discover Jellyfin servers on the local network via UDP broadcast.
 pub async fn discover_jellyfin_servers() -> Vec<JellyfinServer> {
     tokio::task::spawn_blocking(|| {
@@ -267,6 +311,62 @@ pub async fn browse_items(
         .collect()
 }
 
+/// Every audio item visible to a user, for mirroring the server's
+/// catalogue. Fails instead of returning an empty list, so a server that
+/// is down is not mistaken for an empty one.
+pub async fn list_audio_items(
+    base_url: &str,
+    token: &str,
+    user_id: &str,
+) -> Result<Vec<JellyfinTrack>, anyhow::Error> {
+    const PAGE: usize = 500;
+    let base = base_url.trim_end_matches('/');
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let mut tracks = vec![];
+    loop {
+        let url = format!(
+            "{}/Users/{}/Items?IncludeItemTypes=Audio&Recursive=true\
+             &Fields=Genres,ProductionYear&SortBy=SortName&StartIndex={}&Limit={}",
+            base,
+            user_id,
+            tracks.len(),
+            PAGE
+        );
+        let resp = client
+            .get(&url)
+            .header("X-Emby-Token", token)
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            anyhow::bail!("HTTP {status}");
+        }
+        let body: AudioItemsResponse = resp.json().await?;
+        let count = body.items.len();
+        tracks.extend(body.items.into_iter().map(|item| {
+            let artist = item.artists.join(", ");
+            JellyfinTrack {
+                stream_url: format!("{}/Items/{}/Download?api_key={}", base, item.id, token),
+                id: item.id,
+                title: item.name,
+                album_artist: item.album_artist.unwrap_or_else(|| artist.clone()),
+                artist,
+                album: item.album.unwrap_or_default(),
+                year: item.production_year,
+                track_number: item.index_number,
+                disc_number: item.parent_index_number,
+                duration: (item.run_time_ticks.unwrap_or(0) / 10_000) as u32,
+                genre: item.genres.into_iter().next(),
+            }
+        }));
+        if count < PAGE || tracks.len() >= body.total_record_count {
+            return Ok(tracks);
+        }
+    }
+}
+
 async fn fetch_jellyfin_items(base_url: &str, token: &str, url: &str) -> Vec<JellyfinItem> {
     let client = match reqwest::Client::builder()
         .timeout(Duration::from_secs(10))
@@ -422,6 +422,18 @@ pub struct BaseItemDto {
     /// in `EntryIds=` for remove/move calls.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub playlist_item_id: Option<String>,
+    /// Chapter markers of audiobook tracks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapters: Option<Vec<ChapterInfo>>,
+}
+
+/// `ChapterInfo` — `StartPositionTicks` is 100-ns ticks per spec.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ChapterInfo {
+    pub start_position_ticks: i64,
+    pub name: Option<String>,
+    pub image_date_modified: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -12,10 +12,11 @@ use std::path::PathBuf;
 
 use super::auth::{self, AuthedUser, EmbyAuth};
 use super::dto::{
-    AuthenticationResult, BaseItemDto, ImageBlurHashes, ImageProviderInfo, ImageTags, ItemsResult,
-    MediaSource, MediaStream, NameGuidPair, PlaybackInfoResponse, PlaylistCreationResult,
-    PublicSystemInfo, RemoteImageInfo, RemoteImageResult, SessionInfoDto, SystemInfo,
-    UserConfiguration, UserDto, UserItemDataDto, UserPolicy, ViewsResult, JELLYFIN_API_VERSION,
+    AuthenticationResult, BaseItemDto, ChapterInfo, ImageBlurHashes, ImageProviderInfo, ImageTags,
+    ItemsResult, MediaSource, MediaStream, NameGuidPair, PlaybackInfoResponse,
+    PlaylistCreationResult, PublicSystemInfo, RemoteImageInfo, RemoteImageResult, SessionInfoDto,
+    SystemInfo, UserConfiguration, UserDto, UserItemDataDto, UserPolicy, ViewsResult,
+    JELLYFIN_API_VERSION,
 };
 use super::mapping;
 use super::JellyfinState;
@@ -593,6 +594,20 @@ async fn track_to_dto(state: &JellyfinState, t: &Track) -> BaseItemDto {
 
     let user_data = user_data_for(state, mapping::KIND_TRACK, &t.id, &id).await;
 
+    let chapters = repo::chapter::find_by_track(state.pool.clone(), &t.id)
+        .await
+        .unwrap_or_default();
+    let chapters = (!chapters.is_empty()).then(|| {
+        chapters
+            .into_iter()
+            .map(|c| ChapterInfo {
+                start_position_ticks: (c.start_ms as i64) * TICKS_PER_MS,
+                name: Some(c.title),
+                image_date_modified: "0001-01-01T00:00:00.0000000Z".to_string(),
+            })
+            .collect()
+    });
+
     let media_source = MediaSource {
         protocol: "File",
         id: Some(id.clone()),
@@ -661,6 +676,7 @@ async fn track_to_dto(state: &JellyfinState, t: &Track) -> BaseItemDto {
         }),
         image_blur_hashes: Some(ImageBlurHashes::default()),
         user_data: Some(user_data),
+        chapters,
         ..Default::default()
     }
 }
@@ -2130,8 +2146,8 @@ async fn resolve_audio_track(state: &JellyfinState, guid: &str) -> Option<Track>
     repo::track::find(state.pool.clone(), &native).await.ok()?
 }
 
-/// `GET /Audio/{itemId}/Lyrics` — returns the parsed `LyricDto` for the
-/// audio item, or 404 if no sidecar is on disk.
+/// `GET /Audio/{itemId}/Lyrics` — returns the `LyricDto` for the audio
+/// item (sidecar or embedded tags), or 404 if it has none.
 pub async fn get_lyrics(
     _user: AuthedUser,
     state: web::Data<JellyfinState>,
@@ -2140,13 +2156,13 @@ pub async fn get_lyrics(
     let Some(track) = resolve_audio_track(&state, &path.into_inner()).await else {
         return HttpResponse::NotFound().finish();
     };
-    let track_path = PathBuf::from(&track.path);
-    let Some(sidecar) = super::lyrics::find_sidecar(&track_path) else {
-        return HttpResponse::NotFound().finish();
-    };
-    match super::lyrics::parse_sidecar(&sidecar) {
-        Some(dto) => HttpResponse::Ok().json(dto),
-        None => HttpResponse::NotFound().finish(),
+    match rockbox_library::lyrics::get_lyrics(state.pool.clone(), &track).await {
+        Ok(Some(lyrics)) => HttpResponse::Ok().json(super::lyrics::to_dto(&lyrics)),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(e) => {
+            tracing::error!("jellyfin: get_lyrics {}: {e}", track.id);
+            HttpResponse::InternalServerError().finish()
+        }
     }
 }
 
@@ -2174,17 +2190,16 @@ pub async fn upload_lyrics(
         tracing::error!("jellyfin: upload_lyrics {}: {e}", track.id);
         return HttpResponse::InternalServerError().finish();
     }
+    let _ = repo::lyrics::delete(state.pool.clone(), &track.id).await;
     // Return the freshly-parsed lyrics so clients don't need a follow-up GET.
-    let Some(sidecar) = super::lyrics::find_sidecar(&track_path) else {
-        return HttpResponse::NoContent().finish();
-    };
-    match super::lyrics::parse_sidecar(&sidecar) {
-        Some(dto) => HttpResponse::Ok().json(dto),
-        None => HttpResponse::NoContent().finish(),
+    match rockbox_library::lyrics::get_lyrics(state.pool.clone(), &track).await {
+        Ok(Some(lyrics)) => HttpResponse::Ok().json(super::lyrics::to_dto(&lyrics)),
+        _ => HttpResponse::NoContent().finish(),
     }
 }
 
-/// `DELETE /Audio/{itemId}/Lyrics` — remove sidecars. Idempotent.
+/// `DELETE /Audio/{itemId}/Lyrics` — remove sidecars. Idempotent. Lyrics
+/// embedded in the file's tags are left alone.
 pub async fn delete_lyrics(
     _user: AuthedUser,
     state: web::Data<JellyfinState>,
@@ -2194,7 +2209,8 @@ pub async fn delete_lyrics(
         return HttpResponse::NotFound().finish();
     };
     let track_path = PathBuf::from(&track.path);
-    super::lyrics::delete_sidecar(&track_path);
+    rockbox_library::lyrics::delete_sidecar(&track_path);
+    let _ = repo::lyrics::delete(state.pool.clone(), &track.id).await;
     HttpResponse::NoContent().finish()
 }
 
@@ -2271,8 +2287,9 @@ async fn build_similar_response(
 
 /// `GET /Items/{itemId}/Similar` — the OpenAPI spec's generic
 /// dispatcher. Peeks at the item kind and calls into the plugin
-/// orchestrator; returns an empty `ItemsResult` when Last.fm is not
-/// configured or when the kind isn't one the orchestrator supports.
+/// orchestrator; returns an empty `ItemsResult` when the kind isn't one
+/// the orchestrator supports, or for artists and albums when Last.fm is
+/// not configured.
 pub async fn similar_items(
     _user: AuthedUser,
     state: web::Data<JellyfinState>,
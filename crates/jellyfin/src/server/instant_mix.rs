@@ -6,10 +6,12 @@
 //! deterministic-but-shuffled expansion:
 //!
 //! 1. The seed's own tracks (or the seed track itself) come first.
-//! 2. Fill from the seed's artist (same `artist_id`).
-//! 3. Fill from the seed's genre (same `genre_id`).
-//! 4. If still short, pad with a random tail from the whole library.
-//! 5. Shuffle the whole result and truncate to the requested `limit`.
+//! 2. Fill from the tracks that sound closest to the seed, once the
+//!    background acoustic analysis (`rockbox-similarity`) has run.
+//! 3. Fill from the seed's artist (same `artist_id`).
+//! 4. Fill from the seed's genre (same `genre_id`).
+//! 5. If still short, pad with a random tail from the whole library.
+//! 6. Shuffle the whole result and truncate to the requested `limit`.
 //!
 //! Dedup is by native id — the seed track is anchored at position 0 to
 //! keep "start playing this song" behaviour intact.
@@ -20,6 +22,7 @@ use rand::thread_rng;
 use rockbox_library::entity::track::Track;
 use rockbox_library::repo;
 use rockbox_playlists::PlaylistStore;
+use rockbox_similarity::SimilarityStore;
 use sqlx::{Pool, Sqlite};
 
 use super::mapping::{KIND_ALBUM, KIND_ARTIST, KIND_PLAYLIST, KIND_TRACK};
@@ -154,6 +157,21 @@ pub async fn generate(
         _ => return Vec::new(),
     }
 
+    // Fill from acoustic neighbours of the seed tracks.
+    if acc.len() < limit {
+        let seed_ids: Vec<String> = acc.iter().map(|t| t.id.clone()).collect();
+        if let Ok(rows) = SimilarityStore::new(pool.clone())
+            .similar_tracks(&seed_ids, limit - acc.len())
+            .await
+        {
+            for t in rows {
+                if seen.insert(t.id.clone()) {
+                    acc.push(t);
+                }
+            }
+        }
+    }
+
     // Fill from same-artist matches (if we know an artist).
     if let Some(artist_id) = hint_artist.as_ref() {
         fill_from(
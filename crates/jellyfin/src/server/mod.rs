@@ -144,16 +144,20 @@ pub async fn start() -> anyhow::Result<()> {
         }
     });
 
-    HttpServer::new(move || {
+    let mut server = HttpServer::new(move || {
         App::new()
             .app_data(state.clone())
             .wrap(Cors::permissive())
             .configure(configure_routes)
             .default_service(web::to(log_unrouted))
     })
-    .bind(&addr)?
-    .run()
-    .await?;
+    .bind(rockbox_tls::plain_addr(&addr))?;
+    if let Some(tls) = rockbox_tls::tls() {
+        let tls_addr = tls.addr(&addr)?;
+        tracing::info!("Jellyfin API server listening on {tls_addr} (TLS)");
+        server = server.bind_rustls_0_23(tls_addr, tls.server_config())?;
+    }
+    server.run().await?;
 
     Ok(())
 }
@@ -237,8 +241,9 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         .route("/Items/Latest", web::get().to(handlers::items_latest))
         .route("/Items/Prefixes", web::get().to(handlers::items_prefixes))
         // Item-detail rails that clients probe. We have no extras / similar /
-        // intros / chapter markers, so empty results are correct — but they
-        // must be ROUTED so they don't show up in the unrouted-404 log.
+        // intros (chapter markers ride on the item itself), so empty results
+        // are correct — but they must be ROUTED so they don't show up in the
+        // unrouted-404 log.
         .route(
             "/Items/{id}/SpecialFeatures",
             web::get().to(handlers::empty_array),
@@ -408,7 +413,7 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             "/Audio/{id}/universal",
             web::head().to(handlers::audio_universal),
         )
-        // Lyrics — sidecar .lrc/.txt next to the audio file. Registered
+        // Lyrics — sidecar .lrc/.txt or embedded tags. Registered
         // alongside the other /Audio/{id}/… routes; the specific suffix
         // means no risk of shadowing stream/universal.
         .route("/Audio/{id}/Lyrics", web::get().to(handlers::get_lyrics))
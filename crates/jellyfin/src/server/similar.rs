@@ -7,10 +7,13 @@
 //!
 //! Both plugins are optional; when the config carries neither we short
 //! circuit with an empty result — that matches the "only enabled if
-//! tokens are present" requirement in the settings.
+//! tokens are present" requirement in the settings. Track seeds are the
+//! exception: without Last.fm (or when it knows no local match) they fall
+//! back to the offline acoustic neighbours from `rockbox-similarity`.
 
 use rockbox_library::entity::{album::Album, artist::Artist, track::Track};
 use rockbox_library::repo;
+use rockbox_similarity::SimilarityStore;
 use sqlx::{Pool, Sqlite};
 
 use super::lastfm::LastFm;
@@ -33,9 +36,9 @@ impl SimilarResult {
     }
 }
 
-/// Compute similar items for the given seed. Returns an empty
-/// [`SimilarResult`] when Last.fm is not configured — the whole
-/// endpoint is intentionally a no-op without a token.
+/// Compute similar items for the given seed. Artists and albums need
+/// Last.fm and come back empty without it; tracks fall back to their
+/// acoustic neighbours.
 pub async fn similar(
     pool: &Pool<Sqlite>,
     lastfm: Option<&LastFm>,
@@ -44,16 +47,17 @@ pub async fn similar(
     native_id: &str,
     limit: usize,
 ) -> SimilarResult {
-    let Some(lastfm) = lastfm else {
-        return SimilarResult::default();
-    };
     let limit = limit.max(1);
-    match kind {
-        KIND_ARTIST => similar_artists(pool, lastfm, mb, native_id, limit).await,
-        KIND_ALBUM => similar_albums(pool, lastfm, mb, native_id, limit).await,
-        KIND_TRACK => similar_tracks(pool, lastfm, mb, native_id, limit).await,
+    let result = match (lastfm, kind) {
+        (Some(lastfm), KIND_ARTIST) => similar_artists(pool, lastfm, mb, native_id, limit).await,
+        (Some(lastfm), KIND_ALBUM) => similar_albums(pool, lastfm, mb, native_id, limit).await,
+        (Some(lastfm), KIND_TRACK) => similar_tracks(pool, lastfm, mb, native_id, limit).await,
         _ => SimilarResult::default(),
+    };
+    if result.is_empty() && kind == KIND_TRACK {
+        return similar_tracks_acoustic(pool, native_id, limit).await;
     }
+    result
 }
 
 // ── Artists ─────────────────────────────────────────────────────────────────
@@ -219,6 +223,21 @@ async fn similar_tracks(
     }
 }
 
+async fn similar_tracks_acoustic(
+    pool: &Pool<Sqlite>,
+    native_id: &str,
+    limit: usize,
+) -> SimilarResult {
+    let tracks = SimilarityStore::new(pool.clone())
+        .similar_tracks(&[native_id.to_string()], limit)
+        .await
+        .unwrap_or_default();
+    SimilarResult {
+        tracks,
+        ..Default::default()
+    }
+}
+
 async fn find_local_track(pool: &Pool<Sqlite>, title: &str, artist: &str) -> Option<Track> {
     let matches = repo::track::filter(
         pool.clone(),
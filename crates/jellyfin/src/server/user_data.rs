@@ -7,14 +7,27 @@
 //! For tracks we merge with rockbox-playlists' `track_stats` on read so
 //! playback counters from the audio engine appear on the Jellyfin side
 //! without an explicit sync. Writes go only to `jf_user_data` so
-//! Jellyfin edits never disturb the engine's own bookkeeping.
+//! Jellyfin edits never disturb the engine's own bookkeeping. Ratings
+//! of tracks, albums and artists are the exception: they are the
+//! library's own 1–5 stars, shown to Jellyfin on its 0–10 scale.
 
 use anyhow::Result;
 use chrono::{DateTime, TimeZone, Utc};
 use sqlx::{Pool, Row, Sqlite};
 
+use rockbox_library::{entity::rating, ratings, repo};
+
 use super::mapping::KIND_TRACK;
 
+/// Jellyfin rates 0–10; the library 0–5 stars.
+fn jellyfin_rating(stars: u8) -> f64 {
+    stars as f64 * 2.0
+}
+
+fn stars(rating: f64) -> u8 {
+    (rating / 2.0).round().clamp(0.0, rating::MAX_RATING as f64) as u8
+}
+
 /// Rolled-up user data for one item, ready to be serialized as
 /// `UserItemDataDto`.
 #[derive(Debug, Clone, Default)]
@@ -77,6 +90,12 @@ pub async fn get(pool: &Pool<Sqlite>, kind: &str, native_id: &str) -> UserData {
     .map(row_to_user_data)
     .unwrap_or_default();
 
+    if rating::is_kind(kind) {
+        if let Ok(Some(stars)) = repo::rating::find(pool.clone(), kind, native_id).await {
+            ud.rating = Some(jellyfin_rating(stars));
+        }
+    }
+
     // Merge with rockbox-playlists' track_stats for tracks.
     if kind == KIND_TRACK {
         if let Ok(Some(row)) =
@@ -136,7 +155,15 @@ pub async fn update(
         .clone()
         .or(existing.last_played_date.clone());
     let likes = patch.likes.or(existing.likes);
-    let rating = patch.rating.or(existing.rating);
+    let mut rating = patch.rating.or(existing.rating);
+    if rating::is_kind(kind) {
+        if let Some(r) = patch.rating {
+            ratings::set(pool.clone(), kind, native_id, stars(r)).await?;
+        }
+        rating = repo::rating::find(pool.clone(), kind, native_id)
+            .await?
+            .map(jellyfin_rating);
+    }
     let updated_at = Utc::now().to_rfc3339();
 
     sqlx::query(
@@ -17,6 +17,23 @@ pub struct KodiEntry {
     pub stream_url: Option<String>,
 }
 
+/// A song in Kodi's music library, with the tags needed to index it.
+#[derive(Debug, Clone)]
+pub struct KodiSong {
+    pub song_id: u64,
+    pub title: String,
+    pub artist: String,
+    pub album_artist: String,
+    pub album: String,
+    pub year: Option<u32>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    /// Length in milliseconds.
+    pub duration: u32,
+    pub genre: Option<String>,
+    pub stream_url: String,
+}
+
 /// Discover Kodi/XBMC servers on the local network via mDNS (_xbmc-jsonrpc-h._tcp.local.).
 pub async fn discover_kodi_servers() -> Vec<KodiServer> {
     tokio::task::spawn_blocking(|| {
@@ -161,15 +178,6 @@ pub async fn browse_directory(
         }
     };
 
-    // Parse host:port from base_url for building VFS stream URLs.
-    let host_port = base_url
-        .strip_prefix("http://")
-        .unwrap_or(base_url)
-        .split('/')
-        .next()
-        .unwrap_or("")
-        .to_string();
-
     files
         .into_iter()
         .filter_map(|f| {
@@ -183,17 +191,7 @@ pub async fn browse_directory(
             let is_container = filetype == "directory";
 
             let stream_url = if !is_container {
-                let encoded = percent_encode(&file);
-                let url = match (
-                    user.filter(|u| !u.is_empty()),
-                    pass.filter(|p| !p.is_empty()),
-                ) {
-                    (Some(u), Some(p)) => {
-                        format!("http://{}:{}@{}/vfs/{}", u, p, host_port, encoded)
-                    }
-                    _ => format!("http://{}/vfs/{}", host_port, encoded),
-                };
-                Some(url)
+                Some(vfs_url(base_url, user, pass, &file))
             } else {
                 None
             };
@@ -208,6 +206,103 @@ pub async fn browse_directory(
         .collect()
 }
 
+/// Every song in Kodi's music library via JSON-RPC AudioLibrary.GetSongs,
+/// for mirroring its catalogue. Fails instead of returning an empty list,
+/// so a server that is down is not mistaken for an empty one.
+pub async fn list_songs(
+    base_url: &str,
+    user: Option<&str>,
+    pass: Option<&str>,
+) -> anyhow::Result<Vec<KodiSong>> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "AudioLibrary.GetSongs",
+        "params": {
+            "properties": [
+                "title", "artist", "albumartist", "album", "year",
+                "track", "disc", "duration", "genre", "file"
+            ]
+        },
+        "id": 1
+    });
+    let resp = post_jsonrpc(base_url, user, pass, &body)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Kodi JSON-RPC request failed"))?;
+    if let Some(err) = resp.get("error") {
+        anyhow::bail!("Kodi GetSongs: JSON-RPC error: {err}");
+    }
+    let Some(result) = resp.get("result") else {
+        anyhow::bail!("Kodi GetSongs: unexpected response shape");
+    };
+    let songs = result
+        .get("songs")
+        .and_then(|s| s.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let text = |v: &serde_json::Value, key: &str| -> String {
+        match v.get(key) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Array(a)) => a
+                .iter()
+                .filter_map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => String::new(),
+        }
+    };
+    let number = |v: &serde_json::Value, key: &str| -> Option<u32> {
+        v.get(key)
+            .and_then(|n| n.as_u64())
+            .filter(|n| *n > 0)
+            .map(|n| n as u32)
+    };
+
+    Ok(songs
+        .iter()
+        .filter_map(|song| {
+            let song_id = song.get("songid")?.as_u64()?;
+            let file = song.get("file")?.as_str()?;
+            let artist = text(song, "artist");
+            let album_artist = Some(text(song, "albumartist"))
+                .filter(|a| !a.is_empty())
+                .unwrap_or_else(|| artist.clone());
+            Some(KodiSong {
+                song_id,
+                title: text(song, "title"),
+                artist,
+                album_artist,
+                album: text(song, "album"),
+                year: number(song, "year"),
+                track_number: number(song, "track"),
+                disc_number: number(song, "disc"),
+                // Kodi reports seconds.
+                duration: number(song, "duration").unwrap_or(0) * 1000,
+                genre: Some(text(song, "genre")).filter(|g| !g.is_empty()),
+                stream_url: vfs_url(base_url, user, pass, file),
+            })
+        })
+        .collect())
+}
+
+/// HTTP URL Kodi serves `file` at through its virtual file system.
+fn vfs_url(base_url: &str, user: Option<&str>, pass: Option<&str>, file: &str) -> String {
+    let host_port = base_url
+        .strip_prefix("http://")
+        .unwrap_or(base_url)
+        .split('/')
+        .next()
+        .unwrap_or("");
+    let encoded = percent_encode(file);
+    match (
+        user.filter(|u| !u.is_empty()),
+        pass.filter(|p| !p.is_empty()),
+    ) {
+        (Some(u), Some(p)) => format!("http://{}:{}@{}/vfs/{}", u, p, host_port, encoded),
+        _ => format!("http://{}/vfs/{}", host_port, encoded),
+    }
+}
+
 /// Split `http://host:port?kodi_user=u&kodi_pass=p` into its components.
 /// Returns `(base_url, Option<user>, Option<pass>)`.
 pub fn parse_base_url(url: &str) -> (String, Option<String>, Option<String>) {
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS radio_station (
+    id VARCHAR(255) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    stream_url VARCHAR(255) NOT NULL UNIQUE,
+    homepage_url VARCHAR(255),
+    genre VARCHAR(255),
+    country VARCHAR(255),
+    codec VARCHAR(255),
+    bitrate INT,
+    favicon VARCHAR(255),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS podcast_channels (
+    id TEXT PRIMARY KEY,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    description TEXT,
+    author TEXT,
+    link TEXT,
+    image_url TEXT,
+    auto_download INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'new',
+    error_message TEXT,
+    last_refreshed INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS podcast_episodes (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    guid TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    audio_url TEXT NOT NULL,
+    mime_type TEXT,
+    file_size INTEGER,
+    duration_secs INTEGER,
+    published_at INTEGER,
+    status TEXT NOT NULL DEFAULT 'new',
+    local_path TEXT,
+    position_ms INTEGER NOT NULL DEFAULT 0,
+    played INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (channel_id, guid)
+);
+
+CREATE INDEX IF NOT EXISTS idx_podcast_episodes_channel ON podcast_episodes (channel_id);
+CREATE INDEX IF NOT EXISTS idx_podcast_episodes_audio_url ON podcast_episodes (audio_url);
+CREATE INDEX IF NOT EXISTS idx_podcast_episodes_local_path ON podcast_episodes (local_path);
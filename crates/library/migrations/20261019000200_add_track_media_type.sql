@@ -0,0 +1,4 @@
+ALTER TABLE track ADD COLUMN media_type VARCHAR(16) NOT NULL DEFAULT 'music';
+UPDATE track SET media_type = 'book'
+  WHERE path LIKE '%.m4b'
+     OR lower(genre) IN ('audiobook', 'audiobooks', 'audio book', 'spoken word', 'spoken & audio');
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS track_chapter (
+    id VARCHAR(255) PRIMARY KEY,
+    track_id VARCHAR(255) NOT NULL,
+    position INT NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    start_ms INT NOT NULL,
+    end_ms INT NOT NULL,
+    UNIQUE (track_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_track_chapter_track ON track_chapter (track_id);
+
+CREATE TABLE IF NOT EXISTS audiobook_bookmark (
+    album_id VARCHAR(255) PRIMARY KEY,
+    track_id VARCHAR(255) NOT NULL,
+    position_ms INT NOT NULL DEFAULT 0,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
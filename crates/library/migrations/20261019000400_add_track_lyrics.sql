@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS track_lyrics (
+    track_id VARCHAR(255) PRIMARY KEY,
+    content TEXT,
+    stamp INTEGER NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
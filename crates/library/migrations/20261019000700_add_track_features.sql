@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS track_features (
+    track_id VARCHAR(255) PRIMARY KEY,
+    tempo REAL,
+    centroid REAL,
+    rolloff REAL,
+    loudness REAL,
+    chroma BLOB,
+    stamp INTEGER NOT NULL,
+    analyzed_at INTEGER NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS playlist_files (
+    path TEXT PRIMARY KEY,
+    playlist_id TEXT NOT NULL UNIQUE,
+    modified_at INTEGER NOT NULL,
+    synced_at INTEGER NOT NULL
+);
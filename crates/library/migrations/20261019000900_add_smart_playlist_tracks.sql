@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS smart_playlist_tracks (
+    smart_playlist_id TEXT NOT NULL,
+    track_id TEXT NOT NULL,
+    added_at INTEGER NOT NULL,
+    PRIMARY KEY (smart_playlist_id, track_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_smart_playlist_tracks_track_id ON smart_playlist_tracks(track_id);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS rating (
+    kind VARCHAR(16) NOT NULL,
+    item_id VARCHAR(255) NOT NULL,
+    rating INT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (kind, item_id)
+);
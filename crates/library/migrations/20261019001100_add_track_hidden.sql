@@ -0,0 +1 @@
+ALTER TABLE track ADD COLUMN hidden BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS remote_source (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    username TEXT,
+    password TEXT,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    prefer_local INTEGER NOT NULL DEFAULT 1,
+    status TEXT NOT NULL DEFAULT 'new',
+    error_message TEXT,
+    last_synced INTEGER,
+    track_count INTEGER NOT NULL DEFAULT 0,
+    conflict_count INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+ALTER TABLE track ADD COLUMN source_id VARCHAR(255) DEFAULT NULL;
+ALTER TABLE track ADD COLUMN remote_id VARCHAR(255) DEFAULT NULL;
+CREATE INDEX IF NOT EXISTS idx_track_source ON track (source_id, remote_id);
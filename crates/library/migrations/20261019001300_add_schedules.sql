@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS schedule (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    action TEXT NOT NULL,
+    target TEXT,
+    cron TEXT,
+    run_at INTEGER,
+    volume INTEGER,
+    ramp_secs INTEGER NOT NULL DEFAULT 0,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    next_run INTEGER,
+    last_run INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedule_next_run ON schedule (enabled, next_run);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS dsp_profile (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    settings TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS dsp_profile_output (
+    output TEXT PRIMARY KEY,
+    profile_id TEXT NOT NULL
+);
@@ -1,10 +1,14 @@
 use crate::album_art::extract_and_save_album_cover_with_key;
+use crate::audiobooks;
+use crate::changes;
 use crate::copyright_message::extract_copyright_message;
 use crate::entity::album::Album;
 use crate::entity::album_tracks::AlbumTracks;
 use crate::entity::artist::Artist;
 use crate::entity::artist_tracks::ArtistTracks;
+use crate::entity::track::MEDIA_TYPE_MUSIC;
 use crate::label::extract_label;
+use crate::ratings;
 use crate::{entity::track::Track, repo};
 use anyhow::{anyhow, Error};
 use chrono::Utc;
@@ -22,9 +26,9 @@ use std::{
 };
 use tokio::{fs, sync::Semaphore};
 
-const AUDIO_EXTENSIONS: [&str; 18] = [
-    "mp3", "ogg", "flac", "m4a", "aac", "mp4", "alac", "wav", "wv", "mpc", "aiff", "aif", "ac3",
-    "opus", "spx", "sid", "ape", "wma",
+const AUDIO_EXTENSIONS: [&str; 19] = [
+    "mp3", "ogg", "flac", "m4a", "m4b", "aac", "mp4", "alac", "wav", "wv", "mpc", "aiff", "aif",
+    "ac3", "opus", "spx", "sid", "ape", "wma",
 ];
 
 const MAX_CONCURRENT_SCANS: usize = 1;
@@ -34,7 +38,11 @@ pub fn scan_audio_files(
     audio_dir: PathBuf,
 ) -> BoxFuture<'static, Result<Vec<PathBuf>, Error>> {
     let sem = Arc::new(Semaphore::new(MAX_CONCURRENT_SCANS));
-    scan_audio_files_inner(pool, audio_dir, sem)
+    Box::pin(async move {
+        let scanned = scan_audio_files_inner(pool, audio_dir, sem).await?;
+        changes::library_changed();
+        Ok(scanned)
+    })
 }
 
 fn scan_audio_files_inner(
@@ -100,11 +108,18 @@ pub async fn reconcile_deletions(pool: Pool<Sqlite>) -> Result<u64, Error> {
     let tracks = repo::track::all(pool.clone()).await?;
     let mut removed = 0u64;
     for track in tracks {
-        if Path::new(&track.path).exists() {
+        if track.is_remote || Path::new(&track.path).exists() {
             continue;
         }
         match repo::track::delete_by_path(pool.clone(), &track.path).await {
-            Ok(Some(_)) => removed += 1,
+            Ok(Some(_)) => {
+                removed += 1;
+                changes::track_changed(&track.id);
+                rockbox_webhooks::emit(rockbox_webhooks::Event::FileRemoved {
+                    path: track.path,
+                    track_id: Some(track.id),
+                });
+            }
             Ok(None) => {}
             Err(e) => tracing::warn!("reconcile delete {}: {}", track.path, e),
         }
@@ -193,6 +208,21 @@ pub async fn save_audio_metadata(
             }
         }
 
+        // Books indexed before chapter support get their chapters on the
+        // next scan.
+        if existing_track.is_book()
+            && !existing_track.is_remote
+            && repo::chapter::count_by_track(pool.clone(), &existing_track.id).await? == 0
+        {
+            save_chapters(
+                pool.clone(),
+                &existing_track.id,
+                path,
+                existing_track.length,
+            )
+            .await;
+        }
+
         return Ok(());
     }
     let track_hash = format!("{:x}", md5::compute(path.as_bytes()));
@@ -237,6 +267,9 @@ pub async fn save_audio_metadata(
     )
     .await?;
 
+    let genre = option_string(&entry.genre_string);
+    let media_type = audiobooks::detect_media_type(path, genre.as_deref());
+    let length = clamp_u64_to_u32(entry.length);
     let track_id = repo::track::save(
         pool.clone(),
         Track {
@@ -245,7 +278,7 @@ pub async fn save_audio_metadata(
             title,
             artist,
             album,
-            genre: option_string(&entry.genre_string),
+            genre,
             year: clamp_i32_to_u32(entry.year),
             track_number: clamp_i32_to_u32(entry.tracknum),
             disc_number: entry.discnum.max(0) as u32,
@@ -255,7 +288,7 @@ pub async fn save_audio_metadata(
             bitrate: entry.bitrate,
             frequency: clamp_u64_to_u32(entry.frequency),
             filesize: clamp_u64_to_u32(entry.filesize),
-            length: clamp_u64_to_u32(entry.length),
+            length,
             md5: track_hash,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -268,6 +301,14 @@ pub async fn save_audio_metadata(
     )
     .await?;
 
+    if media_type != MEDIA_TYPE_MUSIC {
+        repo::track::update_media_type(pool.clone(), &track_id, media_type).await?;
+    }
+    if !is_remote_path(path) {
+        save_chapters(pool.clone(), &track_id, path, length).await;
+        ratings::import_from_tags(pool.clone(), &track_id, path).await;
+    }
+
     repo::album_tracks::save(
         pool.clone(),
         AlbumTracks {
@@ -291,6 +332,16 @@ pub async fn save_audio_metadata(
     Ok(())
 }
 
+/// A file with unreadable chapters is still a playable track, so failures
+/// are only logged.
+async fn save_chapters(pool: Pool<Sqlite>, track_id: &str, path: &str, length_ms: u32) {
+    match audiobooks::sync_chapters(pool, track_id, path, length_ms).await {
+        Ok(0) => {}
+        Ok(n) => println!("{} {} chapters", "Found".bright_green(), n),
+        Err(e) => tracing::warn!("chapters {}: {}", path, e),
+    }
+}
+
 /// Save metadata for a streaming URL directly to the DB without probing the stream.
 /// Called by the UPnP renderer which already has title/artist/album/duration from DIDL-Lite.
 pub async fn save_stream_metadata(
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use anyhow::Error;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    chapters,
+    entity::track::{MEDIA_TYPE_BOOK, MEDIA_TYPE_MUSIC},
+    repo,
+};
+
+const DEFAULT_BOOK_GENRES: &str = "audiobook,audiobooks,audio book,spoken word,spoken & audio";
+const DEFAULT_BOOK_DIRS: &str = "Audiobooks";
+
+fn env_list(name: &str, default: &str) -> Vec<String> {
+    std::env::var(name)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Decide whether a scanned file is a book: `.m4b` files always are, as are
+/// files tagged with one of the `ROCKBOX_AUDIOBOOK_GENRES` and files under
+/// one of the `ROCKBOX_AUDIOBOOK_DIRS` (comma-separated; absolute paths
+/// match as a prefix, bare names match any folder of that name).
+pub fn detect_media_type(path: &str, genre: Option<&str>) -> &'static str {
+    if path.to_ascii_lowercase().ends_with(".m4b") {
+        return MEDIA_TYPE_BOOK;
+    }
+    if let Some(genre) = genre {
+        let genre = genre.trim().to_lowercase();
+        if env_list("ROCKBOX_AUDIOBOOK_GENRES", DEFAULT_BOOK_GENRES)
+            .iter()
+            .any(|g| g.to_lowercase() == genre)
+        {
+            return MEDIA_TYPE_BOOK;
+        }
+    }
+    let parent = Path::new(path).parent();
+    for dir in env_list("ROCKBOX_AUDIOBOOK_DIRS", DEFAULT_BOOK_DIRS) {
+        let matched = if dir.starts_with('/') {
+            parent.is_some_and(|p| p.starts_with(&dir))
+        } else {
+            parent.is_some_and(|p| {
+                p.components()
+                    .any(|c| c.as_os_str().to_string_lossy().eq_ignore_ascii_case(&dir))
+            })
+        };
+        if matched {
+            return MEDIA_TYPE_BOOK;
+        }
+    }
+    MEDIA_TYPE_MUSIC
+}
+
+/// Read the chapters embedded in a track's file and store them, replacing
+/// any previous ones. Returns how many were found.
+pub async fn sync_chapters(
+    pool: Pool<Sqlite>,
+    track_id: &str,
+    path: &str,
+    length_ms: u32,
+) -> Result<usize, Error> {
+    let path = path.to_string();
+    let marks = tokio::task::spawn_blocking(move || chapters::read_chapters(&path)).await??;
+    let chapters = chapters::to_chapters(track_id, marks, length_ms as u64);
+    repo::chapter::save_all(pool, track_id, &chapters).await?;
+    Ok(chapters.len())
+}
@@ -0,0 +1,37 @@
+//! Change feed for anything derived from the library, such as smart
+//! playlist membership. Writers call [`track_changed`] or
+//! [`library_changed`]; readers [`subscribe`] and re-evaluate.
+
+use std::sync::OnceLock;
+
+use tokio::sync::broadcast;
+
+/// Changes buffered per subscriber; a lagging one should start over from
+/// the whole library.
+const CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A track was added, retagged, removed, played, skipped, liked or
+    /// unliked.
+    Track(String),
+    /// Too much changed to list, e.g. after a full scan.
+    Library,
+}
+
+fn feed() -> &'static broadcast::Sender<Change> {
+    static FEED: OnceLock<broadcast::Sender<Change>> = OnceLock::new();
+    FEED.get_or_init(|| broadcast::channel(CAPACITY).0)
+}
+
+pub fn track_changed(track_id: &str) {
+    let _ = feed().send(Change::Track(track_id.to_string()));
+}
+
+pub fn library_changed() {
+    let _ = feed().send(Change::Library);
+}
+
+pub fn subscribe() -> broadcast::Receiver<Change> {
+    feed().subscribe()
+}
@@ -0,0 +1,674 @@
+//! Chapter markers embedded in audio files: QuickTime chapter tracks and
+//! Nero `chpl` atoms in MP4/M4B, `CHAP`/`CTOC` frames in ID3v2 tags.
+
+use anyhow::{anyhow, Error};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+};
+
+use crate::{entity::chapter::Chapter, id3};
+
+/// `moov` atoms larger than this are not read — a sane file keeps its
+/// index far below it even for day-long books.
+const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A chapter as found in the file. `end_ms` is only known for ID3 chapters;
+/// [`to_chapters`] derives the rest from the next chapter's start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterMark {
+    pub title: String,
+    pub start_ms: u64,
+    pub end_ms: Option<u64>,
+}
+
+/// Read the chapters of the file at `path`. Files without chapters (or in a
+/// format that cannot carry them) give an empty list.
+pub fn read_chapters(path: &str) -> Result<Vec<ChapterMark>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    read_chapters_from(&mut reader)
+}
+
+pub fn read_chapters_from<R: Read + Seek>(reader: &mut R) -> Result<Vec<ChapterMark>, Error> {
+    let mut magic = [0u8; 8];
+    let n = read_up_to(reader, &mut magic)?;
+    reader.seek(SeekFrom::Start(0))?;
+    if n >= 3 && &magic[..3] == b"ID3" {
+        return read_id3_chapters(reader);
+    }
+    if n == 8 && &magic[4..8] == b"ftyp" {
+        return read_mp4_chapters(reader);
+    }
+    Ok(Vec::new())
+}
+
+/// Turn the marks of a track `length_ms` long into chapter rows: sorted,
+/// titled, and each ending where the next one starts.
+pub fn to_chapters(track_id: &str, marks: Vec<ChapterMark>, length_ms: u64) -> Vec<Chapter> {
+    let mut marks: Vec<ChapterMark> = marks
+        .into_iter()
+        .filter(|m| length_ms == 0 || m.start_ms < length_ms)
+        .collect();
+    marks.sort_by_key(|m| m.start_ms);
+    marks.dedup_by_key(|m| m.start_ms);
+
+    let starts: Vec<u64> = marks.iter().map(|m| m.start_ms).collect();
+    marks
+        .into_iter()
+        .enumerate()
+        .map(|(i, mark)| {
+            let next_start = starts.get(i + 1).copied().unwrap_or(length_ms);
+            let end_ms = match mark.end_ms {
+                Some(end) if end > mark.start_ms && end <= next_start => end,
+                _ => next_start.max(mark.start_ms),
+            };
+            let title = match mark.title.trim() {
+                "" => format!("Chapter {}", i + 1),
+                title => title.to_string(),
+            };
+            Chapter {
+                id: format!("{:x}", md5::compute(format!("{}:{}", track_id, i))),
+                track_id: track_id.to_string(),
+                position: i as u32,
+                title,
+                start_ms: clamp_u32(mark.start_ms),
+                end_ms: clamp_u32(end_ms),
+            }
+        })
+        .collect()
+}
+
+fn clamp_u32(value: u64) -> u32 {
+    value.min(u32::MAX as u64) as u32
+}
+
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    Ok(read)
+}
+
+fn be_u16(b: &[u8]) -> u16 {
+    u16::from_be_bytes([b[0], b[1]])
+}
+
+fn be_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn be_u64(b: &[u8]) -> u64 {
+    u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+}
+
+// ── ID3v2 ─────────────────────────────────────────────────────────────────
+
+struct Id3Chapter {
+    title: String,
+    start_ms: u64,
+    end_ms: u64,
+}
+
+struct Id3Toc {
+    top_level: bool,
+    children: Vec<String>,
+}
+
+fn read_id3_chapters<R: Read + Seek>(reader: &mut R) -> Result<Vec<ChapterMark>, Error> {
+    let Some((version, tag)) = id3::read_tag(reader)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut chapters: HashMap<String, Id3Chapter> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+    let mut tocs: HashMap<String, Id3Toc> = HashMap::new();
+    for (id, body) in id3::frames(&tag, version) {
+        match id {
+            b"CHAP" => {
+                if let Some((element_id, chapter)) = parse_chap(body, version) {
+                    order.push(element_id.clone());
+                    chapters.insert(element_id, chapter);
+                }
+            }
+            b"CTOC" => {
+                if let Some((element_id, toc)) = parse_ctoc(body) {
+                    tocs.insert(element_id, toc);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // A top-level table of contents gives the play order; without one the
+    // chapters are taken in tag order (and sorted by start time later).
+    let mut ids = Vec::new();
+    if let Some(root) = tocs.values().find(|toc| toc.top_level) {
+        flatten_toc(root, &tocs, &mut ids, 0);
+    }
+    if ids.is_empty() {
+        ids = order;
+    }
+    Ok(ids
+        .iter()
+        .filter_map(|id| chapters.remove(id))
+        .map(|c| ChapterMark {
+            title: c.title,
+            start_ms: c.start_ms,
+            end_ms: (c.end_ms > c.start_ms && c.end_ms != u32::MAX as u64).then_some(c.end_ms),
+        })
+        .collect())
+}
+
+fn flatten_toc(toc: &Id3Toc, tocs: &HashMap<String, Id3Toc>, out: &mut Vec<String>, depth: u8) {
+    for child in &toc.children {
+        match tocs.get(child) {
+            Some(nested) if depth < 8 => flatten_toc(nested, tocs, out, depth + 1),
+            Some(_) => {}
+            None => out.push(child.clone()),
+        }
+    }
+}
+
+fn parse_chap(body: &[u8], version: u8) -> Option<(String, Id3Chapter)> {
+    let (element_id, rest) = id3::take_cstring(body)?;
+    if rest.len() < 16 {
+        return None;
+    }
+    let start_ms = be_u32(&rest[0..4]) as u64;
+    let end_ms = be_u32(&rest[4..8]) as u64;
+    let title = id3::frames(&rest[16..], version)
+        .into_iter()
+        .find(|(id, _)| *id == b"TIT2")
+        .map(|(_, text)| id3::decode_text(text))
+        .unwrap_or_default();
+    Some((
+        element_id,
+        Id3Chapter {
+            title,
+            start_ms,
+            end_ms,
+        },
+    ))
+}
+
+fn parse_ctoc(body: &[u8]) -> Option<(String, Id3Toc)> {
+    let (element_id, rest) = id3::take_cstring(body)?;
+    if rest.len() < 2 {
+        return None;
+    }
+    let top_level = rest[0] & 0x02 != 0;
+    let count = rest[1];
+    let mut rest = &rest[2..];
+    let mut children = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (child, tail) = id3::take_cstring(rest)?;
+        children.push(child);
+        rest = tail;
+    }
+    Some((
+        element_id,
+        Id3Toc {
+            top_level,
+            children,
+        },
+    ))
+}
+
+// ── MP4 ───────────────────────────────────────────────────────────────────
+
+/// Child atoms of an atom body, as `(type, body)` pairs.
+fn atoms(mut data: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut out = Vec::new();
+    while data.len() >= 8 {
+        let size = be_u32(&data[0..4]) as u64;
+        let kind = &data[4..8];
+        let (header, size) = match size {
+            0 => (8, data.len() as u64),
+            1 if data.len() >= 16 => (16, be_u64(&data[8..16])),
+            _ => (8, size),
+        };
+        if size < header as u64 || size > data.len() as u64 {
+            break;
+        }
+        out.push((kind, &data[header..size as usize]));
+        data = &data[size as usize..];
+    }
+    out
+}
+
+fn child<'a>(data: &'a [u8], kind: &[u8]) -> Option<&'a [u8]> {
+    atoms(data)
+        .into_iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, body)| body)
+}
+
+fn atom_path<'a>(data: &'a [u8], kinds: &[&[u8]]) -> Option<&'a [u8]> {
+    kinds.iter().try_fold(data, |data, kind| child(data, kind))
+}
+
+/// Find the top-level `moov` atom and read it into memory.
+fn read_moov<R: Read + Seek>(reader: &mut R) -> Result<Option<Vec<u8>>, Error> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    let mut pos = 0u64;
+    while pos + 8 <= file_len {
+        reader.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 16];
+        reader.read_exact(&mut header[..8])?;
+        let mut header_len = 8u64;
+        let size = match be_u32(&header[0..4]) as u64 {
+            0 => file_len - pos,
+            1 => {
+                reader.read_exact(&mut header[8..16])?;
+                header_len = 16;
+                be_u64(&header[8..16])
+            }
+            size => size,
+        };
+        if size < header_len {
+            return Err(anyhow!("invalid mp4 atom size at {}", pos));
+        }
+        if &header[4..8] == b"moov" {
+            let body_len = size - header_len;
+            if body_len > MAX_MOOV_SIZE {
+                return Err(anyhow!("moov atom too large ({} bytes)", body_len));
+            }
+            let mut moov = vec![0u8; body_len as usize];
+            reader.read_exact(&mut moov)?;
+            return Ok(Some(moov));
+        }
+        pos += size;
+    }
+    Ok(None)
+}
+
+fn read_mp4_chapters<R: Read + Seek>(reader: &mut R) -> Result<Vec<ChapterMark>, Error> {
+    let Some(moov) = read_moov(reader)? else {
+        return Ok(Vec::new());
+    };
+    let chapters = read_chapter_track(reader, &moov)?;
+    if !chapters.is_empty() {
+        return Ok(chapters);
+    }
+    Ok(atom_path(&moov, &[b"udta", b"chpl"])
+        .map(parse_chpl)
+        .unwrap_or_default())
+}
+
+/// Nero chapter list: start times in 100 ns units, Pascal-string titles.
+fn parse_chpl(body: &[u8]) -> Vec<ChapterMark> {
+    if body.len() < 5 {
+        return Vec::new();
+    }
+    let version = body[0];
+    let mut data = &body[4..];
+    if version > 0 {
+        data = data.get(4..).unwrap_or_default();
+    }
+    let Some((&count, mut data)) = data.split_first() else {
+        return Vec::new();
+    };
+    let mut marks = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        if data.len() < 9 {
+            break;
+        }
+        let start = be_u64(&data[0..8]);
+        let len = data[8] as usize;
+        let Some(title) = data.get(9..9 + len) else {
+            break;
+        };
+        marks.push(ChapterMark {
+            title: String::from_utf8_lossy(title).to_string(),
+            start_ms: start / 10_000,
+            end_ms: None,
+        });
+        data = &data[9 + len..];
+    }
+    marks
+}
+
+fn track_id(trak: &[u8]) -> Option<u32> {
+    let tkhd = child(trak, b"tkhd")?;
+    let offset = match tkhd.first()? {
+        1 => 20,
+        _ => 12,
+    };
+    tkhd.get(offset..offset + 4).map(be_u32)
+}
+
+/// The QuickTime chapter track: a text track referenced from another
+/// track's `tref/chap`, one sample per chapter.
+fn read_chapter_track<R: Read + Seek>(
+    reader: &mut R,
+    moov: &[u8],
+) -> Result<Vec<ChapterMark>, Error> {
+    let traks: Vec<&[u8]> = atoms(moov)
+        .into_iter()
+        .filter(|(kind, _)| *kind == b"trak")
+        .map(|(_, body)| body)
+        .collect();
+    let chapter_ids: Vec<u32> = traks
+        .iter()
+        .filter_map(|trak| atom_path(trak, &[b"tref", b"chap"]))
+        .flat_map(|chap| chap.chunks_exact(4).map(be_u32).collect::<Vec<_>>())
+        .collect();
+    let Some(trak) = traks
+        .iter()
+        .find(|trak| track_id(trak).is_some_and(|id| chapter_ids.contains(&id)))
+    else {
+        return Ok(Vec::new());
+    };
+    let Some(mdia) = child(trak, b"mdia") else {
+        return Ok(Vec::new());
+    };
+    let timescale = match child(mdia, b"mdhd") {
+        Some(mdhd) if mdhd.first() == Some(&1) => mdhd.get(20..24).map(be_u32),
+        Some(mdhd) => mdhd.get(12..16).map(be_u32),
+        None => None,
+    }
+    .filter(|ts| *ts > 0)
+    .unwrap_or(1000) as u64;
+    let Some(stbl) = atom_path(mdia, &[b"minf", b"stbl"]) else {
+        return Ok(Vec::new());
+    };
+
+    let starts = sample_times(stbl);
+    let offsets = sample_offsets(stbl);
+    let sizes = sample_sizes(stbl, offsets.len());
+    let mut marks = Vec::new();
+    for ((start, offset), size) in starts.iter().zip(&offsets).zip(&sizes) {
+        if *size < 2 {
+            continue;
+        }
+        reader.seek(SeekFrom::Start(*offset))?;
+        let mut sample = vec![0u8; (*size).min(64 * 1024) as usize];
+        reader.read_exact(&mut sample)?;
+        let len = (be_u16(&sample[0..2]) as usize).min(sample.len() - 2);
+        let text = &sample[2..2 + len];
+        let title = if text.starts_with(&[0xfe, 0xff]) || text.starts_with(&[0xff, 0xfe]) {
+            id3::decode_utf16(text, true)
+        } else {
+            String::from_utf8_lossy(text).to_string()
+        };
+        marks.push(ChapterMark {
+            title,
+            start_ms: start * 1000 / timescale,
+            end_ms: None,
+        });
+    }
+    Ok(marks)
+}
+
+/// Start time of every sample, in media timescale units (`stts`).
+fn sample_times(stbl: &[u8]) -> Vec<u64> {
+    let Some(stts) = child(stbl, b"stts").filter(|b| b.len() >= 8) else {
+        return Vec::new();
+    };
+    let mut times = Vec::new();
+    let mut time = 0u64;
+    for entry in stts[8..].chunks_exact(8).take(be_u32(&stts[4..8]) as usize) {
+        let (count, delta) = (be_u32(&entry[0..4]), be_u32(&entry[4..8]) as u64);
+        for _ in 0..count.min(100_000) {
+            times.push(time);
+            time += delta;
+        }
+    }
+    times
+}
+
+/// File offset of every sample, from the chunk offsets (`stco`/`co64`) and
+/// the sample-to-chunk map (`stsc`).
+fn sample_offsets(stbl: &[u8]) -> Vec<u64> {
+    let chunk_offsets: Vec<u64> = if let Some(stco) = child(stbl, b"stco") {
+        stco.get(8..)
+            .unwrap_or_default()
+            .chunks_exact(4)
+            .map(|c| be_u32(c) as u64)
+            .collect()
+    } else if let Some(co64) = child(stbl, b"co64") {
+        co64.get(8..)
+            .unwrap_or_default()
+            .chunks_exact(8)
+            .map(be_u64)
+            .collect()
+    } else {
+        return Vec::new();
+    };
+    let stsc: Vec<(u32, u32)> = child(stbl, b"stsc")
+        .and_then(|b| b.get(8..))
+        .unwrap_or_default()
+        .chunks_exact(12)
+        .map(|e| (be_u32(&e[0..4]), be_u32(&e[4..8])))
+        .collect();
+    let all_sizes = sample_sizes(stbl, usize::MAX);
+
+    let mut offsets = Vec::new();
+    let mut sample = 0usize;
+    for (i, chunk_offset) in chunk_offsets.iter().enumerate() {
+        let chunk = i as u32 + 1;
+        let per_chunk = stsc
+            .iter()
+            .rev()
+            .find(|(first, _)| *first <= chunk)
+            .map(|(_, n)| *n)
+            .unwrap_or(1);
+        let mut offset = *chunk_offset;
+        for _ in 0..per_chunk.min(100_000) {
+            offsets.push(offset);
+            offset += all_sizes.get(sample).copied().unwrap_or(0);
+            sample += 1;
+        }
+    }
+    offsets
+}
+
+/// Size of every sample (`stsz`). A fixed sample size is repeated `count`
+/// times (capped by the declared sample count).
+fn sample_sizes(stbl: &[u8], count: usize) -> Vec<u64> {
+    let Some(stsz) = child(stbl, b"stsz").filter(|b| b.len() >= 12) else {
+        return Vec::new();
+    };
+    let fixed = be_u32(&stsz[4..8]) as u64;
+    let samples = be_u32(&stsz[8..12]) as usize;
+    if fixed != 0 {
+        return vec![fixed; samples.min(count).min(100_000)];
+    }
+    stsz[12..]
+        .chunks_exact(4)
+        .take(samples)
+        .map(|c| be_u32(c) as u64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn atom(kind: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn full_atom(kind: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0];
+        data.extend_from_slice(body);
+        atom(kind, &data)
+    }
+
+    fn id3_frame(id: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn chap(id: &str, title: &str, start: u32, end: u32) -> Vec<u8> {
+        let mut body = format!("{id}\0").into_bytes();
+        body.extend_from_slice(&start.to_be_bytes());
+        body.extend_from_slice(&end.to_be_bytes());
+        body.extend_from_slice(&[0xff; 8]);
+        let mut text = vec![3];
+        text.extend_from_slice(title.as_bytes());
+        body.extend(id3_frame(b"TIT2", &text));
+        id3_frame(b"CHAP", &body)
+    }
+
+    #[test]
+    fn reads_id3_chapters_in_toc_order() {
+        let mut toc = b"toc\0".to_vec();
+        toc.extend_from_slice(&[0x03, 2]);
+        toc.extend_from_slice(b"ch1\0ch0\0");
+        let mut frames = id3_frame(b"CTOC", &toc);
+        frames.extend(chap("ch0", "Opening", 0, 60_000));
+        frames.extend(chap("ch1", "Middle", 60_000, 120_000));
+
+        let mut file = b"ID3\x03\x00\x00".to_vec();
+        let size = frames.len() as u32;
+        file.extend_from_slice(&[
+            (size >> 21 & 0x7f) as u8,
+            (size >> 14 & 0x7f) as u8,
+            (size >> 7 & 0x7f) as u8,
+            (size & 0x7f) as u8,
+        ]);
+        file.extend(frames);
+        file.extend_from_slice(b"\xff\xfbaudio");
+
+        let marks = read_chapters_from(&mut Cursor::new(file)).unwrap();
+        assert_eq!(marks.len(), 2);
+        assert_eq!(marks[0].title, "Middle");
+        assert_eq!(marks[0].start_ms, 60_000);
+        assert_eq!(marks[1].title, "Opening");
+        assert_eq!(marks[1].end_ms, Some(60_000));
+    }
+
+    #[test]
+    fn reads_nero_chpl_chapters() {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+        for (start, title) in [(0u64, "One"), (90_000u64, "Two")] {
+            chpl.extend_from_slice(&(start * 10_000).to_be_bytes());
+            chpl.push(title.len() as u8);
+            chpl.extend_from_slice(title.as_bytes());
+        }
+        let moov = atom(b"moov", &atom(b"udta", &atom(b"chpl", &chpl)));
+        let mut file = atom(b"ftyp", b"M4B \0\0\0\0");
+        file.extend(atom(b"mdat", b"audio"));
+        file.extend(moov);
+
+        let marks = read_chapters_from(&mut Cursor::new(file)).unwrap();
+        assert_eq!(
+            marks.iter().map(|m| m.start_ms).collect::<Vec<_>>(),
+            vec![0, 90_000]
+        );
+        assert_eq!(marks[1].title, "Two");
+    }
+
+    #[test]
+    fn reads_quicktime_chapter_track() {
+        let samples: Vec<Vec<u8>> = ["Intro", "Part One"]
+            .iter()
+            .map(|t| {
+                let mut s = (t.len() as u16).to_be_bytes().to_vec();
+                s.extend_from_slice(t.as_bytes());
+                s
+            })
+            .collect();
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0");
+        let mdat_body: Vec<u8> = samples.concat();
+        let mdat_offset = (ftyp.len() + 8) as u32;
+
+        let tkhd = |id: u32| {
+            let mut body = vec![0u8; 8];
+            body.extend_from_slice(&id.to_be_bytes());
+            body.extend_from_slice(&[0; 8]);
+            full_atom(b"tkhd", &body)
+        };
+        let audio_trak = atom(
+            b"trak",
+            &[tkhd(1), atom(b"tref", &atom(b"chap", &2u32.to_be_bytes()))].concat(),
+        );
+        let mut mdhd = vec![0u8; 8];
+        mdhd.extend_from_slice(&1000u32.to_be_bytes());
+        mdhd.extend_from_slice(&[0; 8]);
+        let mut stts = 1u32.to_be_bytes().to_vec();
+        stts.extend_from_slice(&2u32.to_be_bytes());
+        stts.extend_from_slice(&30_000u32.to_be_bytes());
+        let mut stsc = 1u32.to_be_bytes().to_vec();
+        stsc.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1]);
+        let mut stsz = 0u32.to_be_bytes().to_vec();
+        stsz.extend_from_slice(&2u32.to_be_bytes());
+        for s in &samples {
+            stsz.extend_from_slice(&(s.len() as u32).to_be_bytes());
+        }
+        let mut stco = 1u32.to_be_bytes().to_vec();
+        stco.extend_from_slice(&mdat_offset.to_be_bytes());
+        let stbl = atom(
+            b"stbl",
+            &[
+                full_atom(b"stts", &stts),
+                full_atom(b"stsc", &stsc),
+                full_atom(b"stsz", &stsz),
+                full_atom(b"stco", &stco),
+            ]
+            .concat(),
+        );
+        let text_trak = atom(
+            b"trak",
+            &[
+                tkhd(2),
+                atom(
+                    b"mdia",
+                    &[full_atom(b"mdhd", &mdhd), atom(b"minf", &stbl)].concat(),
+                ),
+            ]
+            .concat(),
+        );
+
+        let mut file = ftyp;
+        file.extend(atom(b"mdat", &mdat_body));
+        file.extend(atom(b"moov", &[audio_trak, text_trak].concat()));
+
+        let marks = read_chapters_from(&mut Cursor::new(file)).unwrap();
+        assert_eq!(marks.len(), 2);
+        assert_eq!(marks[0].title, "Intro");
+        assert_eq!(marks[1].title, "Part One");
+        assert_eq!(marks[1].start_ms, 30_000);
+    }
+
+    #[test]
+    fn fills_in_end_times_and_titles() {
+        let marks = vec![
+            ChapterMark {
+                title: String::new(),
+                start_ms: 60_000,
+                end_ms: None,
+            },
+            ChapterMark {
+                title: "Start".into(),
+                start_ms: 0,
+                end_ms: None,
+            },
+        ];
+        let chapters = to_chapters("t1", marks, 150_000);
+        assert_eq!(chapters[0].title, "Start");
+        assert_eq!(chapters[0].end_ms, 60_000);
+        assert_eq!(chapters[1].title, "Chapter 2");
+        assert_eq!(chapters[1].end_ms, 150_000);
+        assert_eq!(chapters[1].position, 1);
+    }
+
+    #[test]
+    fn files_without_chapters_give_nothing() {
+        let marks = read_chapters_from(&mut Cursor::new(b"fLaC\0\0\0\0".to_vec())).unwrap();
+        assert!(marks.is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(sqlx::FromRow, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub id: String,
+    pub track_id: String,
+    pub position: u32,
+    pub title: String,
+    pub start_ms: u32,
+    pub end_ms: u32,
+}
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Cached lyrics of a track. `content` is the serialized
+/// [`crate::lyrics::Lyrics`], or `None` when the track has none; `stamp`
+/// identifies the file versions they were read from.
+#[derive(sqlx::FromRow, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TrackLyrics {
+    pub track_id: String,
+    pub content: Option<String>,
+    pub stamp: i64,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub updated_at: DateTime<Utc>,
+}
@@ -3,9 +3,14 @@ pub mod album_tracks;
 pub mod artist;
 pub mod artist_genres;
 pub mod artist_tracks;
+pub mod bookmark;
+pub mod chapter;
 pub mod favourites;
 pub mod folder;
 pub mod genre;
+pub mod lyrics;
 pub mod playlist;
 pub mod playlist_tracks;
+pub mod radio_station;
+pub mod rating;
 pub mod track;
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const KIND_TRACK: &str = "track";
+pub const KIND_ALBUM: &str = "album";
+pub const KIND_ARTIST: &str = "artist";
+
+/// Highest rating; 0 means unrated and is never stored.
+pub const MAX_RATING: u8 = 5;
+
+/// A 1–5 star rating of a track, album or artist.
+#[derive(sqlx::FromRow, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Rating {
+    /// One of the `KIND_*` constants.
+    pub kind: String,
+    pub item_id: String,
+    pub rating: u8,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub updated_at: DateTime<Utc>,
+}
+
+pub fn is_kind(kind: &str) -> bool {
+    matches!(kind, KIND_TRACK | KIND_ALBUM | KIND_ARTIST)
+}
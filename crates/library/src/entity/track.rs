@@ -1,6 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Values of `track.media_type`. Books get chapters, a per-book bookmark
+/// and are left out of shuffles.
+pub const MEDIA_TYPE_MUSIC: &str = "music";
+pub const MEDIA_TYPE_BOOK: &str = "book";
+
 #[derive(sqlx::FromRow, Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Track {
     pub id: String,
@@ -30,8 +35,26 @@ pub struct Track {
     pub album_id: String,
     pub genre_id: String,
     pub is_remote: bool,
+    #[serde(default)]
+    pub media_type: String,
+    /// Left out of listings and search, e.g. the lower-quality copy of a
+    /// duplicate. The file and its row stay put.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Remote source the track was synced from, and its id there. `path`
+    /// is then the stream URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_id: Option<String>,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub updated_at: DateTime<Utc>,
 }
+
+impl Track {
+    pub fn is_book(&self) -> bool {
+        self.media_type == MEDIA_TYPE_BOOK
+    }
+}
@@ -0,0 +1,150 @@
+//! Just enough of ID3v2.3/2.4 to reach the frames lofty does not surface:
+//! chapters (`CHAP`/`CTOC`) and synchronised lyrics (`SYLT`).
+
+use anyhow::Error;
+use std::io::Read;
+
+fn be_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn syncsafe(b: &[u8]) -> u32 {
+    ((b[0] as u32 & 0x7f) << 21)
+        | ((b[1] as u32 & 0x7f) << 14)
+        | ((b[2] as u32 & 0x7f) << 7)
+        | (b[3] as u32 & 0x7f)
+}
+
+/// Read the ID3v2 tag at the start of `reader`, returning its major version
+/// and the frame area (unsynchronisation undone, extended header skipped).
+/// `None` when there is no tag or it is ID3v2.2, whose three-character frame
+/// ids none of our frames use.
+pub fn read_tag<R: Read>(reader: &mut R) -> Result<Option<(u8, Vec<u8>)>, Error> {
+    let mut header = [0u8; 10];
+    reader.read_exact(&mut header)?;
+    let version = header[3];
+    let flags = header[5];
+    if &header[..3] != b"ID3" || (version != 3 && version != 4) {
+        return Ok(None);
+    }
+    let size = syncsafe(&header[6..10]) as usize;
+    let mut tag = vec![0u8; size];
+    reader.read_exact(&mut tag)?;
+
+    if version == 3 && flags & 0x80 != 0 {
+        tag = remove_unsynchronisation(&tag);
+    }
+    let mut offset = 0;
+    if flags & 0x40 != 0 && tag.len() >= 4 {
+        offset = match version {
+            3 => be_u32(&tag[0..4]) as usize + 4,
+            _ => syncsafe(&tag[0..4]) as usize,
+        };
+    }
+    Ok(Some((
+        version,
+        tag.get(offset..).unwrap_or_default().to_vec(),
+    )))
+}
+
+fn remove_unsynchronisation(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        out.push(data[i]);
+        if data[i] == 0xff && data.get(i + 1) == Some(&0x00) {
+            i += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Frames of an ID3v2.3/2.4 tag body (or of the sub-frames embedded in a
+/// `CHAP`/`CTOC` frame), as `(id, body)` pairs.
+pub fn frames(mut data: &[u8], version: u8) -> Vec<(&[u8], &[u8])> {
+    let mut frames = Vec::new();
+    while data.len() >= 10 && data[0] != 0 {
+        let id = &data[0..4];
+        let size = match version {
+            4 => syncsafe(&data[4..8]),
+            _ => be_u32(&data[4..8]),
+        } as usize;
+        let Some(body) = data.get(10..10 + size) else {
+            break;
+        };
+        frames.push((id, body));
+        data = &data[10 + size..];
+    }
+    frames
+}
+
+pub fn take_cstring(data: &[u8]) -> Option<(String, &[u8])> {
+    let end = data.iter().position(|b| *b == 0)?;
+    Some((
+        String::from_utf8_lossy(&data[..end]).to_string(),
+        &data[end + 1..],
+    ))
+}
+
+/// Split a string terminated as `encoding` requires (one NUL byte, or two
+/// for UTF-16) off the front of `data`.
+pub fn take_text(data: &[u8], encoding: u8) -> Option<(String, &[u8])> {
+    match encoding {
+        1 | 2 => {
+            let end = data.chunks_exact(2).position(|c| c == [0, 0])? * 2;
+            Some((decode_utf16(&data[..end], encoding == 2), &data[end + 2..]))
+        }
+        _ => {
+            let end = data.iter().position(|b| *b == 0)?;
+            Some((
+                decode_latin1_or_utf8(&data[..end], encoding),
+                &data[end + 1..],
+            ))
+        }
+    }
+}
+
+/// Text frame body: an encoding byte followed by the text.
+pub fn decode_text(data: &[u8]) -> String {
+    let Some((&encoding, text)) = data.split_first() else {
+        return String::new();
+    };
+    let decoded = match encoding {
+        1 | 2 => decode_utf16(text, encoding == 2),
+        _ => decode_latin1_or_utf8(text, encoding),
+    };
+    decoded.trim_end_matches('\0').to_string()
+}
+
+fn decode_latin1_or_utf8(text: &[u8], encoding: u8) -> String {
+    match encoding {
+        0 => text.iter().map(|b| *b as char).collect(),
+        _ => String::from_utf8_lossy(text).to_string(),
+    }
+}
+
+/// UTF-16 text, honouring a byte-order mark when there is one.
+pub fn decode_utf16(mut data: &[u8], mut big_endian: bool) -> String {
+    if data.len() >= 2 {
+        match (data[0], data[1]) {
+            (0xfe, 0xff) => {
+                big_endian = true;
+                data = &data[2..];
+            }
+            (0xff, 0xfe) => {
+                big_endian = false;
+                data = &data[2..];
+            }
+            _ => {}
+        }
+    }
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| match big_endian {
+            true => u16::from_be_bytes([c[0], c[1]]),
+            false => u16::from_le_bytes([c[0], c[1]]),
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
@@ -9,10 +9,18 @@ use tracing::{debug, info, warn};
 pub mod album_art;
 pub mod artists;
 pub mod audio_scan;
+pub mod audiobooks;
+pub mod changes;
+pub mod chapters;
 pub mod copyright_message;
 pub mod entity;
 pub mod genres;
+mod id3;
 pub mod label;
+pub mod lyrics;
+pub mod radio;
+pub mod ratings;
+pub mod replaygain;
 pub mod repo;
 pub mod watcher;
 
@@ -103,6 +111,153 @@ pub async fn create_connection_pool() -> Result<Pool<Sqlite>, Error> {
         Err(_) => warn!("is_remote column already exists"),
     }
 
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000000_add_radio_stations.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("radio_station table already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000100_add_podcast_tables.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("podcast tables already exist"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000200_add_track_media_type.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("media_type column already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000300_add_audiobook_tables.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("audiobook tables already exist"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000400_add_track_lyrics.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("track_lyrics table already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000500_add_webhook_tables.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("webhook tables already exist"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000600_add_api_tokens.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("api_tokens table already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000700_add_track_features.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("track_features table already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000800_add_playlist_files.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("playlist_files table already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019000900_add_smart_playlist_tracks.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("smart_playlist_tracks table already exists"),
+    }
+
+    match pool
+        .execute(include_str!("../migrations/20261019001000_add_ratings.sql"))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("rating table already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019001100_add_track_hidden.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("hidden column already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019001200_add_remote_sources.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("remote_source table already exists"),
+    }
+
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019001300_add_schedules.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("schedule table already exists"),
+    }
+    match pool
+        .execute(include_str!(
+            "../migrations/20261019001400_add_dsp_profiles.sql"
+        ))
+        .await
+    {
+        Ok(_) => {}
+        Err(_) => warn!("dsp_profile table already exists"),
+    }
+
     /*
     pool.execute(include_str!(
         "../migrations/20260501000000_fix_datetime_formats.sql"
@@ -0,0 +1,584 @@
+//! Lyrics for library tracks, shared by every front end.
+//!
+//! Sources, in order of preference: a `.lrc` / `.txt` sidecar next to the
+//! audio file (what most players expect for offline lyric storage, and where
+//! uploads are written), then the tags — ID3 `SYLT` (synced) and `USLT`,
+//! Vorbis `LYRICS` / `UNSYNCEDLYRICS`, MP4 `©lyr`. Synced lyrics win over
+//! plain ones wherever they come from; tag lyrics that are themselves LRC
+//! text count as synced.
+//!
+//! Results are cached in the `track_lyrics` table, keyed by track and
+//! invalidated when the audio file or its sidecar changes.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::Error;
+use lofty::{
+    file::TaggedFileExt,
+    probe::Probe,
+    tag::{ItemKey, Tag},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+
+use crate::{entity::track::Track, id3, repo};
+
+pub const SOURCE_SIDECAR: &str = "sidecar";
+pub const SOURCE_SYNCED_TAG: &str = "sylt";
+pub const SOURCE_TAG: &str = "tag";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LyricLine {
+    /// `None` for unsynced lyrics.
+    pub start_ms: Option<u64>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lyrics {
+    pub synced: bool,
+    /// Where the lyrics were read from — one of the `SOURCE_*` constants.
+    pub source: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub by: Option<String>,
+    pub creator: Option<String>,
+    pub version: Option<String>,
+    pub length_ms: Option<u64>,
+    /// LRC `offset:` in milliseconds, already applied to the line starts.
+    pub offset_ms: Option<i64>,
+    pub lines: Vec<LyricLine>,
+}
+
+impl Lyrics {
+    /// The lyrics as plain text, one line per line.
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|l| l.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Lyrics of `track`, from the cache when the files have not changed since
+/// they were read. Remote tracks have none.
+pub async fn get_lyrics(pool: Pool<Sqlite>, track: &Track) -> Result<Option<Lyrics>, Error> {
+    if track.is_remote {
+        return Ok(None);
+    }
+    let path = track.path.clone();
+    let stamp = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || files_stamp(Path::new(&path))
+    })
+    .await?;
+
+    if let Some(cached) = repo::lyrics::find(pool.clone(), &track.id).await? {
+        if cached.stamp == stamp {
+            return Ok(match cached.content {
+                Some(content) => serde_json::from_str(&content).ok(),
+                None => None,
+            });
+        }
+    }
+
+    let lyrics = tokio::task::spawn_blocking(move || read_lyrics(Path::new(&path))).await??;
+    let content = match &lyrics {
+        Some(lyrics) => Some(serde_json::to_string(lyrics)?),
+        None => None,
+    };
+    repo::lyrics::save(pool, &track.id, content.as_deref(), stamp).await?;
+    Ok(lyrics)
+}
+
+/// Index of the synced line being sung at `position_ms`.
+pub fn current_line(lyrics: &Lyrics, position_ms: u64) -> Option<usize> {
+    if !lyrics.synced {
+        return None;
+    }
+    lyrics
+        .lines
+        .iter()
+        .rposition(|l| l.start_ms.is_some_and(|start| start <= position_ms))
+}
+
+/// Modification times of the audio file and its sidecar folded into one
+/// number, so a change to either invalidates the cache.
+fn files_stamp(track_path: &Path) -> i64 {
+    let mtime = |path: &Path| {
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    };
+    let sidecar = find_sidecar(track_path).map(|p| mtime(&p)).unwrap_or(0);
+    mtime(track_path).wrapping_mul(31).wrapping_add(sidecar)
+}
+
+/// Read the lyrics of the file at `track_path`, bypassing the cache.
+pub fn read_lyrics(track_path: &Path) -> Result<Option<Lyrics>, Error> {
+    let sidecar = find_sidecar(track_path).and_then(|p| parse_sidecar(&p));
+    if sidecar.as_ref().is_some_and(|l| l.synced) {
+        return Ok(sidecar);
+    }
+    let embedded = read_embedded(track_path)?;
+    Ok(match (sidecar, embedded) {
+        (_, Some(embedded)) if embedded.synced => Some(embedded),
+        (Some(sidecar), _) => Some(sidecar),
+        (None, embedded) => embedded,
+    })
+}
+
+/// Lyrics stored in the file's tags.
+pub fn read_embedded(track_path: &Path) -> Result<Option<Lyrics>, Error> {
+    let is_id3 = track_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("mp3"));
+    if is_id3 {
+        let mut reader = BufReader::new(File::open(track_path)?);
+        if let Ok(Some((version, tag))) = id3::read_tag(&mut reader) {
+            if let Some(lyrics) = parse_sylt(&tag, version) {
+                return Ok(Some(lyrics));
+            }
+        }
+    }
+
+    let tagged_file = match Probe::open(track_path).and_then(|p| p.read()) {
+        Ok(tagged_file) => tagged_file,
+        Err(_) => return Ok(None),
+    };
+    let text = tagged_file.tags().iter().find_map(tag_lyrics);
+    Ok(text.and_then(|text| {
+        let mut lyrics = parse_text(&text);
+        lyrics.source = SOURCE_TAG.to_string();
+        (!lyrics.lines.is_empty()).then_some(lyrics)
+    }))
+}
+
+fn tag_lyrics(tag: &Tag) -> Option<String> {
+    [
+        ItemKey::Lyrics,
+        ItemKey::Unknown("UNSYNCEDLYRICS".to_string()),
+    ]
+    .iter()
+    .filter_map(|key| tag.get_string(key))
+    .find(|text| !text.trim().is_empty())
+    .map(|text| text.to_string())
+}
+
+/// Tag text is often LRC pasted verbatim; use it as synced lyrics when it
+/// carries timestamps.
+fn parse_text(text: &str) -> Lyrics {
+    let lrc = parse_lrc(text);
+    match lrc.synced {
+        true => lrc,
+        false => parse_plain(text),
+    }
+}
+
+/// The first `SYLT` frame holding lyrics with millisecond timestamps.
+fn parse_sylt(tag: &[u8], version: u8) -> Option<Lyrics> {
+    id3::frames(tag, version)
+        .into_iter()
+        .filter(|(id, _)| *id == b"SYLT")
+        .find_map(|(_, body)| {
+            // encoding, language[3], timestamp format, content type,
+            // descriptor, then (text, timestamp) pairs.
+            let (&encoding, rest) = body.split_first()?;
+            let rest = rest.get(3..)?;
+            let (&format, rest) = rest.split_first()?;
+            let (&content_type, rest) = rest.split_first()?;
+            // Format 2 is milliseconds (1 is MPEG frames); type 1 is lyrics.
+            if format != 2 || content_type > 1 {
+                return None;
+            }
+            let (_, mut rest) = id3::take_text(rest, encoding)?;
+            let mut lines = Vec::new();
+            while let Some((text, tail)) = id3::take_text(rest, encoding) {
+                let Some(stamp) = tail.get(0..4) else {
+                    break;
+                };
+                let start = u32::from_be_bytes([stamp[0], stamp[1], stamp[2], stamp[3]]);
+                // Lines often carry their line break at the front.
+                lines.push(LyricLine {
+                    start_ms: Some(start as u64),
+                    text: text.trim_matches(['\r', '\n']).to_string(),
+                });
+                rest = &tail[4..];
+            }
+            if lines.is_empty() {
+                return None;
+            }
+            lines.sort_by_key(|l| l.start_ms);
+            Some(Lyrics {
+                synced: true,
+                source: SOURCE_SYNCED_TAG.to_string(),
+                lines,
+                ..Default::default()
+            })
+        })
+}
+
+/// Locate a lyric sidecar next to `track_path`. Prefers `.lrc` (synced)
+/// over `.txt` (plain) and matches case-insensitively so `Song.MP3`
+/// and `song.lrc` still pair up.
+pub fn find_sidecar(track_path: &Path) -> Option<PathBuf> {
+    let parent = track_path.parent()?;
+    let stem = track_path.file_stem()?.to_str()?;
+    let dir = std::fs::read_dir(parent).ok()?;
+    let mut txt_hit: Option<PathBuf> = None;
+    for entry in dir.flatten() {
+        let path = entry.path();
+        let Some(fname) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !fname.eq_ignore_ascii_case(stem) {
+            continue;
+        }
+        let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match ext.to_ascii_lowercase().as_str() {
+            "lrc" => return Some(path),
+            "txt" if txt_hit.is_none() => txt_hit = Some(path),
+            _ => {}
+        }
+    }
+    txt_hit
+}
+
+/// Path we write to on upload. Always `.lrc` — even when the caller
+/// only sent plain text, since `.lrc` is a strict superset.
+pub fn sidecar_write_path(track_path: &Path) -> Option<PathBuf> {
+    let parent = track_path.parent()?;
+    let stem = track_path.file_stem()?.to_str()?;
+    Some(parent.join(format!("{stem}.lrc")))
+}
+
+/// Parse the sidecar file at `path`. Returns `None` if the file is
+/// unreadable or empty. Chooses parser by extension — plain-text `.txt`
+/// files skip the LRC header logic.
+pub fn parse_sidecar(path: &Path) -> Option<Lyrics> {
+    let text = std::fs::read_to_string(path).ok()?;
+    if text.trim().is_empty() {
+        return None;
+    }
+    let is_lrc = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|e| e.eq_ignore_ascii_case("lrc"))
+        .unwrap_or(false);
+    let mut lyrics = if is_lrc {
+        parse_lrc(&text)
+    } else {
+        parse_plain(&text)
+    };
+    lyrics.source = SOURCE_SIDECAR.to_string();
+    Some(lyrics)
+}
+
+/// Parse LRC content. Handles header tags and per-line timestamps.
+/// A line with N timestamps yields N `LyricLine`s all pointing at the
+/// same text — matches how synced players expand karaoke-style lines.
+pub fn parse_lrc(text: &str) -> Lyrics {
+    let mut lyrics = Lyrics::default();
+    let mut lines: Vec<LyricLine> = Vec::new();
+    let mut has_synced = false;
+
+    for raw_line in text.lines() {
+        let line = raw_line.trim_end_matches('\r').trim();
+        if line.is_empty() {
+            continue;
+        }
+        // Extract every `[…]` prefix; the remainder is the lyric text.
+        let mut rest = line;
+        let mut tags: Vec<&str> = Vec::new();
+        while let Some(inner) = rest.strip_prefix('[') {
+            let Some(end) = inner.find(']') else {
+                break;
+            };
+            tags.push(&inner[..end]);
+            rest = &inner[end + 1..];
+        }
+        if tags.is_empty() {
+            // No brackets → plain text line (rare in LRC, treat as unsynced).
+            lines.push(LyricLine {
+                text: rest.trim().to_string(),
+                start_ms: None,
+            });
+            continue;
+        }
+
+        let lyric_text = rest.trim().to_string();
+        for tag in tags {
+            if let Some(ms) = parse_timestamp_tag(tag) {
+                has_synced = true;
+                lines.push(LyricLine {
+                    text: lyric_text.clone(),
+                    start_ms: Some(ms),
+                });
+            } else if let Some((key, value)) = parse_metadata_tag(tag) {
+                apply_metadata(&mut lyrics, key, value);
+            }
+        }
+    }
+
+    // Apply LRC `offset:` field (in milliseconds; positive = later).
+    if let Some(offset) = lyrics.offset_ms {
+        for line in &mut lines {
+            if let Some(start) = line.start_ms.as_mut() {
+                *start = (*start as i64 + offset).max(0) as u64;
+            }
+        }
+    }
+    // Synced players expect lines in time order; repeated choruses written
+    // as `[t1][t2]text` would otherwise come out of order.
+    if has_synced {
+        lines.sort_by_key(|l| l.start_ms);
+    }
+    lyrics.synced = has_synced;
+    lyrics.lines = lines;
+    lyrics
+}
+
+/// Parse plain-text lyrics — one line per `LyricLine`, all unsynced.
+pub fn parse_plain(text: &str) -> Lyrics {
+    let lines = text
+        .lines()
+        .map(|l| LyricLine {
+            text: l.trim_end_matches('\r').trim_end().to_string(),
+            start_ms: None,
+        })
+        .collect();
+    Lyrics {
+        synced: false,
+        lines,
+        ..Default::default()
+    }
+}
+
+/// Try to parse `mm:ss.xx` / `mm:ss` / `mm:ss.xxx` inside a `[…]` tag.
+/// Returns milliseconds.
+fn parse_timestamp_tag(tag: &str) -> Option<u64> {
+    // First char must be a digit; header tags like `ar:foo` won't match.
+    if !tag.chars().next()?.is_ascii_digit() {
+        return None;
+    }
+    let (mm_str, rest) = tag.split_once(':')?;
+    let minutes: u64 = mm_str.parse().ok()?;
+    let (ss_str, frac_str) = match rest.split_once('.') {
+        Some((s, f)) => (s, f),
+        None => (rest, "0"),
+    };
+    let seconds: u64 = ss_str.parse().ok()?;
+    // LRC fractions can be 2 or 3 digits — normalize to milliseconds.
+    let mut frac: u64 = frac_str.parse().ok()?;
+    match frac_str.len() {
+        1 => frac *= 100,
+        2 => frac *= 10,
+        3 => {} // already ms
+        _ => return None,
+    }
+    Some(minutes * 60 * 1000 + seconds * 1000 + frac)
+}
+
+/// Header-style tags: `ar:artist`, `ti:title`, `offset:200`, etc.
+fn parse_metadata_tag(tag: &str) -> Option<(&str, &str)> {
+    let (k, v) = tag.split_once(':')?;
+    Some((k.trim(), v.trim()))
+}
+
+fn apply_metadata(lyrics: &mut Lyrics, key: &str, value: &str) {
+    match key.to_ascii_lowercase().as_str() {
+        "ar" => lyrics.artist = Some(value.to_string()),
+        "al" => lyrics.album = Some(value.to_string()),
+        "ti" => lyrics.title = Some(value.to_string()),
+        "au" => lyrics.author = Some(value.to_string()),
+        "by" => lyrics.by = Some(value.to_string()),
+        "length" | "len" => {
+            // LRC length is `mm:ss`.
+            if let Some((m, s)) = value.split_once(':') {
+                let mm: u64 = m.parse().unwrap_or(0);
+                let ss: u64 = s.parse().unwrap_or(0);
+                lyrics.length_ms = Some((mm * 60 + ss) * 1000);
+            }
+        }
+        "offset" => lyrics.offset_ms = Some(value.parse().unwrap_or(0)),
+        "re" => lyrics.creator = Some(value.to_string()),
+        "ve" => lyrics.version = Some(value.to_string()),
+        _ => {}
+    }
+}
+
+/// Write LRC (or plain) text next to `track_path`, replacing any sidecar
+/// already there.
+pub fn write_sidecar(track_path: &Path, text: &str) -> Result<PathBuf, Error> {
+    let out = sidecar_write_path(track_path)
+        .ok_or_else(|| anyhow::anyhow!("cannot derive sidecar path from {track_path:?}"))?;
+    std::fs::write(&out, text)?;
+    Ok(out)
+}
+
+/// Delete any sidecar `.lrc` / `.txt` next to `track_path`. Returns
+/// `true` if at least one file was removed. Missing file = success (idempotent).
+pub fn delete_sidecar(track_path: &Path) -> bool {
+    let mut removed = false;
+    if let Some(sc) = find_sidecar(track_path) {
+        if std::fs::remove_file(&sc).is_ok() {
+            removed = true;
+        }
+    }
+    // Also try the canonical write path, since find_sidecar might have
+    // returned the .txt while an obsolete .lrc still lingers.
+    if let Some(canonical) = sidecar_write_path(track_path) {
+        if canonical.exists() && std::fs::remove_file(&canonical).is_ok() {
+            removed = true;
+        }
+    }
+    removed
+}
+
+/// Serialize lyrics back to LRC text — enough round-trip for external
+/// players. Metadata that has no LRC tag (author, creator, version) is
+/// dropped, and the offset is not written since it is already applied.
+pub fn to_lrc(lyrics: &Lyrics) -> String {
+    let mut out = String::new();
+    let headers = [
+        ("ar", &lyrics.artist),
+        ("al", &lyrics.album),
+        ("ti", &lyrics.title),
+        ("by", &lyrics.by),
+    ];
+    for (key, value) in headers {
+        if let Some(v) = value.as_deref() {
+            out.push_str(&format!("[{key}:{v}]\n"));
+        }
+    }
+    for line in &lyrics.lines {
+        match line.start_ms {
+            Some(ms) => {
+                let mm = ms / 60_000;
+                let ss = (ms / 1_000) % 60;
+                let hh = (ms % 1_000) / 10;
+                out.push_str(&format!("[{mm:02}:{ss:02}.{hh:02}]{}\n", line.text));
+            }
+            None => {
+                out.push_str(&line.text);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_synced_lrc_with_header() {
+        let src = "[ar:The Artist]\n[ti:Song]\n[00:12.34]Hello world\n[01:00.00]Bye\n";
+        let lyrics = parse_lrc(src);
+        assert_eq!(lyrics.artist.as_deref(), Some("The Artist"));
+        assert_eq!(lyrics.title.as_deref(), Some("Song"));
+        assert!(lyrics.synced);
+        assert_eq!(lyrics.lines.len(), 2);
+        assert_eq!(lyrics.lines[0].text, "Hello world");
+        assert_eq!(lyrics.lines[0].start_ms, Some(12_340));
+        assert_eq!(lyrics.lines[1].start_ms, Some(60_000));
+    }
+
+    #[test]
+    fn multiple_timestamps_yield_multiple_lines() {
+        let src = "[00:20.00][00:10.00]Chorus\n[00:15.00]Verse\n";
+        let lyrics = parse_lrc(src);
+        let starts: Vec<_> = lyrics.lines.iter().map(|l| l.start_ms).collect();
+        assert_eq!(starts, vec![Some(10_000), Some(15_000), Some(20_000)]);
+        assert_eq!(lyrics.lines[0].text, "Chorus");
+        assert_eq!(lyrics.lines[2].text, "Chorus");
+    }
+
+    #[test]
+    fn applies_offset() {
+        let lyrics = parse_lrc("[offset:-500]\n[00:01.00]One\n[00:00.20]Zero\n");
+        assert_eq!(lyrics.lines[0].start_ms, Some(0));
+        assert_eq!(lyrics.lines[1].start_ms, Some(500));
+    }
+
+    #[test]
+    fn parses_plain_text() {
+        let src = "line one\nline two\n";
+        let lyrics = parse_plain(src);
+        assert_eq!(lyrics.lines.len(), 2);
+        assert!(lyrics.lines.iter().all(|l| l.start_ms.is_none()));
+        assert!(!lyrics.synced);
+    }
+
+    #[test]
+    fn tag_text_with_timestamps_is_synced() {
+        assert!(parse_text("[00:01.00]One\n[00:02.00]Two").synced);
+        let plain = parse_text("One\nTwo");
+        assert!(!plain.synced);
+        assert_eq!(plain.text(), "One\nTwo");
+    }
+
+    #[test]
+    fn finds_current_line() {
+        let lyrics = parse_lrc("[00:01.00]One\n[00:05.00]Two\n");
+        assert_eq!(current_line(&lyrics, 500), None);
+        assert_eq!(current_line(&lyrics, 1_000), Some(0));
+        assert_eq!(current_line(&lyrics, 9_000), Some(1));
+        assert_eq!(current_line(&parse_plain("One"), 9_000), None);
+    }
+
+    #[test]
+    fn parses_sylt_frame() {
+        let mut body = vec![3u8];
+        body.extend(b"eng");
+        body.extend([2u8, 1u8]);
+        body.extend(b"\0");
+        for (text, ms) in [("\nFirst", 1_000u32), ("\nSecond", 2_500)] {
+            body.extend(text.as_bytes());
+            body.push(0);
+            body.extend(ms.to_be_bytes());
+        }
+        let mut frame = b"SYLT".to_vec();
+        frame.extend((body.len() as u32).to_be_bytes());
+        frame.extend([0u8, 0u8]);
+        frame.extend(&body);
+
+        let lyrics = parse_sylt(&frame, 3).unwrap();
+        assert!(lyrics.synced);
+        assert_eq!(lyrics.source, SOURCE_SYNCED_TAG);
+        assert_eq!(
+            lyrics.lines,
+            vec![
+                LyricLine {
+                    start_ms: Some(1_000),
+                    text: "First".into()
+                },
+                LyricLine {
+                    start_ms: Some(2_500),
+                    text: "Second".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn lrc_round_trip() {
+        let src = "[ar:A]\n[00:01.50]One\n[01:02.00]Two\n";
+        assert_eq!(to_lrc(&parse_lrc(src)), src);
+    }
+}
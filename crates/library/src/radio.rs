@@ -0,0 +1,258 @@
+use anyhow::Error;
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::{entity::radio_station::RadioStation, repo};
+
+/// A station entry read from an M3U or PLS station list, before it is
+/// stored in the `radio_station` table.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StationEntry {
+    pub name: String,
+    pub stream_url: String,
+}
+
+/// Station fields supplied by API clients when adding or editing a station.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StationInput {
+    pub name: String,
+    pub stream_url: String,
+    pub homepage_url: Option<String>,
+    pub genre: Option<String>,
+    pub country: Option<String>,
+    pub codec: Option<String>,
+    pub bitrate: Option<u32>,
+    pub favicon: Option<String>,
+}
+
+impl StationInput {
+    /// A station needs a name and an `http(s)://` stream URL.
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty() && is_stream_url(&self.stream_url)
+    }
+}
+
+/// Parse an M3U / extended M3U station list. `#EXTINF` titles name the
+/// following URL; entries without a title fall back to the URL itself.
+/// Local paths are ignored — only `http(s)://` streams are radio stations.
+pub fn parse_m3u(content: &str) -> Vec<StationEntry> {
+    let mut entries = Vec::new();
+    let mut pending_title: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:<duration> [attrs],<title>
+            pending_title = info
+                .split_once(',')
+                .map(|(_, title)| title.trim().to_string())
+                .filter(|t| !t.is_empty());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if !is_stream_url(line) {
+            pending_title = None;
+            continue;
+        }
+        entries.push(StationEntry {
+            name: pending_title.take().unwrap_or_else(|| line.to_string()),
+            stream_url: line.to_string(),
+        });
+    }
+
+    entries
+}
+
+/// Parse a PLS station list (`FileN=` / `TitleN=` pairs under `[playlist]`).
+pub fn parse_pls(content: &str) -> Vec<StationEntry> {
+    let mut files: Vec<(u32, String)> = Vec::new();
+    let mut titles: std::collections::HashMap<u32, String> = Default::default();
+
+    for line in content.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if let Some(n) = key.strip_prefix("file").and_then(|n| n.parse().ok()) {
+            files.push((n, value.to_string()));
+        } else if let Some(n) = key.strip_prefix("title").and_then(|n| n.parse().ok()) {
+            titles.insert(n, value.to_string());
+        }
+    }
+
+    files.sort_by_key(|(n, _)| *n);
+    files
+        .into_iter()
+        .filter(|(_, url)| is_stream_url(url))
+        .map(|(n, url)| StationEntry {
+            name: titles
+                .remove(&n)
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| url.clone()),
+            stream_url: url,
+        })
+        .collect()
+}
+
+/// Parse a station list, sniffing the format from its content: PLS files
+/// start with a `[playlist]` section, everything else is treated as M3U.
+pub fn parse_station_list(content: &str) -> Vec<StationEntry> {
+    let is_pls = content
+        .lines()
+        .map(|l| l.trim().trim_start_matches('\u{feff}'))
+        .find(|l| !l.is_empty())
+        .map(|l| l.eq_ignore_ascii_case("[playlist]"))
+        .unwrap_or(false);
+    if is_pls {
+        parse_pls(content)
+    } else {
+        parse_m3u(content)
+    }
+}
+
+/// Store every station found in `content`. Stations whose stream URL is
+/// already known are updated in place, so re-importing a list is safe.
+pub async fn import_stations(
+    pool: Pool<Sqlite>,
+    content: &str,
+    genre: Option<&str>,
+) -> Result<Vec<RadioStation>, Error> {
+    let mut stations = Vec::new();
+    for entry in parse_station_list(content) {
+        let now = Utc::now();
+        let id = repo::radio_station::save(
+            pool.clone(),
+            RadioStation {
+                id: cuid::cuid1()?,
+                name: entry.name,
+                stream_url: entry.stream_url,
+                genre: genre.map(|g| g.to_string()),
+                created_at: now,
+                updated_at: now,
+                ..Default::default()
+            },
+        )
+        .await?;
+        if let Some(station) = repo::radio_station::find(pool.clone(), &id).await? {
+            stations.push(station);
+        }
+    }
+    Ok(stations)
+}
+
+pub async fn create_station(
+    pool: Pool<Sqlite>,
+    input: StationInput,
+) -> Result<Option<RadioStation>, Error> {
+    let now = Utc::now();
+    let id = repo::radio_station::save(
+        pool.clone(),
+        RadioStation {
+            id: cuid::cuid1()?,
+            name: input.name,
+            stream_url: input.stream_url,
+            homepage_url: input.homepage_url,
+            genre: input.genre,
+            country: input.country,
+            codec: input.codec,
+            bitrate: input.bitrate,
+            favicon: input.favicon,
+            created_at: now,
+            updated_at: now,
+        },
+    )
+    .await?;
+    Ok(repo::radio_station::find(pool, &id).await?)
+}
+
+/// Replace every editable field of station `id`. Returns `None` when no
+/// such station exists.
+pub async fn update_station(
+    pool: Pool<Sqlite>,
+    id: &str,
+    input: StationInput,
+) -> Result<Option<RadioStation>, Error> {
+    let Some(existing) = repo::radio_station::find(pool.clone(), id).await? else {
+        return Ok(None);
+    };
+    repo::radio_station::update(
+        pool.clone(),
+        RadioStation {
+            name: input.name,
+            stream_url: input.stream_url,
+            homepage_url: input.homepage_url,
+            genre: input.genre,
+            country: input.country,
+            codec: input.codec,
+            bitrate: input.bitrate,
+            favicon: input.favicon,
+            ..existing
+        },
+    )
+    .await?;
+    Ok(repo::radio_station::find(pool, id).await?)
+}
+
+pub fn is_stream_url(s: &str) -> bool {
+    s.starts_with("http://") || s.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_extended_m3u() {
+        let m3u = "#EXTM3U\n\
+                   #EXTINF:-1 tvg-logo=\"x.png\",Radio Paradise\n\
+                   https://stream.radioparadise.com/aac-320\n\
+                   \n\
+                   http://ice1.somafm.com/groovesalad-128-mp3\n\
+                   /home/user/Music/local.mp3\n";
+        let entries = parse_m3u(m3u);
+        assert_eq!(
+            entries,
+            vec![
+                StationEntry {
+                    name: "Radio Paradise".into(),
+                    stream_url: "https://stream.radioparadise.com/aac-320".into(),
+                },
+                StationEntry {
+                    name: "http://ice1.somafm.com/groovesalad-128-mp3".into(),
+                    stream_url: "http://ice1.somafm.com/groovesalad-128-mp3".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_pls_in_index_order() {
+        let pls = "[playlist]\n\
+                   NumberOfEntries=2\n\
+                   File2=http://b.example/stream\n\
+                   Title2=Second\n\
+                   File1=http://a.example/stream\n\
+                   Title1=First\n\
+                   Length1=-1\n\
+                   Version=2\n";
+        let entries = parse_station_list(pls);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "First");
+        assert_eq!(entries[0].stream_url, "http://a.example/stream");
+        assert_eq!(entries[1].name, "Second");
+    }
+
+    #[test]
+    fn pls_without_title_uses_url() {
+        let entries = parse_station_list("[playlist]\nFile1=https://x.example/live\n");
+        assert_eq!(entries[0].name, "https://x.example/live");
+    }
+}
@@ -0,0 +1,217 @@
+//! 1–5 star ratings of tracks, albums and artists, shared by every front
+//! end.
+//!
+//! Ratings live in the `rating` table. Track ratings can also travel with
+//! the files: ID3 `POPM` and Vorbis `FMPS_RATING` / `RATING` tags are read
+//! when a track is first scanned (unless `ROCKBOX_RATINGS_READ_TAGS=0`), and
+//! written back on every change when `ROCKBOX_RATINGS_WRITE_TAGS=1`.
+
+use std::{borrow::Cow, fs::File, io::BufReader, path::Path};
+
+use anyhow::Error;
+use lofty::{
+    config::{ParseOptions, WriteOptions},
+    file::{AudioFile, TaggedFileExt},
+    id3::v2::{Frame, FrameId, PopularimeterFrame},
+    mpeg::MpegFile,
+    probe::Probe,
+    tag::{ItemKey, Tag, TagExt, TagType},
+};
+use sqlx::{Pool, Sqlite};
+use tracing::warn;
+
+use crate::{
+    changes,
+    entity::rating::{KIND_TRACK, MAX_RATING},
+    id3, repo,
+};
+
+/// `POPM` bytes written for 0–5 stars, the scale Windows Media Player and
+/// most taggers share.
+const POPM_BYTES: [u8; 6] = [0, 1, 64, 128, 196, 255];
+
+/// The `POPM` email we write under.
+const POPM_EMAIL: &str = "rockbox";
+
+fn env_flag(name: &str, default: bool) -> bool {
+    match std::env::var(name) {
+        Ok(v) => matches!(v.trim(), "1" | "true" | "yes" | "on"),
+        Err(_) => default,
+    }
+}
+
+pub fn stars_from_popm(byte: u8) -> u8 {
+    match byte {
+        0 => 0,
+        1..=31 => 1,
+        32..=95 => 2,
+        96..=159 => 3,
+        160..=223 => 4,
+        _ => 5,
+    }
+}
+
+pub fn popm_from_stars(stars: u8) -> u8 {
+    POPM_BYTES[stars.min(MAX_RATING) as usize]
+}
+
+/// `FMPS_RATING` is 0.0–1.0; `RATING` is 0–5 for some taggers and 0–100
+/// for others.
+fn stars_from_vorbis(key: &str, value: &str) -> Option<u8> {
+    let v: f64 = value.trim().parse().ok()?;
+    let stars = match key {
+        "FMPS_RATING" => v * 5.0,
+        _ if v <= 5.0 => v,
+        _ => v / 20.0,
+    };
+    Some(stars.round().clamp(0.0, MAX_RATING as f64) as u8)
+}
+
+/// The rating stored in a file's tags, if any.
+pub fn read_tags(path: &Path) -> Result<Option<u8>, Error> {
+    let is_id3 = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("mp3"));
+    if is_id3 {
+        let mut reader = BufReader::new(File::open(path)?);
+        if let Ok(Some((version, tag))) = id3::read_tag(&mut reader) {
+            // email, rating byte, then an optional play counter.
+            let rating = id3::frames(&tag, version)
+                .into_iter()
+                .filter(|(id, _)| *id == b"POPM")
+                .filter_map(|(_, body)| id3::take_cstring(body))
+                .filter_map(|(_, rest)| rest.first().copied())
+                .map(stars_from_popm)
+                .find(|stars| *stars > 0);
+            return Ok(rating);
+        }
+        return Ok(None);
+    }
+
+    let tagged_file = match Probe::open(path).and_then(|p| p.read()) {
+        Ok(tagged_file) => tagged_file,
+        Err(_) => return Ok(None),
+    };
+    Ok(tagged_file.tags().iter().find_map(vorbis_rating))
+}
+
+fn vorbis_rating(tag: &Tag) -> Option<u8> {
+    ["FMPS_RATING", "RATING"].iter().find_map(|key| {
+        tag.get_string(&ItemKey::Unknown(key.to_string()))
+            .and_then(|value| stars_from_vorbis(key, value))
+            .filter(|stars| *stars > 0)
+    })
+}
+
+/// Write `stars` into a file's tags, replacing any rating there. Files
+/// without ID3v2 or Vorbis comments are left alone.
+pub fn write_tags(path: &Path, stars: u8) -> Result<(), Error> {
+    let is_id3 = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("mp3"));
+    if is_id3 {
+        // Edit the ID3v2 tag itself: a generic `Tag` would drop the frames
+        // lofty doesn't map, chapters and synced lyrics among them.
+        let mut file = MpegFile::read_from(&mut File::open(path)?, ParseOptions::new())?;
+        if file.id3v2().is_none() {
+            file.set_id3v2(Default::default());
+        }
+        if let Some(tag) = file.id3v2_mut() {
+            let _ = tag.remove(&FrameId::Valid(Cow::Borrowed("POPM"))).count();
+            if stars > 0 {
+                tag.insert(Frame::Popularimeter(PopularimeterFrame::new(
+                    POPM_EMAIL.to_string(),
+                    popm_from_stars(stars),
+                    0,
+                )));
+            }
+        }
+        file.save_to_path(path, WriteOptions::default())?;
+        return Ok(());
+    }
+
+    let mut tagged_file = Probe::open(path)?.read()?;
+    let Some(tag) = tagged_file.tag_mut(TagType::VorbisComments) else {
+        return Ok(());
+    };
+    let fmps = ItemKey::Unknown("FMPS_RATING".to_string());
+    let rating = ItemKey::Unknown("RATING".to_string());
+    match stars {
+        0 => {
+            tag.remove_key(&fmps);
+            tag.remove_key(&rating);
+        }
+        _ => {
+            tag.insert_text(fmps, format!("{:.1}", stars as f64 / MAX_RATING as f64));
+            tag.insert_text(rating, (stars as u32 * 20).to_string());
+        }
+    }
+    tag.save_to_path(path, WriteOptions::default())?;
+    Ok(())
+}
+
+/// Rate an item (0 clears its rating). Track ratings are written to the
+/// file's tags when `ROCKBOX_RATINGS_WRITE_TAGS=1`.
+pub async fn set(pool: Pool<Sqlite>, kind: &str, item_id: &str, stars: u8) -> Result<(), Error> {
+    let stars = stars.min(MAX_RATING);
+    repo::rating::save(pool.clone(), kind, item_id, stars).await?;
+    if kind != KIND_TRACK {
+        return Ok(());
+    }
+    changes::track_changed(item_id);
+    if env_flag("ROCKBOX_RATINGS_WRITE_TAGS", false) {
+        if let Some(track) = repo::track::find(pool, item_id).await? {
+            if !track.is_remote {
+                let path = track.path.clone();
+                let written =
+                    tokio::task::spawn_blocking(move || write_tags(Path::new(&path), stars))
+                        .await?;
+                if let Err(e) = written {
+                    warn!("ratings: could not tag {}: {}", track.path, e);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Take a newly scanned track's rating from its tags.
+pub async fn import_from_tags(pool: Pool<Sqlite>, track_id: &str, path: &str) {
+    if !env_flag("ROCKBOX_RATINGS_READ_TAGS", true) {
+        return;
+    }
+    match read_tags(Path::new(path)) {
+        Ok(Some(stars)) => {
+            if let Err(e) = repo::rating::save(pool, KIND_TRACK, track_id, stars).await {
+                warn!("ratings: could not save rating of {}: {}", path, e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("ratings: could not read {}: {}", path, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn popm_round_trips() {
+        for stars in 0..=MAX_RATING {
+            assert_eq!(stars_from_popm(popm_from_stars(stars)), stars);
+        }
+        assert_eq!(stars_from_popm(0), 0);
+        assert_eq!(stars_from_popm(100), 3);
+    }
+
+    #[test]
+    fn vorbis_scales() {
+        assert_eq!(stars_from_vorbis("FMPS_RATING", "0.8"), Some(4));
+        assert_eq!(stars_from_vorbis("RATING", "3"), Some(3));
+        assert_eq!(stars_from_vorbis("RATING", "60"), Some(3));
+        assert_eq!(stars_from_vorbis("RATING", "100"), Some(5));
+        assert_eq!(stars_from_vorbis("RATING", "n/a"), None);
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Error;
+use lofty::{file::TaggedFileExt, probe::Probe, tag::ItemKey};
+
+/// ReplayGain and R128 tags of a track as (name, value) pairs, named the
+/// way Vorbis comments spell them ("REPLAYGAIN_TRACK_GAIN",
+/// "R128_TRACK_GAIN", …). Empty when the file can't be read.
+pub fn extract_replaygain(track_path: &str) -> Result<Vec<(String, String)>, Error> {
+    let probe = match Probe::open(track_path) {
+        Ok(p) => p,
+        Err(e) => {
+            println!("replaygain: cannot open {}: {}", track_path, e);
+            return Ok(Vec::new());
+        }
+    };
+    let tagged_file = match probe.read() {
+        Ok(tagged_file) => tagged_file,
+        Err(e) => {
+            println!("Error opening file: {}", e);
+            return Ok(Vec::new());
+        }
+    };
+
+    let mut tags = Vec::new();
+    for tag in tagged_file.tags() {
+        for item in tag.items() {
+            let name = match item.key() {
+                ItemKey::ReplayGainTrackGain => "REPLAYGAIN_TRACK_GAIN".to_string(),
+                ItemKey::ReplayGainAlbumGain => "REPLAYGAIN_ALBUM_GAIN".to_string(),
+                ItemKey::ReplayGainTrackPeak => "REPLAYGAIN_TRACK_PEAK".to_string(),
+                ItemKey::ReplayGainAlbumPeak => "REPLAYGAIN_ALBUM_PEAK".to_string(),
+                // R128 gains, and ReplayGain in formats lofty doesn't map,
+                // e.g. "----:com.apple.iTunes:replaygain_track_gain".
+                ItemKey::Unknown(key) => {
+                    let key = key.rsplit(':').next().unwrap_or(key).to_uppercase();
+                    if !key.starts_with("REPLAYGAIN_") && !key.starts_with("R128_") {
+                        continue;
+                    }
+                    key
+                }
+                _ => continue,
+            };
+            if let Some(value) = item.value().text() {
+                tags.push((name, value.to_string()));
+            }
+        }
+    }
+
+    Ok(tags)
+}
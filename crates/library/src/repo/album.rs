@@ -90,11 +90,11 @@ pub async fn find_by_artist(
                 SELECT 1 FROM track
                 WHERE track.album_id = album.id
                   AND track.artist_id = $1
-                  AND track.is_remote = 0
+                  AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
             )
         )
         AND EXISTS (
-            SELECT 1 FROM track WHERE track.album_id = album.id AND track.is_remote = 0
+            SELECT 1 FROM track WHERE track.album_id = album.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
         )
         ORDER BY album.title ASC
         "#,
@@ -121,11 +121,11 @@ pub async fn count_by_artist(
         SELECT artist_id, COUNT(DISTINCT album_id) as cnt FROM (
             SELECT album.artist_id, album.id AS album_id FROM album
             WHERE EXISTS (
-                SELECT 1 FROM track WHERE track.album_id = album.id AND track.is_remote = 0
+                SELECT 1 FROM track WHERE track.album_id = album.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
             )
             UNION
             SELECT track.artist_id, track.album_id FROM track
-            WHERE track.is_remote = 0 AND track.artist_id != ''
+            WHERE (track.is_remote = 0 OR track.source_id IS NOT NULL) AND track.artist_id != ''
         )
         GROUP BY artist_id
         "#,
@@ -161,7 +161,7 @@ pub async fn all(pool: Pool<Sqlite>) -> Result<Vec<Album>, sqlx::Error> {
     match sqlx::query_as::<_, Album>(
         r#"
         SELECT * FROM album WHERE EXISTS (
-          SELECT 1 FROM track WHERE track.album_id = album.id AND track.is_remote = 0
+          SELECT 1 FROM track WHERE track.album_id = album.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
         ) ORDER BY title ASC
         "#,
     )
@@ -176,6 +176,26 @@ pub async fn all(pool: Pool<Sqlite>) -> Result<Vec<Album>, sqlx::Error> {
     }
 }
 
+/// Albums made of audiobook tracks — each one is a book.
+pub async fn books(pool: Pool<Sqlite>) -> Result<Vec<Album>, sqlx::Error> {
+    match sqlx::query_as::<_, Album>(
+        r#"
+        SELECT * FROM album WHERE EXISTS (
+          SELECT 1 FROM track WHERE track.album_id = album.id AND track.media_type = 'book'
+        ) ORDER BY title ASC
+        "#,
+    )
+    .fetch_all(&pool)
+    .await
+    {
+        Ok(albums) => Ok(albums),
+        Err(e) => {
+            warn!("Error finding books: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
 /// Paginated album list narrowed by Jellyfin's alpha-jump filter params —
 /// see [`super::artist::filtered`] for the parameter semantics.
 pub async fn filtered(
@@ -196,7 +216,7 @@ pub async fn filtered(
     let offset_idx = binds.len() + 2;
     let sql = format!(
         "SELECT * FROM album WHERE EXISTS (
-           SELECT 1 FROM track WHERE track.album_id = album.id AND track.is_remote = 0
+           SELECT 1 FROM track WHERE track.album_id = album.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
          ) {extra}
          ORDER BY title COLLATE NOCASE LIMIT ?{limit_idx} OFFSET ?{offset_idx}",
         extra = if where_sql.is_empty() {
@@ -226,7 +246,7 @@ pub async fn count_filtered(
     );
     let sql = format!(
         "SELECT COUNT(*) FROM album WHERE EXISTS (
-           SELECT 1 FROM track WHERE track.album_id = album.id AND track.is_remote = 0
+           SELECT 1 FROM track WHERE track.album_id = album.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
          ) {extra}",
         extra = if where_sql.is_empty() {
             String::new()
@@ -247,7 +267,7 @@ pub async fn name_prefixes(pool: Pool<Sqlite>) -> Result<Vec<String>, sqlx::Erro
         "album",
         "title",
         Some(
-            "EXISTS (SELECT 1 FROM track WHERE track.album_id = album.id AND track.is_remote = 0)",
+            "EXISTS (SELECT 1 FROM track WHERE track.album_id = album.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL))",
         ),
     )
     .await
@@ -265,3 +285,39 @@ pub async fn update_album_art(
         .await?;
     Ok(())
 }
+
+const ORPHANS: &str =
+    "SELECT id FROM album WHERE NOT EXISTS (SELECT 1 FROM track WHERE track.album_id = album.id)";
+
+/// Albums no track points at any more.
+pub async fn orphans(pool: Pool<Sqlite>) -> Result<Vec<Album>, sqlx::Error> {
+    sqlx::query_as::<_, Album>(&format!(
+        "SELECT * FROM album WHERE id IN ({}) ORDER BY title ASC",
+        ORPHANS
+    ))
+    .fetch_all(&pool)
+    .await
+}
+
+/// Delete [`orphans`] and the rows hanging off them. Returns how many
+/// albums were removed.
+pub async fn delete_orphans(pool: Pool<Sqlite>) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!(
+        "DELETE FROM album_tracks WHERE album_id IN ({})",
+        ORPHANS
+    ))
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(&format!(
+        "DELETE FROM rating WHERE kind = 'album' AND item_id IN ({})",
+        ORPHANS
+    ))
+    .execute(&mut *tx)
+    .await?;
+    let result = sqlx::query(&format!("DELETE FROM album WHERE id IN ({})", ORPHANS))
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
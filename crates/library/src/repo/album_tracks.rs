@@ -45,7 +45,7 @@ pub async fn find_by_album(pool: Pool<Sqlite>, album_id: &str) -> Result<Vec<Tra
         r#"
         SELECT track.* FROM album_tracks
         INNER JOIN track ON album_tracks.track_id = track.id
-        WHERE album_tracks.album_id = $1
+        WHERE album_tracks.album_id = $1 AND track.hidden = 0
         ORDER BY track.disc_number, track.track_number ASC
         "#,
     )
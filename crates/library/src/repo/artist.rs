@@ -89,7 +89,7 @@ pub async fn all(pool: Pool<Sqlite>) -> Result<Vec<Artist>, Error> {
     match sqlx::query_as::<_, Artist>(
         r#"
         SELECT * FROM artist WHERE EXISTS (
-          SELECT 1 FROM track WHERE track.artist_id = artist.id AND track.is_remote = 0
+          SELECT 1 FROM track WHERE track.artist_id = artist.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
         ) ORDER BY name ASC
         "#,
     )
@@ -126,7 +126,7 @@ pub async fn filtered(
     let offset_idx = binds.len() + 2;
     let sql = format!(
         "SELECT * FROM artist WHERE EXISTS (
-           SELECT 1 FROM track WHERE track.artist_id = artist.id AND track.is_remote = 0
+           SELECT 1 FROM track WHERE track.artist_id = artist.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
          ) {extra}
          ORDER BY name COLLATE NOCASE LIMIT ?{limit_idx} OFFSET ?{offset_idx}",
         extra = if where_sql.is_empty() {
@@ -156,7 +156,7 @@ pub async fn count_filtered(
     );
     let sql = format!(
         "SELECT COUNT(*) FROM artist WHERE EXISTS (
-           SELECT 1 FROM track WHERE track.artist_id = artist.id AND track.is_remote = 0
+           SELECT 1 FROM track WHERE track.artist_id = artist.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL)
          ) {extra}",
         extra = if where_sql.is_empty() {
             String::new()
@@ -176,7 +176,7 @@ pub async fn name_prefixes(pool: Pool<Sqlite>) -> Result<Vec<String>, Error> {
         &pool,
         "artist",
         "name",
-        Some("EXISTS (SELECT 1 FROM track WHERE track.artist_id = artist.id AND track.is_remote = 0)"),
+        Some("EXISTS (SELECT 1 FROM track WHERE track.artist_id = artist.id AND (track.is_remote = 0 OR track.source_id IS NOT NULL))"),
     )
     .await
 }
@@ -243,3 +243,42 @@ pub async fn save_artist_genre(
         }
     }
 }
+
+const ORPHANS: &str = "SELECT id FROM artist
+     WHERE NOT EXISTS (SELECT 1 FROM track WHERE track.artist_id = artist.id)
+       AND NOT EXISTS (SELECT 1 FROM album WHERE album.artist_id = artist.id)";
+
+/// Artists neither a track nor an album points at any more.
+pub async fn orphans(pool: Pool<Sqlite>) -> Result<Vec<Artist>, Error> {
+    sqlx::query_as::<_, Artist>(&format!(
+        "SELECT * FROM artist WHERE id IN ({}) ORDER BY name ASC",
+        ORPHANS
+    ))
+    .fetch_all(&pool)
+    .await
+}
+
+/// Delete [`orphans`] and the rows hanging off them. Returns how many
+/// artists were removed.
+pub async fn delete_orphans(pool: Pool<Sqlite>) -> Result<u64, Error> {
+    let mut tx = pool.begin().await?;
+    for table in ["artist_tracks", "artist_genres"] {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE artist_id IN ({})",
+            table, ORPHANS
+        ))
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query(&format!(
+        "DELETE FROM rating WHERE kind = 'artist' AND item_id IN ({})",
+        ORPHANS
+    ))
+    .execute(&mut *tx)
+    .await?;
+    let result = sqlx::query(&format!("DELETE FROM artist WHERE id IN ({})", ORPHANS))
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
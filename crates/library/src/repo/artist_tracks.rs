@@ -48,7 +48,7 @@ pub async fn find_by_artist(
         r#"
         SELECT * FROM artist_tracks
         LEFT JOIN track ON artist_tracks.track_id = track.id
-        WHERE artist_tracks.artist_id = $1
+        WHERE artist_tracks.artist_id = $1 AND track.hidden = 0
         ORDER BY title ASC
         "#,
     )
@@ -0,0 +1,48 @@
+use crate::entity::bookmark::Bookmark;
+use sqlx::{Error, Pool, Sqlite};
+
+pub async fn save(
+    pool: Pool<Sqlite>,
+    album_id: &str,
+    track_id: &str,
+    position_ms: u32,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO audiobook_bookmark (album_id, track_id, position_ms, updated_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT(album_id) DO UPDATE SET
+          track_id = excluded.track_id,
+          position_ms = excluded.position_ms,
+          updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(album_id)
+    .bind(track_id)
+    .bind(position_ms)
+    .bind(chrono::Utc::now())
+    .execute(&pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn find(pool: Pool<Sqlite>, album_id: &str) -> Result<Option<Bookmark>, Error> {
+    sqlx::query_as::<_, Bookmark>("SELECT * FROM audiobook_bookmark WHERE album_id = $1")
+        .bind(album_id)
+        .fetch_optional(&pool)
+        .await
+}
+
+pub async fn all(pool: Pool<Sqlite>) -> Result<Vec<Bookmark>, Error> {
+    sqlx::query_as::<_, Bookmark>("SELECT * FROM audiobook_bookmark ORDER BY updated_at DESC")
+        .fetch_all(&pool)
+        .await
+}
+
+pub async fn delete(pool: Pool<Sqlite>, album_id: &str) -> Result<bool, Error> {
+    let result = sqlx::query("DELETE FROM audiobook_bookmark WHERE album_id = $1")
+        .bind(album_id)
+        .execute(&pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
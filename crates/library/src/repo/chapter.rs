@@ -0,0 +1,49 @@
+use crate::entity::chapter::Chapter;
+use sqlx::{Error, Pool, Sqlite};
+
+/// Replace the chapters of a track.
+pub async fn save_all(
+    pool: Pool<Sqlite>,
+    track_id: &str,
+    chapters: &[Chapter],
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM track_chapter WHERE track_id = $1")
+        .bind(track_id)
+        .execute(&mut *tx)
+        .await?;
+    for chapter in chapters {
+        sqlx::query(
+            r#"
+            INSERT INTO track_chapter (id, track_id, position, title, start_ms, end_ms)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(&chapter.id)
+        .bind(track_id)
+        .bind(chapter.position)
+        .bind(&chapter.title)
+        .bind(chapter.start_ms)
+        .bind(chapter.end_ms)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn find_by_track(pool: Pool<Sqlite>, track_id: &str) -> Result<Vec<Chapter>, Error> {
+    sqlx::query_as::<_, Chapter>(
+        "SELECT * FROM track_chapter WHERE track_id = $1 ORDER BY position ASC",
+    )
+    .bind(track_id)
+    .fetch_all(&pool)
+    .await
+}
+
+pub async fn count_by_track(pool: Pool<Sqlite>, track_id: &str) -> Result<i64, Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM track_chapter WHERE track_id = $1")
+        .bind(track_id)
+        .fetch_one(&pool)
+        .await
+}
@@ -1,3 +1,4 @@
+use crate::changes;
 use crate::entity::{album::Album, favourites::Favourites, track::Track};
 use sqlx::{Pool, Sqlite};
 
@@ -37,6 +38,9 @@ pub async fn save(pool: Pool<Sqlite>, favourite: Favourites) -> Result<(), sqlx:
     .bind(&favourite.created_at)
     .execute(&pool)
     .await?;
+    if let Some(track_id) = &favourite.track_id {
+        changes::track_changed(track_id);
+    }
     Ok(())
 }
 
@@ -90,5 +94,6 @@ pub async fn delete(pool: Pool<Sqlite>, id: &str) -> Result<(), sqlx::Error> {
     .bind(id)
     .execute(&pool)
     .await?;
+    changes::track_changed(id);
     Ok(())
 }
@@ -36,7 +36,7 @@ pub async fn all(pool: Pool<Sqlite>) -> Result<Vec<Genre>, Error> {
         SELECT DISTINCT g.* FROM genre g
         INNER JOIN artist_genres ag ON ag.genre_id = g.id
         INNER JOIN track t ON t.artist_id = ag.artist_id
-        WHERE t.is_remote = 0
+        WHERE (t.is_remote = 0 OR t.source_id IS NOT NULL)
         ORDER BY g.name ASC
         "#,
     )
@@ -71,7 +71,7 @@ pub async fn find_tracks(pool: Pool<Sqlite>, genre_id: &str) -> Result<Vec<Track
         r#"
         SELECT DISTINCT t.* FROM track t
         INNER JOIN artist_genres ag ON ag.artist_id = t.artist_id
-        WHERE ag.genre_id = $1 AND t.is_remote = 0
+        WHERE ag.genre_id = $1 AND (t.is_remote = 0 OR t.source_id IS NOT NULL) AND t.hidden = 0
         ORDER BY t.title ASC
         "#,
     )
@@ -86,7 +86,7 @@ pub async fn find_albums(pool: Pool<Sqlite>, genre_id: &str) -> Result<Vec<Album
         SELECT DISTINCT a.* FROM album a
         INNER JOIN track t ON t.album_id = a.id
         INNER JOIN artist_genres ag ON ag.artist_id = t.artist_id
-        WHERE ag.genre_id = $1 AND t.is_remote = 0
+        WHERE ag.genre_id = $1 AND (t.is_remote = 0 OR t.source_id IS NOT NULL) AND t.hidden = 0
         ORDER BY a.title ASC
         "#,
     )
@@ -101,7 +101,7 @@ pub async fn find_artists(pool: Pool<Sqlite>, genre_id: &str) -> Result<Vec<Arti
         SELECT DISTINCT ar.* FROM artist ar
         INNER JOIN artist_genres ag ON ag.artist_id = ar.id
         INNER JOIN track t ON t.artist_id = ar.id
-        WHERE ag.genre_id = $1 AND t.is_remote = 0
+        WHERE ag.genre_id = $1 AND (t.is_remote = 0 OR t.source_id IS NOT NULL) AND t.hidden = 0
         ORDER BY ar.name ASC
         "#,
     )
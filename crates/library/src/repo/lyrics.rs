@@ -0,0 +1,42 @@
+use crate::entity::lyrics::TrackLyrics;
+use sqlx::{Error, Pool, Sqlite};
+
+pub async fn save(
+    pool: Pool<Sqlite>,
+    track_id: &str,
+    content: Option<&str>,
+    stamp: i64,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO track_lyrics (track_id, content, stamp, updated_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT(track_id) DO UPDATE SET
+          content = excluded.content,
+          stamp = excluded.stamp,
+          updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(track_id)
+    .bind(content)
+    .bind(stamp)
+    .bind(chrono::Utc::now())
+    .execute(&pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn find(pool: Pool<Sqlite>, track_id: &str) -> Result<Option<TrackLyrics>, Error> {
+    sqlx::query_as::<_, TrackLyrics>("SELECT * FROM track_lyrics WHERE track_id = $1")
+        .bind(track_id)
+        .fetch_optional(&pool)
+        .await
+}
+
+pub async fn delete(pool: Pool<Sqlite>, track_id: &str) -> Result<(), Error> {
+    sqlx::query("DELETE FROM track_lyrics WHERE track_id = $1")
+        .bind(track_id)
+        .execute(&pool)
+        .await?;
+    Ok(())
+}
@@ -2,10 +2,15 @@ pub mod album;
 pub mod album_tracks;
 pub mod artist;
 pub mod artist_tracks;
+pub mod bookmark;
+pub mod chapter;
 pub mod favourites;
 pub mod folder;
 pub mod genre;
+pub mod lyrics;
 pub(crate) mod name_filter;
 pub mod playlist;
 pub mod playlist_tracks;
+pub mod radio_station;
+pub mod rating;
 pub mod track;
@@ -0,0 +1,117 @@
+use crate::entity::radio_station::RadioStation;
+use sqlx::{Error, Pool, Sqlite};
+
+/// Insert a station, or refresh its metadata when the stream URL is already
+/// known. Returns the id of the stored row.
+pub async fn save(pool: Pool<Sqlite>, station: RadioStation) -> Result<String, Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO radio_station (
+          id,
+          name,
+          stream_url,
+          homepage_url,
+          genre,
+          country,
+          codec,
+          bitrate,
+          favicon,
+          created_at,
+          updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT(stream_url) DO UPDATE SET
+          name = excluded.name,
+          homepage_url = COALESCE(excluded.homepage_url, homepage_url),
+          genre = COALESCE(excluded.genre, genre),
+          country = COALESCE(excluded.country, country),
+          codec = COALESCE(excluded.codec, codec),
+          bitrate = COALESCE(excluded.bitrate, bitrate),
+          favicon = COALESCE(excluded.favicon, favicon),
+          updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&station.id)
+    .bind(&station.name)
+    .bind(&station.stream_url)
+    .bind(&station.homepage_url)
+    .bind(&station.genre)
+    .bind(&station.country)
+    .bind(&station.codec)
+    .bind(station.bitrate)
+    .bind(&station.favicon)
+    .bind(station.created_at)
+    .bind(station.updated_at)
+    .execute(&pool)
+    .await?;
+
+    let row: (String,) = sqlx::query_as("SELECT id FROM radio_station WHERE stream_url = $1")
+        .bind(&station.stream_url)
+        .fetch_one(&pool)
+        .await?;
+    Ok(row.0)
+}
+
+pub async fn update(pool: Pool<Sqlite>, station: RadioStation) -> Result<bool, Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE radio_station SET
+          name = $2,
+          stream_url = $3,
+          homepage_url = $4,
+          genre = $5,
+          country = $6,
+          codec = $7,
+          bitrate = $8,
+          favicon = $9,
+          updated_at = $10
+        WHERE id = $1
+        "#,
+    )
+    .bind(&station.id)
+    .bind(&station.name)
+    .bind(&station.stream_url)
+    .bind(&station.homepage_url)
+    .bind(&station.genre)
+    .bind(&station.country)
+    .bind(&station.codec)
+    .bind(station.bitrate)
+    .bind(&station.favicon)
+    .bind(chrono::Utc::now())
+    .execute(&pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn find(pool: Pool<Sqlite>, id: &str) -> Result<Option<RadioStation>, Error> {
+    sqlx::query_as::<_, RadioStation>("SELECT * FROM radio_station WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+}
+
+pub async fn find_by_stream_url(
+    pool: Pool<Sqlite>,
+    stream_url: &str,
+) -> Result<Option<RadioStation>, Error> {
+    sqlx::query_as::<_, RadioStation>("SELECT * FROM radio_station WHERE stream_url = $1")
+        .bind(stream_url)
+        .fetch_optional(&pool)
+        .await
+}
+
+pub async fn all(pool: Pool<Sqlite>) -> Result<Vec<RadioStation>, Error> {
+    sqlx::query_as::<_, RadioStation>(
+        "SELECT * FROM radio_station ORDER BY name COLLATE NOCASE ASC",
+    )
+    .fetch_all(&pool)
+    .await
+}
+
+pub async fn delete(pool: Pool<Sqlite>, id: &str) -> Result<bool, Error> {
+    let result = sqlx::query("DELETE FROM radio_station WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
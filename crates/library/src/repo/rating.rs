@@ -0,0 +1,53 @@
+use crate::entity::rating::Rating;
+use sqlx::{Error, Pool, Sqlite};
+
+/// Store `rating` (1–5) for an item, or forget it when `rating` is 0.
+pub async fn save(pool: Pool<Sqlite>, kind: &str, item_id: &str, rating: u8) -> Result<(), Error> {
+    if rating == 0 {
+        delete(pool, kind, item_id).await?;
+        return Ok(());
+    }
+    sqlx::query(
+        r#"
+        INSERT INTO rating (kind, item_id, rating, updated_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT(kind, item_id) DO UPDATE SET
+          rating = excluded.rating,
+          updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(kind)
+    .bind(item_id)
+    .bind(rating)
+    .bind(chrono::Utc::now())
+    .execute(&pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn find(pool: Pool<Sqlite>, kind: &str, item_id: &str) -> Result<Option<u8>, Error> {
+    sqlx::query_scalar("SELECT rating FROM rating WHERE kind = $1 AND item_id = $2")
+        .bind(kind)
+        .bind(item_id)
+        .fetch_optional(&pool)
+        .await
+}
+
+/// Every rated item of `kind`, best first.
+pub async fn all(pool: Pool<Sqlite>, kind: &str) -> Result<Vec<Rating>, Error> {
+    sqlx::query_as::<_, Rating>(
+        "SELECT * FROM rating WHERE kind = $1 ORDER BY rating DESC, updated_at DESC",
+    )
+    .bind(kind)
+    .fetch_all(&pool)
+    .await
+}
+
+pub async fn delete(pool: Pool<Sqlite>, kind: &str, item_id: &str) -> Result<bool, Error> {
+    let result = sqlx::query("DELETE FROM rating WHERE kind = $1 AND item_id = $2")
+        .bind(kind)
+        .bind(item_id)
+        .execute(&pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -27,9 +27,11 @@ pub async fn save(pool: Pool<Sqlite>, track: Track) -> Result<String, Error> {
           artist_id,
           album_id,
           album_art,
-          is_remote
+          is_remote,
+          source_id,
+          remote_id
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
         "#,
     )
     .bind(&track.id)
@@ -55,6 +57,8 @@ pub async fn save(pool: Pool<Sqlite>, track: Track) -> Result<String, Error> {
     .bind(&track.album_id)
     .bind(&track.album_art)
     .bind(track.is_remote)
+    .bind(&track.source_id)
+    .bind(&track.remote_id)
     .execute(&pool)
     .await {
         Ok(_) => Ok(track.id.clone()),
@@ -78,7 +82,10 @@ pub async fn filter(
     pool: Pool<Sqlite>,
     r#where: (String, Vec<String>),
 ) -> Result<Vec<Track>, Error> {
-    let sql = format!("SELECT * FROM track WHERE is_remote = 0 AND {}", r#where.0);
+    let sql = format!(
+        "SELECT * FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 AND {}",
+        r#where.0
+    );
     let mut query = sqlx::query_as(&sql);
 
     for value in r#where.1 {
@@ -131,6 +138,14 @@ pub async fn delete_by_path(pool: Pool<Sqlite>, path: &str) -> Result<Option<Tra
         .bind(&track.id)
         .execute(&mut *tx)
         .await?;
+    sqlx::query("DELETE FROM track_chapter WHERE track_id = $1")
+        .bind(&track.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM track_lyrics WHERE track_id = $1")
+        .bind(&track.id)
+        .execute(&mut *tx)
+        .await?;
     sqlx::query("DELETE FROM track WHERE id = $1")
         .bind(&track.id)
         .execute(&mut *tx)
@@ -142,7 +157,7 @@ pub async fn delete_by_path(pool: Pool<Sqlite>, path: &str) -> Result<Option<Tra
 
 pub async fn all(pool: Pool<Sqlite>) -> Result<Vec<Track>, Error> {
     let result: Vec<Track> =
-        sqlx::query_as("SELECT * FROM track WHERE is_remote = 0 ORDER BY title ASC")
+        sqlx::query_as("SELECT * FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 ORDER BY title ASC")
             .fetch_all(&pool)
             .await?;
     Ok(result)
@@ -167,7 +182,7 @@ pub async fn filtered(
     let limit_idx = binds.len() + 1;
     let offset_idx = binds.len() + 2;
     let sql = format!(
-        "SELECT * FROM track WHERE is_remote = 0 {extra}
+        "SELECT * FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 {extra}
          ORDER BY title COLLATE NOCASE LIMIT ?{limit_idx} OFFSET ?{offset_idx}",
         extra = if where_sql.is_empty() {
             String::new()
@@ -195,7 +210,7 @@ pub async fn count_filtered(
         name_less_than,
     );
     let sql = format!(
-        "SELECT COUNT(*) FROM track WHERE is_remote = 0 {extra}",
+        "SELECT COUNT(*) FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 {extra}",
         extra = if where_sql.is_empty() {
             String::new()
         } else {
@@ -210,7 +225,13 @@ pub async fn count_filtered(
 }
 
 pub async fn name_prefixes(pool: Pool<Sqlite>) -> Result<Vec<String>, Error> {
-    super::name_filter::prefixes(&pool, "track", "title", Some("is_remote = 0")).await
+    super::name_filter::prefixes(
+        &pool,
+        "track",
+        "title",
+        Some("(is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0"),
+    )
+    .await
 }
 
 pub async fn update_album_art(pool: Pool<Sqlite>, id: &str, album_art: &str) -> Result<(), Error> {
@@ -222,9 +243,64 @@ pub async fn update_album_art(pool: Pool<Sqlite>, id: &str, album_art: &str) ->
     Ok(())
 }
 
+pub async fn update_media_type(
+    pool: Pool<Sqlite>,
+    id: &str,
+    media_type: &str,
+) -> Result<(), Error> {
+    sqlx::query("UPDATE track SET media_type = $2 WHERE id = $1")
+        .bind(id)
+        .bind(media_type)
+        .execute(&pool)
+        .await?;
+    Ok(())
+}
+
+/// Paths of every track marked as a book.
+pub async fn book_paths(pool: Pool<Sqlite>) -> Result<Vec<String>, Error> {
+    sqlx::query_scalar("SELECT path FROM track WHERE media_type = 'book'")
+        .fetch_all(&pool)
+        .await
+}
+
+/// Set the media type of every track under `folder`. Returns how many
+/// tracks changed.
+pub async fn update_media_type_by_folder(
+    pool: Pool<Sqlite>,
+    folder: &str,
+    media_type: &str,
+) -> Result<u64, Error> {
+    let prefix = format!("{}/", folder.trim_end_matches('/'));
+    let result = sqlx::query(
+        "UPDATE track SET media_type = $2 WHERE substr(path, 1, length($1)) = $1 AND media_type != $2",
+    )
+    .bind(&prefix)
+    .bind(media_type)
+    .execute(&pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Set the media type of every track tagged with `genre` (case-insensitive).
+/// Returns how many tracks changed.
+pub async fn update_media_type_by_genre(
+    pool: Pool<Sqlite>,
+    genre: &str,
+    media_type: &str,
+) -> Result<u64, Error> {
+    let result = sqlx::query(
+        "UPDATE track SET media_type = $2 WHERE lower(genre) = lower($1) AND media_type != $2",
+    )
+    .bind(genre)
+    .bind(media_type)
+    .execute(&pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
 pub async fn find_by_artist(pool: Pool<Sqlite>, artist: &str) -> Result<Vec<Track>, Error> {
     let result: Vec<Track> = sqlx::query_as(
-        "SELECT * FROM track WHERE is_remote = 0 AND artist = $1 ORDER BY title ASC",
+        "SELECT * FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 AND artist = $1 ORDER BY title ASC",
     )
     .bind(artist)
     .fetch_all(&pool)
@@ -233,20 +309,22 @@ pub async fn find_by_artist(pool: Pool<Sqlite>, artist: &str) -> Result<Vec<Trac
 }
 
 pub async fn find_by_album(pool: Pool<Sqlite>, album: &str) -> Result<Vec<Track>, Error> {
-    let result: Vec<Track> =
-        sqlx::query_as("SELECT * FROM track WHERE is_remote = 0 AND album = $1 ORDER BY title ASC")
-            .bind(album)
-            .fetch_all(&pool)
-            .await?;
+    let result: Vec<Track> = sqlx::query_as(
+        "SELECT * FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 AND album = $1 ORDER BY title ASC",
+    )
+    .bind(album)
+    .fetch_all(&pool)
+    .await?;
     Ok(result)
 }
 
 pub async fn find_by_title(pool: Pool<Sqlite>, title: &str) -> Result<Vec<Track>, Error> {
-    let result: Vec<Track> =
-        sqlx::query_as("SELECT * FROM track WHERE is_remote = 0 AND title = $1 ORDER BY title ASC")
-            .bind(title)
-            .fetch_all(&pool)
-            .await?;
+    let result: Vec<Track> = sqlx::query_as(
+        "SELECT * FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 AND title = $1 ORDER BY title ASC",
+    )
+    .bind(title)
+    .fetch_all(&pool)
+    .await?;
     Ok(result)
 }
 
@@ -288,7 +366,7 @@ pub async fn find_by_artist_album_date(
     date: &str,
 ) -> Result<Vec<Track>, Error> {
     let result: Vec<Track> = sqlx::query_as(
-        "SELECT * FROM track WHERE is_remote = 0 AND artist = $1 AND album = $2 AND year_string = $3 ORDER BY title ASC",
+        "SELECT * FROM track WHERE (is_remote = 0 OR source_id IS NOT NULL) AND hidden = 0 AND artist = $1 AND album = $2 AND year_string = $3 ORDER BY title ASC",
     )
     .bind(artist)
     .bind(album)
@@ -297,3 +375,67 @@ pub async fn find_by_artist_album_date(
     .await?;
     Ok(result)
 }
+
+/// Hide or unhide a track. Returns whether it exists.
+pub async fn set_hidden(pool: Pool<Sqlite>, id: &str, hidden: bool) -> Result<bool, Error> {
+    let result = sqlx::query("UPDATE track SET hidden = $2 WHERE id = $1")
+        .bind(id)
+        .bind(hidden)
+        .execute(&pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn hidden(pool: Pool<Sqlite>) -> Result<Vec<Track>, Error> {
+    let result: Vec<Track> =
+        sqlx::query_as("SELECT * FROM track WHERE hidden = 1 ORDER BY title ASC")
+            .fetch_all(&pool)
+            .await?;
+    Ok(result)
+}
+
+/// Every track synced from a remote source, hidden ones included.
+pub async fn find_by_source(pool: Pool<Sqlite>, source_id: &str) -> Result<Vec<Track>, Error> {
+    let result: Vec<Track> = sqlx::query_as("SELECT * FROM track WHERE source_id = $1")
+        .bind(source_id)
+        .fetch_all(&pool)
+        .await?;
+    Ok(result)
+}
+
+/// Refresh the tags and stream URL of a track synced from a remote source.
+/// Album and artist links are left alone.
+pub async fn update_from_source(pool: Pool<Sqlite>, track: &Track) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE track SET
+          path = $2,
+          title = $3,
+          artist = $4,
+          album = $5,
+          album_artist = $6,
+          genre = $7,
+          year = $8,
+          track_number = $9,
+          disc_number = $10,
+          length = $11,
+          updated_at = $12
+        WHERE id = $1
+        "#,
+    )
+    .bind(&track.id)
+    .bind(&track.path)
+    .bind(&track.title)
+    .bind(&track.artist)
+    .bind(&track.album)
+    .bind(&track.album_artist)
+    .bind(&track.genre)
+    .bind(track.year)
+    .bind(track.track_number)
+    .bind(track.disc_number)
+    .bind(track.length)
+    .bind(chrono::Utc::now())
+    .execute(&pool)
+    .await?;
+    Ok(())
+}
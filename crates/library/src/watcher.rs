@@ -1,4 +1,5 @@
 use crate::audio_scan::{reconcile_deletions, save_audio_metadata, scan_audio_files};
+use crate::changes;
 use crate::repo;
 use anyhow::Error;
 use notify::event::{ModifyKind, RenameMode};
@@ -150,8 +151,29 @@ async fn add_path(pool: &Pool<Sqlite>, path: &Path) {
     }
     let path_str = path.to_string_lossy().to_string();
     debug!("watcher: add {}", path_str);
+    // Data modifications come through here too; only announce new files
+    // to webhooks.
+    let known = matches!(
+        repo::track::find_by_path(pool.clone(), &path_str).await,
+        Ok(Some(_))
+    );
     if let Err(e) = save_audio_metadata(pool.clone(), &path_str, None).await {
         warn!("watcher: failed to add {}: {}", path_str, e);
+        return;
+    }
+    let track_id = repo::track::find_by_path(pool.clone(), &path_str)
+        .await
+        .ok()
+        .flatten()
+        .map(|t| t.id);
+    if let Some(id) = &track_id {
+        changes::track_changed(id);
+    }
+    if !known {
+        rockbox_webhooks::emit(rockbox_webhooks::Event::FileAdded {
+            path: path_str,
+            track_id,
+        });
     }
 }
 
@@ -161,7 +183,14 @@ async fn remove_path(pool: &Pool<Sqlite>, path: &Path) {
     }
     let path_str = path.to_string_lossy().to_string();
     match repo::track::delete_by_path(pool.clone(), &path_str).await {
-        Ok(Some(track)) => info!("watcher: removed {} ({})", track.title, path_str),
+        Ok(Some(track)) => {
+            info!("watcher: removed {} ({})", track.title, path_str);
+            changes::track_changed(&track.id);
+            rockbox_webhooks::emit(rockbox_webhooks::Event::FileRemoved {
+                path: path_str,
+                track_id: Some(track.id),
+            });
+        }
         Ok(None) => debug!("watcher: remove for unknown path {}", path_str),
         Err(e) => warn!("watcher: failed to remove {}: {}", path_str, e),
     }
@@ -0,0 +1,110 @@
+// Hardware volume through an ALSA simple-mixer element ("PCM", "Master",
+// "Digital", … — `amixer -D <card> scontrols` lists them). The firmware's
+// volume in tenths of a dB maps straight onto the element's dB scale,
+// clamped to the range the card offers.
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use alsa::{
+        mixer::{MilliBel, Mixer, SelemChannelId, SelemId},
+        Round,
+    };
+
+    pub struct Element {
+        mixer: Mixer,
+        control: String,
+        range: (MilliBel, MilliBel),
+        has_switch: bool,
+    }
+
+    // alsa::Mixer wraps a raw snd_mixer_t. It is only touched with the
+    // mixer's state lock held, never from two threads at once.
+    unsafe impl Send for Element {}
+
+    impl Element {
+        pub fn open(device: &str, control: &str) -> Result<Self, String> {
+            let mixer = Mixer::new(device, false).map_err(|e| format!("{device}: {e}"))?;
+            let (range, has_switch) = {
+                let selem = mixer
+                    .find_selem(&SelemId::new(control, 0))
+                    .ok_or_else(|| format!("{device}: no mixer control {control:?}"))?;
+                if !selem.has_playback_volume() {
+                    return Err(format!("{device}: {control:?} has no playback volume"));
+                }
+                (selem.get_playback_db_range(), selem.has_playback_switch())
+            };
+            Ok(Self {
+                mixer,
+                control: control.to_string(),
+                range,
+                has_switch,
+            })
+        }
+
+        /// The element's range in dB.
+        pub fn range_db(&self) -> (f32, f32) {
+            (self.range.0.to_db(), self.range.1.to_db())
+        }
+
+        pub fn set_volume(&self, vol_l: i32, vol_r: i32) -> Result<(), String> {
+            let _ = self.mixer.handle_events();
+            let selem = self
+                .mixer
+                .find_selem(&SelemId::new(&self.control, 0))
+                .ok_or_else(|| format!("mixer control {:?} went away", self.control))?;
+            let level = |tenth_db: i32| {
+                if tenth_db == i32::MIN {
+                    self.range.0
+                } else {
+                    MilliBel((tenth_db as i64 * 10).clamp(self.range.0 .0, self.range.1 .0))
+                }
+            };
+            let result = if selem.is_playback_mono() {
+                selem.set_playback_db(
+                    SelemChannelId::mono(),
+                    level(vol_l.max(vol_r)),
+                    Round::Floor,
+                )
+            } else {
+                selem
+                    .set_playback_db(SelemChannelId::FrontLeft, level(vol_l), Round::Floor)
+                    .and_then(|_| {
+                        selem.set_playback_db(
+                            SelemChannelId::FrontRight,
+                            level(vol_r),
+                            Round::Floor,
+                        )
+                    })
+            };
+            result.map_err(|e| format!("{}: {e}", self.control))?;
+            if self.has_switch {
+                let on = vol_l != i32::MIN || vol_r != i32::MIN;
+                selem
+                    .set_playback_switch_all(on as i32)
+                    .map_err(|e| format!("{}: {e}", self.control))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    pub struct Element;
+
+    impl Element {
+        pub fn open(_device: &str, _control: &str) -> Result<Self, String> {
+            Err("hardware volume needs ALSA (Linux only)".to_string())
+        }
+
+        pub fn range_db(&self) -> (f32, f32) {
+            (0.0, 0.0)
+        }
+
+        pub fn set_volume(&self, _vol_l: i32, _vol_r: i32) -> Result<(), String> {
+            Ok(())
+        }
+    }
+}
+
+pub use imp::Element;
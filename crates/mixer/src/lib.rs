@@ -0,0 +1,343 @@
+// Volume and loudness normalisation for the PCM sinks that drive an audio
+// device (cpal and alsa).
+//
+// The firmware hands the sink its volume through audiohw_set_volume(), in
+// tenths of a dB per channel. What happens to it depends on the mode:
+//
+//   software — the sink scales the samples on a dB curve, with TPDF dither
+//              wherever the result is rounded back to integers.
+//   hardware — an ALSA simple-mixer element takes the volume, so samples
+//              reach the DAC unscaled. Falls back to software if the
+//              element can't be opened.
+//   fixed    — volume changes are ignored; an external amp sets the level.
+//
+// ReplayGain / R128 gain applies in every mode. The sinks scale by it as
+// the firmware's PCM comes in (`replaygain_gain`), so it changes with the
+// track; volume is applied as the device drains the ring (`volume_gains`),
+// so it changes at once. While the mixer drives the output, the firmware
+// DSP's own ReplayGain stage is kept off so the gain isn't applied twice.
+//
+// State is process-wide. The sinks, the settings that configure the mixer
+// and the HTTP / gRPC handlers must all live in one staticlib
+// (rockbox-server), or each would see its own copy.
+
+mod hardware;
+pub mod replaygain;
+mod software;
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+pub use replaygain::{ReplayGain, ReplayGainMode, TrackGain};
+pub use software::{db_to_gain, volume_gain, Dither};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MixerMode {
+    #[default]
+    Software,
+    Hardware,
+    Fixed,
+}
+
+impl MixerMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            MixerMode::Software => "software",
+            MixerMode::Hardware => "hardware",
+            MixerMode::Fixed => "fixed",
+        }
+    }
+}
+
+impl FromStr for MixerMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "software" => Ok(MixerMode::Software),
+            "hardware" => Ok(MixerMode::Hardware),
+            "fixed" => Ok(MixerMode::Fixed),
+            other => Err(format!(
+                "unknown mixer {other:?} (expected software, hardware or fixed)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixerConfig {
+    pub mode: MixerMode,
+    /// ALSA card whose mixer hardware mode uses, e.g. "default" or "hw:1".
+    pub device: String,
+    /// Simple-mixer element hardware mode sets.
+    pub control: String,
+    /// Dither when scaled samples are rounded.
+    pub dither: bool,
+    pub replaygain: ReplayGain,
+}
+
+impl Default for MixerConfig {
+    fn default() -> Self {
+        Self {
+            mode: MixerMode::Software,
+            device: "default".to_string(),
+            control: "PCM".to_string(),
+            dither: true,
+            replaygain: ReplayGain::default(),
+        }
+    }
+}
+
+/// What the mixer is doing now.
+#[derive(Debug, Clone, Default)]
+pub struct Status {
+    /// Mode in effect; differs from `config.mode` when hardware fell back.
+    pub mode: MixerMode,
+    pub config: MixerConfig,
+    /// Why hardware mode isn't in effect.
+    pub error: Option<String>,
+    /// dB range of the hardware element.
+    pub hardware_range_db: Option<(f32, f32)>,
+    /// Firmware volume per channel in dB; `None` when muted.
+    pub volume_db: (Option<f32>, Option<f32>),
+    /// Loudness tags of the track playing.
+    pub track: Option<TrackGain>,
+    pub replaygain_db: f32,
+    pub clipping_prevented: bool,
+}
+
+struct State {
+    config: MixerConfig,
+    hardware: Option<hardware::Element>,
+    error: Option<String>,
+    volume: (i32, i32),
+    track: Option<TrackGain>,
+    shuffle: bool,
+    applied: replaygain::Applied,
+    sinks: Vec<&'static str>,
+}
+
+static STATE: OnceLock<Mutex<State>> = OnceLock::new();
+
+// Linear gains as f32 bits, read by the audio threads without locking.
+static VOLUME_L: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0f32
+static VOLUME_R: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0f32
+static REPLAYGAIN: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0f32
+static DITHER: AtomicBool = AtomicBool::new(true);
+
+fn state() -> &'static Mutex<State> {
+    STATE.get_or_init(|| {
+        Mutex::new(State {
+            config: MixerConfig::default(),
+            hardware: None,
+            error: None,
+            volume: (0, 0),
+            track: None,
+            shuffle: false,
+            applied: Default::default(),
+            sinks: Vec::new(),
+        })
+    })
+}
+
+impl State {
+    fn mode(&self) -> MixerMode {
+        match self.config.mode {
+            MixerMode::Hardware if self.hardware.is_none() => MixerMode::Software,
+            mode => mode,
+        }
+    }
+
+    // Recompute the gains the sinks read and push the volume to hardware.
+    fn update(&mut self) {
+        let (left, right) = match self.mode() {
+            MixerMode::Software => (volume_gain(self.volume.0), volume_gain(self.volume.1)),
+            MixerMode::Hardware | MixerMode::Fixed => (1.0, 1.0),
+        };
+        VOLUME_L.store(left.to_bits(), Ordering::Relaxed);
+        VOLUME_R.store(right.to_bits(), Ordering::Relaxed);
+        if let Some(element) = &self.hardware {
+            if let Err(e) = element.set_volume(self.volume.0, self.volume.1) {
+                tracing::warn!("mixer: {e}");
+            }
+        }
+
+        self.applied = self
+            .config
+            .replaygain
+            .apply(&self.track.unwrap_or_default(), self.shuffle);
+        REPLAYGAIN.store(
+            db_to_gain(self.applied.gain_db).to_bits(),
+            Ordering::Relaxed,
+        );
+        DITHER.store(self.config.dither, Ordering::Relaxed);
+    }
+}
+
+/// Called by a sink when the firmware initialises it, so `drives` knows
+/// which outputs scale through the mixer.
+pub fn register_sink(name: &'static str) {
+    let mut state = state().lock().unwrap();
+    if !state.sinks.contains(&name) {
+        state.sinks.push(name);
+    }
+}
+
+/// Whether audio for `output` (the `audio_output` setting) goes through a
+/// sink that applies the mixer. "builtin" is whichever device sink the
+/// build has.
+pub fn drives(output: Option<&str>) -> bool {
+    let sinks = &state().lock().unwrap().sinks;
+    match output {
+        None | Some("builtin") => !sinks.is_empty(),
+        Some(name) => sinks.contains(&name),
+    }
+}
+
+pub fn configure(config: MixerConfig) {
+    let mut state = state().lock().unwrap();
+    let reopen = config.mode == MixerMode::Hardware
+        && (state.hardware.is_none()
+            || state.config.device != config.device
+            || state.config.control != config.control);
+    if config.mode != MixerMode::Hardware {
+        state.hardware = None;
+        state.error = None;
+    } else if reopen {
+        state.hardware = None;
+        match hardware::Element::open(&config.device, &config.control) {
+            Ok(element) => {
+                let (min, max) = element.range_db();
+                tracing::info!(
+                    "mixer: hardware volume on {} {:?} ({min:.1} to {max:.1} dB)",
+                    config.device,
+                    config.control
+                );
+                state.hardware = Some(element);
+                state.error = None;
+            }
+            Err(e) => {
+                tracing::warn!("mixer: {e}; using software volume");
+                state.error = Some(e);
+            }
+        }
+    }
+    state.config = config;
+    state.update();
+}
+
+pub fn config() -> MixerConfig {
+    state().lock().unwrap().config.clone()
+}
+
+/// Firmware volume per channel, in tenths of a dB; `i32::MIN` mutes.
+pub fn set_volume(vol_l: i32, vol_r: i32) {
+    let mut state = state().lock().unwrap();
+    if state.volume != (vol_l, vol_r) {
+        state.volume = (vol_l, vol_r);
+        state.update();
+    }
+}
+
+/// Loudness tags of the track now playing (`None` for untagged audio),
+/// and whether shuffle is on for `ReplayGainMode::Shuffle`.
+pub fn set_track(track: Option<TrackGain>, shuffle: bool) {
+    let mut state = state().lock().unwrap();
+    if state.track != track || state.shuffle != shuffle {
+        state.track = track;
+        state.shuffle = shuffle;
+        state.update();
+    }
+}
+
+/// Per-channel volume gain for the device side of the sink.
+pub fn volume_gains() -> (f32, f32) {
+    (
+        f32::from_bits(VOLUME_L.load(Ordering::Relaxed)),
+        f32::from_bits(VOLUME_R.load(Ordering::Relaxed)),
+    )
+}
+
+/// ReplayGain for the firmware side of the sink.
+pub fn replaygain_gain() -> f32 {
+    f32::from_bits(REPLAYGAIN.load(Ordering::Relaxed))
+}
+
+pub fn dither() -> bool {
+    DITHER.load(Ordering::Relaxed)
+}
+
+/// Whether samples pass through unscaled: no software volume below 0 dB
+/// and no ReplayGain.
+pub fn is_transparent() -> bool {
+    volume_gains() == (1.0, 1.0) && replaygain_gain() == 1.0
+}
+
+pub fn status() -> Status {
+    let state = state().lock().unwrap();
+    let db = |tenth_db: i32| (tenth_db != i32::MIN).then(|| tenth_db as f32 / 10.0);
+    Status {
+        mode: state.mode(),
+        config: state.config.clone(),
+        error: state.error.clone(),
+        hardware_range_db: state.hardware.as_ref().map(|e| e.range_db()),
+        volume_db: (db(state.volume.0), db(state.volume.1)),
+        track: state.track,
+        replaygain_db: state.applied.gain_db,
+        clipping_prevented: state.applied.clipping_prevented,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The mixer is global, so everything that touches it is one test.
+    #[test]
+    fn modes_decide_where_the_volume_goes() {
+        register_sink("cpal");
+        assert!(drives(None));
+        assert!(drives(Some("builtin")));
+        assert!(drives(Some("cpal")));
+        assert!(!drives(Some("airplay")));
+
+        configure(MixerConfig::default());
+        set_volume(-60, -120);
+        let (left, right) = volume_gains();
+        assert!((left - 0.501).abs() < 1e-3);
+        assert!((right - 0.251).abs() < 1e-3);
+        assert!(!is_transparent());
+
+        configure(MixerConfig {
+            mode: MixerMode::Fixed,
+            ..MixerConfig::default()
+        });
+        assert_eq!(volume_gains(), (1.0, 1.0));
+        assert!(is_transparent());
+
+        configure(MixerConfig {
+            mode: MixerMode::Fixed,
+            replaygain: ReplayGain {
+                mode: ReplayGainMode::Track,
+                preamp_db: 0.0,
+                prevent_clipping: false,
+            },
+            ..MixerConfig::default()
+        });
+        set_track(
+            Some(TrackGain {
+                track_gain_db: Some(-6.0),
+                ..Default::default()
+            }),
+            false,
+        );
+        assert!((replaygain_gain() - 0.501).abs() < 1e-3);
+        assert_eq!(status().replaygain_db, -6.0);
+        set_track(None, false);
+        assert_eq!(replaygain_gain(), 1.0);
+
+        assert_eq!("hardware".parse(), Ok(MixerMode::Hardware));
+        assert!("loud".parse::<MixerMode>().is_err());
+    }
+}
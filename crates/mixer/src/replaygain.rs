@@ -0,0 +1,326 @@
+// ReplayGain and R128 loudness normalisation. The rules follow the
+// firmware's dsp_replaygain_update (lib/rbcodec/dsp/dsp_misc.c), so moving
+// the gain from the DSP into the sinks doesn't change what the user hears,
+// apart from the R128 tags the firmware can't read.
+
+use std::str::FromStr;
+
+/// Which gain applies. Numbered like the firmware's
+/// `replaygain_settings.type`, so the existing setting drives the mixer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayGainMode {
+    Track,
+    Album,
+    /// Track gain while shuffle is on, album gain otherwise.
+    Shuffle,
+    #[default]
+    Off,
+}
+
+impl ReplayGainMode {
+    pub fn from_firmware(value: i32) -> Self {
+        match value {
+            0 => ReplayGainMode::Track,
+            1 => ReplayGainMode::Album,
+            2 => ReplayGainMode::Shuffle,
+            _ => ReplayGainMode::Off,
+        }
+    }
+
+    pub fn to_firmware(self) -> i32 {
+        match self {
+            ReplayGainMode::Track => 0,
+            ReplayGainMode::Album => 1,
+            ReplayGainMode::Shuffle => 2,
+            ReplayGainMode::Off => 3,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ReplayGainMode::Track => "track",
+            ReplayGainMode::Album => "album",
+            ReplayGainMode::Shuffle => "shuffle",
+            ReplayGainMode::Off => "off",
+        }
+    }
+}
+
+impl FromStr for ReplayGainMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "track" => Ok(ReplayGainMode::Track),
+            "album" => Ok(ReplayGainMode::Album),
+            "shuffle" => Ok(ReplayGainMode::Shuffle),
+            "off" => Ok(ReplayGainMode::Off),
+            other => Err(format!(
+                "unknown replaygain mode {other:?} (expected track, album, shuffle or off)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReplayGain {
+    pub mode: ReplayGainMode,
+    /// Added to the tagged gain. Not applied to tracks without gain tags.
+    pub preamp_db: f32,
+    /// Lower the gain so the tagged peak stays below full scale. Works
+    /// with `mode` off too, as long as the track has a peak tag.
+    pub prevent_clipping: bool,
+}
+
+/// Loudness tags of one track. Peaks are linear, 1.0 being full scale.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TrackGain {
+    pub track_gain_db: Option<f32>,
+    pub album_gain_db: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub album_peak: Option<f32>,
+}
+
+/// R128 gains are relative to -23 LUFS, ReplayGain's to about -18 LUFS.
+const R128_TO_REPLAYGAIN_DB: f32 = 5.0;
+
+impl TrackGain {
+    /// Read `REPLAYGAIN_*` and `R128_*` tags. Keys are case-insensitive;
+    /// a ReplayGain tag wins over the R128 one for the same gain.
+    pub fn from_tags<'a>(tags: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut gain = TrackGain::default();
+        let (mut r128_track, mut r128_album) = (None, None);
+        for (key, value) in tags {
+            match key.to_ascii_uppercase().as_str() {
+                "REPLAYGAIN_TRACK_GAIN" => {
+                    gain.track_gain_db = gain.track_gain_db.or(number(value))
+                }
+                "REPLAYGAIN_ALBUM_GAIN" => {
+                    gain.album_gain_db = gain.album_gain_db.or(number(value))
+                }
+                "REPLAYGAIN_TRACK_PEAK" => gain.track_peak = gain.track_peak.or(number(value)),
+                "REPLAYGAIN_ALBUM_PEAK" => gain.album_peak = gain.album_peak.or(number(value)),
+                "R128_TRACK_GAIN" => r128_track = r128_track.or(r128(value)),
+                "R128_ALBUM_GAIN" => r128_album = r128_album.or(r128(value)),
+                _ => {}
+            }
+        }
+        gain.track_gain_db = gain.track_gain_db.or(r128_track);
+        gain.album_gain_db = gain.album_gain_db.or(r128_album);
+        gain
+    }
+
+    /// Gains the firmware parsed into an mp3entry: linear factors in Q7.24,
+    /// 0 meaning the tag is missing.
+    pub fn from_firmware(
+        track_gain: i64,
+        album_gain: i64,
+        track_peak: i64,
+        album_peak: i64,
+    ) -> Self {
+        let factor = |value: i64| (value > 0).then(|| value as f32 / (1 << 24) as f32);
+        TrackGain {
+            track_gain_db: factor(track_gain).map(|g| 20.0 * g.log10()),
+            album_gain_db: factor(album_gain).map(|g| 20.0 * g.log10()),
+            track_peak: factor(track_peak),
+            album_peak: factor(album_peak),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == TrackGain::default()
+    }
+}
+
+/// Gain worked out for a track.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Applied {
+    pub gain_db: f32,
+    /// Whether `prevent_clipping` lowered the gain.
+    pub clipping_prevented: bool,
+}
+
+impl ReplayGain {
+    pub fn apply(&self, track: &TrackGain, shuffle: bool) -> Applied {
+        if self.mode == ReplayGainMode::Off && !self.prevent_clipping {
+            return Applied::default();
+        }
+        let album = match self.mode {
+            ReplayGainMode::Album => true,
+            ReplayGainMode::Shuffle => !shuffle,
+            ReplayGainMode::Track | ReplayGainMode::Off => false,
+        };
+        let (gain, peak) = if album {
+            (
+                track.album_gain_db.or(track.track_gain_db),
+                track.album_peak.or(track.track_peak),
+            )
+        } else {
+            (
+                track.track_gain_db.or(track.album_gain_db),
+                track.track_peak.or(track.album_peak),
+            )
+        };
+
+        let mut gain_db = match gain {
+            Some(gain) if self.mode != ReplayGainMode::Off => gain + self.preamp_db,
+            _ => 0.0,
+        };
+        let mut clipping_prevented = false;
+        if let Some(peak) = peak.filter(|&p| p > 0.0) {
+            let ceiling = -20.0 * peak.log10();
+            if self.prevent_clipping && gain_db > ceiling {
+                gain_db = ceiling;
+                clipping_prevented = true;
+            }
+        }
+        Applied {
+            gain_db,
+            clipping_prevented,
+        }
+    }
+}
+
+/// The leading number of a tag value such as "-6.48 dB" or "0.988".
+fn number(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].parse().ok()
+}
+
+/// An R128 gain: dB in Q7.8, as a decimal integer.
+fn r128(value: &str) -> Option<f32> {
+    let q78: i16 = value.trim().parse().ok()?;
+    Some(q78 as f32 / 256.0 + R128_TO_REPLAYGAIN_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gain(mode: ReplayGainMode, preamp_db: f32, prevent_clipping: bool) -> ReplayGain {
+        ReplayGain {
+            mode,
+            preamp_db,
+            prevent_clipping,
+        }
+    }
+
+    #[test]
+    fn reads_replaygain_and_r128_tags() {
+        let tags = TrackGain::from_tags([
+            ("replaygain_track_gain", "-6.48 dB"),
+            ("REPLAYGAIN_TRACK_PEAK", "0.988"),
+            ("R128_ALBUM_GAIN", "-512"),
+            ("TITLE", "ignored"),
+        ]);
+        assert_eq!(tags.track_gain_db, Some(-6.48));
+        assert_eq!(tags.track_peak, Some(0.988));
+        // -2 dB against -23 LUFS is +3 dB against ReplayGain's reference.
+        assert_eq!(tags.album_gain_db, Some(3.0));
+        assert_eq!(tags.album_peak, None);
+
+        assert_eq!("album".parse(), Ok(ReplayGainMode::Album));
+        assert!("loudest".parse::<ReplayGainMode>().is_err());
+
+        let both = TrackGain::from_tags([
+            ("R128_TRACK_GAIN", "256"),
+            ("REPLAYGAIN_TRACK_GAIN", "+1.5 dB"),
+        ]);
+        assert_eq!(both.track_gain_db, Some(1.5));
+    }
+
+    #[test]
+    fn firmware_gains_are_q7_24_factors() {
+        let half = 1 << 23;
+        let gain = TrackGain::from_firmware(half, 0, 1 << 24, 0);
+        assert!((gain.track_gain_db.unwrap() + 6.0206).abs() < 1e-3);
+        assert_eq!(gain.album_gain_db, None);
+        assert_eq!(gain.track_peak, Some(1.0));
+    }
+
+    #[test]
+    fn picks_the_gain_for_the_mode() {
+        let tags = TrackGain {
+            track_gain_db: Some(-4.0),
+            album_gain_db: Some(-2.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            gain(ReplayGainMode::Track, 0.0, false)
+                .apply(&tags, false)
+                .gain_db,
+            -4.0
+        );
+        assert_eq!(
+            gain(ReplayGainMode::Album, 1.0, false)
+                .apply(&tags, false)
+                .gain_db,
+            -1.0
+        );
+        assert_eq!(
+            gain(ReplayGainMode::Shuffle, 0.0, false)
+                .apply(&tags, true)
+                .gain_db,
+            -4.0
+        );
+        assert_eq!(
+            gain(ReplayGainMode::Shuffle, 0.0, false)
+                .apply(&tags, false)
+                .gain_db,
+            -2.0
+        );
+        assert_eq!(
+            gain(ReplayGainMode::Off, 3.0, false)
+                .apply(&tags, false)
+                .gain_db,
+            0.0
+        );
+
+        let track_only = TrackGain {
+            track_gain_db: Some(-4.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            gain(ReplayGainMode::Album, 0.0, false)
+                .apply(&track_only, false)
+                .gain_db,
+            -4.0
+        );
+        // No tags: the preamp isn't applied either.
+        assert_eq!(
+            gain(ReplayGainMode::Track, 6.0, false)
+                .apply(&TrackGain::default(), false)
+                .gain_db,
+            0.0
+        );
+    }
+
+    #[test]
+    fn clipping_prevention_caps_the_gain_at_the_peak() {
+        let tags = TrackGain {
+            track_gain_db: Some(3.0),
+            track_peak: Some(0.5),
+            ..Default::default()
+        };
+        let applied = gain(ReplayGainMode::Track, 6.0, true).apply(&tags, false);
+        assert!(applied.clipping_prevented);
+        assert!((applied.gain_db - 6.0206).abs() < 1e-3);
+
+        let quiet = gain(ReplayGainMode::Track, 0.0, true).apply(&tags, false);
+        assert!(!quiet.clipping_prevented);
+        assert_eq!(quiet.gain_db, 3.0);
+
+        // Off, but still keeping a loud master below full scale.
+        let hot = TrackGain {
+            track_peak: Some(1.25),
+            ..Default::default()
+        };
+        let applied = gain(ReplayGainMode::Off, 0.0, true).apply(&hot, false);
+        assert!(applied.clipping_prevented);
+        assert!(applied.gain_db < -1.9);
+    }
+}
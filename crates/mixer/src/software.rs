@@ -0,0 +1,122 @@
+// Software gain: a dB curve for the volume and TPDF dither whenever scaled
+// samples are rounded back to integers. Unity gain is a plain copy, so a
+// sink at 0 dB with no ReplayGain stays bit-exact.
+
+/// Linear gain for a level in dB.
+pub fn db_to_gain(db: f32) -> f32 {
+    10.0_f32.powf(db / 20.0)
+}
+
+/// Linear gain for a firmware volume in tenths of a dB (`i32::MIN` mutes).
+/// Volume attenuates only; ReplayGain is what may boost.
+pub fn volume_gain(tenth_db: i32) -> f32 {
+    if tenth_db == i32::MIN {
+        return 0.0;
+    }
+    db_to_gain(tenth_db as f32 / 10.0).min(1.0)
+}
+
+/// Scales samples and rounds them with triangular (TPDF) dither. One per
+/// audio thread; the noise generator isn't shared.
+pub struct Dither {
+    state: u32,
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dither {
+    pub fn new() -> Self {
+        Self { state: 0x9e37_79b9 }
+    }
+
+    // xorshift32: plenty for noise, and cheap enough for the audio thread.
+    fn next(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Triangular noise in (-1, 1) LSB: the sum of two uniform variables.
+    fn noise(&mut self) -> f64 {
+        let a = self.next() as f64 / u32::MAX as f64;
+        let b = self.next() as f64 / u32::MAX as f64;
+        a - b
+    }
+
+    /// Scale a 16-bit sample by `gain` into a signed `bits`-wide integer
+    /// (16 to 32), dithering the rounding when `dither` is set.
+    pub fn scale(&mut self, sample: i16, gain: f32, bits: u32, dither: bool) -> i32 {
+        if gain == 1.0 {
+            return (sample as i32) << (bits - 16);
+        }
+        let full = (1_i64 << (bits - 1)) as f64;
+        let mut value = sample as f64 * gain as f64 * (full / 32768.0);
+        if dither && gain != 0.0 {
+            value += self.noise();
+        }
+        value.round().clamp(-full, full - 1.0) as i32
+    }
+
+    /// Scale interleaved S16LE stereo in place by per-channel `gains`.
+    pub fn scale_s16(&mut self, pcm: &mut [u8], gains: (f32, f32), dither: bool) {
+        if gains == (1.0, 1.0) {
+            return;
+        }
+        for (i, sample) in pcm.chunks_exact_mut(2).enumerate() {
+            let gain = if i % 2 == 0 { gains.0 } else { gains.1 };
+            let value = i16::from_le_bytes([sample[0], sample[1]]);
+            let scaled = self.scale(value, gain, 16, dither) as i16;
+            sample.copy_from_slice(&scaled.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_follows_the_db_curve_without_boosting() {
+        assert_eq!(volume_gain(0), 1.0);
+        assert!((volume_gain(-60) - 0.501).abs() < 1e-3);
+        assert_eq!(volume_gain(60), 1.0);
+        assert_eq!(volume_gain(i32::MIN), 0.0);
+    }
+
+    #[test]
+    fn unity_gain_is_bit_exact() {
+        let mut dither = Dither::new();
+        let mut pcm = [0x34, 0x12, 0xfe, 0xff];
+        dither.scale_s16(&mut pcm, (1.0, 1.0), true);
+        assert_eq!(pcm, [0x34, 0x12, 0xfe, 0xff]);
+        assert_eq!(dither.scale(-2, 1.0, 24, true), -2 << 8);
+        assert_eq!(dither.scale(i16::MIN, 1.0, 32, true), i32::MIN);
+    }
+
+    #[test]
+    fn scaling_stays_within_one_lsb_of_the_exact_value() {
+        let mut dither = Dither::new();
+        for _ in 0..1000 {
+            let value = dither.scale(10_001, 0.5, 16, true);
+            assert!((4_999..=5_002).contains(&value), "{value}");
+        }
+        // Without dither the rounding is plain.
+        assert_eq!(dither.scale(10_001, 0.5, 16, false), 5_001);
+        // More bits keep the fraction the 16-bit result loses.
+        assert_eq!(dither.scale(10_001, 0.5, 24, false), 10_001 << 7);
+    }
+
+    #[test]
+    fn boosts_saturate_instead_of_wrapping() {
+        let mut dither = Dither::new();
+        assert_eq!(dither.scale(30_000, 2.0, 16, false), i16::MAX as i32);
+        assert_eq!(dither.scale(-30_000, 2.0, 16, false), i16::MIN as i32);
+    }
+}
@@ -321,6 +321,7 @@ command: clear
 command: commands
 command: consume
 command: count
+command: crossfade
 command: currentsong
 command: decoders
 command: delete
@@ -338,12 +339,15 @@ command: listmounts
 command: listplaylists
 command: load
 command: lsinfo
+command: mixrampdb
+command: mixrampdelay
 command: move
 command: moveid
 command: next
 command: notcommands
 command: outputs
 command: pause
+command: password
 command: ping
 command: play
 command: playid
@@ -353,6 +357,7 @@ command: playlistsearch
 command: listplaylistinfo
 command: plchanges
 command: previous
+command: readcomments
 command: readpicture
 command: random
 command: rename
@@ -370,6 +375,7 @@ command: shuffle
 command: single
 command: stats
 command: status
+command: sticker
 command: stop
 command: swap
 command: swapid
@@ -1,11 +1,13 @@
 use anyhow::Error;
 use tokio::sync::mpsc::Sender;
 
-use crate::{parse_command, setup_context, Context};
+use crate::{parse_command, permission_ack, setup_context, Context};
 
 use super::{
     albumart::{handle_albumart, handle_readpicture},
-    browse::{handle_listall, handle_listallinfo, handle_listfiles, handle_lsinfo},
+    browse::{
+        handle_listall, handle_listallinfo, handle_listfiles, handle_lsinfo, handle_readcomments,
+    },
     library::{
         handle_config, handle_count, handle_find_album, handle_find_artist, handle_find_title,
         handle_findadd, handle_list_album, handle_list_artist, handle_list_date, handle_list_genre,
@@ -14,17 +16,20 @@ use super::{
         handle_stats, handle_tagtypes, handle_tagtypes_clear, handle_tagtypes_enable,
     },
     playback::{
-        handle_consume, handle_currentsong, handle_disableoutput, handle_enableoutput,
-        handle_getvol, handle_next, handle_outputs, handle_pause, handle_play, handle_playid,
-        handle_previous, handle_random, handle_repeat, handle_seek, handle_seekcur, handle_seekid,
-        handle_setvol, handle_single, handle_status, handle_stop, handle_toggle,
-        handle_toggleoutput,
+        handle_consume, handle_crossfade, handle_currentsong, handle_disableoutput,
+        handle_enableoutput, handle_getvol, handle_mixrampdb, handle_mixrampdelay, handle_next,
+        handle_outputs, handle_pause, handle_play, handle_playid, handle_previous, handle_random,
+        handle_repeat, handle_seek, handle_seekcur, handle_seekid, handle_setvol, handle_single,
+        handle_status, handle_stop, handle_toggle, handle_toggleoutput,
     },
     queue::{
         handle_add, handle_addid, handle_clear, handle_delete, handle_move, handle_moveid,
         handle_playlistid, handle_playlistinfo, handle_shuffle, handle_swap, handle_swapid,
     },
-    system::{handle_decoders, handle_notcommands, handle_ping, handle_urlhandlers},
+    sticker::handle_sticker,
+    system::{
+        handle_decoders, handle_notcommands, handle_password, handle_ping, handle_urlhandlers,
+    },
 };
 
 pub async fn handle_command_list_begin(
@@ -41,6 +46,11 @@ pub async fn handle_command_list_begin(
 
     for request in commands {
         let command = parse_command(&request)?;
+        // MPD aborts the whole list at the first command it refuses.
+        if let Some(ack) = permission_ack(&ctx, &command) {
+            tx.send(ack.clone().into_bytes()).await?;
+            return Ok(ack);
+        }
         let response = match_command(&command, &mut ctx, request, tx.clone()).await?;
         // Binary commands already sent via tx; send non-empty text responses now.
         if !response.is_empty() {
@@ -66,6 +76,11 @@ pub async fn handle_command_list_ok_begin(
 
     for request in commands {
         let command = parse_command(&request)?;
+        // MPD aborts the whole list at the first command it refuses.
+        if let Some(ack) = permission_ack(&ctx, &command) {
+            tx.send(ack.clone().into_bytes()).await?;
+            return Ok(ack);
+        }
         let response = match_command(&command, &mut ctx, request, tx.clone()).await?;
         // Binary commands (albumart/readpicture) already sent via tx with list_OK;
         // text commands return their response string here.
@@ -99,6 +114,9 @@ pub async fn match_command(
         "random" => handle_random(ctx, request, tx.clone()).await,
         "repeat" => handle_repeat(ctx, request, tx.clone()).await,
         "consume" => handle_consume(ctx, request, tx.clone()).await,
+        "crossfade" => handle_crossfade(ctx, request, tx.clone()).await,
+        "mixrampdb" => handle_mixrampdb(ctx, request, tx.clone()).await,
+        "mixrampdelay" => handle_mixrampdelay(ctx, request, tx.clone()).await,
         "getvol" => handle_getvol(ctx, request, tx.clone()).await,
         "setvol" => handle_setvol(ctx, request, tx.clone()).await,
         "volume" => handle_setvol(ctx, request, tx.clone()).await,
@@ -145,6 +163,8 @@ pub async fn match_command(
         "listfiles" => handle_listfiles(ctx, request, tx.clone()).await,
         "albumart" => handle_albumart(ctx, request, tx.clone()).await,
         "readpicture" => handle_readpicture(ctx, request, tx.clone()).await,
+        "readcomments" => handle_readcomments(ctx, request, tx.clone()).await,
+        "sticker" => handle_sticker(ctx, request, tx.clone()).await,
         "listplaylists" => handle_listplaylists(ctx, request, tx.clone()).await,
         "listplaylistinfo" => handle_listplaylistinfo(ctx, request, tx.clone()).await,
         "load" => handle_load(ctx, request, tx.clone()).await,
@@ -155,6 +175,7 @@ pub async fn match_command(
         "find album" => handle_find_album(ctx, request, tx.clone()).await,
         "find title" => handle_find_title(ctx, request, tx.clone()).await,
         "ping" => handle_ping(ctx, request, tx.clone()).await,
+        "password" => handle_password(ctx, request, tx.clone()).await,
         "notcommands" => handle_notcommands(ctx, request, tx.clone()).await,
         "urlhandlers" => handle_urlhandlers(ctx, request, tx.clone()).await,
         _ => {
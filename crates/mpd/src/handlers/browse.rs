@@ -153,6 +153,57 @@ pub async fn handle_listfiles(
     Ok(response)
 }
 
+/// `readcomments` — the song's lyrics as one `LYRICS` entry per line
+/// (synced timestamps dropped), which is all the comments we expose.
+pub async fn handle_readcomments(
+    ctx: &mut Context,
+    request: &str,
+    tx: Sender<Vec<u8>>,
+) -> Result<String, Error> {
+    let request = request.trim();
+    let re = Regex::new(r#"^([\w-]+)(?:\s+"?([^"]*)"?)?$"#).unwrap();
+    let music_dir = get_music_dir()?;
+    let uri = re
+        .captures(request)
+        .and_then(|c| c.get(2))
+        .map(|m| m.as_str())
+        .unwrap_or("");
+    if uri.is_empty() {
+        if !ctx.batch {
+            tx.send(b"ACK [2@0] {readcomments} missing argument\n".to_vec())
+                .await?;
+        }
+        return Ok("ACK [2@0] {readcomments} missing argument\n".to_string());
+    }
+    let path = match uri.starts_with(&music_dir) {
+        true => uri.to_string(),
+        false => format!("{}/{}", music_dir, uri),
+    };
+
+    let track = ctx.kv.lock().await.get(&path).cloned();
+    let Some(track) = track else {
+        if !ctx.batch {
+            tx.send(b"ACK [50@0] {readcomments} No such file or directory\n".to_vec())
+                .await?;
+        }
+        return Ok("ACK [50@0] {readcomments} No such file or directory\n".to_string());
+    };
+
+    let mut response = String::new();
+    if let Some(lyrics) = rockbox_library::lyrics::get_lyrics(ctx.pool.clone(), &track).await? {
+        for line in lyrics.text().lines() {
+            response.push_str(&format!("LYRICS: {}\n", line));
+        }
+    }
+    response.push_str("OK\n");
+
+    if !ctx.batch {
+        tx.send(response.clone().into_bytes()).await?;
+    }
+
+    Ok(response)
+}
+
 async fn build_file_metadata(
     ctx: Context,
     path: &str,
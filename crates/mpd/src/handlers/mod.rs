@@ -4,6 +4,7 @@ pub mod browse;
 pub mod library;
 pub mod playback;
 pub mod queue;
+pub mod sticker;
 pub mod system;
 
 #[derive(Debug, Clone, PartialEq)]
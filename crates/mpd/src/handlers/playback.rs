@@ -1,8 +1,12 @@
 use anyhow::Error;
+use rockbox_autoqueue::Source;
+use rockbox_hls::transition;
 use rockbox_rpc::api::rockbox::v1alpha1::{
-    AdjustVolumeRequest, HardStopRequest, NextRequest, PauseRequest, PlayRequest, PreviousRequest,
-    ResumeRequest, SaveSettingsRequest, StartRequest,
+    AdjustVolumeRequest, GetCurrentRequest, HardStopRequest, NextRequest, PauseRequest,
+    PlayRequest, PreviousRequest, ResumeRequest, SaveSettingsRequest, SetAutoQueueRequest,
+    ShufflePlaylistRequest, StartRequest,
 };
+use rockbox_tracklist::shuffle::ShuffleMode;
 use tokio::sync::mpsc::Sender;
 
 use crate::Context;
@@ -173,14 +177,17 @@ pub async fn handle_status(
     let volume = ((volume + 80) * 100 / 80).max(0).min(100);
 
     let current_track = ctx.current_track.lock().await;
-    let consume = ctx.consume.lock().await;
-    let consume_val = if *consume { 1 } else { 0 };
-    drop(consume);
+    let consume_val = if rockbox_autoqueue::config().enabled {
+        1
+    } else {
+        0
+    };
+    let transitions = transition_status();
 
     if current_track.is_none() {
         let response = format!(
-            "volume: {}\nrepeat: {}\nrandom: {}\nsingle: 0\nconsume: {}\nplaylist: 0\nplaylistlength: 0\nstate: {}\nOK\n",
-            volume, repeat, random, consume_val, status,
+            "volume: {}\nrepeat: {}\nrandom: {}\nsingle: 0\nconsume: {}\n{}playlist: 0\nplaylistlength: 0\nstate: {}\nOK\n",
+            volume, repeat, random, consume_val, transitions, status,
         );
         if !ctx.batch {
             tx.send(response.clone().into_bytes()).await?;
@@ -206,8 +213,8 @@ pub async fn handle_status(
     let current_playlist = ctx.current_playlist.lock().await;
     if current_playlist.is_none() {
         let response = format!(
-            "volume: {}\nrepeat: {}\nrandom: {}\nsingle: {}\nconsume: {}\nplaylist: 0\nplaylistlength: 0\nstate: {}\nsong: 0\nelapsed: {}\ntime: {}\nduration: {}\naudio: {}\nbitrate: {}\nOK\n",
-            volume, repeat, random, single, consume_val, status, elapsed, time, duration, audio, bitrate,
+            "volume: {}\nrepeat: {}\nrandom: {}\nsingle: {}\nconsume: {}\n{}playlist: 0\nplaylistlength: 0\nstate: {}\nsong: 0\nelapsed: {}\ntime: {}\nduration: {}\naudio: {}\nbitrate: {}\nOK\n",
+            volume, repeat, random, single, consume_val, transitions, status, elapsed, time, duration, audio, bitrate,
         );
         if !ctx.batch {
             tx.send(response.clone().into_bytes()).await?;
@@ -220,8 +227,8 @@ pub async fn handle_status(
     let song = current_playlist.index;
 
     let response = format!(
-        "volume: {}\nrepeat: {}\nrandom: {}\nsingle: {}\nconsume: {}\nplaylist: {}\nplaylistlength: {}\nstate: {}\nsong: {}\nsongid: {}\nnextsong: {}\nnextsongid: {}\ntime: {}\nelapsed: {}\nduration: {}\naudio: {}\nbitrate: {}\nOK\n",
-        volume, repeat, random, single, consume_val, playlistlength, playlistlength, status,
+        "volume: {}\nrepeat: {}\nrandom: {}\nsingle: {}\nconsume: {}\n{}playlist: {}\nplaylistlength: {}\nstate: {}\nsong: {}\nsongid: {}\nnextsong: {}\nnextsongid: {}\ntime: {}\nelapsed: {}\nduration: {}\naudio: {}\nbitrate: {}\nOK\n",
+        volume, repeat, random, single, consume_val, transitions, playlistlength, playlistlength, status,
         song, song + 1, song + 1, song + 2,
         time, elapsed, duration, audio, bitrate,
     );
@@ -439,12 +446,63 @@ pub async fn handle_random(
         return Ok("ACK [2@0] {random} incorrect arguments\n".to_string());
     }
 
+    // Besides 0 and 1, random takes a shuffle mode (tracks, album,
+    // artist_spread or smart), which also turns it on.
+    let (random, mode) = match arg.unwrap().trim_matches('"') {
+        "0" => (false, None),
+        "1" => (true, None),
+        mode => match mode.parse::<ShuffleMode>() {
+            Ok(mode) => (true, Some(mode)),
+            Err(_) => {
+                if !ctx.batch {
+                    tx.send(b"ACK [2@0] {random} incorrect arguments\n".to_vec())
+                        .await?;
+                }
+                return Ok("ACK [2@0] {random} incorrect arguments\n".to_string());
+            }
+        },
+    };
     ctx.settings
         .save_settings(SaveSettingsRequest {
-            playlist_shuffle: Some(arg.unwrap().trim_matches('"') == "1"),
+            playlist_shuffle: Some(random),
+            shuffle_mode: mode.map(|mode| mode.as_str().to_string()),
             ..Default::default()
         })
         .await?;
+
+    // Turning shuffle on shuffles uniformly; reorder the queue when another
+    // mode is chosen, keeping the current track playing.
+    let mode = mode.unwrap_or_else(|| {
+        rockbox_settings::read_settings()
+            .ok()
+            .and_then(|s| s.shuffle_mode)
+            .and_then(|mode| mode.parse().ok())
+            .unwrap_or_default()
+    });
+    if random && mode != ShuffleMode::Tracks {
+        let current = ctx.playlist.get_current(GetCurrentRequest {}).await?;
+        ctx.playlist
+            .shuffle_playlist(ShufflePlaylistRequest {
+                start_index: current.into_inner().index,
+                mode: Some(mode.as_str().to_string()),
+                seed: None,
+            })
+            .await?;
+        match ctx.event_sender.send(Subsystem::Playlist) {
+            Ok(_) => {}
+            Err(_) => {}
+        }
+    }
+
+    let auto_queue = rockbox_autoqueue::config();
+    if auto_queue.enabled && auto_queue.source != Source::SmartPlaylist {
+        ctx.playback
+            .set_auto_queue(SetAutoQueueRequest {
+                source: Some(radio_source(random).as_str().to_string()),
+                ..Default::default()
+            })
+            .await?;
+    }
     if !ctx.batch {
         tx.send(b"OK\n".to_vec()).await?;
     }
@@ -507,9 +565,140 @@ pub async fn handle_consume(
         }
         return Ok("ACK [2@0] {consume} incorrect arguments\n".to_string());
     }
-    let mut consume = ctx.consume.lock().await;
-    *consume = arg.unwrap().trim_matches('"') == "1";
-    drop(consume);
+    // Consume doubles as the endless-radio switch: with it on the queue
+    // keeps growing, from a shuffle when random is on and from an instant
+    // mix of the queue otherwise. A smart playlist source is left alone.
+    let enabled = arg.unwrap().trim_matches('"') == "1";
+    let source = match rockbox_autoqueue::config().source {
+        Source::SmartPlaylist => None,
+        _ => {
+            let random = ctx.current_settings.lock().await.playlist_shuffle;
+            Some(radio_source(random).as_str().to_string())
+        }
+    };
+    ctx.playback
+        .set_auto_queue(SetAutoQueueRequest {
+            enabled: Some(enabled),
+            source,
+            ..Default::default()
+        })
+        .await?;
+    if !ctx.batch {
+        tx.send(b"OK\n".to_vec()).await?;
+    }
+    Ok("OK\n".to_string())
+}
+
+/// Auto-queue source that follows MPD's `random` flag.
+fn radio_source(random: bool) -> Source {
+    match random {
+        true => Source::Shuffle,
+        false => Source::InstantMix,
+    }
+}
+
+/// `xfade`, `mixrampdb` and `mixrampdelay` status lines, which describe the
+/// transitions between streamed (HLS / DASH) sources.
+fn transition_status() -> String {
+    let config = transition::config();
+    let mut lines = String::new();
+    if config.crossfade_secs > 0.0 {
+        lines.push_str(&format!(
+            "xfade: {}\n",
+            config.crossfade_secs.round() as i64
+        ));
+    }
+    lines.push_str(&format!("mixrampdb: {}\n", config.mixramp_db));
+    if config.mixramp() {
+        lines.push_str(&format!("mixrampdelay: {}\n", config.mixramp_delay));
+    }
+    lines
+}
+
+pub async fn handle_crossfade(
+    ctx: &mut Context,
+    request: &str,
+    tx: Sender<Vec<u8>>,
+) -> Result<String, Error> {
+    let secs = request
+        .split_whitespace()
+        .nth(1)
+        .and_then(|arg| arg.trim_matches('"').parse::<u32>().ok());
+    let Some(secs) = secs else {
+        if !ctx.batch {
+            tx.send(b"ACK [2@0] {crossfade} incorrect arguments\n".to_vec())
+                .await?;
+        }
+        return Ok("ACK [2@0] {crossfade} incorrect arguments\n".to_string());
+    };
+    ctx.settings
+        .save_settings(SaveSettingsRequest {
+            stream_crossfade_secs: Some(secs as f32),
+            ..Default::default()
+        })
+        .await?;
+    if !ctx.batch {
+        tx.send(b"OK\n".to_vec()).await?;
+    }
+    Ok("OK\n".to_string())
+}
+
+pub async fn handle_mixrampdb(
+    ctx: &mut Context,
+    request: &str,
+    tx: Sender<Vec<u8>>,
+) -> Result<String, Error> {
+    let db = request
+        .split_whitespace()
+        .nth(1)
+        .and_then(|arg| arg.trim_matches('"').parse::<f32>().ok())
+        .filter(|db| db.is_finite());
+    let Some(db) = db else {
+        if !ctx.batch {
+            tx.send(b"ACK [2@0] {mixrampdb} incorrect arguments\n".to_vec())
+                .await?;
+        }
+        return Ok("ACK [2@0] {mixrampdb} incorrect arguments\n".to_string());
+    };
+    ctx.settings
+        .save_settings(SaveSettingsRequest {
+            mixramp_db: Some(db),
+            ..Default::default()
+        })
+        .await?;
+    if !ctx.batch {
+        tx.send(b"OK\n".to_vec()).await?;
+    }
+    Ok("OK\n".to_string())
+}
+
+/// `mixrampdelay nan` (or any negative delay) turns MixRamp off.
+pub async fn handle_mixrampdelay(
+    ctx: &mut Context,
+    request: &str,
+    tx: Sender<Vec<u8>>,
+) -> Result<String, Error> {
+    let delay = request
+        .split_whitespace()
+        .nth(1)
+        .and_then(|arg| arg.trim_matches('"').parse::<f32>().ok());
+    let Some(delay) = delay else {
+        if !ctx.batch {
+            tx.send(b"ACK [2@0] {mixrampdelay} incorrect arguments\n".to_vec())
+                .await?;
+        }
+        return Ok("ACK [2@0] {mixrampdelay} incorrect arguments\n".to_string());
+    };
+    let delay = match delay.is_nan() || delay < 0.0 {
+        true => -1.0,
+        false => delay,
+    };
+    ctx.settings
+        .save_settings(SaveSettingsRequest {
+            mixramp_delay: Some(delay),
+            ..Default::default()
+        })
+        .await?;
     if !ctx.batch {
         tx.send(b"OK\n".to_vec()).await?;
     }
@@ -15,7 +15,10 @@ pub async fn handle_shuffle(
     tx: Sender<Vec<u8>>,
 ) -> Result<String, Error> {
     ctx.playlist
-        .shuffle_playlist(ShufflePlaylistRequest { start_index: 0 })
+        .shuffle_playlist(ShufflePlaylistRequest {
+            start_index: 0,
+            ..Default::default()
+        })
         .await?;
     if !ctx.batch {
         tx.send(b"OK\n".to_vec()).await?;
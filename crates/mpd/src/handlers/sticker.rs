@@ -0,0 +1,195 @@
+//! `sticker` commands, backed by the library's ratings. Only the `rating`
+//! sticker exists; like most MPD clients it is on a 0–10 scale, two points
+//! per star. Songs are addressed by URI, albums and artists by name.
+
+use crate::{handlers::Subsystem, Context};
+use anyhow::Error;
+use rockbox_library::{
+    entity::rating::{KIND_ALBUM, KIND_ARTIST, KIND_TRACK, MAX_RATING},
+    repo,
+};
+use rockbox_rpc::api::rockbox::v1alpha1::SetRatingRequest;
+use rockbox_settings::get_music_dir;
+use tokio::sync::mpsc::Sender;
+
+const STICKER: &str = "rating";
+
+/// Split a request into words, honouring MPD's double quotes and
+/// backslash escapes.
+fn split_args(request: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut chars = request.trim().chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut arg = String::new();
+        if c == '"' {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => arg.extend(chars.next()),
+                    '"' => break,
+                    c => arg.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                arg.push(c);
+                chars.next();
+            }
+        }
+        args.push(arg);
+    }
+    args
+}
+
+fn to_sticker(stars: u8) -> u8 {
+    stars * 2
+}
+
+fn to_stars(value: &str) -> Option<u8> {
+    let value: f64 = value.trim().parse().ok()?;
+    Some((value / 2.0).round().clamp(0.0, MAX_RATING as f64) as u8)
+}
+
+/// The library kind and id a sticker `type` and `uri` refer to.
+async fn resolve(
+    ctx: &Context,
+    r#type: &str,
+    uri: &str,
+) -> Result<Option<(&'static str, String)>, Error> {
+    match r#type.to_lowercase().as_str() {
+        "song" => {
+            let music_dir = get_music_dir()?;
+            let path = match uri.starts_with(&music_dir) {
+                true => uri.to_string(),
+                false => format!("{}/{}", music_dir, uri),
+            };
+            let track = ctx.kv.lock().await.get(&path).cloned();
+            Ok(track.map(|t| (KIND_TRACK, t.id)))
+        }
+        "album" => {
+            let albums = repo::album::all(ctx.pool.clone()).await?;
+            Ok(albums
+                .into_iter()
+                .find(|a| a.title == uri)
+                .map(|a| (KIND_ALBUM, a.id)))
+        }
+        "artist" | "albumartist" => {
+            let artist = repo::artist::find_by_name(ctx.pool.clone(), uri).await?;
+            Ok(artist.map(|a| (KIND_ARTIST, a.id)))
+        }
+        _ => Ok(None),
+    }
+}
+
+pub async fn handle_sticker(
+    ctx: &mut Context,
+    request: &str,
+    tx: Sender<Vec<u8>>,
+) -> Result<String, Error> {
+    let response = sticker(ctx, request).await?;
+    if !ctx.batch {
+        tx.send(response.clone().into_bytes()).await?;
+    }
+    Ok(response)
+}
+
+async fn sticker(ctx: &mut Context, request: &str) -> Result<String, Error> {
+    let args = split_args(request);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (subcommand, r#type, uri, rest) = match args.as_slice() {
+        [_, subcommand, r#type, uri, rest @ ..] => (*subcommand, *r#type, *uri, rest),
+        _ => return Ok("ACK [2@0] {sticker} missing argument\n".to_string()),
+    };
+    let name = rest.first().copied();
+    if name.is_some_and(|name| name != STICKER) {
+        return Ok("ACK [50@0] {sticker} no such sticker\n".to_string());
+    }
+
+    if subcommand == "find" {
+        return find(ctx, r#type, uri).await;
+    }
+
+    let Some((kind, id)) = resolve(ctx, r#type, uri).await? else {
+        return Ok("ACK [50@0] {sticker} no such song\n".to_string());
+    };
+    let rating = repo::rating::find(ctx.pool.clone(), kind, &id).await?;
+
+    match subcommand {
+        "get" => match rating {
+            Some(stars) => Ok(format!("sticker: {}={}\nOK\n", STICKER, to_sticker(stars))),
+            None => Ok("ACK [50@0] {sticker} no such sticker\n".to_string()),
+        },
+        "list" => match rating {
+            Some(stars) => Ok(format!("sticker: {}={}\nOK\n", STICKER, to_sticker(stars))),
+            None => Ok("OK\n".to_string()),
+        },
+        "set" => {
+            let Some(stars) = rest.get(1).and_then(|value| to_stars(value)) else {
+                return Ok("ACK [2@0] {sticker} invalid rating\n".to_string());
+            };
+            set_rating(ctx, kind, id, stars).await?;
+            Ok("OK\n".to_string())
+        }
+        "delete" => {
+            if rating.is_none() {
+                return Ok("ACK [50@0] {sticker} no such sticker\n".to_string());
+            }
+            set_rating(ctx, kind, id, 0).await?;
+            Ok("OK\n".to_string())
+        }
+        _ => Ok(format!(
+            "ACK [2@0] {{sticker}} unknown sticker command \"{}\"\n",
+            subcommand
+        )),
+    }
+}
+
+async fn set_rating(ctx: &mut Context, kind: &str, id: String, stars: u8) -> Result<(), Error> {
+    ctx.library
+        .set_rating(SetRatingRequest {
+            kind: kind.to_string(),
+            id,
+            rating: stars as i32,
+        })
+        .await?;
+    let _ = ctx.event_sender.send(Subsystem::Sticker);
+    Ok(())
+}
+
+/// `sticker find song "dir" rating`: every rated song below `dir`.
+async fn find(ctx: &Context, r#type: &str, uri: &str) -> Result<String, Error> {
+    if !r#type.eq_ignore_ascii_case("song") {
+        return Ok("ACK [2@0] {sticker} unsupported type\n".to_string());
+    }
+    let music_dir = format!("{}/", get_music_dir()?);
+    let dir = match uri.trim_matches('/') {
+        "" => String::new(),
+        dir => format!("{}/", dir),
+    };
+    let mut response = String::new();
+    for rating in repo::rating::all(ctx.pool.clone(), KIND_TRACK).await? {
+        let Some(track) = repo::track::find(ctx.pool.clone(), &rating.item_id).await? else {
+            continue;
+        };
+        let Some(file) = track.path.strip_prefix(&music_dir) else {
+            continue;
+        };
+        if file.starts_with(&dir) {
+            response.push_str(&format!(
+                "file: {}\nsticker: {}={}\n",
+                file,
+                STICKER,
+                to_sticker(rating.rating)
+            ));
+        }
+    }
+    response.push_str("OK\n");
+    Ok(response)
+}
@@ -106,6 +106,29 @@ pub async fn handle_ping(
     Ok("OK\n".to_string())
 }
 
+pub async fn handle_password(
+    ctx: &mut Context,
+    request: &str,
+    tx: Sender<Vec<u8>>,
+) -> Result<String, Error> {
+    let password = request
+        .trim()
+        .trim_start_matches("password")
+        .trim()
+        .trim_matches('"');
+    let response = match ctx.auth.verify(password).await? {
+        Some(token) => {
+            ctx.permission = Some(token.scope);
+            "OK\n".to_string()
+        }
+        None => "ACK [3@0] {password} incorrect password\n".to_string(),
+    };
+    if !ctx.batch {
+        tx.send(response.clone().into_bytes()).await?;
+    }
+    Ok(response)
+}
+
 pub async fn handle_notcommands(
     ctx: &mut Context,
     _request: &str,
@@ -2,7 +2,9 @@ use anyhow::Error;
 use handlers::{
     albumart::{handle_albumart, handle_readpicture},
     batch::{handle_command_list_begin, handle_command_list_ok_begin},
-    browse::{handle_listall, handle_listallinfo, handle_listfiles, handle_lsinfo},
+    browse::{
+        handle_listall, handle_listallinfo, handle_listfiles, handle_lsinfo, handle_readcomments,
+    },
     library::{
         handle_config, handle_count, handle_find, handle_find_album, handle_find_artist,
         handle_find_title, handle_findadd, handle_list_album, handle_list_artist, handle_list_date,
@@ -12,24 +14,26 @@ use handlers::{
         handle_tagtypes_enable,
     },
     playback::{
-        handle_consume, handle_currentsong, handle_disableoutput, handle_enableoutput,
-        handle_getvol, handle_next, handle_outputs, handle_pause, handle_play, handle_playid,
-        handle_previous, handle_random, handle_repeat, handle_seek, handle_seekcur, handle_seekid,
-        handle_setvol, handle_single, handle_status, handle_stop, handle_toggle,
-        handle_toggleoutput,
+        handle_consume, handle_crossfade, handle_currentsong, handle_disableoutput,
+        handle_enableoutput, handle_getvol, handle_mixrampdb, handle_mixrampdelay, handle_next,
+        handle_outputs, handle_pause, handle_play, handle_playid, handle_previous, handle_random,
+        handle_repeat, handle_seek, handle_seekcur, handle_seekid, handle_setvol, handle_single,
+        handle_status, handle_stop, handle_toggle, handle_toggleoutput,
     },
     queue::{
         handle_add, handle_addid, handle_clear, handle_delete, handle_deleteid, handle_move,
         handle_moveid, handle_playlistid, handle_playlistinfo, handle_shuffle, handle_swap,
         handle_swapid,
     },
+    sticker::handle_sticker,
     system::{
         handle_binarylimit, handle_commands, handle_decoders, handle_idle, handle_noidle,
-        handle_notcommands, handle_ping, handle_urlhandlers,
+        handle_notcommands, handle_password, handle_ping, handle_urlhandlers,
     },
     Subsystem,
 };
 use kv::{build_tracks_kv, KV};
+use rockbox_auth::{mpd_scope, Auth, AuthConfig, Scope};
 use rockbox_graphql::{
     schema::objects::{audio_status::AudioStatus, playlist::Playlist, track::Track},
     simplebroker::SimpleBroker,
@@ -54,7 +58,9 @@ use tokio::{
     sync::{broadcast, Mutex},
 };
 use tokio_stream::StreamExt;
-use tonic::transport::Channel;
+use tonic::{
+    codegen::InterceptedService, metadata::MetadataValue, transport::Channel, Request, Status,
+};
 use tracing::{debug, warn};
 
 pub mod consts;
@@ -62,18 +68,28 @@ pub mod dir;
 pub mod handlers;
 pub mod kv;
 
+/// Channel to this process's own gRPC server. Every call carries the
+/// internal token, so MPD clients keep working once API tokens are enforced.
+pub type Internal = InterceptedService<Channel, fn(Request<()>) -> Result<Request<()>, Status>>;
+
+fn internal_token(mut request: Request<()>) -> Result<Request<()>, Status> {
+    if let Ok(value) = MetadataValue::try_from(rockbox_auth::internal_authorization()) {
+        request.metadata_mut().insert("authorization", value);
+    }
+    Ok(request)
+}
+
 #[derive(Clone)]
 pub struct Context {
-    pub library: LibraryServiceClient<Channel>,
-    pub playback: PlaybackServiceClient<Channel>,
-    pub settings: SettingsServiceClient<Channel>,
-    pub sound: SoundServiceClient<Channel>,
-    pub playlist: PlaylistServiceClient<Channel>,
-    pub system: SystemServiceClient<Channel>,
-    pub saved_playlist: SavedPlaylistServiceClient<Channel>,
-    pub smart_playlist: SmartPlaylistServiceClient<Channel>,
+    pub library: LibraryServiceClient<Internal>,
+    pub playback: PlaybackServiceClient<Internal>,
+    pub settings: SettingsServiceClient<Internal>,
+    pub sound: SoundServiceClient<Internal>,
+    pub playlist: PlaylistServiceClient<Internal>,
+    pub system: SystemServiceClient<Internal>,
+    pub saved_playlist: SavedPlaylistServiceClient<Internal>,
+    pub smart_playlist: SmartPlaylistServiceClient<Internal>,
     pub single: Arc<Mutex<String>>,
-    pub consume: Arc<Mutex<bool>>,
     pub batch: bool,
     pub event_sender: broadcast::Sender<Subsystem>,
     pub event_receiver: Arc<Mutex<broadcast::Receiver<Subsystem>>>,
@@ -83,6 +99,10 @@ pub struct Context {
     pub pool: Pool<Sqlite>,
     pub kv: Arc<Mutex<KV<entity::track::Track>>>,
     pub current_settings: Arc<Mutex<UserSettings>>,
+    pub auth: Auth,
+    /// What this connection may do; `None` until `password` is sent when
+    /// API tokens are enforced.
+    pub permission: Option<Scope>,
 }
 
 pub struct MpdServer {}
@@ -120,6 +140,9 @@ impl MpdServer {
 }
 
 pub async fn handle_client(mut ctx: Context, stream: TcpStream) -> Result<(), Error> {
+    let peer = stream.peer_addr().ok().map(|addr| addr.ip());
+    ctx.permission = ctx.auth.resolve(peer, None).await?;
+
     let mut buf = [0; 4096];
     let (reader_stream, writer_stream) = tokio::io::split(stream);
     let mut reader = tokio::io::BufReader::new(reader_stream);
@@ -145,6 +168,11 @@ pub async fn handle_client(mut ctx: Context, stream: TcpStream) -> Result<(), Er
         let command = parse_command(&request)?;
         debug!("mpd request: {}", request.trim());
 
+        if let Some(ack) = permission_ack(&ctx, &command) {
+            tx.send(ack.into_bytes()).await?;
+            continue;
+        }
+
         match command.as_str() {
             "play" => handle_play(&mut ctx, &request, tx.clone()).await?,
             "stop" => handle_stop(&mut ctx, &request, tx.clone()).await?,
@@ -159,6 +187,9 @@ pub async fn handle_client(mut ctx: Context, stream: TcpStream) -> Result<(), Er
             "random" => handle_random(&mut ctx, &request, tx.clone()).await?,
             "repeat" => handle_repeat(&mut ctx, &request, tx.clone()).await?,
             "consume" => handle_consume(&mut ctx, &request, tx.clone()).await?,
+            "crossfade" => handle_crossfade(&mut ctx, &request, tx.clone()).await?,
+            "mixrampdb" => handle_mixrampdb(&mut ctx, &request, tx.clone()).await?,
+            "mixrampdelay" => handle_mixrampdelay(&mut ctx, &request, tx.clone()).await?,
             "getvol" => handle_getvol(&mut ctx, &request, tx.clone()).await?,
             "setvol" => handle_setvol(&mut ctx, &request, tx.clone()).await?,
             "volume" => handle_setvol(&mut ctx, &request, tx.clone()).await?,
@@ -208,6 +239,8 @@ pub async fn handle_client(mut ctx: Context, stream: TcpStream) -> Result<(), Er
             "listfiles" => handle_listfiles(&mut ctx, &request, tx.clone()).await?,
             "albumart" => handle_albumart(&mut ctx, &request, tx.clone()).await?,
             "readpicture" => handle_readpicture(&mut ctx, &request, tx.clone()).await?,
+            "readcomments" => handle_readcomments(&mut ctx, &request, tx.clone()).await?,
+            "sticker" => handle_sticker(&mut ctx, &request, tx.clone()).await?,
             "listplaylists" => handle_listplaylists(&mut ctx, &request, tx.clone()).await?,
             "listplaylistinfo" => handle_listplaylistinfo(&mut ctx, &request, tx.clone()).await?,
             "load" => handle_load(&mut ctx, &request, tx.clone()).await?,
@@ -219,6 +252,7 @@ pub async fn handle_client(mut ctx: Context, stream: TcpStream) -> Result<(), Er
             "find title" => handle_find_title(&mut ctx, &request, tx.clone()).await?,
             "binarylimit" => handle_binarylimit(&mut ctx, &request, tx.clone()).await?,
             "ping" => handle_ping(&mut ctx, &request, tx.clone()).await?,
+            "password" => handle_password(&mut ctx, &request, tx.clone()).await?,
             "notcommands" => handle_notcommands(&mut ctx, &request, tx.clone()).await?,
             "urlhandlers" => handle_urlhandlers(&mut ctx, &request, tx.clone()).await?,
             "commands" => handle_commands(&mut ctx, &request, tx.clone()).await?,
@@ -250,6 +284,20 @@ pub async fn handle_client(mut ctx: Context, stream: TcpStream) -> Result<(), Er
     Ok(())
 }
 
+/// The `ACK` refusing `command` when this connection lacks the permission
+/// it needs.
+pub fn permission_ack(ctx: &Context, command: &str) -> Option<String> {
+    let required = mpd_scope(command)?;
+    if ctx.permission.is_some_and(|granted| granted >= required) {
+        return None;
+    }
+    let name = command.split_whitespace().next().unwrap_or_default();
+    Some(format!(
+        "ACK [4@0] {{{}}} you don't have permission for \"{}\"\n",
+        name, name
+    ))
+}
+
 fn parse_command(request: &str) -> Result<String, Error> {
     let command = request.split_whitespace().next().unwrap_or_default();
 
@@ -278,15 +326,22 @@ pub async fn setup_context(batch: bool, ctx: Option<Context>) -> Result<Context,
 
     let pool = create_connection_pool().await?;
     let kv = Arc::new(Mutex::new(build_tracks_kv(pool.clone()).await?));
-
-    let library = LibraryServiceClient::connect(url.clone()).await?;
-    let playback = PlaybackServiceClient::connect(url.clone()).await?;
-    let settings = SettingsServiceClient::connect(url.clone()).await?;
-    let sound = SoundServiceClient::connect(url.clone()).await?;
-    let playlist = PlaylistServiceClient::connect(url.clone()).await?;
-    let system = SystemServiceClient::connect(url.clone()).await?;
-    let saved_playlist = SavedPlaylistServiceClient::connect(url.clone()).await?;
-    let smart_playlist = SmartPlaylistServiceClient::connect(url.clone()).await?;
+    let settings = rockbox_settings::read_settings().unwrap_or_default();
+    let auth = Auth::new(
+        pool.clone(),
+        AuthConfig::new(settings.auth_enabled, settings.auth_trust_localhost),
+    );
+
+    let channel = Channel::from_shared(url)?.connect().await?;
+    let token = internal_token as fn(Request<()>) -> Result<Request<()>, Status>;
+    let library = LibraryServiceClient::with_interceptor(channel.clone(), token);
+    let playback = PlaybackServiceClient::with_interceptor(channel.clone(), token);
+    let settings = SettingsServiceClient::with_interceptor(channel.clone(), token);
+    let sound = SoundServiceClient::with_interceptor(channel.clone(), token);
+    let playlist = PlaylistServiceClient::with_interceptor(channel.clone(), token);
+    let system = SystemServiceClient::with_interceptor(channel.clone(), token);
+    let saved_playlist = SavedPlaylistServiceClient::with_interceptor(channel.clone(), token);
+    let smart_playlist = SmartPlaylistServiceClient::with_interceptor(channel, token);
 
     let (event_sender, event_receiver) = broadcast::channel(16);
 
@@ -300,10 +355,6 @@ pub async fn setup_context(batch: bool, ctx: Option<Context>) -> Result<Context,
         saved_playlist,
         smart_playlist,
         single: Arc::new(Mutex::new("0".to_string())),
-        consume: match ctx {
-            Some(ref ctx) => ctx.consume.clone(),
-            None => Arc::new(Mutex::new(false)),
-        },
         batch,
         event_sender: match ctx {
             Some(ref ctx) => ctx.clone().event_sender,
@@ -328,6 +379,8 @@ pub async fn setup_context(batch: bool, ctx: Option<Context>) -> Result<Context,
         pool,
         kv,
         current_settings: Arc::new(Mutex::new(rockbox_sys::settings::get_global_settings())),
+        auth,
+        permission: ctx.and_then(|ctx| ctx.permission),
     })
 }
 
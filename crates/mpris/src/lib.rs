@@ -1,4 +1,4 @@
-use std::{env, future, sync::Arc};
+use std::{future, sync::Arc};
 
 use anyhow::Error;
 use async_std::stream::StreamExt;
@@ -7,11 +7,15 @@ use rockbox_graphql::{
     schema::objects::{audio_status::AudioStatus, track::Track},
     simplebroker::SimpleBroker,
 };
-use rockbox_rpc::api::rockbox::v1alpha1::{
-    playback_service_client::PlaybackServiceClient, settings_service_client::SettingsServiceClient,
-    sound_service_client::SoundServiceClient, AdjustVolumeRequest, GetGlobalSettingsRequest,
-    HardStopRequest, NextRequest, PauseRequest, PlayOrPauseRequest, PlayRequest, PlayTrackRequest,
-    PreviousRequest, ResumeRequest, SaveSettingsRequest,
+use rockbox_rpc::{
+    api::rockbox::v1alpha1::{
+        playback_service_client::PlaybackServiceClient,
+        settings_service_client::SettingsServiceClient, sound_service_client::SoundServiceClient,
+        AdjustVolumeRequest, GetGlobalSettingsRequest, HardStopRequest, NextRequest, PauseRequest,
+        PlayOrPauseRequest, PlayRequest, PlayTrackRequest, PreviousRequest, ResumeRequest,
+        SaveSettingsRequest,
+    },
+    tls,
 };
 use tokio::sync::Mutex;
 use tracing::warn;
@@ -26,20 +30,12 @@ pub struct MprisServer {
 
 impl MprisServer {
     pub async fn start() -> Result<Self, Error> {
-        let host = env::var("ROCKBOX_HOST").unwrap_or_else(|_| "localhost".to_string());
-        let port = env::var("ROCKBOX_PORT").unwrap_or_else(|_| "6061".to_string());
-        let url = format!("tcp://{}:{}", host, port);
-
         let rt = tokio::runtime::Runtime::new()?;
-        let client = Arc::new(Mutex::new(
-            rt.block_on(PlaybackServiceClient::connect(url.clone()))?,
-        ));
-        let settings_service_client = Arc::new(Mutex::new(
-            rt.block_on(SettingsServiceClient::connect(url.clone()))?,
-        ));
-        let sound_service_client = Arc::new(Mutex::new(
-            rt.block_on(SoundServiceClient::connect(url.clone()))?,
-        ));
+        let channel = rt.block_on(tls::connect_internal(&tls::grpc_url()))?;
+        let client = Arc::new(Mutex::new(PlaybackServiceClient::new(channel.clone())));
+        let settings_service_client =
+            Arc::new(Mutex::new(SettingsServiceClient::new(channel.clone())));
+        let sound_service_client = Arc::new(Mutex::new(SoundServiceClient::new(channel)));
 
         let player = Player::builder(PLAYER_NAME)
             .can_play(true)
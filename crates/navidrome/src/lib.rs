@@ -332,7 +332,7 @@ pub fn compute_token(password: &str, salt: &str) -> String {
 }
 
 /// Build the Subsonic auth query string fragment.
-fn auth_params(user: &str, token: &str, salt: &str) -> String {
+pub fn auth_params(user: &str, token: &str, salt: &str) -> String {
     format!(
         "u={}&t={}&s={}&v={}&c={}&f=json",
         user, token, salt, API_VERSION, CLIENT_NAME
@@ -705,6 +705,56 @@ pub async fn get_album_with_songs(
     }
 }
 
+/// Every song on the server, album by album, for mirroring its catalogue.
+/// Unlike the browse helpers this fails instead of returning an empty
+/// list, so a server that is down is not mistaken for an empty one.
+pub async fn list_all_songs(
+    base_url: &str,
+    user: &str,
+    token: &str,
+    salt: &str,
+) -> Result<Vec<NavidromeSong>, anyhow::Error> {
+    const PAGE: usize = 500;
+    let base = base_url.trim_end_matches('/');
+    let mut songs = vec![];
+    let mut offset = 0;
+    loop {
+        let url = format!(
+            "{}/rest/getAlbumList2.view?type=alphabeticalByName&size={}&offset={}&{}",
+            base,
+            PAGE,
+            offset,
+            auth_params(user, token, salt)
+        );
+        let albums = fetch_subsonic(&url)
+            .await?
+            .album_list2
+            .and_then(|w| w.album)
+            .unwrap_or_default();
+        let count = albums.len();
+        for album in albums {
+            let url = format!(
+                "{}/rest/getAlbum.view?id={}&{}",
+                base,
+                percent_encode(&album.id),
+                auth_params(user, token, salt)
+            );
+            let detail = fetch_subsonic(&url).await?.album;
+            songs.extend(
+                detail
+                    .and_then(|a| a.song)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|s| s.into_song()),
+            );
+        }
+        if count < PAGE {
+            return Ok(songs);
+        }
+        offset += PAGE;
+    }
+}
+
 /// Fetch a single song's metadata (used to resolve cover art for the currently playing track).
 pub async fn get_song(
     base_url: &str,
@@ -1,6 +1,16 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use rand::seq::SliceRandom;
-use rockbox_library::{audio_scan::scan_audio_files, entity::favourites::Favourites, repo};
+use rockbox_library::{
+    audio_scan::scan_audio_files,
+    entity::{
+        favourites::Favourites,
+        rating::{KIND_ALBUM, KIND_ARTIST, KIND_TRACK, MAX_RATING},
+    },
+    radio::{self, StationInput},
+    ratings, repo,
+};
+use rockbox_podcasts::{status as podcast_status, PodcastChannel, PodcastEpisode};
+use rockbox_similarity::SimilarityStore;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -91,6 +101,17 @@ pub struct TopSongsParams {
     pub count: Option<i64>,
 }
 
+#[derive(Deserialize, Default)]
+pub struct SimilarSongsParams {
+    pub u: Option<String>,
+    pub p: Option<String>,
+    pub t: Option<String>,
+    pub s: Option<String>,
+    pub f: Option<String>,
+    pub id: Option<String>,
+    pub count: Option<i64>,
+}
+
 #[derive(Deserialize, Default)]
 pub struct LyricsParams {
     pub u: Option<String>,
@@ -143,6 +164,17 @@ pub struct StarParams {
     pub artist_id: Option<String>,
 }
 
+#[derive(Deserialize, Default)]
+pub struct SetRatingParams {
+    pub u: Option<String>,
+    pub p: Option<String>,
+    pub t: Option<String>,
+    pub s: Option<String>,
+    pub f: Option<String>,
+    pub id: Option<String>,
+    pub rating: Option<i64>,
+}
+
 #[derive(Deserialize, Default)]
 pub struct SongsByGenreParams {
     pub u: Option<String>,
@@ -155,6 +187,35 @@ pub struct SongsByGenreParams {
     pub offset: Option<i64>,
 }
 
+#[derive(Deserialize, Default)]
+pub struct InternetRadioParams {
+    pub u: Option<String>,
+    pub p: Option<String>,
+    pub t: Option<String>,
+    pub s: Option<String>,
+    pub f: Option<String>,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "streamUrl")]
+    pub stream_url: Option<String>,
+    #[serde(rename = "homepageUrl")]
+    pub homepage_url: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct PodcastParams {
+    pub u: Option<String>,
+    pub p: Option<String>,
+    pub t: Option<String>,
+    pub s: Option<String>,
+    pub f: Option<String>,
+    pub id: Option<String>,
+    pub url: Option<String>,
+    #[serde(rename = "includeEpisodes")]
+    pub include_episodes: Option<bool>,
+    pub count: Option<i64>,
+}
+
 // ── Auth helper ───────────────────────────────────────────────────────────────
 
 fn auth_check(
@@ -225,6 +286,30 @@ fn artist_to_json(a: &rockbox_library::entity::artist::Artist, album_count: i64)
     })
 }
 
+/// Set `userRating` on every rated item of `kind`.
+async fn add_user_ratings(state: &SubsonicState, kind: &str, items: &mut [Value]) {
+    let ratings: HashMap<String, u8> = match repo::rating::all(state.pool.clone(), kind).await {
+        Ok(ratings) => ratings.into_iter().map(|r| (r.item_id, r.rating)).collect(),
+        Err(e) => {
+            tracing::error!("userRating: {e}");
+            return;
+        }
+    };
+    for item in items.iter_mut() {
+        let rating = item["id"].as_str().and_then(|id| ratings.get(id)).copied();
+        if let (Some(rating), Some(obj)) = (rating, item.as_object_mut()) {
+            obj.insert("userRating".to_string(), json!(rating));
+        }
+    }
+}
+
+fn user_rating_attr(v: &Value) -> String {
+    match v["userRating"].as_i64() {
+        Some(rating) => format!(r#" userRating="{rating}""#),
+        None => String::new(),
+    }
+}
+
 fn mime_for_path(path: &str) -> &'static str {
     match path.rsplit('.').next().map(|s| s.to_lowercase()).as_deref() {
         Some("mp3") => "audio/mpeg",
@@ -422,19 +507,21 @@ pub async fn get_artist(
             vec![]
         }
     };
-    let album_jsons: Vec<Value> = albums.iter().map(|a| album_to_child(a, 0)).collect();
-    let json_data = json!({
-        "artist": {
-            "id": artist.id,
-            "name": artist.name,
-            "albumCount": album_jsons.len(),
-            "coverArt": format!("ar-{}", artist.id),
-            "album": album_jsons,
-        }
+    let mut album_jsons: Vec<Value> = albums.iter().map(|a| album_to_child(a, 0)).collect();
+    add_user_ratings(&state, KIND_ALBUM, &mut album_jsons).await;
+    let mut artist_json = json!({
+        "id": artist.id,
+        "name": artist.name,
+        "albumCount": album_jsons.len(),
+        "coverArt": format!("ar-{}", artist.id),
     });
+    add_user_ratings(&state, KIND_ARTIST, std::slice::from_mut(&mut artist_json)).await;
+    let rating_attr = user_rating_attr(&artist_json);
+    artist_json["album"] = json!(album_jsons);
+    let json_data = json!({ "artist": artist_json });
     let albums_xml: String = album_jsons.iter().map(|a| album_elem_xml(a)).collect();
     let xml = format!(
-        r#"<artist id="{}" name="{}" albumCount="{}">{albums_xml}</artist>"#,
+        r#"<artist id="{}" name="{}" albumCount="{}"{rating_attr}>{albums_xml}</artist>"#,
         xml_escape(&artist.id),
         xml_escape(&artist.name),
         album_jsons.len()
@@ -477,23 +564,25 @@ pub async fn get_album(
             vec![]
         }
     };
-    let song_jsons: Vec<Value> = tracks.iter().map(track_to_child).collect();
-    let json_data = json!({
-        "album": {
-            "id": album.id,
-            "name": album.title,
-            "artist": album.artist,
-            "artistId": album.artist_id,
-            "coverArt": format!("al-{}", album.id),
-            "songCount": song_jsons.len(),
-            "duration": tracks.iter().map(|t| t.length / 1000).sum::<u32>(),
-            "year": if album.year > 0 { json!(album.year) } else { Value::Null },
-            "song": song_jsons,
-        }
+    let mut song_jsons: Vec<Value> = tracks.iter().map(track_to_child).collect();
+    add_user_ratings(&state, KIND_TRACK, &mut song_jsons).await;
+    let mut album_json = json!({
+        "id": album.id,
+        "name": album.title,
+        "artist": album.artist,
+        "artistId": album.artist_id,
+        "coverArt": format!("al-{}", album.id),
+        "songCount": song_jsons.len(),
+        "duration": tracks.iter().map(|t| t.length / 1000).sum::<u32>(),
+        "year": if album.year > 0 { json!(album.year) } else { Value::Null },
     });
+    add_user_ratings(&state, KIND_ALBUM, std::slice::from_mut(&mut album_json)).await;
+    let rating_attr = user_rating_attr(&album_json);
+    album_json["song"] = json!(song_jsons);
+    let json_data = json!({ "album": album_json });
     let songs_xml: String = song_jsons.iter().map(|s| song_elem_xml(s)).collect();
     let xml = format!(
-        r#"<album id="{}" name="{}" artist="{}" artistId="{}" songCount="{}">{songs_xml}</album>"#,
+        r#"<album id="{}" name="{}" artist="{}" artistId="{}" songCount="{}"{rating_attr}>{songs_xml}</album>"#,
         xml_escape(&album.id),
         xml_escape(&album.title),
         xml_escape(&album.artist),
@@ -528,7 +617,8 @@ pub async fn get_song(state: web::Data<SubsonicState>, query: web::Query<IdParam
             return response::respond_error(f, 0, "database error");
         }
     };
-    let song = track_to_child(&track);
+    let mut song = track_to_child(&track);
+    add_user_ratings(&state, KIND_TRACK, std::slice::from_mut(&mut song)).await;
     let xml = song_elem_xml(&song);
     response::respond(f, json!({"song": song}), &xml)
 }
@@ -554,19 +644,36 @@ pub async fn stream(
         Some(id) => id,
         None => return response::respond_error(f, 10, "Required parameter is missing: id"),
     };
-    let track = match repo::track::find(state.pool.clone(), id).await {
-        Ok(Some(t)) => t,
-        Ok(None) => return response::respond_error(f, 70, "Song not found"),
-        Err(e) => {
-            tracing::error!("stream: {e}");
-            return response::respond_error(f, 0, "database error");
+    let path = if let Some(episode_id) = id.strip_prefix(PODCAST_STREAM_PREFIX) {
+        match state.podcast_store.get_episode(episode_id).await {
+            Ok(Some(episode)) if episode.status == podcast_status::COMPLETED => {
+                match episode.local_path {
+                    Some(path) => path,
+                    None => return response::respond_error(f, 70, "Episode not downloaded"),
+                }
+            }
+            Ok(Some(_)) => return response::respond_error(f, 70, "Episode not downloaded"),
+            Ok(None) => return response::respond_error(f, 70, "Podcast episode not found"),
+            Err(e) => {
+                tracing::error!("stream: {e}");
+                return response::respond_error(f, 0, "database error");
+            }
+        }
+    } else {
+        match repo::track::find(state.pool.clone(), id).await {
+            Ok(Some(t)) => t.path,
+            Ok(None) => return response::respond_error(f, 70, "Song not found"),
+            Err(e) => {
+                tracing::error!("stream: {e}");
+                return response::respond_error(f, 0, "database error");
+            }
         }
     };
-    let content_type = mime_for_path(&track.path);
-    let file_size = match std::fs::metadata(&track.path) {
+    let content_type = mime_for_path(&path);
+    let file_size = match std::fs::metadata(&path) {
         Ok(m) => m.len(),
         Err(e) => {
-            tracing::error!("stream stat {}: {e}", track.path);
+            tracing::error!("stream stat {}: {e}", path);
             return response::respond_error(f, 0, "could not read file");
         }
     };
@@ -585,7 +692,7 @@ pub async fn stream(
                     .min(file_size.saturating_sub(1));
                 if start <= end {
                     use std::io::{Read, Seek, SeekFrom};
-                    match std::fs::File::open(&track.path) {
+                    match std::fs::File::open(&path) {
                         Ok(mut file) => {
                             let _ = file.seek(SeekFrom::Start(start));
                             let length = (end - start + 1) as usize;
@@ -604,7 +711,7 @@ pub async fn stream(
                                 .body(buf);
                         }
                         Err(e) => {
-                            tracing::error!("stream range open {}: {e}", track.path);
+                            tracing::error!("stream range open {}: {e}", path);
                             return response::respond_error(f, 0, "could not read file");
                         }
                     }
@@ -613,18 +720,18 @@ pub async fn stream(
         }
     }
 
-    match std::fs::read(&track.path) {
+    match std::fs::read(&path) {
         Ok(data) => HttpResponse::Ok()
             .content_type(content_type)
             .insert_header(("Accept-Ranges", "bytes"))
             .insert_header(("Content-Length", file_size.to_string()))
             .insert_header((
                 "Content-Disposition",
-                format!("attachment; filename=\"{}\"", safe_filename(&track.path)),
+                format!("attachment; filename=\"{}\"", safe_filename(&path)),
             ))
             .body(data),
         Err(e) => {
-            tracing::error!("stream read {}: {e}", track.path);
+            tracing::error!("stream read {}: {e}", path);
             response::respond_error(f, 0, "could not read file")
         }
     }
@@ -794,7 +901,7 @@ pub async fn search3(
     let all_artists = repo::artist::all(state.pool.clone())
         .await
         .unwrap_or_default();
-    let artists: Vec<Value> = all_artists
+    let mut artists: Vec<Value> = all_artists
         .iter()
         .filter(|a| term.is_empty() || a.name.to_lowercase().contains(&term))
         .skip(artist_offset)
@@ -805,7 +912,7 @@ pub async fn search3(
     let all_albums = repo::album::all(state.pool.clone())
         .await
         .unwrap_or_default();
-    let albums: Vec<Value> = all_albums
+    let mut albums: Vec<Value> = all_albums
         .iter()
         .filter(|a| {
             term.is_empty()
@@ -820,7 +927,7 @@ pub async fn search3(
     let all_tracks = repo::track::all(state.pool.clone())
         .await
         .unwrap_or_default();
-    let songs: Vec<Value> = all_tracks
+    let mut songs: Vec<Value> = all_tracks
         .iter()
         .filter(|t| {
             term.is_empty()
@@ -833,6 +940,10 @@ pub async fn search3(
         .map(track_to_child)
         .collect();
 
+    add_user_ratings(&state, KIND_ARTIST, &mut artists).await;
+    add_user_ratings(&state, KIND_ALBUM, &mut albums).await;
+    add_user_ratings(&state, KIND_TRACK, &mut songs).await;
+
     let json_data = json!({
         "searchResult3": {
             "artist": artists,
@@ -844,9 +955,10 @@ pub async fn search3(
         .iter()
         .map(|a| {
             format!(
-                r#"<artist id="{}" name="{}"/>"#,
+                r#"<artist id="{}" name="{}"{}/>"#,
                 xml_escape(a["id"].as_str().unwrap_or("")),
-                xml_escape(a["name"].as_str().unwrap_or(""))
+                xml_escape(a["name"].as_str().unwrap_or("")),
+                user_rating_attr(a)
             )
         })
         .collect();
@@ -1024,15 +1136,27 @@ pub async fn get_album_list2(
                 favs.iter().map(|a| a.id.clone()).collect();
             albums.retain(|a| fav_ids.contains(&a.id));
         }
+        "highest" => {
+            // Rated albums only, best first
+            let rated: HashMap<String, u8> = repo::rating::all(state.pool.clone(), KIND_ALBUM)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|r| (r.item_id, r.rating))
+                .collect();
+            albums.retain(|a| rated.contains_key(&a.id));
+            albums.sort_by(|a, b| rated[&b.id].cmp(&rated[&a.id]));
+        }
         _ => {}
     }
 
-    let album_jsons: Vec<Value> = albums
+    let mut album_jsons: Vec<Value> = albums
         .iter()
         .skip(offset)
         .take(size)
         .map(|a| album_to_child(a, 0))
         .collect();
+    add_user_ratings(&state, KIND_ALBUM, &mut album_jsons).await;
 
     let json_data = json!({ "albumList2": { "album": album_jsons } });
     let xml_inner: String = album_jsons.iter().map(|a| album_elem_xml(a)).collect();
@@ -1084,7 +1208,8 @@ pub async fn get_random_songs(
     tracks.shuffle(&mut rng);
     tracks.truncate(size);
 
-    let song_jsons: Vec<Value> = tracks.iter().map(track_to_child).collect();
+    let mut song_jsons: Vec<Value> = tracks.iter().map(track_to_child).collect();
+    add_user_ratings(&state, KIND_TRACK, &mut song_jsons).await;
     let json_data = json!({ "randomSongs": { "song": song_jsons } });
     let xml_inner: String = song_jsons.iter().map(|s| song_elem_xml(s)).collect();
     let xml = format!(r#"<randomSongs>{xml_inner}</randomSongs>"#);
@@ -1298,6 +1423,49 @@ pub async fn unstar(
     response::respond(f, json!({}), "")
 }
 
+/// `id` may be a song, album or artist; a rating of 0 removes it.
+pub async fn set_rating(
+    state: web::Data<SubsonicState>,
+    query: web::Query<SetRatingParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let id = match q.id.as_deref() {
+        Some(id) => id,
+        None => return response::respond_error(f, 10, "Required parameter is missing: id"),
+    };
+    let rating = match q.rating {
+        Some(r) if (0..=MAX_RATING as i64).contains(&r) => r as u8,
+        Some(_) => return response::respond_error(f, 0, "rating must be between 0 and 5"),
+        None => return response::respond_error(f, 10, "Required parameter is missing: rating"),
+    };
+    let pool = state.pool.clone();
+    let kind = if matches!(repo::track::find(pool.clone(), id).await, Ok(Some(_))) {
+        KIND_TRACK
+    } else if matches!(repo::album::find(pool.clone(), id).await, Ok(Some(_))) {
+        KIND_ALBUM
+    } else if matches!(repo::artist::find(pool.clone(), id).await, Ok(Some(_))) {
+        KIND_ARTIST
+    } else {
+        return response::respond_error(f, 70, "Item not found");
+    };
+    if let Err(e) = ratings::set(pool, kind, id, rating).await {
+        tracing::error!("setRating: {e}");
+        return response::respond_error(f, 0, "database error");
+    }
+    response::respond(f, json!({}), "")
+}
+
 // ── Artist / Album info ───────────────────────────────────────────────────────
 
 pub async fn get_artist_info2(
@@ -1412,11 +1580,39 @@ pub async fn get_album_info2(
     response::respond(f, json_data, "<albumInfo/>")
 }
 
-pub async fn get_similar_songs2(
+/// Tracks that sound like the song, album or artist `id`, from the
+/// offline acoustic analysis. Empty until the analysis has covered them.
+async fn similar_songs(state: &SubsonicState, id: &str, count: usize) -> Vec<Value> {
+    let seeds: Vec<String> = match repo::track::find(state.pool.clone(), id).await {
+        Ok(Some(track)) => vec![track.id],
+        _ => {
+            let mut tracks = repo::album_tracks::find_by_album(state.pool.clone(), id)
+                .await
+                .unwrap_or_default();
+            if tracks.is_empty() {
+                tracks = repo::artist_tracks::find_by_artist(state.pool.clone(), id)
+                    .await
+                    .unwrap_or_default();
+            }
+            tracks.into_iter().map(|t| t.id).collect()
+        }
+    };
+    SimilarityStore::new(state.pool.clone())
+        .similar_tracks(&seeds, count)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(track_to_child)
+        .collect()
+}
+
+/// `getSimilarSongs` and `getSimilarSongs2` only differ in the element
+/// name.
+async fn respond_similar_songs(
     state: web::Data<SubsonicState>,
-    query: web::Query<IdParam>,
+    q: SimilarSongsParams,
+    key: &str,
 ) -> HttpResponse {
-    let q = query.into_inner();
     let f = q.f.as_deref();
     if let Some(r) = auth_check(
         &state,
@@ -1428,13 +1624,33 @@ pub async fn get_similar_songs2(
     ) {
         return r;
     }
+    let count = q.count.unwrap_or(50).max(1) as usize;
+    let songs = match q.id.as_deref() {
+        Some(id) => similar_songs(&state, id, count).await,
+        None => Vec::new(),
+    };
+    let xml_inner: String = songs.iter().map(song_elem_xml).collect();
     response::respond(
         f,
-        json!({ "similarSongs2": { "song": [] } }),
-        "<similarSongs2/>",
+        json!({ key: { "song": songs } }),
+        &format!("<{key}>{xml_inner}</{key}>"),
     )
 }
 
+pub async fn get_similar_songs(
+    state: web::Data<SubsonicState>,
+    query: web::Query<SimilarSongsParams>,
+) -> HttpResponse {
+    respond_similar_songs(state, query.into_inner(), "similarSongs").await
+}
+
+pub async fn get_similar_songs2(
+    state: web::Data<SubsonicState>,
+    query: web::Query<SimilarSongsParams>,
+) -> HttpResponse {
+    respond_similar_songs(state, query.into_inner(), "similarSongs2").await
+}
+
 pub async fn get_top_songs(
     state: web::Data<SubsonicState>,
     query: web::Query<TopSongsParams>,
@@ -1490,8 +1706,49 @@ pub async fn get_lyrics(
     ) {
         return r;
     }
-    let json_data = json!({ "lyrics": { "artist": "", "title": "", "value": "" } });
-    response::respond(f, json_data, r#"<lyrics artist="" title=""/>"#)
+    // Non-standard `id` wins; otherwise match on title, then artist.
+    let track = match q.id.as_deref() {
+        Some(id) => repo::track::find(state.pool.clone(), id)
+            .await
+            .ok()
+            .flatten(),
+        None => match q.title.as_deref().filter(|t| !t.is_empty()) {
+            Some(title) => repo::track::find_by_title(state.pool.clone(), title)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .find(|t| {
+                    q.artist
+                        .as_deref()
+                        .map_or(true, |a| t.artist.eq_ignore_ascii_case(a))
+                }),
+            None => None,
+        },
+    };
+    let lyrics = match &track {
+        Some(track) => rockbox_library::lyrics::get_lyrics(state.pool.clone(), track)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("getLyrics {}: {e}", track.id);
+                None
+            }),
+        None => None,
+    };
+    let Some((track, lyrics)) = track.zip(lyrics) else {
+        let json_data = json!({ "lyrics": { "artist": "", "title": "", "value": "" } });
+        return response::respond(f, json_data, r#"<lyrics artist="" title=""/>"#);
+    };
+    let value = lyrics.text();
+    let json_data = json!({
+        "lyrics": { "artist": track.artist, "title": track.title, "value": value }
+    });
+    let xml = format!(
+        r#"<lyrics artist="{}" title="{}">{}</lyrics>"#,
+        xml_escape(&track.artist),
+        xml_escape(&track.title),
+        xml_escape(&value)
+    );
+    response::respond(f, json_data, &xml)
 }
 
 // ── Aliases for older/folder-browsing API calls ───────────────────────────────
@@ -1602,19 +1859,20 @@ fn safe_filename(path: &str) -> String {
 
 fn album_elem_xml(a: &Value) -> String {
     format!(
-        r#"<album id="{}" name="{}" artist="{}" artistId="{}" songCount="{}" coverArt="{}"/>"#,
+        r#"<album id="{}" name="{}" artist="{}" artistId="{}" songCount="{}" coverArt="{}"{}/>"#,
         xml_escape(a["id"].as_str().unwrap_or("")),
         xml_escape(a["name"].as_str().unwrap_or("")),
         xml_escape(a["artist"].as_str().unwrap_or("")),
         xml_escape(a["artistId"].as_str().unwrap_or("")),
         a["songCount"].as_i64().unwrap_or(0),
-        xml_escape(a["coverArt"].as_str().unwrap_or(""))
+        xml_escape(a["coverArt"].as_str().unwrap_or("")),
+        user_rating_attr(a)
     )
 }
 
 fn song_elem_xml(s: &Value) -> String {
     format!(
-        r#"<song id="{}" parent="{}" title="{}" album="{}" artist="{}" isDir="false" coverArt="{}" duration="{}" bitRate="{}" track="{}" contentType="{}" suffix="{}" albumId="{}" artistId="{}"/>"#,
+        r#"<song id="{}" parent="{}" title="{}" album="{}" artist="{}" isDir="false" coverArt="{}" duration="{}" bitRate="{}" track="{}" contentType="{}" suffix="{}" albumId="{}" artistId="{}"{}/>"#,
         xml_escape(s["id"].as_str().unwrap_or("")),
         xml_escape(s["parent"].as_str().unwrap_or("")),
         xml_escape(s["title"].as_str().unwrap_or("")),
@@ -1627,7 +1885,8 @@ fn song_elem_xml(s: &Value) -> String {
         xml_escape(s["contentType"].as_str().unwrap_or("audio/mpeg")),
         xml_escape(s["suffix"].as_str().unwrap_or("")),
         xml_escape(s["albumId"].as_str().unwrap_or("")),
-        xml_escape(s["artistId"].as_str().unwrap_or(""))
+        xml_escape(s["artistId"].as_str().unwrap_or("")),
+        user_rating_attr(s)
     )
 }
 
@@ -1750,8 +2009,12 @@ pub async fn start_scan(
     tokio::spawn(async move {
         let home = std::env::var("HOME").unwrap_or_default();
         let path = std::env::var("ROCKBOX_LIBRARY").unwrap_or_else(|_| format!("{}/Music", home));
-        if let Err(e) = scan_audio_files(pool, path.into()).await {
-            tracing::error!("startScan: {e}");
+        match scan_audio_files(pool, path.clone().into()).await {
+            Ok(scanned) => rockbox_webhooks::emit(rockbox_webhooks::Event::LibraryScanFinished {
+                path,
+                tracks: scanned.len(),
+            }),
+            Err(e) => tracing::error!("startScan: {e}"),
         }
         flag.store(false, Ordering::Relaxed);
     });
@@ -2207,6 +2470,534 @@ pub async fn delete_playlist(
     response::respond(f, json!({}), "")
 }
 
+// ── Internet radio ────────────────────────────────────────────────────────────
+
+pub async fn get_internet_radio_stations(
+    state: web::Data<SubsonicState>,
+    query: web::Query<CommonParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let stations = match repo::radio_station::all(state.pool.clone()).await {
+        Ok(stations) => stations,
+        Err(e) => {
+            tracing::error!("getInternetRadioStations: {e}");
+            return response::respond_error(f, 0, "database error");
+        }
+    };
+    let entries: Vec<Value> = stations
+        .iter()
+        .map(|s| {
+            json!({
+                "id": s.id,
+                "name": s.name,
+                "streamUrl": s.stream_url,
+                "homePageUrl": s.homepage_url,
+            })
+        })
+        .collect();
+    let xml_inner: String = stations
+        .iter()
+        .map(|s| {
+            format!(
+                r#"<internetRadioStation id="{}" name="{}" streamUrl="{}" homePageUrl="{}"/>"#,
+                xml_escape(&s.id),
+                xml_escape(&s.name),
+                xml_escape(&s.stream_url),
+                xml_escape(s.homepage_url.as_deref().unwrap_or(""))
+            )
+        })
+        .collect();
+    let json_data = json!({ "internetRadioStations": { "internetRadioStation": entries } });
+    let xml = format!("<internetRadioStations>{xml_inner}</internetRadioStations>");
+    response::respond(f, json_data, &xml)
+}
+
+pub async fn create_internet_radio_station(
+    state: web::Data<SubsonicState>,
+    query: web::Query<InternetRadioParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let (Some(stream_url), Some(name)) = (q.stream_url, q.name) else {
+        return response::respond_error(f, 10, "Required parameter is missing: streamUrl, name");
+    };
+    let input = StationInput {
+        name,
+        stream_url,
+        homepage_url: q.homepage_url,
+        ..Default::default()
+    };
+    if !input.is_valid() {
+        return response::respond_error(f, 0, "Invalid stream URL");
+    }
+    if let Err(e) = radio::create_station(state.pool.clone(), input).await {
+        tracing::error!("createInternetRadioStation: {e}");
+        return response::respond_error(f, 0, "database error");
+    }
+    response::respond(f, json!({}), "")
+}
+
+pub async fn update_internet_radio_station(
+    state: web::Data<SubsonicState>,
+    query: web::Query<InternetRadioParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let (Some(id), Some(stream_url), Some(name)) = (q.id, q.stream_url, q.name) else {
+        return response::respond_error(
+            f,
+            10,
+            "Required parameter is missing: id, streamUrl, name",
+        );
+    };
+    let existing = match repo::radio_station::find(state.pool.clone(), &id).await {
+        Ok(Some(station)) => station,
+        Ok(None) => return response::respond_error(f, 70, "Internet radio station not found"),
+        Err(e) => {
+            tracing::error!("updateInternetRadioStation: {e}");
+            return response::respond_error(f, 0, "database error");
+        }
+    };
+    // Subsonic only knows name and URLs; keep the rest of the station as is.
+    let input = StationInput {
+        name,
+        stream_url,
+        homepage_url: q.homepage_url,
+        genre: existing.genre,
+        country: existing.country,
+        codec: existing.codec,
+        bitrate: existing.bitrate,
+        favicon: existing.favicon,
+    };
+    if !input.is_valid() {
+        return response::respond_error(f, 0, "Invalid stream URL");
+    }
+    if let Err(e) = radio::update_station(state.pool.clone(), &id, input).await {
+        tracing::error!("updateInternetRadioStation: {e}");
+        return response::respond_error(f, 0, "database error");
+    }
+    response::respond(f, json!({}), "")
+}
+
+pub async fn delete_internet_radio_station(
+    state: web::Data<SubsonicState>,
+    query: web::Query<IdParam>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let id = match q.id.as_deref() {
+        Some(id) => id,
+        None => return response::respond_error(f, 10, "Required parameter is missing: id"),
+    };
+    match repo::radio_station::delete(state.pool.clone(), id).await {
+        Ok(true) => response::respond(f, json!({}), ""),
+        Ok(false) => response::respond_error(f, 70, "Internet radio station not found"),
+        Err(e) => {
+            tracing::error!("deleteInternetRadioStation: {e}");
+            response::respond_error(f, 0, "database error")
+        }
+    }
+}
+
+// ── Podcasts ──────────────────────────────────────────────────────────────────
+
+/// Prefix of the `streamId` given to downloaded episodes, so `stream` can
+/// tell them apart from library track ids.
+const PODCAST_STREAM_PREFIX: &str = "pe-";
+
+fn podcast_episode_to_json(e: &PodcastEpisode) -> Value {
+    let downloaded = e.status == podcast_status::COMPLETED;
+    let path = e.local_path.as_deref().unwrap_or(&e.audio_url);
+    let publish_date = e
+        .published_at
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|d| d.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    json!({
+        "id": e.id,
+        "streamId": downloaded.then(|| format!("{PODCAST_STREAM_PREFIX}{}", e.id)),
+        "channelId": e.channel_id,
+        "parent": e.channel_id,
+        "title": e.title,
+        "description": e.description,
+        "publishDate": publish_date,
+        "status": e.status,
+        "isDir": false,
+        "isVideo": false,
+        "type": "podcast",
+        "duration": e.duration_secs,
+        "size": e.file_size,
+        "contentType": e.mime_type.as_deref().unwrap_or_else(|| mime_for_path(path)),
+        "suffix": path.rsplit('.').next().unwrap_or("mp3").to_lowercase(),
+    })
+}
+
+fn podcast_episode_xml(e: &Value) -> String {
+    let stream_id = match e["streamId"].as_str() {
+        Some(id) => format!(r#" streamId="{}""#, xml_escape(id)),
+        None => String::new(),
+    };
+    format!(
+        r#"<episode id="{}"{stream_id} channelId="{}" parent="{}" title="{}" description="{}" publishDate="{}" status="{}" isDir="false" duration="{}" size="{}" contentType="{}" suffix="{}"/>"#,
+        xml_escape(e["id"].as_str().unwrap_or("")),
+        xml_escape(e["channelId"].as_str().unwrap_or("")),
+        xml_escape(e["parent"].as_str().unwrap_or("")),
+        xml_escape(e["title"].as_str().unwrap_or("")),
+        xml_escape(e["description"].as_str().unwrap_or("")),
+        xml_escape(e["publishDate"].as_str().unwrap_or("")),
+        xml_escape(e["status"].as_str().unwrap_or("")),
+        e["duration"].as_i64().unwrap_or(0),
+        e["size"].as_i64().unwrap_or(0),
+        xml_escape(e["contentType"].as_str().unwrap_or("")),
+        xml_escape(e["suffix"].as_str().unwrap_or("")),
+    )
+}
+
+fn podcast_channel_json_xml(
+    c: &PodcastChannel,
+    episodes: Option<&[PodcastEpisode]>,
+) -> (Value, String) {
+    let episodes: Vec<Value> = episodes
+        .unwrap_or_default()
+        .iter()
+        .map(podcast_episode_to_json)
+        .collect();
+    let episodes_xml: String = episodes.iter().map(podcast_episode_xml).collect();
+    let json_data = json!({
+        "id": c.id,
+        "url": c.url,
+        "title": c.title,
+        "description": c.description,
+        "originalImageUrl": c.image_url,
+        "status": c.status,
+        "errorMessage": c.error_message,
+        "episode": episodes,
+    });
+    let xml = format!(
+        r#"<channel id="{}" url="{}" title="{}" description="{}" originalImageUrl="{}" status="{}" errorMessage="{}">{episodes_xml}</channel>"#,
+        xml_escape(&c.id),
+        xml_escape(&c.url),
+        xml_escape(&c.title),
+        xml_escape(c.description.as_deref().unwrap_or("")),
+        xml_escape(c.image_url.as_deref().unwrap_or("")),
+        xml_escape(&c.status),
+        xml_escape(c.error_message.as_deref().unwrap_or("")),
+    );
+    (json_data, xml)
+}
+
+pub async fn get_podcasts(
+    state: web::Data<SubsonicState>,
+    query: web::Query<PodcastParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let channels = match q.id.as_deref() {
+        Some(id) => match state.podcast_store.get_channel(id).await {
+            Ok(Some(channel)) => vec![channel],
+            Ok(None) => return response::respond_error(f, 70, "Podcast channel not found"),
+            Err(e) => {
+                tracing::error!("getPodcasts: {e}");
+                return response::respond_error(f, 0, "database error");
+            }
+        },
+        None => match state.podcast_store.list_channels().await {
+            Ok(channels) => channels,
+            Err(e) => {
+                tracing::error!("getPodcasts: {e}");
+                return response::respond_error(f, 0, "database error");
+            }
+        },
+    };
+    let include_episodes = q.include_episodes.unwrap_or(true);
+    let mut entries = Vec::with_capacity(channels.len());
+    let mut xml_inner = String::new();
+    for channel in &channels {
+        let episodes = if include_episodes {
+            state
+                .podcast_store
+                .list_episodes(&channel.id)
+                .await
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let (json_data, xml) =
+            podcast_channel_json_xml(channel, include_episodes.then_some(&episodes[..]));
+        entries.push(json_data);
+        xml_inner.push_str(&xml);
+    }
+    let json_data = json!({ "podcasts": { "channel": entries } });
+    let xml = format!("<podcasts>{xml_inner}</podcasts>");
+    response::respond(f, json_data, &xml)
+}
+
+pub async fn get_podcast_episode(
+    state: web::Data<SubsonicState>,
+    query: web::Query<IdParam>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let id = match q.id.as_deref() {
+        Some(id) => id,
+        None => return response::respond_error(f, 10, "Required parameter is missing: id"),
+    };
+    match state.podcast_store.get_episode(id).await {
+        Ok(Some(episode)) => {
+            let episode = podcast_episode_to_json(&episode);
+            let xml = podcast_episode_xml(&episode).replacen("<episode ", "<podcastEpisode ", 1);
+            response::respond(f, json!({ "podcastEpisode": episode }), &xml)
+        }
+        Ok(None) => response::respond_error(f, 70, "Podcast episode not found"),
+        Err(e) => {
+            tracing::error!("getPodcastEpisode: {e}");
+            response::respond_error(f, 0, "database error")
+        }
+    }
+}
+
+pub async fn get_newest_podcasts(
+    state: web::Data<SubsonicState>,
+    query: web::Query<PodcastParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let episodes = match state
+        .podcast_store
+        .newest_episodes(q.count.unwrap_or(20))
+        .await
+    {
+        Ok(episodes) => episodes,
+        Err(e) => {
+            tracing::error!("getNewestPodcasts: {e}");
+            return response::respond_error(f, 0, "database error");
+        }
+    };
+    let entries: Vec<Value> = episodes.iter().map(podcast_episode_to_json).collect();
+    let xml_inner: String = entries.iter().map(podcast_episode_xml).collect();
+    let json_data = json!({ "newestPodcasts": { "episode": entries } });
+    let xml = format!("<newestPodcasts>{xml_inner}</newestPodcasts>");
+    response::respond(f, json_data, &xml)
+}
+
+/// Feeds are refreshed in the background; the response returns at once.
+pub async fn refresh_podcasts(
+    state: web::Data<SubsonicState>,
+    query: web::Query<CommonParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let store = state.podcast_store.clone();
+    tokio::spawn(async move {
+        if let Err(e) = store.refresh_all().await {
+            tracing::error!("refreshPodcasts: {e}");
+        }
+    });
+    response::respond(f, json!({}), "")
+}
+
+pub async fn create_podcast_channel(
+    state: web::Data<SubsonicState>,
+    query: web::Query<PodcastParams>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let url = match q.url.as_deref() {
+        Some(url) if !url.trim().is_empty() => url,
+        _ => return response::respond_error(f, 10, "Required parameter is missing: url"),
+    };
+    if let Err(e) = state.podcast_store.subscribe(url, false).await {
+        tracing::error!("createPodcastChannel: {e}");
+        return response::respond_error(f, 0, &e.to_string());
+    }
+    response::respond(f, json!({}), "")
+}
+
+pub async fn delete_podcast_channel(
+    state: web::Data<SubsonicState>,
+    query: web::Query<IdParam>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let id = match q.id.as_deref() {
+        Some(id) => id,
+        None => return response::respond_error(f, 10, "Required parameter is missing: id"),
+    };
+    match state.podcast_store.delete_channel(id).await {
+        Ok(true) => response::respond(f, json!({}), ""),
+        Ok(false) => response::respond_error(f, 70, "Podcast channel not found"),
+        Err(e) => {
+            tracing::error!("deletePodcastChannel: {e}");
+            response::respond_error(f, 0, "database error")
+        }
+    }
+}
+
+/// Start downloading an episode; clients poll `getPodcasts` for the status.
+pub async fn download_podcast_episode(
+    state: web::Data<SubsonicState>,
+    query: web::Query<IdParam>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let id = match q.id.as_deref() {
+        Some(id) => id,
+        None => return response::respond_error(f, 10, "Required parameter is missing: id"),
+    };
+    match state.podcast_store.get_episode(id).await {
+        Ok(Some(_)) => {
+            state.podcast_store.spawn_download(id.to_string());
+            response::respond(f, json!({}), "")
+        }
+        Ok(None) => response::respond_error(f, 70, "Podcast episode not found"),
+        Err(e) => {
+            tracing::error!("downloadPodcastEpisode: {e}");
+            response::respond_error(f, 0, "database error")
+        }
+    }
+}
+
+/// Subsonic "deletes" an episode by removing its download.
+pub async fn delete_podcast_episode(
+    state: web::Data<SubsonicState>,
+    query: web::Query<IdParam>,
+) -> HttpResponse {
+    let q = query.into_inner();
+    let f = q.f.as_deref();
+    if let Some(r) = auth_check(
+        &state,
+        q.u.as_deref(),
+        q.p.as_deref(),
+        q.t.as_deref(),
+        q.s.as_deref(),
+        f,
+    ) {
+        return r;
+    }
+    let id = match q.id.as_deref() {
+        Some(id) => id,
+        None => return response::respond_error(f, 10, "Required parameter is missing: id"),
+    };
+    match state.podcast_store.delete_download(id).await {
+        Ok(true) => response::respond(f, json!({}), ""),
+        Ok(false) => response::respond_error(f, 70, "Podcast episode not found"),
+        Err(e) => {
+            tracing::error!("deletePodcastEpisode: {e}");
+            response::respond_error(f, 0, "database error")
+        }
+    }
+}
+
 // ── Internal helpers ──────────────────────────────────────────────────────────
 
 async fn get_playlist_by_id(state: &SubsonicState, id: &str, f: Option<&str>) -> HttpResponse {
@@ -5,6 +5,7 @@ use actix_cors::Cors;
 use actix_web::{web, App, HttpServer};
 use rockbox_library::create_connection_pool;
 use rockbox_playlists::PlaylistStore;
+use rockbox_podcasts::PodcastStore;
 use rockbox_settings::read_settings;
 use sqlx::{Pool, Sqlite};
 use std::sync::{
@@ -55,6 +56,7 @@ pub fn get_now_playing() -> Option<NowPlayingInfo> {
 pub struct SubsonicState {
     pub pool: Pool<Sqlite>,
     pub playlist_store: PlaylistStore,
+    pub podcast_store: PodcastStore,
     pub username: Arc<String>,
     pub password: Arc<String>,
     pub scan_running: Arc<AtomicBool>,
@@ -122,16 +124,18 @@ pub async fn start() -> anyhow::Result<()> {
     let pool = create_connection_pool().await?;
     let playlist_store = PlaylistStore::new(pool.clone());
     playlist_store.seed().await?;
+    let podcast_store = PodcastStore::new(pool.clone());
 
     let state = web::Data::new(SubsonicState {
         pool,
         playlist_store,
+        podcast_store,
         username: Arc::new(username),
         password: Arc::new(password),
         scan_running: Arc::new(AtomicBool::new(false)),
     });
 
-    HttpServer::new(move || {
+    let mut server = HttpServer::new(move || {
         let cors = Cors::permissive();
         App::new()
             .app_data(state.clone())
@@ -373,6 +377,14 @@ pub async fn start() -> anyhow::Result<()> {
                 "/rest/unstar{_:(\\.view)?}",
                 web::post().to(handlers::unstar),
             )
+            .route(
+                "/rest/setRating{_:(\\.view)?}",
+                web::get().to(handlers::set_rating),
+            )
+            .route(
+                "/rest/setRating{_:(\\.view)?}",
+                web::post().to(handlers::set_rating),
+            )
             // Artist / album info
             .route(
                 "/rest/getArtistInfo{_:(\\.view)?}",
@@ -408,11 +420,11 @@ pub async fn start() -> anyhow::Result<()> {
             )
             .route(
                 "/rest/getSimilarSongs{_:(\\.view)?}",
-                web::get().to(handlers::get_similar_songs2),
+                web::get().to(handlers::get_similar_songs),
             )
             .route(
                 "/rest/getSimilarSongs{_:(\\.view)?}",
-                web::post().to(handlers::get_similar_songs2),
+                web::post().to(handlers::get_similar_songs),
             )
             .route(
                 "/rest/getSimilarSongs2{_:(\\.view)?}",
@@ -438,6 +450,104 @@ pub async fn start() -> anyhow::Result<()> {
                 "/rest/getLyrics{_:(\\.view)?}",
                 web::post().to(handlers::get_lyrics),
             )
+            // Internet radio
+            .route(
+                "/rest/getInternetRadioStations{_:(\\.view)?}",
+                web::get().to(handlers::get_internet_radio_stations),
+            )
+            .route(
+                "/rest/getInternetRadioStations{_:(\\.view)?}",
+                web::post().to(handlers::get_internet_radio_stations),
+            )
+            .route(
+                "/rest/createInternetRadioStation{_:(\\.view)?}",
+                web::get().to(handlers::create_internet_radio_station),
+            )
+            .route(
+                "/rest/createInternetRadioStation{_:(\\.view)?}",
+                web::post().to(handlers::create_internet_radio_station),
+            )
+            .route(
+                "/rest/updateInternetRadioStation{_:(\\.view)?}",
+                web::get().to(handlers::update_internet_radio_station),
+            )
+            .route(
+                "/rest/updateInternetRadioStation{_:(\\.view)?}",
+                web::post().to(handlers::update_internet_radio_station),
+            )
+            .route(
+                "/rest/deleteInternetRadioStation{_:(\\.view)?}",
+                web::get().to(handlers::delete_internet_radio_station),
+            )
+            .route(
+                "/rest/deleteInternetRadioStation{_:(\\.view)?}",
+                web::post().to(handlers::delete_internet_radio_station),
+            )
+            // Podcasts
+            .route(
+                "/rest/getPodcasts{_:(\\.view)?}",
+                web::get().to(handlers::get_podcasts),
+            )
+            .route(
+                "/rest/getPodcasts{_:(\\.view)?}",
+                web::post().to(handlers::get_podcasts),
+            )
+            .route(
+                "/rest/getPodcastEpisode{_:(\\.view)?}",
+                web::get().to(handlers::get_podcast_episode),
+            )
+            .route(
+                "/rest/getPodcastEpisode{_:(\\.view)?}",
+                web::post().to(handlers::get_podcast_episode),
+            )
+            .route(
+                "/rest/getNewestPodcasts{_:(\\.view)?}",
+                web::get().to(handlers::get_newest_podcasts),
+            )
+            .route(
+                "/rest/getNewestPodcasts{_:(\\.view)?}",
+                web::post().to(handlers::get_newest_podcasts),
+            )
+            .route(
+                "/rest/refreshPodcasts{_:(\\.view)?}",
+                web::get().to(handlers::refresh_podcasts),
+            )
+            .route(
+                "/rest/refreshPodcasts{_:(\\.view)?}",
+                web::post().to(handlers::refresh_podcasts),
+            )
+            .route(
+                "/rest/createPodcastChannel{_:(\\.view)?}",
+                web::get().to(handlers::create_podcast_channel),
+            )
+            .route(
+                "/rest/createPodcastChannel{_:(\\.view)?}",
+                web::post().to(handlers::create_podcast_channel),
+            )
+            .route(
+                "/rest/deletePodcastChannel{_:(\\.view)?}",
+                web::get().to(handlers::delete_podcast_channel),
+            )
+            .route(
+                "/rest/deletePodcastChannel{_:(\\.view)?}",
+                web::post().to(handlers::delete_podcast_channel),
+            )
+            .route(
+                "/rest/downloadPodcastEpisode{_:(\\.view)?}",
+                web::get().to(handlers::download_podcast_episode),
+            )
+            .route(
+                "/rest/downloadPodcastEpisode{_:(\\.view)?}",
+                web::post().to(handlers::download_podcast_episode),
+            )
+            .route(
+                "/rest/deletePodcastEpisode{_:(\\.view)?}",
+                web::get().to(handlers::delete_podcast_episode),
+            )
+            .route(
+                "/rest/deletePodcastEpisode{_:(\\.view)?}",
+                web::post().to(handlers::delete_podcast_episode),
+            )
             // Aliases for older API versions
             .route(
                 "/rest/getAlbumList{_:(\\.view)?}",
@@ -464,9 +574,13 @@ pub async fn start() -> anyhow::Result<()> {
                 web::post().to(handlers::search2),
             )
     })
-    .bind(addr)?
-    .run()
-    .await?;
+    .bind(rockbox_tls::plain_addr(&addr))?;
+    if let Some(tls) = rockbox_tls::tls() {
+        let tls_addr = tls.addr(&addr)?;
+        tracing::info!("Subsonic API server listening on {tls_addr} (TLS)");
+        server = server.bind_rustls_0_23(tls_addr, tls.server_config())?;
+    }
+    server.run().await?;
 
     Ok(())
 }
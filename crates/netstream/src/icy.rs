@@ -0,0 +1,249 @@
+//! Shoutcast / Icecast in-band ("ICY") metadata.
+//!
+//! When a request carries `Icy-MetaData: 1`, radio servers answer with an
+//! `icy-metaint: N` header and interleave a metadata block after every N
+//! bytes of audio: one length byte (×16) followed by that many bytes of
+//! `StreamTitle='…';StreamUrl='…';` text, NUL-padded. The codec must never
+//! see those blocks, so the prefetch thread strips them before buffering
+//! and records the latest title here for the broker to pick up.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Station-level headers and the most recent `StreamTitle` of an ICY stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IcyMetadata {
+    /// `icy-name` response header.
+    pub name: Option<String>,
+    /// `icy-genre` response header.
+    pub genre: Option<String>,
+    /// `icy-br` response header, in kbps.
+    pub bitrate: Option<u32>,
+    /// Latest `StreamTitle` seen in the stream, usually "Artist - Title".
+    pub title: Option<String>,
+}
+
+impl IcyMetadata {
+    /// Split `title` into `(artist, title)` on the conventional " - "
+    /// separator. Returns `None` when the stream sends a bare title.
+    pub fn artist_and_title(&self) -> Option<(String, String)> {
+        let (artist, title) = self.title.as_deref()?.split_once(" - ")?;
+        let (artist, title) = (artist.trim(), title.trim());
+        if artist.is_empty() || title.is_empty() {
+            return None;
+        }
+        Some((artist.to_string(), title.to_string()))
+    }
+}
+
+/// The ICY metadata of a URL, and how many open streams share it.
+#[derive(Default)]
+struct Entry {
+    streams: usize,
+    metadata: Option<IcyMetadata>,
+}
+
+/// Metadata per stream URL (fragment stripped, as in `rb_net_open`), kept
+/// while any stream of that URL is open.
+static ICY_METADATA: Lazy<Mutex<HashMap<String, Entry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn key(url: &str) -> &str {
+    let url = url.trim();
+    url.split('#').next().unwrap_or(url)
+}
+
+/// Latest ICY metadata for `url`, if it is an open ICY stream.
+pub fn icy_metadata(url: &str) -> Option<IcyMetadata> {
+    ICY_METADATA
+        .lock()
+        .unwrap()
+        .get(key(url))
+        .and_then(|entry| entry.metadata.clone())
+}
+
+/// A stream of `url` was opened.
+pub(crate) fn retain(url: &str) {
+    let mut map = ICY_METADATA.lock().unwrap();
+    map.entry(key(url).to_string()).or_default().streams += 1;
+}
+
+/// A stream of `url` was closed; the metadata goes with the last one.
+pub(crate) fn release(url: &str) {
+    let mut map = ICY_METADATA.lock().unwrap();
+    if let Some(entry) = map.get_mut(key(url)) {
+        entry.streams = entry.streams.saturating_sub(1);
+        if entry.streams == 0 {
+            map.remove(key(url));
+        }
+    }
+}
+
+pub(crate) fn set_headers(url: &str, headers: IcyMetadata) {
+    if let Some(entry) = ICY_METADATA.lock().unwrap().get_mut(key(url)) {
+        entry.metadata = Some(headers);
+    }
+}
+
+pub(crate) fn set_title(url: &str, title: String) {
+    if let Some(entry) = ICY_METADATA.lock().unwrap().get_mut(key(url)) {
+        entry.metadata.get_or_insert_with(Default::default).title = Some(title);
+    }
+}
+
+/// Extract `StreamTitle` from a metadata block.
+pub fn parse_stream_title(block: &[u8]) -> Option<String> {
+    let end = block.iter().position(|&b| b == 0).unwrap_or(block.len());
+    let text = String::from_utf8_lossy(&block[..end]);
+    let start = text.find("StreamTitle='")? + "StreamTitle='".len();
+    let rest = &text[start..];
+    // Titles may contain apostrophes, so the field ends at the "';" that
+    // precedes the next key (or at the last quote of the block).
+    let value = match rest.find("';") {
+        Some(i) => &rest[..i],
+        None => rest.trim_end().trim_end_matches('\''),
+    };
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+enum State {
+    /// Audio bytes left before the next length byte.
+    Audio(usize),
+    /// Next byte is the metadata length (in 16-byte units).
+    Length,
+    /// Metadata bytes left to collect.
+    Meta(usize),
+}
+
+/// Incremental demuxer separating audio from interleaved metadata blocks.
+/// Reads can split blocks anywhere, so all state carries over between calls.
+pub(crate) struct IcyDemuxer {
+    metaint: usize,
+    state: State,
+    meta: Vec<u8>,
+}
+
+impl IcyDemuxer {
+    pub(crate) fn new(metaint: usize) -> Self {
+        IcyDemuxer {
+            metaint,
+            state: State::Audio(metaint),
+            meta: Vec::new(),
+        }
+    }
+
+    /// Append the audio part of `input` to `audio` and return the
+    /// `StreamTitle` of every metadata block completed by this chunk.
+    pub(crate) fn push(&mut self, mut input: &[u8], audio: &mut Vec<u8>) -> Vec<String> {
+        let mut titles = Vec::new();
+        while !input.is_empty() {
+            match self.state {
+                State::Audio(left) => {
+                    let n = usize::min(left, input.len());
+                    audio.extend_from_slice(&input[..n]);
+                    input = &input[n..];
+                    self.state = if left == n {
+                        State::Length
+                    } else {
+                        State::Audio(left - n)
+                    };
+                }
+                State::Length => {
+                    let len = input[0] as usize * 16;
+                    input = &input[1..];
+                    self.meta.clear();
+                    self.state = if len == 0 {
+                        State::Audio(self.metaint)
+                    } else {
+                        State::Meta(len)
+                    };
+                }
+                State::Meta(left) => {
+                    let n = usize::min(left, input.len());
+                    self.meta.extend_from_slice(&input[..n]);
+                    input = &input[n..];
+                    if left == n {
+                        if let Some(title) = parse_stream_title(&self.meta) {
+                            titles.push(title);
+                        }
+                        self.state = State::Audio(self.metaint);
+                    } else {
+                        self.state = State::Meta(left - n);
+                    }
+                }
+            }
+        }
+        titles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta_block(text: &str) -> Vec<u8> {
+        let padded = text.len().div_ceil(16) * 16;
+        let mut block = vec![(padded / 16) as u8];
+        block.extend_from_slice(text.as_bytes());
+        block.resize(padded + 1, 0);
+        block
+    }
+
+    #[test]
+    fn parses_stream_title_with_apostrophe() {
+        let block = b"StreamTitle='Guns N' Roses - Don't Cry';StreamUrl='';\0\0";
+        assert_eq!(
+            parse_stream_title(block).as_deref(),
+            Some("Guns N' Roses - Don't Cry")
+        );
+    }
+
+    #[test]
+    fn empty_stream_title_is_none() {
+        assert_eq!(parse_stream_title(b"StreamTitle='';\0\0\0"), None);
+    }
+
+    #[test]
+    fn demuxer_strips_blocks_across_chunk_boundaries() {
+        let mut stream = Vec::new();
+        stream.extend_from_slice(b"AAAA");
+        stream.extend(meta_block("StreamTitle='Artist - One';"));
+        stream.extend_from_slice(b"BBBB");
+        stream.push(0); // empty block
+        stream.extend_from_slice(b"CCCC");
+        stream.extend(meta_block("StreamTitle='Artist - Two';"));
+        stream.extend_from_slice(b"DD");
+
+        let mut demuxer = IcyDemuxer::new(4);
+        let mut audio = Vec::new();
+        let mut titles = Vec::new();
+        for chunk in stream.chunks(3) {
+            titles.extend(demuxer.push(chunk, &mut audio));
+        }
+
+        assert_eq!(audio, b"AAAABBBBCCCCDD");
+        assert_eq!(titles, vec!["Artist - One", "Artist - Two"]);
+    }
+
+    #[test]
+    fn splits_artist_and_title() {
+        let meta = IcyMetadata {
+            title: Some("Daft Punk - Around the World".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            meta.artist_and_title(),
+            Some(("Daft Punk".into(), "Around the World".into()))
+        );
+        let bare = IcyMetadata {
+            title: Some("Station jingle".into()),
+            ..Default::default()
+        };
+        assert_eq!(bare.artist_and_title(), None);
+    }
+}
@@ -1,4 +1,4 @@
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use std::collections::{HashMap, VecDeque};
 use std::ffi::CStr;
 use std::io::Read;
@@ -9,6 +9,11 @@ use std::thread;
 use std::time::Duration;
 use tracing::{debug, warn};
 
+mod icy;
+
+use icy::IcyDemuxer;
+pub use icy::{icy_metadata, parse_stream_title, IcyMetadata};
+
 /// Sentinel handle ID returned on error.
 const INVALID_HANDLE: i32 = -1;
 
@@ -76,6 +81,7 @@ impl Drop for StreamState {
         let (lock, cv) = &*self.pair;
         lock.lock().unwrap().stop = true;
         cv.notify_all();
+        icy::release(&self.url);
     }
 }
 
@@ -88,12 +94,16 @@ impl Drop for StreamState {
 ///
 /// `expected_bytes`: how many bytes this response should deliver.
 /// Pass `None` if unknown (live / chunked streams).
+///
+/// `icy`: demuxer for a response that interleaves ICY metadata.  Range
+/// reconnects never ask for metadata, so it is dropped on reconnect.
 fn reader_loop(
     pair: Arc<(Mutex<Prefetch>, Condvar)>,
     url: String,
     start_offset: u64,
     expected_bytes: Option<u64>,
     mut response: reqwest::blocking::Response,
+    mut icy: Option<IcyDemuxer>,
 ) {
     let mut buf = vec![0u8; 64 * 1024];
     let mut retries: u32 = 0;
@@ -149,7 +159,7 @@ fn reader_loop(
                         }
                     }
                     let range_header = format!("bytes={}-", resume_offset);
-                    match CLIENT.get(&url).header("Range", &range_header).send() {
+                    match get(&url).header("Range", &range_header).send() {
                         Ok(resp) if resp.status().as_u16() == 206 => {
                             warn!(
                                 "[netstream] premature TCP close confirmed; \
@@ -157,6 +167,7 @@ fn reader_loop(
                                 resume_offset
                             );
                             response = resp;
+                            icy = None;
                             retries = 0; // fresh connection — reset retry budget
                             continue;
                         }
@@ -202,13 +213,24 @@ fn reader_loop(
             }
 
             Ok(n) => {
+                let mut audio = Vec::new();
+                let chunk = match icy.as_mut() {
+                    Some(demuxer) => {
+                        for title in demuxer.push(&buf[..n], &mut audio) {
+                            debug!("[netstream] icy: StreamTitle={:?} url={}", title, url);
+                            icy::set_title(&url, title);
+                        }
+                        &audio[..]
+                    }
+                    None => &buf[..n],
+                };
                 let (lock, cv) = &*pair;
                 let mut pf = lock.lock().unwrap();
                 if pf.stop {
                     return;
                 }
-                pf.data.extend(&buf[..n]);
-                pf.bytes_written += n as u64;
+                pf.data.extend(chunk);
+                pf.bytes_written += chunk.len() as u64;
                 retries = 0;
                 cv.notify_all();
             }
@@ -245,9 +267,10 @@ fn reader_loop(
                     }
                 }
                 let range_header = format!("bytes={}-", resume_offset);
-                match CLIENT.get(&url).header("Range", &range_header).send() {
+                match get(&url).header("Range", &range_header).send() {
                     Ok(resp) if resp.status().as_u16() == 206 => {
                         response = resp;
+                        icy = None;
                     }
                     Ok(_) | Err(_) => {
                         debug!("[netstream] Range reconnect failed, will retry");
@@ -266,7 +289,7 @@ fn spawn_reader(
     expected_bytes: Option<u64>,
     response: reqwest::blocking::Response,
 ) -> thread::JoinHandle<()> {
-    thread::spawn(move || reader_loop(pair, url, start_offset, expected_bytes, response))
+    thread::spawn(move || reader_loop(pair, url, start_offset, expected_bytes, response, None))
 }
 
 impl StreamState {
@@ -284,6 +307,33 @@ impl StreamState {
         }
     }
 
+    /// Record the station headers of an ICY response and build the demuxer
+    /// for its `icy-metaint` interval.  Returns `None` for plain HTTP.
+    fn icy_demuxer(url: &str, resp: &reqwest::blocking::Response) -> Option<IcyDemuxer> {
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let metaint = header("icy-metaint")?.parse::<usize>().ok()?;
+        if metaint == 0 {
+            return None;
+        }
+        debug!("[netstream] icy: metaint={} url={}", metaint, url);
+        icy::set_headers(
+            url,
+            IcyMetadata {
+                name: header("icy-name"),
+                genre: header("icy-genre"),
+                bitrate: header("icy-br").and_then(|b| b.parse().ok()),
+                title: None,
+            },
+        );
+        Some(IcyDemuxer::new(metaint))
+    }
+
     /// Open `url` without blocking the caller.  The HTTP handshake and the
     /// prefetch download both happen on a background thread.  The handle is
     /// valid immediately; the first `read_into` call will block until data
@@ -293,12 +343,15 @@ impl StreamState {
     /// while the TCP/TLS handshake is in progress, which was causing audible
     /// blips in the currently-playing track when a new HTTP URL was queued.
     fn new(url: String) -> Self {
+        icy::retain(&url);
         let pair = Arc::new((Mutex::new(Prefetch::new()), Condvar::new()));
         let pair_bg = Arc::clone(&pair);
         let url_bg = url.clone();
 
         let t = thread::spawn(move || {
-            let response = match CLIENT.get(&url_bg).send() {
+            // Ask Shoutcast/Icecast servers for in-band metadata; plain
+            // HTTP servers ignore the header.
+            let response = match get(&url_bg).header("Icy-MetaData", "1").send() {
                 Ok(r) if r.status().is_success() => r,
                 Ok(r) => {
                     warn!("[netstream] open: HTTP {} for {}", r.status(), url_bg);
@@ -338,8 +391,10 @@ impl StreamState {
                 cv.notify_all();
             }
 
+            let icy = Self::icy_demuxer(&url_bg, &response);
+
             // Now run the prefetch loop inline on this same thread.
-            reader_loop(pair_bg, url_bg, 0, content_length, response);
+            reader_loop(pair_bg, url_bg, 0, content_length, response, icy);
         });
 
         StreamState {
@@ -575,7 +630,7 @@ impl StreamState {
             if attempt > 0 {
                 thread::sleep(Duration::from_millis(300 * attempt as u64));
             }
-            match CLIENT.get(&self.url).header("Range", &range_header).send() {
+            match get(&self.url).header("Range", &range_header).send() {
                 Ok(resp) if resp.status().as_u16() == 206 => {
                     self.update_content_length_from_content_range(&resp);
                     if Self::parse_content_range_start(&resp) != Some(new_pos) {
@@ -654,8 +709,57 @@ static CLIENT: Lazy<reqwest::blocking::Client> = Lazy::new(|| {
         .expect("failed to build global HTTP client")
 });
 
+/// Adds credentials to the URLs requested; see [`set_authenticator`].
+static AUTHENTICATOR: OnceCell<fn(&str) -> Option<String>> = OnceCell::new();
+
+/// Install the hook that adds credentials to a stream's URL each time it
+/// is requested, so the paths the firmware and the library hold can go
+/// without them. A URL the hook returns `None` for is requested as is.
+pub fn set_authenticator(authenticate: fn(&str) -> Option<String>) {
+    let _ = AUTHENTICATOR.set(authenticate);
+}
+
+/// A GET of `url`, with whatever credentials the authenticator adds.
+fn get(url: &str) -> reqwest::blocking::RequestBuilder {
+    match AUTHENTICATOR
+        .get()
+        .and_then(|authenticate| authenticate(url))
+    {
+        Some(url) => CLIENT.get(url),
+        None => CLIENT.get(url),
+    }
+}
+
 static NEXT_HANDLE: AtomicI32 = AtomicI32::new(0);
 
+/// Streams opened ahead of time by `prefetch`, waiting for `rb_net_open`.
+static WARM: Lazy<Mutex<HashMap<String, StreamState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Most streams kept warm at once; an unclaimed one is dropped, not leaked.
+const WARM_CAP: usize = 2;
+
+fn normalize_url(url: &str) -> String {
+    let s = url.trim();
+    s.split('#').next().unwrap_or(s).to_owned()
+}
+
+/// Start opening and buffering `url` before anyone asks for it, so that the
+/// next track's handshake and first bytes are ready when playback reaches it
+/// and the firmware's gapless / crossfade join isn't starved. The next
+/// `rb_net_open` of the same URL takes the warm stream over.
+pub fn prefetch(url: &str) {
+    let url = normalize_url(url);
+    let mut warm = WARM.lock().unwrap();
+    if warm.contains_key(&url) {
+        return;
+    }
+    if warm.len() >= WARM_CAP {
+        warm.clear();
+    }
+    debug!("[netstream] prefetch: url={}", url);
+    warm.insert(url.clone(), StreamState::new(url));
+}
+
 // ------------------------------------------------------------------
 // Public C ABI
 // ------------------------------------------------------------------
@@ -678,16 +782,13 @@ pub unsafe extern "C" fn rb_net_open(url: *const c_char) -> i32 {
         return INVALID_HANDLE;
     }
     let url_str = match CStr::from_ptr(url).to_str() {
-        Ok(s) => {
-            let s = s.trim();
-            let s = s.split('#').next().unwrap_or(s);
-            s.to_owned()
-        }
+        Ok(s) => normalize_url(s),
         Err(_) => return INVALID_HANDLE,
     };
 
     debug!("[netstream] rb_net_open: url={}", url_str);
-    let state = StreamState::new(url_str.clone());
+    let warm = WARM.lock().unwrap().remove(&url_str);
+    let state = warm.unwrap_or_else(|| StreamState::new(url_str.clone()));
     let handle = NEXT_HANDLE.fetch_add(1, Ordering::SeqCst);
     STREAMS
         .lock()
@@ -899,6 +1000,8 @@ pub unsafe extern "C" fn rb_net_content_type(h: i32, dst: *mut c_char, n: libc::
 #[no_mangle]
 pub extern "C" fn rb_net_close(h: i32) {
     debug!("[netstream] rb_net_close: h={}", h);
+    // Dropping the stream releases its ICY metadata, which stays for
+    // other handles still open on the same URL.
     STREAMS.lock().unwrap().remove(&h);
 }
 
@@ -1112,6 +1215,31 @@ mod tests {
         rb_net_close(handle);
     }
 
+    #[test]
+    fn test_open_takes_over_prefetched_stream() {
+        let body: &[u8] = b"next track";
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/next.mp3")
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create();
+
+        let url = c_url(&server, "/next.mp3");
+        prefetch(url.to_str().unwrap());
+        prefetch(url.to_str().unwrap());
+        let handle = unsafe { rb_net_open(url.as_ptr()) };
+        assert!(handle >= 0);
+
+        let mut buf = vec![0u8; 64];
+        let n = unsafe { rb_net_read(handle, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        assert_eq!(&buf[..n as usize], body);
+        mock.assert();
+
+        rb_net_close(handle);
+    }
+
     #[test]
     fn test_read_invalid_handle() {
         let mut buf = vec![0u8; 16];
@@ -1399,4 +1527,63 @@ mod tests {
 
         rb_net_close(handle);
     }
+
+    // ------------------------------------------------------------------
+    // ICY metadata
+    // ------------------------------------------------------------------
+
+    /// Metadata blocks are stripped from the audio and their title is
+    /// published for the stream URL until its last handle is closed.
+    #[test]
+    fn test_icy_metadata_is_stripped() {
+        let title = b"StreamTitle='Artist - Song';";
+        let mut block = vec![2u8];
+        block.extend_from_slice(title);
+        block.resize(33, 0);
+
+        let mut body = b"AAAAAAAA".to_vec();
+        body.extend_from_slice(&block);
+        body.extend_from_slice(b"BBBBBBBB");
+        body.push(0);
+
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", "/live")
+            .match_header("icy-metadata", "1")
+            .with_status(200)
+            .with_header("icy-metaint", "8")
+            .with_header("icy-name", "Test FM")
+            .with_body(body)
+            .create();
+
+        let url = c_url(&server, "/live");
+        let handle = unsafe { rb_net_open(url.as_ptr()) };
+        assert!(handle >= 0);
+
+        let mut audio = Vec::new();
+        let mut buf = vec![0u8; 64];
+        loop {
+            let n =
+                unsafe { rb_net_read(handle, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+            assert!(n >= 0, "read should not fail");
+            if n == 0 {
+                break;
+            }
+            audio.extend_from_slice(&buf[..n as usize]);
+        }
+        assert_eq!(audio, b"AAAAAAAABBBBBBBB");
+
+        let stream_url = url.to_str().unwrap();
+        let meta = icy_metadata(stream_url).expect("icy metadata for open stream");
+        assert_eq!(meta.name.as_deref(), Some("Test FM"));
+        assert_eq!(meta.title.as_deref(), Some("Artist - Song"));
+
+        // Closing one of two handles on the URL keeps the metadata.
+        let other = unsafe { rb_net_open(url.as_ptr()) };
+        rb_net_close(handle);
+        assert!(icy_metadata(stream_url).is_some());
+
+        rb_net_close(other);
+        assert_eq!(icy_metadata(stream_url), None);
+    }
 }
@@ -0,0 +1,568 @@
+//! Playlist files: M3U/M3U8 (with `#EXTINF`), PLS, XSPF and JSPF.
+//!
+//! Entries carry locations as plain paths (or http URLs) whatever the
+//! format; the XSPF and JSPF readers and writers convert to and from the
+//! URIs those formats use.
+
+use std::{fmt, path::Path, str::FromStr};
+
+use anyhow::{anyhow, Error, Result};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use quick_xml::{escape::escape, events::Event, Reader};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    M3u,
+    Pls,
+    Xspf,
+    Jspf,
+}
+
+impl Format {
+    /// The format of a file named `path`, from its extension.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        path.extension()?.to_str()?.to_lowercase().parse().ok()
+    }
+
+    /// The format of an upload without a name, from its first characters.
+    pub fn sniff(content: &str) -> Format {
+        let start = content.trim_start_matches('\u{feff}').trim_start();
+        if start.starts_with('<') {
+            Format::Xspf
+        } else if start.starts_with('{') {
+            Format::Jspf
+        } else if start.to_lowercase().starts_with("[playlist]") {
+            Format::Pls
+        } else {
+            Format::M3u
+        }
+    }
+
+    /// Extension of exported files; M3U is always written as UTF-8.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::M3u => "m3u8",
+            Format::Pls => "pls",
+            Format::Xspf => "xspf",
+            Format::Jspf => "jspf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::M3u => "audio/x-mpegurl; charset=utf-8",
+            Format::Pls => "audio/x-scpls",
+            Format::Xspf => "application/xspf+xml",
+            Format::Jspf => "application/json",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::M3u => "m3u",
+            Format::Pls => "pls",
+            Format::Xspf => "xspf",
+            Format::Jspf => "jspf",
+        })
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "m3u" | "m3u8" => Ok(Format::M3u),
+            "pls" => Ok(Format::Pls),
+            "xspf" => Ok(Format::Xspf),
+            "jspf" => Ok(Format::Jspf),
+            _ => Err(anyhow!(
+                "unknown playlist format: {} (expected m3u, m3u8, pls, xspf or jspf)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Path, relative or absolute, or URL.
+    pub location: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistFile {
+    pub title: Option<String>,
+    pub entries: Vec<Entry>,
+}
+
+pub fn parse(format: Format, content: &str) -> Result<PlaylistFile> {
+    let content = content.trim_start_matches('\u{feff}');
+    match format {
+        Format::M3u => Ok(parse_m3u(content)),
+        Format::Pls => Ok(parse_pls(content)),
+        Format::Xspf => parse_xspf(content),
+        Format::Jspf => parse_jspf(content),
+    }
+}
+
+pub fn write(format: Format, playlist: &PlaylistFile) -> Result<String> {
+    match format {
+        Format::M3u => Ok(write_m3u(playlist)),
+        Format::Pls => Ok(write_pls(playlist)),
+        Format::Xspf => Ok(write_xspf(playlist)),
+        Format::Jspf => write_jspf(playlist),
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
+
+/// Split an `Artist - Title` display name; without a separator it is all
+/// title.
+fn split_display(s: &str) -> (Option<String>, Option<String>) {
+    match s.split_once(" - ") {
+        Some((artist, title)) => (non_empty(artist), non_empty(title)),
+        None => (None, non_empty(s)),
+    }
+}
+
+fn display(entry: &Entry) -> Option<String> {
+    match (&entry.artist, &entry.title) {
+        (Some(artist), Some(title)) => Some(format!("{} - {}", artist, title)),
+        (None, Some(title)) => Some(title.clone()),
+        _ => None,
+    }
+}
+
+/// Whole seconds, or -1 when unknown as M3U and PLS write it.
+fn seconds(duration_ms: Option<i64>) -> i64 {
+    duration_ms.map(|ms| (ms + 500) / 1000).unwrap_or(-1)
+}
+
+fn from_seconds(s: &str) -> Option<i64> {
+    let secs: f64 = s.trim().parse().ok()?;
+    (secs >= 0.0).then_some((secs * 1000.0) as i64)
+}
+
+fn parse_m3u(content: &str) -> PlaylistFile {
+    let mut playlist = PlaylistFile::default();
+    let mut next = Entry::default();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:<seconds>[ key="value"...],<display name>
+            let (head, name) = info.split_once(',').unwrap_or((info, ""));
+            let secs = head.split_whitespace().next().unwrap_or_default();
+            next.duration_ms = from_seconds(secs);
+            let (artist, title) = split_display(name);
+            next.artist = artist;
+            next.title = title;
+        } else if let Some(title) = line.strip_prefix("#PLAYLIST:") {
+            playlist.title = non_empty(title);
+        } else if let Some(album) = line.strip_prefix("#EXTALB:") {
+            next.album = non_empty(album);
+        } else if let Some(artist) = line.strip_prefix("#EXTART:") {
+            next.artist = non_empty(artist);
+        } else if !line.starts_with('#') {
+            next.location = line.to_string();
+            playlist.entries.push(std::mem::take(&mut next));
+        }
+    }
+    playlist
+}
+
+fn write_m3u(playlist: &PlaylistFile) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    if let Some(title) = &playlist.title {
+        out.push_str(&format!("#PLAYLIST:{}\n", title));
+    }
+    for entry in &playlist.entries {
+        if let Some(name) = display(entry) {
+            out.push_str(&format!(
+                "#EXTINF:{},{}\n",
+                seconds(entry.duration_ms),
+                name
+            ));
+        }
+        if let Some(album) = &entry.album {
+            out.push_str(&format!("#EXTALB:{}\n", album));
+        }
+        out.push_str(&entry.location);
+        out.push('\n');
+    }
+    out
+}
+
+fn parse_pls(content: &str) -> PlaylistFile {
+    // FileN / TitleN / LengthN keys, in any order.
+    let mut entries: Vec<(u32, Entry)> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let (field, number) =
+            key.split_at(key.find(|c: char| c.is_ascii_digit()).unwrap_or(key.len()));
+        let Ok(number) = number.parse::<u32>() else {
+            continue;
+        };
+        let i = match entries.iter().position(|(n, _)| *n == number) {
+            Some(i) => i,
+            None => {
+                entries.push((number, Entry::default()));
+                entries.len() - 1
+            }
+        };
+        let entry = &mut entries[i].1;
+        match field {
+            "file" => entry.location = value.trim().to_string(),
+            "title" => (entry.artist, entry.title) = split_display(value),
+            "length" => entry.duration_ms = from_seconds(value),
+            _ => {}
+        }
+    }
+    entries.sort_by_key(|(n, _)| *n);
+    PlaylistFile {
+        title: None,
+        entries: entries
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .filter(|entry| !entry.location.is_empty())
+            .collect(),
+    }
+}
+
+fn write_pls(playlist: &PlaylistFile) -> String {
+    let mut out = String::from("[playlist]\n");
+    for (i, entry) in playlist.entries.iter().enumerate() {
+        let n = i + 1;
+        out.push_str(&format!("File{}={}\n", n, entry.location));
+        if let Some(name) = display(entry) {
+            out.push_str(&format!("Title{}={}\n", n, name));
+        }
+        out.push_str(&format!("Length{}={}\n", n, seconds(entry.duration_ms)));
+    }
+    out.push_str(&format!(
+        "NumberOfEntries={}\nVersion=2\n",
+        playlist.entries.len()
+    ));
+    out
+}
+
+/// Characters escaped in relative URIs: everything but unreserved
+/// characters and `/`.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// The path or URL an XSPF/JSPF location URI stands for.
+fn location_from_uri(uri: &str) -> String {
+    let uri = uri.trim();
+    if uri.starts_with("file:") {
+        if let Some(path) = Url::parse(uri).ok().and_then(|u| u.to_file_path().ok()) {
+            return path.to_string_lossy().into_owned();
+        }
+    }
+    if uri.contains("://") {
+        return uri.to_string();
+    }
+    percent_decode_str(uri).decode_utf8_lossy().into_owned()
+}
+
+fn location_to_uri(location: &str) -> String {
+    if location.contains("://") {
+        return location.to_string();
+    }
+    if location.starts_with('/') {
+        if let Ok(url) = Url::from_file_path(location) {
+            return url.to_string();
+        }
+    }
+    utf8_percent_encode(location, PATH_SEGMENT).to_string()
+}
+
+fn parse_xspf(content: &str) -> Result<PlaylistFile> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut playlist = PlaylistFile::default();
+    let mut entry = Entry::default();
+    let mut is_xspf = false;
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut text = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = e.name().as_ref().to_vec();
+                match name.as_slice() {
+                    b"playlist" => is_xspf = true,
+                    b"track" => entry = Entry::default(),
+                    _ => {}
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Text(e) => text.push_str(&e.unescape()?),
+            Event::CData(e) => text.push_str(&String::from_utf8_lossy(&e)),
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(|p| p.as_slice()).unwrap_or_default();
+                let value = std::mem::take(&mut text);
+                match (parent, name.as_slice()) {
+                    (b"trackList", b"track") => {
+                        let done = std::mem::take(&mut entry);
+                        if !done.location.is_empty() {
+                            playlist.entries.push(done);
+                        }
+                    }
+                    // The first location is the one to play.
+                    (b"track", b"location") if entry.location.is_empty() => {
+                        entry.location = location_from_uri(&value)
+                    }
+                    (b"track", b"title") => entry.title = non_empty(&value),
+                    (b"track", b"creator") => entry.artist = non_empty(&value),
+                    (b"track", b"album") => entry.album = non_empty(&value),
+                    (b"track", b"duration") => entry.duration_ms = value.trim().parse().ok(),
+                    (b"playlist", b"title") => playlist.title = non_empty(&value),
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !is_xspf {
+        return Err(anyhow!("not an XSPF playlist"));
+    }
+    Ok(playlist)
+}
+
+fn write_xspf(playlist: &PlaylistFile) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n",
+    );
+    if let Some(title) = &playlist.title {
+        out.push_str(&format!("  <title>{}</title>\n", escape(title)));
+    }
+    out.push_str("  <trackList>\n");
+    for entry in &playlist.entries {
+        out.push_str("    <track>\n");
+        out.push_str(&format!(
+            "      <location>{}</location>\n",
+            escape(location_to_uri(&entry.location))
+        ));
+        for (tag, value) in [
+            ("title", &entry.title),
+            ("creator", &entry.artist),
+            ("album", &entry.album),
+        ] {
+            if let Some(value) = value {
+                out.push_str(&format!("      <{tag}>{}</{tag}>\n", escape(value)));
+            }
+        }
+        if let Some(duration) = entry.duration_ms {
+            out.push_str(&format!("      <duration>{}</duration>\n", duration));
+        }
+        out.push_str("    </track>\n");
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+    out
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Jspf {
+    playlist: JspfPlaylist,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct JspfPlaylist {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default)]
+    track: Vec<JspfTrack>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct JspfTrack {
+    #[serde(default)]
+    location: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    creator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    album: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration: Option<i64>,
+}
+
+fn parse_jspf(content: &str) -> Result<PlaylistFile> {
+    let jspf: Jspf = serde_json::from_str(content)?;
+    Ok(PlaylistFile {
+        title: jspf.playlist.title,
+        entries: jspf
+            .playlist
+            .track
+            .into_iter()
+            .filter_map(|track| {
+                Some(Entry {
+                    location: location_from_uri(track.location.first()?),
+                    title: track.title,
+                    artist: track.creator,
+                    album: track.album,
+                    duration_ms: track.duration,
+                })
+            })
+            .collect(),
+    })
+}
+
+fn write_jspf(playlist: &PlaylistFile) -> Result<String> {
+    let jspf = Jspf {
+        playlist: JspfPlaylist {
+            title: playlist.title.clone(),
+            track: playlist
+                .entries
+                .iter()
+                .map(|entry| JspfTrack {
+                    location: vec![location_to_uri(&entry.location)],
+                    title: entry.title.clone(),
+                    creator: entry.artist.clone(),
+                    album: entry.album.clone(),
+                    duration: entry.duration_ms,
+                })
+                .collect(),
+        },
+    };
+    Ok(serde_json::to_string_pretty(&jspf)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PlaylistFile {
+        PlaylistFile {
+            title: Some("Road trip".to_string()),
+            entries: vec![
+                Entry {
+                    location: "/music/Björk/Post/01 Army of Me.flac".to_string(),
+                    title: Some("Army of Me".to_string()),
+                    artist: Some("Björk".to_string()),
+                    album: Some("Post".to_string()),
+                    duration_ms: Some(234_000),
+                },
+                Entry {
+                    location: "Other/a & b.mp3".to_string(),
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        for format in [Format::M3u, Format::Xspf, Format::Jspf] {
+            let written = write(format, &sample()).unwrap();
+            assert_eq!(parse(format, &written).unwrap(), sample(), "{}", format);
+        }
+        // PLS has no playlist title or album.
+        let pls = parse(Format::Pls, &write(Format::Pls, &sample()).unwrap()).unwrap();
+        assert_eq!(pls.title, None);
+        assert_eq!(pls.entries[0].title.as_deref(), Some("Army of Me"));
+        assert_eq!(pls.entries[1].location, "Other/a & b.mp3");
+    }
+
+    #[test]
+    fn m3u_extinf() {
+        let playlist = parse(
+            Format::M3u,
+            "\u{feff}#EXTM3U\r\n\
+             #EXTINF:-1 tvg-id=\"x\",Just a title\r\n\
+             song.mp3\r\n\
+             \r\n\
+             # a comment\r\n\
+             #EXTINF:61.5,Nina Simone - Sinnerman\r\n\
+             C:\\Music\\sinnerman.mp3\r\n\
+             http://radio.example/stream\r\n",
+        )
+        .unwrap();
+        assert_eq!(playlist.entries.len(), 3);
+        assert_eq!(playlist.entries[0].title.as_deref(), Some("Just a title"));
+        assert_eq!(playlist.entries[0].duration_ms, None);
+        assert_eq!(playlist.entries[1].artist.as_deref(), Some("Nina Simone"));
+        assert_eq!(playlist.entries[1].duration_ms, Some(61_500));
+        assert_eq!(playlist.entries[1].location, "C:\\Music\\sinnerman.mp3");
+        assert_eq!(playlist.entries[2].title, None);
+    }
+
+    #[test]
+    fn pls_entries_follow_their_numbers() {
+        let playlist = parse(
+            Format::Pls,
+            "[playlist]\nFile2=b.mp3\nTitle1=A\nFile1=a.mp3\nLength2=-1\nNumberOfEntries=2\n",
+        )
+        .unwrap();
+        let locations: Vec<&str> = playlist
+            .entries
+            .iter()
+            .map(|e| e.location.as_str())
+            .collect();
+        assert_eq!(locations, ["a.mp3", "b.mp3"]);
+        assert_eq!(playlist.entries[0].title.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn xspf_locations_are_uris() {
+        let written = write(Format::Xspf, &sample()).unwrap();
+        assert!(written.contains("file:///music/Bj%C3%B6rk/Post/01%20Army%20of%20Me.flac"));
+        assert!(written.contains("<location>Other/a%20&amp;%20b.mp3</location>"));
+        assert!(parse(Format::Xspf, "<rss></rss>").is_err());
+    }
+
+    #[test]
+    fn formats_from_paths() {
+        assert_eq!(Format::from_path(Path::new("a/b.M3U8")), Some(Format::M3u));
+        assert_eq!(Format::from_path(Path::new("b.xspf")), Some(Format::Xspf));
+        assert_eq!(Format::from_path(Path::new("b.txt")), None);
+        assert_eq!(
+            Format::sniff("\u{feff}[Playlist]\nFile1=a.mp3"),
+            Format::Pls
+        );
+        assert_eq!(Format::sniff("  <?xml version=\"1.0\"?>"), Format::Xspf);
+        assert_eq!(Format::sniff("{\"playlist\": {}}"), Format::Jspf);
+        assert_eq!(Format::sniff("#EXTM3U"), Format::M3u);
+    }
+}
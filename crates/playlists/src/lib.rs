@@ -1,13 +1,21 @@
+pub mod formats;
+pub mod live;
 pub mod resolver;
 pub mod rules;
+pub mod sync;
+pub mod transfer;
 
 use anyhow::{anyhow, Result};
 use chrono::Utc;
 use rules::RuleCriteria;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite};
+use tracing::warn;
 use uuid::Uuid;
 
+pub use live::{start_live_task, SmartPlaylistDelta};
+pub use sync::start_sync_task;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaylistFolder {
     pub id: String,
@@ -281,7 +289,7 @@ impl PlaylistStore {
             .execute(&self.pool)
             .await?;
         }
-        Ok(())
+        self.touch(playlist_id).await
     }
 
     pub async fn remove_track(&self, playlist_id: &str, track_id: &str) -> Result<bool> {
@@ -291,9 +299,29 @@ impl PlaylistStore {
                 .bind(track_id)
                 .execute(&self.pool)
                 .await?;
+        self.touch(playlist_id).await?;
         Ok(result.rows_affected() > 0)
     }
 
+    /// Replace every track of a playlist, keeping the order of `track_ids`.
+    pub async fn replace_tracks(&self, playlist_id: &str, track_ids: &[String]) -> Result<()> {
+        sqlx::query("DELETE FROM saved_playlist_tracks WHERE playlist_id = ?")
+            .bind(playlist_id)
+            .execute(&self.pool)
+            .await?;
+        self.add_tracks(playlist_id, track_ids).await
+    }
+
+    /// Mark a playlist as changed, so synced playlist files are rewritten.
+    async fn touch(&self, playlist_id: &str) -> Result<()> {
+        sqlx::query("UPDATE saved_playlists SET updated_at = ? WHERE id = ?")
+            .bind(Utc::now().timestamp())
+            .bind(playlist_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn get_track_ids(&self, playlist_id: &str) -> Result<Vec<String>> {
         let rows = sqlx::query(
             "SELECT track_id FROM saved_playlist_tracks WHERE playlist_id = ? ORDER BY position ASC",
@@ -515,7 +543,7 @@ impl PlaylistStore {
         .bind(now)
         .execute(&self.pool)
         .await?;
-        Ok(SmartPlaylist {
+        let playlist = SmartPlaylist {
             id,
             name: name.to_string(),
             description: description.map(|s| s.to_string()),
@@ -525,7 +553,11 @@ impl PlaylistStore {
             rules: rules.clone(),
             created_at: now,
             updated_at: now,
-        })
+        };
+        if let Err(e) = self.refresh_smart_playlist(&playlist).await {
+            warn!("smart playlist {}: refresh failed: {}", playlist.id, e);
+        }
+        Ok(playlist)
     }
 
     pub async fn update_smart_playlist(
@@ -556,6 +588,11 @@ impl PlaylistStore {
         if result.rows_affected() == 0 {
             return Err(anyhow!("Smart playlist not found or is a system playlist"));
         }
+        if let Some(playlist) = self.get_smart_playlist(id).await? {
+            if let Err(e) = self.refresh_smart_playlist(&playlist).await {
+                warn!("smart playlist {}: refresh failed: {}", id, e);
+            }
+        }
         Ok(())
     }
 
@@ -564,7 +601,11 @@ impl PlaylistStore {
             .bind(id)
             .execute(&self.pool)
             .await?;
-        Ok(result.rows_affected() > 0)
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        self.clear_smart_playlist_members(id).await?;
+        Ok(true)
     }
 
     // ── Track stats ────────────────────────────────────────────────────────
@@ -584,6 +625,7 @@ impl PlaylistStore {
         .bind(now)
         .execute(&self.pool)
         .await?;
+        rockbox_library::changes::track_changed(track_id);
         Ok(())
     }
 
@@ -602,6 +644,7 @@ impl PlaylistStore {
         .bind(now)
         .execute(&self.pool)
         .await?;
+        rockbox_library::changes::track_changed(track_id);
         Ok(())
     }
 
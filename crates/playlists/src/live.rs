@@ -0,0 +1,402 @@
+//! Live smart playlists.
+//!
+//! The tracks each smart playlist holds are kept in `smart_playlist_tracks`
+//! and re-evaluated as plays, skips, likes and library changes come in on
+//! the library change feed. Every difference is published as a
+//! [`SmartPlaylistDelta`] so clients can update a view without re-fetching
+//! it.
+
+use std::{collections::HashSet, sync::OnceLock, time::Duration};
+
+use anyhow::Result;
+use chrono::Utc;
+use rockbox_library::changes::{self, Change};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time::Interval,
+};
+use tracing::error;
+
+use crate::{
+    resolver,
+    rules::{RuleCriteria, SortField},
+    PlaylistStore, SmartPlaylist,
+};
+
+/// Deltas buffered per subscriber before the slowest one starts losing them.
+const CAPACITY: usize = 256;
+
+/// Changes arriving this close together are evaluated in one pass.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Past this many changed tracks a pass re-resolves every playlist instead.
+const MAX_INCREMENTAL: usize = 500;
+
+/// Tracks that joined or left a smart playlist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartPlaylistDelta {
+    pub playlist_id: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SmartPlaylistDelta {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn bus() -> &'static broadcast::Sender<SmartPlaylistDelta> {
+    static BUS: OnceLock<broadcast::Sender<SmartPlaylistDelta>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(CAPACITY).0)
+}
+
+pub fn subscribe() -> broadcast::Receiver<SmartPlaylistDelta> {
+    bus().subscribe()
+}
+
+/// Whether a track's place in the sort order, not just the rules, decides
+/// if it is in the playlist.
+fn is_ranked(rules: &RuleCriteria) -> bool {
+    rules.limit.is_some() || rules.has_total_limit()
+}
+
+/// Random picks are left alone until they stop matching, so a "50 random
+/// tracks" playlist doesn't reshuffle on every play.
+fn is_random_pick(rules: &RuleCriteria) -> bool {
+    is_ranked(rules) && matches!(rules.sort_by, Some(SortField::Random))
+}
+
+impl PlaylistStore {
+    /// Ids of the tracks a smart playlist held after its last evaluation.
+    pub async fn smart_playlist_members(&self, id: &str) -> Result<HashSet<String>> {
+        let rows =
+            sqlx::query("SELECT track_id FROM smart_playlist_tracks WHERE smart_playlist_id = ?")
+                .bind(id)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(rows.into_iter().map(|r| r.get(0)).collect())
+    }
+
+    /// Resolve a smart playlist from scratch and store the difference.
+    pub async fn refresh_smart_playlist(
+        &self,
+        playlist: &SmartPlaylist,
+    ) -> Result<SmartPlaylistDelta> {
+        let members = self.smart_playlist_members(&playlist.id).await?;
+        if is_random_pick(&playlist.rules) && !members.is_empty() {
+            let members: Vec<String> = members.into_iter().collect();
+            return self.prune(playlist, &members).await;
+        }
+        let tracks = resolver::resolve_tracks(&self.pool, &playlist.rules).await?;
+        let resolved: HashSet<&str> = tracks.iter().map(|t| t.id.as_str()).collect();
+        let delta = SmartPlaylistDelta {
+            playlist_id: playlist.id.clone(),
+            added: tracks
+                .iter()
+                .filter(|t| !members.contains(&t.id))
+                .map(|t| t.id.clone())
+                .collect(),
+            removed: members
+                .iter()
+                .filter(|id| !resolved.contains(id.as_str()))
+                .cloned()
+                .collect(),
+        };
+        self.apply_delta(&delta).await?;
+        Ok(delta)
+    }
+
+    /// Re-resolve every smart playlist.
+    pub async fn refresh_smart_playlists(&self) -> Result<()> {
+        for playlist in self.list_smart_playlists().await? {
+            self.refresh_smart_playlist(&playlist).await?;
+        }
+        Ok(())
+    }
+
+    /// Re-evaluate the given tracks against every smart playlist. Only
+    /// playlists cut down by a limit are resolved again in full, since one
+    /// track moving can push another one out.
+    pub async fn refresh_smart_playlist_tracks(&self, track_ids: &[String]) -> Result<()> {
+        for playlist in self.list_smart_playlists().await? {
+            if is_random_pick(&playlist.rules) {
+                let members = self.smart_playlist_members(&playlist.id).await?;
+                let touched: Vec<String> = track_ids
+                    .iter()
+                    .filter(|id| members.contains(*id))
+                    .cloned()
+                    .collect();
+                self.prune(&playlist, &touched).await?;
+            } else if is_ranked(&playlist.rules) {
+                self.refresh_smart_playlist(&playlist).await?;
+            } else {
+                let members = self.smart_playlist_members(&playlist.id).await?;
+                let matching =
+                    resolver::matching_ids(&self.pool, &playlist.rules, track_ids).await?;
+                let mut seen = HashSet::new();
+                let mut delta = SmartPlaylistDelta {
+                    playlist_id: playlist.id.clone(),
+                    added: vec![],
+                    removed: vec![],
+                };
+                for id in track_ids.iter().filter(|id| seen.insert(id.as_str())) {
+                    match (members.contains(id), matching.contains(id)) {
+                        (false, true) => delta.added.push(id.clone()),
+                        (true, false) => delta.removed.push(id.clone()),
+                        _ => {}
+                    }
+                }
+                self.apply_delta(&delta).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the tracks among `members` that no longer match the rules.
+    async fn prune(
+        &self,
+        playlist: &SmartPlaylist,
+        members: &[String],
+    ) -> Result<SmartPlaylistDelta> {
+        let matching = resolver::matching_ids(&self.pool, &playlist.rules, members).await?;
+        let delta = SmartPlaylistDelta {
+            playlist_id: playlist.id.clone(),
+            added: vec![],
+            removed: members
+                .iter()
+                .filter(|id| !matching.contains(*id))
+                .cloned()
+                .collect(),
+        };
+        self.apply_delta(&delta).await?;
+        Ok(delta)
+    }
+
+    /// Forget every track of a deleted smart playlist.
+    pub(crate) async fn clear_smart_playlist_members(&self, id: &str) -> Result<()> {
+        let delta = SmartPlaylistDelta {
+            playlist_id: id.to_string(),
+            added: vec![],
+            removed: self.smart_playlist_members(id).await?.into_iter().collect(),
+        };
+        self.apply_delta(&delta).await
+    }
+
+    /// Store a delta and publish it when it changes anything.
+    async fn apply_delta(&self, delta: &SmartPlaylistDelta) -> Result<()> {
+        if delta.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now().timestamp();
+        let mut tx = self.pool.begin().await?;
+        for id in &delta.added {
+            sqlx::query(
+                "INSERT OR IGNORE INTO smart_playlist_tracks (smart_playlist_id, track_id, added_at)
+                 VALUES (?, ?, ?)",
+            )
+            .bind(&delta.playlist_id)
+            .bind(id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for id in &delta.removed {
+            sqlx::query(
+                "DELETE FROM smart_playlist_tracks WHERE smart_playlist_id = ? AND track_id = ?",
+            )
+            .bind(&delta.playlist_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        let _ = bus().send(delta.clone());
+        Ok(())
+    }
+}
+
+/// What a batch of changes asks to re-evaluate; `None` is everything.
+type Pending = Option<Vec<String>>;
+
+fn add_change(pending: &mut Pending, change: Result<Change, RecvError>) {
+    if let (Some(ids), Ok(Change::Track(id))) = (pending.as_mut(), change) {
+        if ids.len() < MAX_INCREMENTAL {
+            ids.push(id);
+            return;
+        }
+    }
+    *pending = None;
+}
+
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Resolve every smart playlist once, then keep them current from the
+/// library change feed. Time windows such as "added in the last 30 days"
+/// drift without any change, so they are also re-resolved every
+/// `ROCKBOX_SMART_PLAYLIST_REFRESH_SECS` seconds (default 3600, 0 disables).
+pub fn start_live_task(store: PlaylistStore) {
+    let secs: u64 = std::env::var("ROCKBOX_SMART_PLAYLIST_REFRESH_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600);
+    tokio::spawn(async move {
+        let mut changes = changes::subscribe();
+        if let Err(e) = store.refresh_smart_playlists().await {
+            error!("smart playlists: refresh failed: {}", e);
+        }
+        let mut interval = (secs > 0).then(|| {
+            let period = Duration::from_secs(secs);
+            tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+        });
+        loop {
+            let mut pending: Pending = Some(vec![]);
+            tokio::select! {
+                _ = tick(&mut interval) => pending = None,
+                change = changes.recv() => {
+                    add_change(&mut pending, change);
+                    let debounce = tokio::time::sleep(DEBOUNCE);
+                    tokio::pin!(debounce);
+                    loop {
+                        tokio::select! {
+                            _ = &mut debounce => break,
+                            change = changes.recv() => add_change(&mut pending, change),
+                        }
+                    }
+                }
+            }
+            let result = match pending {
+                Some(ids) => store.refresh_smart_playlist_tracks(&ids).await,
+                None => store.refresh_smart_playlists().await,
+            };
+            if let Err(e) = result {
+                error!("smart playlists: refresh failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rockbox_library::{entity::track::Track, repo};
+    use serde_json::json;
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    async fn store() -> PlaylistStore {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for migration in [
+            include_str!("../../library/migrations/20240923093823_create_tables.sql"),
+            include_str!("../../library/migrations/20241020125757_add-album_id-column.sql"),
+            include_str!("../../library/migrations/20260425000000_add_playlist_tables.sql"),
+            include_str!("../../library/migrations/20260428000000_add_is_remote_to_track.sql"),
+            include_str!("../../library/migrations/20261019000200_add_track_media_type.sql"),
+            include_str!("../../library/migrations/20261019000900_add_smart_playlist_tracks.sql"),
+            include_str!("../../library/migrations/20261019001000_add_ratings.sql"),
+            include_str!("../../library/migrations/20261019001100_add_track_hidden.sql"),
+            include_str!("../../library/migrations/20261019001200_add_remote_sources.sql"),
+        ] {
+            pool.execute(migration).await.unwrap();
+        }
+        for id in ["a", "b", "c"] {
+            repo::track::save(
+                pool.clone(),
+                Track {
+                    id: id.to_string(),
+                    path: format!("/music/{}.flac", id),
+                    title: id.to_string(),
+                    md5: id.to_string(),
+                    created_at: Utc::now(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+        PlaylistStore::new(pool)
+    }
+
+    fn sorted(ids: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut ids: Vec<String> = ids.into_iter().collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn plays_move_tracks_out_of_never_played() {
+        let store = store().await;
+        let rules: RuleCriteria = serde_json::from_value(json!({
+            "conditions": [{"field":"play_count","operator":"equals","value":0}]
+        }))
+        .unwrap();
+        let playlist = store
+            .create_smart_playlist("Never played", None, None, None, &rules)
+            .await
+            .unwrap();
+        assert_eq!(
+            sorted(store.smart_playlist_members(&playlist.id).await.unwrap()),
+            ["a", "b", "c"]
+        );
+
+        let mut deltas = subscribe();
+        store.record_play("b").await.unwrap();
+        store
+            .refresh_smart_playlist_tracks(&["b".to_string(), "c".to_string()])
+            .await
+            .unwrap();
+        let delta = loop {
+            let delta = deltas.recv().await.unwrap();
+            if delta.playlist_id == playlist.id {
+                break delta;
+            }
+        };
+        assert!(delta.added.is_empty());
+        assert_eq!(delta.removed, ["b"]);
+        assert_eq!(
+            sorted(store.smart_playlist_members(&playlist.id).await.unwrap()),
+            ["a", "c"]
+        );
+
+        store.delete_smart_playlist(&playlist.id).await.unwrap();
+        assert!(store
+            .smart_playlist_members(&playlist.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn limited_playlists_are_resolved_again() {
+        let store = store().await;
+        let rules: RuleCriteria = serde_json::from_value(json!({
+            "sort_by": "play_count", "sort_order": "DESC", "limit": 1
+        }))
+        .unwrap();
+        let playlist = store
+            .create_smart_playlist("Top 1", None, None, None, &rules)
+            .await
+            .unwrap();
+        store.record_play("c").await.unwrap();
+        store
+            .refresh_smart_playlist_tracks(&["c".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            sorted(store.smart_playlist_members(&playlist.id).await.unwrap()),
+            ["c"]
+        );
+    }
+}
@@ -1,8 +1,15 @@
-use crate::rules::{resolve, Candidate, RuleCriteria};
+use crate::rules::{
+    self, Candidate, Condition, MatchType, RuleCriteria, RuleField, RuleGroup, RuleOperator,
+    SortField, SortOrder, TimeUnit,
+};
 use crate::PlaylistStore;
 use anyhow::Result;
-use rockbox_library::repo;
-use sqlx::{Pool, Sqlite};
+use chrono::Utc;
+use rockbox_library::{
+    entity::{rating::KIND_TRACK, track::Track},
+    repo,
+};
+use sqlx::{sqlite::SqliteRow, FromRow, Pool, Row, Sqlite};
 use std::collections::{HashMap, HashSet};
 
 /// Build the candidate vector from the library's local tracks, joined with
@@ -27,6 +34,12 @@ pub async fn build_candidates(
         .map(|t| t.id)
         .collect();
 
+    let ratings: HashMap<String, u8> = repo::rating::all(pool.clone(), KIND_TRACK)
+        .await?
+        .into_iter()
+        .map(|r| (r.item_id, r.rating))
+        .collect();
+
     let candidates: Vec<Candidate> = all_tracks
         .iter()
         .map(|t| {
@@ -38,14 +51,16 @@ pub async fn build_candidates(
                 album: t.album.clone(),
                 year: t.year.map(|y| y as i64),
                 genre: t.genre.clone(),
-                duration_ms: t.length as i64 * 1000,
+                duration_ms: t.length as i64,
                 bitrate: t.bitrate as i64,
+                filesize: t.filesize as i64,
                 date_added_ts: t.created_at.timestamp(),
                 play_count: stats.map(|s| s.play_count).unwrap_or(0),
                 skip_count: stats.map(|s| s.skip_count).unwrap_or(0),
                 last_played: stats.and_then(|s| s.last_played),
                 last_skipped: stats.and_then(|s| s.last_skipped),
                 is_liked: liked_ids.contains(&t.id),
+                rating: ratings.get(&t.id).copied().unwrap_or(0) as i64,
             }
         })
         .collect();
@@ -53,44 +68,416 @@ pub async fn build_candidates(
     Ok((candidates, all_tracks))
 }
 
+// ── SQL ────────────────────────────────────────────────────────────────────
+//
+// Rules are turned into a WHERE clause over the library joined with stats
+// and likes, so only matching tracks leave SQLite. A condition SQLite can't
+// answer exactly (an `is` or `contains` on non-ASCII text, where its case
+// folding differs from Rust's) is checked in memory over the rows the rest of the
+// rules let through.
+
+const FROM_TRACKS: &str = "FROM track t
+     LEFT JOIN track_stats s ON s.track_id = t.id
+     LEFT JOIN (SELECT DISTINCT track_id FROM favourites
+                WHERE track_id IS NOT NULL AND track_id != '') f ON f.track_id = t.id
+     LEFT JOIN rating r ON r.kind = 'track' AND r.item_id = t.id
+     WHERE (t.is_remote = 0 OR t.source_id IS NOT NULL) AND t.hidden = 0";
+
+const SELECT_CANDIDATES: &str = "SELECT t.*,
+            COALESCE(s.play_count, 0) AS stats_play_count,
+            COALESCE(s.skip_count, 0) AS stats_skip_count,
+            s.last_played AS stats_last_played,
+            s.last_skipped AS stats_last_skipped,
+            (f.track_id IS NOT NULL) AS stats_liked,
+            COALESCE(r.rating, 0) AS stats_rating";
+
+enum Bind {
+    Int(i64),
+    Text(String),
+}
+
+struct Filter {
+    sql: String,
+    binds: Vec<Bind>,
+}
+
+impl Filter {
+    fn new(sql: impl Into<String>, binds: Vec<Bind>) -> Self {
+        Filter {
+            sql: sql.into(),
+            binds,
+        }
+    }
+
+    fn never() -> Self {
+        Filter::new("0", vec![])
+    }
+
+    fn join(parts: Vec<Filter>, op: &str) -> Self {
+        if parts.is_empty() {
+            return Filter::new("1", vec![]);
+        }
+        let sql = parts
+            .iter()
+            .map(|p| format!("({})", p.sql))
+            .collect::<Vec<_>>()
+            .join(op);
+        let binds = parts.into_iter().flat_map(|p| p.binds).collect();
+        Filter { sql, binds }
+    }
+}
+
+fn column(field: &RuleField) -> &'static str {
+    match field {
+        RuleField::PlayCount => "COALESCE(s.play_count, 0)",
+        RuleField::SkipCount => "COALESCE(s.skip_count, 0)",
+        RuleField::LastPlayed => "s.last_played",
+        RuleField::LastSkipped => "s.last_skipped",
+        RuleField::DateAdded => "COALESCE(CAST(strftime('%s', t.created_at) AS INTEGER), 0)",
+        RuleField::Year => "t.year",
+        RuleField::Genre => "COALESCE(t.genre, '')",
+        RuleField::Artist => "t.artist",
+        RuleField::Album => "t.album",
+        RuleField::DurationMs => "t.length",
+        RuleField::Bitrate => "t.bitrate",
+        RuleField::Rating => "COALESCE(r.rating, 0)",
+        RuleField::IsLiked => "(f.track_id IS NOT NULL)",
+    }
+}
+
+fn numeric_sql(col: &str, cond: &Condition) -> Filter {
+    let n = cond.value.as_ref().and_then(|v| v.as_i64()).unwrap_or(0);
+    let n2 = cond.value2.as_ref().and_then(|v| v.as_i64()).unwrap_or(0);
+    let op = match cond.operator {
+        RuleOperator::Equals | RuleOperator::Is => "=",
+        RuleOperator::IsNot => "!=",
+        RuleOperator::GreaterThan => ">",
+        RuleOperator::LessThan => "<",
+        RuleOperator::GreaterThanOrEqual => ">=",
+        RuleOperator::LessThanOrEqual => "<=",
+        RuleOperator::Between => {
+            return Filter::new(
+                format!("{col} >= ? AND {col} <= ?"),
+                vec![Bind::Int(n), Bind::Int(n2)],
+            )
+        }
+        _ => return Filter::never(),
+    };
+    Filter::new(format!("{col} {op} ?"), vec![Bind::Int(n)])
+}
+
+fn timestamp_sql(col: &str, cond: &Condition, now: i64) -> Filter {
+    let secs = cond
+        .value
+        .as_ref()
+        .and_then(|v| v.as_i64())
+        .map(|n| cond.unit.as_ref().unwrap_or(&TimeUnit::Days).to_seconds(n));
+    match (&cond.operator, secs) {
+        (RuleOperator::IsEmpty, _) => Filter::new(format!("{col} IS NULL"), vec![]),
+        (RuleOperator::IsNotEmpty, _) => Filter::new(format!("{col} IS NOT NULL"), vec![]),
+        (RuleOperator::InLast, Some(secs)) => Filter::new(
+            format!("{col} IS NOT NULL AND ? - {col} <= ?"),
+            vec![Bind::Int(now), Bind::Int(secs)],
+        ),
+        (RuleOperator::NotInLast, Some(secs)) => Filter::new(
+            format!("{col} IS NULL OR ? - {col} > ?"),
+            vec![Bind::Int(now), Bind::Int(secs)],
+        ),
+        (RuleOperator::InLast | RuleOperator::NotInLast, None) => Filter::never(),
+        _ => numeric_sql(&format!("COALESCE({col}, 0)"), cond),
+    }
+}
+
+/// `None` when SQLite's ASCII-only case folding could disagree.
+fn string_sql(col: &str, cond: &Condition) -> Option<Filter> {
+    let target = cond
+        .value
+        .as_ref()
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let filter = match cond.operator {
+        RuleOperator::Is
+        | RuleOperator::Equals
+        | RuleOperator::IsNot
+        | RuleOperator::Contains
+        | RuleOperator::NotContains
+            if !target.is_ascii() =>
+        {
+            return None
+        }
+        RuleOperator::Is | RuleOperator::Equals => Filter::new(
+            format!("{col} = ? COLLATE NOCASE"),
+            vec![Bind::Text(target)],
+        ),
+        RuleOperator::IsNot => Filter::new(
+            format!("{col} <> ? COLLATE NOCASE"),
+            vec![Bind::Text(target)],
+        ),
+        RuleOperator::Contains => Filter::new(
+            format!("instr(lower({col}), lower(?)) > 0"),
+            vec![Bind::Text(target)],
+        ),
+        RuleOperator::NotContains => Filter::new(
+            format!("instr(lower({col}), lower(?)) = 0"),
+            vec![Bind::Text(target)],
+        ),
+        RuleOperator::IsEmpty => Filter::new(format!("{col} = ''"), vec![]),
+        RuleOperator::IsNotEmpty => Filter::new(format!("{col} != ''"), vec![]),
+        _ => Filter::never(),
+    };
+    Some(filter)
+}
+
+fn condition_sql(cond: &Condition, now: i64) -> Option<Filter> {
+    let col = column(&cond.field);
+    let filter = match cond.field {
+        RuleField::PlayCount
+        | RuleField::SkipCount
+        | RuleField::DurationMs
+        | RuleField::Bitrate => numeric_sql(col, cond),
+        RuleField::Rating => match cond.operator {
+            RuleOperator::IsEmpty => Filter::new(format!("{col} = 0"), vec![]),
+            RuleOperator::IsNotEmpty => Filter::new(format!("{col} > 0"), vec![]),
+            _ => numeric_sql(col, cond),
+        },
+        RuleField::Year => match cond.operator {
+            RuleOperator::IsEmpty => Filter::new("t.year IS NULL", vec![]),
+            RuleOperator::IsNotEmpty => Filter::new("t.year IS NOT NULL", vec![]),
+            _ => {
+                let numeric = numeric_sql(col, cond);
+                Filter::new(
+                    format!("t.year IS NOT NULL AND ({})", numeric.sql),
+                    numeric.binds,
+                )
+            }
+        },
+        RuleField::LastPlayed | RuleField::LastSkipped | RuleField::DateAdded => {
+            timestamp_sql(col, cond, now)
+        }
+        RuleField::Genre | RuleField::Artist | RuleField::Album => string_sql(col, cond)?,
+        RuleField::IsLiked => {
+            let want = cond
+                .value
+                .as_ref()
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            Filter::new(format!("{col} = ?"), vec![Bind::Int(want as i64)])
+        }
+    };
+    Some(filter)
+}
+
+fn group_sql(
+    match_type: &MatchType,
+    negate: bool,
+    conditions: &[Condition],
+    groups: &[RuleGroup],
+    now: i64,
+) -> Option<Filter> {
+    let parts = conditions
+        .iter()
+        .map(|cond| condition_sql(cond, now))
+        .chain(
+            groups
+                .iter()
+                .map(|g| group_sql(&g.match_type, g.negate, &g.conditions, &g.groups, now)),
+        )
+        .collect::<Option<Vec<_>>>()?;
+    let op = match match_type {
+        MatchType::All => " AND ",
+        MatchType::Any => " OR ",
+    };
+    let filter = Filter::join(parts, op);
+    Some(match negate {
+        true => Filter::new(format!("NOT ({})", filter.sql), filter.binds),
+        false => filter,
+    })
+}
+
+/// The WHERE clause for `criteria`, and whether it is exact. When it isn't,
+/// it lets through a superset of the matches.
+fn where_sql(criteria: &RuleCriteria, now: i64) -> (Filter, bool) {
+    if let Some(filter) = group_sql(
+        &criteria.match_type,
+        false,
+        &criteria.conditions,
+        &criteria.groups,
+        now,
+    ) {
+        return (filter, true);
+    }
+    let filter = match criteria.match_type {
+        MatchType::All => Filter::join(
+            criteria
+                .conditions
+                .iter()
+                .filter_map(|cond| condition_sql(cond, now))
+                .chain(criteria.groups.iter().filter_map(|g| {
+                    group_sql(&g.match_type, g.negate, &g.conditions, &g.groups, now)
+                }))
+                .collect(),
+            " AND ",
+        ),
+        MatchType::Any => Filter::new("1", vec![]),
+    };
+    (filter, false)
+}
+
+/// ORDER BY matching `rules::resolve`, whose sort is stable over tracks
+/// listed by title.
+fn order_sql(criteria: &RuleCriteria) -> String {
+    let sort_by = criteria.sort_by.as_ref().unwrap_or(&SortField::DateAdded);
+    let dir = match criteria.sort_order.as_ref().unwrap_or(&SortOrder::Desc) {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    let key = match sort_by {
+        SortField::Random => return "RANDOM()".to_string(),
+        SortField::PlayCount => column(&RuleField::PlayCount),
+        SortField::SkipCount => column(&RuleField::SkipCount),
+        SortField::LastPlayed => "COALESCE(s.last_played, 0)",
+        SortField::DateAdded => column(&RuleField::DateAdded),
+        SortField::Year => "COALESCE(t.year, 0)",
+        SortField::Title => "t.title",
+        SortField::Artist => "t.artist",
+        SortField::Album => "t.album",
+        SortField::DurationMs => "t.length",
+        SortField::Rating => column(&RuleField::Rating),
+    };
+    format!("{key} {dir}, t.title ASC")
+}
+
+fn bind_all<'q>(
+    mut query: sqlx::query::Query<'q, Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+    binds: Vec<Bind>,
+) -> sqlx::query::Query<'q, Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+    for bind in binds {
+        query = match bind {
+            Bind::Int(n) => query.bind(n),
+            Bind::Text(s) => query.bind(s),
+        };
+    }
+    query
+}
+
+fn row_to_candidate(row: &SqliteRow) -> Result<(Candidate, Track)> {
+    let t = Track::from_row(row)?;
+    let candidate = Candidate {
+        id: t.id.clone(),
+        title: t.title.clone(),
+        artist: t.artist.clone(),
+        album: t.album.clone(),
+        year: t.year.map(|y| y as i64),
+        genre: t.genre.clone(),
+        duration_ms: t.length as i64,
+        bitrate: t.bitrate as i64,
+        filesize: t.filesize as i64,
+        date_added_ts: t.created_at.timestamp(),
+        play_count: row.try_get("stats_play_count")?,
+        skip_count: row.try_get("stats_skip_count")?,
+        last_played: row.try_get("stats_last_played")?,
+        last_skipped: row.try_get("stats_last_skipped")?,
+        is_liked: row.try_get("stats_liked")?,
+        rating: row.try_get("stats_rating")?,
+    };
+    Ok((candidate, t))
+}
+
 /// Resolve a rule criteria over the library and return matching tracks
 /// (in the order produced by the rule resolver — i.e. honouring sort/limit).
-pub async fn resolve_tracks(
-    store: &PlaylistStore,
-    pool: &Pool<Sqlite>,
-    criteria: &RuleCriteria,
-) -> Result<Vec<rockbox_library::entity::track::Track>> {
-    let (candidates, all_tracks) = build_candidates(store, pool).await?;
-    let resolved = resolve(criteria, candidates);
-    let track_map: HashMap<&str, &rockbox_library::entity::track::Track> =
-        all_tracks.iter().map(|t| (t.id.as_str(), t)).collect();
-    Ok(resolved
+pub async fn resolve_tracks(pool: &Pool<Sqlite>, criteria: &RuleCriteria) -> Result<Vec<Track>> {
+    let now = Utc::now().timestamp();
+    let (filter, exact) = where_sql(criteria, now);
+    let mut sql = format!(
+        "{SELECT_CANDIDATES} {FROM_TRACKS} AND ({}) ORDER BY {}",
+        filter.sql,
+        order_sql(criteria)
+    );
+    let sql_limit = criteria
+        .limit
+        .filter(|_| exact && !criteria.has_total_limit());
+    if let Some(limit) = sql_limit {
+        sql.push_str(&format!(" LIMIT {}", limit));
+    }
+
+    let rows = bind_all(sqlx::query(&sql), filter.binds)
+        .fetch_all(pool)
+        .await?;
+    let mut candidates = Vec::with_capacity(rows.len());
+    let mut tracks = HashMap::with_capacity(rows.len());
+    for row in &rows {
+        let (candidate, track) = row_to_candidate(row)?;
+        if exact || rules::matches(criteria, &candidate, now) {
+            tracks.insert(track.id.clone(), track);
+            candidates.push(candidate);
+        }
+    }
+    rules::apply_limits(&mut candidates, criteria);
+    Ok(candidates
         .iter()
-        .filter_map(|c| track_map.get(c.id.as_str()).map(|t| (*t).clone()))
+        .filter_map(|c| tracks.remove(&c.id))
         .collect())
 }
 
-/// Count how many tracks would be included in the smart playlist
-/// described by `criteria`.
-pub async fn count_tracks(
-    store: &PlaylistStore,
+/// The tracks among `track_ids` that meet the conditions and groups of
+/// `criteria`, regardless of its sort and limits.
+pub async fn matching_ids(
     pool: &Pool<Sqlite>,
     criteria: &RuleCriteria,
-) -> Result<i64> {
-    let (candidates, _) = build_candidates(store, pool).await?;
-    let resolved = resolve(criteria, candidates);
-    Ok(resolved.len() as i64)
+    track_ids: &[String],
+) -> Result<HashSet<String>> {
+    if track_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let now = Utc::now().timestamp();
+    let (filter, exact) = where_sql(criteria, now);
+    let sql = format!(
+        "{SELECT_CANDIDATES} {FROM_TRACKS} AND t.id IN ({}) AND ({})",
+        vec!["?"; track_ids.len()].join(", "),
+        filter.sql
+    );
+    let mut query = sqlx::query(&sql);
+    for id in track_ids {
+        query = query.bind(id);
+    }
+    let rows = bind_all(query, filter.binds).fetch_all(pool).await?;
+    let mut matching = HashSet::with_capacity(rows.len());
+    for row in &rows {
+        let (candidate, _) = row_to_candidate(row)?;
+        if exact || rules::matches(criteria, &candidate, now) {
+            matching.insert(candidate.id);
+        }
+    }
+    Ok(matching)
+}
+
+/// Count how many tracks would be included in the smart playlist
+/// described by `criteria`.
+pub async fn count_tracks(pool: &Pool<Sqlite>, criteria: &RuleCriteria) -> Result<i64> {
+    let (filter, exact) = where_sql(criteria, Utc::now().timestamp());
+    if !exact || criteria.has_total_limit() {
+        return Ok(resolve_tracks(pool, criteria).await?.len() as i64);
+    }
+    let sql = format!("SELECT COUNT(*) {FROM_TRACKS} AND ({})", filter.sql);
+    let count: i64 = bind_all(sqlx::query(&sql), filter.binds)
+        .fetch_one(pool)
+        .await?
+        .get(0);
+    Ok(match criteria.limit {
+        Some(limit) => count.min(limit as i64),
+        None => count,
+    })
 }
 
 /// Resolve and return the unique album ids from the matching tracks,
 /// preserving the order in which the resolver returned them (i.e. the
 /// album of the first matching track first, etc.).
 pub async fn resolve_album_ids(
-    store: &PlaylistStore,
     pool: &Pool<Sqlite>,
     criteria: &RuleCriteria,
 ) -> Result<Vec<String>> {
-    let tracks = resolve_tracks(store, pool, criteria).await?;
+    let tracks = resolve_tracks(pool, criteria).await?;
     let mut seen: HashSet<String> = HashSet::new();
     let mut ordered: Vec<String> = Vec::new();
     for t in tracks {
@@ -104,11 +491,10 @@ pub async fn resolve_album_ids(
 /// Resolve and return the unique artist ids from the matching tracks,
 /// preserving order.
 pub async fn resolve_artist_ids(
-    store: &PlaylistStore,
     pool: &Pool<Sqlite>,
     criteria: &RuleCriteria,
 ) -> Result<Vec<String>> {
-    let tracks = resolve_tracks(store, pool, criteria).await?;
+    let tracks = resolve_tracks(pool, criteria).await?;
     let mut seen: HashSet<String> = HashSet::new();
     let mut ordered: Vec<String> = Vec::new();
     for t in tracks {
@@ -123,11 +509,10 @@ pub async fn resolve_artist_ids(
 /// returned in the order their first matching track appears in the resolver
 /// output.
 pub async fn filter_albums(
-    store: &PlaylistStore,
     pool: &Pool<Sqlite>,
     criteria: &RuleCriteria,
 ) -> Result<Vec<rockbox_library::entity::album::Album>> {
-    let ordered_ids = resolve_album_ids(store, pool, criteria).await?;
+    let ordered_ids = resolve_album_ids(pool, criteria).await?;
     let mut albums = Vec::with_capacity(ordered_ids.len());
     for id in ordered_ids {
         if let Some(album) = repo::album::find(pool.clone(), &id).await? {
@@ -139,11 +524,10 @@ pub async fn filter_albums(
 
 /// Resolve a `RuleCriteria` and return the matching `Artist`s.
 pub async fn filter_artists(
-    store: &PlaylistStore,
     pool: &Pool<Sqlite>,
     criteria: &RuleCriteria,
 ) -> Result<Vec<rockbox_library::entity::artist::Artist>> {
-    let ordered_ids = resolve_artist_ids(store, pool, criteria).await?;
+    let ordered_ids = resolve_artist_ids(pool, criteria).await?;
     let mut artists = Vec::with_capacity(ordered_ids.len());
     for id in ordered_ids {
         if let Some(artist) = repo::artist::find(pool.clone(), &id).await? {
@@ -152,3 +536,318 @@ pub async fn filter_artists(
     }
     Ok(artists)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    const DAY: i64 = 86_400;
+
+    struct Fixture {
+        title: &'static str,
+        artist: &'static str,
+        genre: Option<&'static str>,
+        year: Option<u32>,
+        length: u32,
+        size: u32,
+        added_days_ago: i64,
+        plays: i64,
+        played_days_ago: Option<i64>,
+        liked: bool,
+        rating: u8,
+    }
+
+    impl Fixture {
+        fn new(
+            title: &'static str,
+            artist: &'static str,
+            genre: Option<&'static str>,
+            year: Option<u32>,
+            length: u32,
+            size: u32,
+            added_days_ago: i64,
+        ) -> Self {
+            Fixture {
+                title,
+                artist,
+                genre,
+                year,
+                length,
+                size,
+                added_days_ago,
+                plays: 0,
+                played_days_ago: None,
+                liked: false,
+                rating: 0,
+            }
+        }
+
+        fn played(self, plays: i64, days_ago: Option<i64>) -> Self {
+            Fixture {
+                plays,
+                played_days_ago: days_ago,
+                ..self
+            }
+        }
+
+        fn liked(self) -> Self {
+            Fixture {
+                liked: true,
+                ..self
+            }
+        }
+
+        fn rated(self, rating: u8) -> Self {
+            Fixture { rating, ..self }
+        }
+    }
+
+    async fn library() -> (PlaylistStore, Pool<Sqlite>) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for migration in [
+            include_str!("../../library/migrations/20240923093823_create_tables.sql"),
+            include_str!("../../library/migrations/20241020125757_add-album_id-column.sql"),
+            include_str!("../../library/migrations/20260425000000_add_playlist_tables.sql"),
+            include_str!("../../library/migrations/20260428000000_add_is_remote_to_track.sql"),
+            include_str!("../../library/migrations/20261019000200_add_track_media_type.sql"),
+            include_str!("../../library/migrations/20261019001000_add_ratings.sql"),
+            include_str!("../../library/migrations/20261019001100_add_track_hidden.sql"),
+            include_str!("../../library/migrations/20261019001200_add_remote_sources.sql"),
+        ] {
+            pool.execute(migration).await.unwrap();
+        }
+
+        let now = Utc::now().timestamp();
+        let tracks = [
+            Fixture::new(
+                "So What",
+                "Miles Davis",
+                Some("Jazz"),
+                Some(1959),
+                562_000,
+                9_000_000,
+                400,
+            )
+            .played(12, Some(2))
+            .liked()
+            .rated(5),
+            Fixture::new(
+                "Blue in Green",
+                "Miles Davis",
+                Some("Jazz"),
+                Some(1959),
+                337_000,
+                5_000_000,
+                400,
+            )
+            .played(1, Some(90)),
+            Fixture::new(
+                "Hoochie Coochie Man",
+                "Muddy Waters",
+                Some("Blues"),
+                Some(1954),
+                170_000,
+                3_000_000,
+                10,
+            )
+            .rated(3),
+            Fixture::new(
+                "Army of Me",
+                "Björk",
+                Some("Electronic"),
+                Some(1995),
+                234_000,
+                4_000_000,
+                5,
+            )
+            .played(2, Some(40))
+            .liked(),
+            Fixture::new(
+                "Hyperballad",
+                "BJÖRK",
+                Some("Electronic"),
+                None,
+                321_000,
+                6_000_000,
+                50,
+            )
+            .played(30, Some(1))
+            .rated(4),
+            Fixture::new(
+                "Take Five",
+                "Dave Brubeck",
+                Some("jazz"),
+                Some(1959),
+                324_000,
+                5_500_000,
+                700,
+            )
+            .played(2, Some(300)),
+            Fixture::new("Untitled", "Unknown", None, None, 60_000, 1_000_000, 1),
+        ];
+        for (i, f) in tracks.into_iter().enumerate() {
+            let id = format!("t{}", i);
+            repo::track::save(
+                pool.clone(),
+                Track {
+                    id: id.clone(),
+                    path: format!("/music/{}.flac", f.title),
+                    title: f.title.to_string(),
+                    artist: f.artist.to_string(),
+                    album: format!("{} album", f.artist),
+                    genre: f.genre.map(str::to_string),
+                    year: f.year,
+                    length: f.length,
+                    filesize: f.size,
+                    md5: id.clone(),
+                    created_at: Utc.timestamp_opt(now - f.added_days_ago * DAY, 0).unwrap(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            if f.plays > 0 {
+                sqlx::query(
+                    "INSERT INTO track_stats (track_id, play_count, skip_count, last_played, updated_at)
+                     VALUES (?, ?, 0, ?, ?)",
+                )
+                .bind(&id)
+                .bind(f.plays)
+                .bind(f.played_days_ago.map(|d| now - d * DAY))
+                .bind(now)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+            if f.liked {
+                sqlx::query("INSERT INTO favourites (id, track_id) VALUES (?, ?)")
+                    .bind(format!("f{}", i))
+                    .bind(&id)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+            if f.rating > 0 {
+                repo::rating::save(pool.clone(), KIND_TRACK, &id, f.rating)
+                    .await
+                    .unwrap();
+            }
+        }
+        (PlaylistStore::new(pool.clone()), pool)
+    }
+
+    fn criteria(value: serde_json::Value) -> RuleCriteria {
+        serde_json::from_value(value).unwrap()
+    }
+
+    async fn titles(pool: &Pool<Sqlite>, c: &RuleCriteria) -> Vec<String> {
+        resolve_tracks(pool, c)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sql_agrees_with_the_in_memory_resolver() {
+        let (store, pool) = library().await;
+        let cases = [
+            json!({}),
+            json!({ "conditions": [{"field":"genre","operator":"is","value":"JAZZ"}], "sort_by": "title", "sort_order": "ASC" }),
+            json!({ "conditions": [{"field":"artist","operator":"contains","value":"björk"}] }),
+            json!({ "conditions": [{"field":"artist","operator":"contains","value":"bjo"}] }),
+            json!({ "conditions": [{"field":"artist","operator":"is","value":"BJÖRK"}] }),
+            json!({ "conditions": [{"field":"artist","operator":"is_not","value":"björk"}] }),
+            json!({ "conditions": [{"field":"genre","operator":"is_empty"}] }),
+            json!({ "conditions": [{"field":"year","operator":"is_not_empty"}], "sort_by": "year" }),
+            json!({ "conditions": [{"field":"year","operator":"between","value":1950,"value2":1960}] }),
+            json!({ "conditions": [{"field":"last_played","operator":"in_last","value":1,"unit":"weeks"}] }),
+            json!({ "conditions": [{"field":"last_played","operator":"not_in_last","value":30}], "sort_by": "play_count" }),
+            json!({ "conditions": [{"field":"date_added","operator":"in_last","value":60,"unit":"days"}] }),
+            json!({ "conditions": [{"field":"is_liked","operator":"is","value":true}] }),
+            json!({ "conditions": [{"field":"rating","operator":"greater_than_or_equal","value":4}], "sort_by": "rating" }),
+            json!({ "conditions": [{"field":"rating","operator":"is_empty"}], "sort_by": "title" }),
+            json!({ "sort_by": "rating", "sort_order": "ASC", "limit": 4 }),
+            json!({ "conditions": [{"field":"duration_ms","operator":"greater_than","value":300000}], "sort_by": "duration_ms", "limit": 3 }),
+            json!({ "match_type": "any", "conditions": [
+                {"field":"play_count","operator":"equals","value":0},
+                {"field":"artist","operator":"not_contains","value":"ö"}
+            ], "sort_by": "last_played", "sort_order": "ASC" }),
+            json!({ "conditions": [{"field":"play_count","operator":"less_than","value":20}],
+                    "groups": [{ "match_type": "any", "negate": true, "conditions": [
+                        {"field":"genre","operator":"is","value":"Electronic"},
+                        {"field":"year","operator":"is_empty"}
+                    ]}], "sort_by": "artist" }),
+            json!({ "conditions": [{"field":"album","operator":"contains","value":"Ö"}],
+                    "groups": [{ "conditions": [{"field":"bitrate","operator":"in_last","value":1}] }] }),
+            json!({ "sort_by": "title", "limit_duration_ms": 1_000_000 }),
+            json!({ "sort_by": "year", "sort_order": "ASC", "limit": 5, "limit_size_bytes": 12_000_000 }),
+        ];
+        for case in cases {
+            let c = criteria(case.clone());
+            let (candidates, _) = build_candidates(&store, &pool).await.unwrap();
+            let expected: Vec<String> = rules::resolve(&c, candidates)
+                .into_iter()
+                .map(|c| c.title)
+                .collect();
+            assert_eq!(titles(&pool, &c).await, expected, "{}", case);
+            assert_eq!(
+                count_tracks(&pool, &c).await.unwrap(),
+                expected.len() as i64,
+                "{}",
+                case
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn nested_groups() {
+        let (store, pool) = library().await;
+        // (Jazz OR Blues) AND fewer than 3 plays AND NOT played in the last
+        // 30 days.
+        let c = criteria(json!({
+            "match_type": "all",
+            "conditions": [{"field":"play_count","operator":"less_than","value":3}],
+            "groups": [
+                { "match_type": "any", "conditions": [
+                    {"field":"genre","operator":"is","value":"Jazz"},
+                    {"field":"genre","operator":"is","value":"Blues"}
+                ]},
+                { "negate": true, "conditions": [
+                    {"field":"last_played","operator":"in_last","value":30,"unit":"days"}
+                ]}
+            ],
+            "sort_by": "title",
+            "sort_order": "ASC"
+        }));
+        assert_eq!(
+            titles(&pool, &c).await,
+            ["Blue in Green", "Hoochie Coochie Man", "Take Five"]
+        );
+    }
+
+    #[tokio::test]
+    async fn total_limits_stop_before_overflowing() {
+        let (store, pool) = library().await;
+        let c = criteria(
+            json!({ "sort_by": "title", "sort_order": "ASC", "limit_duration_ms": 700_000 }),
+        );
+        // 234 000 + 337 000 fit, adding 170 000 would not.
+        assert_eq!(titles(&pool, &c).await, ["Army of Me", "Blue in Green"]);
+        let c = criteria(
+            json!({ "sort_by": "title", "sort_order": "ASC", "limit_size_bytes": 12_000_000 }),
+        );
+        assert_eq!(
+            titles(&pool, &c).await,
+            ["Army of Me", "Blue in Green", "Hoochie Coochie Man"]
+        );
+    }
+}
@@ -31,6 +31,7 @@ pub enum RuleField {
     DurationMs,
     Bitrate,
     IsLiked,
+    Rating,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -85,6 +86,7 @@ pub enum SortField {
     Artist,
     Album,
     DurationMs,
+    Rating,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -117,17 +119,48 @@ pub struct Condition {
     pub unit: Option<TimeUnit>,
 }
 
+/// Conditions and nested groups combined by their own match type, e.g.
+/// `genre is Jazz OR genre is Blues` inside an `all` criteria.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RuleGroup {
+    #[serde(default)]
+    pub match_type: MatchType,
+    /// Match the tracks the group would otherwise leave out.
+    #[serde(default)]
+    pub negate: bool,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub groups: Vec<RuleGroup>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RuleCriteria {
     #[serde(default)]
     pub match_type: MatchType,
     #[serde(default)]
     pub conditions: Vec<Condition>,
+    /// Nested groups, combined with `conditions` by `match_type`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<RuleGroup>,
     pub limit: Option<usize>,
+    /// Stop before the tracks add up to more than this many milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_duration_ms: Option<i64>,
+    /// Stop before the tracks add up to more than this many bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_size_bytes: Option<i64>,
     pub sort_by: Option<SortField>,
     pub sort_order: Option<SortOrder>,
 }
 
+impl RuleCriteria {
+    /// Whether a track's duration or size decides where the list stops.
+    pub fn has_total_limit(&self) -> bool {
+        self.limit_duration_ms.is_some() || self.limit_size_bytes.is_some()
+    }
+}
+
 // ── Candidate track fed into the resolver ──────────────────────────────────
 
 #[derive(Debug, Clone)]
@@ -140,6 +173,7 @@ pub struct Candidate {
     pub genre: Option<String>,
     pub duration_ms: i64,
     pub bitrate: i64,
+    pub filesize: i64,
     pub date_added_ts: i64,
     // stats (default 0 / None when never played)
     pub play_count: i64,
@@ -147,6 +181,8 @@ pub struct Candidate {
     pub last_played: Option<i64>,
     pub last_skipped: Option<i64>,
     pub is_liked: bool,
+    /// 1–5 stars, 0 when unrated.
+    pub rating: i64,
 }
 
 // ── Resolver ───────────────────────────────────────────────────────────────
@@ -155,30 +191,73 @@ pub fn resolve(criteria: &RuleCriteria, mut candidates: Vec<Candidate>) -> Vec<C
     let now = Utc::now().timestamp();
 
     // Filter
-    candidates.retain(|c| {
-        if criteria.conditions.is_empty() {
-            return true;
-        }
-        let results: Vec<bool> = criteria
-            .conditions
-            .iter()
-            .map(|cond| eval_condition(cond, c, now))
-            .collect();
-        match criteria.match_type {
-            MatchType::All => results.iter().all(|&r| r),
-            MatchType::Any => results.iter().any(|&r| r),
-        }
-    });
+    candidates.retain(|c| matches(criteria, c, now));
 
     // Sort
     sort_candidates(&mut candidates, criteria, now);
 
     // Limit
+    apply_limits(&mut candidates, criteria);
+
+    candidates
+}
+
+/// Whether a track meets the conditions and groups of `criteria`.
+pub fn matches(criteria: &RuleCriteria, c: &Candidate, now: i64) -> bool {
+    eval_group(
+        &criteria.match_type,
+        false,
+        &criteria.conditions,
+        &criteria.groups,
+        c,
+        now,
+    )
+}
+
+/// Cut sorted tracks down to the track count, total duration and total
+/// size limits of `criteria`.
+pub fn apply_limits(candidates: &mut Vec<Candidate>, criteria: &RuleCriteria) {
     if let Some(limit) = criteria.limit {
         candidates.truncate(limit);
     }
+    let (mut duration, mut size) = (0, 0);
+    let fits = candidates
+        .iter()
+        .take_while(|c| {
+            duration += c.duration_ms;
+            size += c.filesize;
+            criteria.limit_duration_ms.is_none_or(|max| duration <= max)
+                && criteria.limit_size_bytes.is_none_or(|max| size <= max)
+        })
+        .count();
+    candidates.truncate(fits);
+}
 
-    candidates
+fn eval_group(
+    match_type: &MatchType,
+    negate: bool,
+    conditions: &[Condition],
+    groups: &[RuleGroup],
+    c: &Candidate,
+    now: i64,
+) -> bool {
+    let mut results = conditions
+        .iter()
+        .map(|cond| eval_condition(cond, c, now))
+        .chain(
+            groups
+                .iter()
+                .map(|g| eval_group(&g.match_type, g.negate, &g.conditions, &g.groups, c, now)),
+        );
+    let matched = if conditions.is_empty() && groups.is_empty() {
+        true
+    } else {
+        match match_type {
+            MatchType::All => results.all(|r| r),
+            MatchType::Any => results.any(|r| r),
+        }
+    };
+    matched != negate
 }
 
 fn eval_condition(cond: &Condition, c: &Candidate, now: i64) -> bool {
@@ -187,13 +266,17 @@ fn eval_condition(cond: &Condition, c: &Candidate, now: i64) -> bool {
         RuleField::SkipCount => eval_numeric(cond, c.skip_count),
         RuleField::DurationMs => eval_numeric(cond, c.duration_ms),
         RuleField::Bitrate => eval_numeric(cond, c.bitrate),
-        RuleField::Year => {
-            if let Some(y) = c.year {
-                eval_numeric(cond, y)
-            } else {
-                matches!(cond.operator, RuleOperator::IsEmpty)
-            }
-        }
+        RuleField::Rating => match &cond.operator {
+            RuleOperator::IsEmpty => c.rating == 0,
+            RuleOperator::IsNotEmpty => c.rating > 0,
+            _ => eval_numeric(cond, c.rating),
+        },
+        RuleField::Year => match (c.year, &cond.operator) {
+            (year, RuleOperator::IsEmpty) => year.is_none(),
+            (year, RuleOperator::IsNotEmpty) => year.is_some(),
+            (Some(y), _) => eval_numeric(cond, y),
+            (None, _) => false,
+        },
         RuleField::LastPlayed => eval_timestamp(cond, c.last_played, now),
         RuleField::LastSkipped => eval_timestamp(cond, c.last_skipped, now),
         RuleField::DateAdded => eval_timestamp(cond, Some(c.date_added_ts), now),
@@ -260,8 +343,8 @@ fn eval_string(cond: &Condition, val: Option<&str>) -> bool {
     let s = val.unwrap_or("");
     let target = cond.value.as_ref().and_then(|v| v.as_str()).unwrap_or("");
     match cond.operator {
-        RuleOperator::Is | RuleOperator::Equals => s.eq_ignore_ascii_case(target),
-        RuleOperator::IsNot => !s.eq_ignore_ascii_case(target),
+        RuleOperator::Is | RuleOperator::Equals => s.to_lowercase() == target.to_lowercase(),
+        RuleOperator::IsNot => s.to_lowercase() != target.to_lowercase(),
         RuleOperator::Contains => s.to_lowercase().contains(&target.to_lowercase()),
         RuleOperator::NotContains => !s.to_lowercase().contains(&target.to_lowercase()),
         RuleOperator::IsEmpty => s.is_empty(),
@@ -295,6 +378,7 @@ fn sort_candidates(candidates: &mut Vec<Candidate>, criteria: &RuleCriteria, _no
             SortField::Artist => a.artist.cmp(&b.artist),
             SortField::Album => a.album.cmp(&b.album),
             SortField::DurationMs => a.duration_ms.cmp(&b.duration_ms),
+            SortField::Rating => a.rating.cmp(&b.rating),
             SortField::Random => std::cmp::Ordering::Equal,
         };
         if asc {
@@ -304,3 +388,39 @@ fn sort_candidates(candidates: &mut Vec<Candidate>, criteria: &RuleCriteria, _no
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flat_rules_keep_their_shape() {
+        let stored = json!({ "match_type": "all",
+          "conditions": [{"field":"play_count","operator":"greater_than","value":0}],
+          "limit": 50, "sort_by": "play_count", "sort_order": "DESC" });
+        let criteria: RuleCriteria = serde_json::from_value(stored).unwrap();
+        assert!(criteria.groups.is_empty());
+        assert!(!criteria.has_total_limit());
+        let saved = serde_json::to_value(&criteria).unwrap();
+        assert!(saved.get("groups").is_none());
+        assert!(saved.get("limit_duration_ms").is_none());
+    }
+
+    #[test]
+    fn groups_nest() {
+        let criteria: RuleCriteria = serde_json::from_value(json!({
+            "groups": [{ "match_type": "any", "negate": true, "groups": [
+                { "conditions": [{"field":"genre","operator":"is","value":"Jazz"}] }
+            ]}],
+            "limit_duration_ms": 3_600_000
+        }))
+        .unwrap();
+        assert_eq!(criteria.match_type, MatchType::All);
+        let group = &criteria.groups[0];
+        assert!(group.negate);
+        assert_eq!(group.match_type, MatchType::Any);
+        assert_eq!(group.groups[0].conditions[0].field, RuleField::Genre);
+        assert!(criteria.has_total_limit());
+    }
+}
@@ -0,0 +1,380 @@
+//! Two-way sync between saved playlists and a folder of playlist files.
+//!
+//! Every playlist file in the folder is a saved playlist and every saved
+//! playlist has a file there (written as M3U8 when it was created in the
+//! app). Whichever side changed since the last pass wins; deleting either
+//! side deletes the other.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use sqlx::Row;
+use tracing::{error, info, warn};
+
+use crate::{
+    formats::{self, Format},
+    PlaylistStore,
+};
+
+struct SyncedFile {
+    path: String,
+    playlist_id: String,
+    /// File mtime when it was last read or written.
+    modified_at: i64,
+    /// Playlist `updated_at` when it was last read or written.
+    synced_at: i64,
+}
+
+/// Sync the saved playlists with the files in `dir` every
+/// `ROCKBOX_PLAYLIST_SYNC_INTERVAL_SECS` seconds (default 30, 0 disables).
+pub fn start_sync_task(store: PlaylistStore, dir: PathBuf) {
+    let secs: u64 = std::env::var("ROCKBOX_PLAYLIST_SYNC_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(30);
+    if secs == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = store.sync_dir(&dir).await {
+                error!("playlists: sync with {} failed: {}", dir.display(), e);
+            }
+        }
+    });
+}
+
+async fn mtime(path: &Path) -> Result<i64> {
+    let modified = tokio::fs::metadata(path).await?.modified()?;
+    Ok(modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0))
+}
+
+/// A file name for a playlist that is safe on any filesystem.
+fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = name.trim().trim_start_matches('.');
+    if name.is_empty() {
+        "Playlist".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `<name>.m3u8` in `dir`, numbered when that file already exists.
+async fn free_path(dir: &Path, name: &str) -> PathBuf {
+    let name = file_name(name);
+    let mut path = dir.join(format!("{}.m3u8", name));
+    let mut n = 2;
+    while tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        path = dir.join(format!("{} ({}).m3u8", name, n));
+        n += 1;
+    }
+    path
+}
+
+impl PlaylistStore {
+    async fn synced_files(&self) -> Result<Vec<SyncedFile>> {
+        let rows =
+            sqlx::query("SELECT path, playlist_id, modified_at, synced_at FROM playlist_files")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| SyncedFile {
+                path: r.get(0),
+                playlist_id: r.get(1),
+                modified_at: r.get(2),
+                synced_at: r.get(3),
+            })
+            .collect())
+    }
+
+    async fn save_synced_file(&self, path: &Path, playlist_id: &str) -> Result<()> {
+        let synced_at = match self.get(playlist_id).await? {
+            Some(playlist) => playlist.updated_at,
+            None => 0,
+        };
+        sqlx::query(
+            "INSERT OR REPLACE INTO playlist_files (path, playlist_id, modified_at, synced_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(path.to_string_lossy())
+        .bind(playlist_id)
+        .bind(mtime(path).await?)
+        .bind(synced_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn forget_synced_file(&self, path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM playlist_files WHERE path = ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Read the playlist file at `path` into the saved playlist
+    /// `playlist_id`, or into a new one named after the file.
+    async fn read_file(
+        &self,
+        path: &Path,
+        format: Format,
+        playlist_id: Option<&str>,
+    ) -> Result<()> {
+        let file = formats::parse(format, &tokio::fs::read_to_string(path).await?)?;
+        let (playlist_id, unmatched) = match playlist_id {
+            Some(id) => {
+                let (ids, unmatched) = self.match_entries(file.entries, path.parent()).await?;
+                self.replace_tracks(id, &ids).await?;
+                (id.to_string(), unmatched)
+            }
+            None => {
+                let name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+                let report = self
+                    .import_playlist(file, name.as_deref(), None, path.parent())
+                    .await?;
+                (report.playlist.id, report.unmatched)
+            }
+        };
+        if !unmatched.is_empty() {
+            warn!(
+                "playlists: {} entries of {} match no library track",
+                unmatched.len(),
+                path.display()
+            );
+        }
+        self.save_synced_file(path, &playlist_id).await
+    }
+
+    async fn write_file(&self, path: &Path, format: Format, playlist_id: &str) -> Result<()> {
+        if let Some(content) = self
+            .export_playlist(playlist_id, format, path.parent())
+            .await?
+        {
+            tokio::fs::write(path, content).await?;
+            self.save_synced_file(path, playlist_id).await?;
+        }
+        Ok(())
+    }
+
+    /// One pass of the two-way sync with the playlist files in `dir`.
+    ///
+    /// A folder that already has synced files but is missing or unreadable
+    /// (an unmounted drive, say) is left alone for this pass: its files only
+    /// look deleted, and recreating it would write to the mount point.
+    pub async fn sync_dir(&self, dir: &Path) -> Result<()> {
+        let synced_files: Vec<SyncedFile> = self
+            .synced_files()
+            .await?
+            .into_iter()
+            .filter(|synced| Path::new(&synced.path).parent() == Some(dir))
+            .collect();
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if !synced_files.is_empty() => {
+                warn!(
+                    "playlists: cannot read {}, skipping sync: {}",
+                    dir.display(),
+                    e
+                );
+                return Ok(());
+            }
+            Err(_) => {
+                tokio::fs::create_dir_all(dir).await?;
+                tokio::fs::read_dir(dir).await?
+            }
+        };
+        let mut files: Vec<(PathBuf, Format)> = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if let Some(format) = Format::from_path(&path) {
+                files.push((path, format));
+            }
+        }
+
+        let mut known = HashSet::new();
+        let mut tracked_playlists = HashSet::new();
+        for synced in synced_files {
+            let path = PathBuf::from(&synced.path);
+            let format = Format::from_path(&path).unwrap_or(Format::M3u);
+            known.insert(path.clone());
+
+            if !tokio::fs::try_exists(&path).await.unwrap_or(true) {
+                info!(
+                    "playlists: {} was removed, deleting its playlist",
+                    synced.path
+                );
+                self.delete(&synced.playlist_id).await?;
+                self.forget_synced_file(&synced.path).await?;
+                continue;
+            }
+            let Some(playlist) = self.get(&synced.playlist_id).await? else {
+                info!(
+                    "playlists: playlist of {} was deleted, removing it",
+                    synced.path
+                );
+                if let Err(e) = tokio::fs::remove_file(&path).await {
+                    warn!("playlists: cannot remove {}: {}", path.display(), e);
+                    continue;
+                }
+                self.forget_synced_file(&synced.path).await?;
+                continue;
+            };
+            tracked_playlists.insert(playlist.id.clone());
+
+            // One unreadable or unparsable file doesn't hold up the others.
+            let result = match mtime(&path).await {
+                Ok(modified) if modified > synced.modified_at => {
+                    self.read_file(&path, format, Some(&playlist.id)).await
+                }
+                Ok(_) if playlist.updated_at > synced.synced_at => {
+                    self.write_file(&path, format, &playlist.id).await
+                }
+                Ok(_) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("playlists: cannot sync {}: {}", path.display(), e);
+            }
+        }
+
+        for (path, format) in files {
+            if known.contains(&path) {
+                continue;
+            }
+            match self.read_file(&path, format, None).await {
+                Ok(()) => info!("playlists: imported {}", path.display()),
+                Err(e) => warn!("playlists: cannot import {}: {}", path.display(), e),
+            }
+        }
+
+        for playlist in self.list().await? {
+            if tracked_playlists.contains(&playlist.id) {
+                continue;
+            }
+            // Playlists imported above were just given their file.
+            if self.has_synced_file(&playlist.id).await? {
+                continue;
+            }
+            let path = free_path(dir, &playlist.name).await;
+            if let Err(e) = self.write_file(&path, Format::M3u, &playlist.id).await {
+                warn!("playlists: cannot write {}: {}", path.display(), e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn has_synced_file(&self, playlist_id: &str) -> Result<bool> {
+        let row = sqlx::query("SELECT 1 FROM playlist_files WHERE playlist_id = ?")
+            .bind(playlist_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    async fn store() -> PlaylistStore {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for migration in [
+            include_str!("../../library/migrations/20240923093823_create_tables.sql"),
+            include_str!("../../library/migrations/20241020125757_add-album_id-column.sql"),
+            include_str!("../../library/migrations/20260425000000_add_playlist_tables.sql"),
+            include_str!("../../library/migrations/20260428000000_add_is_remote_to_track.sql"),
+            include_str!("../../library/migrations/20261019000200_add_track_media_type.sql"),
+            include_str!("../../library/migrations/20261019000800_add_playlist_files.sql"),
+            include_str!("../../library/migrations/20261019001000_add_ratings.sql"),
+            include_str!("../../library/migrations/20261019001100_add_track_hidden.sql"),
+            include_str!("../../library/migrations/20261019001200_add_remote_sources.sql"),
+        ] {
+            pool.execute(migration).await.unwrap();
+        }
+        PlaylistStore::new(pool)
+    }
+
+    #[tokio::test]
+    async fn missing_folder_does_not_delete_its_playlists() {
+        let root = std::env::temp_dir().join(format!("rockbox-sync-{}", uuid::Uuid::new_v4()));
+        let music = root.join("music");
+        let other = root.join("other");
+        std::fs::create_dir_all(&other).unwrap();
+        let store = store().await;
+        let playlist = store.create("Road Trip", None, None, None).await.unwrap();
+
+        store.sync_dir(&music).await.unwrap();
+        assert!(music.join("Road Trip.m3u8").exists());
+
+        // The drive goes away: nothing is deleted and the mount point is
+        // not recreated.
+        std::fs::remove_dir_all(&music).unwrap();
+        store.sync_dir(&music).await.unwrap();
+        assert!(!music.exists());
+        assert!(store.get(&playlist.id).await.unwrap().is_some());
+
+        // Another folder's pass ignores the files synced with this one.
+        store.sync_dir(&other).await.unwrap();
+        assert!(store.get(&playlist.id).await.unwrap().is_some());
+        assert!(std::fs::read_dir(&other).unwrap().next().is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn unparsable_file_does_not_stop_the_pass() {
+        let dir = std::env::temp_dir().join(format!("rockbox-sync-{}", uuid::Uuid::new_v4()));
+        let store = store().await;
+        store.create("Road Trip", None, None, None).await.unwrap();
+        store.sync_dir(&dir).await.unwrap();
+
+        // The synced file turns into something that isn't text, and a new
+        // one shows up next to it.
+        let road_trip = dir.join("Road Trip.m3u8");
+        std::fs::write(&road_trip, [0xff, 0xfe, 0x00, 0xc3]).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&road_trip)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        std::fs::write(dir.join("Commute.m3u8"), "#EXTM3U\n").unwrap();
+
+        store.sync_dir(&dir).await.unwrap();
+        let names: HashSet<String> = store
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert!(names.contains("Commute"));
+        assert!(names.contains("Road Trip"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,433 @@
+//! Importing playlist files into saved playlists and exporting them back.
+//!
+//! An entry is matched to a library track by its path (resolved against
+//! the file's folder when relative), then by the last two components of
+//! the path, then by title and artist with the duration as a tie-breaker.
+//! Entries that match nothing are reported back rather than dropped
+//! silently.
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::Result;
+use rockbox_library::{entity::track::Track, repo};
+use serde::Serialize;
+
+use crate::{
+    formats::{self, Entry, Format, PlaylistFile},
+    Playlist, PlaylistStore,
+};
+
+/// Durations further apart than this rule a title/artist match out.
+const DURATION_SLACK_MS: i64 = 5000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub playlist: Playlist,
+    pub matched: usize,
+    pub unmatched: Vec<Entry>,
+}
+
+/// Lowercase alphanumerics and single spaces, without a leading "the".
+fn normalize(s: &str) -> String {
+    let s: String = s
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    match s.strip_prefix("the ") {
+        Some(rest) => rest.to_string(),
+        None => s,
+    }
+}
+
+/// `path` with `.` and `..` resolved, without touching the filesystem.
+fn clean(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// The library path an entry's location points at. Windows separators are
+/// accepted; relative locations are taken from `base_dir`.
+fn resolve(location: &str, base_dir: Option<&Path>) -> String {
+    if location.contains("://") {
+        return location.to_string();
+    }
+    let location = location.replace('\\', "/");
+    let path = Path::new(&location);
+    let path = match base_dir {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path.to_path_buf(),
+    };
+    clean(&path).to_string_lossy().into_owned()
+}
+
+/// Folder and file name, which usually survive a move to another machine.
+fn tail(path: &str) -> Option<String> {
+    let path = path.replace('\\', "/");
+    let mut parts = path.rsplit('/').filter(|p| !p.is_empty());
+    let file = parts.next()?;
+    let dir = parts.next()?;
+    Some(format!("{}/{}", dir, file).to_lowercase())
+}
+
+/// Artist and title guessed from a file name like `01 - Artist - Title.mp3`.
+fn from_file_name(location: &str) -> (Option<String>, Option<String>) {
+    let location = location.replace('\\', "/");
+    let stem = Path::new(&location)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = stem
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches([' ', '.', '-', '_']);
+    match stem.split_once(" - ") {
+        Some((artist, title)) => (Some(artist.to_string()), Some(title.to_string())),
+        None => (None, Some(stem.to_string())),
+    }
+}
+
+/// `path` relative to `base`, climbing out with `..` where needed.
+fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let path: Vec<Component> = path.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut out = PathBuf::new();
+    for _ in common..base.len() {
+        out.push("..");
+    }
+    for component in &path[common..] {
+        out.push(component);
+    }
+    out
+}
+
+struct Candidate {
+    id: String,
+    artists: [String; 2],
+    length: i64,
+}
+
+/// Index of the library for matching playlist entries.
+pub struct Matcher {
+    by_path: HashMap<String, String>,
+    by_tail: HashMap<String, Vec<String>>,
+    by_title: HashMap<String, Vec<Candidate>>,
+}
+
+impl Matcher {
+    pub fn new(tracks: Vec<Track>) -> Self {
+        let mut matcher = Matcher {
+            by_path: HashMap::new(),
+            by_tail: HashMap::new(),
+            by_title: HashMap::new(),
+        };
+        for track in tracks {
+            if let Some(tail) = tail(&track.path) {
+                matcher
+                    .by_tail
+                    .entry(tail)
+                    .or_default()
+                    .push(track.id.clone());
+            }
+            matcher
+                .by_title
+                .entry(normalize(&track.title))
+                .or_default()
+                .push(Candidate {
+                    id: track.id.clone(),
+                    artists: [normalize(&track.artist), normalize(&track.album_artist)],
+                    length: track.length as i64,
+                });
+            matcher.by_path.insert(track.path, track.id);
+        }
+        matcher
+    }
+
+    pub async fn load(store: &PlaylistStore) -> Result<Self> {
+        Ok(Matcher::new(repo::track::all(store.pool.clone()).await?))
+    }
+
+    /// Id of the library track `entry` stands for.
+    pub fn find(&self, entry: &Entry, base_dir: Option<&Path>) -> Option<String> {
+        let path = resolve(&entry.location, base_dir);
+        if let Some(id) = self.by_path.get(&path) {
+            return Some(id.clone());
+        }
+        if let Some([id]) = tail(&path)
+            .and_then(|t| self.by_tail.get(&t))
+            .map(Vec::as_slice)
+        {
+            return Some(id.clone());
+        }
+
+        let (file_artist, file_title) = from_file_name(&entry.location);
+        let title = normalize(entry.title.as_ref().or(file_title.as_ref())?);
+        let artist = entry
+            .artist
+            .as_ref()
+            .or(file_artist.as_ref())
+            .map(|a| normalize(a))
+            .filter(|a| !a.is_empty());
+        self.by_title
+            .get(&title)?
+            .iter()
+            .filter(|c| match &artist {
+                Some(artist) => c
+                    .artists
+                    .iter()
+                    .any(|a| !a.is_empty() && (a.contains(artist) || artist.contains(a))),
+                None => true,
+            })
+            .map(|c| {
+                let gap = match entry.duration_ms {
+                    Some(ms) if c.length > 0 => (ms - c.length).abs(),
+                    _ => 0,
+                };
+                (gap, c)
+            })
+            .filter(|(gap, _)| *gap <= DURATION_SLACK_MS)
+            .min_by_key(|(gap, _)| *gap)
+            .map(|(_, c)| c.id.clone())
+    }
+}
+
+impl PlaylistStore {
+    /// Library tracks for `entries`, in order, and the entries that match
+    /// none.
+    pub async fn match_entries(
+        &self,
+        entries: Vec<Entry>,
+        base_dir: Option<&Path>,
+    ) -> Result<(Vec<String>, Vec<Entry>)> {
+        let matcher = Matcher::load(self).await?;
+        let mut ids = Vec::with_capacity(entries.len());
+        let mut unmatched = Vec::new();
+        for entry in entries {
+            let id = match matcher.find(&entry, base_dir) {
+                Some(id) => Some(id),
+                // Streams are not in the index; look them up as they are.
+                None if entry.location.contains("://") => {
+                    repo::track::find_by_path(self.pool.clone(), &entry.location)
+                        .await?
+                        .map(|t| t.id)
+                }
+                None => None,
+            };
+            match id {
+                Some(id) => ids.push(id),
+                None => unmatched.push(entry),
+            }
+        }
+        Ok((ids, unmatched))
+    }
+
+    /// Create a saved playlist from a parsed playlist file. It is named
+    /// `name`, else the file's own title, else "Imported playlist".
+    pub async fn import_playlist(
+        &self,
+        file: PlaylistFile,
+        name: Option<&str>,
+        folder_id: Option<&str>,
+        base_dir: Option<&Path>,
+    ) -> Result<ImportReport> {
+        let name = name
+            .map(str::to_string)
+            .or(file.title)
+            .unwrap_or_else(|| "Imported playlist".to_string());
+        let (ids, unmatched) = self.match_entries(file.entries, base_dir).await?;
+        let playlist = self.create(&name, None, None, folder_id).await?;
+        self.add_tracks(&playlist.id, &ids).await?;
+        let playlist = self.get(&playlist.id).await?.unwrap_or(playlist);
+        Ok(ImportReport {
+            playlist,
+            matched: ids.len(),
+            unmatched,
+        })
+    }
+
+    /// The saved playlist `id` as a playlist file, or `None` when there is
+    /// no such playlist. With `relative_to`, local paths are written
+    /// relative to that folder.
+    pub async fn export_playlist(
+        &self,
+        id: &str,
+        format: Format,
+        relative_to: Option<&Path>,
+    ) -> Result<Option<String>> {
+        let Some(playlist) = self.get(id).await? else {
+            return Ok(None);
+        };
+        let mut entries = Vec::new();
+        for track_id in self.get_track_ids(id).await? {
+            let track = match repo::track::find(self.pool.clone(), &track_id).await? {
+                Some(track) => Some(track),
+                None => repo::track::find_by_path(self.pool.clone(), &track_id).await?,
+            };
+            let Some(track) = track else {
+                continue;
+            };
+            let location = match relative_to {
+                Some(base) if !track.is_remote => relative_to_path(&track.path, base),
+                _ => track.path,
+            };
+            entries.push(Entry {
+                location,
+                title: Some(track.title).filter(|t| !t.is_empty()),
+                artist: Some(track.artist).filter(|a| !a.is_empty()),
+                album: Some(track.album).filter(|a| !a.is_empty()),
+                duration_ms: Some(track.length as i64).filter(|l| *l > 0),
+            });
+        }
+        let file = PlaylistFile {
+            title: Some(playlist.name),
+            entries,
+        };
+        Ok(Some(formats::write(format, &file)?))
+    }
+}
+
+fn relative_to_path(path: &str, base: &Path) -> String {
+    relative_to(&clean(Path::new(path)), &clean(base))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: &str, path: &str, artist: &str, title: &str, length: u32) -> Track {
+        Track {
+            id: id.to_string(),
+            path: path.to_string(),
+            artist: artist.to_string(),
+            title: title.to_string(),
+            length,
+            ..Default::default()
+        }
+    }
+
+    fn matcher() -> Matcher {
+        Matcher::new(vec![
+            track(
+                "army",
+                "/music/Björk/Post/01 Army of Me.flac",
+                "Björk",
+                "Army of Me",
+                234_000,
+            ),
+            track(
+                "live",
+                "/music/Live/Army of Me.flac",
+                "Björk",
+                "Army of Me",
+                400_000,
+            ),
+            track(
+                "who",
+                "/music/The Who/Tommy/Overture.mp3",
+                "The Who",
+                "Overture",
+                300_000,
+            ),
+            track(
+                "other",
+                "/music/Other/Overture.mp3",
+                "Someone",
+                "Overture",
+                120_000,
+            ),
+        ])
+    }
+
+    fn entry(location: &str) -> Entry {
+        Entry {
+            location: location.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matches_by_path() {
+        let m = matcher();
+        let base = Path::new("/music/Playlists");
+        assert_eq!(
+            m.find(&entry("/music/Björk/Post/01 Army of Me.flac"), None)
+                .as_deref(),
+            Some("army")
+        );
+        assert_eq!(
+            m.find(&entry("../Björk/./Post/01 Army of Me.flac"), Some(base))
+                .as_deref(),
+            Some("army")
+        );
+        // Another machine's music folder.
+        assert_eq!(
+            m.find(&entry("D:\\Muziek\\Tommy\\Overture.mp3"), None)
+                .as_deref(),
+            Some("who")
+        );
+    }
+
+    #[test]
+    fn matches_by_tags() {
+        let m = matcher();
+        let tagged = |artist: &str, title: &str, duration_ms: Option<i64>| Entry {
+            location: "nowhere.mp3".to_string(),
+            artist: Some(artist.to_string()),
+            title: Some(title.to_string()),
+            duration_ms,
+            ..Default::default()
+        };
+        assert_eq!(m.find(&tagged("bjork", "ARMY OF ME", None), None), None);
+        assert_eq!(
+            m.find(&tagged("Björk", "Army Of Me!", Some(233_000)), None)
+                .as_deref(),
+            Some("army")
+        );
+        assert_eq!(
+            m.find(&tagged("Björk", "Army of Me", Some(398_000)), None)
+                .as_deref(),
+            Some("live")
+        );
+        assert_eq!(
+            m.find(&tagged("Who", "Overture", None), None).as_deref(),
+            Some("who")
+        );
+        assert_eq!(
+            m.find(&tagged("Björk", "Army of Me", Some(10_000)), None),
+            None
+        );
+        assert_eq!(
+            m.find(&entry("C:/rips/03 - The Who - Overture.ogg"), None)
+                .as_deref(),
+            Some("who")
+        );
+        assert_eq!(m.find(&entry("/music/Unknown.mp3"), None), None);
+    }
+
+    #[test]
+    fn relative_paths_climb_out() {
+        assert_eq!(
+            relative_to_path("/music/Björk/Post/a.flac", Path::new("/music/Playlists")),
+            "../Björk/Post/a.flac"
+        );
+        assert_eq!(
+            relative_to_path("/music/Playlists/x/a.flac", Path::new("/music/Playlists/")),
+            "x/a.flac"
+        );
+    }
+}
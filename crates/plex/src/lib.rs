@@ -18,6 +18,22 @@ pub struct PlexEntry {
     pub stream_url: Option<String>,
 }
 
+/// A track in a music library section, with the tags needed to index it.
+#[derive(Debug, Clone)]
+pub struct PlexTrack {
+    pub rating_key: String,
+    pub title: String,
+    pub artist: String,
+    pub album_artist: String,
+    pub album: String,
+    pub year: Option<u32>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    /// Length in milliseconds.
+    pub duration: u32,
+    pub stream_url: String,
+}
+
 /// Discover Plex Media Servers on the local network via mDNS (_plex._tcp).
 pub async fn discover_plex_servers() -> Vec<PlexServer> {
     tokio::task::spawn_blocking(|| {
@@ -124,6 +140,51 @@ pub async fn browse_plex(base_url: &str, token: Option<&str>, api_path: &str) ->
     entries
 }
 
+/// Every track in every music section of a Plex server, for mirroring its
+/// catalogue. Fails instead of returning an empty list, so a server that is
+/// down is not mistaken for an empty one.
+pub async fn list_tracks(base_url: &str, token: Option<&str>) -> anyhow::Result<Vec<PlexTrack>> {
+    let xml = fetch_plex_xml(base_url, token, "/library/sections")
+        .await
+        .ok_or_else(|| anyhow::anyhow!("cannot list Plex library sections"))?;
+    let mut tracks = vec![];
+    for section in elements(&xml, "Directory") {
+        if attr_value(section, "type").as_deref() != Some("artist") {
+            continue;
+        }
+        let Some(key) = attr_value(section, "key") else {
+            continue;
+        };
+        // type=10: tracks, flattened across artists and albums.
+        let path = format!("/library/sections/{}/all?type=10", key);
+        let xml = fetch_plex_xml(base_url, token, &path)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("cannot list Plex section {}", key))?;
+        for element in elements(&xml, "Track") {
+            let (Some(rating_key), Some(part_key)) =
+                (attr_value(element, "ratingKey"), extract_part_key(element))
+            else {
+                continue;
+            };
+            let number = |name: &str| attr_value(element, name).and_then(|v| v.parse().ok());
+            let album_artist = attr_value(element, "grandparentTitle").unwrap_or_default();
+            tracks.push(PlexTrack {
+                rating_key,
+                title: attr_value(element, "title").unwrap_or_default(),
+                artist: attr_value(element, "originalTitle").unwrap_or(album_artist.clone()),
+                album_artist,
+                album: attr_value(element, "parentTitle").unwrap_or_default(),
+                year: number("parentYear").or_else(|| number("year")),
+                track_number: number("index"),
+                disc_number: number("parentIndex"),
+                duration: number("duration").unwrap_or(0),
+                stream_url: build_stream_url(base_url, token, &part_key),
+            });
+        }
+    }
+    Ok(tracks)
+}
+
 async fn fetch_plex_xml(base_url: &str, token: Option<&str>, api_path: &str) -> Option<String> {
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(10))
@@ -225,6 +286,32 @@ fn parse_tracks(xml: &str) -> Vec<(String, String, String)> {
     out
 }
 
+/// `<tag .../>` or `<tag ...>...</tag>` elements, opening tag through the
+/// end of the element.
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{} ", tag);
+    let close = format!("</{}>", tag);
+    let mut out = vec![];
+    let mut pos = 0;
+    while let Some(rel) = xml[pos..].find(&open) {
+        let start = pos + rel;
+        let Some(head) = xml[start..].find('>') else {
+            break;
+        };
+        let end = if xml[..start + head].ends_with('/') {
+            start + head + 1
+        } else {
+            match xml[start..].find(&close) {
+                Some(p) => start + p + close.len(),
+                None => break,
+            }
+        };
+        out.push(&xml[start..end]);
+        pos = end;
+    }
+    out
+}
+
 fn extract_part_key(element: &str) -> Option<String> {
     let part_start = element.find("<Part ")?;
     attr_value(&element[part_start..], "key")
@@ -245,7 +332,8 @@ fn build_stream_url(base_url: &str, token: Option<&str>, part_key: &str) -> Stri
 
 fn attr_value(element: &str, name: &str) -> Option<String> {
     for quote in ['"', '\''] {
-        let needle = format!("{}={}", name, quote);
+        // Leading space so `parentTitle` does not match `grandparentTitle`.
+        let needle = format!(" {}={}", name, quote);
         if let Some(start) = element.find(&needle) {
             let after = start + needle.len();
             if let Some(end) = element[after..].find(quote) {
@@ -0,0 +1,310 @@
+//! RSS 2.0 (with the iTunes extensions) and Atom feed parsing.
+//!
+//! Only what the podcast store needs is extracted: channel metadata and the
+//! entries that carry an audio enclosure. Entries without one are skipped.
+
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Feed {
+    pub title: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub link: Option<String>,
+    pub image_url: Option<String>,
+    pub episodes: Vec<FeedEpisode>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedEpisode {
+    /// `<guid>` / `<id>`, falling back to the enclosure URL.
+    pub guid: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub audio_url: String,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub duration_secs: Option<i64>,
+    /// Unix timestamp.
+    pub published_at: Option<i64>,
+}
+
+fn attr(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == name)
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
+
+/// Parse `<itunes:duration>`: plain seconds, `MM:SS` or `HH:MM:SS`.
+pub fn parse_duration(s: &str) -> Option<i64> {
+    let mut secs = 0i64;
+    for part in s.trim().split(':') {
+        let n: f64 = part.trim().parse().ok()?;
+        secs = secs * 60 + n as i64;
+    }
+    Some(secs)
+}
+
+/// Parse an RFC 2822 (RSS) or RFC 3339 (Atom) date into a Unix timestamp.
+pub fn parse_date(s: &str) -> Option<i64> {
+    let s = s.trim();
+    DateTime::parse_from_rfc2822(s)
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .ok()
+        .map(|d| d.timestamp())
+}
+
+/// Handle the attributes of a start or empty tag.
+fn on_tag(e: &BytesStart, in_entry: bool, feed: &mut Feed, episode: &mut FeedEpisode) {
+    match e.name().as_ref() {
+        b"enclosure" if in_entry => {
+            if let Some(url) = attr(e, b"url") {
+                episode.audio_url = url;
+                episode.mime_type = attr(e, b"type");
+                episode.file_size = attr(e, b"length").and_then(|l| l.parse().ok());
+            }
+        }
+        b"link" => match attr(e, b"rel").as_deref() {
+            Some("enclosure") if in_entry => {
+                if let Some(url) = attr(e, b"href") {
+                    episode.audio_url = url;
+                    episode.mime_type = attr(e, b"type");
+                    episode.file_size = attr(e, b"length").and_then(|l| l.parse().ok());
+                }
+            }
+            None | Some("alternate") if !in_entry && feed.link.is_none() => {
+                feed.link = attr(e, b"href");
+            }
+            _ => {}
+        },
+        b"itunes:image" if !in_entry => {
+            if let Some(href) = attr(e, b"href") {
+                feed.image_url = Some(href);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parse an RSS 2.0 or Atom document.
+pub fn parse_feed(xml: &str) -> Result<Feed> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut feed = Feed::default();
+    let mut episode = FeedEpisode::default();
+    let mut in_entry = false;
+    let mut is_feed = false;
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = e.name().as_ref().to_vec();
+                match name.as_slice() {
+                    b"rss" | b"feed" | b"rdf:RDF" => is_feed = true,
+                    b"item" | b"entry" => {
+                        in_entry = true;
+                        episode = FeedEpisode::default();
+                    }
+                    _ => {}
+                }
+                on_tag(&e, in_entry, &mut feed, &mut episode);
+                path.push(name);
+                text.clear();
+            }
+            Event::Empty(e) => on_tag(&e, in_entry, &mut feed, &mut episode),
+            Event::Text(e) => text.push_str(&e.unescape()?),
+            Event::CData(e) => text.push_str(&String::from_utf8_lossy(&e)),
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(|p| p.as_slice()).unwrap_or_default();
+                let value = std::mem::take(&mut text);
+                if in_entry {
+                    match name.as_slice() {
+                        b"item" | b"entry" => {
+                            in_entry = false;
+                            let mut done = std::mem::take(&mut episode);
+                            if !done.audio_url.is_empty() {
+                                if done.guid.is_empty() {
+                                    done.guid = done.audio_url.clone();
+                                }
+                                if done.title.is_empty() {
+                                    done.title = done.guid.clone();
+                                }
+                                feed.episodes.push(done);
+                            }
+                        }
+                        b"title" => episode.title = value.trim().to_string(),
+                        b"guid" | b"id" => episode.guid = value.trim().to_string(),
+                        b"description" | b"summary" | b"content" | b"itunes:summary" => {
+                            if episode.description.is_none() {
+                                episode.description = non_empty(&value);
+                            }
+                        }
+                        b"itunes:duration" => episode.duration_secs = parse_duration(&value),
+                        b"pubDate" | b"published" | b"dc:date" => {
+                            episode.published_at = parse_date(&value)
+                        }
+                        b"updated" if episode.published_at.is_none() => {
+                            episode.published_at = parse_date(&value)
+                        }
+                        _ => {}
+                    }
+                } else {
+                    match (parent, name.as_slice()) {
+                        (b"channel" | b"feed", b"title") => feed.title = value.trim().to_string(),
+                        (b"channel", b"description") | (b"feed", b"subtitle") => {
+                            feed.description = non_empty(&value)
+                        }
+                        (b"channel", b"itunes:summary") if feed.description.is_none() => {
+                            feed.description = non_empty(&value)
+                        }
+                        (b"channel", b"link") if feed.link.is_none() => {
+                            feed.link = non_empty(&value)
+                        }
+                        (b"channel", b"itunes:author") | (b"author", b"name") => {
+                            feed.author = non_empty(&value)
+                        }
+                        (b"image", b"url") | (b"feed", b"logo") if feed.image_url.is_none() => {
+                            feed.image_url = non_empty(&value)
+                        }
+                        (b"feed", b"icon") if feed.image_url.is_none() => {
+                            feed.image_url = non_empty(&value)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !is_feed {
+        return Err(anyhow!("not an RSS or Atom feed"));
+    }
+    if feed.title.is_empty() {
+        feed.title = feed
+            .link
+            .clone()
+            .unwrap_or_else(|| "Untitled podcast".into());
+    }
+    Ok(feed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Rockbox Weekly</title>
+    <link>https://example.com/show</link>
+    <description><![CDATA[News & <b>notes</b>]]></description>
+    <itunes:author>The Team</itunes:author>
+    <itunes:image href="https://example.com/cover.jpg"/>
+    <item>
+      <title>Episode 2</title>
+      <guid isPermaLink="false">ep-2</guid>
+      <pubDate>Tue, 10 Jun 2025 08:00:00 +0000</pubDate>
+      <itunes:duration>01:02:03</itunes:duration>
+      <enclosure url="https://example.com/ep2.mp3" type="audio/mpeg" length="1234"/>
+    </item>
+    <item>
+      <title>Blog post without audio</title>
+      <guid>post-1</guid>
+    </item>
+    <item>
+      <title>Episode 1</title>
+      <itunes:duration>95</itunes:duration>
+      <enclosure url="https://example.com/ep1.mp3" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn parses_rss_with_itunes_extensions() {
+        let feed = parse_feed(RSS).unwrap();
+        assert_eq!(feed.title, "Rockbox Weekly");
+        assert_eq!(feed.description.as_deref(), Some("News & <b>notes</b>"));
+        assert_eq!(feed.author.as_deref(), Some("The Team"));
+        assert_eq!(feed.link.as_deref(), Some("https://example.com/show"));
+        assert_eq!(
+            feed.image_url.as_deref(),
+            Some("https://example.com/cover.jpg")
+        );
+        assert_eq!(feed.episodes.len(), 2);
+
+        let ep2 = &feed.episodes[0];
+        assert_eq!(ep2.guid, "ep-2");
+        assert_eq!(ep2.audio_url, "https://example.com/ep2.mp3");
+        assert_eq!(ep2.mime_type.as_deref(), Some("audio/mpeg"));
+        assert_eq!(ep2.file_size, Some(1234));
+        assert_eq!(ep2.duration_secs, Some(3723));
+        assert_eq!(ep2.published_at, Some(1749542400));
+
+        // No <guid>: the enclosure URL identifies the episode.
+        assert_eq!(feed.episodes[1].guid, "https://example.com/ep1.mp3");
+        assert_eq!(feed.episodes[1].duration_secs, Some(95));
+    }
+
+    #[test]
+    fn parses_atom_enclosure_links() {
+        let atom = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Atom Cast</title>
+  <subtitle>An Atom podcast</subtitle>
+  <link href="https://atom.example/"/>
+  <author><name>Jane</name></author>
+  <logo>https://atom.example/logo.png</logo>
+  <entry>
+    <id>urn:uuid:1</id>
+    <title>First</title>
+    <published>2025-01-02T03:04:05Z</published>
+    <summary>Hello</summary>
+    <link rel="alternate" href="https://atom.example/first"/>
+    <link rel="enclosure" type="audio/ogg" length="42" href="https://atom.example/first.ogg"/>
+  </entry>
+</feed>"#;
+        let feed = parse_feed(atom).unwrap();
+        assert_eq!(feed.title, "Atom Cast");
+        assert_eq!(feed.description.as_deref(), Some("An Atom podcast"));
+        assert_eq!(feed.author.as_deref(), Some("Jane"));
+        assert_eq!(feed.link.as_deref(), Some("https://atom.example/"));
+        assert_eq!(
+            feed.image_url.as_deref(),
+            Some("https://atom.example/logo.png")
+        );
+        assert_eq!(feed.episodes.len(), 1);
+        let ep = &feed.episodes[0];
+        assert_eq!(ep.guid, "urn:uuid:1");
+        assert_eq!(ep.audio_url, "https://atom.example/first.ogg");
+        assert_eq!(ep.mime_type.as_deref(), Some("audio/ogg"));
+        assert_eq!(ep.file_size, Some(42));
+        assert_eq!(ep.description.as_deref(), Some("Hello"));
+        assert_eq!(ep.published_at, Some(1735787045));
+    }
+
+    #[test]
+    fn rejects_non_feed_documents() {
+        assert!(parse_feed("<html><body>nope</body></html>").is_err());
+    }
+}
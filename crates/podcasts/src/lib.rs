@@ -0,0 +1,797 @@
+pub mod feed;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use feed::{Feed, FeedEpisode};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Episode and channel states, named after the Subsonic `PodcastStatus`
+/// values so the navidrome handlers can pass them through unchanged.
+pub mod status {
+    pub const NEW: &str = "new";
+    pub const DOWNLOADING: &str = "downloading";
+    pub const COMPLETED: &str = "completed";
+    pub const ERROR: &str = "error";
+    pub const DELETED: &str = "deleted";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodcastChannel {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub link: Option<String>,
+    pub image_url: Option<String>,
+    pub auto_download: bool,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub last_refreshed: Option<i64>,
+    pub episode_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodcastEpisode {
+    pub id: String,
+    pub channel_id: String,
+    pub guid: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub audio_url: String,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub duration_secs: Option<i64>,
+    pub published_at: Option<i64>,
+    pub status: String,
+    pub local_path: Option<String>,
+    pub position_ms: i64,
+    pub played: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl PodcastEpisode {
+    /// Where the player should read the episode from: the downloaded copy
+    /// when there is one, the enclosure URL (streamed by netstream) otherwise.
+    pub fn playback_path(&self) -> String {
+        match &self.local_path {
+            Some(path) if self.status == status::COMPLETED && Path::new(path).exists() => {
+                path.clone()
+            }
+            _ => self.audio_url.clone(),
+        }
+    }
+}
+
+const CHANNEL_COLUMNS: &str = "c.id, c.url, c.title, c.description, c.author, c.link, \
+     c.image_url, c.auto_download, c.status, c.error_message, c.last_refreshed, \
+     (SELECT COUNT(*) FROM podcast_episodes e WHERE e.channel_id = c.id), \
+     c.created_at, c.updated_at";
+
+const EPISODE_COLUMNS: &str = "id, channel_id, guid, title, description, audio_url, mime_type, \
+     file_size, duration_secs, published_at, status, local_path, position_ms, played, \
+     created_at, updated_at";
+
+fn channel_from_row(r: SqliteRow) -> PodcastChannel {
+    PodcastChannel {
+        id: r.get(0),
+        url: r.get(1),
+        title: r.get(2),
+        description: r.get(3),
+        author: r.get(4),
+        link: r.get(5),
+        image_url: r.get(6),
+        auto_download: r.get::<i64, _>(7) != 0,
+        status: r.get(8),
+        error_message: r.get(9),
+        last_refreshed: r.get(10),
+        episode_count: r.get(11),
+        created_at: r.get(12),
+        updated_at: r.get(13),
+    }
+}
+
+fn episode_from_row(r: SqliteRow) -> PodcastEpisode {
+    PodcastEpisode {
+        id: r.get(0),
+        channel_id: r.get(1),
+        guid: r.get(2),
+        title: r.get(3),
+        description: r.get(4),
+        audio_url: r.get(5),
+        mime_type: r.get(6),
+        file_size: r.get(7),
+        duration_secs: r.get(8),
+        published_at: r.get(9),
+        status: r.get(10),
+        local_path: r.get(11),
+        position_ms: r.get(12),
+        played: r.get::<i64, _>(13) != 0,
+        created_at: r.get(14),
+        updated_at: r.get(15),
+    }
+}
+
+/// Directory downloaded episodes are stored in, one sub-directory per
+/// channel. `ROCKBOX_PODCAST_DIR` overrides `~/.cache/rockbox/podcasts`.
+/// Kept outside `music_dir` so the library scanner never indexes episodes.
+pub fn download_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("ROCKBOX_PODCAST_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".cache/rockbox/podcasts")
+}
+
+/// Feeds and enclosures are only ever fetched over http(s). A feed is
+/// untrusted input, so a `file://` or bare path enclosure must not get a
+/// local file copied into the download directory.
+fn is_remote(url: &str) -> bool {
+    let scheme = url.split_once("://").map(|(scheme, _)| scheme);
+    scheme.is_some_and(|s| s.eq_ignore_ascii_case("http") || s.eq_ignore_ascii_case("https"))
+}
+
+fn extension_for(episode: &PodcastEpisode) -> &'static str {
+    match episode.mime_type.as_deref() {
+        Some("audio/mpeg") | Some("audio/mp3") => return "mp3",
+        Some("audio/mp4") | Some("audio/x-m4a") | Some("audio/m4a") => return "m4a",
+        Some("audio/aac") => return "aac",
+        Some("audio/ogg") => return "ogg",
+        Some("audio/opus") => return "opus",
+        Some("audio/flac") => return "flac",
+        _ => {}
+    }
+    let path = episode.audio_url.split(['?', '#']).next().unwrap_or("");
+    match path.rsplit('.').next().map(|e| e.to_ascii_lowercase()) {
+        Some(ext) if ext == "m4a" || ext == "m4b" => "m4a",
+        Some(ext) if ext == "ogg" || ext == "oga" => "ogg",
+        Some(ext) if ext == "opus" => "opus",
+        Some(ext) if ext == "aac" => "aac",
+        Some(ext) if ext == "flac" => "flac",
+        _ => "mp3",
+    }
+}
+
+#[derive(Clone)]
+pub struct PodcastStore {
+    pool: Pool<Sqlite>,
+    client: reqwest::Client,
+    download_dir: PathBuf,
+}
+
+impl PodcastStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self {
+            pool,
+            client: reqwest::Client::new(),
+            download_dir: download_dir(),
+        }
+    }
+
+    pub fn with_download_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.download_dir = dir.into();
+        self
+    }
+
+    // ── Channels ───────────────────────────────────────────────────────────
+
+    pub async fn list_channels(&self) -> Result<Vec<PodcastChannel>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {CHANNEL_COLUMNS} FROM podcast_channels c ORDER BY c.title COLLATE NOCASE ASC"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(channel_from_row).collect())
+    }
+
+    pub async fn get_channel(&self, id: &str) -> Result<Option<PodcastChannel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {CHANNEL_COLUMNS} FROM podcast_channels c WHERE c.id = ?"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(channel_from_row))
+    }
+
+    pub async fn find_channel_by_url(&self, url: &str) -> Result<Option<PodcastChannel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {CHANNEL_COLUMNS} FROM podcast_channels c WHERE c.url = ?"
+        ))
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(channel_from_row))
+    }
+
+    /// Subscribe to the feed at `url` and import its episodes. Subscribing
+    /// twice to the same URL refreshes the existing channel instead.
+    pub async fn subscribe(&self, url: &str, auto_download: bool) -> Result<PodcastChannel> {
+        let url = url.trim();
+        if let Some(existing) = self.find_channel_by_url(url).await? {
+            self.refresh_channel(&existing.id).await?;
+            return self
+                .get_channel(&existing.id)
+                .await?
+                .ok_or_else(|| anyhow!("podcast channel disappeared"));
+        }
+
+        let feed = self.fetch_feed(url).await?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO podcast_channels (id, url, title, description, author, link, image_url, \
+             auto_download, status, last_refreshed, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(url)
+        .bind(&feed.title)
+        .bind(&feed.description)
+        .bind(&feed.author)
+        .bind(&feed.link)
+        .bind(&feed.image_url)
+        .bind(auto_download as i64)
+        .bind(status::COMPLETED)
+        .bind(now)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        let new_episodes = self.store_episodes(&id, &feed.episodes).await?;
+        if auto_download {
+            // Only the latest episode — back catalogues can be huge.
+            if let Some(latest) = self.list_episodes(&id).await?.into_iter().next() {
+                self.spawn_download(latest.id);
+            }
+        }
+        info!(
+            "podcasts: subscribed to {} ({} episodes)",
+            feed.title,
+            new_episodes.len()
+        );
+        self.get_channel(&id)
+            .await?
+            .ok_or_else(|| anyhow!("podcast channel disappeared"))
+    }
+
+    pub async fn set_auto_download(&self, id: &str, auto_download: bool) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE podcast_channels SET auto_download = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(auto_download as i64)
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unsubscribe and remove every downloaded episode of the channel.
+    pub async fn delete_channel(&self, id: &str) -> Result<bool> {
+        for episode in self.list_episodes(id).await? {
+            remove_file(episode.local_path.as_deref()).await;
+        }
+        let _ = tokio::fs::remove_dir(self.download_dir.join(id)).await;
+        sqlx::query("DELETE FROM podcast_episodes WHERE channel_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        let result = sqlx::query("DELETE FROM podcast_channels WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Refresh ────────────────────────────────────────────────────────────
+
+    /// Re-fetch a channel's feed, update its metadata and add new episodes.
+    /// Returns the new episodes. Fetch errors are recorded on the channel.
+    pub async fn refresh_channel(&self, id: &str) -> Result<Vec<PodcastEpisode>> {
+        let channel = self
+            .get_channel(id)
+            .await?
+            .ok_or_else(|| anyhow!("podcast channel not found"))?;
+        let now = Utc::now().timestamp();
+
+        let feed = match self.fetch_feed(&channel.url).await {
+            Ok(feed) => feed,
+            Err(e) => {
+                sqlx::query(
+                    "UPDATE podcast_channels SET status = ?, error_message = ?, \
+                     last_refreshed = ?, updated_at = ? WHERE id = ?",
+                )
+                .bind(status::ERROR)
+                .bind(e.to_string())
+                .bind(now)
+                .bind(now)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+                return Err(e);
+            }
+        };
+
+        sqlx::query(
+            "UPDATE podcast_channels SET title = ?, description = ?, author = ?, link = ?, \
+             image_url = ?, status = ?, error_message = NULL, last_refreshed = ?, updated_at = ? \
+             WHERE id = ?",
+        )
+        .bind(&feed.title)
+        .bind(&feed.description)
+        .bind(&feed.author)
+        .bind(&feed.link)
+        .bind(&feed.image_url)
+        .bind(status::COMPLETED)
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        let new_episodes = self.store_episodes(id, &feed.episodes).await?;
+        if channel.auto_download {
+            for episode in &new_episodes {
+                self.spawn_download(episode.id.clone());
+            }
+        }
+        Ok(new_episodes)
+    }
+
+    /// Refresh every channel; one failing feed does not stop the others.
+    pub async fn refresh_all(&self) -> Result<usize> {
+        let mut added = 0;
+        for channel in self.list_channels().await? {
+            match self.refresh_channel(&channel.id).await {
+                Ok(new_episodes) => added += new_episodes.len(),
+                Err(e) => warn!("podcasts: refreshing {} failed: {}", channel.url, e),
+            }
+        }
+        Ok(added)
+    }
+
+    async fn fetch_feed(&self, url: &str) -> Result<Feed> {
+        if !is_remote(url) {
+            return Err(anyhow!("podcast feed must be an http(s) URL: {}", url));
+        }
+        let xml = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        feed::parse_feed(&xml)
+    }
+
+    /// Insert the episodes not seen before (keyed by channel + guid) and
+    /// return them, newest first.
+    async fn store_episodes(
+        &self,
+        channel_id: &str,
+        episodes: &[FeedEpisode],
+    ) -> Result<Vec<PodcastEpisode>> {
+        let now = Utc::now().timestamp();
+        let mut new_episodes = Vec::new();
+        for episode in episodes {
+            if !is_remote(&episode.audio_url) {
+                warn!(
+                    "podcasts: skipping episode {} with non-http enclosure {}",
+                    episode.guid, episode.audio_url
+                );
+                continue;
+            }
+            let id = Uuid::new_v4().to_string();
+            let result = sqlx::query(
+                "INSERT INTO podcast_episodes (id, channel_id, guid, title, description, \
+                 audio_url, mime_type, file_size, duration_secs, published_at, status, \
+                 created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+                 ON CONFLICT(channel_id, guid) DO UPDATE SET \
+                   title = excluded.title, \
+                   description = excluded.description, \
+                   audio_url = excluded.audio_url, \
+                   mime_type = excluded.mime_type, \
+                   file_size = excluded.file_size, \
+                   duration_secs = excluded.duration_secs, \
+                   published_at = excluded.published_at",
+            )
+            .bind(&id)
+            .bind(channel_id)
+            .bind(&episode.guid)
+            .bind(&episode.title)
+            .bind(&episode.description)
+            .bind(&episode.audio_url)
+            .bind(&episode.mime_type)
+            .bind(episode.file_size)
+            .bind(episode.duration_secs)
+            .bind(episode.published_at)
+            .bind(status::NEW)
+            .bind(now)
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+            // On conflict the existing row keeps its id, so the freshly
+            // generated one only exists when the episode is new.
+            if result.rows_affected() > 0 {
+                if let Some(episode) = self.get_episode(&id).await? {
+                    new_episodes.push(episode);
+                }
+            }
+        }
+        new_episodes.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+        Ok(new_episodes)
+    }
+
+    // ── Episodes ───────────────────────────────────────────────────────────
+
+    /// Episodes of a channel, newest first.
+    pub async fn list_episodes(&self, channel_id: &str) -> Result<Vec<PodcastEpisode>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {EPISODE_COLUMNS} FROM podcast_episodes WHERE channel_id = ? \
+             ORDER BY published_at DESC, created_at DESC"
+        ))
+        .bind(channel_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(episode_from_row).collect())
+    }
+
+    /// The `count` most recently published episodes across all channels.
+    pub async fn newest_episodes(&self, count: i64) -> Result<Vec<PodcastEpisode>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {EPISODE_COLUMNS} FROM podcast_episodes \
+             ORDER BY published_at DESC, created_at DESC LIMIT ?"
+        ))
+        .bind(count)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(episode_from_row).collect())
+    }
+
+    pub async fn get_episode(&self, id: &str) -> Result<Option<PodcastEpisode>> {
+        let row = sqlx::query(&format!(
+            "SELECT {EPISODE_COLUMNS} FROM podcast_episodes WHERE id = ?"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(episode_from_row))
+    }
+
+    /// Find the episode being played from `path`, which is either its
+    /// enclosure URL (streaming) or its downloaded file.
+    pub async fn find_episode_by_path(&self, path: &str) -> Result<Option<PodcastEpisode>> {
+        let row = sqlx::query(&format!(
+            "SELECT {EPISODE_COLUMNS} FROM podcast_episodes \
+             WHERE local_path = ? OR audio_url = ? LIMIT 1"
+        ))
+        .bind(path)
+        .bind(path)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(episode_from_row))
+    }
+
+    pub async fn set_position(&self, id: &str, position_ms: i64) -> Result<bool> {
+        let result =
+            sqlx::query("UPDATE podcast_episodes SET position_ms = ?, updated_at = ? WHERE id = ?")
+                .bind(position_ms.max(0))
+                .bind(Utc::now().timestamp())
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark an episode played (resetting its resume position) or unplayed.
+    pub async fn set_played(&self, id: &str, played: bool) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE podcast_episodes SET played = ?, position_ms = 0, updated_at = ? WHERE id = ?",
+        )
+        .bind(played as i64)
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Downloads ──────────────────────────────────────────────────────────
+
+    /// Download an episode in the background; its status moves through
+    /// `downloading` to `completed` or `error`.
+    pub fn spawn_download(&self, id: String) {
+        let store = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = store.download_episode(&id).await {
+                error!("podcasts: download of episode {} failed: {}", id, e);
+            }
+        });
+    }
+
+    /// Download an episode and wait for it to finish.
+    pub async fn download_episode(&self, id: &str) -> Result<PodcastEpisode> {
+        let episode = self
+            .get_episode(id)
+            .await?
+            .ok_or_else(|| anyhow!("podcast episode not found"))?;
+        if episode.status == status::COMPLETED
+            && episode
+                .local_path
+                .as_deref()
+                .is_some_and(|p| Path::new(p).exists())
+        {
+            return Ok(episode);
+        }
+
+        self.set_status(id, status::DOWNLOADING, None).await?;
+        let dir = self.download_dir.join(&episode.channel_id);
+        let dest = dir.join(format!("{}.{}", episode.id, extension_for(&episode)));
+        match self.fetch_to_file(&episode.audio_url, &dir, &dest).await {
+            Ok(()) => {
+                let path = dest.to_string_lossy().to_string();
+                self.set_status(id, status::COMPLETED, Some(&path)).await?;
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&dest).await;
+                self.set_status(id, status::ERROR, None).await?;
+                return Err(e);
+            }
+        }
+        self.get_episode(id)
+            .await?
+            .ok_or_else(|| anyhow!("podcast episode disappeared"))
+    }
+
+    async fn fetch_to_file(&self, url: &str, dir: &Path, dest: &Path) -> Result<()> {
+        if !is_remote(url) {
+            return Err(anyhow!("podcast enclosure must be an http(s) URL: {}", url));
+        }
+        tokio::fs::create_dir_all(dir).await?;
+        let mut response = self.client.get(url).send().await?.error_for_status()?;
+        let mut file = tokio::fs::File::create(dest).await?;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Remove the downloaded copy of an episode; it can still be streamed.
+    pub async fn delete_download(&self, id: &str) -> Result<bool> {
+        let Some(episode) = self.get_episode(id).await? else {
+            return Ok(false);
+        };
+        remove_file(episode.local_path.as_deref()).await;
+        self.set_status(id, status::DELETED, None).await?;
+        Ok(true)
+    }
+
+    /// Delete the downloads of episodes that were marked played more than
+    /// `grace_secs` ago. Returns how many files were removed.
+    pub async fn cleanup_played(&self, grace_secs: i64) -> Result<usize> {
+        let cutoff = Utc::now().timestamp() - grace_secs;
+        let rows = sqlx::query(&format!(
+            "SELECT {EPISODE_COLUMNS} FROM podcast_episodes \
+             WHERE played = 1 AND local_path IS NOT NULL AND updated_at <= ?"
+        ))
+        .bind(cutoff)
+        .fetch_all(&self.pool)
+        .await?;
+        let episodes: Vec<PodcastEpisode> = rows.into_iter().map(episode_from_row).collect();
+        for episode in &episodes {
+            remove_file(episode.local_path.as_deref()).await;
+            self.set_status(&episode.id, status::DELETED, None).await?;
+        }
+        Ok(episodes.len())
+    }
+
+    async fn set_status(&self, id: &str, status: &str, local_path: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "UPDATE podcast_episodes SET status = ?, local_path = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(status)
+        .bind(local_path)
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+async fn remove_file(path: Option<&str>) {
+    if let Some(path) = path {
+        if let Err(e) = tokio::fs::remove_file(path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("podcasts: could not remove {}: {}", path, e);
+            }
+        }
+    }
+}
+
+/// Refresh all feeds and clean up played downloads every
+/// `ROCKBOX_PODCAST_REFRESH_SECS` seconds (default `3600`, `0` disables).
+/// Played episodes keep their download for one refresh interval so an
+/// episode that was just finished is never deleted under the player.
+pub fn start_refresh_task(store: PodcastStore) {
+    let secs: u64 = std::env::var("ROCKBOX_PODCAST_REFRESH_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600);
+    if secs == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(secs));
+        loop {
+            interval.tick().await;
+            match store.refresh_all().await {
+                Ok(0) => {}
+                Ok(n) => info!("podcasts: {} new episodes", n),
+                Err(e) => error!("podcasts: refresh failed: {}", e),
+            }
+            match store.cleanup_played(secs as i64).await {
+                Ok(0) => {}
+                Ok(n) => info!("podcasts: removed {} played downloads", n),
+                Err(e) => error!("podcasts: cleanup failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Mock, ServerGuard};
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    async fn store(dir: &Path) -> PodcastStore {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        pool.execute(include_str!(
+            "../../library/migrations/20261019000100_add_podcast_tables.sql"
+        ))
+        .await
+        .unwrap();
+        PodcastStore::new(pool).with_download_dir(dir.join("downloads"))
+    }
+
+    /// An RSS feed with one episode per `(guid, enclosure url)`.
+    fn feed_xml(episodes: &[(&str, &str)]) -> String {
+        let items: String = episodes
+            .iter()
+            .enumerate()
+            .map(|(i, (guid, audio_url))| {
+                format!(
+                    "<item><title>{guid}</title><guid>{guid}</guid>\
+                     <pubDate>Tue, 1{i} Jun 2025 08:00:00 +0000</pubDate>\
+                     <enclosure url=\"{audio_url}\" type=\"audio/mpeg\"/></item>"
+                )
+            })
+            .collect();
+        format!("<rss><channel><title>Remote Show</title>{items}</channel></rss>")
+    }
+
+    async fn serve_feed(server: &mut ServerGuard, body: String) -> Mock {
+        server
+            .mock("GET", "/feed.xml")
+            .with_header("content-type", "application/rss+xml")
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn subscribe_refresh_download_and_cleanup() {
+        let dir = std::env::temp_dir().join(format!("rockbox-podcasts-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let audio_url = format!("{}/episode.mp3", server.url());
+        let audio = server
+            .mock("GET", "/episode.mp3")
+            .with_header("content-type", "audio/mpeg")
+            .with_body("ID3 not really audio")
+            .create_async()
+            .await;
+        let feed = serve_feed(&mut server, feed_xml(&[("one", &audio_url)])).await;
+        let store = store(&dir).await;
+
+        let url = format!("{}/feed.xml", server.url());
+        let channel = store.subscribe(&url, false).await.unwrap();
+        assert_eq!(channel.title, "Remote Show");
+        assert_eq!(channel.episode_count, 1);
+
+        // Refreshing only adds episodes that were not seen before.
+        feed.remove_async().await;
+        serve_feed(
+            &mut server,
+            feed_xml(&[("one", &audio_url), ("two", &audio_url)]),
+        )
+        .await;
+        let new_episodes = store.refresh_channel(&channel.id).await.unwrap();
+        assert_eq!(new_episodes.len(), 1);
+        assert_eq!(new_episodes[0].guid, "two");
+        assert!(store.refresh_channel(&channel.id).await.unwrap().is_empty());
+
+        let episode = store.download_episode(&new_episodes[0].id).await.unwrap();
+        audio.assert_async().await;
+        assert_eq!(episode.status, status::COMPLETED);
+        let path = episode.local_path.clone().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"ID3 not really audio");
+        assert_eq!(episode.playback_path(), path);
+        let found = store.find_episode_by_path(&path).await.unwrap().unwrap();
+        assert_eq!(found.id, episode.id);
+
+        store.set_position(&episode.id, 42_000).await.unwrap();
+        let episode = store.get_episode(&episode.id).await.unwrap().unwrap();
+        assert_eq!(episode.position_ms, 42_000);
+
+        // Played downloads survive the grace period, then get removed.
+        store.set_played(&episode.id, true).await.unwrap();
+        assert_eq!(store.cleanup_played(3600).await.unwrap(), 0);
+        assert_eq!(store.cleanup_played(0).await.unwrap(), 1);
+        assert!(!Path::new(&path).exists());
+        let episode = store.get_episode(&episode.id).await.unwrap().unwrap();
+        assert_eq!(episode.status, status::DELETED);
+        assert_eq!(episode.playback_path(), episode.audio_url);
+
+        assert!(store.delete_channel(&channel.id).await.unwrap());
+        assert!(store.list_episodes(&channel.id).await.unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn local_feeds_and_enclosures_are_rejected() {
+        let dir = std::env::temp_dir().join(format!("rockbox-podcasts-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let secret = dir.join("secret.txt");
+        std::fs::write(&secret, b"do not copy").unwrap();
+        let store = store(&dir).await;
+
+        let feed = dir.join("feed.xml");
+        std::fs::write(&feed, feed_xml(&[("one", "https://example.com/a.mp3")])).unwrap();
+        assert!(store
+            .subscribe(&format!("file://{}", feed.display()), false)
+            .await
+            .is_err());
+        assert!(store
+            .subscribe(&feed.to_string_lossy(), false)
+            .await
+            .is_err());
+
+        let mut server = mockito::Server::new_async().await;
+        let local = secret.to_string_lossy().to_string();
+        let file_url = format!("file://{local}");
+        serve_feed(
+            &mut server,
+            feed_xml(&[("file", &file_url), ("path", &local)]),
+        )
+        .await;
+        let channel = store
+            .subscribe(&format!("{}/feed.xml", server.url()), false)
+            .await
+            .unwrap();
+        assert_eq!(channel.episode_count, 0);
+        assert!(store.list_episodes(&channel.id).await.unwrap().is_empty());
+        assert!(!dir.join("downloads").join(&channel.id).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -26,6 +26,10 @@ use tokio::sync::Mutex;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::Error as WsError;
 use tokio_tungstenite::{connect_async_tls_with_config, Connector};
+use tonic::codegen::InterceptedService;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
 
 const AUDIO_EXTENSIONS: [&str; 18] = [
     "mp3", "ogg", "flac", "m4a", "aac", "mp4", "alac", "wav", "wv", "mpc", "aiff", "aif", "ac3",
@@ -43,6 +47,27 @@ pub mod api {
     }
 }
 
+type Interceptor = fn(Request<()>) -> Result<Request<()>, Status>;
+
+fn internal_token(mut request: Request<()>) -> Result<Request<()>, Status> {
+    if let Ok(value) = MetadataValue::try_from(rockbox_auth::internal_authorization()) {
+        request.metadata_mut().insert("authorization", value);
+    }
+    Ok(request)
+}
+
+/// Playback client for the daemon's own gRPC server, sending the internal
+/// token so it keeps working once API tokens are enforced.
+async fn playback_client(
+    url: &str,
+) -> Result<PlaybackServiceClient<InterceptedService<Channel, Interceptor>>, Error> {
+    let channel = Channel::from_shared(url.to_string())?.connect().await?;
+    Ok(PlaybackServiceClient::with_interceptor(
+        channel,
+        internal_token as Interceptor,
+    ))
+}
+
 pub async fn run_ws_session(token: String) -> Result<(), Error> {
     // Install the default crypto provider for rustls 0.23+. ring instead
     // of aws_lc_rs because aws-lc-sys's cmake cross-compile doesn't survive
@@ -142,7 +167,7 @@ pub async fn run_ws_session(token: String) -> Result<(), Error> {
     // Retry gRPC connection up to 10 times with 1 second delay
     let mut client = None;
     for attempt in 1..=10 {
-        match PlaybackServiceClient::connect(url.clone()).await {
+        match playback_client(&url).await {
             Ok(c) => {
                 client = Some(c);
                 break;
@@ -228,7 +253,7 @@ pub async fn start_track_stream(tx: tokio::sync::mpsc::Sender<String>) -> Result
     // Retry gRPC connection up to 10 times with 1 second delay
     let mut client = None;
     for attempt in 1..=10 {
-        match PlaybackServiceClient::connect(url.clone()).await {
+        match playback_client(&url).await {
             Ok(c) => {
                 client = Some(c);
                 break;
@@ -281,7 +306,7 @@ async fn start_status_stream(tx: tokio::sync::mpsc::Sender<String>) -> Result<()
     // Retry gRPC connection up to 10 times with 1 second delay
     let mut client = None;
     for attempt in 1..=10 {
-        match PlaybackServiceClient::connect(url.clone()).await {
+        match playback_client(&url).await {
             Ok(c) => {
                 client = Some(c);
                 break;
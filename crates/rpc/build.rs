@@ -12,8 +12,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/rockbox/v1alpha1/metadata.proto",
                 "proto/rockbox/v1alpha1/playback.proto",
                 "proto/rockbox/v1alpha1/playlist.proto",
+                "proto/rockbox/v1alpha1/radio.proto",
                 "proto/rockbox/v1alpha1/saved_playlist.proto",
                 "proto/rockbox/v1alpha1/smart_playlist.proto",
+                "proto/rockbox/v1alpha1/schedule.proto",
                 "proto/rockbox/v1alpha1/settings.proto",
                 "proto/rockbox/v1alpha1/sound.proto",
                 "proto/rockbox/v1alpha1/system.proto",
@@ -1617,6 +1617,77 @@ pub struct FilterArtistsResponse {
     #[prost(message, repeated, tag = "1")]
     pub artists: ::prost::alloc::vec::Vec<Artist>,
 }
+/// Lyrics come from a sidecar .lrc/.txt or the file's tags. `start_ms` is
+/// unset for unsynced lyrics; `found` is false when the track has none.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LyricLine {
+    #[prost(uint64, optional, tag = "1")]
+    pub start_ms: ::core::option::Option<u64>,
+    #[prost(string, tag = "2")]
+    pub text: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLyricsRequest {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLyricsResponse {
+    #[prost(bool, tag = "1")]
+    pub found: bool,
+    #[prost(bool, tag = "2")]
+    pub synced: bool,
+    #[prost(string, tag = "3")]
+    pub source: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "4")]
+    pub lines: ::prost::alloc::vec::Vec<LyricLine>,
+}
+/// Ratings are 1–5 stars; 0 means unrated. `kind` is "track", "album" or
+/// "artist". Setting a rating of 0 clears it.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Rating {
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub item_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub rating: i32,
+    #[prost(int64, tag = "4")]
+    pub updated_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRatingRequest {
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRatingResponse {
+    #[prost(int32, tag = "1")]
+    pub rating: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetRatingRequest {
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub rating: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetRatingResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRatingsRequest {
+    #[prost(string, optional, tag = "1")]
+    pub kind: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRatingsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub ratings: ::prost::alloc::vec::Vec<Rating>,
+}
 /// Generated client implementations.
 pub mod library_service_client {
     #![allow(
@@ -2013,6 +2084,75 @@ pub mod library_service_client {
             ));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_lyrics(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetLyricsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetLyricsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rockbox.v1alpha1.LibraryService/GetLyrics");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.LibraryService",
+                "GetLyrics",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_rating(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRatingRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRatingResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rockbox.v1alpha1.LibraryService/GetRating");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.LibraryService",
+                "GetRating",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_rating(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetRatingRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetRatingResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rockbox.v1alpha1.LibraryService/SetRating");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.LibraryService",
+                "SetRating",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_ratings(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRatingsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRatingsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rockbox.v1alpha1.LibraryService/GetRatings");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.LibraryService",
+                "GetRatings",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -2101,6 +2241,22 @@ pub mod library_service_server {
             &self,
             request: tonic::Request<super::FilterArtistsRequest>,
         ) -> std::result::Result<tonic::Response<super::FilterArtistsResponse>, tonic::Status>;
+        async fn get_lyrics(
+            &self,
+            request: tonic::Request<super::GetLyricsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetLyricsResponse>, tonic::Status>;
+        async fn get_rating(
+            &self,
+            request: tonic::Request<super::GetRatingRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRatingResponse>, tonic::Status>;
+        async fn set_rating(
+            &self,
+            request: tonic::Request<super::SetRatingRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetRatingResponse>, tonic::Status>;
+        async fn get_ratings(
+            &self,
+            request: tonic::Request<super::GetRatingsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRatingsResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct LibraryServiceServer<T> {
@@ -2858,6 +3014,162 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/rockbox.v1alpha1.LibraryService/GetLyrics" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetLyricsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<T: LibraryService> tonic::server::UnaryService<super::GetLyricsRequest> for GetLyricsSvc<T> {
+                        type Response = super::GetLyricsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetLyricsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as LibraryService>::get_lyrics(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetLyricsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.LibraryService/GetRating" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRatingSvc<T: LibraryService>(pub Arc<T>);
+                    impl<T: LibraryService> tonic::server::UnaryService<super::GetRatingRequest> for GetRatingSvc<T> {
+                        type Response = super::GetRatingResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRatingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as LibraryService>::get_rating(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetRatingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.LibraryService/SetRating" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetRatingSvc<T: LibraryService>(pub Arc<T>);
+                    impl<T: LibraryService> tonic::server::UnaryService<super::SetRatingRequest> for SetRatingSvc<T> {
+                        type Response = super::SetRatingResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetRatingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as LibraryService>::set_rating(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetRatingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.LibraryService/GetRatings" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRatingsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<T: LibraryService> tonic::server::UnaryService<super::GetRatingsRequest> for GetRatingsSvc<T> {
+                        type Response = super::GetRatingsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRatingsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as LibraryService>::get_ratings(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetRatingsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => Box::pin(async move {
                     let mut response = http::Response::new(empty_body());
                     let headers = response.headers_mut();
@@ -3880,6 +4192,50 @@ pub struct PlayArtistTracksRequest {
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct PlayArtistTracksResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlayTrackRadioRequest {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+    #[prost(int32, optional, tag = "2")]
+    pub limit: ::core::option::Option<i32>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct PlayTrackRadioResponse {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetAutoQueueRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAutoQueueResponse {
+    #[prost(bool, tag = "1")]
+    pub enabled: bool,
+    #[prost(string, tag = "2")]
+    pub source: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub smart_playlist_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub seed_track_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetAutoQueueRequest {
+    #[prost(bool, optional, tag = "1")]
+    pub enabled: ::core::option::Option<bool>,
+    #[prost(string, optional, tag = "2")]
+    pub source: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub smart_playlist_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub seed_track_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetAutoQueueResponse {
+    #[prost(bool, tag = "1")]
+    pub enabled: bool,
+    #[prost(string, tag = "2")]
+    pub source: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub smart_playlist_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub seed_track_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PlayPlaylistRequest {
     #[prost(string, tag = "1")]
     pub playlist_id: ::prost::alloc::string::String,
@@ -4299,26 +4655,83 @@ pub mod playback_service_client {
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn play_playlist(
+        pub async fn play_track_radio(
             &mut self,
-            request: impl tonic::IntoRequest<super::PlayPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::PlayPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::PlayTrackRadioRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlayTrackRadioResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.PlaybackService/PlayPlaylist",
+                "/rockbox.v1alpha1.PlaybackService/PlayTrackRadio",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
                 "rockbox.v1alpha1.PlaybackService",
-                "PlayPlaylist",
+                "PlayTrackRadio",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn play_directory(
+        pub async fn get_auto_queue(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAutoQueueRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetAutoQueueResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.PlaybackService/GetAutoQueue",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.PlaybackService",
+                "GetAutoQueue",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_auto_queue(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetAutoQueueRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetAutoQueueResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.PlaybackService/SetAutoQueue",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.PlaybackService",
+                "SetAutoQueue",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn play_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PlayPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlayPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.PlaybackService/PlayPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.PlaybackService",
+                "PlayPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn play_directory(
             &mut self,
             request: impl tonic::IntoRequest<super::PlayDirectoryRequest>,
         ) -> std::result::Result<tonic::Response<super::PlayDirectoryResponse>, tonic::Status>
@@ -4549,6 +4962,18 @@ pub mod playback_service_server {
             &self,
             request: tonic::Request<super::PlayArtistTracksRequest>,
         ) -> std::result::Result<tonic::Response<super::PlayArtistTracksResponse>, tonic::Status>;
+        async fn play_track_radio(
+            &self,
+            request: tonic::Request<super::PlayTrackRadioRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlayTrackRadioResponse>, tonic::Status>;
+        async fn get_auto_queue(
+            &self,
+            request: tonic::Request<super::GetAutoQueueRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetAutoQueueResponse>, tonic::Status>;
+        async fn set_auto_queue(
+            &self,
+            request: tonic::Request<super::SetAutoQueueRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetAutoQueueResponse>, tonic::Status>;
         async fn play_playlist(
             &self,
             request: tonic::Request<super::PlayPlaylistRequest>,
@@ -5273,6 +5698,132 @@ pub mod playback_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/rockbox.v1alpha1.PlaybackService/PlayTrackRadio" => {
+                    #[allow(non_camel_case_types)]
+                    struct PlayTrackRadioSvc<T: PlaybackService>(pub Arc<T>);
+                    impl<T: PlaybackService>
+                        tonic::server::UnaryService<super::PlayTrackRadioRequest>
+                        for PlayTrackRadioSvc<T>
+                    {
+                        type Response = super::PlayTrackRadioResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PlayTrackRadioRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PlaybackService>::play_track_radio(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PlayTrackRadioSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.PlaybackService/GetAutoQueue" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAutoQueueSvc<T: PlaybackService>(pub Arc<T>);
+                    impl<T: PlaybackService>
+                        tonic::server::UnaryService<super::GetAutoQueueRequest>
+                        for GetAutoQueueSvc<T>
+                    {
+                        type Response = super::GetAutoQueueResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAutoQueueRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PlaybackService>::get_auto_queue(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAutoQueueSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.PlaybackService/SetAutoQueue" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetAutoQueueSvc<T: PlaybackService>(pub Arc<T>);
+                    impl<T: PlaybackService>
+                        tonic::server::UnaryService<super::SetAutoQueueRequest>
+                        for SetAutoQueueSvc<T>
+                    {
+                        type Response = super::SetAutoQueueResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetAutoQueueRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PlaybackService>::set_auto_queue(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetAutoQueueSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/rockbox.v1alpha1.PlaybackService/PlayPlaylist" => {
                     #[allow(non_camel_case_types)]
                     struct PlayPlaylistSvc<T: PlaybackService>(pub Arc<T>);
@@ -5858,10 +6409,16 @@ pub struct InsertArtistTracksRequest {
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct InsertArtistTracksResponse {}
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ShufflePlaylistRequest {
     #[prost(int32, tag = "1")]
     pub start_index: i32,
+    /// "tracks", "album", "artist_spread" or "smart"; defaults to the
+    /// shuffle_mode setting.
+    #[prost(string, optional, tag = "2")]
+    pub mode: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint64, optional, tag = "3")]
+    pub seed: ::core::option::Option<u64>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct ShufflePlaylistResponse {}
@@ -7351,156 +7908,126 @@ pub mod playlist_service_server {
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct PlaylistFolder {
-    #[prost(string, tag = "1")]
-    pub id: ::prost::alloc::string::String,
-    #[prost(string, tag = "2")]
-    pub name: ::prost::alloc::string::String,
-    #[prost(int64, tag = "3")]
-    pub created_at: i64,
-    #[prost(int64, tag = "4")]
-    pub updated_at: i64,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct CreatePlaylistFolderRequest {
-    #[prost(string, tag = "1")]
-    pub name: ::prost::alloc::string::String,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct CreatePlaylistFolderResponse {
-    #[prost(message, optional, tag = "1")]
-    pub folder: ::core::option::Option<PlaylistFolder>,
-}
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct GetPlaylistFoldersRequest {}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetPlaylistFoldersResponse {
-    #[prost(message, repeated, tag = "1")]
-    pub folders: ::prost::alloc::vec::Vec<PlaylistFolder>,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct DeletePlaylistFolderRequest {
-    #[prost(string, tag = "1")]
-    pub id: ::prost::alloc::string::String,
-}
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct DeletePlaylistFolderResponse {}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SavedPlaylist {
+pub struct RadioStation {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
-    #[prost(string, optional, tag = "3")]
-    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "3")]
+    pub stream_url: ::prost::alloc::string::String,
     #[prost(string, optional, tag = "4")]
-    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    pub homepage_url: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "5")]
-    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(int64, tag = "6")]
-    pub track_count: i64,
-    #[prost(int64, tag = "7")]
+    pub genre: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "6")]
+    pub country: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "7")]
+    pub codec: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "8")]
+    pub bitrate: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "9")]
+    pub favicon: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, tag = "10")]
     pub created_at: i64,
-    #[prost(int64, tag = "8")]
+    #[prost(int64, tag = "11")]
     pub updated_at: i64,
 }
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetRadioStationsRequest {}
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSavedPlaylistsRequest {
-    #[prost(string, optional, tag = "1")]
-    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSavedPlaylistsResponse {
+pub struct GetRadioStationsResponse {
     #[prost(message, repeated, tag = "1")]
-    pub playlists: ::prost::alloc::vec::Vec<SavedPlaylist>,
+    pub stations: ::prost::alloc::vec::Vec<RadioStation>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSavedPlaylistRequest {
+pub struct GetRadioStationRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSavedPlaylistResponse {
+pub struct GetRadioStationResponse {
     #[prost(message, optional, tag = "1")]
-    pub playlist: ::core::option::Option<SavedPlaylist>,
+    pub station: ::core::option::Option<RadioStation>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct CreateSavedPlaylistRequest {
+pub struct CreateRadioStationRequest {
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
-    #[prost(string, optional, tag = "2")]
-    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "2")]
+    pub stream_url: ::prost::alloc::string::String,
     #[prost(string, optional, tag = "3")]
-    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    pub homepage_url: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "4")]
-    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(string, repeated, tag = "5")]
-    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    pub genre: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub country: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "6")]
+    pub codec: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "7")]
+    pub bitrate: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "8")]
+    pub favicon: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct CreateSavedPlaylistResponse {
+pub struct CreateRadioStationResponse {
     #[prost(message, optional, tag = "1")]
-    pub playlist: ::core::option::Option<SavedPlaylist>,
+    pub station: ::core::option::Option<RadioStation>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct UpdateSavedPlaylistRequest {
+pub struct UpdateRadioStationRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
-    #[prost(string, optional, tag = "3")]
-    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "3")]
+    pub stream_url: ::prost::alloc::string::String,
     #[prost(string, optional, tag = "4")]
-    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    pub homepage_url: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "5")]
-    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+    pub genre: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "6")]
+    pub country: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "7")]
+    pub codec: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "8")]
+    pub bitrate: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "9")]
+    pub favicon: ::core::option::Option<::prost::alloc::string::String>,
 }
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct UpdateSavedPlaylistResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct DeleteSavedPlaylistRequest {
+pub struct UpdateRadioStationResponse {
+    #[prost(message, optional, tag = "1")]
+    pub station: ::core::option::Option<RadioStation>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteRadioStationRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct DeleteSavedPlaylistResponse {}
+pub struct DeleteRadioStationResponse {}
+/// content is an M3U or PLS station list.
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSavedPlaylistTracksRequest {
+pub struct ImportRadioStationsRequest {
     #[prost(string, tag = "1")]
-    pub playlist_id: ::prost::alloc::string::String,
+    pub content: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub genre: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSavedPlaylistTracksResponse {
-    #[prost(string, repeated, tag = "1")]
-    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+pub struct ImportRadioStationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub stations: ::prost::alloc::vec::Vec<RadioStation>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct AddTracksToSavedPlaylistRequest {
+pub struct PlayRadioStationRequest {
     #[prost(string, tag = "1")]
-    pub playlist_id: ::prost::alloc::string::String,
-    #[prost(string, repeated, tag = "2")]
-    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    pub id: ::prost::alloc::string::String,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct AddTracksToSavedPlaylistResponse {}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RemoveTrackFromSavedPlaylistRequest {
-    #[prost(string, tag = "1")]
-    pub playlist_id: ::prost::alloc::string::String,
-    #[prost(string, tag = "2")]
-    pub track_id: ::prost::alloc::string::String,
-}
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct RemoveTrackFromSavedPlaylistResponse {}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct PlaySavedPlaylistRequest {
-    #[prost(string, tag = "1")]
-    pub playlist_id: ::prost::alloc::string::String,
-}
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct PlaySavedPlaylistResponse {}
+pub struct PlayRadioStationResponse {}
 /// Generated client implementations.
-pub mod saved_playlist_service_client {
+pub mod radio_service_client {
     #![allow(
         unused_variables,
         dead_code,
@@ -7511,10 +8038,10 @@ pub mod saved_playlist_service_client {
     use tonic::codegen::http::Uri;
     use tonic::codegen::*;
     #[derive(Debug, Clone)]
-    pub struct SavedPlaylistServiceClient<T> {
+    pub struct RadioServiceClient<T> {
         inner: tonic::client::Grpc<T>,
     }
-    impl SavedPlaylistServiceClient<tonic::transport::Channel> {
+    impl RadioServiceClient<tonic::transport::Channel> {
         /// Attempt to create a new client by connecting to a given endpoint.
         pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
         where
@@ -7525,7 +8052,7 @@ pub mod saved_playlist_service_client {
             Ok(Self::new(conn))
         }
     }
-    impl<T> SavedPlaylistServiceClient<T>
+    impl<T> RadioServiceClient<T>
     where
         T: tonic::client::GrpcService<tonic::body::BoxBody>,
         T::Error: Into<StdError>,
@@ -7543,7 +8070,7 @@ pub mod saved_playlist_service_client {
         pub fn with_interceptor<F>(
             inner: T,
             interceptor: F,
-        ) -> SavedPlaylistServiceClient<InterceptedService<T, F>>
+        ) -> RadioServiceClient<InterceptedService<T, F>>
         where
             F: tonic::service::Interceptor,
             T::ResponseBody: Default,
@@ -7556,7 +8083,7 @@ pub mod saved_playlist_service_client {
             <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
                 Into<StdError> + std::marker::Send + std::marker::Sync,
         {
-            SavedPlaylistServiceClient::new(InterceptedService::new(inner, interceptor))
+            RadioServiceClient::new(InterceptedService::new(inner, interceptor))
         }
         /// Compress requests with the given encoding.
         ///
@@ -7589,244 +8116,143 @@ pub mod saved_playlist_service_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
-        pub async fn create_playlist_folder(
-            &mut self,
-            request: impl tonic::IntoRequest<super::CreatePlaylistFolderRequest>,
-        ) -> std::result::Result<tonic::Response<super::CreatePlaylistFolderResponse>, tonic::Status>
-        {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/CreatePlaylistFolder",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "CreatePlaylistFolder",
-            ));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_playlist_folders(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetPlaylistFoldersRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetPlaylistFoldersResponse>, tonic::Status>
-        {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/GetPlaylistFolders",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "GetPlaylistFolders",
-            ));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn delete_playlist_folder(
+        pub async fn get_radio_stations(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeletePlaylistFolderRequest>,
-        ) -> std::result::Result<tonic::Response<super::DeletePlaylistFolderResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::GetRadioStationsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRadioStationsResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/DeletePlaylistFolder",
+                "/rockbox.v1alpha1.RadioService/GetRadioStations",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "DeletePlaylistFolder",
+                "rockbox.v1alpha1.RadioService",
+                "GetRadioStations",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_saved_playlists(
+        pub async fn get_radio_station(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetSavedPlaylistsRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistsResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::GetRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRadioStationResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylists",
+                "/rockbox.v1alpha1.RadioService/GetRadioStation",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "GetSavedPlaylists",
+                "rockbox.v1alpha1.RadioService",
+                "GetRadioStation",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_saved_playlist(
+        pub async fn create_radio_station(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::CreateRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateRadioStationResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylist",
+                "/rockbox.v1alpha1.RadioService/CreateRadioStation",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "GetSavedPlaylist",
+                "rockbox.v1alpha1.RadioService",
+                "CreateRadioStation",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_saved_playlist(
+        pub async fn update_radio_station(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::CreateSavedPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::UpdateRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateRadioStationResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/CreateSavedPlaylist",
+                "/rockbox.v1alpha1.RadioService/UpdateRadioStation",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "CreateSavedPlaylist",
+                "rockbox.v1alpha1.RadioService",
+                "UpdateRadioStation",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_saved_playlist(
+        pub async fn delete_radio_station(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateSavedPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::DeleteRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteRadioStationResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/UpdateSavedPlaylist",
+                "/rockbox.v1alpha1.RadioService/DeleteRadioStation",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "UpdateSavedPlaylist",
+                "rockbox.v1alpha1.RadioService",
+                "DeleteRadioStation",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn delete_saved_playlist(
+        pub async fn import_radio_stations(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::DeleteSavedPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::ImportRadioStationsRequest>,
+        ) -> std::result::Result<tonic::Response<super::ImportRadioStationsResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/DeleteSavedPlaylist",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "DeleteSavedPlaylist",
-            ));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_saved_playlist_tracks(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetSavedPlaylistTracksRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetSavedPlaylistTracksResponse>,
-            tonic::Status,
-        > {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylistTracks",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "GetSavedPlaylistTracks",
-            ));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn add_tracks_to_saved_playlist(
-            &mut self,
-            request: impl tonic::IntoRequest<super::AddTracksToSavedPlaylistRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::AddTracksToSavedPlaylistResponse>,
-            tonic::Status,
-        > {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/AddTracksToSavedPlaylist",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "AddTracksToSavedPlaylist",
-            ));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn remove_track_from_saved_playlist(
-            &mut self,
-            request: impl tonic::IntoRequest<super::RemoveTrackFromSavedPlaylistRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::RemoveTrackFromSavedPlaylistResponse>,
-            tonic::Status,
-        > {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/RemoveTrackFromSavedPlaylist",
+                "/rockbox.v1alpha1.RadioService/ImportRadioStations",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "RemoveTrackFromSavedPlaylist",
+                "rockbox.v1alpha1.RadioService",
+                "ImportRadioStations",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn play_saved_playlist(
+        pub async fn play_radio_station(
             &mut self,
-            request: impl tonic::IntoRequest<super::PlaySavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::PlaySavedPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::PlayRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlayRadioStationResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SavedPlaylistService/PlaySavedPlaylist",
+                "/rockbox.v1alpha1.RadioService/PlayRadioStation",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SavedPlaylistService",
-                "PlaySavedPlaylist",
+                "rockbox.v1alpha1.RadioService",
+                "PlayRadioStation",
             ));
             self.inner.unary(req, path, codec).await
         }
     }
 }
 /// Generated server implementations.
-pub mod saved_playlist_service_server {
+pub mod radio_service_server {
     #![allow(
         unused_variables,
         dead_code,
@@ -7835,76 +8261,47 @@ pub mod saved_playlist_service_server {
         clippy::let_unit_value
     )]
     use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with SavedPlaylistServiceServer.
+    /// Generated trait containing gRPC methods that should be implemented for use with RadioServiceServer.
     #[async_trait]
-    pub trait SavedPlaylistService: std::marker::Send + std::marker::Sync + 'static {
-        async fn create_playlist_folder(
-            &self,
-            request: tonic::Request<super::CreatePlaylistFolderRequest>,
-        ) -> std::result::Result<tonic::Response<super::CreatePlaylistFolderResponse>, tonic::Status>;
-        async fn get_playlist_folders(
-            &self,
-            request: tonic::Request<super::GetPlaylistFoldersRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetPlaylistFoldersResponse>, tonic::Status>;
-        async fn delete_playlist_folder(
-            &self,
-            request: tonic::Request<super::DeletePlaylistFolderRequest>,
-        ) -> std::result::Result<tonic::Response<super::DeletePlaylistFolderResponse>, tonic::Status>;
-        async fn get_saved_playlists(
-            &self,
-            request: tonic::Request<super::GetSavedPlaylistsRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistsResponse>, tonic::Status>;
-        async fn get_saved_playlist(
-            &self,
-            request: tonic::Request<super::GetSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistResponse>, tonic::Status>;
-        async fn create_saved_playlist(
+    pub trait RadioService: std::marker::Send + std::marker::Sync + 'static {
+        async fn get_radio_stations(
             &self,
-            request: tonic::Request<super::CreateSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::CreateSavedPlaylistResponse>, tonic::Status>;
-        async fn update_saved_playlist(
+            request: tonic::Request<super::GetRadioStationsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRadioStationsResponse>, tonic::Status>;
+        async fn get_radio_station(
             &self,
-            request: tonic::Request<super::UpdateSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateSavedPlaylistResponse>, tonic::Status>;
-        async fn delete_saved_playlist(
+            request: tonic::Request<super::GetRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetRadioStationResponse>, tonic::Status>;
+        async fn create_radio_station(
             &self,
-            request: tonic::Request<super::DeleteSavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::DeleteSavedPlaylistResponse>, tonic::Status>;
-        async fn get_saved_playlist_tracks(
+            request: tonic::Request<super::CreateRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateRadioStationResponse>, tonic::Status>;
+        async fn update_radio_station(
             &self,
-            request: tonic::Request<super::GetSavedPlaylistTracksRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetSavedPlaylistTracksResponse>,
-            tonic::Status,
-        >;
-        async fn add_tracks_to_saved_playlist(
+            request: tonic::Request<super::UpdateRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateRadioStationResponse>, tonic::Status>;
+        async fn delete_radio_station(
             &self,
-            request: tonic::Request<super::AddTracksToSavedPlaylistRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::AddTracksToSavedPlaylistResponse>,
-            tonic::Status,
-        >;
-        async fn remove_track_from_saved_playlist(
+            request: tonic::Request<super::DeleteRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteRadioStationResponse>, tonic::Status>;
+        async fn import_radio_stations(
             &self,
-            request: tonic::Request<super::RemoveTrackFromSavedPlaylistRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::RemoveTrackFromSavedPlaylistResponse>,
-            tonic::Status,
-        >;
-        async fn play_saved_playlist(
+            request: tonic::Request<super::ImportRadioStationsRequest>,
+        ) -> std::result::Result<tonic::Response<super::ImportRadioStationsResponse>, tonic::Status>;
+        async fn play_radio_station(
             &self,
-            request: tonic::Request<super::PlaySavedPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::PlaySavedPlaylistResponse>, tonic::Status>;
+            request: tonic::Request<super::PlayRadioStationRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlayRadioStationResponse>, tonic::Status>;
     }
     #[derive(Debug)]
-    pub struct SavedPlaylistServiceServer<T> {
+    pub struct RadioServiceServer<T> {
         inner: Arc<T>,
         accept_compression_encodings: EnabledCompressionEncodings,
         send_compression_encodings: EnabledCompressionEncodings,
         max_decoding_message_size: Option<usize>,
         max_encoding_message_size: Option<usize>,
     }
-    impl<T> SavedPlaylistServiceServer<T> {
+    impl<T> RadioServiceServer<T> {
         pub fn new(inner: T) -> Self {
             Self::from_arc(Arc::new(inner))
         }
@@ -7952,9 +8349,9 @@ pub mod saved_playlist_service_server {
             self
         }
     }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for SavedPlaylistServiceServer<T>
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for RadioServiceServer<T>
     where
-        T: SavedPlaylistService,
+        T: RadioService,
         B: Body + std::marker::Send + 'static,
         B::Error: Into<StdError> + std::marker::Send + 'static,
     {
@@ -7969,23 +8366,22 @@ pub mod saved_playlist_service_server {
         }
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             match req.uri().path() {
-                "/rockbox.v1alpha1.SavedPlaylistService/CreatePlaylistFolder" => {
+                "/rockbox.v1alpha1.RadioService/GetRadioStations" => {
                     #[allow(non_camel_case_types)]
-                    struct CreatePlaylistFolderSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::CreatePlaylistFolderRequest>
-                        for CreatePlaylistFolderSvc<T>
+                    struct GetRadioStationsSvc<T: RadioService>(pub Arc<T>);
+                    impl<T: RadioService>
+                        tonic::server::UnaryService<super::GetRadioStationsRequest>
+                        for GetRadioStationsSvc<T>
                     {
-                        type Response = super::CreatePlaylistFolderResponse;
+                        type Response = super::GetRadioStationsResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreatePlaylistFolderRequest>,
+                            request: tonic::Request<super::GetRadioStationsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::create_playlist_folder(&inner, request)
-                                    .await
+                                <T as RadioService>::get_radio_stations(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -7996,7 +8392,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CreatePlaylistFolderSvc(inner);
+                        let method = GetRadioStationsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8012,23 +8408,21 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/GetPlaylistFolders" => {
+                "/rockbox.v1alpha1.RadioService/GetRadioStation" => {
                     #[allow(non_camel_case_types)]
-                    struct GetPlaylistFoldersSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::GetPlaylistFoldersRequest>
-                        for GetPlaylistFoldersSvc<T>
+                    struct GetRadioStationSvc<T: RadioService>(pub Arc<T>);
+                    impl<T: RadioService> tonic::server::UnaryService<super::GetRadioStationRequest>
+                        for GetRadioStationSvc<T>
                     {
-                        type Response = super::GetPlaylistFoldersResponse;
+                        type Response = super::GetRadioStationResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetPlaylistFoldersRequest>,
+                            request: tonic::Request<super::GetRadioStationRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::get_playlist_folders(&inner, request)
-                                    .await
+                                <T as RadioService>::get_radio_station(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -8039,7 +8433,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetPlaylistFoldersSvc(inner);
+                        let method = GetRadioStationSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8055,23 +8449,22 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/DeletePlaylistFolder" => {
+                "/rockbox.v1alpha1.RadioService/CreateRadioStation" => {
                     #[allow(non_camel_case_types)]
-                    struct DeletePlaylistFolderSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::DeletePlaylistFolderRequest>
-                        for DeletePlaylistFolderSvc<T>
+                    struct CreateRadioStationSvc<T: RadioService>(pub Arc<T>);
+                    impl<T: RadioService>
+                        tonic::server::UnaryService<super::CreateRadioStationRequest>
+                        for CreateRadioStationSvc<T>
                     {
-                        type Response = super::DeletePlaylistFolderResponse;
+                        type Response = super::CreateRadioStationResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeletePlaylistFolderRequest>,
+                            request: tonic::Request<super::CreateRadioStationRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::delete_playlist_folder(&inner, request)
-                                    .await
+                                <T as RadioService>::create_radio_station(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -8082,7 +8475,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = DeletePlaylistFolderSvc(inner);
+                        let method = CreateRadioStationSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8098,22 +8491,2209 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylists" => {
+                "/rockbox.v1alpha1.RadioService/UpdateRadioStation" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSavedPlaylistsSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
+                    struct UpdateRadioStationSvc<T: RadioService>(pub Arc<T>);
+                    impl<T: RadioService>
+                        tonic::server::UnaryService<super::UpdateRadioStationRequest>
+                        for UpdateRadioStationSvc<T>
+                    {
+                        type Response = super::UpdateRadioStationResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateRadioStationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as RadioService>::update_radio_station(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateRadioStationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.RadioService/DeleteRadioStation" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteRadioStationSvc<T: RadioService>(pub Arc<T>);
+                    impl<T: RadioService>
+                        tonic::server::UnaryService<super::DeleteRadioStationRequest>
+                        for DeleteRadioStationSvc<T>
+                    {
+                        type Response = super::DeleteRadioStationResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteRadioStationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as RadioService>::delete_radio_station(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DeleteRadioStationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.RadioService/ImportRadioStations" => {
+                    #[allow(non_camel_case_types)]
+                    struct ImportRadioStationsSvc<T: RadioService>(pub Arc<T>);
+                    impl<T: RadioService>
+                        tonic::server::UnaryService<super::ImportRadioStationsRequest>
+                        for ImportRadioStationsSvc<T>
+                    {
+                        type Response = super::ImportRadioStationsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ImportRadioStationsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as RadioService>::import_radio_stations(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ImportRadioStationsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.RadioService/PlayRadioStation" => {
+                    #[allow(non_camel_case_types)]
+                    struct PlayRadioStationSvc<T: RadioService>(pub Arc<T>);
+                    impl<T: RadioService>
+                        tonic::server::UnaryService<super::PlayRadioStationRequest>
+                        for PlayRadioStationSvc<T>
+                    {
+                        type Response = super::PlayRadioStationResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PlayRadioStationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as RadioService>::play_radio_station(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PlayRadioStationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    let mut response = http::Response::new(empty_body());
+                    let headers = response.headers_mut();
+                    headers.insert(
+                        tonic::Status::GRPC_STATUS,
+                        (tonic::Code::Unimplemented as i32).into(),
+                    );
+                    headers.insert(
+                        http::header::CONTENT_TYPE,
+                        tonic::metadata::GRPC_CONTENT_TYPE,
+                    );
+                    Ok(response)
+                }),
+            }
+        }
+    }
+    impl<T> Clone for RadioServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "rockbox.v1alpha1.RadioService";
+    impl<T> tonic::server::NamedService for RadioServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistFolder {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub created_at: i64,
+    #[prost(int64, tag = "4")]
+    pub updated_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreatePlaylistFolderRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreatePlaylistFolderResponse {
+    #[prost(message, optional, tag = "1")]
+    pub folder: ::core::option::Option<PlaylistFolder>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetPlaylistFoldersRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPlaylistFoldersResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub folders: ::prost::alloc::vec::Vec<PlaylistFolder>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeletePlaylistFolderRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DeletePlaylistFolderResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SavedPlaylist {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, tag = "6")]
+    pub track_count: i64,
+    #[prost(int64, tag = "7")]
+    pub created_at: i64,
+    #[prost(int64, tag = "8")]
+    pub updated_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSavedPlaylistsRequest {
+    #[prost(string, optional, tag = "1")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSavedPlaylistsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub playlists: ::prost::alloc::vec::Vec<SavedPlaylist>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSavedPlaylistResponse {
+    #[prost(message, optional, tag = "1")]
+    pub playlist: ::core::option::Option<SavedPlaylist>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "5")]
+    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSavedPlaylistResponse {
+    #[prost(message, optional, tag = "1")]
+    pub playlist: ::core::option::Option<SavedPlaylist>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UpdateSavedPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DeleteSavedPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSavedPlaylistTracksRequest {
+    #[prost(string, tag = "1")]
+    pub playlist_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSavedPlaylistTracksResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddTracksToSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub playlist_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct AddTracksToSavedPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveTrackFromSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub playlist_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub track_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RemoveTrackFromSavedPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaySavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub playlist_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct PlaySavedPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistFileEntry {
+    #[prost(string, tag = "1")]
+    pub location: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub title: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub artist: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub album: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, optional, tag = "5")]
+    pub duration_ms: ::core::option::Option<i64>,
+}
+/// format is one of m3u, m3u8, pls, xspf, jspf; guessed from the content
+/// when absent.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub content: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub format: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub base_dir: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportSavedPlaylistResponse {
+    #[prost(message, optional, tag = "1")]
+    pub playlist: ::core::option::Option<SavedPlaylist>,
+    #[prost(int32, tag = "2")]
+    pub matched: i32,
+    #[prost(message, repeated, tag = "3")]
+    pub unmatched: ::prost::alloc::vec::Vec<PlaylistFileEntry>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportSavedPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub format: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub relative_to: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportSavedPlaylistResponse {
+    #[prost(string, tag = "1")]
+    pub content: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_type: ::prost::alloc::string::String,
+}
+/// Generated client implementations.
+pub mod saved_playlist_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub struct SavedPlaylistServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl SavedPlaylistServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> SavedPlaylistServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> SavedPlaylistServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            SavedPlaylistServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        pub async fn create_playlist_folder(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreatePlaylistFolderRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreatePlaylistFolderResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/CreatePlaylistFolder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "CreatePlaylistFolder",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_playlist_folders(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetPlaylistFoldersRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetPlaylistFoldersResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/GetPlaylistFolders",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "GetPlaylistFolders",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_playlist_folder(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeletePlaylistFolderRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeletePlaylistFolderResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/DeletePlaylistFolder",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "DeletePlaylistFolder",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_saved_playlists(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSavedPlaylistsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylists",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "GetSavedPlaylists",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "GetSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateSavedPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/CreateSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "CreateSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateSavedPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/UpdateSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "UpdateSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteSavedPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/DeleteSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "DeleteSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_saved_playlist_tracks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSavedPlaylistTracksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSavedPlaylistTracksResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylistTracks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "GetSavedPlaylistTracks",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn add_tracks_to_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddTracksToSavedPlaylistRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AddTracksToSavedPlaylistResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/AddTracksToSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "AddTracksToSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn remove_track_from_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoveTrackFromSavedPlaylistRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveTrackFromSavedPlaylistResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/RemoveTrackFromSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "RemoveTrackFromSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn play_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PlaySavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlaySavedPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/PlaySavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "PlaySavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn import_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ImportSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::ImportSavedPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/ImportSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "ImportSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn export_saved_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::ExportSavedPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SavedPlaylistService/ExportSavedPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SavedPlaylistService",
+                "ExportSavedPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod saved_playlist_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with SavedPlaylistServiceServer.
+    #[async_trait]
+    pub trait SavedPlaylistService: std::marker::Send + std::marker::Sync + 'static {
+        async fn create_playlist_folder(
+            &self,
+            request: tonic::Request<super::CreatePlaylistFolderRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreatePlaylistFolderResponse>, tonic::Status>;
+        async fn get_playlist_folders(
+            &self,
+            request: tonic::Request<super::GetPlaylistFoldersRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetPlaylistFoldersResponse>, tonic::Status>;
+        async fn delete_playlist_folder(
+            &self,
+            request: tonic::Request<super::DeletePlaylistFolderRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeletePlaylistFolderResponse>, tonic::Status>;
+        async fn get_saved_playlists(
+            &self,
+            request: tonic::Request<super::GetSavedPlaylistsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistsResponse>, tonic::Status>;
+        async fn get_saved_playlist(
+            &self,
+            request: tonic::Request<super::GetSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSavedPlaylistResponse>, tonic::Status>;
+        async fn create_saved_playlist(
+            &self,
+            request: tonic::Request<super::CreateSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateSavedPlaylistResponse>, tonic::Status>;
+        async fn update_saved_playlist(
+            &self,
+            request: tonic::Request<super::UpdateSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateSavedPlaylistResponse>, tonic::Status>;
+        async fn delete_saved_playlist(
+            &self,
+            request: tonic::Request<super::DeleteSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteSavedPlaylistResponse>, tonic::Status>;
+        async fn get_saved_playlist_tracks(
+            &self,
+            request: tonic::Request<super::GetSavedPlaylistTracksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSavedPlaylistTracksResponse>,
+            tonic::Status,
+        >;
+        async fn add_tracks_to_saved_playlist(
+            &self,
+            request: tonic::Request<super::AddTracksToSavedPlaylistRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AddTracksToSavedPlaylistResponse>,
+            tonic::Status,
+        >;
+        async fn remove_track_from_saved_playlist(
+            &self,
+            request: tonic::Request<super::RemoveTrackFromSavedPlaylistRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveTrackFromSavedPlaylistResponse>,
+            tonic::Status,
+        >;
+        async fn play_saved_playlist(
+            &self,
+            request: tonic::Request<super::PlaySavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlaySavedPlaylistResponse>, tonic::Status>;
+        async fn import_saved_playlist(
+            &self,
+            request: tonic::Request<super::ImportSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::ImportSavedPlaylistResponse>, tonic::Status>;
+        async fn export_saved_playlist(
+            &self,
+            request: tonic::Request<super::ExportSavedPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::ExportSavedPlaylistResponse>, tonic::Status>;
+    }
+    #[derive(Debug)]
+    pub struct SavedPlaylistServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> SavedPlaylistServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for SavedPlaylistServiceServer<T>
+    where
+        T: SavedPlaylistService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/rockbox.v1alpha1.SavedPlaylistService/CreatePlaylistFolder" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreatePlaylistFolderSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::CreatePlaylistFolderRequest>
+                        for CreatePlaylistFolderSvc<T>
+                    {
+                        type Response = super::CreatePlaylistFolderResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreatePlaylistFolderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::create_playlist_folder(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreatePlaylistFolderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/GetPlaylistFolders" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPlaylistFoldersSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::GetPlaylistFoldersRequest>
+                        for GetPlaylistFoldersSvc<T>
+                    {
+                        type Response = super::GetPlaylistFoldersResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPlaylistFoldersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::get_playlist_folders(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetPlaylistFoldersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/DeletePlaylistFolder" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeletePlaylistFolderSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::DeletePlaylistFolderRequest>
+                        for DeletePlaylistFolderSvc<T>
+                    {
+                        type Response = super::DeletePlaylistFolderResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeletePlaylistFolderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::delete_playlist_folder(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DeletePlaylistFolderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylists" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSavedPlaylistsSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
                         tonic::server::UnaryService<super::GetSavedPlaylistsRequest>
                         for GetSavedPlaylistsSvc<T>
                     {
-                        type Response = super::GetSavedPlaylistsResponse;
+                        type Response = super::GetSavedPlaylistsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSavedPlaylistsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::get_saved_playlists(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSavedPlaylistsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::GetSavedPlaylistRequest>
+                        for GetSavedPlaylistSvc<T>
+                    {
+                        type Response = super::GetSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::get_saved_playlist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/CreateSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::CreateSavedPlaylistRequest>
+                        for CreateSavedPlaylistSvc<T>
+                    {
+                        type Response = super::CreateSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::create_saved_playlist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/UpdateSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::UpdateSavedPlaylistRequest>
+                        for UpdateSavedPlaylistSvc<T>
+                    {
+                        type Response = super::UpdateSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::update_saved_playlist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/DeleteSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::DeleteSavedPlaylistRequest>
+                        for DeleteSavedPlaylistSvc<T>
+                    {
+                        type Response = super::DeleteSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::delete_saved_playlist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DeleteSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylistTracks" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSavedPlaylistTracksSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::GetSavedPlaylistTracksRequest>
+                        for GetSavedPlaylistTracksSvc<T>
+                    {
+                        type Response = super::GetSavedPlaylistTracksResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSavedPlaylistTracksRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::get_saved_playlist_tracks(
+                                    &inner, request,
+                                )
+                                .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSavedPlaylistTracksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/AddTracksToSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct AddTracksToSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::AddTracksToSavedPlaylistRequest>
+                        for AddTracksToSavedPlaylistSvc<T>
+                    {
+                        type Response = super::AddTracksToSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddTracksToSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::add_tracks_to_saved_playlist(
+                                    &inner, request,
+                                )
+                                .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = AddTracksToSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/RemoveTrackFromSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveTrackFromSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::RemoveTrackFromSavedPlaylistRequest>
+                        for RemoveTrackFromSavedPlaylistSvc<T>
+                    {
+                        type Response = super::RemoveTrackFromSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoveTrackFromSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::remove_track_from_saved_playlist(
+                                    &inner, request,
+                                )
+                                .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RemoveTrackFromSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/PlaySavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct PlaySavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::PlaySavedPlaylistRequest>
+                        for PlaySavedPlaylistSvc<T>
+                    {
+                        type Response = super::PlaySavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PlaySavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::play_saved_playlist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PlaySavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/ImportSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct ImportSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::ImportSavedPlaylistRequest>
+                        for ImportSavedPlaylistSvc<T>
+                    {
+                        type Response = super::ImportSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ImportSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::import_saved_playlist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ImportSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SavedPlaylistService/ExportSavedPlaylist" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExportSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
+                    impl<T: SavedPlaylistService>
+                        tonic::server::UnaryService<super::ExportSavedPlaylistRequest>
+                        for ExportSavedPlaylistSvc<T>
+                    {
+                        type Response = super::ExportSavedPlaylistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExportSavedPlaylistRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SavedPlaylistService>::export_saved_playlist(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ExportSavedPlaylistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    let mut response = http::Response::new(empty_body());
+                    let headers = response.headers_mut();
+                    headers.insert(
+                        tonic::Status::GRPC_STATUS,
+                        (tonic::Code::Unimplemented as i32).into(),
+                    );
+                    headers.insert(
+                        http::header::CONTENT_TYPE,
+                        tonic::metadata::GRPC_CONTENT_TYPE,
+                    );
+                    Ok(response)
+                }),
+            }
+        }
+    }
+    impl<T> Clone for SavedPlaylistServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "rockbox.v1alpha1.SavedPlaylistService";
+    impl<T> tonic::server::NamedService for SavedPlaylistServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuleCondition {
+    #[prost(string, tag = "1")]
+    pub field: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub operator: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub value: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub value2: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub unit: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuleCriteria {
+    #[prost(string, tag = "1")]
+    pub match_type: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub conditions: ::prost::alloc::vec::Vec<RuleCondition>,
+    #[prost(int32, optional, tag = "3")]
+    pub limit: ::core::option::Option<i32>,
+    #[prost(string, optional, tag = "4")]
+    pub sort_by: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub sort_order: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SmartPlaylist {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bool, tag = "6")]
+    pub is_system: bool,
+    #[prost(message, optional, tag = "7")]
+    pub rules: ::core::option::Option<RuleCriteria>,
+    #[prost(int64, tag = "8")]
+    pub created_at: i64,
+    #[prost(int64, tag = "9")]
+    pub updated_at: i64,
+    #[prost(int64, tag = "10")]
+    pub track_count: i64,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetSmartPlaylistsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSmartPlaylistsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub playlists: ::prost::alloc::vec::Vec<SmartPlaylist>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSmartPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSmartPlaylistResponse {
+    #[prost(message, optional, tag = "1")]
+    pub playlist: ::core::option::Option<SmartPlaylist>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSmartPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag = "5")]
+    pub rules: ::core::option::Option<RuleCriteria>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSmartPlaylistResponse {
+    #[prost(message, optional, tag = "1")]
+    pub playlist: ::core::option::Option<SmartPlaylist>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSmartPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag = "6")]
+    pub rules: ::core::option::Option<RuleCriteria>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UpdateSmartPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteSmartPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DeleteSmartPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSmartPlaylistTracksRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSmartPlaylistTracksResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaySmartPlaylistRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct PlaySmartPlaylistResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackStats {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+    #[prost(int64, tag = "2")]
+    pub play_count: i64,
+    #[prost(int64, tag = "3")]
+    pub skip_count: i64,
+    #[prost(int64, optional, tag = "4")]
+    pub last_played: ::core::option::Option<i64>,
+    #[prost(int64, optional, tag = "5")]
+    pub last_skipped: ::core::option::Option<i64>,
+    #[prost(int64, tag = "6")]
+    pub updated_at: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordTrackPlayedRequest {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RecordTrackPlayedResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordTrackSkippedRequest {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RecordTrackSkippedResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTrackStatsRequest {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTrackStatsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub stats: ::core::option::Option<TrackStats>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamSmartPlaylistChangesRequest {
+    #[prost(string, optional, tag = "1")]
+    pub id: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SmartPlaylistChange {
+    #[prost(string, tag = "1")]
+    pub playlist_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub added: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "3")]
+    pub removed: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Generated client implementations.
+pub mod smart_playlist_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub struct SmartPlaylistServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl SmartPlaylistServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> SmartPlaylistServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> SmartPlaylistServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            SmartPlaylistServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        pub async fn get_smart_playlists(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSmartPlaylistsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylists",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "GetSmartPlaylists",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_smart_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "GetSmartPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_smart_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateSmartPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/CreateSmartPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "CreateSmartPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_smart_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateSmartPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/UpdateSmartPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "UpdateSmartPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_smart_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteSmartPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/DeleteSmartPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "DeleteSmartPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_smart_playlist_tracks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSmartPlaylistTracksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSmartPlaylistTracksResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylistTracks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "GetSmartPlaylistTracks",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn play_smart_playlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PlaySmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlaySmartPlaylistResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/PlaySmartPlaylist",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "PlaySmartPlaylist",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn record_track_played(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RecordTrackPlayedRequest>,
+        ) -> std::result::Result<tonic::Response<super::RecordTrackPlayedResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackPlayed",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "RecordTrackPlayed",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn record_track_skipped(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RecordTrackSkippedRequest>,
+        ) -> std::result::Result<tonic::Response<super::RecordTrackSkippedResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackSkipped",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "RecordTrackSkipped",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_track_stats(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTrackStatsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetTrackStatsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/GetTrackStats",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "GetTrackStats",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn stream_smart_playlist_changes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamSmartPlaylistChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::SmartPlaylistChange>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rockbox.v1alpha1.SmartPlaylistService/StreamSmartPlaylistChanges",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "rockbox.v1alpha1.SmartPlaylistService",
+                "StreamSmartPlaylistChanges",
+            ));
+            self.inner.server_streaming(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod smart_playlist_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with SmartPlaylistServiceServer.
+    #[async_trait]
+    pub trait SmartPlaylistService: std::marker::Send + std::marker::Sync + 'static {
+        async fn get_smart_playlists(
+            &self,
+            request: tonic::Request<super::GetSmartPlaylistsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistsResponse>, tonic::Status>;
+        async fn get_smart_playlist(
+            &self,
+            request: tonic::Request<super::GetSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistResponse>, tonic::Status>;
+        async fn create_smart_playlist(
+            &self,
+            request: tonic::Request<super::CreateSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateSmartPlaylistResponse>, tonic::Status>;
+        async fn update_smart_playlist(
+            &self,
+            request: tonic::Request<super::UpdateSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateSmartPlaylistResponse>, tonic::Status>;
+        async fn delete_smart_playlist(
+            &self,
+            request: tonic::Request<super::DeleteSmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteSmartPlaylistResponse>, tonic::Status>;
+        async fn get_smart_playlist_tracks(
+            &self,
+            request: tonic::Request<super::GetSmartPlaylistTracksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSmartPlaylistTracksResponse>,
+            tonic::Status,
+        >;
+        async fn play_smart_playlist(
+            &self,
+            request: tonic::Request<super::PlaySmartPlaylistRequest>,
+        ) -> std::result::Result<tonic::Response<super::PlaySmartPlaylistResponse>, tonic::Status>;
+        async fn record_track_played(
+            &self,
+            request: tonic::Request<super::RecordTrackPlayedRequest>,
+        ) -> std::result::Result<tonic::Response<super::RecordTrackPlayedResponse>, tonic::Status>;
+        async fn record_track_skipped(
+            &self,
+            request: tonic::Request<super::RecordTrackSkippedRequest>,
+        ) -> std::result::Result<tonic::Response<super::RecordTrackSkippedResponse>, tonic::Status>;
+        async fn get_track_stats(
+            &self,
+            request: tonic::Request<super::GetTrackStatsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetTrackStatsResponse>, tonic::Status>;
+        /// Server streaming response type for the StreamSmartPlaylistChanges method.
+        type StreamSmartPlaylistChangesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::SmartPlaylistChange, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        async fn stream_smart_playlist_changes(
+            &self,
+            request: tonic::Request<super::StreamSmartPlaylistChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::StreamSmartPlaylistChangesStream>,
+            tonic::Status,
+        >;
+    }
+    #[derive(Debug)]
+    pub struct SmartPlaylistServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> SmartPlaylistServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for SmartPlaylistServiceServer<T>
+    where
+        T: SmartPlaylistService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylists" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSmartPlaylistsSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::GetSmartPlaylistsRequest>
+                        for GetSmartPlaylistsSvc<T>
+                    {
+                        type Response = super::GetSmartPlaylistsResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetSavedPlaylistsRequest>,
+                            request: tonic::Request<super::GetSmartPlaylistsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::get_saved_playlists(&inner, request)
+                                <T as SmartPlaylistService>::get_smart_playlists(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -8125,7 +10705,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetSavedPlaylistsSvc(inner);
+                        let method = GetSmartPlaylistsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8141,22 +10721,22 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylist" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylist" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::GetSavedPlaylistRequest>
-                        for GetSavedPlaylistSvc<T>
+                    struct GetSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::GetSmartPlaylistRequest>
+                        for GetSmartPlaylistSvc<T>
                     {
-                        type Response = super::GetSavedPlaylistResponse;
+                        type Response = super::GetSmartPlaylistResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetSavedPlaylistRequest>,
+                            request: tonic::Request<super::GetSmartPlaylistRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::get_saved_playlist(&inner, request)
+                                <T as SmartPlaylistService>::get_smart_playlist(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -8168,7 +10748,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetSavedPlaylistSvc(inner);
+                        let method = GetSmartPlaylistSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8184,22 +10764,22 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/CreateSavedPlaylist" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/CreateSmartPlaylist" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::CreateSavedPlaylistRequest>
-                        for CreateSavedPlaylistSvc<T>
+                    struct CreateSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::CreateSmartPlaylistRequest>
+                        for CreateSmartPlaylistSvc<T>
                     {
-                        type Response = super::CreateSavedPlaylistResponse;
+                        type Response = super::CreateSmartPlaylistResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateSavedPlaylistRequest>,
+                            request: tonic::Request<super::CreateSmartPlaylistRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::create_saved_playlist(&inner, request)
+                                <T as SmartPlaylistService>::create_smart_playlist(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -8211,7 +10791,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CreateSavedPlaylistSvc(inner);
+                        let method = CreateSmartPlaylistSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8227,22 +10807,22 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/UpdateSavedPlaylist" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/UpdateSmartPlaylist" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::UpdateSavedPlaylistRequest>
-                        for UpdateSavedPlaylistSvc<T>
+                    struct UpdateSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::UpdateSmartPlaylistRequest>
+                        for UpdateSmartPlaylistSvc<T>
                     {
-                        type Response = super::UpdateSavedPlaylistResponse;
+                        type Response = super::UpdateSmartPlaylistResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateSavedPlaylistRequest>,
+                            request: tonic::Request<super::UpdateSmartPlaylistRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::update_saved_playlist(&inner, request)
+                                <T as SmartPlaylistService>::update_smart_playlist(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -8254,7 +10834,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = UpdateSavedPlaylistSvc(inner);
+                        let method = UpdateSmartPlaylistSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8270,22 +10850,22 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/DeleteSavedPlaylist" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/DeleteSmartPlaylist" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::DeleteSavedPlaylistRequest>
-                        for DeleteSavedPlaylistSvc<T>
+                    struct DeleteSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::DeleteSmartPlaylistRequest>
+                        for DeleteSmartPlaylistSvc<T>
                     {
-                        type Response = super::DeleteSavedPlaylistResponse;
+                        type Response = super::DeleteSmartPlaylistResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteSavedPlaylistRequest>,
+                            request: tonic::Request<super::DeleteSmartPlaylistRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::delete_saved_playlist(&inner, request)
+                                <T as SmartPlaylistService>::delete_smart_playlist(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -8297,7 +10877,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = DeleteSavedPlaylistSvc(inner);
+                        let method = DeleteSmartPlaylistSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8313,22 +10893,22 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/GetSavedPlaylistTracks" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylistTracks" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSavedPlaylistTracksSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::GetSavedPlaylistTracksRequest>
-                        for GetSavedPlaylistTracksSvc<T>
+                    struct GetSmartPlaylistTracksSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::GetSmartPlaylistTracksRequest>
+                        for GetSmartPlaylistTracksSvc<T>
                     {
-                        type Response = super::GetSavedPlaylistTracksResponse;
+                        type Response = super::GetSmartPlaylistTracksResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetSavedPlaylistTracksRequest>,
+                            request: tonic::Request<super::GetSmartPlaylistTracksRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::get_saved_playlist_tracks(
+                                <T as SmartPlaylistService>::get_smart_playlist_tracks(
                                     &inner, request,
                                 )
                                 .await
@@ -8342,7 +10922,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetSavedPlaylistTracksSvc(inner);
+                        let method = GetSmartPlaylistTracksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8358,25 +10938,23 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/AddTracksToSavedPlaylist" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/PlaySmartPlaylist" => {
                     #[allow(non_camel_case_types)]
-                    struct AddTracksToSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::AddTracksToSavedPlaylistRequest>
-                        for AddTracksToSavedPlaylistSvc<T>
+                    struct PlaySmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::PlaySmartPlaylistRequest>
+                        for PlaySmartPlaylistSvc<T>
                     {
-                        type Response = super::AddTracksToSavedPlaylistResponse;
+                        type Response = super::PlaySmartPlaylistResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::AddTracksToSavedPlaylistRequest>,
+                            request: tonic::Request<super::PlaySmartPlaylistRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::add_tracks_to_saved_playlist(
-                                    &inner, request,
-                                )
-                                .await
+                                <T as SmartPlaylistService>::play_smart_playlist(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -8387,7 +10965,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = AddTracksToSavedPlaylistSvc(inner);
+                        let method = PlaySmartPlaylistSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8403,25 +10981,23 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/RemoveTrackFromSavedPlaylist" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackPlayed" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoveTrackFromSavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::RemoveTrackFromSavedPlaylistRequest>
-                        for RemoveTrackFromSavedPlaylistSvc<T>
+                    struct RecordTrackPlayedSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::RecordTrackPlayedRequest>
+                        for RecordTrackPlayedSvc<T>
                     {
-                        type Response = super::RemoveTrackFromSavedPlaylistResponse;
+                        type Response = super::RecordTrackPlayedResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemoveTrackFromSavedPlaylistRequest>,
+                            request: tonic::Request<super::RecordTrackPlayedRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::remove_track_from_saved_playlist(
-                                    &inner, request,
-                                )
-                                .await
+                                <T as SmartPlaylistService>::record_track_played(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -8432,7 +11008,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RemoveTrackFromSavedPlaylistSvc(inner);
+                        let method = RecordTrackPlayedSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8448,23 +11024,113 @@ pub mod saved_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SavedPlaylistService/PlaySavedPlaylist" => {
+                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackSkipped" => {
                     #[allow(non_camel_case_types)]
-                    struct PlaySavedPlaylistSvc<T: SavedPlaylistService>(pub Arc<T>);
-                    impl<T: SavedPlaylistService>
-                        tonic::server::UnaryService<super::PlaySavedPlaylistRequest>
-                        for PlaySavedPlaylistSvc<T>
+                    struct RecordTrackSkippedSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::RecordTrackSkippedRequest>
+                        for RecordTrackSkippedSvc<T>
                     {
-                        type Response = super::PlaySavedPlaylistResponse;
+                        type Response = super::RecordTrackSkippedResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RecordTrackSkippedRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SmartPlaylistService>::record_track_skipped(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RecordTrackSkippedSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SmartPlaylistService/GetTrackStats" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTrackStatsSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::UnaryService<super::GetTrackStatsRequest>
+                        for GetTrackStatsSvc<T>
+                    {
+                        type Response = super::GetTrackStatsResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::PlaySavedPlaylistRequest>,
+                            request: tonic::Request<super::GetTrackStatsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SmartPlaylistService>::get_track_stats(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTrackStatsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SmartPlaylistService/StreamSmartPlaylistChanges" => {
+                    #[allow(non_camel_case_types)]
+                    struct StreamSmartPlaylistChangesSvc<T: SmartPlaylistService>(pub Arc<T>);
+                    impl<T: SmartPlaylistService>
+                        tonic::server::ServerStreamingService<
+                            super::StreamSmartPlaylistChangesRequest,
+                        > for StreamSmartPlaylistChangesSvc<T>
+                    {
+                        type Response = super::SmartPlaylistChange;
+                        type ResponseStream = T::StreamSmartPlaylistChangesStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::StreamSmartPlaylistChangesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SavedPlaylistService>::play_saved_playlist(&inner, request)
-                                    .await
+                                <T as SmartPlaylistService>::stream_smart_playlist_changes(
+                                    &inner, request,
+                                )
+                                .await
                             };
                             Box::pin(fut)
                         }
@@ -8475,7 +11141,7 @@ pub mod saved_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = PlaySavedPlaylistSvc(inner);
+                        let method = StreamSmartPlaylistChangesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8486,7 +11152,7 @@ pub mod saved_playlist_service_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.unary(method, req).await;
+                        let res = grpc.server_streaming(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
@@ -8507,7 +11173,7 @@ pub mod saved_playlist_service_server {
             }
         }
     }
-    impl<T> Clone for SavedPlaylistServiceServer<T> {
+    impl<T> Clone for SmartPlaylistServiceServer<T> {
         fn clone(&self) -> Self {
             let inner = self.inner.clone();
             Self {
@@ -8520,177 +11186,159 @@ pub mod saved_playlist_service_server {
         }
     }
     /// Generated gRPC service name
-    pub const SERVICE_NAME: &str = "rockbox.v1alpha1.SavedPlaylistService";
-    impl<T> tonic::server::NamedService for SavedPlaylistServiceServer<T> {
+    pub const SERVICE_NAME: &str = "rockbox.v1alpha1.SmartPlaylistService";
+    impl<T> tonic::server::NamedService for SmartPlaylistServiceServer<T> {
         const NAME: &'static str = SERVICE_NAME;
     }
 }
+/// action is play_playlist, play_radio, resume or sleep. A schedule runs once
+/// at run_at or whenever cron (five fields, server local time) matches.
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RuleCondition {
-    #[prost(string, tag = "1")]
-    pub field: ::prost::alloc::string::String,
-    #[prost(string, tag = "2")]
-    pub operator: ::prost::alloc::string::String,
-    #[prost(string, optional, tag = "3")]
-    pub value: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(string, optional, tag = "4")]
-    pub value2: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(string, optional, tag = "5")]
-    pub unit: ::core::option::Option<::prost::alloc::string::String>,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RuleCriteria {
-    #[prost(string, tag = "1")]
-    pub match_type: ::prost::alloc::string::String,
-    #[prost(message, repeated, tag = "2")]
-    pub conditions: ::prost::alloc::vec::Vec<RuleCondition>,
-    #[prost(int32, optional, tag = "3")]
-    pub limit: ::core::option::Option<i32>,
-    #[prost(string, optional, tag = "4")]
-    pub sort_by: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(string, optional, tag = "5")]
-    pub sort_order: ::core::option::Option<::prost::alloc::string::String>,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SmartPlaylist {
+pub struct Schedule {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
-    #[prost(string, optional, tag = "3")]
-    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "3")]
+    pub action: ::prost::alloc::string::String,
     #[prost(string, optional, tag = "4")]
-    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    pub target: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "5")]
-    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(bool, tag = "6")]
-    pub is_system: bool,
-    #[prost(message, optional, tag = "7")]
-    pub rules: ::core::option::Option<RuleCriteria>,
+    pub cron: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, optional, tag = "6")]
+    pub run_at: ::core::option::Option<i64>,
+    #[prost(int32, optional, tag = "7")]
+    pub volume: ::core::option::Option<i32>,
     #[prost(int64, tag = "8")]
+    pub ramp_secs: i64,
+    #[prost(bool, tag = "9")]
+    pub enabled: bool,
+    #[prost(int64, optional, tag = "10")]
+    pub next_run: ::core::option::Option<i64>,
+    #[prost(int64, optional, tag = "11")]
+    pub last_run: ::core::option::Option<i64>,
+    #[prost(int64, tag = "12")]
     pub created_at: i64,
-    #[prost(int64, tag = "9")]
+    #[prost(int64, tag = "13")]
     pub updated_at: i64,
-    #[prost(int64, tag = "10")]
-    pub track_count: i64,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct GetSmartPlaylistsRequest {}
+pub struct GetSchedulesRequest {}
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSmartPlaylistsResponse {
+pub struct GetSchedulesResponse {
     #[prost(message, repeated, tag = "1")]
-    pub playlists: ::prost::alloc::vec::Vec<SmartPlaylist>,
+    pub schedules: ::prost::alloc::vec::Vec<Schedule>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSmartPlaylistRequest {
+pub struct GetScheduleRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSmartPlaylistResponse {
+pub struct GetScheduleResponse {
     #[prost(message, optional, tag = "1")]
-    pub playlist: ::core::option::Option<SmartPlaylist>,
+    pub schedule: ::core::option::Option<Schedule>,
 }
+/// Exactly one of cron and run_at is required.
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct CreateSmartPlaylistRequest {
-    #[prost(string, tag = "1")]
-    pub name: ::prost::alloc::string::String,
-    #[prost(string, optional, tag = "2")]
-    pub description: ::core::option::Option<::prost::alloc::string::String>,
+pub struct CreateScheduleRequest {
+    #[prost(string, optional, tag = "1")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "2")]
+    pub action: ::prost::alloc::string::String,
     #[prost(string, optional, tag = "3")]
-    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    pub target: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "4")]
-    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(message, optional, tag = "5")]
-    pub rules: ::core::option::Option<RuleCriteria>,
+    pub cron: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, optional, tag = "5")]
+    pub run_at: ::core::option::Option<i64>,
+    #[prost(int32, optional, tag = "6")]
+    pub volume: ::core::option::Option<i32>,
+    #[prost(int64, optional, tag = "7")]
+    pub ramp_secs: ::core::option::Option<i64>,
+    #[prost(bool, optional, tag = "8")]
+    pub enabled: ::core::option::Option<bool>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct CreateSmartPlaylistResponse {
+pub struct CreateScheduleResponse {
     #[prost(message, optional, tag = "1")]
-    pub playlist: ::core::option::Option<SmartPlaylist>,
+    pub schedule: ::core::option::Option<Schedule>,
 }
+/// Unset fields keep their value; cron and run_at replace each other.
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct UpdateSmartPlaylistRequest {
+pub struct UpdateScheduleRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
-    #[prost(string, tag = "2")]
-    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "3")]
-    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    pub action: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "4")]
-    pub image: ::core::option::Option<::prost::alloc::string::String>,
+    pub target: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag = "5")]
-    pub folder_id: ::core::option::Option<::prost::alloc::string::String>,
-    #[prost(message, optional, tag = "6")]
-    pub rules: ::core::option::Option<RuleCriteria>,
+    pub cron: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, optional, tag = "6")]
+    pub run_at: ::core::option::Option<i64>,
+    #[prost(int32, optional, tag = "7")]
+    pub volume: ::core::option::Option<i32>,
+    #[prost(int64, optional, tag = "8")]
+    pub ramp_secs: ::core::option::Option<i64>,
+    #[prost(bool, optional, tag = "9")]
+    pub enabled: ::core::option::Option<bool>,
 }
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct UpdateSmartPlaylistResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct DeleteSmartPlaylistRequest {
-    #[prost(string, tag = "1")]
-    pub id: ::prost::alloc::string::String,
+pub struct UpdateScheduleResponse {
+    #[prost(message, optional, tag = "1")]
+    pub schedule: ::core::option::Option<Schedule>,
 }
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct DeleteSmartPlaylistResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSmartPlaylistTracksRequest {
+pub struct DeleteScheduleRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
 }
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DeleteScheduleResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetSmartPlaylistTracksResponse {
-    #[prost(string, repeated, tag = "1")]
-    pub track_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct PlaySmartPlaylistRequest {
+pub struct RunScheduleRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct PlaySmartPlaylistResponse {}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct TrackStats {
-    #[prost(string, tag = "1")]
-    pub track_id: ::prost::alloc::string::String,
+pub struct RunScheduleResponse {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SleepTimer {
+    #[prost(int64, tag = "1")]
+    pub ends_at: i64,
     #[prost(int64, tag = "2")]
-    pub play_count: i64,
+    pub remaining_secs: i64,
     #[prost(int64, tag = "3")]
-    pub skip_count: i64,
-    #[prost(int64, optional, tag = "4")]
-    pub last_played: ::core::option::Option<i64>,
-    #[prost(int64, optional, tag = "5")]
-    pub last_skipped: ::core::option::Option<i64>,
-    #[prost(int64, tag = "6")]
-    pub updated_at: i64,
-}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RecordTrackPlayedRequest {
-    #[prost(string, tag = "1")]
-    pub track_id: ::prost::alloc::string::String,
+    pub fade_secs: i64,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct RecordTrackPlayedResponse {}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RecordTrackSkippedRequest {
-    #[prost(string, tag = "1")]
-    pub track_id: ::prost::alloc::string::String,
+pub struct GetSleepTimerRequest {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetSleepTimerResponse {
+    #[prost(message, optional, tag = "1")]
+    pub timer: ::core::option::Option<SleepTimer>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct RecordTrackSkippedResponse {}
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetTrackStatsRequest {
-    #[prost(string, tag = "1")]
-    pub track_id: ::prost::alloc::string::String,
+pub struct SetSleepTimerRequest {
+    #[prost(uint32, tag = "1")]
+    pub minutes: u32,
+    #[prost(int64, tag = "2")]
+    pub fade_secs: i64,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetTrackStatsResponse {
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SetSleepTimerResponse {
     #[prost(message, optional, tag = "1")]
-    pub stats: ::core::option::Option<TrackStats>,
+    pub timer: ::core::option::Option<SleepTimer>,
 }
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CancelSleepTimerRequest {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CancelSleepTimerResponse {}
 /// Generated client implementations.
-pub mod smart_playlist_service_client {
+pub mod schedule_service_client {
     #![allow(
         unused_variables,
         dead_code,
@@ -8701,10 +11349,10 @@ pub mod smart_playlist_service_client {
     use tonic::codegen::http::Uri;
     use tonic::codegen::*;
     #[derive(Debug, Clone)]
-    pub struct SmartPlaylistServiceClient<T> {
+    pub struct ScheduleServiceClient<T> {
         inner: tonic::client::Grpc<T>,
     }
-    impl SmartPlaylistServiceClient<tonic::transport::Channel> {
+    impl ScheduleServiceClient<tonic::transport::Channel> {
         /// Attempt to create a new client by connecting to a given endpoint.
         pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
         where
@@ -8715,7 +11363,7 @@ pub mod smart_playlist_service_client {
             Ok(Self::new(conn))
         }
     }
-    impl<T> SmartPlaylistServiceClient<T>
+    impl<T> ScheduleServiceClient<T>
     where
         T: tonic::client::GrpcService<tonic::body::BoxBody>,
         T::Error: Into<StdError>,
@@ -8733,7 +11381,7 @@ pub mod smart_playlist_service_client {
         pub fn with_interceptor<F>(
             inner: T,
             interceptor: F,
-        ) -> SmartPlaylistServiceClient<InterceptedService<T, F>>
+        ) -> ScheduleServiceClient<InterceptedService<T, F>>
         where
             F: tonic::service::Interceptor,
             T::ResponseBody: Default,
@@ -8746,7 +11394,7 @@ pub mod smart_playlist_service_client {
             <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
                 Into<StdError> + std::marker::Send + std::marker::Sync,
         {
-            SmartPlaylistServiceClient::new(InterceptedService::new(inner, interceptor))
+            ScheduleServiceClient::new(InterceptedService::new(inner, interceptor))
         }
         /// Compress requests with the given encoding.
         ///
@@ -8779,202 +11427,181 @@ pub mod smart_playlist_service_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
-        pub async fn get_smart_playlists(
+        pub async fn get_schedules(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetSmartPlaylistsRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistsResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::GetSchedulesRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSchedulesResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylists",
+                "/rockbox.v1alpha1.ScheduleService/GetSchedules",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "GetSmartPlaylists",
+                "rockbox.v1alpha1.ScheduleService",
+                "GetSchedules",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_smart_playlist(
+        pub async fn get_schedule(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::GetScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetScheduleResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylist",
+                "/rockbox.v1alpha1.ScheduleService/GetSchedule",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "GetSmartPlaylist",
+                "rockbox.v1alpha1.ScheduleService",
+                "GetSchedule",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_smart_playlist(
+        pub async fn create_schedule(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::CreateSmartPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::CreateScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateScheduleResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/CreateSmartPlaylist",
+                "/rockbox.v1alpha1.ScheduleService/CreateSchedule",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "CreateSmartPlaylist",
+                "rockbox.v1alpha1.ScheduleService",
+                "CreateSchedule",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_smart_playlist(
+        pub async fn update_schedule(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateSmartPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::UpdateScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateScheduleResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/UpdateSmartPlaylist",
+                "/rockbox.v1alpha1.ScheduleService/UpdateSchedule",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "UpdateSmartPlaylist",
+                "rockbox.v1alpha1.ScheduleService",
+                "UpdateSchedule",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn delete_smart_playlist(
+        pub async fn delete_schedule(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::DeleteSmartPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::DeleteScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteScheduleResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/DeleteSmartPlaylist",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "DeleteSmartPlaylist",
-            ));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_smart_playlist_tracks(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetSmartPlaylistTracksRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetSmartPlaylistTracksResponse>,
-            tonic::Status,
-        > {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylistTracks",
+                "/rockbox.v1alpha1.ScheduleService/DeleteSchedule",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "GetSmartPlaylistTracks",
+                "rockbox.v1alpha1.ScheduleService",
+                "DeleteSchedule",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn play_smart_playlist(
+        pub async fn run_schedule(
             &mut self,
-            request: impl tonic::IntoRequest<super::PlaySmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::PlaySmartPlaylistResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::RunScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::RunScheduleResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/PlaySmartPlaylist",
+                "/rockbox.v1alpha1.ScheduleService/RunSchedule",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "PlaySmartPlaylist",
+                "rockbox.v1alpha1.ScheduleService",
+                "RunSchedule",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn record_track_played(
+        pub async fn get_sleep_timer(
             &mut self,
-            request: impl tonic::IntoRequest<super::RecordTrackPlayedRequest>,
-        ) -> std::result::Result<tonic::Response<super::RecordTrackPlayedResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::GetSleepTimerRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSleepTimerResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackPlayed",
+                "/rockbox.v1alpha1.ScheduleService/GetSleepTimer",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "RecordTrackPlayed",
+                "rockbox.v1alpha1.ScheduleService",
+                "GetSleepTimer",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn record_track_skipped(
+        pub async fn set_sleep_timer(
             &mut self,
-            request: impl tonic::IntoRequest<super::RecordTrackSkippedRequest>,
-        ) -> std::result::Result<tonic::Response<super::RecordTrackSkippedResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::SetSleepTimerRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetSleepTimerResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackSkipped",
+                "/rockbox.v1alpha1.ScheduleService/SetSleepTimer",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "RecordTrackSkipped",
+                "rockbox.v1alpha1.ScheduleService",
+                "SetSleepTimer",
             ));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_track_stats(
+        pub async fn cancel_sleep_timer(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetTrackStatsRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetTrackStatsResponse>, tonic::Status>
+            request: impl tonic::IntoRequest<super::CancelSleepTimerRequest>,
+        ) -> std::result::Result<tonic::Response<super::CancelSleepTimerResponse>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
             })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/rockbox.v1alpha1.SmartPlaylistService/GetTrackStats",
+                "/rockbox.v1alpha1.ScheduleService/CancelSleepTimer",
             );
             let mut req = request.into_request();
             req.extensions_mut().insert(GrpcMethod::new(
-                "rockbox.v1alpha1.SmartPlaylistService",
-                "GetTrackStats",
+                "rockbox.v1alpha1.ScheduleService",
+                "CancelSleepTimer",
             ));
             self.inner.unary(req, path, codec).await
         }
     }
 }
 /// Generated server implementations.
-pub mod smart_playlist_service_server {
+pub mod schedule_service_server {
     #![allow(
         unused_variables,
         dead_code,
@@ -8983,62 +11610,55 @@ pub mod smart_playlist_service_server {
         clippy::let_unit_value
     )]
     use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with SmartPlaylistServiceServer.
+    /// Generated trait containing gRPC methods that should be implemented for use with ScheduleServiceServer.
     #[async_trait]
-    pub trait SmartPlaylistService: std::marker::Send + std::marker::Sync + 'static {
-        async fn get_smart_playlists(
-            &self,
-            request: tonic::Request<super::GetSmartPlaylistsRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistsResponse>, tonic::Status>;
-        async fn get_smart_playlist(
-            &self,
-            request: tonic::Request<super::GetSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetSmartPlaylistResponse>, tonic::Status>;
-        async fn create_smart_playlist(
-            &self,
-            request: tonic::Request<super::CreateSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::CreateSmartPlaylistResponse>, tonic::Status>;
-        async fn update_smart_playlist(
+    pub trait ScheduleService: std::marker::Send + std::marker::Sync + 'static {
+        async fn get_schedules(
             &self,
-            request: tonic::Request<super::UpdateSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateSmartPlaylistResponse>, tonic::Status>;
-        async fn delete_smart_playlist(
+            request: tonic::Request<super::GetSchedulesRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSchedulesResponse>, tonic::Status>;
+        async fn get_schedule(
             &self,
-            request: tonic::Request<super::DeleteSmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::DeleteSmartPlaylistResponse>, tonic::Status>;
-        async fn get_smart_playlist_tracks(
+            request: tonic::Request<super::GetScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetScheduleResponse>, tonic::Status>;
+        async fn create_schedule(
             &self,
-            request: tonic::Request<super::GetSmartPlaylistTracksRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetSmartPlaylistTracksResponse>,
-            tonic::Status,
-        >;
-        async fn play_smart_playlist(
+            request: tonic::Request<super::CreateScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::CreateScheduleResponse>, tonic::Status>;
+        async fn update_schedule(
             &self,
-            request: tonic::Request<super::PlaySmartPlaylistRequest>,
-        ) -> std::result::Result<tonic::Response<super::PlaySmartPlaylistResponse>, tonic::Status>;
-        async fn record_track_played(
+            request: tonic::Request<super::UpdateScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateScheduleResponse>, tonic::Status>;
+        async fn delete_schedule(
             &self,
-            request: tonic::Request<super::RecordTrackPlayedRequest>,
-        ) -> std::result::Result<tonic::Response<super::RecordTrackPlayedResponse>, tonic::Status>;
-        async fn record_track_skipped(
+            request: tonic::Request<super::DeleteScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeleteScheduleResponse>, tonic::Status>;
+        async fn run_schedule(
             &self,
-            request: tonic::Request<super::RecordTrackSkippedRequest>,
-        ) -> std::result::Result<tonic::Response<super::RecordTrackSkippedResponse>, tonic::Status>;
-        async fn get_track_stats(
+            request: tonic::Request<super::RunScheduleRequest>,
+        ) -> std::result::Result<tonic::Response<super::RunScheduleResponse>, tonic::Status>;
+        async fn get_sleep_timer(
             &self,
-            request: tonic::Request<super::GetTrackStatsRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetTrackStatsResponse>, tonic::Status>;
+            request: tonic::Request<super::GetSleepTimerRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetSleepTimerResponse>, tonic::Status>;
+        async fn set_sleep_timer(
+            &self,
+            request: tonic::Request<super::SetSleepTimerRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetSleepTimerResponse>, tonic::Status>;
+        async fn cancel_sleep_timer(
+            &self,
+            request: tonic::Request<super::CancelSleepTimerRequest>,
+        ) -> std::result::Result<tonic::Response<super::CancelSleepTimerResponse>, tonic::Status>;
     }
     #[derive(Debug)]
-    pub struct SmartPlaylistServiceServer<T> {
+    pub struct ScheduleServiceServer<T> {
         inner: Arc<T>,
         accept_compression_encodings: EnabledCompressionEncodings,
         send_compression_encodings: EnabledCompressionEncodings,
         max_decoding_message_size: Option<usize>,
         max_encoding_message_size: Option<usize>,
     }
-    impl<T> SmartPlaylistServiceServer<T> {
+    impl<T> ScheduleServiceServer<T> {
         pub fn new(inner: T) -> Self {
             Self::from_arc(Arc::new(inner))
         }
@@ -9086,9 +11706,9 @@ pub mod smart_playlist_service_server {
             self
         }
     }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for SmartPlaylistServiceServer<T>
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ScheduleServiceServer<T>
     where
-        T: SmartPlaylistService,
+        T: ScheduleService,
         B: Body + std::marker::Send + 'static,
         B::Error: Into<StdError> + std::marker::Send + 'static,
     {
@@ -9103,66 +11723,21 @@ pub mod smart_playlist_service_server {
         }
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             match req.uri().path() {
-                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylists" => {
-                    #[allow(non_camel_case_types)]
-                    struct GetSmartPlaylistsSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::GetSmartPlaylistsRequest>
-                        for GetSmartPlaylistsSvc<T>
-                    {
-                        type Response = super::GetSmartPlaylistsResponse;
-                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(
-                            &mut self,
-                            request: tonic::Request<super::GetSmartPlaylistsRequest>,
-                        ) -> Self::Future {
-                            let inner = Arc::clone(&self.0);
-                            let fut = async move {
-                                <T as SmartPlaylistService>::get_smart_playlists(&inner, request)
-                                    .await
-                            };
-                            Box::pin(fut)
-                        }
-                    }
-                    let accept_compression_encodings = self.accept_compression_encodings;
-                    let send_compression_encodings = self.send_compression_encodings;
-                    let max_decoding_message_size = self.max_decoding_message_size;
-                    let max_encoding_message_size = self.max_encoding_message_size;
-                    let inner = self.inner.clone();
-                    let fut = async move {
-                        let method = GetSmartPlaylistsSvc(inner);
-                        let codec = tonic::codec::ProstCodec::default();
-                        let mut grpc = tonic::server::Grpc::new(codec)
-                            .apply_compression_config(
-                                accept_compression_encodings,
-                                send_compression_encodings,
-                            )
-                            .apply_max_message_size_config(
-                                max_decoding_message_size,
-                                max_encoding_message_size,
-                            );
-                        let res = grpc.unary(method, req).await;
-                        Ok(res)
-                    };
-                    Box::pin(fut)
-                }
-                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylist" => {
+                "/rockbox.v1alpha1.ScheduleService/GetSchedules" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::GetSmartPlaylistRequest>
-                        for GetSmartPlaylistSvc<T>
+                    struct GetSchedulesSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService> tonic::server::UnaryService<super::GetSchedulesRequest>
+                        for GetSchedulesSvc<T>
                     {
-                        type Response = super::GetSmartPlaylistResponse;
+                        type Response = super::GetSchedulesResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetSmartPlaylistRequest>,
+                            request: tonic::Request<super::GetSchedulesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::get_smart_playlist(&inner, request)
-                                    .await
+                                <T as ScheduleService>::get_schedules(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9173,7 +11748,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetSmartPlaylistSvc(inner);
+                        let method = GetSchedulesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9189,23 +11764,21 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/CreateSmartPlaylist" => {
+                "/rockbox.v1alpha1.ScheduleService/GetSchedule" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::CreateSmartPlaylistRequest>
-                        for CreateSmartPlaylistSvc<T>
+                    struct GetScheduleSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService> tonic::server::UnaryService<super::GetScheduleRequest>
+                        for GetScheduleSvc<T>
                     {
-                        type Response = super::CreateSmartPlaylistResponse;
+                        type Response = super::GetScheduleResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateSmartPlaylistRequest>,
+                            request: tonic::Request<super::GetScheduleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::create_smart_playlist(&inner, request)
-                                    .await
+                                <T as ScheduleService>::get_schedule(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9216,7 +11789,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CreateSmartPlaylistSvc(inner);
+                        let method = GetScheduleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9232,23 +11805,22 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/UpdateSmartPlaylist" => {
+                "/rockbox.v1alpha1.ScheduleService/CreateSchedule" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::UpdateSmartPlaylistRequest>
-                        for UpdateSmartPlaylistSvc<T>
+                    struct CreateScheduleSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService>
+                        tonic::server::UnaryService<super::CreateScheduleRequest>
+                        for CreateScheduleSvc<T>
                     {
-                        type Response = super::UpdateSmartPlaylistResponse;
+                        type Response = super::CreateScheduleResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateSmartPlaylistRequest>,
+                            request: tonic::Request<super::CreateScheduleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::update_smart_playlist(&inner, request)
-                                    .await
+                                <T as ScheduleService>::create_schedule(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9259,7 +11831,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = UpdateSmartPlaylistSvc(inner);
+                        let method = CreateScheduleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9275,23 +11847,22 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/DeleteSmartPlaylist" => {
+                "/rockbox.v1alpha1.ScheduleService/UpdateSchedule" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteSmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::DeleteSmartPlaylistRequest>
-                        for DeleteSmartPlaylistSvc<T>
+                    struct UpdateScheduleSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService>
+                        tonic::server::UnaryService<super::UpdateScheduleRequest>
+                        for UpdateScheduleSvc<T>
                     {
-                        type Response = super::DeleteSmartPlaylistResponse;
+                        type Response = super::UpdateScheduleResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteSmartPlaylistRequest>,
+                            request: tonic::Request<super::UpdateScheduleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::delete_smart_playlist(&inner, request)
-                                    .await
+                                <T as ScheduleService>::update_schedule(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9302,7 +11873,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = DeleteSmartPlaylistSvc(inner);
+                        let method = UpdateScheduleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9318,25 +11889,22 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/GetSmartPlaylistTracks" => {
+                "/rockbox.v1alpha1.ScheduleService/DeleteSchedule" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSmartPlaylistTracksSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::GetSmartPlaylistTracksRequest>
-                        for GetSmartPlaylistTracksSvc<T>
+                    struct DeleteScheduleSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService>
+                        tonic::server::UnaryService<super::DeleteScheduleRequest>
+                        for DeleteScheduleSvc<T>
                     {
-                        type Response = super::GetSmartPlaylistTracksResponse;
+                        type Response = super::DeleteScheduleResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetSmartPlaylistTracksRequest>,
+                            request: tonic::Request<super::DeleteScheduleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::get_smart_playlist_tracks(
-                                    &inner, request,
-                                )
-                                .await
+                                <T as ScheduleService>::delete_schedule(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9347,7 +11915,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetSmartPlaylistTracksSvc(inner);
+                        let method = DeleteScheduleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9363,23 +11931,21 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/PlaySmartPlaylist" => {
+                "/rockbox.v1alpha1.ScheduleService/RunSchedule" => {
                     #[allow(non_camel_case_types)]
-                    struct PlaySmartPlaylistSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::PlaySmartPlaylistRequest>
-                        for PlaySmartPlaylistSvc<T>
+                    struct RunScheduleSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService> tonic::server::UnaryService<super::RunScheduleRequest>
+                        for RunScheduleSvc<T>
                     {
-                        type Response = super::PlaySmartPlaylistResponse;
+                        type Response = super::RunScheduleResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::PlaySmartPlaylistRequest>,
+                            request: tonic::Request<super::RunScheduleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::play_smart_playlist(&inner, request)
-                                    .await
+                                <T as ScheduleService>::run_schedule(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9390,7 +11956,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = PlaySmartPlaylistSvc(inner);
+                        let method = RunScheduleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9406,23 +11972,22 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackPlayed" => {
+                "/rockbox.v1alpha1.ScheduleService/GetSleepTimer" => {
                     #[allow(non_camel_case_types)]
-                    struct RecordTrackPlayedSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::RecordTrackPlayedRequest>
-                        for RecordTrackPlayedSvc<T>
+                    struct GetSleepTimerSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService>
+                        tonic::server::UnaryService<super::GetSleepTimerRequest>
+                        for GetSleepTimerSvc<T>
                     {
-                        type Response = super::RecordTrackPlayedResponse;
+                        type Response = super::GetSleepTimerResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RecordTrackPlayedRequest>,
+                            request: tonic::Request<super::GetSleepTimerRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::record_track_played(&inner, request)
-                                    .await
+                                <T as ScheduleService>::get_sleep_timer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9433,7 +11998,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RecordTrackPlayedSvc(inner);
+                        let method = GetSleepTimerSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9449,23 +12014,22 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/RecordTrackSkipped" => {
+                "/rockbox.v1alpha1.ScheduleService/SetSleepTimer" => {
                     #[allow(non_camel_case_types)]
-                    struct RecordTrackSkippedSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::RecordTrackSkippedRequest>
-                        for RecordTrackSkippedSvc<T>
+                    struct SetSleepTimerSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService>
+                        tonic::server::UnaryService<super::SetSleepTimerRequest>
+                        for SetSleepTimerSvc<T>
                     {
-                        type Response = super::RecordTrackSkippedResponse;
+                        type Response = super::SetSleepTimerResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RecordTrackSkippedRequest>,
+                            request: tonic::Request<super::SetSleepTimerRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::record_track_skipped(&inner, request)
-                                    .await
+                                <T as ScheduleService>::set_sleep_timer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9476,7 +12040,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RecordTrackSkippedSvc(inner);
+                        let method = SetSleepTimerSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9492,22 +12056,22 @@ pub mod smart_playlist_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rockbox.v1alpha1.SmartPlaylistService/GetTrackStats" => {
+                "/rockbox.v1alpha1.ScheduleService/CancelSleepTimer" => {
                     #[allow(non_camel_case_types)]
-                    struct GetTrackStatsSvc<T: SmartPlaylistService>(pub Arc<T>);
-                    impl<T: SmartPlaylistService>
-                        tonic::server::UnaryService<super::GetTrackStatsRequest>
-                        for GetTrackStatsSvc<T>
+                    struct CancelSleepTimerSvc<T: ScheduleService>(pub Arc<T>);
+                    impl<T: ScheduleService>
+                        tonic::server::UnaryService<super::CancelSleepTimerRequest>
+                        for CancelSleepTimerSvc<T>
                     {
-                        type Response = super::GetTrackStatsResponse;
+                        type Response = super::CancelSleepTimerResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetTrackStatsRequest>,
+                            request: tonic::Request<super::CancelSleepTimerRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as SmartPlaylistService>::get_track_stats(&inner, request).await
+                                <T as ScheduleService>::cancel_sleep_timer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -9518,7 +12082,7 @@ pub mod smart_playlist_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetTrackStatsSvc(inner);
+                        let method = CancelSleepTimerSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -9550,7 +12114,7 @@ pub mod smart_playlist_service_server {
             }
         }
     }
-    impl<T> Clone for SmartPlaylistServiceServer<T> {
+    impl<T> Clone for ScheduleServiceServer<T> {
         fn clone(&self) -> Self {
             let inner = self.inner.clone();
             Self {
@@ -9563,8 +12127,8 @@ pub mod smart_playlist_service_server {
         }
     }
     /// Generated gRPC service name
-    pub const SERVICE_NAME: &str = "rockbox.v1alpha1.SmartPlaylistService";
-    impl<T> tonic::server::NamedService for SmartPlaylistServiceServer<T> {
+    pub const SERVICE_NAME: &str = "rockbox.v1alpha1.ScheduleService";
+    impl<T> tonic::server::NamedService for ScheduleServiceServer<T> {
         const NAME: &'static str = SERVICE_NAME;
     }
 }
@@ -10070,6 +12634,18 @@ pub struct SaveSettingsRequest {
     pub pbe: ::core::option::Option<i32>,
     #[prost(int32, optional, tag = "40")]
     pub pbe_precut: ::core::option::Option<i32>,
+    #[prost(string, optional, tag = "41")]
+    pub shuffle_mode: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "42")]
+    pub playlists_dir: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(float, optional, tag = "43")]
+    pub stream_crossfade_secs: ::core::option::Option<f32>,
+    #[prost(string, optional, tag = "44")]
+    pub stream_crossfade_curve: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(float, optional, tag = "45")]
+    pub mixramp_db: ::core::option::Option<f32>,
+    #[prost(float, optional, tag = "46")]
+    pub mixramp_delay: ::core::option::Option<f32>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct SaveSettingsResponse {}
@@ -10667,6 +13243,52 @@ pub struct SetPbeRequest {
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct SetPbeResponse {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetMixerRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMixerResponse {
+    #[prost(string, tag = "1")]
+    pub mode: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub requested_mode: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag = "4")]
+    pub device: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub control: ::prost::alloc::string::String,
+    #[prost(bool, tag = "6")]
+    pub dither: bool,
+    #[prost(string, tag = "7")]
+    pub replaygain: ::prost::alloc::string::String,
+    #[prost(float, tag = "8")]
+    pub preamp: f32,
+    #[prost(bool, tag = "9")]
+    pub prevent_clipping: bool,
+    #[prost(float, tag = "10")]
+    pub replaygain_db: f32,
+    #[prost(bool, tag = "11")]
+    pub clipping_prevented: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetMixerRequest {
+    #[prost(string, optional, tag = "1")]
+    pub mode: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "2")]
+    pub device: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub control: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bool, optional, tag = "4")]
+    pub dither: ::core::option::Option<bool>,
+    #[prost(string, optional, tag = "5")]
+    pub replaygain: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(float, optional, tag = "6")]
+    pub preamp: ::core::option::Option<f32>,
+    #[prost(bool, optional, tag = "7")]
+    pub prevent_clipping: ::core::option::Option<bool>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SetMixerResponse {}
 /// Generated client implementations.
 pub mod sound_service_client {
     #![allow(
@@ -11092,6 +13714,36 @@ pub mod sound_service_client {
                 .insert(GrpcMethod::new("rockbox.v1alpha1.SoundService", "SetPbe"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn get_mixer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetMixerRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetMixerResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rockbox.v1alpha1.SoundService/GetMixer");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rockbox.v1alpha1.SoundService", "GetMixer"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_mixer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetMixerRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetMixerResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rockbox.v1alpha1.SoundService/SetMixer");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rockbox.v1alpha1.SoundService", "SetMixer"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -11187,6 +13839,14 @@ pub mod sound_service_server {
             &self,
             request: tonic::Request<super::SetPbeRequest>,
         ) -> std::result::Result<tonic::Response<super::SetPbeResponse>, tonic::Status>;
+        async fn get_mixer(
+            &self,
+            request: tonic::Request<super::GetMixerRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetMixerResponse>, tonic::Status>;
+        async fn set_mixer(
+            &self,
+            request: tonic::Request<super::SetMixerRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetMixerResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct SoundServiceServer<T> {
@@ -12057,6 +14717,84 @@ pub mod sound_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/rockbox.v1alpha1.SoundService/GetMixer" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetMixerSvc<T: SoundService>(pub Arc<T>);
+                    impl<T: SoundService> tonic::server::UnaryService<super::GetMixerRequest> for GetMixerSvc<T> {
+                        type Response = super::GetMixerResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetMixerRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SoundService>::get_mixer(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetMixerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rockbox.v1alpha1.SoundService/SetMixer" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetMixerSvc<T: SoundService>(pub Arc<T>);
+                    impl<T: SoundService> tonic::server::UnaryService<super::SetMixerRequest> for SetMixerSvc<T> {
+                        type Response = super::SetMixerResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetMixerRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SoundService>::set_mixer(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetMixerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => Box::pin(async move {
                     let mut response = http::Response::new(empty_body());
                     let headers = response.headers_mut();
@@ -0,0 +1,85 @@
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use rockbox_auth::{grpc_scope, request_token, Auth, Denied};
+use tonic::{body::BoxBody, transport::server::TcpConnectInfo, Status};
+use tower::{Layer, Service};
+
+/// Checks the API token of every gRPC (and gRPC-web) call against the scope
+/// its method needs, before the call reaches a service.
+#[derive(Clone)]
+pub struct AuthLayer {
+    auth: Auth,
+}
+
+impl AuthLayer {
+    pub fn new(auth: Auth) -> Self {
+        Self { auth }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthService<S> {
+    inner: S,
+    auth: Auth,
+}
+
+impl<S, B> Service<http::Request<B>> for AuthService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // The clone may not be ready; keep the one poll_ready was called on.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let auth = self.auth.clone();
+
+        Box::pin(async move {
+            let peer = req
+                .extensions()
+                .get::<TcpConnectInfo>()
+                .and_then(|info| info.remote_addr())
+                .map(|addr| addr.ip());
+            let token = request_token(
+                req.headers()
+                    .get(http::header::AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok()),
+                req.uri().query().unwrap_or_default(),
+            );
+            let required = grpc_scope(req.uri().path());
+
+            match auth.authorize(peer, token.as_deref(), required).await {
+                Ok(_) => inner.call(req).await,
+                Err(denied) => {
+                    let status = match denied {
+                        Denied::Unauthenticated => Status::unauthenticated(denied.to_string()),
+                        Denied::Forbidden { .. } => Status::permission_denied(denied.to_string()),
+                        Denied::Internal(_) => Status::internal(denied.to_string()),
+                    };
+                    Ok(status.into_http())
+                }
+            }
+        })
+    }
+}
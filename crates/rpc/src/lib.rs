@@ -2,6 +2,7 @@ use anyhow::Error;
 use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
 use tokio::fs;
 
+pub mod auth;
 pub mod bluetooth;
 pub mod browse;
 pub mod device;
@@ -10,12 +11,15 @@ pub mod library;
 pub mod metadata;
 pub mod playback;
 pub mod playlist;
+pub mod radio;
 pub mod saved_playlist;
+pub mod schedule;
 pub mod server;
 pub mod settings;
 pub mod smart_playlist;
 pub mod sound;
 pub mod system;
+pub mod tls;
 pub mod types;
 
 pub const AUDIO_EXTENSIONS: [&str; 17] = [
@@ -980,6 +984,14 @@ pub mod api {
                     chromecast_port: None,
                     snapcast_tcp_host: None,
                     snapcast_tcp_port: None,
+                    alsa_device: None,
+                    alsa_exclusive: None,
+                    alsa_bit_perfect: None,
+                    alsa_dop: None,
+                    mixer: None,
+                    mixer_device: None,
+                    mixer_control: None,
+                    mixer_dither: None,
                     subsonic_username: None,
                     subsonic_password: None,
                     subsonic_port: None,
@@ -987,6 +999,8 @@ pub mod api {
                     cmaf_http_port: None,
                     cmaf_bitrate: None,
                     cmaf_segment_dir: None,
+                    cmaf_renditions: None,
+                    cmaf_dvr_window: None,
                     s3_enabled: None,
                     s3_host: None,
                     s3_port: None,
@@ -994,6 +1008,30 @@ pub mod api {
                     s3_secret_key: None,
                     lastfm_api_key: None,
                     musicbrainz_user_agent: None,
+                    mqtt_host: None,
+                    mqtt_port: None,
+                    mqtt_username: None,
+                    mqtt_password: None,
+                    mqtt_topic: None,
+                    mqtt_client_id: None,
+                    auth_enabled: None,
+                    auth_trust_localhost: None,
+                    tls_cert: None,
+                    tls_key: None,
+                    tls_self_signed: None,
+                    tls_port_offset: None,
+                    tls_only: None,
+                    auto_queue: None,
+                    auto_queue_source: None,
+                    auto_queue_smart_playlist: None,
+                    auto_queue_seed: None,
+                    shuffle_mode: self.shuffle_mode,
+                    playlists_dir: self.playlists_dir,
+                    stream_crossfade_secs: self.stream_crossfade_secs,
+                    stream_crossfade_curve: self.stream_crossfade_curve,
+                    mixramp_db: self.mixramp_db,
+                    mixramp_delay: self.mixramp_delay,
+                    stream_timeshift_window: None,
                 }
             }
         }
@@ -1024,6 +1062,22 @@ pub fn rockbox_url() -> String {
     format!("http://127.0.0.1:{}", port)
 }
 
+/// HTTP client for calls to `rockbox_url()`. It carries the per-process
+/// internal token, so these hops keep working once API tokens are enforced.
+/// Never point it at another host.
+pub fn internal_client() -> reqwest::Client {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Ok(value) =
+        reqwest::header::HeaderValue::from_str(&rockbox_auth::internal_authorization())
+    {
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap_or_default()
+}
+
 pub fn read_files(path: String) -> BoxFuture<'static, Result<Vec<String>, Error>> {
     Box::pin(async move {
         if path.starts_with("upnp://") {
@@ -1091,7 +1145,9 @@ pub fn read_upnp_files(path: String) -> BoxFuture<'static, Result<Vec<String>, E
 #[macro_export]
 macro_rules! check_and_load_player {
     ($response:expr, $tracks:expr, $shuffle:expr) => {
-        let client = reqwest::Client::new();
+        // Firmware playback takes the ALSA device back from a DSD file.
+        rockbox_alsa_sink::stop_dsd();
+        let client = $crate::internal_client();
         let response = client
             .get(format!("{}/player", rockbox_url()))
             .send()
@@ -1103,7 +1159,7 @@ macro_rules! check_and_load_player {
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
         if player.is_cast_device {
-            let client = reqwest::Client::new();
+            let client = $crate::internal_client();
             let body = serde_json::json!({
                 "tracks": $tracks,
                 "shuffle": $shuffle,
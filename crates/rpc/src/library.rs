@@ -1,8 +1,14 @@
 use std::pin::Pin;
 
 use rockbox_graphql::{simplebroker::SimpleBroker, types::ScanCompleted};
-use rockbox_library::{entity::favourites::Favourites, repo};
-use rockbox_playlists::{resolver, rules::RuleCriteria, PlaylistStore};
+use rockbox_library::{
+    entity::{
+        favourites::Favourites,
+        rating::{self, KIND_ALBUM, KIND_ARTIST, KIND_TRACK, MAX_RATING},
+    },
+    lyrics, ratings, repo,
+};
+use rockbox_playlists::{resolver, rules::RuleCriteria};
 use sqlx::Sqlite;
 use tokio_stream::{Stream, StreamExt};
 
@@ -12,11 +18,13 @@ use crate::{
         FilterAlbumsResponse, FilterArtistsRequest, FilterArtistsResponse, GetAlbumRequest,
         GetAlbumResponse, GetAlbumsRequest, GetAlbumsResponse, GetArtistRequest, GetArtistResponse,
         GetArtistsRequest, GetArtistsResponse, GetLikedAlbumsRequest, GetLikedAlbumsResponse,
-        GetLikedTracksRequest, GetLikedTracksResponse, GetTrackRequest, GetTrackResponse,
-        GetTracksRequest, GetTracksResponse, LikeAlbumRequest, LikeAlbumResponse, LikeTrackRequest,
-        LikeTrackResponse, ScanLibraryRequest, ScanLibraryResponse, SearchPlaylist, SearchRequest,
-        SearchResponse, StreamLibraryRequest, StreamLibraryResponse, UnlikeAlbumRequest,
-        UnlikeAlbumResponse, UnlikeTrackRequest, UnlikeTrackResponse,
+        GetLikedTracksRequest, GetLikedTracksResponse, GetLyricsRequest, GetLyricsResponse,
+        GetRatingRequest, GetRatingResponse, GetRatingsRequest, GetRatingsResponse,
+        GetTrackRequest, GetTrackResponse, GetTracksRequest, GetTracksResponse, LikeAlbumRequest,
+        LikeAlbumResponse, LikeTrackRequest, LikeTrackResponse, LyricLine, Rating,
+        ScanLibraryRequest, ScanLibraryResponse, SearchPlaylist, SearchRequest, SearchResponse,
+        SetRatingRequest, SetRatingResponse, StreamLibraryRequest, StreamLibraryResponse,
+        UnlikeAlbumRequest, UnlikeAlbumResponse, UnlikeTrackRequest, UnlikeTrackResponse,
     },
     rockbox_url,
 };
@@ -24,17 +32,11 @@ use crate::{
 pub struct Library {
     pool: sqlx::Pool<Sqlite>,
     client: reqwest::Client,
-    store: PlaylistStore,
 }
 
 impl Library {
     pub fn new(pool: sqlx::Pool<Sqlite>, client: reqwest::Client) -> Self {
-        let store = PlaylistStore::new(pool.clone());
-        Self {
-            pool,
-            client,
-            store,
-        }
+        Self { pool, client }
     }
 }
 
@@ -373,7 +375,7 @@ impl LibraryService for Library {
         let rules_json = request.into_inner().rules_json;
         let criteria: RuleCriteria = serde_json::from_str(&rules_json)
             .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
-        let albums = resolver::filter_albums(&self.store, &self.pool, &criteria)
+        let albums = resolver::filter_albums(&self.pool, &criteria)
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
         Ok(tonic::Response::new(FilterAlbumsResponse {
@@ -388,7 +390,7 @@ impl LibraryService for Library {
         let rules_json = request.into_inner().rules_json;
         let criteria: RuleCriteria = serde_json::from_str(&rules_json)
             .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
-        let artists = resolver::filter_artists(&self.store, &self.pool, &criteria)
+        let artists = resolver::filter_artists(&self.pool, &criteria)
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
         Ok(tonic::Response::new(FilterArtistsResponse {
@@ -396,6 +398,109 @@ impl LibraryService for Library {
         }))
     }
 
+    async fn get_lyrics(
+        &self,
+        request: tonic::Request<GetLyricsRequest>,
+    ) -> Result<tonic::Response<GetLyricsResponse>, tonic::Status> {
+        let track_id = request.into_inner().track_id;
+        let track = repo::track::find(self.pool.clone(), &track_id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let lyrics = match track {
+            Some(track) => lyrics::get_lyrics(self.pool.clone(), &track)
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?,
+            None => None,
+        };
+        let Some(lyrics) = lyrics else {
+            return Ok(tonic::Response::new(GetLyricsResponse::default()));
+        };
+        Ok(tonic::Response::new(GetLyricsResponse {
+            found: true,
+            synced: lyrics.synced,
+            source: lyrics.source,
+            lines: lyrics
+                .lines
+                .into_iter()
+                .map(|l| LyricLine {
+                    start_ms: l.start_ms,
+                    text: l.text,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_rating(
+        &self,
+        request: tonic::Request<GetRatingRequest>,
+    ) -> Result<tonic::Response<GetRatingResponse>, tonic::Status> {
+        let params = request.into_inner();
+        check_kind(&params.kind)?;
+        let rating = repo::rating::find(self.pool.clone(), &params.kind, &params.id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetRatingResponse {
+            rating: rating.unwrap_or(0) as i32,
+        }))
+    }
+
+    async fn set_rating(
+        &self,
+        request: tonic::Request<SetRatingRequest>,
+    ) -> Result<tonic::Response<SetRatingResponse>, tonic::Status> {
+        let params = request.into_inner();
+        check_kind(&params.kind)?;
+        if !(0..=MAX_RATING as i32).contains(&params.rating) {
+            return Err(tonic::Status::invalid_argument(format!(
+                "rating must be between 0 and {}",
+                MAX_RATING
+            )));
+        }
+        ratings::set(
+            self.pool.clone(),
+            &params.kind,
+            &params.id,
+            params.rating as u8,
+        )
+        .await
+        .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(SetRatingResponse {}))
+    }
+
+    async fn get_ratings(
+        &self,
+        request: tonic::Request<GetRatingsRequest>,
+    ) -> Result<tonic::Response<GetRatingsResponse>, tonic::Status> {
+        let kinds = match request.into_inner().kind {
+            Some(kind) => {
+                check_kind(&kind)?;
+                vec![kind]
+            }
+            None => [KIND_TRACK, KIND_ALBUM, KIND_ARTIST]
+                .map(String::from)
+                .to_vec(),
+        };
+        let mut results = vec![];
+        for kind in kinds {
+            results.extend(
+                repo::rating::all(self.pool.clone(), &kind)
+                    .await
+                    .map_err(|e| tonic::Status::internal(e.to_string()))?,
+            );
+        }
+        Ok(tonic::Response::new(GetRatingsResponse {
+            ratings: results
+                .into_iter()
+                .map(|r| Rating {
+                    kind: r.kind,
+                    item_id: r.item_id,
+                    rating: r.rating as i32,
+                    updated_at: r.updated_at.timestamp(),
+                })
+                .collect(),
+        }))
+    }
+
     type StreamLibraryStream = Pin<
         Box<
             dyn Stream<Item = Result<StreamLibraryResponse, tonic::Status>> + Send + Sync + 'static,
@@ -417,3 +522,13 @@ impl LibraryService for Library {
         ))
     }
 }
+
+fn check_kind(kind: &str) -> Result<(), tonic::Status> {
+    match rating::is_kind(kind) {
+        true => Ok(()),
+        false => Err(tonic::Status::invalid_argument(format!(
+            "unknown kind: {}",
+            kind
+        ))),
+    }
+}
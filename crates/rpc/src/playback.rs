@@ -4,10 +4,12 @@ use crate::{
     api::rockbox::v1alpha1::{playback_service_server::PlaybackService, *},
     check_and_load_player, read_files, rockbox_url, AUDIO_EXTENSIONS,
 };
+use rockbox_autoqueue::AutoQueueUpdate;
 use rockbox_graphql::schema;
 use rockbox_graphql::schema::objects::track::Track;
 use rockbox_graphql::simplebroker::SimpleBroker;
 use rockbox_library::repo;
+use rockbox_similarity::SimilarityStore;
 use rockbox_sys::{self as rb, types::audio_status::AudioStatus};
 use sqlx::Sqlite;
 use tokio_stream::{Stream, StreamExt};
@@ -61,12 +63,16 @@ impl PlaybackService for Playback {
         _request: tonic::Request<PauseRequest>,
     ) -> Result<tonic::Response<PauseResponse>, tonic::Status> {
         // HLS/DASH pause is a local sink-side action — it just stops pushing
-        // PCM. The broadcaster keeps streaming and the consumer's segment
-        // buffer fills up; resume picks up at the live edge again.
+        // PCM. The broadcaster keeps streaming and live segments pile up in
+        // the consumer's time-shift buffer; resume picks up where it paused.
         if rockbox_hls::player_is_active() {
             rockbox_hls::player_pause();
             return Ok(tonic::Response::new(PauseResponse::default()));
         }
+        if rockbox_alsa_sink::dsd_active() {
+            rockbox_alsa_sink::set_dsd_paused(true);
+            return Ok(tonic::Response::new(PauseResponse::default()));
+        }
         self.client
             .put(&format!("{}/player/pause", rockbox_url()))
             .send()
@@ -90,7 +96,11 @@ impl PlaybackService for Playback {
             }
             return Ok(tonic::Response::new(PlayOrPauseResponse::default()));
         }
-        let client = reqwest::Client::new();
+        if rockbox_alsa_sink::dsd_active() {
+            rockbox_alsa_sink::set_dsd_paused(!rockbox_alsa_sink::dsd_paused());
+            return Ok(tonic::Response::new(PlayOrPauseResponse::default()));
+        }
+        let client = crate::internal_client();
         let response = client
             .get(&format!("{}/player/status", rockbox_url()))
             .send()
@@ -100,7 +110,7 @@ impl PlaybackService for Playback {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         match response.status {
             1 => {
                 client
@@ -151,6 +161,10 @@ impl PlaybackService for Playback {
             rockbox_hls::player_resume();
             return Ok(tonic::Response::new(ResumeResponse::default()));
         }
+        if rockbox_alsa_sink::dsd_active() {
+            rockbox_alsa_sink::set_dsd_paused(false);
+            return Ok(tonic::Response::new(ResumeResponse::default()));
+        }
         self.client
             .put(&format!("{}/player/resume", rockbox_url()))
             .send()
@@ -332,7 +346,7 @@ impl PlaybackService for Playback {
         // consumer stops decoding and pushing PCM. Don't propagate to the
         // broadcaster — other consumers may still be listening.
         let was_hls = rockbox_hls::player_stop();
-        if was_hls {
+        if was_hls || rockbox_alsa_sink::stop_dsd() {
             return Ok(tonic::Response::new(HardStopResponse::default()));
         }
         tokio::task::spawn_blocking(move || {
@@ -363,7 +377,7 @@ impl PlaybackService for Playback {
         check_and_load_player!(response, tracks, shuffle.unwrap_or_default());
 
         let url = format!("{}/playlists", rockbox_url());
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .post(&url)
             .json(&body)
@@ -373,7 +387,7 @@ impl PlaybackService for Playback {
 
         if let Some(true) = shuffle {
             let url = format!("{}/playlists/shuffle", rockbox_url());
-            let client = reqwest::Client::new();
+            let client = crate::internal_client();
             client
                 .put(&url)
                 .send()
@@ -386,7 +400,7 @@ impl PlaybackService for Playback {
             None => format!("{}/playlists/start", rockbox_url()),
         };
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .put(&url)
             .send()
@@ -416,7 +430,7 @@ impl PlaybackService for Playback {
         check_and_load_player!(response, tracks, shuffle.unwrap_or_default());
 
         let url = format!("{}/playlists", rockbox_url());
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .post(&url)
             .json(&body)
@@ -426,7 +440,7 @@ impl PlaybackService for Playback {
 
         if let Some(true) = shuffle {
             let url = format!("{}/playlists/shuffle", rockbox_url());
-            let client = reqwest::Client::new();
+            let client = crate::internal_client();
             client
                 .put(&url)
                 .send()
@@ -439,7 +453,7 @@ impl PlaybackService for Playback {
             None => format!("{}/playlists/start", rockbox_url()),
         };
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .put(&url)
             .send()
@@ -448,6 +462,83 @@ impl PlaybackService for Playback {
         Ok(tonic::Response::new(PlayArtistTracksResponse::default()))
     }
 
+    async fn play_track_radio(
+        &self,
+        request: tonic::Request<PlayTrackRadioRequest>,
+    ) -> Result<tonic::Response<PlayTrackRadioResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let limit = request
+            .limit
+            .map(|l| l.max(1) as usize)
+            .unwrap_or(rockbox_similarity::DEFAULT_LIMIT);
+        let tracks = SimilarityStore::new(self.pool.clone())
+            .radio(&request.track_id, limit)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let tracks = tracks.into_iter().map(|t| t.path).collect::<Vec<String>>();
+        let body = serde_json::json!({
+            "tracks": tracks,
+        });
+
+        let response = PlayTrackRadioResponse::default();
+        check_and_load_player!(response, tracks, false);
+
+        let url = format!("{}/playlists", rockbox_url());
+        let client = crate::internal_client();
+        client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+        let url = format!("{}/playlists/start", rockbox_url());
+        client
+            .put(&url)
+            .send()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(PlayTrackRadioResponse::default()))
+    }
+
+    async fn get_auto_queue(
+        &self,
+        _request: tonic::Request<GetAutoQueueRequest>,
+    ) -> Result<tonic::Response<GetAutoQueueResponse>, tonic::Status> {
+        let config = rockbox_autoqueue::config();
+        Ok(tonic::Response::new(GetAutoQueueResponse {
+            enabled: config.enabled,
+            source: config.source.as_str().to_string(),
+            smart_playlist_id: config.smart_playlist_id,
+            seed_track_id: config.seed_track_id,
+        }))
+    }
+
+    async fn set_auto_queue(
+        &self,
+        request: tonic::Request<SetAutoQueueRequest>,
+    ) -> Result<tonic::Response<SetAutoQueueResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let source = request
+            .source
+            .map(|s| s.parse::<rockbox_autoqueue::Source>())
+            .transpose()
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        let config = rockbox_autoqueue::update(AutoQueueUpdate {
+            enabled: request.enabled,
+            source,
+            smart_playlist_id: request.smart_playlist_id,
+            seed_track_id: request.seed_track_id,
+        })
+        .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        Ok(tonic::Response::new(SetAutoQueueResponse {
+            enabled: config.enabled,
+            source: config.source.as_str().to_string(),
+            smart_playlist_id: config.smart_playlist_id,
+            seed_track_id: config.seed_track_id,
+        }))
+    }
+
     async fn play_playlist(
         &self,
         _request: tonic::Request<PlayPlaylistRequest>,
@@ -519,7 +610,7 @@ impl PlaybackService for Playback {
         check_and_load_player!(response, tracks, shuffle.unwrap_or_default());
 
         let url = format!("{}/playlists", rockbox_url());
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .post(&url)
             .json(&body)
@@ -529,7 +620,7 @@ impl PlaybackService for Playback {
 
         if let Some(true) = shuffle {
             let url = format!("{}/playlists/shuffle", rockbox_url());
-            let client = reqwest::Client::new();
+            let client = crate::internal_client();
             client
                 .put(&url)
                 .send()
@@ -542,7 +633,7 @@ impl PlaybackService for Playback {
             None => format!("{}/playlists/start", rockbox_url()),
         };
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .put(&url)
             .send()
@@ -612,7 +703,7 @@ impl PlaybackService for Playback {
         check_and_load_player!(response, tracks, shuffle.unwrap_or_default());
 
         let url = format!("{}/playlists", rockbox_url());
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .post(&url)
             .json(&body)
@@ -622,7 +713,7 @@ impl PlaybackService for Playback {
 
         if let Some(true) = shuffle {
             let url = format!("{}/playlists/shuffle", rockbox_url());
-            let client = reqwest::Client::new();
+            let client = crate::internal_client();
             client
                 .put(&url)
                 .send()
@@ -635,7 +726,7 @@ impl PlaybackService for Playback {
             None => format!("{}/playlists/start", rockbox_url()),
         };
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .put(&url)
             .send()
@@ -666,6 +757,19 @@ impl PlaybackService for Playback {
             return Ok(tonic::Response::new(PlayTrackResponse::default()));
         }
 
+        // The firmware has no DSD decoder: DSF / DFF files go straight to the
+        // ALSA sink as DoP, with the firmware stopped so the device is free.
+        if rockbox_alsa_sink::dsd::is_dsd_path(&path) {
+            rockbox_hls::player_stop();
+            tokio::task::spawn_blocking(move || {
+                rb::with_kernel_lock(|| rb::playback::hard_stop());
+            })
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+            rockbox_alsa_sink::play_dsd(&path).map_err(tonic::Status::failed_precondition)?;
+            return Ok(tonic::Response::new(PlayTrackResponse::default()));
+        }
+
         let tracks = vec![path.clone()];
 
         let body = serde_json::json!({
@@ -684,7 +788,7 @@ impl PlaybackService for Playback {
             .and_then(|response| response.error_for_status())
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         let url = format!("{}/playlists/start", rockbox_url());
         client
             .put(&url)
@@ -715,7 +819,7 @@ impl PlaybackService for Playback {
         check_and_load_player!(response, tracks, shuffle.unwrap_or_default());
 
         let url = format!("{}/playlists", rockbox_url());
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .post(&url)
             .json(&body)
@@ -725,7 +829,7 @@ impl PlaybackService for Playback {
 
         if let Some(true) = shuffle {
             let url = format!("{}/playlists/shuffle", rockbox_url());
-            let client = reqwest::Client::new();
+            let client = crate::internal_client();
             client
                 .put(&url)
                 .send()
@@ -738,7 +842,7 @@ impl PlaybackService for Playback {
             None => format!("{}/playlists/start", rockbox_url()),
         };
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .put(&url)
             .send()
@@ -767,7 +871,7 @@ impl PlaybackService for Playback {
         check_and_load_player!(response, tracks, shuffle.unwrap_or_default());
 
         let url = format!("{}/playlists", rockbox_url());
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .post(&url)
             .json(&body)
@@ -777,7 +881,7 @@ impl PlaybackService for Playback {
 
         if let Some(true) = shuffle {
             let url = format!("{}/playlists/shuffle", rockbox_url());
-            let client = reqwest::Client::new();
+            let client = crate::internal_client();
             client
                 .put(&url)
                 .send()
@@ -790,7 +894,7 @@ impl PlaybackService for Playback {
             None => format!("{}/playlists/start", rockbox_url()),
         };
 
-        let client = reqwest::Client::new();
+        let client = crate::internal_client();
         client
             .put(&url)
             .send()
@@ -266,6 +266,19 @@ impl PlaylistService for Playlist {
         request: tonic::Request<InsertTracksRequest>,
     ) -> Result<tonic::Response<InsertTracksResponse>, tonic::Status> {
         let request = request.into_inner();
+
+        // "Play next" on an HLS / DASH stream while the standalone player
+        // is running: hand it over so it can be joined on without a gap.
+        let play_next = request.position == rockbox_sys::PLAYLIST_INSERT_FIRST
+            || request.position == rockbox_sys::PLAYLIST_INSERT;
+        if let Some(first) = request.tracks.first() {
+            if play_next && rockbox_hls::is_hls_or_dash_url(first).is_some() {
+                if let Err(e) = rockbox_hls::player_queue(first) {
+                    tracing::debug!("hls queue: {e}");
+                }
+            }
+        }
+
         let body = serde_json::json!({
             "position": request.position,
             "tracks": request.tracks,
@@ -312,16 +325,25 @@ impl PlaylistService for Playlist {
         request: tonic::Request<ShufflePlaylistRequest>,
     ) -> Result<tonic::Response<ShufflePlaylistResponse>, tonic::Status> {
         let request = request.into_inner();
-        let url = format!(
-            "{}/playlists/shuffle?start_index={}",
-            rockbox_url(),
-            request.start_index
-        );
-        self.client
+        let url = format!("{}/playlists/shuffle", rockbox_url());
+        let mut query = vec![("start_index", request.start_index.to_string())];
+        if let Some(mode) = request.mode {
+            query.push(("mode", mode));
+        }
+        if let Some(seed) = request.seed {
+            query.push(("seed", seed.to_string()));
+        }
+        let response = self
+            .client
             .put(&url)
+            .query(&query)
             .send()
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        if response.status() == 400 {
+            let message = response.text().await.unwrap_or_default();
+            return Err(tonic::Status::invalid_argument(message));
+        }
         Ok(tonic::Response::new(ShufflePlaylistResponse::default()))
     }
 
@@ -0,0 +1,167 @@
+use rockbox_library::{
+    entity::radio_station::RadioStation as RsRadioStation,
+    radio::{self, StationInput},
+    repo,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::api::rockbox::v1alpha1::{
+    radio_service_server::RadioService, CreateRadioStationRequest, CreateRadioStationResponse,
+    DeleteRadioStationRequest, DeleteRadioStationResponse, GetRadioStationRequest,
+    GetRadioStationResponse, GetRadioStationsRequest, GetRadioStationsResponse,
+    ImportRadioStationsRequest, ImportRadioStationsResponse, PlayRadioStationRequest,
+    PlayRadioStationResponse, RadioStation as ProtoRadioStation, UpdateRadioStationRequest,
+    UpdateRadioStationResponse,
+};
+use crate::rockbox_url;
+
+pub struct Radio {
+    pool: Pool<Sqlite>,
+    client: reqwest::Client,
+}
+
+impl Radio {
+    pub fn new(pool: Pool<Sqlite>, client: reqwest::Client) -> Self {
+        Self { pool, client }
+    }
+}
+
+fn to_proto_station(s: RsRadioStation) -> ProtoRadioStation {
+    ProtoRadioStation {
+        id: s.id,
+        name: s.name,
+        stream_url: s.stream_url,
+        homepage_url: s.homepage_url,
+        genre: s.genre,
+        country: s.country,
+        codec: s.codec,
+        bitrate: s.bitrate,
+        favicon: s.favicon,
+        created_at: s.created_at.timestamp(),
+        updated_at: s.updated_at.timestamp(),
+    }
+}
+
+fn invalid_station() -> tonic::Status {
+    tonic::Status::invalid_argument("a station needs a name and an http(s) stream URL")
+}
+
+#[tonic::async_trait]
+impl RadioService for Radio {
+    async fn get_radio_stations(
+        &self,
+        _request: tonic::Request<GetRadioStationsRequest>,
+    ) -> Result<tonic::Response<GetRadioStationsResponse>, tonic::Status> {
+        let stations = repo::radio_station::all(self.pool.clone())
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetRadioStationsResponse {
+            stations: stations.into_iter().map(to_proto_station).collect(),
+        }))
+    }
+
+    async fn get_radio_station(
+        &self,
+        request: tonic::Request<GetRadioStationRequest>,
+    ) -> Result<tonic::Response<GetRadioStationResponse>, tonic::Status> {
+        let id = request.into_inner().id;
+        let station = repo::radio_station::find(self.pool.clone(), &id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetRadioStationResponse {
+            station: station.map(to_proto_station),
+        }))
+    }
+
+    async fn create_radio_station(
+        &self,
+        request: tonic::Request<CreateRadioStationRequest>,
+    ) -> Result<tonic::Response<CreateRadioStationResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let input = StationInput {
+            name: req.name,
+            stream_url: req.stream_url,
+            homepage_url: req.homepage_url,
+            genre: req.genre,
+            country: req.country,
+            codec: req.codec,
+            bitrate: req.bitrate,
+            favicon: req.favicon,
+        };
+        if !input.is_valid() {
+            return Err(invalid_station());
+        }
+        let station = radio::create_station(self.pool.clone(), input)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(CreateRadioStationResponse {
+            station: station.map(to_proto_station),
+        }))
+    }
+
+    async fn update_radio_station(
+        &self,
+        request: tonic::Request<UpdateRadioStationRequest>,
+    ) -> Result<tonic::Response<UpdateRadioStationResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let input = StationInput {
+            name: req.name,
+            stream_url: req.stream_url,
+            homepage_url: req.homepage_url,
+            genre: req.genre,
+            country: req.country,
+            codec: req.codec,
+            bitrate: req.bitrate,
+            favicon: req.favicon,
+        };
+        if !input.is_valid() {
+            return Err(invalid_station());
+        }
+        let station = radio::update_station(self.pool.clone(), &req.id, input)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .ok_or_else(|| tonic::Status::not_found("radio station not found"))?;
+        Ok(tonic::Response::new(UpdateRadioStationResponse {
+            station: Some(to_proto_station(station)),
+        }))
+    }
+
+    async fn delete_radio_station(
+        &self,
+        request: tonic::Request<DeleteRadioStationRequest>,
+    ) -> Result<tonic::Response<DeleteRadioStationResponse>, tonic::Status> {
+        let id = request.into_inner().id;
+        repo::radio_station::delete(self.pool.clone(), &id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(DeleteRadioStationResponse {}))
+    }
+
+    async fn import_radio_stations(
+        &self,
+        request: tonic::Request<ImportRadioStationsRequest>,
+    ) -> Result<tonic::Response<ImportRadioStationsResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let stations =
+            radio::import_stations(self.pool.clone(), &req.content, req.genre.as_deref())
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(ImportRadioStationsResponse {
+            stations: stations.into_iter().map(to_proto_station).collect(),
+        }))
+    }
+
+    async fn play_radio_station(
+        &self,
+        request: tonic::Request<PlayRadioStationRequest>,
+    ) -> Result<tonic::Response<PlayRadioStationResponse>, tonic::Status> {
+        let id = request.into_inner().id;
+        let url = format!("{}/radio/stations/{}/play", rockbox_url(), id);
+        self.client
+            .put(&url)
+            .send()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(PlayRadioStationResponse {}))
+    }
+}
@@ -1,4 +1,9 @@
-use rockbox_playlists::{Playlist, PlaylistFolder, PlaylistStore};
+use std::path::Path;
+
+use rockbox_playlists::{
+    formats::{self, Entry, Format},
+    Playlist, PlaylistFolder, PlaylistStore,
+};
 #[cfg(not(feature = "fts5"))]
 use rockbox_typesense::client::{delete_playlist as ts_delete_playlist, insert_playlists};
 #[cfg(not(feature = "fts5"))]
@@ -9,10 +14,12 @@ use crate::api::rockbox::v1alpha1::{
     AddTracksToSavedPlaylistResponse, CreatePlaylistFolderRequest, CreatePlaylistFolderResponse,
     CreateSavedPlaylistRequest, CreateSavedPlaylistResponse, DeletePlaylistFolderRequest,
     DeletePlaylistFolderResponse, DeleteSavedPlaylistRequest, DeleteSavedPlaylistResponse,
-    GetPlaylistFoldersRequest, GetPlaylistFoldersResponse, GetSavedPlaylistRequest,
-    GetSavedPlaylistResponse, GetSavedPlaylistTracksRequest, GetSavedPlaylistTracksResponse,
-    GetSavedPlaylistsRequest, GetSavedPlaylistsResponse, PlaySavedPlaylistRequest,
-    PlaySavedPlaylistResponse, PlaylistFolder as ProtoFolder, RemoveTrackFromSavedPlaylistRequest,
+    ExportSavedPlaylistRequest, ExportSavedPlaylistResponse, GetPlaylistFoldersRequest,
+    GetPlaylistFoldersResponse, GetSavedPlaylistRequest, GetSavedPlaylistResponse,
+    GetSavedPlaylistTracksRequest, GetSavedPlaylistTracksResponse, GetSavedPlaylistsRequest,
+    GetSavedPlaylistsResponse, ImportSavedPlaylistRequest, ImportSavedPlaylistResponse,
+    PlaySavedPlaylistRequest, PlaySavedPlaylistResponse, PlaylistFileEntry,
+    PlaylistFolder as ProtoFolder, RemoveTrackFromSavedPlaylistRequest,
     RemoveTrackFromSavedPlaylistResponse, SavedPlaylist as ProtoPlaylist,
     UpdateSavedPlaylistRequest, UpdateSavedPlaylistResponse,
 };
@@ -63,6 +70,16 @@ fn to_proto_playlist(p: Playlist) -> ProtoPlaylist {
     }
 }
 
+fn to_proto_entry(e: Entry) -> PlaylistFileEntry {
+    PlaylistFileEntry {
+        location: e.location,
+        title: e.title,
+        artist: e.artist,
+        album: e.album,
+        duration_ms: e.duration_ms,
+    }
+}
+
 #[tonic::async_trait]
 impl SavedPlaylistService for SavedPlaylist {
     async fn create_playlist_folder(
@@ -264,4 +281,61 @@ impl SavedPlaylistService for SavedPlaylist {
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
         Ok(tonic::Response::new(PlaySavedPlaylistResponse {}))
     }
+    async fn import_saved_playlist(
+        &self,
+        request: tonic::Request<ImportSavedPlaylistRequest>,
+    ) -> Result<tonic::Response<ImportSavedPlaylistResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let format = match req.format.as_deref() {
+            Some(format) => format
+                .parse::<Format>()
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?,
+            None => Format::sniff(&req.content),
+        };
+        let file = formats::parse(format, &req.content)
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        let report = self
+            .store
+            .import_playlist(
+                file,
+                req.name.as_deref().filter(|n| !n.is_empty()),
+                req.folder_id.as_deref(),
+                req.base_dir.as_deref().map(Path::new),
+            )
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        #[cfg(not(feature = "fts5"))]
+        {
+            let ts_p = to_ts_playlist(&report.playlist);
+            let _ = insert_playlists(vec![ts_p]).await;
+        }
+        Ok(tonic::Response::new(ImportSavedPlaylistResponse {
+            playlist: Some(to_proto_playlist(report.playlist)),
+            matched: report.matched as i32,
+            unmatched: report.unmatched.into_iter().map(to_proto_entry).collect(),
+        }))
+    }
+
+    async fn export_saved_playlist(
+        &self,
+        request: tonic::Request<ExportSavedPlaylistRequest>,
+    ) -> Result<tonic::Response<ExportSavedPlaylistResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let format = match req.format.as_deref() {
+            Some(format) => format
+                .parse::<Format>()
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?,
+            None => Format::M3u,
+        };
+        let content = self
+            .store
+            .export_playlist(&req.id, format, req.relative_to.as_deref().map(Path::new))
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .ok_or_else(|| tonic::Status::not_found("playlist not found"))?;
+        Ok(tonic::Response::new(ExportSavedPlaylistResponse {
+            content,
+            content_type: format.content_type().to_string(),
+        }))
+    }
 }
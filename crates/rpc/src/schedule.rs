@@ -0,0 +1,206 @@
+use rockbox_scheduler::{
+    NewSchedule, Schedule as RsSchedule, ScheduleUpdate, SchedulerStore, SleepTimer as RsSleepTimer,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::api::rockbox::v1alpha1::{
+    schedule_service_server::ScheduleService, CancelSleepTimerRequest, CancelSleepTimerResponse,
+    CreateScheduleRequest, CreateScheduleResponse, DeleteScheduleRequest, DeleteScheduleResponse,
+    GetScheduleRequest, GetScheduleResponse, GetSchedulesRequest, GetSchedulesResponse,
+    GetSleepTimerRequest, GetSleepTimerResponse, RunScheduleRequest, RunScheduleResponse,
+    Schedule as ProtoSchedule, SetSleepTimerRequest, SetSleepTimerResponse,
+    SleepTimer as ProtoSleepTimer, UpdateScheduleRequest, UpdateScheduleResponse,
+};
+use crate::rockbox_url;
+
+pub struct Schedule {
+    store: SchedulerStore,
+    client: reqwest::Client,
+}
+
+impl Schedule {
+    pub fn new(pool: Pool<Sqlite>, client: reqwest::Client) -> Self {
+        Self {
+            store: SchedulerStore::new(pool),
+            client,
+        }
+    }
+}
+
+fn to_proto_schedule(s: RsSchedule) -> ProtoSchedule {
+    ProtoSchedule {
+        id: s.id,
+        name: s.name,
+        action: s.action,
+        target: s.target,
+        cron: s.cron,
+        run_at: s.run_at,
+        volume: s.volume,
+        ramp_secs: s.ramp_secs,
+        enabled: s.enabled,
+        next_run: s.next_run,
+        last_run: s.last_run,
+        created_at: s.created_at,
+        updated_at: s.updated_at,
+    }
+}
+
+fn to_proto_timer(t: RsSleepTimer) -> ProtoSleepTimer {
+    ProtoSleepTimer {
+        ends_at: t.ends_at,
+        remaining_secs: t.remaining_secs,
+        fade_secs: t.fade_secs,
+    }
+}
+
+#[tonic::async_trait]
+impl ScheduleService for Schedule {
+    async fn get_schedules(
+        &self,
+        _request: tonic::Request<GetSchedulesRequest>,
+    ) -> Result<tonic::Response<GetSchedulesResponse>, tonic::Status> {
+        let schedules = self
+            .store
+            .list()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetSchedulesResponse {
+            schedules: schedules.into_iter().map(to_proto_schedule).collect(),
+        }))
+    }
+
+    async fn get_schedule(
+        &self,
+        request: tonic::Request<GetScheduleRequest>,
+    ) -> Result<tonic::Response<GetScheduleResponse>, tonic::Status> {
+        let id = request.into_inner().id;
+        let schedule = self
+            .store
+            .get(&id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetScheduleResponse {
+            schedule: schedule.map(to_proto_schedule),
+        }))
+    }
+
+    async fn create_schedule(
+        &self,
+        request: tonic::Request<CreateScheduleRequest>,
+    ) -> Result<tonic::Response<CreateScheduleResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let schedule = self
+            .store
+            .create(NewSchedule {
+                name: req.name.unwrap_or_default(),
+                action: req.action,
+                target: req.target,
+                cron: req.cron,
+                run_at: req.run_at,
+                volume: req.volume,
+                ramp_secs: req.ramp_secs,
+                enabled: req.enabled,
+            })
+            .await
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        Ok(tonic::Response::new(CreateScheduleResponse {
+            schedule: Some(to_proto_schedule(schedule)),
+        }))
+    }
+
+    async fn update_schedule(
+        &self,
+        request: tonic::Request<UpdateScheduleRequest>,
+    ) -> Result<tonic::Response<UpdateScheduleResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let update = ScheduleUpdate {
+            name: req.name,
+            action: req.action,
+            target: req.target,
+            cron: req.cron,
+            run_at: req.run_at,
+            volume: req.volume,
+            ramp_secs: req.ramp_secs,
+            enabled: req.enabled,
+        };
+        let schedule = self
+            .store
+            .update(&req.id, update)
+            .await
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?
+            .ok_or_else(|| tonic::Status::not_found("schedule not found"))?;
+        Ok(tonic::Response::new(UpdateScheduleResponse {
+            schedule: Some(to_proto_schedule(schedule)),
+        }))
+    }
+
+    async fn delete_schedule(
+        &self,
+        request: tonic::Request<DeleteScheduleRequest>,
+    ) -> Result<tonic::Response<DeleteScheduleResponse>, tonic::Status> {
+        let id = request.into_inner().id;
+        match self.store.delete(&id).await {
+            Ok(true) => Ok(tonic::Response::new(DeleteScheduleResponse {})),
+            Ok(false) => Err(tonic::Status::not_found("schedule not found")),
+            Err(e) => Err(tonic::Status::internal(e.to_string())),
+        }
+    }
+
+    async fn run_schedule(
+        &self,
+        request: tonic::Request<RunScheduleRequest>,
+    ) -> Result<tonic::Response<RunScheduleResponse>, tonic::Status> {
+        let id = request.into_inner().id;
+        let url = format!("{}/schedules/{}/run", rockbox_url(), id);
+        let response = self
+            .client
+            .post(&url)
+            .send()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        if response.status() == 404 {
+            return Err(tonic::Status::not_found("schedule not found"));
+        }
+        Ok(tonic::Response::new(RunScheduleResponse {}))
+    }
+
+    async fn get_sleep_timer(
+        &self,
+        _request: tonic::Request<GetSleepTimerRequest>,
+    ) -> Result<tonic::Response<GetSleepTimerResponse>, tonic::Status> {
+        let timer = self
+            .store
+            .sleep_timer()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetSleepTimerResponse {
+            timer: timer.map(to_proto_timer),
+        }))
+    }
+
+    async fn set_sleep_timer(
+        &self,
+        request: tonic::Request<SetSleepTimerRequest>,
+    ) -> Result<tonic::Response<SetSleepTimerResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let timer = self
+            .store
+            .set_sleep_timer(req.minutes, req.fade_secs)
+            .await
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        Ok(tonic::Response::new(SetSleepTimerResponse {
+            timer: Some(to_proto_timer(timer)),
+        }))
+    }
+
+    async fn cancel_sleep_timer(
+        &self,
+        _request: tonic::Request<CancelSleepTimerRequest>,
+    ) -> Result<tonic::Response<CancelSleepTimerResponse>, tonic::Status> {
+        self.store
+            .cancel_sleep_timer()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(CancelSleepTimerResponse {}))
+    }
+}
@@ -1,5 +1,3 @@
-use std::net::SocketAddr;
-
 use crate::api::rockbox::v1alpha1::bluetooth_service_server::BluetoothServiceServer;
 use crate::api::rockbox::v1alpha1::browse_service_server::BrowseServiceServer;
 use crate::api::rockbox::v1alpha1::device_service_server::DeviceServiceServer;
@@ -7,11 +5,14 @@ use crate::api::rockbox::v1alpha1::genre_service_server::GenreServiceServer;
 use crate::api::rockbox::v1alpha1::library_service_server::LibraryServiceServer;
 use crate::api::rockbox::v1alpha1::playback_service_server::PlaybackServiceServer;
 use crate::api::rockbox::v1alpha1::playlist_service_server::PlaylistServiceServer;
+use crate::api::rockbox::v1alpha1::radio_service_server::RadioServiceServer;
 use crate::api::rockbox::v1alpha1::saved_playlist_service_server::SavedPlaylistServiceServer;
+use crate::api::rockbox::v1alpha1::schedule_service_server::ScheduleServiceServer;
 use crate::api::rockbox::v1alpha1::settings_service_server::SettingsServiceServer;
 use crate::api::rockbox::v1alpha1::smart_playlist_service_server::SmartPlaylistServiceServer;
 use crate::api::rockbox::v1alpha1::sound_service_server::SoundServiceServer;
 use crate::api::rockbox::FILE_DESCRIPTOR_SET;
+use crate::auth::AuthLayer;
 use crate::bluetooth::Bluetooth;
 use crate::browse::Browse;
 use crate::device::Device;
@@ -19,13 +20,18 @@ use crate::genre::Genre;
 use crate::library::Library;
 use crate::playback::Playback;
 use crate::playlist::Playlist;
+use crate::radio::Radio;
 use crate::saved_playlist::SavedPlaylist;
+use crate::schedule::Schedule;
 use crate::settings::Settings;
 use crate::smart_playlist::SmartPlaylistRpc;
 use crate::sound::Sound;
 use crate::system::System;
+use crate::tls::incoming;
+use rockbox_auth::{Auth, AuthConfig};
 use rockbox_library::create_connection_pool;
 use rockbox_playlists::PlaylistStore;
+use tokio::net::TcpListener;
 use tonic::transport::Server;
 
 pub async fn start() -> Result<(), Box<dyn std::error::Error>> {
@@ -34,14 +40,26 @@ pub async fn start() -> Result<(), Box<dyn std::error::Error>> {
         .parse()
         .expect("ROCKBOX_PORT must be a number");
 
-    let addr: SocketAddr = format!("0.0.0.0:{}", rockbox_port).parse()?;
+    let addr = format!("0.0.0.0:{}", rockbox_port);
+    let listener = TcpListener::bind(rockbox_tls::plain_addr(&addr)).await?;
+    let tls = match rockbox_tls::tls() {
+        Some(tls) => Some((tls, TcpListener::bind(tls.addr(&addr)?).await?)),
+        None => None,
+    };
 
-    let client = reqwest::Client::new();
+    let client = crate::internal_client();
     let pool = create_connection_pool().await?;
     let playlist_store = PlaylistStore::new(pool.clone());
 
+    let settings = rockbox_settings::read_settings().unwrap_or_default();
+    let auth = Auth::new(
+        pool.clone(),
+        AuthConfig::new(settings.auth_enabled, settings.auth_trust_localhost),
+    );
+
     Server::builder()
         .accept_http1(true)
+        .layer(AuthLayer::new(auth))
         .add_service(
             tonic_reflection::server::Builder::configure()
                 .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
@@ -83,10 +101,17 @@ pub async fn start() -> Result<(), Box<dyn std::error::Error>> {
         .add_service(tonic_web::enable(SmartPlaylistServiceServer::new(
             SmartPlaylistRpc::new(playlist_store.clone(), pool.clone(), client.clone()),
         )))
+        .add_service(tonic_web::enable(RadioServiceServer::new(Radio::new(
+            pool.clone(),
+            client.clone(),
+        ))))
+        .add_service(tonic_web::enable(ScheduleServiceServer::new(
+            Schedule::new(pool.clone(), client.clone()),
+        )))
         .add_service(tonic_web::enable(BluetoothServiceServer::new(
             Bluetooth::new(client.clone()),
         )))
-        .serve(addr)
+        .serve_with_incoming(incoming(listener, tls))
         .await?;
     Ok(())
 }
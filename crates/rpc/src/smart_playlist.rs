@@ -4,6 +4,8 @@ use rockbox_typesense::client::{delete_playlist as ts_delete_playlist, insert_pl
 #[cfg(not(feature = "fts5"))]
 use rockbox_typesense::types::Playlist as TsPlaylist;
 
+use std::pin::Pin;
+
 use crate::api::rockbox::v1alpha1::{
     smart_playlist_service_server::SmartPlaylistService, CreateSmartPlaylistRequest,
     CreateSmartPlaylistResponse, DeleteSmartPlaylistRequest, DeleteSmartPlaylistResponse,
@@ -13,10 +15,15 @@ use crate::api::rockbox::v1alpha1::{
     PlaySmartPlaylistResponse, RecordTrackPlayedRequest, RecordTrackPlayedResponse,
     RecordTrackSkippedRequest, RecordTrackSkippedResponse, RuleCondition as ProtoRuleCondition,
     RuleCriteria as ProtoRuleCriteria, SmartPlaylist as ProtoSmartPlaylist,
+    SmartPlaylistChange as ProtoSmartPlaylistChange, StreamSmartPlaylistChangesRequest,
     TrackStats as ProtoTrackStats, UpdateSmartPlaylistRequest, UpdateSmartPlaylistResponse,
 };
+use rockbox_graphql::{
+    schema::objects::smart_playlist::SmartPlaylistChange, simplebroker::SimpleBroker,
+};
 use rockbox_playlists::rules::RuleCriteria;
 use sqlx::{Pool, Sqlite};
+use tokio_stream::{Stream, StreamExt};
 
 use crate::rockbox_url;
 
@@ -68,6 +75,7 @@ fn criteria_to_rules(c: &ProtoRuleCriteria) -> RuleCriteria {
                 "duration_ms" => RuleField::DurationMs,
                 "bitrate" => RuleField::Bitrate,
                 "is_liked" => RuleField::IsLiked,
+                "rating" => RuleField::Rating,
                 _ => RuleField::PlayCount,
             };
             let operator = match cond.operator.as_str() {
@@ -125,6 +133,7 @@ fn criteria_to_rules(c: &ProtoRuleCriteria) -> RuleCriteria {
             "artist" => Some(SortField::Artist),
             "album" => Some(SortField::Album),
             "duration_ms" => Some(SortField::DurationMs),
+            "rating" => Some(SortField::Rating),
             _ => None,
         }
     });
@@ -135,10 +144,14 @@ fn criteria_to_rules(c: &ProtoRuleCriteria) -> RuleCriteria {
         _ => None,
     });
 
+    // Nested groups and total limits aren't in the gRPC schema yet.
     RuleCriteria {
         match_type,
         conditions,
+        groups: Vec::new(),
         limit: c.limit.map(|l| l as usize),
+        limit_duration_ms: None,
+        limit_size_bytes: None,
         sort_by,
         sort_order,
     }
@@ -169,6 +182,7 @@ fn to_proto_criteria(p: &rockbox_playlists::rules::RuleCriteria) -> ProtoRuleCri
                 RuleField::DurationMs => "duration_ms",
                 RuleField::Bitrate => "bitrate",
                 RuleField::IsLiked => "is_liked",
+                RuleField::Rating => "rating",
             };
             let operator = match c.operator {
                 RuleOperator::Is => "is",
@@ -217,6 +231,7 @@ fn to_proto_criteria(p: &rockbox_playlists::rules::RuleCriteria) -> ProtoRuleCri
             SortField::Artist => "artist",
             SortField::Album => "album",
             SortField::DurationMs => "duration_ms",
+            SortField::Rating => "rating",
         }
         .to_string()
     });
@@ -303,7 +318,7 @@ impl SmartPlaylistService for SmartPlaylistRpc {
 
         let proto = match playlist {
             Some(p) => {
-                let track_count = resolver::count_tracks(&self.store, &self.pool, &p.rules)
+                let track_count = resolver::count_tracks(&self.pool, &p.rules)
                     .await
                     .map_err(|e| tonic::Status::internal(e.to_string()))?;
                 Some(to_proto_smart_playlist(p, track_count))
@@ -336,7 +351,7 @@ impl SmartPlaylistService for SmartPlaylistRpc {
             )
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
-        let track_count = resolver::count_tracks(&self.store, &self.pool, &playlist.rules)
+        let track_count = resolver::count_tracks(&self.pool, &playlist.rules)
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
         #[cfg(not(feature = "fts5"))]
@@ -379,7 +394,7 @@ impl SmartPlaylistService for SmartPlaylistRpc {
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
         #[cfg(not(feature = "fts5"))]
         if let Ok(Some(updated)) = self.store.get_smart_playlist(&req.id).await {
-            let track_count = resolver::count_tracks(&self.store, &self.pool, &updated.rules)
+            let track_count = resolver::count_tracks(&self.pool, &updated.rules)
                 .await
                 .unwrap_or(0);
             let ts_p = TsPlaylist {
@@ -429,7 +444,7 @@ impl SmartPlaylistService for SmartPlaylistRpc {
             }
         };
 
-        let tracks = resolver::resolve_tracks(&self.store, &self.pool, &criteria)
+        let tracks = resolver::resolve_tracks(&self.pool, &criteria)
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
         let track_ids = tracks.into_iter().map(|t| t.id).collect();
@@ -491,4 +506,36 @@ impl SmartPlaylistService for SmartPlaylistRpc {
             stats: stats.map(to_proto_stats),
         }))
     }
+
+    type StreamSmartPlaylistChangesStream = Pin<
+        Box<
+            dyn Stream<Item = Result<ProtoSmartPlaylistChange, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    async fn stream_smart_playlist_changes(
+        &self,
+        request: tonic::Request<StreamSmartPlaylistChangesRequest>,
+    ) -> Result<tonic::Response<Self::StreamSmartPlaylistChangesStream>, tonic::Status> {
+        let id = request.into_inner().id;
+        let mut stream = SimpleBroker::<SmartPlaylistChange>::subscribe();
+        let output = async_stream::try_stream! {
+            while let Some(change) = stream.next().await {
+                if id.as_ref().is_some_and(|id| *id != change.playlist_id) {
+                    continue;
+                }
+                yield ProtoSmartPlaylistChange {
+                    playlist_id: change.playlist_id,
+                    added: change.added,
+                    removed: change.removed,
+                };
+            }
+        };
+        Ok(tonic::Response::new(
+            Box::pin(output) as Self::StreamSmartPlaylistChangesStream
+        ))
+    }
 }
@@ -90,13 +90,22 @@ impl SoundService for Sound {
         &self,
         _request: tonic::Request<GetPitchRequest>,
     ) -> Result<tonic::Response<GetPitchResponse>, tonic::Status> {
-        Ok(tonic::Response::new(GetPitchResponse::default()))
+        let value = rockbox_sys::sound::get_pitch();
+        Ok(tonic::Response::new(GetPitchResponse { value }))
     }
 
     async fn set_pitch(
         &self,
-        _request: tonic::Request<SetPitchRequest>,
+        request: tonic::Request<SetPitchRequest>,
     ) -> Result<tonic::Response<SetPitchResponse>, tonic::Status> {
+        // Hundredths of a percent, within the pitch screen's 50-200%.
+        let value = request.into_inner().value;
+        if !(5000..=20000).contains(&value) {
+            return Err(tonic::Status::invalid_argument(
+                "pitch must be between 5000 and 20000",
+            ));
+        }
+        rockbox_sys::sound::set_pitch(value);
         Ok(tonic::Response::new(SetPitchResponse::default()))
     }
 
@@ -228,4 +237,68 @@ impl SoundService for Sound {
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
         Ok(tonic::Response::new(SetPbeResponse::default()))
     }
+
+    async fn get_mixer(
+        &self,
+        _request: tonic::Request<GetMixerRequest>,
+    ) -> Result<tonic::Response<GetMixerResponse>, tonic::Status> {
+        let url = format!("{}/player/mixer", rockbox_url());
+        let mixer = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let text = |value: &serde_json::Value| value.as_str().unwrap_or_default().to_string();
+        let replaygain = &mixer["replaygain"];
+        Ok(tonic::Response::new(GetMixerResponse {
+            mode: text(&mixer["mode"]),
+            requested_mode: text(&mixer["requested_mode"]),
+            error: mixer["error"].as_str().map(str::to_string),
+            device: text(&mixer["device"]),
+            control: text(&mixer["control"]),
+            dither: mixer["dither"].as_bool().unwrap_or_default(),
+            replaygain: text(&replaygain["mode"]),
+            preamp: replaygain["preamp"].as_f64().unwrap_or_default() as f32,
+            prevent_clipping: replaygain["prevent_clipping"].as_bool().unwrap_or_default(),
+            replaygain_db: mixer["replaygain_db"].as_f64().unwrap_or_default() as f32,
+            clipping_prevented: mixer["clipping_prevented"].as_bool().unwrap_or_default(),
+        }))
+    }
+
+    async fn set_mixer(
+        &self,
+        request: tonic::Request<SetMixerRequest>,
+    ) -> Result<tonic::Response<SetMixerResponse>, tonic::Status> {
+        let req = request.into_inner();
+        let body = serde_json::json!({
+            "mode": req.mode,
+            "device": req.device,
+            "control": req.control,
+            "dither": req.dither,
+            "replaygain": req.replaygain,
+            "preamp": req.preamp,
+            "prevent_clipping": req.prevent_clipping,
+        });
+        let url = format!("{}/player/mixer", rockbox_url());
+        let response = self
+            .client
+            .put(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::BAD_REQUEST {
+            let message = response.text().await.unwrap_or_default();
+            return Err(tonic::Status::invalid_argument(message));
+        }
+        response
+            .error_for_status()
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(SetMixerResponse::default()))
+    }
 }
@@ -0,0 +1,169 @@
+use std::{
+    io,
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll},
+};
+
+use anyhow::Error;
+use futures::Stream;
+use rockbox_tls::{tokio_rustls::server::TlsStream, Tls, ALPN_H2_HTTP1};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream},
+};
+use tokio_stream::{
+    wrappers::{ReceiverStream, TcpListenerStream},
+    StreamExt,
+};
+use tonic::{
+    codegen::InterceptedService,
+    metadata::MetadataValue,
+    transport::{
+        server::{Connected, TcpConnectInfo},
+        Certificate, Channel, ClientTlsConfig, Endpoint, Uri,
+    },
+    Request, Status,
+};
+
+/// A gRPC connection from either the plain or the TLS listener. Both carry
+/// a [`TcpConnectInfo`], so the auth layer sees the peer either way.
+pub enum Conn {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl Connected for Conn {
+    type ConnectInfo = TcpConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        match self {
+            Conn::Plain(stream) => stream.connect_info(),
+            Conn::Tls(stream) => stream.get_ref().0.connect_info(),
+        }
+    }
+}
+
+impl AsyncRead for Conn {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Conn::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            Conn::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Conn {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Conn::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            Conn::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Conn::Plain(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+            Conn::Tls(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Conn::Plain(stream) => stream.is_write_vectored(),
+            Conn::Tls(stream) => stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Conn::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            Conn::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Conn::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            Conn::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+pub type Incoming = Pin<Box<dyn Stream<Item = io::Result<Conn>> + Send>>;
+
+/// Connections from the plain listener, merged with the ones that finished
+/// a handshake on the TLS listener when there is one.
+pub fn incoming(plain: TcpListener, tls: Option<(&Tls, TcpListener)>) -> Incoming {
+    let plain = TcpListenerStream::new(plain).map(|stream| stream.map(Conn::Plain));
+    match tls {
+        Some((tls, listener)) => {
+            let secure = ReceiverStream::new(tls.incoming(listener, ALPN_H2_HTTP1))
+                .map(|stream| Ok(Conn::Tls(Box::new(stream))));
+            Box::pin(plain.merge(secure))
+        }
+        None => Box::pin(plain),
+    }
+}
+
+/// Channel to a rockboxd gRPC server at `url` (`tcp://`, `http://` or
+/// `https://`). `https://` trusts the system roots, the certificate in
+/// `ROCKBOX_TLS_CA` and the one this machine's rockboxd serves.
+pub async fn connect(url: &str) -> Result<Channel, Error> {
+    let uri = Uri::from_str(url)?;
+    let mut endpoint = Endpoint::from(uri.clone());
+    if uri.scheme_str() == Some("https") {
+        let mut config = ClientTlsConfig::new().with_native_roots();
+        let ca = std::env::var("ROCKBOX_TLS_CA")
+            .ok()
+            .map(std::path::PathBuf::from)
+            .or_else(rockbox_tls::cert_path);
+        if let Some(pem) = ca.and_then(|path| std::fs::read(path).ok()) {
+            config = config.ca_certificate(Certificate::from_pem(pem));
+        }
+        endpoint = endpoint.tls_config(config)?;
+    }
+    Ok(endpoint.connect().await?)
+}
+
+/// Channel that sends [`rockbox_auth::internal_authorization`] with every call.
+pub type Internal = InterceptedService<Channel, fn(Request<()>) -> Result<Request<()>, Status>>;
+
+fn internal_token(mut request: Request<()>) -> Result<Request<()>, Status> {
+    if let Ok(value) = MetadataValue::try_from(rockbox_auth::internal_authorization()) {
+        request.metadata_mut().insert("authorization", value);
+    }
+    Ok(request)
+}
+
+/// [`connect`] for clients running inside rockboxd itself, such as MPRIS:
+/// they carry the internal token, so they keep working once API tokens are
+/// enforced.
+pub async fn connect_internal(url: &str) -> Result<Internal, Error> {
+    Ok(InterceptedService::new(
+        connect(url).await?,
+        internal_token as fn(Request<()>) -> Result<Request<()>, Status>,
+    ))
+}
+
+/// gRPC URL from `ROCKBOX_GRPC_URL`, or `tcp://$ROCKBOX_HOST:$ROCKBOX_PORT`.
+pub fn grpc_url() -> String {
+    if let Ok(url) = std::env::var("ROCKBOX_GRPC_URL") {
+        return url;
+    }
+    let host = std::env::var("ROCKBOX_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("ROCKBOX_PORT").unwrap_or_else(|_| "6061".to_string());
+    format!("tcp://{}:{}", host, port)
+}
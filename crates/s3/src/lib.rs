@@ -84,7 +84,7 @@ pub async fn start() -> anyhow::Result<()> {
         secret_key,
     });
 
-    HttpServer::new(move || {
+    let mut server = HttpServer::new(move || {
         App::new()
             .app_data(state.clone())
             .app_data(web::PayloadConfig::new(MAX_BODY_BYTES))
@@ -102,9 +102,13 @@ pub async fn start() -> anyhow::Result<()> {
             .route("/{bucket}/{key:.*}", web::get().to(handlers::get_object))
             .route("/{bucket}/{key:.*}", web::head().to(handlers::head_object))
     })
-    .bind(&addr)?
-    .run()
-    .await?;
+    .bind(rockbox_tls::plain_addr(&addr))?;
+    if let Some(tls) = rockbox_tls::tls() {
+        let tls_addr = tls.addr(&addr)?;
+        tracing::info!("s3 server listening on {tls_addr} (TLS)");
+        server = server.bind_rustls_0_23(tls_addr, tls.server_config())?;
+    }
+    server.run().await?;
 
     Ok(())
 }
@@ -0,0 +1,223 @@
+//! Five-field cron expressions: `minute hour day-of-month month day-of-week`.
+//!
+//! Each field takes `*`, a number, a range `a-b`, a step `*/n` or `a-b/n`,
+//! or a comma-separated list of those. Day of week runs 0-7 with both 0 and
+//! 7 meaning Sunday. As in Vixie cron, when both day fields are restricted a
+//! day matching either one fires. `@hourly`, `@daily`, `@weekly`, `@monthly`
+//! and `@yearly` are accepted as shorthands.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+/// How far ahead [`Cron::next_after`] looks before giving up, e.g. on
+/// `0 0 31 2 *`.
+const MAX_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn field(spec: &str, min: u32, max: u32) -> Result<u64, Error> {
+    let mut bits = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| anyhow!("invalid step in \"{}\"", part))?;
+                if step == 0 {
+                    return Err(anyhow!("invalid step in \"{}\"", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => {
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                let start: u32 = start
+                    .parse()
+                    .map_err(|_| anyhow!("invalid value \"{}\"", part))?;
+                let end: u32 = end
+                    .parse()
+                    .map_err(|_| anyhow!("invalid value \"{}\"", part))?;
+                // `5/15` means every 15 from 5 to the end of the range.
+                match range.contains('-') || step == 1 {
+                    true => (start, end),
+                    false => (start, max),
+                }
+            }
+        };
+        if start < min || end > max || start > end {
+            return Err(anyhow!("\"{}\" is out of range {}-{}", part, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl FromStr for Cron {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            expr => expr,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(anyhow!(
+                "a cron expression needs 5 fields (minute hour day month weekday), got \"{}\"",
+                s
+            ));
+        }
+        let weekdays = field(fields[4], 0, 7)?;
+        Ok(Cron {
+            minutes: field(fields[0], 0, 59)?,
+            hours: field(fields[1], 0, 23)? as u32,
+            days: field(fields[2], 1, 31)? as u32,
+            months: field(fields[3], 1, 12)? as u16,
+            // Fold 7 onto Sunday.
+            weekdays: ((weekdays | (weekdays >> 7)) & 0x7f) as u8,
+            any_day: fields[2].starts_with('*'),
+            any_weekday: fields[4].starts_with('*'),
+        })
+    }
+}
+
+impl Cron {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (false, true) => day,
+            (true, false) => weekday,
+            (false, false) => day || weekday,
+        }
+    }
+
+    /// The first matching minute strictly after `after`, in its time zone.
+    /// Local times skipped by a DST change never match; repeated ones match
+    /// on their first occurrence.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let local = after.naive_local();
+        let start =
+            local.date().and_hms_opt(local.hour(), local.minute(), 0)? + Duration::minutes(1);
+        let mut date = start.date();
+        let mut first_day = true;
+        for _ in 0..MAX_DAYS {
+            if self.matches_day(date) {
+                let (from_hour, from_minute) = match first_day {
+                    true => (start.hour(), start.minute()),
+                    false => (0, 0),
+                };
+                for hour in from_hour..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    let first_minute = if hour == from_hour { from_minute } else { 0 };
+                    for minute in first_minute..60 {
+                        if self.minutes & (1 << minute) == 0 {
+                            continue;
+                        }
+                        let naive = NaiveDateTime::new(
+                            date,
+                            chrono::NaiveTime::from_hms_opt(hour, minute, 0)?,
+                        );
+                        if let Some(at) = tz.from_local_datetime(&naive).earliest() {
+                            if at > *after {
+                                return Some(at);
+                            }
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+            first_day = false;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        expr.parse::<Cron>()
+            .unwrap()
+            .next_after(&at(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    #[test]
+    fn finds_the_next_matching_minute() {
+        // 2026-10-19 is a Monday.
+        assert_eq!(next("30 7 * * *", "2026-10-19 06:00"), "2026-10-19 07:30");
+        assert_eq!(next("30 7 * * *", "2026-10-19 07:30"), "2026-10-20 07:30");
+        assert_eq!(next("*/15 * * * *", "2026-10-19 07:31"), "2026-10-19 07:45");
+        assert_eq!(next("0 22 * * 1-5", "2026-10-23 23:00"), "2026-10-26 22:00");
+        assert_eq!(next("0 9 * * 0,6", "2026-10-19 12:00"), "2026-10-24 09:00");
+        assert_eq!(next("0 9 * * 7", "2026-10-19 12:00"), "2026-10-25 09:00");
+        assert_eq!(next("@monthly", "2026-10-19 12:00"), "2026-11-01 00:00");
+        assert_eq!(next("0 0 29 2 *", "2026-10-19 12:00"), "2028-02-29 00:00");
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 1st of the month or any Friday.
+        assert_eq!(next("0 8 1 * 5", "2026-10-19 12:00"), "2026-10-23 08:00");
+        assert_eq!(next("0 8 1 * 5", "2026-10-30 12:00"), "2026-11-01 08:00");
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expr in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "a * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(expr.parse::<Cron>().is_err(), "{}", expr);
+        }
+        assert!("0 0 31 2 *"
+            .parse::<Cron>()
+            .unwrap()
+            .next_after(&at("2026-10-19 12:00"))
+            .is_none());
+    }
+}
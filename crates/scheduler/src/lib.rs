@@ -0,0 +1,617 @@
+//! Scheduled playback.
+//!
+//! A schedule runs an action either once, at `run_at`, or on every minute
+//! its `cron` expression matches (in local time). Alarms start a saved
+//! playlist, a radio station or the current queue, ramping the volume up
+//! to `volume` over `ramp_secs`; `sleep` fades out over `ramp_secs` and
+//! pauses. The sleep timer is the one-shot `sleep` schedule with id
+//! [`SLEEP_TIMER_ID`].
+//!
+//! Schedules live in SQLite, so the CLI can edit them while the daemon
+//! runs: [`SchedulerStore::take_due`] is polled and always reads the
+//! table. Running the actions is left to the server, which owns playback.
+
+pub mod cron;
+
+use std::{future::Future, time::Duration};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::cron::Cron;
+
+/// What a schedule does when it fires.
+pub mod action {
+    /// Play a saved playlist; `target` is its id.
+    pub const PLAY_PLAYLIST: &str = "play_playlist";
+    /// Play a radio station; `target` is its id.
+    pub const PLAY_RADIO: &str = "play_radio";
+    /// Resume the current queue.
+    pub const RESUME: &str = "resume";
+    /// Fade out and pause.
+    pub const SLEEP: &str = "sleep";
+    pub const ALL: &[&str] = &[PLAY_PLAYLIST, PLAY_RADIO, RESUME, SLEEP];
+}
+
+/// Id of the schedule behind the sleep timer.
+pub const SLEEP_TIMER_ID: &str = "sleep-timer";
+
+/// Longest volume ramp or fade-out.
+pub const MAX_RAMP_SECS: i64 = 3600;
+
+/// How late a schedule may still run, e.g. after the daemon was down when
+/// it was due. Later ones are skipped rather than waking anyone at noon.
+const MISSED_GRACE_SECS: i64 = 300;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub action: String,
+    /// Playlist or radio station id, for the `play_*` actions.
+    pub target: Option<String>,
+    /// Recurring schedules: five-field cron expression in local time.
+    pub cron: Option<String>,
+    /// One-shot schedules: when to run, as a Unix timestamp.
+    pub run_at: Option<i64>,
+    /// Volume (dB) an alarm ramps up to; the current volume when unset.
+    pub volume: Option<i32>,
+    /// Volume ramp-up for alarms, fade-out for `sleep`.
+    pub ramp_secs: i64,
+    pub enabled: bool,
+    pub next_run: Option<i64>,
+    pub last_run: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewSchedule {
+    /// Defaults to the action.
+    #[serde(default)]
+    pub name: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub cron: Option<String>,
+    pub run_at: Option<i64>,
+    pub volume: Option<i32>,
+    pub ramp_secs: Option<i64>,
+    /// Defaults to `true`.
+    pub enabled: Option<bool>,
+}
+
+/// Fields to change on a schedule; `None` keeps the current value. Setting
+/// `cron` makes the schedule recurring, setting `run_at` makes it one-shot.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScheduleUpdate {
+    pub name: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub cron: Option<String>,
+    pub run_at: Option<i64>,
+    pub volume: Option<i32>,
+    pub ramp_secs: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SleepTimer {
+    /// When playback pauses, as a Unix timestamp.
+    pub ends_at: i64,
+    pub remaining_secs: i64,
+    pub fade_secs: i64,
+}
+
+const SCHEDULE_COLUMNS: &str = "id, name, action, target, cron, run_at, volume, ramp_secs, \
+     enabled, next_run, last_run, created_at, updated_at";
+
+fn schedule_from_row(r: SqliteRow) -> Schedule {
+    Schedule {
+        id: r.get(0),
+        name: r.get(1),
+        action: r.get(2),
+        target: r.get(3),
+        cron: r.get(4),
+        run_at: r.get(5),
+        volume: r.get(6),
+        ramp_secs: r.get(7),
+        enabled: r.get::<i64, _>(8) != 0,
+        next_run: r.get(9),
+        last_run: r.get(10),
+        created_at: r.get(11),
+        updated_at: r.get(12),
+    }
+}
+
+/// Check a schedule and work out when it next runs.
+fn next_run(schedule: &Schedule, now: DateTime<Local>) -> Result<Option<i64>> {
+    if !action::ALL.contains(&schedule.action.as_str()) {
+        return Err(anyhow!("unknown action: {}", schedule.action));
+    }
+    let needs_target =
+        schedule.action == action::PLAY_PLAYLIST || schedule.action == action::PLAY_RADIO;
+    if needs_target && schedule.target.as_deref().unwrap_or_default().is_empty() {
+        return Err(anyhow!("{} needs a target", schedule.action));
+    }
+    if !(0..=MAX_RAMP_SECS).contains(&schedule.ramp_secs) {
+        return Err(anyhow!("ramp_secs must be between 0 and {}", MAX_RAMP_SECS));
+    }
+    let next = match (&schedule.cron, schedule.run_at) {
+        (Some(expr), None) => {
+            let cron: Cron = expr.parse()?;
+            Some(
+                cron.next_after(&now)
+                    .ok_or_else(|| anyhow!("\"{}\" never matches", expr))?
+                    .timestamp(),
+            )
+        }
+        (None, Some(run_at)) => (run_at > now.timestamp()).then_some(run_at),
+        _ => return Err(anyhow!("a schedule needs either cron or run_at")),
+    };
+    Ok(next.filter(|_| schedule.enabled))
+}
+
+fn timestamp(at: i64) -> String {
+    Local
+        .timestamp_opt(at, 0)
+        .single()
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_else(|| at.to_string())
+}
+
+#[derive(Clone)]
+pub struct SchedulerStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SchedulerStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Schedule>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM schedule ORDER BY next_run IS NULL, next_run, name"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(schedule_from_row).collect())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<Schedule>> {
+        let row = sqlx::query(&format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM schedule WHERE id = ?"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(schedule_from_row))
+    }
+
+    pub async fn create(&self, schedule: NewSchedule) -> Result<Schedule> {
+        self.insert(Uuid::new_v4().to_string(), schedule).await
+    }
+
+    async fn insert(&self, id: String, new: NewSchedule) -> Result<Schedule> {
+        let now = Local::now();
+        let name = match new.name.trim() {
+            "" => new.action.clone(),
+            name => name.to_string(),
+        };
+        let mut schedule = Schedule {
+            id,
+            name,
+            action: new.action,
+            target: new.target.filter(|t| !t.is_empty()),
+            cron: new.cron.filter(|c| !c.trim().is_empty()),
+            run_at: new.run_at,
+            volume: new.volume,
+            ramp_secs: new.ramp_secs.unwrap_or(0),
+            enabled: new.enabled.unwrap_or(true),
+            next_run: None,
+            last_run: None,
+            created_at: now.timestamp(),
+            updated_at: now.timestamp(),
+        };
+        schedule.next_run = next_run(&schedule, now)?;
+        if schedule.run_at.is_some() && schedule.next_run.is_none() && schedule.enabled {
+            return Err(anyhow!("run_at is in the past"));
+        }
+        sqlx::query(
+            "INSERT OR REPLACE INTO schedule (id, name, action, target, cron, run_at, volume, \
+             ramp_secs, enabled, next_run, last_run, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL, ?, ?)",
+        )
+        .bind(&schedule.id)
+        .bind(&schedule.name)
+        .bind(&schedule.action)
+        .bind(&schedule.target)
+        .bind(&schedule.cron)
+        .bind(schedule.run_at)
+        .bind(schedule.volume)
+        .bind(schedule.ramp_secs)
+        .bind(schedule.enabled as i64)
+        .bind(schedule.next_run)
+        .bind(schedule.created_at)
+        .bind(schedule.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(schedule)
+    }
+
+    pub async fn update(&self, id: &str, update: ScheduleUpdate) -> Result<Option<Schedule>> {
+        let mut schedule = match self.get(id).await? {
+            Some(schedule) => schedule,
+            None => return Ok(None),
+        };
+        if let Some(name) = update
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            schedule.name = name.to_string();
+        }
+        if let Some(action) = update.action {
+            schedule.action = action;
+        }
+        if let Some(target) = update.target {
+            schedule.target = Some(target).filter(|t| !t.is_empty());
+        }
+        if let Some(cron) = update.cron {
+            schedule.cron = Some(cron);
+            schedule.run_at = None;
+        }
+        if let Some(run_at) = update.run_at {
+            schedule.run_at = Some(run_at);
+            schedule.cron = None;
+        }
+        if let Some(volume) = update.volume {
+            schedule.volume = Some(volume);
+        }
+        if let Some(ramp_secs) = update.ramp_secs {
+            schedule.ramp_secs = ramp_secs;
+        }
+        if let Some(enabled) = update.enabled {
+            schedule.enabled = enabled;
+        }
+        let now = Local::now();
+        schedule.next_run = next_run(&schedule, now)?;
+        if update.run_at.is_some() && schedule.next_run.is_none() && schedule.enabled {
+            return Err(anyhow!("run_at is in the past"));
+        }
+        schedule.updated_at = now.timestamp();
+        sqlx::query(
+            "UPDATE schedule SET name = ?, action = ?, target = ?, cron = ?, run_at = ?, \
+             volume = ?, ramp_secs = ?, enabled = ?, next_run = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&schedule.name)
+        .bind(&schedule.action)
+        .bind(&schedule.target)
+        .bind(&schedule.cron)
+        .bind(schedule.run_at)
+        .bind(schedule.volume)
+        .bind(schedule.ramp_secs)
+        .bind(schedule.enabled as i64)
+        .bind(schedule.next_run)
+        .bind(schedule.updated_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(Some(schedule))
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM schedule WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Claim the schedules due at `now`: each is moved on to its next run
+    /// (one-shot ones are disabled) before being returned, so it runs once
+    /// however often this is polled. Schedules missed by more than a few
+    /// minutes are moved on without being returned.
+    pub async fn take_due(&self, now: DateTime<Local>) -> Result<Vec<Schedule>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM schedule \
+             WHERE enabled = 1 AND next_run IS NOT NULL AND next_run <= ?"
+        ))
+        .bind(now.timestamp())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut due = Vec::new();
+        for mut schedule in rows.into_iter().map(schedule_from_row) {
+            let scheduled = schedule.next_run.unwrap_or_default();
+            match &schedule.cron {
+                Some(_) => schedule.next_run = next_run(&schedule, now).unwrap_or(None),
+                None => {
+                    schedule.enabled = false;
+                    schedule.next_run = None;
+                }
+            }
+            let result = sqlx::query(
+                "UPDATE schedule SET enabled = ?, next_run = ?, last_run = ? \
+                 WHERE id = ? AND next_run = ?",
+            )
+            .bind(schedule.enabled as i64)
+            .bind(schedule.next_run)
+            .bind(now.timestamp())
+            .bind(&schedule.id)
+            .bind(scheduled)
+            .execute(&self.pool)
+            .await?;
+            if result.rows_affected() == 0 {
+                // Claimed by another poller, or edited in the meantime.
+                continue;
+            }
+            if now.timestamp() - scheduled > MISSED_GRACE_SECS {
+                warn!(
+                    "scheduler: skipping \"{}\", missed at {}",
+                    schedule.name,
+                    timestamp(scheduled)
+                );
+                continue;
+            }
+            schedule.last_run = Some(now.timestamp());
+            due.push(schedule);
+        }
+        Ok(due)
+    }
+
+    // ── Sleep timer ────────────────────────────────────────────────────────
+
+    /// Pause playback in `minutes`, fading out over the last `fade_secs`.
+    /// Replaces any running sleep timer.
+    pub async fn set_sleep_timer(&self, minutes: u32, fade_secs: i64) -> Result<SleepTimer> {
+        if minutes == 0 {
+            return Err(anyhow!("the sleep timer needs at least a minute"));
+        }
+        let run_at = Utc::now().timestamp() + minutes as i64 * 60;
+        self.insert(
+            SLEEP_TIMER_ID.to_string(),
+            NewSchedule {
+                name: "Sleep timer".to_string(),
+                action: action::SLEEP.to_string(),
+                run_at: Some(run_at),
+                ramp_secs: Some(fade_secs),
+                ..Default::default()
+            },
+        )
+        .await?;
+        self.sleep_timer()
+            .await?
+            .ok_or_else(|| anyhow!("sleep timer disappeared"))
+    }
+
+    /// The running sleep timer, if any.
+    pub async fn sleep_timer(&self) -> Result<Option<SleepTimer>> {
+        let now = Utc::now().timestamp();
+        Ok(self
+            .get(SLEEP_TIMER_ID)
+            .await?
+            .filter(|s| s.enabled)
+            .and_then(|s| {
+                s.next_run.map(|ends_at| SleepTimer {
+                    ends_at,
+                    remaining_secs: (ends_at - now).max(0),
+                    // The fade ends when the timer does.
+                    fade_secs: s.ramp_secs.min(ends_at - s.created_at),
+                })
+            }))
+    }
+
+    pub async fn cancel_sleep_timer(&self) -> Result<bool> {
+        let cancelled = self.sleep_timer().await?.is_some();
+        self.delete(SLEEP_TIMER_ID).await?;
+        Ok(cancelled)
+    }
+}
+
+/// Poll for due schedules every second and hand each one to `run` on a
+/// task of its own, so a long volume ramp does not hold up the others.
+pub fn start_scheduler<F, Fut>(store: SchedulerStore, run: F)
+where
+    F: Fn(Schedule) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            let due = match store.take_due(Local::now()).await {
+                Ok(due) => due,
+                Err(e) => {
+                    error!("scheduler: {}", e);
+                    continue;
+                }
+            };
+            for schedule in due {
+                info!("scheduler: running \"{}\"", schedule.name);
+                let name = schedule.name.clone();
+                let task = run(schedule);
+                tokio::spawn(async move {
+                    if let Err(e) = task.await {
+                        error!("scheduler: \"{}\" failed: {}", name, e);
+                    }
+                });
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    async fn store() -> SchedulerStore {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        pool.execute(include_str!(
+            "../../library/migrations/20261019001300_add_schedules.sql"
+        ))
+        .await
+        .unwrap();
+        SchedulerStore::new(pool)
+    }
+
+    fn alarm(cron: &str) -> NewSchedule {
+        NewSchedule {
+            name: "Wake up".to_string(),
+            action: action::PLAY_RADIO.to_string(),
+            target: Some("station-1".to_string()),
+            cron: Some(cron.to_string()),
+            volume: Some(-20),
+            ramp_secs: Some(60),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn recurring_schedules_run_once_per_match() {
+        let store = store().await;
+        let schedule = store.create(alarm("* * * * *")).await.unwrap();
+        let first = schedule.next_run.unwrap();
+
+        let now = Local.timestamp_opt(first, 0).unwrap();
+        let due = store.take_due(now).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, schedule.id);
+        assert_eq!(due[0].next_run, Some(first + 60));
+
+        // Polling again within the same minute finds nothing.
+        assert!(store
+            .take_due(now + Duration::seconds(1))
+            .await
+            .unwrap()
+            .is_empty());
+        let stored = store.get(&schedule.id).await.unwrap().unwrap();
+        assert!(stored.enabled);
+        assert_eq!(stored.last_run, Some(first));
+    }
+
+    #[tokio::test]
+    async fn one_shot_schedules_disable_themselves() {
+        let store = store().await;
+        let run_at = Utc::now().timestamp() + 120;
+        let schedule = store
+            .create(NewSchedule {
+                action: action::RESUME.to_string(),
+                run_at: Some(run_at),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(schedule.name, action::RESUME);
+        assert_eq!(schedule.next_run, Some(run_at));
+
+        let now = Local.timestamp_opt(run_at + 1, 0).unwrap();
+        assert_eq!(store.take_due(now).await.unwrap().len(), 1);
+        let stored = store.get(&schedule.id).await.unwrap().unwrap();
+        assert!(!stored.enabled);
+        assert_eq!(stored.next_run, None);
+        assert!(store.take_due(now).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missed_schedules_are_skipped() {
+        let store = store().await;
+        let schedule = store.create(alarm("* * * * *")).await.unwrap();
+        let late = Local
+            .timestamp_opt(schedule.next_run.unwrap() + MISSED_GRACE_SECS + 60, 0)
+            .unwrap();
+        assert!(store.take_due(late).await.unwrap().is_empty());
+        let stored = store.get(&schedule.id).await.unwrap().unwrap();
+        assert!(stored.next_run.unwrap() > late.timestamp());
+    }
+
+    #[tokio::test]
+    async fn invalid_schedules_are_rejected() {
+        let store = store().await;
+        let mut no_target = alarm("0 7 * * *");
+        no_target.target = None;
+        let mut bad_cron = alarm("0 7 * *");
+        bad_cron.cron = Some("0 7 * *".to_string());
+        let mut both = alarm("0 7 * * *");
+        both.run_at = Some(Utc::now().timestamp() + 60);
+        let past = NewSchedule {
+            action: action::SLEEP.to_string(),
+            run_at: Some(Utc::now().timestamp() - 60),
+            ..Default::default()
+        };
+        let unknown = NewSchedule {
+            action: "reboot".to_string(),
+            cron: Some("0 7 * * *".to_string()),
+            ..Default::default()
+        };
+        for schedule in [no_target, bad_cron, both, past, unknown] {
+            assert!(store.create(schedule).await.is_err());
+        }
+        assert!(store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn updating_switches_between_cron_and_run_at() {
+        let store = store().await;
+        let schedule = store.create(alarm("0 7 * * 1-5")).await.unwrap();
+        let run_at = Utc::now().timestamp() + 3600;
+        let updated = store
+            .update(
+                &schedule.id,
+                ScheduleUpdate {
+                    run_at: Some(run_at),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.cron, None);
+        assert_eq!(updated.next_run, Some(run_at));
+
+        let disabled = store
+            .update(
+                &schedule.id,
+                ScheduleUpdate {
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(disabled.next_run, None);
+        assert!(store
+            .update("missing", ScheduleUpdate::default())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn sleep_timer_replaces_and_cancels() {
+        let store = store().await;
+        assert_eq!(store.sleep_timer().await.unwrap(), None);
+
+        store.set_sleep_timer(30, 20).await.unwrap();
+        let timer = store.set_sleep_timer(10, 20).await.unwrap();
+        assert!((599..=600).contains(&timer.remaining_secs));
+        assert_eq!(timer.fade_secs, 20);
+        assert_eq!(store.list().await.unwrap().len(), 1);
+
+        assert!(store.cancel_sleep_timer().await.unwrap());
+        assert!(!store.cancel_sleep_timer().await.unwrap());
+        assert_eq!(store.sleep_timer().await.unwrap(), None);
+    }
+}
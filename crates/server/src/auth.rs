@@ -0,0 +1,59 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header, Method},
+    middleware::Next,
+    web, HttpResponse,
+};
+use rockbox_auth::{request_token, rest_scope, Denied};
+
+use crate::http::AppState;
+
+/// Rejects requests whose token (or lack of one) does not carry the scope
+/// the route needs. CORS preflights pass through untouched.
+pub async fn require_scope(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    if req.method() == Method::OPTIONS {
+        return next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_left_body);
+    }
+
+    let auth = match req.app_data::<web::Data<AppState>>() {
+        Some(state) => state.auth.clone(),
+        None => {
+            return next
+                .call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        }
+    };
+    let peer = req.peer_addr().map(|addr| addr.ip());
+    let token = request_token(
+        req.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok()),
+        req.query_string(),
+    );
+    let required = rest_scope(req.method().as_str(), req.path());
+
+    match auth.authorize(peer, token.as_deref(), required).await {
+        Ok(_) => next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_left_body),
+        Err(denied) => {
+            let response = match denied {
+                Denied::Unauthenticated => HttpResponse::Unauthorized()
+                    .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                    .body(denied.to_string()),
+                Denied::Forbidden { .. } => HttpResponse::Forbidden().body(denied.to_string()),
+                Denied::Internal(_) => HttpResponse::InternalServerError().body(denied.to_string()),
+            };
+            Ok(req.into_response(response).map_into_right_body())
+        }
+    }
+}
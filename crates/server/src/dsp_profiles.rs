@@ -0,0 +1,121 @@
+//! Applies `rockbox-dsp-profiles` profiles to the firmware and switches them
+//! with the output.
+
+use rockbox_dsp_profiles::{Compressor, DspProfileStore, DspSettings, EqBand};
+use rockbox_sys as rb;
+use sqlx::{Pool, Sqlite};
+
+/// The settings in effect now. Call under the kernel lock.
+pub fn current() -> DspSettings {
+    let s = unsafe { rb::global_settings };
+    DspSettings {
+        eq_enabled: s.eq_enabled,
+        eq_precut: s.eq_precut as i32,
+        eq_bands: s
+            .eq_band_settings
+            .iter()
+            .map(|b| EqBand {
+                cutoff: b.cutoff,
+                q: b.q,
+                gain: b.gain,
+            })
+            .collect(),
+        crossfeed: s.crossfeed,
+        crossfeed_direct_gain: s.crossfeed_direct_gain as i32,
+        crossfeed_cross_gain: s.crossfeed_cross_gain as i32,
+        crossfeed_hf_attenuation: s.crossfeed_hf_attenuation as i32,
+        crossfeed_hf_cutoff: s.crossfeed_hf_cutoff as i32,
+        pbe: s.pbe,
+        pbe_precut: s.pbe_precut,
+        compressor: Compressor {
+            threshold: s.compressor_settings.threshold,
+            makeup_gain: s.compressor_settings.makeup_gain,
+            ratio: s.compressor_settings.ratio,
+            knee: s.compressor_settings.knee,
+            release_time: s.compressor_settings.release_time,
+            attack_time: s.compressor_settings.attack_time,
+        },
+        dithering_enabled: s.dithering_enabled,
+    }
+}
+
+/// Copy `settings` into `global_settings` and reconfigure the DSP. Call
+/// under the kernel lock. Does nothing while a bit-perfect alsa sink keeps
+/// the DSP neutral.
+pub fn apply(settings: &DspSettings) {
+    if rockbox_settings::dsp_bypassed() {
+        tracing::info!("dsp profiles: bit-perfect output, profile not applied");
+        return;
+    }
+    unsafe {
+        rb::global_settings.eq_enabled = settings.eq_enabled;
+        rb::global_settings.eq_precut = settings.eq_precut as u32;
+    }
+    rb::sound::dsp::eq_enable(settings.eq_enabled);
+    rb::sound::dsp::set_eq_precut(settings.eq_precut);
+    for (i, band) in settings.eq_bands.iter().take(rb::EQ_NUM_BANDS).enumerate() {
+        let setting = rb::EqBandSetting {
+            cutoff: band.cutoff,
+            q: band.q,
+            gain: band.gain,
+        };
+        unsafe { rb::global_settings.eq_band_settings[i] = setting };
+        rb::sound::dsp::set_eq_coefs(i as i32, &setting);
+    }
+
+    unsafe {
+        rb::global_settings.crossfeed = settings.crossfeed;
+        rb::global_settings.crossfeed_direct_gain = settings.crossfeed_direct_gain as u32;
+        rb::global_settings.crossfeed_cross_gain = settings.crossfeed_cross_gain as u32;
+        rb::global_settings.crossfeed_hf_attenuation = settings.crossfeed_hf_attenuation as u32;
+        rb::global_settings.crossfeed_hf_cutoff = settings.crossfeed_hf_cutoff as u32;
+    }
+    rb::sound::dsp::set_crossfeed_type(settings.crossfeed);
+    rb::sound::dsp::set_crossfeed_direct_gain(settings.crossfeed_direct_gain);
+    rb::sound::dsp::set_crossfeed_cross_params(
+        settings.crossfeed_cross_gain as i64,
+        settings.crossfeed_hf_attenuation as i64,
+        settings.crossfeed_hf_cutoff as i64,
+    );
+
+    unsafe {
+        rb::global_settings.pbe = settings.pbe;
+        rb::global_settings.pbe_precut = settings.pbe_precut;
+    }
+    rb::sound::dsp::pbe_enable(settings.pbe);
+    rb::sound::dsp::pbe_precut(settings.pbe_precut);
+
+    let compressor = rb::CompressorSettings {
+        threshold: settings.compressor.threshold,
+        makeup_gain: settings.compressor.makeup_gain,
+        ratio: settings.compressor.ratio,
+        knee: settings.compressor.knee,
+        release_time: settings.compressor.release_time,
+        attack_time: settings.compressor.attack_time,
+    };
+    unsafe { rb::global_settings.compressor_settings = compressor };
+    rb::sound::dsp::set_compressor(&compressor);
+
+    unsafe { rb::global_settings.dithering_enabled = settings.dithering_enabled };
+    rb::sound::dsp::dither_enable(settings.dithering_enabled);
+}
+
+/// Apply the profile bound to `output`, if there is one. Outputs without a
+/// profile keep whatever is set.
+pub async fn apply_for_output(pool: Pool<Sqlite>, output: String) {
+    let profile = match DspProfileStore::new(pool).for_output(&output).await {
+        Ok(Some(profile)) => profile,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("dsp profiles: {}: {}", output, e);
+            return;
+        }
+    };
+    tracing::info!("dsp profiles: {} -> {}", output, profile.name);
+    let settings = profile.settings;
+    if let Err(e) =
+        tokio::task::spawn_blocking(move || rb::with_kernel_lock(|| apply(&settings))).await
+    {
+        tracing::warn!("dsp profiles: {}: {}", output, e);
+    }
+}
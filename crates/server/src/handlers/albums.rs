@@ -40,7 +40,7 @@ pub async fn filter_albums(
     body: web::Json<RuleCriteria>,
 ) -> HandlerResult {
     let criteria = body.into_inner();
-    let albums = resolver::filter_albums(&state.playlist_store, &state.pool, &criteria)
+    let albums = resolver::filter_albums(&state.pool, &criteria)
         .await
         .map_err(ErrorInternalServerError)?;
     Ok(HttpResponse::Ok().json(albums))
@@ -49,7 +49,7 @@ pub async fn filter_artists(
     body: web::Json<RuleCriteria>,
 ) -> HandlerResult {
     let criteria = body.into_inner();
-    let artists = resolver::filter_artists(&state.playlist_store, &state.pool, &criteria)
+    let artists = resolver::filter_artists(&state.pool, &criteria)
         .await
         .map_err(ErrorInternalServerError)?;
     Ok(HttpResponse::Ok().json(artists))
@@ -0,0 +1,173 @@
+use std::sync::atomic::Ordering;
+
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_library::{
+    entity::{
+        album::Album,
+        bookmark::Bookmark,
+        track::{MEDIA_TYPE_BOOK, MEDIA_TYPE_MUSIC},
+    },
+    repo,
+};
+use rockbox_sys::{self as rb};
+use serde::{Deserialize, Serialize};
+
+use crate::{http::AppState, PLAYLIST_DIRTY};
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Serialize)]
+pub struct Audiobook {
+    #[serde(flatten)]
+    album: Album,
+    bookmark: Option<Bookmark>,
+}
+
+#[derive(Deserialize)]
+pub struct MarkRequest {
+    folder: Option<String>,
+    genre: Option<String>,
+    /// `false` turns matching tracks back into music.
+    #[serde(default = "default_book")]
+    book: bool,
+}
+
+fn default_book() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct BookmarkRequest {
+    track_id: String,
+    position_ms: u32,
+}
+
+pub async fn get_audiobooks(state: web::Data<AppState>) -> HandlerResult {
+    let albums = repo::album::books(state.pool.clone())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let bookmarks = repo::bookmark::all(state.pool.clone())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let books = albums
+        .into_iter()
+        .map(|album| Audiobook {
+            bookmark: bookmarks.iter().find(|b| b.album_id == album.id).cloned(),
+            album,
+        })
+        .collect::<Vec<_>>();
+    Ok(HttpResponse::Ok().json(books))
+}
+
+pub async fn mark_audiobooks(
+    state: web::Data<AppState>,
+    body: web::Json<MarkRequest>,
+) -> HandlerResult {
+    let req = body.into_inner();
+    if req.folder.is_none() && req.genre.is_none() {
+        return Ok(HttpResponse::BadRequest().body("folder or genre is required"));
+    }
+    let media_type = if req.book {
+        MEDIA_TYPE_BOOK
+    } else {
+        MEDIA_TYPE_MUSIC
+    };
+    let mut updated = 0;
+    if let Some(folder) = req.folder.as_deref().filter(|f| !f.trim().is_empty()) {
+        updated += repo::track::update_media_type_by_folder(state.pool.clone(), folder, media_type)
+            .await
+            .map_err(ErrorInternalServerError)?;
+    }
+    if let Some(genre) = req.genre.as_deref().filter(|g| !g.trim().is_empty()) {
+        updated += repo::track::update_media_type_by_genre(state.pool.clone(), genre, media_type)
+            .await
+            .map_err(ErrorInternalServerError)?;
+    }
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "updated": updated })))
+}
+
+pub async fn get_bookmark(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let bookmark = repo::bookmark::find(state.pool.clone(), &path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    match bookmark {
+        Some(bookmark) => Ok(HttpResponse::Ok().json(bookmark)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn save_bookmark(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<BookmarkRequest>,
+) -> HandlerResult {
+    let album_id = path.into_inner();
+    let req = body.into_inner();
+    let tracks = repo::album_tracks::find_by_album(state.pool.clone(), &album_id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if !tracks.iter().any(|t| t.id == req.track_id) {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    repo::bookmark::save(
+        state.pool.clone(),
+        &album_id,
+        &req.track_id,
+        req.position_ms,
+    )
+    .await
+    .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn delete_bookmark(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let deleted = repo::bookmark::delete(state.pool.clone(), &path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    match deleted {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+/// Queue every track of the book in order and resume from its bookmark.
+pub async fn play_audiobook(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let album_id = path.into_inner();
+    let tracks = repo::album_tracks::find_by_album(state.pool.clone(), &album_id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if tracks.is_empty() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let bookmark = repo::bookmark::find(state.pool.clone(), &album_id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let (index, elapsed) = bookmark
+        .and_then(|b| {
+            tracks
+                .iter()
+                .position(|t| t.id == b.track_id)
+                .map(|i| (i as i32, b.position_ms as u64))
+        })
+        .unwrap_or((0, 0));
+    let paths = tracks.into_iter().map(|t| t.path).collect::<Vec<_>>();
+
+    web::block(move || {
+        rb::with_kernel_lock(move || {
+            rb::playback::hard_stop();
+            let dir = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            rb::playlist::create(&dir, None);
+            rb::playlist::build_playlist(
+                paths.iter().map(|p| p.as_str()).collect(),
+                0,
+                paths.len() as i32,
+            );
+            rb::playlist::start(index, elapsed, 0);
+            PLAYLIST_DIRTY.store(true, Ordering::Relaxed);
+        });
+    })
+    .await
+    .map_err(ErrorInternalServerError)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -1,7 +1,10 @@
 use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
 use rockbox_bluetooth::{connect, disconnect, get_devices, scan};
+use rockbox_dsp_profiles::{bluetooth_output, BUILTIN_OUTPUT};
 use serde::Deserialize;
 
+use crate::{dsp_profiles, http::AppState};
+
 type HandlerResult = actix_web::Result<HttpResponse>;
 
 #[derive(Deserialize)]
@@ -20,10 +23,16 @@ pub async fn get_bluetooth_devices() -> HandlerResult {
     Ok(HttpResponse::Ok().json(devices))
 }
 
-pub async fn connect_bluetooth_device(path: web::Path<String>) -> HandlerResult {
+pub async fn connect_bluetooth_device(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
     let address = path.into_inner();
     match connect(&address).await {
-        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Ok(_) => {
+            dsp_profiles::apply_for_output(state.pool.clone(), bluetooth_output(&address)).await;
+            Ok(HttpResponse::Ok().finish())
+        }
         Err(e) => {
             tracing::error!("bluetooth: connect {}: {}", address, e);
             Ok(HttpResponse::InternalServerError().finish())
@@ -31,10 +40,16 @@ pub async fn connect_bluetooth_device(path: web::Path<String>) -> HandlerResult
     }
 }
 
-pub async fn disconnect_bluetooth_device(path: web::Path<String>) -> HandlerResult {
+pub async fn disconnect_bluetooth_device(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
     let address = path.into_inner();
     match disconnect(&address).await {
-        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Ok(_) => {
+            dsp_profiles::apply_for_output(state.pool.clone(), BUILTIN_OUTPUT.to_string()).await;
+            Ok(HttpResponse::Ok().finish())
+        }
         Err(e) => {
             tracing::error!("bluetooth: disconnect {}: {}", address, e);
             Ok(HttpResponse::InternalServerError().finish())
@@ -1,11 +1,20 @@
 use actix_web::{web, HttpResponse};
 use rockbox_settings::{read_settings, save_settings_to_file};
 use rockbox_sys::sound::pcm;
+use rockbox_types::device::Device;
 
-use crate::{http::AppState, GLOBAL_MUTEX};
+use crate::{dsp_profiles, http::AppState, GLOBAL_MUTEX};
 
 type HandlerResult = actix_web::Result<HttpResponse>;
 
+fn device_info(device: &Device) -> rockbox_webhooks::DeviceInfo {
+    rockbox_webhooks::DeviceInfo {
+        id: device.id.clone(),
+        name: device.name.clone(),
+        service: device.service.clone(),
+    }
+}
+
 pub async fn connect(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
     let id = path.into_inner();
     let mut player = state.player.lock().unwrap();
@@ -116,6 +125,8 @@ pub async fn connect(state: web::Data<AppState>, path: web::Path<String>) -> Han
             pcm::cmaf_set_http_port(http_port);
             pcm::cmaf_set_bitrate(bitrate);
             pcm::cmaf_set_segment_dir(settings.cmaf_segment_dir.as_deref());
+            pcm::cmaf_set_renditions(settings.cmaf_renditions.as_deref().unwrap_or_default());
+            pcm::cmaf_set_dvr_window(settings.cmaf_dvr_window.unwrap_or(0));
             pcm::switch_sink(pcm::PCM_SINK_CMAF);
             // Bind the HLS / DASH HTTP server immediately so the WebUI's
             // automatic connect succeeds without waiting for the user to
@@ -136,6 +147,13 @@ pub async fn connect(state: web::Data<AppState>, path: web::Path<String>) -> Han
     for d in devices.iter_mut() {
         d.is_current_device = d.id == device.id;
     }
+    rockbox_webhooks::emit(rockbox_webhooks::Event::DeviceConnected {
+        device: device_info(&device),
+    });
+    tokio::spawn(dsp_profiles::apply_for_output(
+        state.pool.clone(),
+        device.id.clone(),
+    ));
     *current_device = Some(device);
 
     Ok(HttpResponse::Ok().finish())
@@ -168,10 +186,19 @@ pub async fn disconnect(state: web::Data<AppState>, _path: web::Path<String>) ->
         tracing::warn!("disconnect: failed to save settings: {e}");
     }
 
+    if let Some(device) = current_device.as_ref().filter(|d| d.id != "builtin") {
+        rockbox_webhooks::emit(rockbox_webhooks::Event::DeviceDisconnected {
+            device: device_info(device),
+        });
+    }
     for d in devices.iter_mut() {
         d.is_current_device = d.id == "builtin";
     }
     *current_device = devices.iter().find(|d| d.id == "builtin").cloned();
+    tokio::spawn(dsp_profiles::apply_for_output(
+        state.pool.clone(),
+        rockbox_dsp_profiles::BUILTIN_OUTPUT.to_string(),
+    ));
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -1,5 +1,10 @@
-use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    web, HttpResponse,
+};
+use rockbox_mixer::{MixerMode, ReplayGainMode};
 use rockbox_sys as rb;
+use rockbox_sys::types::user_settings::NewGlobalSettings;
 use serde::Deserialize;
 
 type HandlerResult = actix_web::Result<HttpResponse>;
@@ -161,3 +166,97 @@ pub async fn set_pbe(body: web::Json<SetPbeBody>) -> HandlerResult {
     .map_err(ErrorInternalServerError)?;
     Ok(HttpResponse::NoContent().finish())
 }
+
+/// Volume mode, ReplayGain and what they do to the track playing.
+pub async fn get_mixer() -> HandlerResult {
+    let status = rockbox_mixer::status();
+    let config = &status.config;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "mode": status.mode.name(),
+        "requested_mode": config.mode.name(),
+        "error": status.error,
+        "device": config.device,
+        "control": config.control,
+        "dither": config.dither,
+        "hardware_range_db": status.hardware_range_db.map(|(min, max)| [min, max]),
+        "volume_db": {
+            "left": status.volume_db.0,
+            "right": status.volume_db.1,
+        },
+        "replaygain": {
+            "mode": config.replaygain.mode.name(),
+            "preamp": config.replaygain.preamp_db,
+            "prevent_clipping": config.replaygain.prevent_clipping,
+        },
+        "track": status.track.map(|track| serde_json::json!({
+            "track_gain": track.track_gain_db,
+            "album_gain": track.album_gain_db,
+            "track_peak": track.track_peak,
+            "album_peak": track.album_peak,
+        })),
+        "replaygain_db": status.replaygain_db,
+        "clipping_prevented": status.clipping_prevented,
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct SetMixerBody {
+    /// "software", "hardware" or "fixed".
+    pub mode: Option<String>,
+    pub device: Option<String>,
+    pub control: Option<String>,
+    pub dither: Option<bool>,
+    /// "track", "album", "shuffle" or "off".
+    pub replaygain: Option<String>,
+    /// dB, -12 to 12.
+    pub preamp: Option<f32>,
+    pub prevent_clipping: Option<bool>,
+}
+
+pub async fn set_mixer(body: web::Json<SetMixerBody>) -> HandlerResult {
+    let body = body.into_inner();
+    if let Some(mode) = &body.mode {
+        mode.parse::<MixerMode>().map_err(ErrorBadRequest)?;
+    }
+    let replaygain = match &body.replaygain {
+        Some(mode) => Some(mode.parse::<ReplayGainMode>().map_err(ErrorBadRequest)?),
+        None => None,
+    };
+    if let Some(preamp) = body.preamp {
+        if !(-12.0..=12.0).contains(&preamp) {
+            return Err(ErrorBadRequest("preamp must be between -12 and 12 dB"));
+        }
+    }
+    let update = NewGlobalSettings {
+        mixer: body.mode,
+        mixer_device: body.device,
+        mixer_control: body.control,
+        mixer_dither: body.dither,
+        ..Default::default()
+    };
+    web::block(move || {
+        // ReplayGain is a firmware setting: saved with the rest of them and
+        // picked up from settings.toml by update_mixer.
+        if replaygain.is_some() || body.preamp.is_some() || body.prevent_clipping.is_some() {
+            rb::with_kernel_lock(|| {
+                if let Some(mode) = replaygain {
+                    unsafe { rb::global_settings.replaygain_settings.r#type = mode.to_firmware() };
+                }
+                if let Some(preamp) = body.preamp {
+                    let preamp = (preamp * 10.0).round() as i32;
+                    unsafe { rb::global_settings.replaygain_settings.preamp = preamp };
+                }
+                if let Some(noclip) = body.prevent_clipping {
+                    unsafe { rb::global_settings.replaygain_settings.noclip = noclip };
+                }
+                rockbox_settings::apply_replaygain();
+                rockbox_settings::write_settings()
+            })?;
+        }
+        rockbox_settings::update_mixer(&update)
+    })
+    .await
+    .map_err(ErrorInternalServerError)?
+    .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::NoContent().finish())
+}
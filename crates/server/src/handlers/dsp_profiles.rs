@@ -0,0 +1,170 @@
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_dsp_profiles::{DspProfileStore, DspProfileUpdate, DspSettings};
+use rockbox_sys as rb;
+use serde::Deserialize;
+
+use crate::{dsp_profiles, http::AppState};
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct NewProfileBody {
+    name: String,
+    /// Defaults to the settings in effect now.
+    settings: Option<DspSettings>,
+}
+
+#[derive(Deserialize)]
+pub struct ImportAutoEqBody {
+    name: String,
+    /// The contents of an AutoEQ `ParametricEQ.txt`.
+    content: String,
+}
+
+#[derive(Deserialize)]
+pub struct SetOutputBody {
+    profile_id: String,
+}
+
+pub async fn get_profiles(state: web::Data<AppState>) -> HandlerResult {
+    let profiles = DspProfileStore::new(state.pool.clone())
+        .list()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(profiles))
+}
+
+pub async fn get_profile(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    match DspProfileStore::new(state.pool.clone())
+        .get(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(profile) => Ok(HttpResponse::Ok().json(profile)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn create_profile(
+    state: web::Data<AppState>,
+    body: web::Json<NewProfileBody>,
+) -> HandlerResult {
+    let body = body.into_inner();
+    let settings = match body.settings {
+        Some(settings) => settings,
+        None => web::block(|| rb::with_kernel_lock(dsp_profiles::current))
+            .await
+            .map_err(ErrorInternalServerError)?,
+    };
+    match DspProfileStore::new(state.pool.clone())
+        .create(&body.name, settings)
+        .await
+    {
+        Ok(profile) => Ok(HttpResponse::Created().json(profile)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn import_autoeq(
+    state: web::Data<AppState>,
+    body: web::Json<ImportAutoEqBody>,
+) -> HandlerResult {
+    let body = body.into_inner();
+    match DspProfileStore::new(state.pool.clone())
+        .import_autoeq(&body.name, &body.content)
+        .await
+    {
+        Ok(imported) => Ok(HttpResponse::Created().json(imported)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn update_profile(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<DspProfileUpdate>,
+) -> HandlerResult {
+    match DspProfileStore::new(state.pool.clone())
+        .update(&path.into_inner(), body.into_inner())
+        .await
+    {
+        Ok(Some(profile)) => Ok(HttpResponse::Ok().json(profile)),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn delete_profile(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let deleted = DspProfileStore::new(state.pool.clone())
+        .delete(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+pub async fn apply_profile(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let profile = match DspProfileStore::new(state.pool.clone())
+        .get(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(profile) => profile,
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+    web::block(move || rb::with_kernel_lock(|| dsp_profiles::apply(&profile.settings)))
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn get_outputs(state: web::Data<AppState>) -> HandlerResult {
+    let outputs = DspProfileStore::new(state.pool.clone())
+        .outputs()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(outputs))
+}
+
+/// Bind an output to a profile, applying it straight away if that output is
+/// the current device.
+pub async fn set_output(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<SetOutputBody>,
+) -> HandlerResult {
+    let output = path.into_inner();
+    let found = DspProfileStore::new(state.pool.clone())
+        .set_output(&output, &body.profile_id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if !found {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let current = state
+        .current_device
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|d| d.id == output)
+        .unwrap_or(false);
+    if current {
+        dsp_profiles::apply_for_output(state.pool.clone(), output).await;
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn clear_output(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let cleared = DspProfileStore::new(state.pool.clone())
+        .clear_output(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if cleared {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
@@ -0,0 +1,73 @@
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_health::{DuplicateReason, HealthStore};
+use serde::Deserialize;
+
+use crate::http::AppState;
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct HealthQuery {
+    refresh: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct HideDuplicatesRequest {
+    reason: Option<DuplicateReason>,
+}
+
+/// The last library health report; `?refresh=true` checks the library
+/// again first.
+pub async fn get_health(
+    state: web::Data<AppState>,
+    query: web::Query<HealthQuery>,
+) -> HandlerResult {
+    let store = HealthStore::new(state.pool.clone());
+    let report = match query.refresh.unwrap_or(false) {
+        true => store.scan().await,
+        false => store.report().await,
+    }
+    .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Hide all but the best copy of every duplicate, optionally only of
+/// those found by `reason`.
+pub async fn hide_duplicates(
+    state: web::Data<AppState>,
+    body: Option<web::Json<HideDuplicatesRequest>>,
+) -> HandlerResult {
+    let reason = body.map(|b| b.into_inner()).unwrap_or_default().reason;
+    let hidden = HealthStore::new(state.pool.clone())
+        .hide_duplicates(reason)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "hidden": hidden })))
+}
+
+pub async fn remove_orphans(state: web::Data<AppState>) -> HandlerResult {
+    let (albums, artists) = HealthStore::new(state.pool.clone())
+        .remove_orphans()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "albums": albums, "artists": artists })))
+}
+
+async fn set_hidden(state: web::Data<AppState>, id: String, hidden: bool) -> HandlerResult {
+    let found = HealthStore::new(state.pool.clone())
+        .set_hidden(&id, hidden)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(match found {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
+pub async fn hide_track(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    set_hidden(state, path.into_inner(), true).await
+}
+
+pub async fn unhide_track(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    set_hidden(state, path.into_inner(), false).await
+}
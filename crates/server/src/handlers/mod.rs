@@ -1,17 +1,27 @@
 pub mod albums;
 pub mod artists;
+pub mod audiobooks;
 #[cfg(target_os = "linux")]
 pub mod bluetooth;
 pub mod browse;
 pub mod devices;
 pub mod docs;
 pub mod dsp;
+pub mod dsp_profiles;
 pub mod genres;
+pub mod health;
 pub mod player;
 pub mod playlists;
+pub mod podcasts;
+pub mod radio;
+pub mod ratings;
 pub mod saved_playlists;
+pub mod schedules;
 pub mod search;
 pub mod settings;
 pub mod smart_playlists;
+pub mod sources;
 pub mod system;
+pub mod tokens;
 pub mod tracks;
+pub mod webhooks;
@@ -3,15 +3,19 @@ use std::{env, ffi::CString};
 use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
 use local_ip_addr::get_local_ip_address;
 use rand::seq::SliceRandom;
+use rockbox_autoqueue::AutoQueueUpdate;
 use rockbox_chromecast::Chromecast;
-use rockbox_library::repo;
+use rockbox_library::{entity::chapter::Chapter as LibraryChapter, lyrics::Lyrics, repo};
 use rockbox_sys::{
     self as rb,
     types::{audio_status::AudioStatus, mp3_entry::Mp3Entry},
 };
-use rockbox_traits::types::track::Track;
+use rockbox_traits::types::{
+    chapter::{self, Chapter},
+    track::Track,
+};
 use rockbox_types::{device::Device, LoadTracks, NewVolume};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{handlers::playlists::hydrate_entry_from_track, http::AppState, GLOBAL_MUTEX};
 
@@ -55,6 +59,7 @@ pub async fn load(state: web::Data<AppState>, body: web::Json<LoadTracks>) -> Ha
     let rockbox_addr = env::var("ROCKBOX_ADDR").unwrap_or_else(|_| get_local_ip_address().unwrap());
     let rockbox_port = env::var("ROCKBOX_GRAPHQL_PORT").unwrap_or_else(|_| "6062".to_string());
     let mut tracks = Vec::new();
+    let mut has_books = false;
 
     for requested_path in &request.tracks {
         let track = {
@@ -77,6 +82,10 @@ pub async fn load(state: web::Data<AppState>, body: web::Json<LoadTracks>) -> Ha
         };
 
         if let Some(track) = track {
+            has_books |= track.is_book();
+            let chapters = repo::chapter::find_by_track(state.pool.clone(), &track.id)
+                .await
+                .map_err(ErrorInternalServerError)?;
             tracks.push(Track {
                 id: track.id.clone(),
                 title: track.title.clone(),
@@ -96,6 +105,7 @@ pub async fn load(state: web::Data<AppState>, body: web::Json<LoadTracks>) -> Ha
                 ),
                 disc_number: track.disc_number,
                 duration: Some(track.length as f32 / 1000.0),
+                chapters: to_player_chapters(&chapters),
                 ..Default::default()
             });
         }
@@ -105,8 +115,9 @@ pub async fn load(state: web::Data<AppState>, body: web::Json<LoadTracks>) -> Ha
         return Ok(HttpResponse::NotFound().body("No playable tracks found"));
     }
 
+    // Books are always played in order.
     let mut tracks = tracks;
-    if Some(true) == request.shuffle {
+    if Some(true) == request.shuffle && !has_books {
         tracks.shuffle(&mut rand::thread_rng());
     }
 
@@ -375,6 +386,117 @@ pub async fn previous() -> HandlerResult {
     Ok(HttpResponse::Ok().finish())
 }
 
+pub async fn chapters(state: web::Data<AppState>) -> HandlerResult {
+    let (path, _) = current_position(&state)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let chapters = match path {
+        Some(path) => current_chapters(&state, &path)
+            .await
+            .map_err(ErrorInternalServerError)?,
+        None => vec![],
+    };
+    Ok(HttpResponse::Ok().json(chapters))
+}
+
+#[derive(Serialize)]
+pub struct CurrentLyrics {
+    track_id: String,
+    position_ms: u64,
+    /// Index of the line being sung, for synced lyrics.
+    line: Option<usize>,
+    lyrics: Lyrics,
+}
+
+pub async fn lyrics(state: web::Data<AppState>) -> HandlerResult {
+    let (path, position_ms) = current_position(&state)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let track = match path {
+        Some(path) => current_library_track(&state, &path)
+            .await
+            .map_err(ErrorInternalServerError)?,
+        None => None,
+    };
+    let Some(track) = track else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+    let lyrics = rockbox_library::lyrics::get_lyrics(state.pool.clone(), &track)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    match lyrics {
+        Some(lyrics) => Ok(HttpResponse::Ok().json(CurrentLyrics {
+            track_id: track.id,
+            position_ms,
+            line: rockbox_library::lyrics::current_line(&lyrics, position_ms),
+            lyrics,
+        })),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn next_chapter(state: web::Data<AppState>) -> HandlerResult {
+    if state.player.lock().unwrap().is_some() {
+        let mut player = state.player.lock().unwrap();
+        if let Some(p) = player.as_deref_mut() {
+            p.next_chapter().await.map_err(ErrorInternalServerError)?;
+        }
+        return Ok(HttpResponse::Ok().finish());
+    }
+
+    let (path, elapsed) = current_position(&state)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let Some(path) = path else {
+        return Ok(HttpResponse::Ok().finish());
+    };
+    let chapters = current_chapters(&state, &path)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let target = chapter::next_chapter_start(&to_player_chapters(&chapters), elapsed);
+    web::block(move || {
+        rb::with_kernel_lock(move || match target {
+            Some(start_ms) => rb::playback::ff_rewind(start_ms as i32),
+            None => rb::playback::next(),
+        });
+    })
+    .await
+    .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn previous_chapter(state: web::Data<AppState>) -> HandlerResult {
+    if state.player.lock().unwrap().is_some() {
+        let mut player = state.player.lock().unwrap();
+        if let Some(p) = player.as_deref_mut() {
+            p.previous_chapter()
+                .await
+                .map_err(ErrorInternalServerError)?;
+        }
+        return Ok(HttpResponse::Ok().finish());
+    }
+
+    let (path, elapsed) = current_position(&state)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let Some(path) = path else {
+        return Ok(HttpResponse::Ok().finish());
+    };
+    let chapters = current_chapters(&state, &path)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let target = chapter::previous_chapter_start(&to_player_chapters(&chapters), elapsed);
+    web::block(move || {
+        rb::with_kernel_lock(move || match target {
+            Some(start_ms) => rb::playback::ff_rewind(start_ms as i32),
+            None => rb::playback::prev(),
+        });
+    })
+    .await
+    .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
 pub async fn stop() -> HandlerResult {
     web::block(|| {
         rb::with_kernel_lock(|| {
@@ -416,6 +538,44 @@ pub async fn adjust_volume(body: web::Json<NewVolume>) -> HandlerResult {
     Ok(HttpResponse::Ok().json(new_volume))
 }
 
+/// Whether the configured output gets the decoded samples unchanged, and
+/// what the alsa sink negotiated with the device.
+pub async fn get_output() -> HandlerResult {
+    let processing = web::block(|| rb::with_kernel_lock(rockbox_settings::active_processing))
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let output = rockbox_settings::read_settings()
+        .unwrap_or_default()
+        .audio_output
+        .unwrap_or_else(|| "builtin".to_string());
+    if output != "alsa" {
+        return Ok(HttpResponse::Ok().json(serde_json::json!({
+            "output": output,
+            "bit_perfect": false,
+            "reasons": ["only the alsa output can be bit-perfect"],
+        })));
+    }
+
+    let status = rockbox_alsa_sink::status();
+    let mut reasons = status.reasons();
+    // DoP streams never go through the firmware.
+    if status.dsd.is_none() {
+        reasons.extend(processing.iter().map(|stage| format!("{stage} is on")));
+    }
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "output": output,
+        "bit_perfect": reasons.is_empty(),
+        "reasons": reasons,
+        "device": status.device,
+        "exclusive": status.exclusive,
+        "format": status.format.map(|format| format.name()),
+        "rate": status.rate,
+        "source_rate": status.source_rate,
+        "channels": status.channels,
+        "dsd_rate": status.dsd,
+    })))
+}
+
 pub async fn get_current_player(state: web::Data<AppState>) -> HandlerResult {
     let device = state.current_device.lock().unwrap();
 
@@ -431,6 +591,17 @@ pub async fn get_current_player(state: web::Data<AppState>) -> HandlerResult {
     }))
 }
 
+pub async fn get_auto_queue() -> HandlerResult {
+    Ok(HttpResponse::Ok().json(rockbox_autoqueue::config()))
+}
+
+pub async fn set_auto_queue(body: web::Json<AutoQueueUpdate>) -> HandlerResult {
+    match rockbox_autoqueue::update(body.into_inner()) {
+        Ok(config) => Ok(HttpResponse::Ok().json(config)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
 async fn find_track_metadata(
     state: &AppState,
     path: &str,
@@ -464,6 +635,77 @@ async fn find_track_metadata(
     Ok(metadata)
 }
 
+pub(crate) fn to_player_chapters(chapters: &[LibraryChapter]) -> Vec<Chapter> {
+    chapters
+        .iter()
+        .map(|c| Chapter {
+            title: c.title.clone(),
+            start_ms: c.start_ms as u64,
+            end_ms: c.end_ms as u64,
+        })
+        .collect()
+}
+
+/// Path of the track being played and the elapsed time in it, from the
+/// external player when one is connected, the built-in one otherwise.
+async fn current_position(state: &AppState) -> Result<(Option<String>, u64), anyhow::Error> {
+    if state.player.lock().unwrap().is_some() {
+        let mut player = state.player.lock().unwrap();
+        if let Some(p) = player.as_deref_mut() {
+            let playback = p.get_current_playback().await?;
+            let path = playback.current_track.map(|t| match t.path.is_empty() {
+                true => t.uri,
+                false => t.path,
+            });
+            return Ok((path, playback.position_ms as u64));
+        }
+    }
+
+    let position = web::block(|| {
+        rb::with_kernel_lock(|| {
+            let track = rb::playback::current_track()?;
+            let index = rb::playlist::index();
+            let filename = match index >= 0 {
+                true => rb::playlist::get_track_info(index).filename,
+                false => String::new(),
+            };
+            let path = match filename.is_empty() {
+                true => track.path.clone(),
+                false => filename,
+            };
+            Some((path, track.elapsed))
+        })
+    })
+    .await?;
+    Ok(match position {
+        Some((path, elapsed)) => (Some(path), elapsed),
+        None => (None, 0),
+    })
+}
+
+/// The library track playing from `path` (a file path, or a `/tracks/{id}`
+/// URL when casting).
+async fn current_library_track(
+    state: &AppState,
+    path: &str,
+) -> Result<Option<rockbox_library::entity::track::Track>, anyhow::Error> {
+    let hash = format!("{:x}", md5::compute(path.as_bytes()));
+    match repo::track::find_by_md5(state.pool.clone(), &hash).await? {
+        Some(track) => Ok(Some(track)),
+        None => find_internal_track_by_url(state, path).await,
+    }
+}
+
+async fn current_chapters(
+    state: &AppState,
+    path: &str,
+) -> Result<Vec<LibraryChapter>, anyhow::Error> {
+    match current_library_track(state, path).await? {
+        Some(track) => Ok(repo::chapter::find_by_track(state.pool.clone(), &track.id).await?),
+        None => Ok(vec![]),
+    }
+}
+
 async fn ensure_remote_track_metadata(path: String) -> Result<(), anyhow::Error> {
     let status = tokio::task::spawn_blocking(move || -> Result<i32, anyhow::Error> {
         let path_cstr = CString::new(path.as_str())?;
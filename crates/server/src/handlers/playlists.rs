@@ -1,17 +1,28 @@
-use std::{env, sync::atomic::Ordering, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    path::Path,
+    sync::atomic::Ordering,
+    sync::Arc,
+};
 
-use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    web, HttpResponse,
+};
 use futures_util::stream::{FuturesUnordered, StreamExt};
 use local_ip_addr::get_local_ip_address;
-use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use rockbox_graphql::read_files_with_art;
 use rockbox_library::audio_scan::save_audio_metadata;
 use rockbox_library::repo;
+use rockbox_playlists::TrackStats;
 use rockbox_sys::{
     self as rb,
     types::{playlist_amount::PlaylistAmount, playlist_info::PlaylistInfo},
     PLAYLIST_INSERT_LAST, PLAYLIST_INSERT_LAST_SHUFFLED,
 };
+use rockbox_tracklist::shuffle::{self, ShuffleItem, ShuffleMode};
 use rockbox_traits::types::track::Track;
 use rockbox_types::{DeleteTracks, InsertTracks, NewPlaylist, StatusCode};
 use serde::Deserialize;
@@ -160,14 +171,172 @@ pub async fn start_playlist(query: web::Query<StartPlaylistQuery>) -> HandlerRes
 #[derive(Deserialize)]
 pub struct ShuffleQuery {
     start_index: Option<i32>,
+    /// "tracks", "album", "artist_spread" or "smart"; defaults to the
+    /// `shuffle_mode` setting.
+    mode: Option<String>,
+    /// Same seed, same order.
+    seed: Option<u64>,
+}
+
+/// Whether any of `paths` is a book; books always play in order.
+async fn contains_books(state: &AppState, paths: &[String]) -> Result<bool, anyhow::Error> {
+    let books = repo::track::book_paths(state.pool.clone()).await?;
+    if books.is_empty() {
+        return Ok(false);
+    }
+    let books: HashSet<String> = books.into_iter().collect();
+    Ok(paths.iter().any(|path| books.contains(path)))
+}
+
+/// What the shuffle modes need to know about each of `paths`. Files
+/// missing from the library count as their own artist, with their folder
+/// as the album.
+async fn shuffle_items(
+    state: &AppState,
+    paths: &[String],
+) -> Result<Vec<ShuffleItem>, anyhow::Error> {
+    let stats: HashMap<String, TrackStats> = state
+        .playlist_store
+        .get_all_track_stats()
+        .await?
+        .into_iter()
+        .map(|s| (s.track_id.clone(), s))
+        .collect();
+    let liked: HashSet<String> = repo::favourites::all_tracks(state.pool.clone())
+        .await?
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+        .as_secs() as i64;
+
+    let mut items = Vec::with_capacity(paths.len());
+    for path in paths {
+        let item = match repo::track::find_by_path(state.pool.clone(), path).await? {
+            Some(track) => {
+                let stats = stats.get(&track.id);
+                ShuffleItem {
+                    weight: shuffle::smart_weight(
+                        stats.map(|s| s.play_count).unwrap_or_default(),
+                        liked.contains(&track.id),
+                        stats.and_then(|s| s.last_played),
+                        now,
+                    ),
+                    album: track.album_id,
+                    artist: track.artist_id,
+                    disc_number: track.disc_number,
+                    track_number: track.track_number.unwrap_or_default(),
+                }
+            }
+            None => ShuffleItem {
+                album: Path::new(path)
+                    .parent()
+                    .map(|dir| dir.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                artist: path.clone(),
+                weight: 1.0,
+                ..Default::default()
+            },
+        };
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Rebuild the current playlist in the order of `target` (paths) in one
+/// go rather than track by track. With `keep_current`, the track playing
+/// stays first and carries on.
+fn apply_order(keep_current: bool, mut target: Vec<String>) {
+    if target.is_empty() {
+        return;
+    }
+    if keep_current {
+        let current = rb::playlist::get_track_info(rb::playlist::index()).filename;
+        if let Some(i) = target.iter().position(|t| *t == current) {
+            target.remove(i);
+        }
+        if rb::playlist::remove_all_tracks() != 0 {
+            return;
+        }
+        rb::playlist::insert_tracks(
+            target.iter().map(|t| t.as_str()).collect(),
+            PLAYLIST_INSERT_LAST,
+            target.len() as i32,
+        );
+        return;
+    }
+    let first = &target[0];
+    let dir = if first.starts_with("http://") || first.starts_with("https://") {
+        "/".to_string()
+    } else {
+        let dir_parts: Vec<_> = first.split('/').collect();
+        dir_parts[0..dir_parts.len() - 1].join("/")
+    };
+    if rb::playlist::create(&dir, None) == -1 {
+        return;
+    }
+    rb::playlist::build_playlist(
+        target.iter().map(|t| t.as_str()).collect(),
+        0,
+        target.len() as i32,
+    );
 }
 
-pub async fn shuffle_playlist(query: web::Query<ShuffleQuery>) -> HandlerResult {
+pub async fn shuffle_playlist(
+    state: web::Data<AppState>,
+    query: web::Query<ShuffleQuery>,
+) -> HandlerResult {
     let start_index = query.start_index.unwrap_or(0);
+    let mode: ShuffleMode = match &query.mode {
+        Some(mode) => mode.parse().map_err(ErrorBadRequest)?,
+        None => rockbox_settings::read_settings()
+            .ok()
+            .and_then(|s| s.shuffle_mode)
+            .and_then(|mode| mode.parse().ok())
+            .unwrap_or_default(),
+    };
+    let (paths, tick) = web::block(|| {
+        rb::with_kernel_lock(|| {
+            let paths = (0..rb::playlist::amount())
+                .map(|i| rb::playlist::get_track_info(i).filename)
+                .collect::<Vec<String>>();
+            (paths, rb::system::current_tick())
+        })
+    })
+    .await
+    .map_err(ErrorInternalServerError)?;
+    if contains_books(&state, &paths)
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        return Ok(HttpResponse::Ok().body("0"));
+    }
+    let seed = query.seed.unwrap_or(tick as u64);
+
+    // The firmware shuffle moves the selected track to the front; the other
+    // modes then rearrange the tracks after it.
+    let target = match mode {
+        ShuffleMode::Tracks => None,
+        mode => {
+            let items = shuffle_items(&state, &paths)
+                .await
+                .map_err(ErrorInternalServerError)?;
+            let order = shuffle::shuffle_order(&items, mode, &mut StdRng::seed_from_u64(seed));
+            Some(
+                order
+                    .into_iter()
+                    .map(|i| paths[i].clone())
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
     let ret = web::block(move || {
         rb::with_kernel_lock(move || {
-            let seed = rb::system::current_tick();
             let ret = rb::playlist::shuffle(seed as i32, start_index);
+            if let Some(target) = target {
+                apply_order(start_index >= 0, target);
+            }
             PLAYLIST_DIRTY.store(true, Ordering::Relaxed);
             ret
         })
@@ -297,6 +466,14 @@ pub async fn insert_tracks(
 
     persist_remote_track_metadata(state.pool.clone(), tracks_with_art).await;
 
+    if tracklist.position == PLAYLIST_INSERT_LAST_SHUFFLED
+        && contains_books(&state, &tracklist.tracks)
+            .await
+            .map_err(ErrorInternalServerError)?
+    {
+        tracklist.position = PLAYLIST_INSERT_LAST;
+    }
+
     // Check for external player first (async path).
     {
         let mut player = state.player.lock().unwrap();
@@ -0,0 +1,289 @@
+use std::sync::atomic::Ordering;
+
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_sys::{self as rb};
+use serde::Deserialize;
+
+use crate::{http::AppState, PLAYLIST_DIRTY};
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct SubscribeRequest {
+    url: String,
+    #[serde(default)]
+    auto_download: bool,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateChannelRequest {
+    auto_download: bool,
+}
+
+#[derive(Deserialize)]
+pub struct PositionRequest {
+    position_ms: i64,
+}
+
+#[derive(Deserialize)]
+pub struct PlayedRequest {
+    played: bool,
+}
+
+#[derive(Deserialize)]
+pub struct NewestQuery {
+    count: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct DownloadQuery {
+    /// Wait for the download to finish instead of running it in the background.
+    #[serde(default)]
+    wait: bool,
+}
+
+pub async fn get_podcasts(state: web::Data<AppState>) -> HandlerResult {
+    let channels = state
+        .podcast_store
+        .list_channels()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(channels))
+}
+
+pub async fn subscribe_podcast(
+    state: web::Data<AppState>,
+    body: web::Json<SubscribeRequest>,
+) -> HandlerResult {
+    let req = body.into_inner();
+    if req.url.trim().is_empty() {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+    match state
+        .podcast_store
+        .subscribe(&req.url, req.auto_download)
+        .await
+    {
+        Ok(channel) => Ok(HttpResponse::Created().json(channel)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn refresh_podcasts(state: web::Data<AppState>) -> HandlerResult {
+    let added = state
+        .podcast_store
+        .refresh_all()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "new_episodes": added })))
+}
+
+pub async fn get_newest_episodes(
+    state: web::Data<AppState>,
+    query: web::Query<NewestQuery>,
+) -> HandlerResult {
+    let episodes = state
+        .podcast_store
+        .newest_episodes(query.count.unwrap_or(20))
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(episodes))
+}
+
+pub async fn get_podcast(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    match state
+        .podcast_store
+        .get_channel(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(channel) => Ok(HttpResponse::Ok().json(channel)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn update_podcast(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<UpdateChannelRequest>,
+) -> HandlerResult {
+    let id = path.into_inner();
+    let updated = state
+        .podcast_store
+        .set_auto_download(&id, body.auto_download)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if !updated {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let channel = state
+        .podcast_store
+        .get_channel(&id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(channel))
+}
+
+pub async fn delete_podcast(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let deleted = state
+        .podcast_store
+        .delete_channel(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+pub async fn refresh_podcast(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let id = path.into_inner();
+    if state
+        .podcast_store
+        .get_channel(&id)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .is_none()
+    {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    match state.podcast_store.refresh_channel(&id).await {
+        Ok(episodes) => Ok(HttpResponse::Ok().json(episodes)),
+        Err(e) => Ok(HttpResponse::BadGateway().body(e.to_string())),
+    }
+}
+
+pub async fn get_podcast_episodes(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    let episodes = state
+        .podcast_store
+        .list_episodes(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(episodes))
+}
+
+pub async fn get_episode(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    match state
+        .podcast_store
+        .get_episode(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(episode) => Ok(HttpResponse::Ok().json(episode)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn download_episode(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<DownloadQuery>,
+) -> HandlerResult {
+    let id = path.into_inner();
+    if state
+        .podcast_store
+        .get_episode(&id)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .is_none()
+    {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    if !query.wait {
+        state.podcast_store.spawn_download(id);
+        return Ok(HttpResponse::Accepted().finish());
+    }
+    match state.podcast_store.download_episode(&id).await {
+        Ok(episode) => Ok(HttpResponse::Ok().json(episode)),
+        Err(e) => Ok(HttpResponse::BadGateway().body(e.to_string())),
+    }
+}
+
+pub async fn delete_episode_download(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    let deleted = state
+        .podcast_store
+        .delete_download(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+pub async fn set_episode_position(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<PositionRequest>,
+) -> HandlerResult {
+    let updated = state
+        .podcast_store
+        .set_position(&path.into_inner(), body.position_ms)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if updated {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+pub async fn set_episode_played(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<PlayedRequest>,
+) -> HandlerResult {
+    let updated = state
+        .podcast_store
+        .set_played(&path.into_inner(), body.played)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if updated {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+/// Play an episode from its download (or stream it), resuming at the saved
+/// position unless it has already been played to the end.
+pub async fn play_episode(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let episode = match state
+        .podcast_store
+        .get_episode(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(episode) => episode,
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let source = episode.playback_path();
+    let elapsed = if episode.played {
+        0
+    } else {
+        episode.position_ms.max(0) as u64
+    };
+
+    web::block(move || {
+        rb::with_kernel_lock(move || {
+            rb::playback::hard_stop();
+            let dir = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            rb::playlist::create(&dir, None);
+            rb::playlist::build_playlist(vec![source.as_str()], 0, 1);
+            rb::playlist::start(0, elapsed, 0);
+            PLAYLIST_DIRTY.store(true, Ordering::Relaxed);
+        });
+    })
+    .await
+    .map_err(ErrorInternalServerError)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,124 @@
+use std::sync::atomic::Ordering;
+
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_library::{
+    radio::{self, StationInput},
+    repo,
+};
+use rockbox_sys::{self as rb};
+use serde::Deserialize;
+
+use crate::{http::AppState, PLAYLIST_DIRTY};
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    genre: Option<String>,
+}
+
+pub async fn get_radio_stations(state: web::Data<AppState>) -> HandlerResult {
+    let stations = repo::radio_station::all(state.pool.clone())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(stations))
+}
+
+pub async fn get_radio_station(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    match repo::radio_station::find(state.pool.clone(), &path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(station) => Ok(HttpResponse::Ok().json(station)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn create_radio_station(
+    state: web::Data<AppState>,
+    body: web::Json<StationInput>,
+) -> HandlerResult {
+    let input = body.into_inner();
+    if !input.is_valid() {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+    let station = radio::create_station(state.pool.clone(), input)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Created().json(station))
+}
+
+pub async fn update_radio_station(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<StationInput>,
+) -> HandlerResult {
+    let input = body.into_inner();
+    if !input.is_valid() {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+    match radio::update_station(state.pool.clone(), &path.into_inner(), input)
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(station) => Ok(HttpResponse::Ok().json(station)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn delete_radio_station(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    let deleted = repo::radio_station::delete(state.pool.clone(), &path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+/// Import an M3U or PLS station list sent as the raw request body.
+pub async fn import_radio_stations(
+    state: web::Data<AppState>,
+    query: web::Query<ImportQuery>,
+    body: String,
+) -> HandlerResult {
+    let stations = radio::import_stations(state.pool.clone(), &body, query.genre.as_deref())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(stations))
+}
+
+pub async fn play_radio_station(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    let station = match repo::radio_station::find(state.pool.clone(), &path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(station) => station,
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    web::block(move || {
+        rb::with_kernel_lock(move || {
+            rb::playback::hard_stop();
+            let dir = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            rb::playlist::create(&dir, None);
+            rb::playlist::build_playlist(vec![station.stream_url.as_str()], 0, 1);
+            rb::playlist::start(0, 0, 0);
+            PLAYLIST_DIRTY.store(true, Ordering::Relaxed);
+        });
+    })
+    .await
+    .map_err(ErrorInternalServerError)?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,156 @@
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    web, HttpResponse,
+};
+use rockbox_library::{
+    entity::rating::{self, KIND_ALBUM, KIND_ARTIST, KIND_TRACK, MAX_RATING},
+    ratings, repo,
+};
+use serde::Deserialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::http::AppState;
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct RatingsQuery {
+    kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RatingRequest {
+    rating: u8,
+}
+
+async fn exists(pool: Pool<Sqlite>, kind: &str, id: &str) -> Result<bool, sqlx::Error> {
+    Ok(match kind {
+        KIND_TRACK => repo::track::find(pool, id).await?.is_some(),
+        KIND_ALBUM => repo::album::find(pool, id).await?.is_some(),
+        _ => repo::artist::find(pool, id).await?.is_some(),
+    })
+}
+
+async fn get_rating(state: web::Data<AppState>, kind: &str, id: String) -> HandlerResult {
+    if !exists(state.pool.clone(), kind, &id)
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let rating = repo::rating::find(state.pool.clone(), kind, &id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "rating": rating.unwrap_or(0) })))
+}
+
+async fn save_rating(
+    state: web::Data<AppState>,
+    kind: &str,
+    id: String,
+    rating: u8,
+) -> HandlerResult {
+    if rating > MAX_RATING {
+        return Err(ErrorBadRequest(format!(
+            "rating must be between 0 and {}",
+            MAX_RATING
+        )));
+    }
+    if !exists(state.pool.clone(), kind, &id)
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    ratings::set(state.pool.clone(), kind, &id, rating)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Every rated item, best first, optionally of one kind only.
+pub async fn get_ratings(
+    state: web::Data<AppState>,
+    query: web::Query<RatingsQuery>,
+) -> HandlerResult {
+    let kinds = match query.kind.as_deref() {
+        Some(kind) if rating::is_kind(kind) => vec![kind],
+        Some(kind) => return Err(ErrorBadRequest(format!("unknown kind: {}", kind))),
+        None => vec![KIND_TRACK, KIND_ALBUM, KIND_ARTIST],
+    };
+    let mut all = Vec::new();
+    for kind in kinds {
+        all.extend(
+            repo::rating::all(state.pool.clone(), kind)
+                .await
+                .map_err(ErrorInternalServerError)?,
+        );
+    }
+    Ok(HttpResponse::Ok().json(all))
+}
+
+pub async fn get_track_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    get_rating(state, KIND_TRACK, path.into_inner()).await
+}
+
+pub async fn save_track_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<RatingRequest>,
+) -> HandlerResult {
+    save_rating(state, KIND_TRACK, path.into_inner(), body.rating).await
+}
+
+pub async fn delete_track_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    save_rating(state, KIND_TRACK, path.into_inner(), 0).await
+}
+
+pub async fn get_album_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    get_rating(state, KIND_ALBUM, path.into_inner()).await
+}
+
+pub async fn save_album_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<RatingRequest>,
+) -> HandlerResult {
+    save_rating(state, KIND_ALBUM, path.into_inner(), body.rating).await
+}
+
+pub async fn delete_album_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    save_rating(state, KIND_ALBUM, path.into_inner(), 0).await
+}
+
+pub async fn get_artist_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    get_rating(state, KIND_ARTIST, path.into_inner()).await
+}
+
+pub async fn save_artist_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<RatingRequest>,
+) -> HandlerResult {
+    save_rating(state, KIND_ARTIST, path.into_inner(), body.rating).await
+}
+
+pub async fn delete_artist_rating(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    save_rating(state, KIND_ARTIST, path.into_inner(), 0).await
+}
@@ -1,5 +1,11 @@
-use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use std::path::Path;
+
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    web, HttpResponse,
+};
 use rockbox_library::repo;
+use rockbox_playlists::formats::{self, Format};
 use rockbox_sys::{self as rb};
 use serde::Deserialize;
 
@@ -39,6 +45,23 @@ pub struct ListQuery {
     folder_id: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    /// Guessed from the content when absent.
+    format: Option<String>,
+    name: Option<String>,
+    folder_id: Option<String>,
+    /// Folder that relative paths in the file start from.
+    base_dir: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    format: Option<String>,
+    /// Write local paths relative to this folder.
+    relative_to: Option<String>,
+}
+
 pub async fn list_saved_playlists(
     state: web::Data<AppState>,
     query: web::Query<ListQuery>,
@@ -296,3 +319,71 @@ pub async fn delete_playlist_folder(
         .map_err(ErrorInternalServerError)?;
     Ok(HttpResponse::NoContent().finish())
 }
+
+pub async fn import_saved_playlist(
+    state: web::Data<AppState>,
+    query: web::Query<ImportQuery>,
+    body: String,
+) -> HandlerResult {
+    let query = query.into_inner();
+    let format = match query.format.as_deref() {
+        Some(format) => format.parse::<Format>().map_err(ErrorBadRequest)?,
+        None => Format::sniff(&body),
+    };
+    let file = formats::parse(format, &body).map_err(ErrorBadRequest)?;
+    let report = state
+        .playlist_store
+        .import_playlist(
+            file,
+            query.name.as_deref().filter(|n| !n.is_empty()),
+            query.folder_id.as_deref(),
+            query.base_dir.as_deref().map(Path::new),
+        )
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Created().json(report))
+}
+
+pub async fn export_saved_playlist(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+) -> HandlerResult {
+    let id = path.into_inner();
+    let format = match query.format.as_deref() {
+        Some(format) => format.parse::<Format>().map_err(ErrorBadRequest)?,
+        None => Format::M3u,
+    };
+    let Some(playlist) = state
+        .playlist_store
+        .get(&id)
+        .await
+        .map_err(ErrorInternalServerError)?
+    else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+    let Some(content) = state
+        .playlist_store
+        .export_playlist(&id, format, query.relative_to.as_deref().map(Path::new))
+        .await
+        .map_err(ErrorInternalServerError)?
+    else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+    let file_name: String = playlist
+        .name
+        .chars()
+        .map(|c| if c == '"' || c.is_control() { '_' } else { c })
+        .collect();
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"{}.{}\"",
+                file_name,
+                format.extension()
+            ),
+        ))
+        .body(content))
+}
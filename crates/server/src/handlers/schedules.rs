@@ -0,0 +1,131 @@
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_scheduler::{NewSchedule, ScheduleUpdate, SchedulerStore};
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{http::AppState, scheduler};
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct SleepTimerBody {
+    minutes: u32,
+    /// Fade out over the last seconds of the timer.
+    #[serde(default)]
+    fade_secs: i64,
+}
+
+pub async fn get_schedules(state: web::Data<AppState>) -> HandlerResult {
+    let schedules = SchedulerStore::new(state.pool.clone())
+        .list()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(schedules))
+}
+
+pub async fn get_schedule(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    match SchedulerStore::new(state.pool.clone())
+        .get(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(schedule) => Ok(HttpResponse::Ok().json(schedule)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn create_schedule(
+    state: web::Data<AppState>,
+    body: web::Json<NewSchedule>,
+) -> HandlerResult {
+    match SchedulerStore::new(state.pool.clone())
+        .create(body.into_inner())
+        .await
+    {
+        Ok(schedule) => Ok(HttpResponse::Created().json(schedule)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn update_schedule(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<ScheduleUpdate>,
+) -> HandlerResult {
+    match SchedulerStore::new(state.pool.clone())
+        .update(&path.into_inner(), body.into_inner())
+        .await
+    {
+        Ok(Some(schedule)) => Ok(HttpResponse::Ok().json(schedule)),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn delete_schedule(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let deleted = SchedulerStore::new(state.pool.clone())
+        .delete(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+/// Run a schedule's action now, whether or not it is due or enabled.
+pub async fn run_schedule(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let schedule = match SchedulerStore::new(state.pool.clone())
+        .get(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(schedule) => schedule,
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let pool = state.pool.clone();
+    tokio::spawn(async move {
+        let name = schedule.name.clone();
+        if let Err(e) = scheduler::run(pool, schedule).await {
+            error!("scheduler: \"{}\" failed: {}", name, e);
+        }
+    });
+    Ok(HttpResponse::Accepted().finish())
+}
+
+pub async fn get_sleep_timer(state: web::Data<AppState>) -> HandlerResult {
+    match SchedulerStore::new(state.pool.clone())
+        .sleep_timer()
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(timer) => Ok(HttpResponse::Ok().json(timer)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn set_sleep_timer(
+    state: web::Data<AppState>,
+    body: web::Json<SleepTimerBody>,
+) -> HandlerResult {
+    match SchedulerStore::new(state.pool.clone())
+        .set_sleep_timer(body.minutes, body.fade_secs)
+        .await
+    {
+        Ok(timer) => Ok(HttpResponse::Ok().json(timer)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn cancel_sleep_timer(state: web::Data<AppState>) -> HandlerResult {
+    let cancelled = SchedulerStore::new(state.pool.clone())
+        .cancel_sleep_timer()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if cancelled {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
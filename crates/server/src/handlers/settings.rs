@@ -1,6 +1,10 @@
-use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    web, HttpResponse,
+};
 use rockbox_sys as rb;
 use rockbox_sys::types::user_settings::NewGlobalSettings;
+use rockbox_tracklist::shuffle::ShuffleMode;
 
 type HandlerResult = actix_web::Result<HttpResponse>;
 
@@ -13,6 +17,14 @@ pub async fn get_global_settings() -> HandlerResult {
 
 pub async fn update_global_settings(body: web::Json<NewGlobalSettings>) -> HandlerResult {
     let settings = body.into_inner();
+    if let Some(mode) = &settings.shuffle_mode {
+        mode.parse::<ShuffleMode>().map_err(ErrorBadRequest)?;
+    }
+    rockbox_settings::stream_transitions(&settings).map_err(ErrorBadRequest)?;
+    let transitions = settings.clone();
+    let shuffle_mode = settings.shuffle_mode.clone();
+    let playlists_dir = settings.playlists_dir.clone();
+    let output = settings.clone();
     web::block(move || {
         rb::with_kernel_lock(move || {
             if let Err(e) = rockbox_settings::load_settings(Some(settings)) {
@@ -21,6 +33,44 @@ pub async fn update_global_settings(body: web::Json<NewGlobalSettings>) -> Handl
                 tracing::error!("update_global_settings: write_settings failed: {e}");
             }
         });
+        // write_settings only carries over the firmware's settings. The
+        // output fields have to reach the file too: load_settings falls back
+        // on them for updates that leave them out.
+        let output_changed = output.audio_output.is_some()
+            || output.alsa_device.is_some()
+            || output.alsa_exclusive.is_some()
+            || output.alsa_bit_perfect.is_some()
+            || output.alsa_dop.is_some();
+        if shuffle_mode.is_some() || playlists_dir.is_some() || output_changed {
+            let mut settings = rockbox_settings::read_settings().unwrap_or_default();
+            if shuffle_mode.is_some() {
+                settings.shuffle_mode = shuffle_mode;
+            }
+            if playlists_dir.is_some() {
+                settings.playlists_dir = playlists_dir;
+            }
+            if output.audio_output.is_some() {
+                settings.audio_output = output.audio_output;
+            }
+            if output.alsa_device.is_some() {
+                settings.alsa_device = output.alsa_device;
+            }
+            if output.alsa_exclusive.is_some() {
+                settings.alsa_exclusive = output.alsa_exclusive;
+            }
+            if output.alsa_bit_perfect.is_some() {
+                settings.alsa_bit_perfect = output.alsa_bit_perfect;
+            }
+            if output.alsa_dop.is_some() {
+                settings.alsa_dop = output.alsa_dop;
+            }
+            if let Err(e) = rockbox_settings::save_settings_to_file(&settings) {
+                tracing::error!("update_global_settings: saving settings failed: {e}");
+            }
+        }
+        if let Err(e) = rockbox_settings::update_stream_transitions(&transitions) {
+            tracing::error!("update_global_settings: stream transitions failed: {e}");
+        }
     })
     .await
     .map_err(ErrorInternalServerError)?;
@@ -90,7 +90,7 @@ pub async fn get_smart_playlist(
         .map_err(ErrorInternalServerError)?
     {
         Some(p) => {
-            let track_count = resolver::count_tracks(&state.playlist_store, &state.pool, &p.rules)
+            let track_count = resolver::count_tracks(&state.pool, &p.rules)
                 .await
                 .map_err(ErrorInternalServerError)?;
             Ok(HttpResponse::Ok().json(SmartPlaylistResponse::new(p, track_count)))
@@ -118,7 +118,7 @@ pub async fn create_smart_playlist(
         )
         .await
         .map_err(ErrorInternalServerError)?;
-    let track_count = resolver::count_tracks(&state.playlist_store, &state.pool, &playlist.rules)
+    let track_count = resolver::count_tracks(&state.pool, &playlist.rules)
         .await
         .map_err(ErrorInternalServerError)?;
     Ok(HttpResponse::Created().json(SmartPlaylistResponse::new(playlist, track_count)))
@@ -173,7 +173,7 @@ async fn resolve_smart_playlist_tracks(
         Some(p) => p.rules,
         None => return Ok(None),
     };
-    let tracks = resolver::resolve_tracks(&state.playlist_store, &state.pool, &criteria).await?;
+    let tracks = resolver::resolve_tracks(&state.pool, &criteria).await?;
     Ok(Some((criteria, tracks)))
 }
 
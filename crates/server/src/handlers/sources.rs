@@ -0,0 +1,105 @@
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_sources::{NewSource, SourceStore, SourceUpdate};
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::http::AppState;
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct SyncQuery {
+    /// Wait for the sync to finish instead of running it in the background.
+    #[serde(default)]
+    wait: bool,
+}
+
+pub async fn get_sources(state: web::Data<AppState>) -> HandlerResult {
+    let sources = SourceStore::new(state.pool.clone())
+        .list()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(sources))
+}
+
+pub async fn get_source(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    match SourceStore::new(state.pool.clone())
+        .get(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(source) => Ok(HttpResponse::Ok().json(source)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn create_source(
+    state: web::Data<AppState>,
+    body: web::Json<NewSource>,
+) -> HandlerResult {
+    match SourceStore::new(state.pool.clone())
+        .create(body.into_inner())
+        .await
+    {
+        Ok(source) => Ok(HttpResponse::Created().json(source)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn update_source(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<SourceUpdate>,
+) -> HandlerResult {
+    match SourceStore::new(state.pool.clone())
+        .update(&path.into_inner(), body.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(source) => Ok(HttpResponse::Ok().json(source)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn delete_source(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let deleted = SourceStore::new(state.pool.clone())
+        .delete(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+/// Sync a source now. Runs in the background unless `?wait=true`; the
+/// outcome then shows in the source's `status`.
+pub async fn sync_source(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<SyncQuery>,
+) -> HandlerResult {
+    let id = path.into_inner();
+    let store = SourceStore::new(state.pool.clone());
+    if store
+        .get(&id)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .is_none()
+    {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    if !query.wait {
+        tokio::spawn(async move {
+            if let Err(e) = store.sync(&id).await {
+                warn!("sources: syncing {} failed: {}", id, e);
+            }
+        });
+        return Ok(HttpResponse::Accepted().finish());
+    }
+    match store.sync(&id).await {
+        Ok(summary) => Ok(HttpResponse::Ok().json(summary)),
+        Err(e) => Ok(HttpResponse::BadGateway().body(e.to_string())),
+    }
+}
@@ -45,9 +45,13 @@ pub async fn scan_library(
 
     let path = query.path.clone().unwrap_or_else(|| music_library.clone());
 
-    scan_audio_files(state.pool.clone(), path.clone().into())
+    let scanned = scan_audio_files(state.pool.clone(), path.clone().into())
         .await
         .map_err(ErrorInternalServerError)?;
+    rockbox_webhooks::emit(rockbox_webhooks::Event::LibraryScanFinished {
+        path: path.clone(),
+        tracks: scanned.len(),
+    });
 
     let rebuild_index = query
         .rebuild_index
@@ -0,0 +1,47 @@
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_auth::Scope;
+use serde::Deserialize;
+
+use crate::http::AppState;
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct CreateTokenRequest {
+    name: String,
+    scope: Scope,
+}
+
+pub async fn get_tokens(state: web::Data<AppState>) -> HandlerResult {
+    let tokens = state.auth.list().await.map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+pub async fn create_token(
+    state: web::Data<AppState>,
+    body: web::Json<CreateTokenRequest>,
+) -> HandlerResult {
+    let req = body.into_inner();
+    if req.name.trim().is_empty() {
+        return Ok(HttpResponse::BadRequest().body("name must not be empty"));
+    }
+    let token = state
+        .auth
+        .create(req.name.trim(), req.scope)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Created().json(token))
+}
+
+pub async fn delete_token(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let revoked = state
+        .auth
+        .revoke(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if revoked > 0 {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
@@ -1,5 +1,5 @@
 use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
-use rockbox_library::{audio_scan, repo};
+use rockbox_library::{audio_scan, lyrics, repo};
 use serde::Deserialize;
 
 use crate::http::AppState;
@@ -20,6 +20,35 @@ pub async fn get_track(state: web::Data<AppState>, path: web::Path<String>) -> H
     Ok(HttpResponse::Ok().json(track))
 }
 
+pub async fn get_track_lyrics(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    let track = repo::track::find(state.pool.clone(), &path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let lyrics = match track {
+        Some(track) => lyrics::get_lyrics(state.pool.clone(), &track)
+            .await
+            .map_err(ErrorInternalServerError)?,
+        None => None,
+    };
+    match lyrics {
+        Some(lyrics) => Ok(HttpResponse::Ok().json(lyrics)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn get_track_chapters(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HandlerResult {
+    let chapters = repo::chapter::find_by_track(state.pool.clone(), &path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(chapters))
+}
+
 #[derive(Deserialize)]
 pub struct StreamMetadataBody {
     url: String,
@@ -0,0 +1,131 @@
+use actix_web::{error::ErrorInternalServerError, web, HttpResponse};
+use rockbox_webhooks::{WebhookUpdate, EVENT_NAMES};
+use serde::Deserialize;
+
+use crate::http::AppState;
+
+type HandlerResult = actix_web::Result<HttpResponse>;
+
+#[derive(Deserialize)]
+pub struct CreateWebhookRequest {
+    url: String,
+    secret: Option<String>,
+    /// Event names to deliver; omitted or empty means all events.
+    #[serde(default)]
+    events: Vec<String>,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct DeliveriesQuery {
+    limit: Option<i64>,
+}
+
+pub async fn get_webhooks(state: web::Data<AppState>) -> HandlerResult {
+    let webhooks = state
+        .webhook_store
+        .list()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+pub async fn get_webhook_events() -> HandlerResult {
+    Ok(HttpResponse::Ok().json(EVENT_NAMES))
+}
+
+pub async fn create_webhook(
+    state: web::Data<AppState>,
+    body: web::Json<CreateWebhookRequest>,
+) -> HandlerResult {
+    let req = body.into_inner();
+    match state
+        .webhook_store
+        .create(&req.url, req.secret.as_deref(), &req.events, req.enabled)
+        .await
+    {
+        Ok(webhook) => Ok(HttpResponse::Created().json(webhook)),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn get_webhook(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    match state
+        .webhook_store
+        .get(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(webhook) => Ok(HttpResponse::Ok().json(webhook)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn update_webhook(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<WebhookUpdate>,
+) -> HandlerResult {
+    match state
+        .webhook_store
+        .update(&path.into_inner(), body.into_inner())
+        .await
+    {
+        Ok(Some(webhook)) => Ok(HttpResponse::Ok().json(webhook)),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
+}
+
+pub async fn delete_webhook(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    let deleted = state
+        .webhook_store
+        .delete(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+pub async fn get_webhook_deliveries(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<DeliveriesQuery>,
+) -> HandlerResult {
+    let id = path.into_inner();
+    if state
+        .webhook_store
+        .get(&id)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .is_none()
+    {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let deliveries = state
+        .webhook_store
+        .deliveries(&id, query.limit.unwrap_or(50))
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+pub async fn test_webhook(state: web::Data<AppState>, path: web::Path<String>) -> HandlerResult {
+    match state
+        .webhook_store
+        .test(&path.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?
+    {
+        Some(delivery) => Ok(HttpResponse::Ok().json(delivery)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
@@ -1,8 +1,11 @@
+use rockbox_auth::Auth;
 use rockbox_library::entity::track::Track;
 use rockbox_playlists::PlaylistStore;
+use rockbox_podcasts::PodcastStore;
 use rockbox_sys::types::{mp3_entry::Mp3Entry, tree::Entry};
 use rockbox_traits::Player;
 use rockbox_types::device::Device;
+use rockbox_webhooks::WebhookStore;
 use sqlx::Sqlite;
 use std::{
     collections::HashMap,
@@ -20,4 +23,7 @@ pub struct AppState {
     pub player: Arc<Mutex<Option<Box<dyn Player + Send>>>>,
     pub kv: Arc<Mutex<KV<Track>>>,
     pub playlist_store: PlaylistStore,
+    pub podcast_store: PodcastStore,
+    pub webhook_store: WebhookStore,
+    pub auth: Auth,
 }
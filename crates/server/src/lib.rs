@@ -8,6 +8,7 @@ use rockbox_graphql::{
 };
 use rockbox_library::repo;
 use rockbox_mpd::MpdServer;
+use rockbox_podcasts::{PodcastChannel, PodcastEpisode};
 use rockbox_sys::{self as rb, types::mp3_entry::Mp3Entry};
 use sqlx::{Pool, Sqlite};
 use std::{
@@ -24,12 +25,16 @@ use std::{
 // event on the next tick even when index and amount haven't changed (e.g. shuffle).
 pub(crate) static PLAYLIST_DIRTY: AtomicBool = AtomicBool::new(false);
 
+pub mod auth;
 pub mod cache;
+pub mod dsp_profiles;
 pub mod handlers;
 pub mod http;
 pub mod kv;
+pub mod mixer;
 pub mod player_events;
 pub mod scan;
+pub mod scheduler;
 
 // Force netstream FFI symbols into the staticlib output.
 // These functions are called from C code (streamfd.c) but not from any Rust
@@ -50,6 +55,16 @@ mod _netstream {
     static FN_CLOSE: extern "C" fn(i32) = rb_net_close;
 }
 
+// Keep the pcm_alsa_* C ABI, called from pcm-alsa.c, in librockbox_server.a.
+#[cfg(feature = "alsa-sink")]
+#[allow(unused_imports)]
+use rockbox_alsa_sink::_link_alsa_sink as _;
+
+// Same for the pcm_cpal_* C ABI, called from pcm-cpal.c.
+#[cfg(feature = "cpal-sink")]
+#[allow(unused_imports)]
+use rockbox_cpal_sink::_link_cpal_sink as _;
+
 pub const AUDIO_EXTENSIONS: [&str; 17] = [
     "mp3", "ogg", "flac", "m4a", "aac", "mp4", "alac", "wav", "wv", "mpc", "aiff", "ac3", "opus",
     "spx", "sid", "ape", "wma",
@@ -233,6 +248,38 @@ async fn run_http_server() -> Result<(), Error> {
     let playlist_store = rockbox_playlists::PlaylistStore::new(pool.clone());
     playlist_store.seed().await?;
 
+    rockbox_playlists::start_live_task(playlist_store.clone());
+    forward_smart_playlist_changes();
+
+    let podcast_store = rockbox_podcasts::PodcastStore::new(pool.clone());
+    rockbox_podcasts::start_refresh_task(podcast_store.clone());
+
+    rockbox_similarity::start_analysis_task(rockbox_similarity::SimilarityStore::new(pool.clone()));
+    rockbox_health::start_health_task(rockbox_health::HealthStore::new(pool.clone()));
+    rbnetstream::set_authenticator(rockbox_sources::credentials::authenticate);
+    rockbox_sources::start_sync_task(rockbox_sources::SourceStore::new(pool.clone()));
+    {
+        let pool = pool.clone();
+        rockbox_scheduler::start_scheduler(
+            rockbox_scheduler::SchedulerStore::new(pool.clone()),
+            move |schedule| scheduler::run(pool.clone(), schedule),
+        );
+    }
+
+    if let Some(dir) = rockbox_settings::read_settings()
+        .ok()
+        .and_then(|s| s.playlists_dir)
+        .filter(|d| !d.is_empty())
+    {
+        rockbox_playlists::start_sync_task(playlist_store.clone(), dir.into());
+    }
+
+    let webhook_store = rockbox_webhooks::WebhookStore::new(pool.clone());
+    rockbox_webhooks::start_dispatcher(webhook_store.clone());
+    if let Some(config) = mqtt_config() {
+        rockbox_webhooks::mqtt::start(config);
+    }
+
     scan::scan_chromecast_devices(devices.clone());
     scan::scan_upnp_devices(devices.clone());
     scan::scan_airplay_devices(devices.clone());
@@ -240,6 +287,17 @@ async fn run_http_server() -> Result<(), Error> {
     scan::scan_squeezelite_clients(devices.clone());
     player_events::listen_for_playback_changes(player.clone(), pool.clone());
 
+    let settings = rockbox_settings::read_settings().unwrap_or_default();
+    let auth = rockbox_auth::Auth::new(
+        pool.clone(),
+        rockbox_auth::AuthConfig::new(settings.auth_enabled, settings.auth_trust_localhost),
+    );
+    // Lets `rockbox scan`, `rockbox open` and the scripting bridge of the
+    // same user through once tokens are enforced.
+    if let Err(e) = rockbox_auth::write_local_token() {
+        warn!("could not write the local API token: {}", e);
+    }
+
     let state = web::Data::new(AppState {
         pool,
         fs_cache,
@@ -249,12 +307,16 @@ async fn run_http_server() -> Result<(), Error> {
         player,
         kv,
         playlist_store,
+        podcast_store,
+        webhook_store,
+        auth,
     });
 
-    HttpServer::new(move || {
+    let mut server = HttpServer::new(move || {
         let cors = Cors::permissive();
         App::new()
             .app_data(state.clone())
+            .wrap(actix_web::middleware::from_fn(auth::require_scope))
             .wrap(cors)
             // Albums — fixed routes before parametric
             .route(
@@ -267,6 +329,18 @@ async fn run_http_server() -> Result<(), Error> {
                 "/albums/{id}/tracks",
                 web::get().to(handlers::albums::get_album_tracks),
             )
+            .route(
+                "/albums/{id}/rating",
+                web::get().to(handlers::ratings::get_album_rating),
+            )
+            .route(
+                "/albums/{id}/rating",
+                web::put().to(handlers::ratings::save_album_rating),
+            )
+            .route(
+                "/albums/{id}/rating",
+                web::delete().to(handlers::ratings::delete_album_rating),
+            )
             // Artists — fixed routes before parametric
             .route(
                 "/artists/filter",
@@ -285,6 +359,18 @@ async fn run_http_server() -> Result<(), Error> {
                 "/artists/{id}/tracks",
                 web::get().to(handlers::artists::get_artist_tracks),
             )
+            .route(
+                "/artists/{id}/rating",
+                web::get().to(handlers::ratings::get_artist_rating),
+            )
+            .route(
+                "/artists/{id}/rating",
+                web::put().to(handlers::ratings::save_artist_rating),
+            )
+            .route(
+                "/artists/{id}/rating",
+                web::delete().to(handlers::ratings::delete_artist_rating),
+            )
             // Browse
             .route(
                 "/browse/tree-entries",
@@ -336,6 +422,19 @@ async fn run_http_server() -> Result<(), Error> {
                 "/player/previous",
                 web::put().to(handlers::player::previous),
             )
+            .route("/player/lyrics", web::get().to(handlers::player::lyrics))
+            .route(
+                "/player/chapters",
+                web::get().to(handlers::player::chapters),
+            )
+            .route(
+                "/player/next-chapter",
+                web::put().to(handlers::player::next_chapter),
+            )
+            .route(
+                "/player/previous-chapter",
+                web::put().to(handlers::player::previous_chapter),
+            )
             .route("/player/stop", web::put().to(handlers::player::stop))
             .route(
                 "/player/file-position",
@@ -349,6 +448,30 @@ async fn run_http_server() -> Result<(), Error> {
                 "/player/volume",
                 web::put().to(handlers::player::adjust_volume),
             )
+            .route(
+                "/player/output",
+                web::get().to(handlers::player::get_output),
+            )
+            .route(
+                "/player/auto-queue",
+                web::get().to(handlers::player::get_auto_queue),
+            )
+            .route(
+                "/player/auto-queue",
+                web::put().to(handlers::player::set_auto_queue),
+            )
+            .route(
+                "/player/sleep-timer",
+                web::get().to(handlers::schedules::get_sleep_timer),
+            )
+            .route(
+                "/player/sleep-timer",
+                web::put().to(handlers::schedules::set_sleep_timer),
+            )
+            .route(
+                "/player/sleep-timer",
+                web::delete().to(handlers::schedules::cancel_sleep_timer),
+            )
             .route("/player/eq", web::put().to(handlers::dsp::set_eq))
             .route(
                 "/player/crossfeed",
@@ -360,6 +483,49 @@ async fn run_http_server() -> Result<(), Error> {
             )
             .route("/player/afr", web::put().to(handlers::dsp::set_afr))
             .route("/player/pbe", web::put().to(handlers::dsp::set_pbe))
+            .route("/player/mixer", web::get().to(handlers::dsp::get_mixer))
+            .route("/player/mixer", web::put().to(handlers::dsp::set_mixer))
+            // DSP profiles — fixed routes before parametric ones
+            .route(
+                "/dsp/profiles",
+                web::get().to(handlers::dsp_profiles::get_profiles),
+            )
+            .route(
+                "/dsp/profiles",
+                web::post().to(handlers::dsp_profiles::create_profile),
+            )
+            .route(
+                "/dsp/profiles/import",
+                web::post().to(handlers::dsp_profiles::import_autoeq),
+            )
+            .route(
+                "/dsp/profiles/{id}",
+                web::get().to(handlers::dsp_profiles::get_profile),
+            )
+            .route(
+                "/dsp/profiles/{id}",
+                web::put().to(handlers::dsp_profiles::update_profile),
+            )
+            .route(
+                "/dsp/profiles/{id}",
+                web::delete().to(handlers::dsp_profiles::delete_profile),
+            )
+            .route(
+                "/dsp/profiles/{id}/apply",
+                web::post().to(handlers::dsp_profiles::apply_profile),
+            )
+            .route(
+                "/dsp/outputs",
+                web::get().to(handlers::dsp_profiles::get_outputs),
+            )
+            .route(
+                "/dsp/outputs/{output}",
+                web::put().to(handlers::dsp_profiles::set_output),
+            )
+            .route(
+                "/dsp/outputs/{output}",
+                web::delete().to(handlers::dsp_profiles::clear_output),
+            )
             // Playlists — fixed routes before parametric ones
             .route(
                 "/playlists/start",
@@ -422,6 +588,14 @@ async fn run_http_server() -> Result<(), Error> {
                 "/saved-playlists",
                 web::post().to(handlers::saved_playlists::create_saved_playlist),
             )
+            .route(
+                "/saved-playlists/import",
+                web::post().to(handlers::saved_playlists::import_saved_playlist),
+            )
+            .route(
+                "/saved-playlists/{id}/export",
+                web::get().to(handlers::saved_playlists::export_saved_playlist),
+            )
             .route(
                 "/saved-playlists/{id}/tracks",
                 web::get().to(handlers::saved_playlists::get_saved_playlist_tracks),
@@ -496,6 +670,130 @@ async fn run_http_server() -> Result<(), Error> {
                 "/track-stats/{id}",
                 web::get().to(handlers::smart_playlists::get_track_stats),
             )
+            // Internet radio — fixed routes before parametric
+            .route(
+                "/radio/stations",
+                web::get().to(handlers::radio::get_radio_stations),
+            )
+            .route(
+                "/radio/stations",
+                web::post().to(handlers::radio::create_radio_station),
+            )
+            .route(
+                "/radio/stations/import",
+                web::post().to(handlers::radio::import_radio_stations),
+            )
+            .route(
+                "/radio/stations/{id}/play",
+                web::put().to(handlers::radio::play_radio_station),
+            )
+            .route(
+                "/radio/stations/{id}",
+                web::get().to(handlers::radio::get_radio_station),
+            )
+            .route(
+                "/radio/stations/{id}",
+                web::put().to(handlers::radio::update_radio_station),
+            )
+            .route(
+                "/radio/stations/{id}",
+                web::delete().to(handlers::radio::delete_radio_station),
+            )
+            // Podcasts — fixed routes before parametric
+            .route("/podcasts", web::get().to(handlers::podcasts::get_podcasts))
+            .route(
+                "/podcasts",
+                web::post().to(handlers::podcasts::subscribe_podcast),
+            )
+            .route(
+                "/podcasts/refresh",
+                web::post().to(handlers::podcasts::refresh_podcasts),
+            )
+            .route(
+                "/podcasts/episodes/newest",
+                web::get().to(handlers::podcasts::get_newest_episodes),
+            )
+            .route(
+                "/podcasts/{id}",
+                web::get().to(handlers::podcasts::get_podcast),
+            )
+            .route(
+                "/podcasts/{id}",
+                web::put().to(handlers::podcasts::update_podcast),
+            )
+            .route(
+                "/podcasts/{id}",
+                web::delete().to(handlers::podcasts::delete_podcast),
+            )
+            .route(
+                "/podcasts/{id}/refresh",
+                web::post().to(handlers::podcasts::refresh_podcast),
+            )
+            .route(
+                "/podcasts/{id}/episodes",
+                web::get().to(handlers::podcasts::get_podcast_episodes),
+            )
+            .route(
+                "/podcast-episodes/{id}/play",
+                web::put().to(handlers::podcasts::play_episode),
+            )
+            .route(
+                "/podcast-episodes/{id}/download",
+                web::post().to(handlers::podcasts::download_episode),
+            )
+            .route(
+                "/podcast-episodes/{id}/download",
+                web::delete().to(handlers::podcasts::delete_episode_download),
+            )
+            .route(
+                "/podcast-episodes/{id}/position",
+                web::put().to(handlers::podcasts::set_episode_position),
+            )
+            .route(
+                "/podcast-episodes/{id}/played",
+                web::put().to(handlers::podcasts::set_episode_played),
+            )
+            .route(
+                "/podcast-episodes/{id}",
+                web::get().to(handlers::podcasts::get_episode),
+            )
+            // Webhooks — fixed routes before parametric
+            .route("/webhooks", web::get().to(handlers::webhooks::get_webhooks))
+            .route(
+                "/webhooks",
+                web::post().to(handlers::webhooks::create_webhook),
+            )
+            .route(
+                "/webhooks/events",
+                web::get().to(handlers::webhooks::get_webhook_events),
+            )
+            .route(
+                "/webhooks/{id}",
+                web::get().to(handlers::webhooks::get_webhook),
+            )
+            .route(
+                "/webhooks/{id}",
+                web::put().to(handlers::webhooks::update_webhook),
+            )
+            .route(
+                "/webhooks/{id}",
+                web::delete().to(handlers::webhooks::delete_webhook),
+            )
+            .route(
+                "/webhooks/{id}/deliveries",
+                web::get().to(handlers::webhooks::get_webhook_deliveries),
+            )
+            .route(
+                "/webhooks/{id}/test",
+                web::post().to(handlers::webhooks::test_webhook),
+            )
+            // API tokens
+            .route("/tokens", web::get().to(handlers::tokens::get_tokens))
+            .route("/tokens", web::post().to(handlers::tokens::create_token))
+            .route(
+                "/tokens/{id}",
+                web::delete().to(handlers::tokens::delete_token),
+            )
             // Tracks — fixed route before parametric
             .route(
                 "/tracks/stream-metadata",
@@ -503,6 +801,60 @@ async fn run_http_server() -> Result<(), Error> {
             )
             .route("/tracks", web::get().to(handlers::tracks::get_tracks))
             .route("/tracks/{id}", web::get().to(handlers::tracks::get_track))
+            .route(
+                "/tracks/{id}/lyrics",
+                web::get().to(handlers::tracks::get_track_lyrics),
+            )
+            .route(
+                "/tracks/{id}/chapters",
+                web::get().to(handlers::tracks::get_track_chapters),
+            )
+            .route(
+                "/tracks/{id}/rating",
+                web::get().to(handlers::ratings::get_track_rating),
+            )
+            .route(
+                "/tracks/{id}/rating",
+                web::put().to(handlers::ratings::save_track_rating),
+            )
+            .route(
+                "/tracks/{id}/rating",
+                web::delete().to(handlers::ratings::delete_track_rating),
+            )
+            .route(
+                "/tracks/{id}/hidden",
+                web::put().to(handlers::health::hide_track),
+            )
+            .route(
+                "/tracks/{id}/hidden",
+                web::delete().to(handlers::health::unhide_track),
+            )
+            .route("/ratings", web::get().to(handlers::ratings::get_ratings))
+            // Audiobooks — fixed route before parametric
+            .route(
+                "/audiobooks",
+                web::get().to(handlers::audiobooks::get_audiobooks),
+            )
+            .route(
+                "/audiobooks/mark",
+                web::put().to(handlers::audiobooks::mark_audiobooks),
+            )
+            .route(
+                "/audiobooks/{id}/bookmark",
+                web::get().to(handlers::audiobooks::get_bookmark),
+            )
+            .route(
+                "/audiobooks/{id}/bookmark",
+                web::put().to(handlers::audiobooks::save_bookmark),
+            )
+            .route(
+                "/audiobooks/{id}/bookmark",
+                web::delete().to(handlers::audiobooks::delete_bookmark),
+            )
+            .route(
+                "/audiobooks/{id}/play",
+                web::put().to(handlers::audiobooks::play_audiobook),
+            )
             // System
             .route(
                 "/version",
@@ -521,6 +873,60 @@ async fn run_http_server() -> Result<(), Error> {
                 "/scan-library",
                 web::put().to(handlers::system::scan_library),
             )
+            .route(
+                "/library/health",
+                web::get().to(handlers::health::get_health),
+            )
+            .route(
+                "/library/health/duplicates/hide",
+                web::post().to(handlers::health::hide_duplicates),
+            )
+            .route(
+                "/library/health/orphans/remove",
+                web::post().to(handlers::health::remove_orphans),
+            )
+            .route("/sources", web::get().to(handlers::sources::get_sources))
+            .route("/sources", web::post().to(handlers::sources::create_source))
+            .route(
+                "/sources/{id}",
+                web::get().to(handlers::sources::get_source),
+            )
+            .route(
+                "/sources/{id}",
+                web::put().to(handlers::sources::update_source),
+            )
+            .route(
+                "/sources/{id}",
+                web::delete().to(handlers::sources::delete_source),
+            )
+            .route(
+                "/sources/{id}/sync",
+                web::post().to(handlers::sources::sync_source),
+            )
+            .route(
+                "/schedules",
+                web::get().to(handlers::schedules::get_schedules),
+            )
+            .route(
+                "/schedules",
+                web::post().to(handlers::schedules::create_schedule),
+            )
+            .route(
+                "/schedules/{id}",
+                web::get().to(handlers::schedules::get_schedule),
+            )
+            .route(
+                "/schedules/{id}",
+                web::put().to(handlers::schedules::update_schedule),
+            )
+            .route(
+                "/schedules/{id}",
+                web::delete().to(handlers::schedules::delete_schedule),
+            )
+            .route(
+                "/schedules/{id}/run",
+                web::post().to(handlers::schedules::run_schedule),
+            )
             .route("/search", web::get().to(handlers::search::search))
             // Devices
             .route("/devices", web::get().to(handlers::devices::get_devices))
@@ -543,13 +949,54 @@ async fn run_http_server() -> Result<(), Error> {
             .route("/openapi.json", web::get().to(handlers::docs::get_openapi))
             .configure(bluetooth_routes)
     })
-    .bind(addr)?
-    .run()
-    .await?;
+    .bind(rockbox_tls::plain_addr(&addr))?;
+    if let Some(tls) = rockbox_tls::tls() {
+        server = server.bind_rustls_0_23(tls.addr(&addr)?, tls.server_config())?;
+    }
+    server.run().await?;
 
     Ok(())
 }
 
+/// MQTT publishing is enabled by setting `mqtt_host` in settings.toml.
+/// Re-publish smart playlist membership changes on the broker, where the
+/// GraphQL subscription and the gRPC stream pick them up.
+fn forward_smart_playlist_changes() {
+    let mut deltas = rockbox_playlists::live::subscribe();
+    tokio::spawn(async move {
+        loop {
+            match deltas.recv().await {
+                Ok(delta) => {
+                    SimpleBroker::publish(objects::smart_playlist::SmartPlaylistChange::from(delta))
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                    warn!("smart playlists: dropped {} membership changes", n)
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn mqtt_config() -> Option<rockbox_webhooks::mqtt::MqttConfig> {
+    let settings = rockbox_settings::read_settings().ok()?;
+    let host = settings.mqtt_host.filter(|h| !h.is_empty())?;
+    Some(rockbox_webhooks::mqtt::MqttConfig {
+        host,
+        port: settings.mqtt_port.unwrap_or(1883),
+        client_id: settings
+            .mqtt_client_id
+            .unwrap_or_else(|| "rockboxd".to_string()),
+        username: settings.mqtt_username.filter(|u| !u.is_empty()),
+        password: settings.mqtt_password,
+        topic: settings
+            .mqtt_topic
+            .map(|t| t.trim_end_matches('/').to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| "rockbox".to_string()),
+    })
+}
+
 fn bluetooth_routes(_cfg: &mut actix_web::web::ServiceConfig) {
     #[cfg(target_os = "linux")]
     {
@@ -697,6 +1144,36 @@ pub extern "C" fn start_broker() {
     let mut last_stats_elapsed: u64 = 0;
     let mut last_stats_length: u64 = 0;
 
+    // Podcast episode playing from the current path (cached per path) and
+    // the elapsed time last written back as its resume position.
+    let podcast_store = rockbox_podcasts::PodcastStore::new(pool.clone());
+    let mut podcast_lookup: Option<(String, Option<(PodcastEpisode, Option<PodcastChannel>)>)> =
+        None;
+    let mut podcast_saved_elapsed: u64 = 0;
+
+    // Audiobook track last bookmarked and the elapsed time written with it.
+    let mut book_saved_track: Option<String> = None;
+    let mut book_saved_elapsed: u64 = 0;
+
+    // Lyrics of the track playing (loaded once per track) and the index of
+    // the synced line last published.
+    let mut lyrics_lookup: Option<(String, Option<rockbox_library::lyrics::Lyrics>)> = None;
+    let mut lyrics_line: Option<usize> = None;
+
+    // ReplayGain / R128 tags of the track playing, read once per path.
+    let mut replaygain_lookup: Option<(String, Option<rockbox_mixer::TrackGain>)> = None;
+
+    // Last track and playback status seen, to emit webhook / MQTT events
+    // when they change.
+    let mut event_track: Option<Track> = None;
+    let mut last_status: i32 = 0;
+
+    // Title / artist last handed to the CMAF sink's timed metadata.
+    let mut stream_metadata: Option<(String, String)> = None;
+
+    // Queue length at the last auto-queue top-up attempt.
+    let mut autoqueue_at: i32 = i32::MIN;
+
     // Username for the getNowPlaying Subsonic endpoint — read once at startup.
     let subsonic_username = rockbox_settings::read_settings()
         .ok()
@@ -725,6 +1202,8 @@ pub extern "C" fn start_broker() {
         }
 
         let playback_status: AudioStatus = rb::playback::status().into();
+        emit_status_change(last_status, playback_status.status, event_track.as_ref());
+        last_status = playback_status.status;
         SimpleBroker::publish(playback_status);
 
         match rb::playback::current_track() {
@@ -745,6 +1224,8 @@ pub extern "C" fn start_broker() {
                     current_track.path.clone()
                 };
 
+                mixer::follow(Some((&current_track, &lookup_path)), &mut replaygain_lookup);
+
                 let hash = format!("{:x}", md5::compute(lookup_path.as_bytes()));
                 let db_metadata = rt
                     .block_on(repo::track::find_by_md5(pool.clone(), &hash))
@@ -759,7 +1240,128 @@ pub extern "C" fn start_broker() {
                     track.path = lookup_path.clone();
                 }
 
+                // Internet radio: the station stands in for the album and the
+                // live ICY StreamTitle names the song currently on air.
+                if db_metadata.is_none() && lookup_path.starts_with("http") {
+                    if let Some(station) = rt
+                        .block_on(repo::radio_station::find_by_stream_url(
+                            pool.clone(),
+                            &lookup_path,
+                        ))
+                        .ok()
+                        .flatten()
+                    {
+                        track.album = station.name;
+                        track.album_art = station.favicon;
+                    }
+                    if let Some(icy) = rbnetstream::icy_metadata(&lookup_path) {
+                        match icy.artist_and_title() {
+                            Some((artist, title)) => {
+                                track.artist = artist;
+                                track.title = title;
+                            }
+                            None => {
+                                if let Some(title) = icy.title.clone() {
+                                    track.title = title;
+                                }
+                            }
+                        }
+                        if track.album.is_empty() {
+                            track.album = icy.name.unwrap_or_default();
+                        }
+                    }
+                }
+
+                // Podcast episodes: the channel stands in for artist and
+                // album, and the listening position is saved as it plays.
+                if db_metadata.is_none() {
+                    if podcast_lookup.as_ref().map(|(p, _)| p) != Some(&lookup_path) {
+                        let episode = rt
+                            .block_on(podcast_store.find_episode_by_path(&lookup_path))
+                            .ok()
+                            .flatten()
+                            .map(|episode| {
+                                let channel = rt
+                                    .block_on(podcast_store.get_channel(&episode.channel_id))
+                                    .ok()
+                                    .flatten();
+                                (episode, channel)
+                            });
+                        podcast_lookup = Some((lookup_path.clone(), episode));
+                        podcast_saved_elapsed = 0;
+                    }
+                    if let Some((_, Some((episode, channel)))) = podcast_lookup.as_mut() {
+                        track.title = episode.title.clone();
+                        if let Some(channel) = channel {
+                            track.artist = channel
+                                .author
+                                .clone()
+                                .unwrap_or_else(|| channel.title.clone());
+                            track.album = channel.title.clone();
+                            track.album_art = channel.image_url.clone();
+                        }
+                        if !episode.played {
+                            if track.length > 0 && track.elapsed * 10 >= track.length * 9 {
+                                let _ = rt.block_on(podcast_store.set_played(&episode.id, true));
+                                episode.played = true;
+                            } else if track.elapsed.abs_diff(podcast_saved_elapsed) >= 5_000 {
+                                let _ = rt.block_on(
+                                    podcast_store.set_position(&episode.id, track.elapsed as i64),
+                                );
+                                podcast_saved_elapsed = track.elapsed;
+                            }
+                        }
+                    }
+                }
+
                 if let Some(metadata) = db_metadata {
+                    // Audiobooks: remember where listening stopped in the
+                    // book, so it can be resumed from any client.
+                    if metadata.is_book() {
+                        let moved = book_saved_track.as_deref() != Some(metadata.id.as_str());
+                        if moved || track.elapsed.abs_diff(book_saved_elapsed) >= 5_000 {
+                            let _ = rt.block_on(repo::bookmark::save(
+                                pool.clone(),
+                                &metadata.album_id,
+                                &metadata.id,
+                                track.elapsed.min(u32::MAX as u64) as u32,
+                            ));
+                            book_saved_track = Some(metadata.id.clone());
+                            book_saved_elapsed = track.elapsed;
+                        }
+                    }
+
+                    // Synced lyrics: publish the line being sung as it
+                    // changes, for karaoke-style display.
+                    if lyrics_lookup.as_ref().map(|(id, _)| id) != Some(&metadata.id) {
+                        let lyrics = rt
+                            .block_on(rockbox_library::lyrics::get_lyrics(pool.clone(), &metadata))
+                            .ok()
+                            .flatten()
+                            .filter(|l| l.synced);
+                        lyrics_lookup = Some((metadata.id.clone(), lyrics));
+                        lyrics_line = None;
+                    }
+                    if let Some((_, Some(lyrics))) = lyrics_lookup.as_ref() {
+                        let line = rockbox_library::lyrics::current_line(lyrics, track.elapsed);
+                        if line != lyrics_line {
+                            if let Some(index) = line {
+                                let current = &lyrics.lines[index];
+                                SimpleBroker::publish(objects::lyrics::CurrentLyricLine {
+                                    track_id: metadata.id.clone(),
+                                    index,
+                                    start_ms: current.start_ms.unwrap_or_default(),
+                                    next_start_ms: lyrics
+                                        .lines
+                                        .get(index + 1)
+                                        .and_then(|l| l.start_ms),
+                                    text: current.text.clone(),
+                                });
+                            }
+                            lyrics_line = line;
+                        }
+                    }
+
                     // When the URL-keyed record has no album_art (it was saved
                     // from the HTTP stream which has no embedded art), fall back
                     // to the local track identified by the UUID in the URL path.
@@ -792,6 +1394,8 @@ pub extern "C" fn start_broker() {
                     if track.album_artist.is_empty() {
                         track.album_artist = metadata.album_artist.clone();
                     }
+                    emit_track_change(&mut event_track, Some(&track));
+                    follow_stream_metadata(&mut stream_metadata, Some(&track));
                     SimpleBroker::publish(track.clone());
                     rockbox_navidrome::server::set_now_playing(Some(
                         rockbox_navidrome::server::NowPlayingInfo {
@@ -891,14 +1495,50 @@ pub extern "C" fn start_broker() {
                             username: subsonic_username.clone(),
                         },
                     ));
+                    emit_track_change(&mut event_track, Some(&track));
+                    follow_stream_metadata(&mut stream_metadata, Some(&track));
                     SimpleBroker::publish(track);
                 }
             }
             None => {
                 current_scrobble_track = None; // reset on no track
+                mixer::follow(None, &mut replaygain_lookup);
+                emit_track_change(&mut event_track, None);
+                follow_stream_metadata(&mut stream_metadata, None);
             }
         };
 
+        // Endless radio: top the queue up before it runs out. Keyed on the
+        // queue length so an empty pick is not retried every tick.
+        let amount = rb::playlist::amount();
+        let remaining = amount - rb::playlist::index() - 1;
+        if amount > 0 && remaining <= rockbox_autoqueue::LOW_WATER && autoqueue_at != amount {
+            autoqueue_at = amount;
+            let config = rockbox_autoqueue::config();
+            if config.enabled {
+                let queued: Vec<String> = (0..amount)
+                    .map(|i| rb::playlist::get_track_info(i).filename)
+                    .collect();
+                match rt.block_on(rockbox_autoqueue::next_tracks(
+                    &pool,
+                    &config,
+                    &queued,
+                    rockbox_autoqueue::BATCH,
+                )) {
+                    Ok(tracks) if !tracks.is_empty() => {
+                        let paths: Vec<&str> = tracks.iter().map(|t| t.path.as_str()).collect();
+                        rb::playlist::insert_tracks(
+                            paths.clone(),
+                            rb::PLAYLIST_INSERT_LAST,
+                            paths.len() as i32,
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("auto-queue: {}", e),
+                }
+            }
+        }
+
         // Detect what changed.  Consuming DIRTY here covers mutations (shuffle,
         // insert, remove) that leave amount/index unchanged.
         let amount = rb::playlist::amount();
@@ -914,9 +1554,26 @@ pub extern "C" fn start_broker() {
             continue;
         }
 
+        let first_tick = last_playlist_amount == i32::MIN;
         last_playlist_index = current_index;
         last_playlist_amount = amount;
 
+        // Warm up the next track when it is remote so the join into it has
+        // data to work with.
+        if current_index >= 0 && current_index + 1 < amount {
+            let next = rb::playlist::get_track_info(current_index + 1).filename;
+            if next.starts_with("http://") || next.starts_with("https://") {
+                rbnetstream::prefetch(&next);
+            }
+        }
+
+        if content_changed && !first_tick {
+            rockbox_webhooks::emit(rockbox_webhooks::Event::QueueChanged {
+                amount,
+                index: current_index,
+            });
+        }
+
         let entries: Vec<Mp3Entry> = if content_changed {
             // Collect filenames while still holding the mutex (no I/O).
             let mut track_infos: Vec<(String, String)> = Vec::with_capacity(amount as usize);
@@ -1013,6 +1670,84 @@ pub extern "C" fn start_broker() {
     }
 }
 
+fn track_info(track: &Track) -> rockbox_webhooks::TrackInfo {
+    rockbox_webhooks::TrackInfo {
+        id: track.id.clone(),
+        title: track.title.clone(),
+        artist: track.artist.clone(),
+        album: track.album.clone(),
+        path: track.path.clone(),
+        length_ms: track.length,
+    }
+}
+
+/// Emit started / finished / skipped events when the track playing changes.
+/// A track counts as finished once 90 % of it has played.
+/// Hand the title / artist playing to the CMAF sink, which carries it in
+/// the stream as timed ID3. Radio titles change without a new path, so this
+/// compares the text rather than the track.
+fn follow_stream_metadata(last: &mut Option<(String, String)>, current: Option<&Track>) {
+    let current = current.map(|track| (track.title.clone(), track.artist.clone()));
+    if *last == current {
+        return;
+    }
+    let (title, artist) = current.clone().unwrap_or_default();
+    rb::sound::pcm::cmaf_set_metadata(&title, &artist);
+    *last = current;
+}
+
+fn emit_track_change(last: &mut Option<Track>, current: Option<&Track>) {
+    use rockbox_webhooks::{emit, Event};
+
+    let changed = match (last.as_ref(), current) {
+        (Some(previous), Some(current)) => previous.path != current.path,
+        (None, None) => false,
+        _ => true,
+    };
+    if changed {
+        if let Some(previous) = last.as_ref() {
+            let finished = previous.length > 0 && previous.elapsed * 10 >= previous.length * 9;
+            if finished {
+                emit(Event::TrackFinished {
+                    track: track_info(previous),
+                });
+            } else if current.is_some() {
+                // Stopping half-way is reported as playback_stopped instead.
+                emit(Event::TrackSkipped {
+                    track: track_info(previous),
+                    elapsed_ms: previous.elapsed,
+                });
+            }
+        }
+        if let Some(current) = current {
+            emit(Event::TrackStarted {
+                track: track_info(current),
+            });
+        }
+    }
+    *last = current.cloned();
+}
+
+/// `status` is `audio_status()`: 0 stopped, 1 playing, 3 playing but paused.
+fn emit_status_change(previous: i32, status: i32, track: Option<&Track>) {
+    use rockbox_webhooks::{emit, Event};
+
+    let elapsed_ms = track.map(|t| t.elapsed).unwrap_or_default();
+    let event = match (previous, status) {
+        (1, 3) => Event::PlaybackPaused {
+            track: track.map(track_info),
+            elapsed_ms,
+        },
+        (3, 1) => Event::PlaybackResumed {
+            track: track.map(track_info),
+            elapsed_ms,
+        },
+        (1 | 3, 0) => Event::PlaybackStopped,
+        _ => return,
+    };
+    emit(event);
+}
+
 async fn scrobble(track: Track, pool: Pool<Sqlite>) -> Result<(), Error> {
     let album_id = track.album_id.unwrap();
     let track = repo::track::find(pool.clone(), &track.id.unwrap()).await?;
@@ -0,0 +1,41 @@
+//! Tells `rockbox-mixer` the loudness tags of the track playing, so the
+//! sinks apply its ReplayGain / R128 gain.
+
+use rockbox_mixer::TrackGain;
+use rockbox_sys::{self as rb, types::mp3_entry::Mp3Entry};
+
+/// Loudness tags of `track`, read from `path` (its playlist filename).
+/// lofty also sees the R128 tags the firmware's parser skips; what the
+/// firmware parsed is the fallback for streams and files lofty can't read.
+pub fn track_gain(track: &Mp3Entry, path: &str) -> Option<TrackGain> {
+    if !path.starts_with("http") {
+        if let Ok(tags) = rockbox_library::replaygain::extract_replaygain(path) {
+            let gain = TrackGain::from_tags(tags.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+            if !gain.is_empty() {
+                return Some(gain);
+            }
+        }
+    }
+    let gain = TrackGain::from_firmware(
+        track.track_gain,
+        track.album_gain,
+        track.track_peak,
+        track.album_peak,
+    );
+    (!gain.is_empty()).then_some(gain)
+}
+
+/// Point the mixer at the track playing, or at none. `lookup` holds the
+/// tags of the path seen last, so each track's file is read once.
+pub fn follow(track: Option<(&Mp3Entry, &str)>, lookup: &mut Option<(String, Option<TrackGain>)>) {
+    match track {
+        Some((track, path)) => {
+            if lookup.as_ref().map(|(p, _)| p.as_str()) != Some(path) {
+                *lookup = Some((path.to_string(), track_gain(track, path)));
+            }
+        }
+        None => *lookup = None,
+    }
+    let shuffle = unsafe { rb::global_settings.playlist_shuffle };
+    rockbox_mixer::set_track(lookup.as_ref().and_then(|(_, gain)| *gain), shuffle);
+}
@@ -0,0 +1,188 @@
+//! Runs `rockbox-scheduler` schedules on the built-in player: alarms ramp
+//! the volume up from the minimum after starting playback, the sleep timer
+//! steps it down before a short `pcmbuf_fade` and a pause.
+
+use std::{sync::atomic::Ordering, time::Duration};
+
+use anyhow::{anyhow, Error};
+use rockbox_library::repo;
+use rockbox_playlists::PlaylistStore;
+use rockbox_scheduler::{action, Schedule};
+use rockbox_sys as rb;
+use sqlx::{Pool, Sqlite};
+
+use crate::PLAYLIST_DIRTY;
+
+const SOUND_VOLUME: i32 = 0;
+const STATUS_PLAYING: i32 = 1;
+const STATUS_PAUSED: i32 = 3;
+
+/// Run a blocking firmware call off the async runtime.
+async fn firmware<T, F>(f: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    Ok(tokio::task::spawn_blocking(move || rb::with_kernel_lock(f)).await?)
+}
+
+fn volume() -> i32 {
+    rb::system::get_global_status().volume
+}
+
+fn set_volume(value: i32) {
+    rb::sound::adjust_volume(value - volume());
+}
+
+fn play_paths(dir: &str, paths: &[String]) {
+    rb::playback::hard_stop();
+    rb::playlist::create(dir, None);
+    rb::playlist::build_playlist(
+        paths.iter().map(|p| p.as_str()).collect(),
+        0,
+        paths.len() as i32,
+    );
+    rb::playlist::start(0, 0, 0);
+    PLAYLIST_DIRTY.store(true, Ordering::Relaxed);
+}
+
+fn resume() {
+    match rb::playback::status().status {
+        STATUS_PLAYING => {}
+        STATUS_PAUSED => rb::playback::resume(),
+        _ => {
+            let status = rb::system::get_global_status();
+            if status.resume_index == -1 {
+                return;
+            }
+            if rb::playlist::amount() == 0 && rb::playlist::resume() == -1 {
+                return;
+            }
+            rb::playlist::resume_track(
+                status.resume_index,
+                status.resume_crc32,
+                status.resume_elapsed.into(),
+                status.resume_offset.into(),
+            );
+            PLAYLIST_DIRTY.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+async fn playlist_paths(pool: Pool<Sqlite>, id: &str) -> Result<Vec<String>, Error> {
+    let mut paths = Vec::new();
+    for track_id in PlaylistStore::new(pool.clone()).get_track_ids(id).await? {
+        if let Some(track) = repo::track::find(pool.clone(), &track_id).await? {
+            paths.push(track.path);
+        }
+    }
+    match paths.is_empty() {
+        true => Err(anyhow!("playlist {} has no tracks", id)),
+        false => Ok(paths),
+    }
+}
+
+/// Run a schedule's action now.
+pub async fn run(pool: Pool<Sqlite>, schedule: Schedule) -> Result<(), Error> {
+    let target = schedule.target.clone().unwrap_or_default();
+    let start: Box<dyn FnOnce() + Send> = match schedule.action.as_str() {
+        action::SLEEP => return fade_out(schedule.ramp_secs).await,
+        action::RESUME => Box::new(resume),
+        action::PLAY_PLAYLIST => {
+            let paths = playlist_paths(pool, &target).await?;
+            let dir = {
+                let parts: Vec<_> = paths[0].split('/').collect();
+                parts[..parts.len().saturating_sub(1)].join("/")
+            };
+            Box::new(move || play_paths(&dir, &paths))
+        }
+        action::PLAY_RADIO => {
+            let station = repo::radio_station::find(pool, &target)
+                .await?
+                .ok_or_else(|| anyhow!("radio station {} not found", target))?;
+            let dir = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            Box::new(move || play_paths(&dir, &[station.stream_url]))
+        }
+        other => return Err(anyhow!("unknown action: {}", other)),
+    };
+    ramp_up(schedule.volume, schedule.ramp_secs, start).await
+}
+
+/// Start playback with `start`, then bring the volume from the minimum up
+/// to `target` over `secs`. Stops ramping if the volume is changed by hand.
+async fn ramp_up(
+    target: Option<i32>,
+    secs: i64,
+    start: Box<dyn FnOnce() + Send>,
+) -> Result<(), Error> {
+    let (from, to) = firmware(move || {
+        let min = rb::sound::min(SOUND_VOLUME);
+        let max = rb::sound::max(SOUND_VOLUME);
+        let to = target.unwrap_or_else(volume).clamp(min, max);
+        let from = if secs > 0 { min } else { to };
+        set_volume(from);
+        start();
+        (from, to)
+    })
+    .await?;
+
+    let mut expected = from;
+    for step in 1..=secs {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let next = from + ((to - from) as i64 * step / secs) as i32;
+        let changed = firmware(move || {
+            if volume() != expected {
+                return true;
+            }
+            set_volume(next);
+            false
+        })
+        .await?;
+        if changed {
+            break;
+        }
+        expected = next;
+    }
+    Ok(())
+}
+
+/// Fade out over `secs` and pause, then put the volume back for next time.
+async fn fade_out(secs: i64) -> Result<(), Error> {
+    let (playing, from, min) = firmware(|| {
+        (
+            rb::playback::status().status == STATUS_PLAYING,
+            volume(),
+            rb::sound::min(SOUND_VOLUME),
+        )
+    })
+    .await?;
+    if !playing {
+        return Ok(());
+    }
+
+    for step in 1..=secs {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let next = from - ((from - min) as i64 * step / secs) as i32;
+        let stopped = firmware(move || {
+            if rb::playback::status().status != STATUS_PLAYING {
+                return true;
+            }
+            set_volume(next);
+            false
+        })
+        .await?;
+        if stopped {
+            break;
+        }
+    }
+
+    firmware(|| rb::sound::pcmbuf_fade(1, false)).await?;
+    // pcmbuf_fade takes about a third of a second to reach silence.
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    firmware(move || {
+        rb::playback::pause();
+        rb::sound::pcmbuf_fade(0, true);
+        set_volume(from);
+    })
+    .await
+}
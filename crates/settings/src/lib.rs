@@ -1,5 +1,37 @@
-use anyhow::Error;
-use rockbox_sys::{self as rb, sound::pcm, types::user_settings::NewGlobalSettings};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+use anyhow::{anyhow, Error};
+use rockbox_alsa_sink::AlsaConfig;
+use rockbox_hls::{
+    timeshift,
+    transition::{self, Curve, TransitionConfig},
+};
+use rockbox_mixer::{MixerConfig, MixerMode, ReplayGain, ReplayGainMode};
+use rockbox_sys::{
+    self as rb,
+    sound::pcm,
+    types::user_settings::{NewGlobalSettings, ReplaygainSettings},
+    UserSettings,
+};
+
+// PITCH_SPEED_100 in firmware/export/sound.h.
+const PITCH_NORMAL: i32 = 10000;
+// REPLAYGAIN_* in lib/rbcodec/dsp/dsp_misc.h.
+const REPLAYGAIN_TRACK: i32 = 0;
+const REPLAYGAIN_ALBUM: i32 = 1;
+const REPLAYGAIN_SHUFFLE: i32 = 2;
+const REPLAYGAIN_OFF: i32 = 3;
+
+// Set while the firmware runs with neutral DSP for a bit-perfect alsa sink.
+static DSP_BYPASSED: AtomicBool = AtomicBool::new(false);
+// The user's values of what the bypass neutralises, put back when it ends.
+static DSP_SAVED: Mutex<Option<DspValues>> = Mutex::new(None);
+// Set while the output goes through a sink that applies rockbox-mixer, which
+// then owns ReplayGain in place of the firmware DSP.
+static MIXER_DRIVES: AtomicBool = AtomicBool::new(false);
 
 pub fn load_settings(new_settings: Option<NewGlobalSettings>) -> Result<(), Error> {
     let settings: NewGlobalSettings = match new_settings.clone() {
@@ -13,7 +45,8 @@ pub fn load_settings(new_settings: Option<NewGlobalSettings>) -> Result<(), Erro
     };
 
     if new_settings.is_none() {
-        // disable sleep timer
+        // The firmware sleep timer powers the device off; rockboxd's sleep
+        // timer (rockbox-scheduler) fades out and pauses instead.
         rb::system::set_sleeptimer_duration(0);
     }
 
@@ -37,6 +70,28 @@ pub fn load_settings(new_settings: Option<NewGlobalSettings>) -> Result<(), Erro
         std::env::set_var("ROCKBOX_LIBRARY", &default_music_dir);
     }
 
+    // A partial update (the HTTP settings endpoint) leaves out the output
+    // fields; what's on disk still describes the output in use.
+    let saved = match new_settings {
+        Some(_) => read_settings().unwrap_or_default(),
+        None => NewGlobalSettings::default(),
+    };
+    let output = settings
+        .audio_output
+        .as_deref()
+        .or(saved.audio_output.as_deref());
+    let bit_perfect = output == Some("alsa")
+        && settings
+            .alsa_bit_perfect
+            .or(saved.alsa_bit_perfect)
+            .unwrap_or(false);
+    let was_bypassed = DSP_BYPASSED.swap(bit_perfect, Ordering::SeqCst);
+    if was_bypassed && !bit_perfect {
+        // Before save_settings, so values set in this same update win.
+        set_dsp_bypass(false);
+        tracing::info!("audio output: firmware DSP restored");
+    }
+
     rb::settings::save_settings(settings.clone(), new_settings.is_none());
 
     match settings.audio_output.as_deref() {
@@ -127,6 +182,7 @@ pub fn load_settings(new_settings: Option<NewGlobalSettings>) -> Result<(), Erro
             tracing::info!("audio output: cpal (system default device)");
         }
         Some("alsa") => {
+            rockbox_alsa_sink::configure(alsa_config(&settings));
             pcm::switch_sink(pcm::PCM_SINK_ALSA);
             tracing::info!("audio output: alsa (direct libasound, arm-linux-gnueabihf)");
         }
@@ -136,6 +192,8 @@ pub fn load_settings(new_settings: Option<NewGlobalSettings>) -> Result<(), Erro
             pcm::cmaf_set_http_port(http_port);
             pcm::cmaf_set_bitrate(bitrate);
             pcm::cmaf_set_segment_dir(settings.cmaf_segment_dir.as_deref());
+            pcm::cmaf_set_renditions(settings.cmaf_renditions.as_deref().unwrap_or_default());
+            pcm::cmaf_set_dvr_window(settings.cmaf_dvr_window.unwrap_or(0));
             pcm::switch_sink(pcm::PCM_SINK_CMAF);
             // Bind the HLS / DASH HTTP server *now*, before any track plays,
             // so http://host:port/hls/master.m3u8 is reachable as soon as
@@ -145,8 +203,11 @@ pub fn load_settings(new_settings: Option<NewGlobalSettings>) -> Result<(), Erro
             tracing::info!(
                 "audio output: cmaf (HLS at http://localhost:{http_port}/hls/master.m3u8, \
                  DASH at http://localhost:{http_port}/dash/manifest.mpd, \
-                 AAC-LC {} kbps{})",
-                bitrate / 1000,
+                 {}{})",
+                match settings.cmaf_renditions.as_deref() {
+                    Some(renditions) if !renditions.is_empty() => renditions.join(", "),
+                    _ => format!("AAC-LC {} kbps", bitrate / 1000),
+                },
                 settings
                     .cmaf_segment_dir
                     .as_deref()
@@ -179,8 +240,27 @@ pub fn load_settings(new_settings: Option<NewGlobalSettings>) -> Result<(), Erro
         rockbox_upnp::start_renderer(port, name);
     }
 
+    if bit_perfect {
+        set_dsp_bypass(true);
+        if !was_bypassed {
+            tracing::info!("audio output: alsa bit-perfect, firmware DSP bypassed");
+        }
+    }
+
     rb::settings::apply_audio_settings();
 
+    if new_settings.is_none() {
+        apply_stream_transitions(&settings);
+        timeshift::set_window_secs(
+            settings
+                .stream_timeshift_window
+                .unwrap_or(timeshift::DEFAULT_WINDOW_SECS),
+        );
+        apply_mixer(&settings);
+    }
+    MIXER_DRIVES.store(rockbox_mixer::drives(output), Ordering::SeqCst);
+    apply_replaygain();
+
     let enabled = unsafe { rb::global_settings.eq_enabled };
     rb::sound::pcmbuf_set_low_latency(true);
     rb::sound::dsp::eq_enable(enabled);
@@ -206,8 +286,6 @@ pub fn write_settings() -> Result<(), Error> {
     }
     settings.playlist_shuffle = from_c.playlist_shuffle;
     settings.repeat_mode = from_c.repeat_mode;
-    settings.bass = from_c.bass;
-    settings.treble = from_c.treble;
     settings.crossfade = from_c.crossfade;
     settings.fade_on_stop = from_c.fade_on_stop;
     settings.fade_in_delay = from_c.fade_in_delay;
@@ -215,20 +293,26 @@ pub fn write_settings() -> Result<(), Error> {
     settings.fade_out_delay = from_c.fade_out_delay;
     settings.fade_out_duration = from_c.fade_out_duration;
     settings.fade_out_mixmode = from_c.fade_out_mixmode;
-    settings.balance = from_c.balance;
-    settings.stereo_width = from_c.stereo_width;
-    settings.stereosw_mode = from_c.stereosw_mode;
-    settings.surround_enabled = from_c.surround_enabled;
-    settings.surround_balance = from_c.surround_balance;
-    settings.surround_fx1 = from_c.surround_fx1;
-    settings.surround_fx2 = from_c.surround_fx2;
     settings.party_mode = from_c.party_mode;
-    settings.channel_config = from_c.channel_config;
     settings.player_name = from_c.player_name;
-    settings.eq_enabled = from_c.eq_enabled;
-    settings.eq_band_settings = from_c.eq_band_settings;
-    settings.replaygain_settings = from_c.replaygain_settings;
-    settings.compressor_settings = from_c.compressor_settings;
+    // A bit-perfect alsa sink leaves the firmware with neutral DSP; keep the
+    // user's own values on disk for when it's turned off.
+    if !dsp_bypassed() {
+        settings.bass = from_c.bass;
+        settings.treble = from_c.treble;
+        settings.balance = from_c.balance;
+        settings.stereo_width = from_c.stereo_width;
+        settings.stereosw_mode = from_c.stereosw_mode;
+        settings.surround_enabled = from_c.surround_enabled;
+        settings.surround_balance = from_c.surround_balance;
+        settings.surround_fx1 = from_c.surround_fx1;
+        settings.surround_fx2 = from_c.surround_fx2;
+        settings.channel_config = from_c.channel_config;
+        settings.eq_enabled = from_c.eq_enabled;
+        settings.eq_band_settings = from_c.eq_band_settings;
+        settings.replaygain_settings = from_c.replaygain_settings;
+        settings.compressor_settings = from_c.compressor_settings;
+    }
     let content = toml::to_string(&settings)?;
     let path = format!("{}/.config/rockbox.org/settings.toml", home);
     std::fs::write(&path, content)?;
@@ -257,6 +341,327 @@ pub fn save_settings_to_file(settings: &NewGlobalSettings) -> Result<(), Error>
     Ok(())
 }
 
+/// The alsa sink's output mode from the `alsa_*` settings.
+pub fn alsa_config(settings: &NewGlobalSettings) -> AlsaConfig {
+    let defaults = AlsaConfig::default();
+    AlsaConfig {
+        device: settings.alsa_device.clone().unwrap_or(defaults.device),
+        exclusive: settings.alsa_exclusive.unwrap_or(defaults.exclusive),
+        dop: settings.alsa_dop.unwrap_or(defaults.dop),
+    }
+}
+
+/// What [`set_dsp_bypass`] switches off in the running firmware: tone
+/// controls, channel mixing, the DSP stages and ReplayGain. Volume is the
+/// mixer's; `mixer_config` keeps it off the samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DspValues {
+    bass: i32,
+    treble: i32,
+    balance: i32,
+    channel_config: i32,
+    stereo_width: i32,
+    eq_enabled: bool,
+    crossfeed: i32,
+    dithering_enabled: bool,
+    surround_enabled: i32,
+    afr_enabled: i32,
+    pbe: i32,
+    timestretch_enabled: bool,
+    compressor_threshold: i32,
+    replaygain_type: i32,
+}
+
+impl DspValues {
+    /// Values that leave the samples untouched.
+    const NEUTRAL: DspValues = DspValues {
+        bass: 0,
+        treble: 0,
+        balance: 0,
+        channel_config: 0, // SOUND_CHAN_STEREO
+        stereo_width: 100,
+        eq_enabled: false,
+        crossfeed: 0,
+        dithering_enabled: false,
+        surround_enabled: 0,
+        afr_enabled: 0,
+        pbe: 0,
+        timestretch_enabled: false,
+        compressor_threshold: 0,
+        replaygain_type: REPLAYGAIN_OFF,
+    };
+
+    fn read(s: &UserSettings) -> DspValues {
+        DspValues {
+            bass: s.bass,
+            treble: s.treble,
+            balance: s.balance,
+            channel_config: s.channel_config,
+            stereo_width: s.stereo_width,
+            eq_enabled: s.eq_enabled,
+            crossfeed: s.crossfeed,
+            dithering_enabled: s.dithering_enabled,
+            surround_enabled: s.surround_enabled,
+            afr_enabled: s.afr_enabled,
+            pbe: s.pbe,
+            timestretch_enabled: s.timestretch_enabled,
+            compressor_threshold: s.compressor_settings.threshold,
+            replaygain_type: s.replaygain_settings.r#type,
+        }
+    }
+
+    fn write(self, s: &mut UserSettings) {
+        s.bass = self.bass;
+        s.treble = self.treble;
+        s.balance = self.balance;
+        s.channel_config = self.channel_config;
+        s.stereo_width = self.stereo_width;
+        s.eq_enabled = self.eq_enabled;
+        s.crossfeed = self.crossfeed;
+        s.dithering_enabled = self.dithering_enabled;
+        s.surround_enabled = self.surround_enabled;
+        s.afr_enabled = self.afr_enabled;
+        s.pbe = self.pbe;
+        s.timestretch_enabled = self.timestretch_enabled;
+        s.compressor_settings.threshold = self.compressor_threshold;
+        s.replaygain_settings.r#type = self.replaygain_type;
+    }
+}
+
+/// Neutralise `s`, setting the user's values aside in `saved` the first
+/// time, or put them back. `apply_audio_settings` pushes the result to the
+/// DSP.
+fn bypass(s: &mut UserSettings, saved: &mut Option<DspValues>, on: bool) {
+    if on {
+        saved.get_or_insert_with(|| DspValues::read(s));
+        DspValues::NEUTRAL.write(s);
+    } else if let Some(values) = saved.take() {
+        values.write(s);
+    }
+}
+
+fn set_dsp_bypass(on: bool) {
+    let mut saved = DSP_SAVED.lock().unwrap();
+    bypass(
+        unsafe { &mut *std::ptr::addr_of_mut!(rb::global_settings) },
+        &mut saved,
+        on,
+    );
+}
+
+/// Whether a bit-perfect alsa sink keeps the firmware DSP neutral right now.
+pub fn dsp_bypassed() -> bool {
+    DSP_BYPASSED.load(Ordering::SeqCst)
+}
+
+/// Firmware processing currently changing samples on their way to the
+/// sink; empty when they pass through untouched.
+pub fn active_processing() -> Vec<&'static str> {
+    let s = unsafe { rb::global_settings };
+    let stages = [
+        (s.bass != 0, "bass"),
+        (s.treble != 0, "treble"),
+        (s.balance != 0, "balance"),
+        (s.channel_config != 0, "channel configuration"),
+        (s.stereo_width != 100, "stereo width"),
+        (s.eq_enabled, "equalizer"),
+        (s.crossfeed != 0, "crossfeed"),
+        (s.dithering_enabled, "dithering"),
+        (s.surround_enabled != 0, "surround"),
+        (s.afr_enabled != 0, "auditory fatigue reduction"),
+        (s.pbe != 0, "perceptual bass enhancement"),
+        (s.timestretch_enabled, "timestretch"),
+        (s.compressor_settings.threshold != 0, "compressor"),
+    ];
+    let mut active: Vec<_> = stages
+        .into_iter()
+        .filter_map(|(on, stage)| on.then_some(stage))
+        .collect();
+    if rb::sound::get_pitch() != PITCH_NORMAL {
+        active.push("pitch");
+    }
+    let replaygain = if MIXER_DRIVES.load(Ordering::SeqCst) {
+        rockbox_mixer::replaygain_gain() != 1.0
+    } else {
+        s.replaygain_settings.r#type != REPLAYGAIN_OFF
+    };
+    if replaygain {
+        active.push("replaygain");
+    }
+    if rockbox_mixer::volume_gains() != (1.0, 1.0) {
+        active.push("software volume");
+    }
+    active
+}
+
+/// Push the firmware's ReplayGain settings to its DSP, as replaygain_update()
+/// in apps/misc.c does, unless rockbox-mixer applies ReplayGain for the
+/// current output. global_settings keeps the user's values either way, so
+/// they are still reported and saved.
+pub fn apply_replaygain() {
+    let mut settings = unsafe { rb::global_settings.replaygain_settings };
+    if MIXER_DRIVES.load(Ordering::SeqCst) {
+        settings = rb::ReplaygainSettings {
+            noclip: false,
+            r#type: REPLAYGAIN_OFF,
+            preamp: 0,
+        };
+    } else if settings.r#type == REPLAYGAIN_SHUFFLE {
+        settings.r#type = match unsafe { rb::global_settings.playlist_shuffle } {
+            true => REPLAYGAIN_TRACK,
+            false => REPLAYGAIN_ALBUM,
+        };
+    }
+    rb::sound::dsp::replaygain_set_settings(&settings);
+}
+
+/// ReplayGain as the firmware's `replaygain_settings` describe it.
+pub fn replaygain(settings: &ReplaygainSettings) -> ReplayGain {
+    ReplayGain {
+        mode: ReplayGainMode::from_firmware(settings.r#type),
+        preamp_db: settings.preamp as f32 / 10.0,
+        prevent_clipping: settings.noclip,
+    }
+}
+
+/// The mixer from the `mixer*` settings and the firmware's ReplayGain. A
+/// bit-perfect alsa sink keeps software volume and ReplayGain off the
+/// samples; a hardware mixer still follows the volume.
+pub fn mixer_config(settings: &NewGlobalSettings) -> Result<MixerConfig, Error> {
+    let defaults = MixerConfig::default();
+    let mut mode = match settings.mixer.as_deref() {
+        Some(mode) => mode.parse::<MixerMode>().map_err(|e| anyhow!(e))?,
+        None => defaults.mode,
+    };
+    let alsa = settings.audio_output.as_deref() == Some("alsa");
+    // The hardware mixer belongs to the card the alsa sink plays on.
+    let device = match (&settings.mixer_device, &settings.alsa_device) {
+        (Some(device), _) => device.clone(),
+        (None, Some(device)) if alsa => alsa_card(device).unwrap_or(defaults.device),
+        (None, _) => defaults.device,
+    };
+    let mut replaygain = settings
+        .replaygain_settings
+        .as_ref()
+        .map(replaygain)
+        .unwrap_or_default();
+    if alsa && settings.alsa_bit_perfect.unwrap_or(false) {
+        if mode == MixerMode::Software {
+            mode = MixerMode::Fixed;
+        }
+        replaygain = ReplayGain::default();
+    }
+    Ok(MixerConfig {
+        mode,
+        device,
+        control: settings.mixer_control.clone().unwrap_or(defaults.control),
+        dither: settings.mixer_dither.unwrap_or(defaults.dither),
+        replaygain,
+    })
+}
+
+/// The control device of the card an ALSA PCM name refers to: "hw:1,0" and
+/// "plughw:1,0" → "hw:1", "hw:CARD=DAC,DEV=0" → "hw:CARD=DAC".
+fn alsa_card(device: &str) -> Option<String> {
+    let (_, rest) = device.split_once("hw:")?;
+    let card = rest.split(',').next().filter(|card| !card.is_empty())?;
+    Some(format!("hw:{card}"))
+}
+
+fn apply_mixer(settings: &NewGlobalSettings) {
+    match mixer_config(settings) {
+        Ok(config) => rockbox_mixer::configure(config),
+        Err(e) => tracing::warn!("mixer: {e}"),
+    }
+}
+
+/// Save the mixer fields set in `update` and apply the mixer, along with
+/// any change to the ReplayGain and output settings already on disk.
+pub fn update_mixer(update: &NewGlobalSettings) -> Result<(), Error> {
+    let mut settings = read_settings().unwrap_or_default();
+    let changed = update.mixer.is_some()
+        || update.mixer_device.is_some()
+        || update.mixer_control.is_some()
+        || update.mixer_dither.is_some();
+    if update.mixer.is_some() {
+        settings.mixer = update.mixer.clone();
+    }
+    if update.mixer_device.is_some() {
+        settings.mixer_device = update.mixer_device.clone();
+    }
+    if update.mixer_control.is_some() {
+        settings.mixer_control = update.mixer_control.clone();
+    }
+    if update.mixer_dither.is_some() {
+        settings.mixer_dither = update.mixer_dither;
+    }
+    let config = mixer_config(&settings)?;
+    if changed {
+        save_settings_to_file(&settings)?;
+    }
+    rockbox_mixer::configure(config);
+    Ok(())
+}
+
+/// How HLS / DASH streams are joined, from the `stream_crossfade_*` and
+/// `mixramp_*` settings. Without `stream_crossfade_secs` streams follow the
+/// firmware crossfade: its fade-out duration while it is on, else gapless.
+pub fn stream_transitions(settings: &NewGlobalSettings) -> Result<TransitionConfig, Error> {
+    let defaults = TransitionConfig::default();
+    let crossfade_secs = match settings.stream_crossfade_secs {
+        Some(secs) => secs,
+        None if settings.crossfade.unwrap_or(0) != 0 => {
+            settings.fade_out_duration.unwrap_or(0) as f32
+        }
+        None => 0.0,
+    };
+    let curve = match settings.stream_crossfade_curve.as_deref() {
+        Some(curve) => curve.parse::<Curve>().map_err(|e| anyhow!(e))?,
+        None => defaults.curve,
+    };
+    Ok(TransitionConfig {
+        crossfade_secs,
+        curve,
+        mixramp_db: settings.mixramp_db.unwrap_or(defaults.mixramp_db),
+        mixramp_delay: settings.mixramp_delay.unwrap_or(defaults.mixramp_delay),
+    })
+}
+
+fn apply_stream_transitions(settings: &NewGlobalSettings) {
+    match stream_transitions(settings) {
+        Ok(config) => transition::set_config(config),
+        Err(e) => tracing::warn!("stream transitions: {e}"),
+    }
+}
+
+/// Save the stream transition fields set in `update` and apply the result,
+/// along with any change to the firmware crossfade it falls back on.
+pub fn update_stream_transitions(update: &NewGlobalSettings) -> Result<(), Error> {
+    let mut settings = read_settings().unwrap_or_default();
+    let changed = update.stream_crossfade_secs.is_some()
+        || update.stream_crossfade_curve.is_some()
+        || update.mixramp_db.is_some()
+        || update.mixramp_delay.is_some();
+    if update.stream_crossfade_secs.is_some() {
+        settings.stream_crossfade_secs = update.stream_crossfade_secs;
+    }
+    if update.stream_crossfade_curve.is_some() {
+        settings.stream_crossfade_curve = update.stream_crossfade_curve.clone();
+    }
+    if update.mixramp_db.is_some() {
+        settings.mixramp_db = update.mixramp_db;
+    }
+    if update.mixramp_delay.is_some() {
+        settings.mixramp_delay = update.mixramp_delay;
+    }
+    let config = stream_transitions(&settings)?;
+    if changed {
+        save_settings_to_file(&settings)?;
+    }
+    transition::set_config(config);
+    Ok(())
+}
+
 pub fn get_music_dir() -> Result<String, Error> {
     let home = std::env::var("HOME")?;
     let path = format!("{}/.config/rockbox.org/settings.toml", home);
@@ -273,7 +678,37 @@ pub fn get_music_dir() -> Result<String, Error> {
 
 #[cfg(test)]
 mod tests {
-    use rockbox_sys::types::user_settings::{CompressorSettings, NewGlobalSettings};
+    use super::{bypass, DspValues, REPLAYGAIN_ALBUM};
+    use rockbox_sys::{
+        types::user_settings::{CompressorSettings, NewGlobalSettings},
+        UserSettings,
+    };
+
+    #[test]
+    fn bit_perfect_on_then_off_keeps_the_dsp_values() {
+        // Plain integers, booleans and null pointers.
+        let mut s: UserSettings = unsafe { std::mem::zeroed() };
+        s.bass = 6;
+        s.treble = -3;
+        s.stereo_width = 120;
+        s.eq_enabled = true;
+        s.crossfeed = 1;
+        s.compressor_settings.threshold = -24;
+        s.replaygain_settings.r#type = REPLAYGAIN_ALBUM;
+        let before = DspValues::read(&s);
+
+        let mut saved = None;
+        bypass(&mut s, &mut saved, true);
+        assert_eq!(DspValues::read(&s), DspValues::NEUTRAL);
+        // Reloading settings while bit-perfect keeps the values set aside.
+        bypass(&mut s, &mut saved, true);
+        bypass(&mut s, &mut saved, false);
+        assert_eq!(DspValues::read(&s), before);
+        assert!(saved.is_none());
+
+        bypass(&mut s, &mut saved, false);
+        assert_eq!(DspValues::read(&s), before);
+    }
 
     #[test]
     fn compressor_settings_round_trip() {
@@ -304,6 +739,108 @@ mod tests {
         assert_eq!(c.attack_time, 5);
     }
 
+    #[test]
+    fn stream_transitions_follow_the_firmware_crossfade() {
+        let settings = NewGlobalSettings {
+            crossfade: Some(1),
+            fade_out_duration: Some(6),
+            ..Default::default()
+        };
+        let config = super::stream_transitions(&settings).unwrap();
+        assert_eq!(config.crossfade_secs, 6.0);
+        assert!(!config.mixramp());
+
+        let settings = NewGlobalSettings {
+            stream_crossfade_secs: Some(0.0),
+            stream_crossfade_curve: Some("s_curve".to_string()),
+            mixramp_delay: Some(1.5),
+            ..settings
+        };
+        let config = super::stream_transitions(&settings).unwrap();
+        assert_eq!(config.crossfade_secs, 0.0);
+        assert_eq!(config.curve.as_str(), "s_curve");
+        assert!(config.mixramp());
+
+        let settings = NewGlobalSettings {
+            stream_crossfade_curve: Some("cubic".to_string()),
+            ..Default::default()
+        };
+        assert!(super::stream_transitions(&settings).is_err());
+    }
+
+    #[test]
+    fn alsa_config_defaults_to_the_shared_device() {
+        let config = super::alsa_config(&NewGlobalSettings::default());
+        assert_eq!(config, rockbox_alsa_sink::AlsaConfig::default());
+        assert!(!config.exclusive && !config.dop);
+
+        let settings: NewGlobalSettings = toml::from_str(
+            r#"
+audio_output = "alsa"
+alsa_device = "hw:CARD=DAC,DEV=0"
+alsa_exclusive = true
+alsa_dop = true
+"#,
+        )
+        .expect("deserialize");
+        let config = super::alsa_config(&settings);
+        assert_eq!(config.device, "hw:CARD=DAC,DEV=0");
+        assert!(config.exclusive && config.dop);
+    }
+
+    #[test]
+    fn mixer_config_follows_the_output() {
+        let settings: NewGlobalSettings = toml::from_str(
+            r#"
+audio_output = "alsa"
+alsa_device = "plughw:1,0"
+mixer = "hardware"
+mixer_control = "Digital"
+
+[replaygain_settings]
+noclip = true
+type = 1
+preamp = -15
+"#,
+        )
+        .expect("deserialize");
+        let config = super::mixer_config(&settings).unwrap();
+        assert_eq!(config.mode, rockbox_mixer::MixerMode::Hardware);
+        assert_eq!(config.device, "hw:1");
+        assert_eq!(config.control, "Digital");
+        assert!(config.dither);
+        assert_eq!(config.replaygain.mode, rockbox_mixer::ReplayGainMode::Album);
+        assert_eq!(config.replaygain.preamp_db, -1.5);
+        assert!(config.replaygain.prevent_clipping);
+
+        // Bit-perfect: samples untouched, but the card's mixer still works.
+        let bit_perfect = NewGlobalSettings {
+            alsa_bit_perfect: Some(true),
+            ..settings.clone()
+        };
+        let config = super::mixer_config(&bit_perfect).unwrap();
+        assert_eq!(config.mode, rockbox_mixer::MixerMode::Hardware);
+        assert_eq!(config.replaygain, rockbox_mixer::ReplayGain::default());
+        let software = NewGlobalSettings {
+            mixer: None,
+            ..bit_perfect
+        };
+        let config = super::mixer_config(&software).unwrap();
+        assert_eq!(config.mode, rockbox_mixer::MixerMode::Fixed);
+
+        let builtin = NewGlobalSettings {
+            audio_output: None,
+            ..settings
+        };
+        assert_eq!(super::mixer_config(&builtin).unwrap().device, "default");
+
+        let unknown = NewGlobalSettings {
+            mixer: Some("loud".to_string()),
+            ..Default::default()
+        };
+        assert!(super::mixer_config(&unknown).is_err());
+    }
+
     #[test]
     fn compressor_settings_absent_when_none() {
         let settings = NewGlobalSettings {
@@ -0,0 +1,369 @@
+//! Feature extraction from decoded audio. Pure DSP, no I/O: everything
+//! here works on mono `f32` samples so it can be tested with synthetic
+//! signals.
+
+use serde::{Deserialize, Serialize};
+
+/// Analysis frame length in samples (about 46 ms at 44.1 kHz).
+pub const FRAME: usize = 2048;
+const HOP: usize = FRAME / 2;
+
+/// Length of [`Features::vector`].
+pub const DIMENSIONS: usize = 16;
+
+/// Tempo search range. Anything outside is folded by the autocorrelation
+/// onto a multiple inside it.
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+
+/// Frames quieter than this (RMS) do not count towards the spectral means,
+/// so fade-ins and gaps between movements do not drag the centroid down.
+const SILENCE_RMS: f32 = 1e-4;
+
+/// Chroma only looks at A1..~D#8; below, bins are too coarse to tell
+/// semitones apart, above, there is mostly noise and cymbals.
+const CHROMA_MIN_HZ: f32 = 55.0;
+const CHROMA_MAX_HZ: f32 = 5000.0;
+
+/// Weights of each feature group in the distance. Chroma has twelve
+/// components summing to 1, so it gets a larger weight to count about as
+/// much as tempo or timbre.
+const TEMPO_WEIGHT: f32 = 1.0;
+const TIMBRE_WEIGHT: f32 = 1.0;
+const LOUDNESS_WEIGHT: f32 = 0.5;
+const CHROMA_WEIGHT: f32 = 3.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Features {
+    /// Beats per minute, or 0 when no pulse was found.
+    pub tempo: f32,
+    /// Mean spectral centroid ("brightness"), Hz.
+    pub centroid: f32,
+    /// Mean frequency below which 85% of the spectral energy lies, Hz.
+    pub rolloff: f32,
+    /// Mean level, dBFS.
+    pub loudness: f32,
+    /// Energy per pitch class, C to B, summing to 1.
+    pub chroma: [f32; 12],
+}
+
+impl Features {
+    /// Weighted, roughly unit-scaled embedding used for nearest-neighbour
+    /// search: tempo on a log scale, centroid and rolloff as a fraction of
+    /// 11 kHz, loudness over a 60 dB range, then the chroma profile.
+    pub fn vector(&self) -> [f32; DIMENSIONS] {
+        let mut v = [0.0; DIMENSIONS];
+        v[0] = match self.tempo > 0.0 {
+            true => TEMPO_WEIGHT * (self.tempo / MIN_BPM).log2() / (MAX_BPM / MIN_BPM).log2(),
+            false => 0.0,
+        };
+        v[1] = TIMBRE_WEIGHT * (self.centroid / 11025.0).min(1.0);
+        v[2] = TIMBRE_WEIGHT * (self.rolloff / 11025.0).min(1.0);
+        v[3] = LOUDNESS_WEIGHT * ((self.loudness + 60.0) / 60.0).clamp(0.0, 1.0);
+        for (i, c) in self.chroma.iter().enumerate() {
+            v[4 + i] = CHROMA_WEIGHT * c;
+        }
+        v
+    }
+}
+
+/// Euclidean distance between two [`Features::vector`]s.
+pub fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// Component-wise mean of `vectors`, the seed point for multi-track seeds.
+pub fn centroid(vectors: &[[f32; DIMENSIONS]]) -> Option<[f32; DIMENSIONS]> {
+    if vectors.is_empty() {
+        return None;
+    }
+    let mut mean = [0.0; DIMENSIONS];
+    for v in vectors {
+        for (m, x) in mean.iter_mut().zip(v) {
+            *m += x;
+        }
+    }
+    for m in mean.iter_mut() {
+        *m /= vectors.len() as f32;
+    }
+    Some(mean)
+}
+
+/// Features of mono `samples` at `sample_rate`, or `None` when there is
+/// too little (non-silent) audio to say anything.
+pub fn analyze(samples: &[f32], sample_rate: u32) -> Option<Features> {
+    if sample_rate == 0 || samples.len() < FRAME * 8 {
+        return None;
+    }
+    let rate = sample_rate as f32;
+    let bin_hz = rate / FRAME as f32;
+    let window: Vec<f32> = (0..FRAME)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME - 1) as f32).cos())
+        .collect();
+    let pitch_class: Vec<Option<usize>> = (0..=FRAME / 2)
+        .map(|k| {
+            let hz = k as f32 * bin_hz;
+            if !(CHROMA_MIN_HZ..=CHROMA_MAX_HZ).contains(&hz) {
+                return None;
+            }
+            // MIDI note number; C is pitch class 0.
+            let midi = (12.0 * (hz / 440.0).log2() + 69.0).round() as i32;
+            Some(midi.rem_euclid(12) as usize)
+        })
+        .collect();
+
+    let mut re = vec![0.0f32; FRAME];
+    let mut im = vec![0.0f32; FRAME];
+    let mut magnitude = vec![0.0f32; FRAME / 2 + 1];
+    let mut previous = vec![0.0f32; FRAME / 2 + 1];
+
+    let mut energy_sum = 0.0f64;
+    let mut frames = 0usize;
+    let mut centroid_sum = 0.0f64;
+    let mut rolloff_sum = 0.0f64;
+    let mut voiced = 0usize;
+    let mut chroma = [0.0f64; 12];
+    let mut onsets = Vec::with_capacity(samples.len() / HOP);
+
+    for start in (0..=samples.len() - FRAME).step_by(HOP) {
+        let frame = &samples[start..start + FRAME];
+        let energy = frame.iter().map(|s| s * s).sum::<f32>() / FRAME as f32;
+        energy_sum += energy as f64;
+        frames += 1;
+
+        for i in 0..FRAME {
+            re[i] = frame[i] * window[i];
+            im[i] = 0.0;
+        }
+        fft(&mut re, &mut im);
+        for k in 0..=FRAME / 2 {
+            magnitude[k] = (re[k] * re[k] + im[k] * im[k]).sqrt();
+        }
+
+        // Spectral flux of log-compressed magnitudes: the onset strength.
+        let flux: f32 = magnitude
+            .iter()
+            .zip(&previous)
+            .map(|(m, p)| ((1.0 + m).ln() - (1.0 + p).ln()).max(0.0))
+            .sum();
+        onsets.push(flux);
+        previous.copy_from_slice(&magnitude);
+
+        if energy.sqrt() < SILENCE_RMS {
+            continue;
+        }
+        let total: f32 = magnitude.iter().sum();
+        if total <= f32::EPSILON {
+            continue;
+        }
+        voiced += 1;
+        let weighted: f32 = magnitude
+            .iter()
+            .enumerate()
+            .map(|(k, m)| k as f32 * bin_hz * m)
+            .sum();
+        centroid_sum += (weighted / total) as f64;
+
+        let power_total: f32 = magnitude.iter().map(|m| m * m).sum();
+        let mut cumulative = 0.0;
+        let mut rolloff_bin = FRAME / 2;
+        for (k, m) in magnitude.iter().enumerate() {
+            cumulative += m * m;
+            if cumulative >= 0.85 * power_total {
+                rolloff_bin = k;
+                break;
+            }
+        }
+        rolloff_sum += (rolloff_bin as f32 * bin_hz) as f64;
+
+        for (k, m) in magnitude.iter().enumerate() {
+            if let Some(pc) = pitch_class[k] {
+                chroma[pc] += (m * m) as f64;
+            }
+        }
+    }
+
+    if voiced == 0 {
+        return None;
+    }
+    let chroma_total: f64 = chroma.iter().sum();
+    let mut chroma_profile = [0.0f32; 12];
+    if chroma_total > 0.0 {
+        for (c, e) in chroma_profile.iter_mut().zip(chroma) {
+            *c = (e / chroma_total) as f32;
+        }
+    }
+
+    Some(Features {
+        tempo: tempo(&onsets, rate / HOP as f32),
+        centroid: (centroid_sum / voiced as f64) as f32,
+        rolloff: (rolloff_sum / voiced as f64) as f32,
+        loudness: (10.0 * (energy_sum / frames as f64).max(1e-9).log10()) as f32,
+        chroma: chroma_profile,
+    })
+}
+
+/// Tempo from the onset-strength envelope (sampled at `frame_rate` Hz):
+/// the autocorrelation lag with the strongest periodicity between
+/// [`MIN_BPM`] and [`MAX_BPM`], weighted towards 120 BPM to avoid picking
+/// half or double the perceived tempo.
+fn tempo(onsets: &[f32], frame_rate: f32) -> f32 {
+    let mean = onsets.iter().sum::<f32>() / onsets.len().max(1) as f32;
+    let envelope: Vec<f32> = onsets.iter().map(|o| o - mean).collect();
+    let min_lag = (60.0 * frame_rate / MAX_BPM).floor().max(1.0) as usize;
+    let max_lag = (60.0 * frame_rate / MIN_BPM).ceil() as usize;
+    if envelope.len() <= max_lag * 2 {
+        return 0.0;
+    }
+
+    let zero_lag: f32 = envelope.iter().map(|e| e * e).sum();
+    if zero_lag <= f32::EPSILON {
+        return 0.0;
+    }
+    let mut best = (0.0f32, 0usize);
+    for lag in min_lag..=max_lag {
+        let correlation: f32 = envelope[lag..]
+            .iter()
+            .zip(&envelope)
+            .map(|(a, b)| a * b)
+            .sum::<f32>()
+            / zero_lag;
+        let bpm = 60.0 * frame_rate / lag as f32;
+        let prior = (-0.5 * ((bpm / 120.0).log2() / 1.0).powi(2)).exp();
+        if correlation * prior > best.0 {
+            best = (correlation * prior, lag);
+        }
+    }
+    match best.1 {
+        0 => 0.0,
+        lag => 60.0 * frame_rate / lag as f32,
+    }
+}
+
+/// In-place iterative radix-2 FFT. `re.len()` must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        let (w_im, w_re) = angle.sin_cos();
+        for start in (0..n).step_by(len) {
+            let (mut cur_re, mut cur_im) = (1.0f32, 0.0f32);
+            for k in 0..len / 2 {
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cur_re - im[b] * cur_im;
+                let t_im = re[b] * cur_im + im[b] * cur_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+                let next_re = cur_re * w_re - cur_im * w_im;
+                cur_im = cur_re * w_im + cur_im * w_re;
+                cur_re = next_re;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 22050;
+
+    fn sine(hz: f32, secs: f32, amplitude: f32) -> Vec<f32> {
+        (0..(RATE as f32 * secs) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * hz * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    /// Short decaying noise bursts `bpm` times a minute.
+    fn clicks(bpm: f32, secs: f32) -> Vec<f32> {
+        let period = (60.0 / bpm * RATE as f32) as usize;
+        let mut seed = 1u32;
+        (0..(RATE as f32 * secs) as usize)
+            .map(|i| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let noise = (seed >> 16) as f32 / 32768.0 - 1.0;
+                let phase = i % period;
+                noise * (-(phase as f32) / 200.0).exp()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fft_finds_a_pure_tone() {
+        let mut re: Vec<f32> = (0..64)
+            .map(|i| (2.0 * std::f32::consts::PI * 4.0 * i as f32 / 64.0).cos())
+            .collect();
+        let mut im = vec![0.0; 64];
+        fft(&mut re, &mut im);
+        let peak = (0..32)
+            .max_by(|a, b| re[*a].hypot(im[*a]).total_cmp(&re[*b].hypot(im[*b])))
+            .unwrap();
+        assert_eq!(peak, 4);
+    }
+
+    #[test]
+    fn tone_has_its_pitch_class_and_brightness() {
+        let features = analyze(&sine(440.0, 5.0, 0.5), RATE).unwrap();
+        let strongest = (0..12)
+            .max_by(|a, b| features.chroma[*a].total_cmp(&features.chroma[*b]))
+            .unwrap();
+        assert_eq!(strongest, 9, "A is pitch class 9: {:?}", features.chroma);
+        assert!(
+            (features.centroid - 440.0).abs() < 60.0,
+            "{}",
+            features.centroid
+        );
+        assert!(
+            (features.loudness - -9.0).abs() < 1.0,
+            "{}",
+            features.loudness
+        );
+
+        let brighter = analyze(&sine(3000.0, 5.0, 0.5), RATE).unwrap();
+        assert!(brighter.centroid > features.centroid);
+        assert!(brighter.rolloff > features.rolloff);
+    }
+
+    #[test]
+    fn click_track_tempo_is_found() {
+        let features = analyze(&clicks(120.0, 20.0), RATE).unwrap();
+        assert!((features.tempo - 120.0).abs() < 4.0, "{}", features.tempo);
+    }
+
+    #[test]
+    fn silence_and_short_clips_are_rejected() {
+        assert!(analyze(&vec![0.0; RATE as usize * 5], RATE).is_none());
+        assert!(analyze(&sine(440.0, 0.1, 0.5), RATE).is_none());
+    }
+
+    #[test]
+    fn similar_signals_are_closer() {
+        let a4 = analyze(&sine(440.0, 5.0, 0.5), RATE).unwrap().vector();
+        let a4_quieter = analyze(&sine(440.0, 5.0, 0.3), RATE).unwrap().vector();
+        let c7 = analyze(&sine(2093.0, 5.0, 0.5), RATE).unwrap().vector();
+        assert!(distance(&a4, &a4_quieter) < distance(&a4, &c7));
+        assert_eq!(centroid(&[a4, a4]).unwrap(), a4);
+        assert!(centroid(&[]).is_none());
+    }
+}
@@ -0,0 +1,110 @@
+//! Decodes the part of a file the analysis looks at into mono samples.
+
+use std::{fs::File, path::Path};
+
+use anyhow::{anyhow, Error};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, SeekMode, SeekTo},
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+    units::Time,
+};
+
+/// Seconds of audio analysed per track. Enough for a stable tempo and
+/// timbre estimate while keeping a library pass cheap.
+const EXCERPT_SECS: u64 = 60;
+
+/// Intros are skipped: the excerpt starts this far in, or a quarter of the
+/// way into shorter tracks.
+const SKIP_SECS: u64 = 30;
+
+/// Mono samples of an excerpt of `path`, and their sample rate.
+pub fn decode_excerpt(path: &Path) -> Result<(Vec<f32>, u32), Error> {
+    let file = File::open(path)?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow!("no audio track"))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow!("unknown sample rate"))?;
+    let duration_secs = track
+        .codec_params
+        .n_frames
+        .map(|frames| frames / sample_rate as u64);
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let skip = match duration_secs {
+        Some(duration) => SKIP_SECS.min(duration / 4),
+        None => 0,
+    };
+    if skip > 0 {
+        // Not every format can seek; analysing the intro is fine then.
+        let _ = format.seek(
+            SeekMode::Coarse,
+            SeekTo::Time {
+                time: Time::new(skip, 0.0),
+                track_id: Some(track_id),
+            },
+        );
+        decoder.reset();
+    }
+
+    let wanted = EXCERPT_SECS as usize * sample_rate as usize;
+    let mut samples = Vec::with_capacity(wanted);
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    while samples.len() < wanted {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet loses a few milliseconds, not the track.
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let channels = decoded.spec().channels.count().max(1);
+        let buffer = buffer
+            .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+        if buffer.capacity() < decoded.capacity() * channels {
+            *buffer = SampleBuffer::new(decoded.capacity() as u64, *decoded.spec());
+        }
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+    samples.truncate(wanted);
+    Ok((samples, sample_rate))
+}
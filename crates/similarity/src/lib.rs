@@ -0,0 +1,352 @@
+//! Offline acoustic similarity.
+//!
+//! A background pass decodes an excerpt of every local music track with
+//! symphonia and stores a small feature vector (tempo, spectral centroid
+//! and rolloff, loudness, chroma) in the `track_features` table. Instant
+//! mixes, Subsonic `getSimilarSongs` and track radio then look up the
+//! nearest neighbours of a seed without any network access.
+
+pub mod analysis;
+mod decode;
+
+use std::{collections::HashMap, path::Path, time::Duration};
+
+use anyhow::{Error, Result};
+use chrono::Utc;
+use rockbox_library::{entity::track::Track, repo};
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use tracing::{debug, error, info};
+
+pub use analysis::{Features, DIMENSIONS};
+
+/// Default number of tracks returned for a seed.
+pub const DEFAULT_LIMIT: usize = 50;
+
+/// Features of the audio in `path`, or `None` when it is silent or too
+/// short to analyse. Blocking: decodes up to a minute of audio.
+pub fn analyze_file(path: &Path) -> Result<Option<Features>, Error> {
+    let (samples, sample_rate) = decode::decode_excerpt(path)?;
+    Ok(analysis::analyze(&samples, sample_rate))
+}
+
+/// Modification time of `path`; a track is re-analysed when it changes.
+fn file_stamp(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let secs = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(secs as i64)
+}
+
+fn chroma_to_bytes(chroma: &[f32; 12]) -> Vec<u8> {
+    chroma.iter().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+fn chroma_from_bytes(bytes: &[u8]) -> Option<[f32; 12]> {
+    if bytes.len() != 12 * 4 {
+        return None;
+    }
+    let mut chroma = [0.0; 12];
+    for (c, chunk) in chroma.iter_mut().zip(bytes.chunks_exact(4)) {
+        *c = f32::from_le_bytes(chunk.try_into().ok()?);
+    }
+    Some(chroma)
+}
+
+fn features_from_row(r: &SqliteRow) -> Option<Features> {
+    let chroma: Option<Vec<u8>> = r.get("chroma");
+    Some(Features {
+        tempo: r.get::<Option<f64>, _>("tempo")? as f32,
+        centroid: r.get::<Option<f64>, _>("centroid")? as f32,
+        rolloff: r.get::<Option<f64>, _>("rolloff")? as f32,
+        loudness: r.get::<Option<f64>, _>("loudness")? as f32,
+        chroma: chroma_from_bytes(&chroma?)?,
+    })
+}
+
+#[derive(Clone)]
+pub struct SimilarityStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SimilarityStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Record the analysis of `track_id` at file version `stamp`. `None`
+    /// marks a file that could not be analysed, so it is not retried until
+    /// it changes.
+    pub async fn save(
+        &self,
+        track_id: &str,
+        stamp: i64,
+        features: Option<&Features>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO track_features
+              (track_id, tempo, centroid, rolloff, loudness, chroma, stamp, analyzed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT(track_id) DO UPDATE SET
+              tempo = excluded.tempo,
+              centroid = excluded.centroid,
+              rolloff = excluded.rolloff,
+              loudness = excluded.loudness,
+              chroma = excluded.chroma,
+              stamp = excluded.stamp,
+              analyzed_at = excluded.analyzed_at
+            "#,
+        )
+        .bind(track_id)
+        .bind(features.map(|f| f.tempo as f64))
+        .bind(features.map(|f| f.centroid as f64))
+        .bind(features.map(|f| f.rolloff as f64))
+        .bind(features.map(|f| f.loudness as f64))
+        .bind(features.map(|f| chroma_to_bytes(&f.chroma)))
+        .bind(stamp)
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, track_id: &str) -> Result<Option<Features>> {
+        let row = sqlx::query("SELECT * FROM track_features WHERE track_id = $1")
+            .bind(track_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.as_ref().and_then(features_from_row))
+    }
+
+    /// Feature vectors of every analysed track.
+    pub async fn vectors(&self) -> Result<Vec<(String, [f32; DIMENSIONS])>> {
+        let rows = sqlx::query("SELECT * FROM track_features WHERE chroma IS NOT NULL")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .filter_map(|r| Some((r.get("track_id"), features_from_row(r)?.vector())))
+            .collect())
+    }
+
+    /// Tracks whose last analysis gave nothing: undecodable, silent or too
+    /// short files.
+    pub async fn unanalysable(&self) -> Result<Vec<String>> {
+        Ok(
+            sqlx::query_scalar("SELECT track_id FROM track_features WHERE chroma IS NULL")
+                .fetch_all(&self.pool)
+                .await?,
+        )
+    }
+
+    /// Drop the features of tracks that left the library.
+    pub async fn prune(&self) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM track_features WHERE track_id NOT IN (SELECT id FROM track)")
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Up to `limit` analysed tracks closest to the seeds (their mean when
+    /// there are several), nearest first, with their distance. Seeds are
+    /// left out; an unanalysed seed set gives an empty list.
+    pub async fn nearest(&self, seed_ids: &[String], limit: usize) -> Result<Vec<(String, f32)>> {
+        let rows = sqlx::query("SELECT * FROM track_features WHERE chroma IS NOT NULL")
+            .fetch_all(&self.pool)
+            .await?;
+        let mut seeds = Vec::new();
+        let mut candidates = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let Some(features) = features_from_row(row) else {
+                continue;
+            };
+            let id: String = row.get("track_id");
+            match seed_ids.contains(&id) {
+                true => seeds.push(features.vector()),
+                false => candidates.push((id, features.vector())),
+            }
+        }
+        let Some(center) = analysis::centroid(&seeds) else {
+            return Ok(Vec::new());
+        };
+        let mut scored: Vec<(String, f32)> = candidates
+            .into_iter()
+            .map(|(id, vector)| (id, analysis::distance(&center, &vector)))
+            .collect();
+        scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    /// [`SimilarityStore::nearest`] resolved to library tracks.
+    pub async fn similar_tracks(&self, seed_ids: &[String], limit: usize) -> Result<Vec<Track>> {
+        let mut tracks = Vec::new();
+        for (id, _) in self.nearest(seed_ids, limit).await? {
+            if let Some(track) = repo::track::find(self.pool.clone(), &id).await? {
+                tracks.push(track);
+            }
+        }
+        Ok(tracks)
+    }
+
+    /// Tracklist of a radio started from `track_id`: the seed, then the
+    /// tracks that sound closest to it. Until the seed has been analysed,
+    /// the rest of its artist's tracks stand in for the neighbours.
+    pub async fn radio(&self, track_id: &str, limit: usize) -> Result<Vec<Track>> {
+        let seed = match repo::track::find(self.pool.clone(), track_id).await? {
+            Some(seed) => seed,
+            None => return Ok(Vec::new()),
+        };
+        let mut tracks = self
+            .similar_tracks(&[seed.id.clone()], limit.saturating_sub(1))
+            .await?;
+        if tracks.is_empty() {
+            tracks = repo::artist_tracks::find_by_artist(self.pool.clone(), &seed.artist_id)
+                .await?
+                .into_iter()
+                .filter(|t| t.id != seed.id)
+                .take(limit.saturating_sub(1))
+                .collect();
+        }
+        tracks.insert(0, seed);
+        Ok(tracks)
+    }
+
+    /// Analyse every local music track that is new or changed since its
+    /// last analysis. Returns how many were analysed.
+    pub async fn analyze_pending(&self) -> Result<usize> {
+        let stamps: HashMap<String, i64> =
+            sqlx::query("SELECT track_id, stamp FROM track_features")
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(|r| (r.get("track_id"), r.get("stamp")))
+                .collect();
+
+        let mut analyzed = 0;
+        for track in repo::track::all(self.pool.clone()).await? {
+            if track.is_remote || track.is_book() {
+                continue;
+            }
+            let Some(stamp) = file_stamp(Path::new(&track.path)) else {
+                continue;
+            };
+            if stamps.get(&track.id) == Some(&stamp) {
+                continue;
+            }
+            let path = track.path.clone();
+            let features = tokio::task::spawn_blocking(move || analyze_file(Path::new(&path)))
+                .await?
+                .unwrap_or_else(|e| {
+                    debug!("similarity: cannot analyse {}: {}", track.path, e);
+                    None
+                });
+            self.save(&track.id, stamp, features.as_ref()).await?;
+            analyzed += 1;
+        }
+        Ok(analyzed)
+    }
+}
+
+/// Analyse new and changed tracks a minute after startup, then every
+/// `ROCKBOX_ANALYSIS_INTERVAL_SECS` seconds (default `3600`, `0` disables).
+pub fn start_analysis_task(store: SimilarityStore) {
+    let secs: u64 = std::env::var("ROCKBOX_ANALYSIS_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600);
+    if secs == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        let start = tokio::time::Instant::now() + Duration::from_secs(60);
+        let mut interval = tokio::time::interval_at(start, Duration::from_secs(secs));
+        loop {
+            interval.tick().await;
+            match store.prune().await {
+                Ok(0) => {}
+                Ok(n) => info!("similarity: dropped features of {} removed tracks", n),
+                Err(e) => error!("similarity: prune failed: {}", e),
+            }
+            match store.analyze_pending().await {
+                Ok(0) => {}
+                Ok(n) => info!("similarity: analysed {} tracks", n),
+                Err(e) => error!("similarity: analysis failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    async fn store() -> SimilarityStore {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        pool.execute(include_str!(
+            "../../library/migrations/20261019000700_add_track_features.sql"
+        ))
+        .await
+        .unwrap();
+        SimilarityStore::new(pool)
+    }
+
+    fn features(tempo: f32, centroid: f32, pitch_class: usize) -> Features {
+        let mut chroma = [0.0; 12];
+        chroma[pitch_class] = 1.0;
+        Features {
+            tempo,
+            centroid,
+            rolloff: centroid * 2.0,
+            loudness: -12.0,
+            chroma,
+        }
+    }
+
+    #[tokio::test]
+    async fn nearest_tracks_are_ranked_by_distance() {
+        let store = store().await;
+        store
+            .save("seed", 1, Some(&features(120.0, 1500.0, 0)))
+            .await
+            .unwrap();
+        store
+            .save("close", 1, Some(&features(122.0, 1600.0, 0)))
+            .await
+            .unwrap();
+        store
+            .save("far", 1, Some(&features(70.0, 4000.0, 6)))
+            .await
+            .unwrap();
+        store.save("broken", 1, None).await.unwrap();
+
+        let seed = vec!["seed".to_string()];
+        let ids: Vec<String> = store
+            .nearest(&seed, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, ["close", "far"]);
+        assert_eq!(store.nearest(&seed, 1).await.unwrap().len(), 1);
+        assert!(store
+            .nearest(&["broken".to_string()], 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store.get("close").await.unwrap(),
+            Some(features(122.0, 1600.0, 0))
+        );
+        assert_eq!(store.get("broken").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,823 @@
+//! Remote library sources.
+//!
+//! A source is a Subsonic, Jellyfin, Plex, Kodi or UPnP server whose
+//! catalogue is mirrored into the library, so its tracks show up in search,
+//! smart playlists and every listing next to the local ones. Synced tracks
+//! remember where they came from (`source_id`, `remote_id`) and use the
+//! stream URL, without credentials, as their path; netstream adds the
+//! source's credentials from [`credentials`] when it plays one.
+//! When a source prefers local copies, a track the library already has from
+//! elsewhere is synced hidden instead of showing up twice.
+
+pub mod credentials;
+pub mod remote;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use remote::RemoteTrack;
+use rockbox_health::duplicates::normalize;
+use rockbox_library::{
+    changes,
+    entity::{
+        album::Album, album_tracks::AlbumTracks, artist::Artist, artist_tracks::ArtistTracks,
+        track::Track,
+    },
+    repo,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Kinds of server a source can point at.
+pub mod kind {
+    pub const SUBSONIC: &str = "subsonic";
+    pub const JELLYFIN: &str = "jellyfin";
+    pub const PLEX: &str = "plex";
+    pub const KODI: &str = "kodi";
+    pub const UPNP: &str = "upnp";
+    pub const ALL: &[&str] = &[SUBSONIC, JELLYFIN, PLEX, KODI, UPNP];
+}
+
+/// Sync states of a source.
+pub mod status {
+    pub const NEW: &str = "new";
+    pub const SYNCING: &str = "syncing";
+    pub const OK: &str = "ok";
+    pub const ERROR: &str = "error";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteSource {
+    pub id: String,
+    pub kind: String,
+    pub name: String,
+    /// Server URL; for UPnP, the ContentDirectory control URL.
+    pub url: String,
+    pub username: Option<String>,
+    /// Password, API key (Jellyfin without a username) or token (Plex).
+    /// Never sent back to clients.
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+    pub enabled: bool,
+    /// Sync tracks the library already has from elsewhere hidden.
+    pub prefer_local: bool,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub last_synced: Option<i64>,
+    pub track_count: i64,
+    /// Tracks found on this source and elsewhere in the library on the
+    /// last sync.
+    pub conflict_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewSource {
+    pub kind: String,
+    /// Defaults to the URL.
+    #[serde(default)]
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Defaults to `true`.
+    pub prefer_local: Option<bool>,
+}
+
+/// Fields to change on a source; `None` keeps the current value.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SourceUpdate {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub enabled: Option<bool>,
+    pub prefer_local: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub conflicts: usize,
+}
+
+const SOURCE_COLUMNS: &str = "id, kind, name, url, username, password, enabled, prefer_local, \
+     status, error_message, last_synced, \
+     (SELECT COUNT(*) FROM track t WHERE t.source_id = remote_source.id), \
+     conflict_count, created_at, updated_at";
+
+fn source_from_row(r: SqliteRow) -> RemoteSource {
+    RemoteSource {
+        id: r.get(0),
+        kind: r.get(1),
+        name: r.get(2),
+        url: r.get(3),
+        username: r.get(4),
+        password: r.get(5),
+        enabled: r.get::<i64, _>(6) != 0,
+        prefer_local: r.get::<i64, _>(7) != 0,
+        status: r.get(8),
+        error_message: r.get(9),
+        last_synced: r.get(10),
+        track_count: r.get(11),
+        conflict_count: r.get(12),
+        created_at: r.get(13),
+        updated_at: r.get(14),
+    }
+}
+
+/// Ids of the sources being synced, shared by every [`SourceStore`].
+fn syncing() -> &'static Mutex<HashSet<String>> {
+    static SYNCING: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    SYNCING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// What two copies of a recording have in common, whatever their tags'
+/// spelling. `None` for untitled tracks, which are never conflicts.
+fn conflict_key(artist: &str, album: &str, title: &str) -> Option<(String, String, String)> {
+    let title = normalize(title);
+    (!title.is_empty()).then(|| (normalize(artist), normalize(album), title))
+}
+
+/// A synced track as its server last listed it.
+fn as_remote(track: &Track) -> RemoteTrack {
+    RemoteTrack {
+        remote_id: track.remote_id.clone().unwrap_or_default(),
+        title: track.title.clone(),
+        artist: track.artist.clone(),
+        album_artist: track.album_artist.clone(),
+        album: track.album.clone(),
+        year: track.year,
+        track_number: track.track_number,
+        disc_number: track.disc_number,
+        length: track.length,
+        genre: track.genre.clone(),
+        stream_url: track.path.clone(),
+    }
+}
+
+#[derive(Clone)]
+pub struct SourceStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SourceStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<RemoteSource>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SOURCE_COLUMNS} FROM remote_source ORDER BY name COLLATE NOCASE ASC"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(source_from_row).collect())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<RemoteSource>> {
+        let row = sqlx::query(&format!(
+            "SELECT {SOURCE_COLUMNS} FROM remote_source WHERE id = ?"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(source_from_row))
+    }
+
+    /// Add a source. It is synced on the next periodic run, or right away
+    /// through [`SourceStore::sync`].
+    pub async fn create(&self, source: NewSource) -> Result<RemoteSource> {
+        if !kind::ALL.contains(&source.kind.as_str()) {
+            return Err(anyhow!("unknown source kind: {}", source.kind));
+        }
+        let url = source.url.trim();
+        if url.is_empty() {
+            return Err(anyhow!("source url is required"));
+        }
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+        let name = match source.name.trim() {
+            "" => url,
+            name => name,
+        };
+        sqlx::query(
+            "INSERT INTO remote_source (id, kind, name, url, username, password, enabled, \
+             prefer_local, status, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&source.kind)
+        .bind(name)
+        .bind(url)
+        .bind(&source.username)
+        .bind(&source.password)
+        .bind(source.prefer_local.unwrap_or(true) as i64)
+        .bind(status::NEW)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        self.get(&id)
+            .await?
+            .ok_or_else(|| anyhow!("remote source disappeared"))
+    }
+
+    pub async fn update(&self, id: &str, update: SourceUpdate) -> Result<Option<RemoteSource>> {
+        let result = sqlx::query(
+            "UPDATE remote_source SET name = COALESCE(?, name), url = COALESCE(?, url), \
+             username = COALESCE(?, username), password = COALESCE(?, password), \
+             enabled = COALESCE(?, enabled), prefer_local = COALESCE(?, prefer_local), \
+             updated_at = ? WHERE id = ?",
+        )
+        .bind(update.name.as_deref().map(str::trim))
+        .bind(update.url.as_deref().map(str::trim))
+        .bind(&update.username)
+        .bind(&update.password)
+        .bind(update.enabled.map(|e| e as i64))
+        .bind(update.prefer_local.map(|p| p as i64))
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let source = self.get(id).await?;
+        if let Some(source) = &source {
+            self.sign_in(source).await;
+        }
+        Ok(source)
+    }
+
+    /// Remove a source along with every track synced from it.
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let removed = self
+            .remove_tracks(repo::track::find_by_source(self.pool.clone(), id).await?)
+            .await?;
+        let result = sqlx::query("DELETE FROM remote_source WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        credentials::forget(id);
+        if removed > 0 {
+            changes::library_changed();
+        }
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_tracks(&self, tracks: Vec<Track>) -> Result<usize> {
+        for track in &tracks {
+            repo::track::delete_by_path(self.pool.clone(), &track.path).await?;
+        }
+        Ok(tracks.len())
+    }
+
+    async fn set_status(&self, id: &str, status: &str, error_message: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE remote_source SET status = ?, error_message = ? WHERE id = ?")
+            .bind(status)
+            .bind(error_message)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ── Sync ───────────────────────────────────────────────────────────────
+
+    /// Mirror the source's catalogue into the library: add new tracks,
+    /// refresh changed ones and remove those gone from the server. Errors
+    /// are recorded on the source, and leave its tracks as they were.
+    pub async fn sync(&self, id: &str) -> Result<SyncSummary> {
+        let source = self
+            .get(id)
+            .await?
+            .ok_or_else(|| anyhow!("remote source not found"))?;
+        if !syncing().lock().unwrap().insert(source.id.clone()) {
+            return Err(anyhow!("{} is already syncing", source.name));
+        }
+        let result = self.run_sync(&source).await;
+        syncing().lock().unwrap().remove(&source.id);
+        if let Err(e) = &result {
+            self.set_status(&source.id, status::ERROR, Some(&e.to_string()))
+                .await?;
+        }
+        result
+    }
+
+    async fn run_sync(&self, source: &RemoteSource) -> Result<SyncSummary> {
+        self.set_status(&source.id, status::SYNCING, None).await?;
+        remote::sign_in(source).await?;
+        let tracks = remote::fetch(source).await?;
+        let summary = self.apply(source, tracks).await?;
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "UPDATE remote_source SET status = ?, error_message = NULL, last_synced = ?, \
+             conflict_count = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(status::OK)
+        .bind(now)
+        .bind(summary.conflicts as i64)
+        .bind(now)
+        .bind(&source.id)
+        .execute(&self.pool)
+        .await?;
+        if summary.added + summary.updated + summary.removed > 0 {
+            changes::library_changed();
+        }
+        info!(
+            "sources: {} synced, {} added, {} updated, {} removed, {} conflicts",
+            source.name, summary.added, summary.updated, summary.removed, summary.conflicts
+        );
+        Ok(summary)
+    }
+
+    async fn apply(&self, source: &RemoteSource, tracks: Vec<RemoteTrack>) -> Result<SyncSummary> {
+        let mut existing: HashMap<String, Track> =
+            repo::track::find_by_source(self.pool.clone(), &source.id)
+                .await?
+                .into_iter()
+                .filter_map(|t| Some((t.remote_id.clone()?, t)))
+                .collect();
+        // Visible tracks from the local library and other sources.
+        let elsewhere: HashSet<(String, String, String)> = match source.prefer_local {
+            true => repo::track::all(self.pool.clone())
+                .await?
+                .iter()
+                .filter(|t| t.source_id.as_deref() != Some(source.id.as_str()))
+                .filter_map(|t| conflict_key(&t.artist, &t.album, &t.title))
+                .collect(),
+            false => HashSet::new(),
+        };
+
+        let mut summary = SyncSummary::default();
+        let mut seen = HashSet::new();
+        for remote in tracks {
+            if !seen.insert(remote.remote_id.clone()) {
+                continue;
+            }
+            let conflict = conflict_key(&remote.artist, &remote.album, &remote.title)
+                .is_some_and(|key| elsewhere.contains(&key));
+            if conflict {
+                summary.conflicts += 1;
+            }
+            match existing.remove(&remote.remote_id) {
+                Some(track) if as_remote(&track) == remote => {}
+                Some(track) => {
+                    let updated = Track {
+                        path: remote.stream_url,
+                        title: remote.title,
+                        artist: remote.artist,
+                        album: remote.album,
+                        album_artist: remote.album_artist,
+                        genre: remote.genre,
+                        year: remote.year,
+                        track_number: remote.track_number,
+                        disc_number: remote.disc_number,
+                        length: remote.length,
+                        ..track
+                    };
+                    repo::track::update_from_source(self.pool.clone(), &updated).await?;
+                    summary.updated += 1;
+                }
+                None => {
+                    let track_id = self.insert(source, remote).await?;
+                    // Existing tracks keep whatever visibility the user gave them.
+                    if conflict {
+                        repo::track::set_hidden(self.pool.clone(), &track_id, true).await?;
+                    }
+                    summary.added += 1;
+                }
+            }
+        }
+        summary.removed = self.remove_tracks(existing.into_values().collect()).await?;
+        Ok(summary)
+    }
+
+    /// Save a new remote track with its album and artist, the way the
+    /// scanner saves local files, so remote albums merge with local ones
+    /// of the same name.
+    async fn insert(&self, source: &RemoteSource, remote: RemoteTrack) -> Result<String> {
+        let year = remote.year.unwrap_or(0);
+        let artist_id = repo::artist::save(
+            self.pool.clone(),
+            Artist {
+                id: cuid::cuid1()?,
+                name: remote.artist.clone(),
+                bio: None,
+                image: None,
+                genres: None,
+            },
+        )
+        .await?;
+        let album_md5 = format!(
+            "{:x}",
+            md5::compute(format!("{}{}{}", remote.album_artist, remote.album, year).as_bytes())
+        );
+        let album_id = repo::album::save(
+            self.pool.clone(),
+            Album {
+                id: cuid::cuid1()?,
+                title: remote.album.clone(),
+                artist: remote.album_artist.clone(),
+                year,
+                year_string: remote.year.map(|y| y.to_string()).unwrap_or_default(),
+                album_art: None,
+                md5: album_md5,
+                artist_id: artist_id.clone(),
+                label: None,
+                copyright_message: None,
+            },
+        )
+        .await?;
+
+        let track_md5 = format!(
+            "{:x}",
+            md5::compute(format!("{}:{}", source.id, remote.remote_id).as_bytes())
+        );
+        let track_id = repo::track::save(
+            self.pool.clone(),
+            Track {
+                id: cuid::cuid1()?,
+                path: remote.stream_url,
+                title: remote.title,
+                artist: remote.artist,
+                album: remote.album,
+                album_artist: remote.album_artist,
+                genre: remote.genre,
+                year: remote.year,
+                year_string: remote.year.map(|y| y.to_string()),
+                track_number: remote.track_number,
+                disc_number: remote.disc_number,
+                length: remote.length,
+                md5: track_md5,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                artist_id: artist_id.clone(),
+                album_id: album_id.clone(),
+                is_remote: true,
+                source_id: Some(source.id.clone()),
+                remote_id: Some(remote.remote_id),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        repo::album_tracks::save(
+            self.pool.clone(),
+            AlbumTracks {
+                id: cuid::cuid1()?,
+                album_id,
+                track_id: track_id.clone(),
+            },
+        )
+        .await?;
+        repo::artist_tracks::save(
+            self.pool.clone(),
+            ArtistTracks {
+                id: cuid::cuid1()?,
+                artist_id,
+                track_id: track_id.clone(),
+            },
+        )
+        .await?;
+        Ok(track_id)
+    }
+
+    /// Sign in to the source's server so its tracks play; a failure is
+    /// logged and leaves them unplayable until it succeeds.
+    async fn sign_in(&self, source: &RemoteSource) {
+        if let Err(e) = remote::sign_in(source).await {
+            warn!("sources: signing in to {} failed: {}", source.name, e);
+        }
+    }
+
+    /// Sign in to every source's server.
+    pub async fn sign_in_all(&self) -> Result<()> {
+        for source in self.list().await? {
+            self.sign_in(&source).await;
+        }
+        Ok(())
+    }
+
+    /// Sync every enabled source; one failing server does not stop the
+    /// others. Returns how many synced.
+    pub async fn sync_all(&self) -> Result<usize> {
+        let mut synced = 0;
+        for source in self.list().await? {
+            if !source.enabled {
+                continue;
+            }
+            match self.sync(&source.id).await {
+                Ok(_) => synced += 1,
+                Err(e) => warn!("sources: syncing {} failed: {}", source.name, e),
+            }
+        }
+        Ok(synced)
+    }
+}
+
+/// Sign in to every source right away, so synced tracks play, then sync
+/// every enabled source a minute after startup and every
+/// `ROCKBOX_SOURCES_SYNC_SECS` seconds (default `21600`, `0` disables).
+pub fn start_sync_task(store: SourceStore) {
+    let secs: u64 = std::env::var("ROCKBOX_SOURCES_SYNC_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(21600);
+    tokio::spawn(async move {
+        if let Err(e) = store.sign_in_all().await {
+            error!("sources: sign-in failed: {}", e);
+        }
+        if secs == 0 {
+            return;
+        }
+        let start = tokio::time::Instant::now() + Duration::from_secs(60);
+        let mut interval = tokio::time::interval_at(start, Duration::from_secs(secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = store.sync_all().await {
+                error!("sources: sync failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Mock, ServerGuard};
+    use sqlx::{sqlite::SqlitePoolOptions, Executor};
+
+    async fn store() -> (SourceStore, Pool<Sqlite>) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for migration in [
+            include_str!("../../library/migrations/20240923093823_create_tables.sql"),
+            include_str!("../../library/migrations/20241011011557_add_artist_id_column.sql"),
+            include_str!("../../library/migrations/20241020125757_add-album_id-column.sql"),
+            include_str!("../../library/migrations/20251218042124_add_album_label.sql"),
+            include_str!("../../library/migrations/20251218044147_add_album_copyright_message.sql"),
+            include_str!("../../library/migrations/20260425000000_add_playlist_tables.sql"),
+            include_str!("../../library/migrations/20260428000000_add_is_remote_to_track.sql"),
+            include_str!("../../library/migrations/20261019000200_add_track_media_type.sql"),
+            include_str!("../../library/migrations/20261019000300_add_audiobook_tables.sql"),
+            include_str!("../../library/migrations/20261019000400_add_track_lyrics.sql"),
+            include_str!("../../library/migrations/20261019001000_add_ratings.sql"),
+            include_str!("../../library/migrations/20261019001100_add_track_hidden.sql"),
+            include_str!("../../library/migrations/20261019001200_add_remote_sources.sql"),
+        ] {
+            pool.execute(migration).await.unwrap();
+        }
+        // SQLite rejects the trailing ADD CONSTRAINT; the statements before
+        // it still apply.
+        let _ = pool
+            .execute(include_str!(
+                "../../library/migrations/20251218173111_add_artist_genres.sql"
+            ))
+            .await;
+        (SourceStore::new(pool.clone()), pool)
+    }
+
+    fn song(id: &str, title: &str, track: u32) -> String {
+        format!(
+            r#"{{"id":"{id}","title":"{title}","artist":"Miles Davis","album":"Kind of Blue","duration":300,"track":{track},"year":1959}}"#
+        )
+    }
+
+    /// A Subsonic server with one album holding `songs`.
+    async fn stub(server: &mut ServerGuard, songs: &[String]) -> Vec<Mock> {
+        let albums = server
+            .mock("GET", Matcher::Regex(r"^/rest/getAlbumList2\.view".to_string()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"subsonic-response":{"status":"ok","albumList2":{"album":[{"id":"al-1","name":"Kind of Blue","artist":"Miles Davis"}]}}}"#,
+            )
+            .create_async()
+            .await;
+        let album = server
+            .mock("GET", Matcher::Regex(r"^/rest/getAlbum\.view".to_string()))
+            .match_query(Matcher::UrlEncoded("id".to_string(), "al-1".to_string()))
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"subsonic-response":{{"status":"ok","album":{{"id":"al-1","name":"Kind of Blue","artist":"Miles Davis","song":[{}]}}}}}}"#,
+                songs.join(",")
+            ))
+            .create_async()
+            .await;
+        vec![albums, album]
+    }
+
+    async fn unstub(mocks: Vec<Mock>) {
+        for mock in mocks {
+            mock.remove_async().await;
+        }
+    }
+
+    async fn subsonic_source(store: &SourceStore, url: String) -> RemoteSource {
+        store
+            .create(NewSource {
+                kind: kind::SUBSONIC.to_string(),
+                name: "Navidrome".to_string(),
+                url,
+                username: Some("admin".to_string()),
+                password: Some("secret".to_string()),
+                prefer_local: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn sync_mirrors_subsonic_catalogue() {
+        let mut server = mockito::Server::new_async().await;
+        let (store, pool) = store().await;
+        let source = subsonic_source(&store, server.url()).await;
+        assert_eq!(source.status, status::NEW);
+
+        let mocks = stub(
+            &mut server,
+            &[
+                song("s-1", "So What", 1),
+                song("s-2", "Freddie Freeloader", 2),
+            ],
+        )
+        .await;
+        let summary = store.sync(&source.id).await.unwrap();
+        assert_eq!(summary.added, 2);
+
+        let tracks = repo::track::all(pool.clone()).await.unwrap();
+        assert_eq!(tracks.len(), 2);
+        let so_what = tracks.iter().find(|t| t.title == "So What").unwrap();
+        assert!(so_what.is_remote);
+        assert_eq!(so_what.source_id.as_deref(), Some(source.id.as_str()));
+        assert_eq!(so_what.remote_id.as_deref(), Some("s-1"));
+        assert_eq!(so_what.length, 300_000);
+        // The path carries no credentials; netstream adds them.
+        assert_eq!(
+            so_what.path,
+            format!("{}/rest/stream.view?id=s-1", server.url())
+        );
+        let stream = credentials::authenticate(&so_what.path).unwrap();
+        assert!(stream.starts_with(&format!("{}&u=admin&t=", so_what.path)));
+        let album = repo::album::find(pool.clone(), &so_what.album_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(album.title, "Kind of Blue");
+        assert_eq!(
+            repo::album_tracks::find_by_album(pool.clone(), &album.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let source = store.get(&source.id).await.unwrap().unwrap();
+        assert_eq!(source.status, status::OK);
+        assert_eq!(source.track_count, 2);
+        assert!(source.last_synced.is_some());
+
+        // Nothing changed: the stream URLs, and so the paths, are stable.
+        assert_eq!(
+            store.sync(&source.id).await.unwrap(),
+            SyncSummary::default()
+        );
+
+        // A retitled song is updated in place, a removed one deleted.
+        unstub(mocks).await;
+        let _mocks = stub(&mut server, &[song("s-1", "So What (Take 2)", 1)]).await;
+        let summary = store.sync(&source.id).await.unwrap();
+        assert_eq!((summary.updated, summary.removed), (1, 1));
+        let tracks = repo::track::all(pool.clone()).await.unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].id, so_what.id);
+        assert_eq!(tracks[0].title, "So What (Take 2)");
+
+        assert!(store.delete(&source.id).await.unwrap());
+        assert!(repo::track::all(pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn tracks_already_in_the_library_are_synced_hidden() {
+        let mut server = mockito::Server::new_async().await;
+        let (store, pool) = store().await;
+        repo::track::save(
+            pool.clone(),
+            Track {
+                id: "local".to_string(),
+                path: "/music/so_what.flac".to_string(),
+                md5: "local".to_string(),
+                title: "So What".to_string(),
+                artist: "Miles Davis".to_string(),
+                album: "Kind of Blue".to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let source = subsonic_source(&store, server.url()).await;
+        let _mocks = stub(
+            &mut server,
+            &[song("s-1", "So What", 1), song("s-2", "Blue in Green", 3)],
+        )
+        .await;
+
+        let summary = store.sync(&source.id).await.unwrap();
+        assert_eq!((summary.added, summary.conflicts), (2, 1));
+        let visible: Vec<String> = repo::track::all(pool.clone())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(visible.len(), 2);
+        assert!(visible.contains(&"local".to_string()));
+        let hidden = repo::track::hidden(pool.clone()).await.unwrap();
+        assert_eq!(hidden.len(), 1);
+        assert_eq!(hidden[0].remote_id.as_deref(), Some("s-1"));
+        let source = store.get(&source.id).await.unwrap().unwrap();
+        assert_eq!((source.track_count, source.conflict_count), (2, 1));
+
+        let updated = store
+            .update(
+                &source.id,
+                SourceUpdate {
+                    prefer_local: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!updated.prefer_local);
+        assert_eq!(updated.username.as_deref(), Some("admin"));
+
+        // Without the preference both copies show.
+        store.delete(&source.id).await.unwrap();
+        let source = store
+            .create(NewSource {
+                kind: kind::SUBSONIC.to_string(),
+                url: server.url(),
+                username: Some("admin".to_string()),
+                prefer_local: Some(false),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(source.name, server.url());
+        let summary = store.sync(&source.id).await.unwrap();
+        assert_eq!((summary.added, summary.conflicts), (2, 0));
+        assert_eq!(repo::track::all(pool).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_sync_is_recorded_and_keeps_tracks() {
+        let mut server = mockito::Server::new_async().await;
+        let (store, pool) = store().await;
+        let source = subsonic_source(&store, server.url()).await;
+        let mocks = stub(&mut server, &[song("s-1", "So What", 1)]).await;
+        store.sync(&source.id).await.unwrap();
+
+        unstub(mocks).await;
+        let _down = server
+            .mock("GET", Matcher::Any)
+            .with_status(500)
+            .with_body("{}")
+            .create_async()
+            .await;
+        assert!(store.sync(&source.id).await.is_err());
+        let source = store.get(&source.id).await.unwrap().unwrap();
+        assert_eq!(source.status, status::ERROR);
+        assert!(source.error_message.is_some());
+        assert_eq!(source.track_count, 1);
+        assert_eq!(repo::track::all(pool).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_kinds_are_rejected() {
+        let (store, _) = store().await;
+        let result = store
+            .create(NewSource {
+                kind: "napster".to_string(),
+                url: "http://localhost".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(store.list().await.unwrap().is_empty());
+    }
+}
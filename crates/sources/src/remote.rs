@@ -0,0 +1,200 @@
+//! Catalogue listing for each kind of remote server, on top of the browse
+//! clients in the per-service crates.
+
+use anyhow::{anyhow, Result};
+
+use uuid::Uuid;
+
+use crate::{
+    credentials::{self, without_query, without_userinfo, Credentials},
+    kind, RemoteSource,
+};
+
+/// A track as listed by a remote server.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RemoteTrack {
+    /// The server's own id for the track, stable across syncs.
+    pub remote_id: String,
+    pub title: String,
+    pub artist: String,
+    pub album_artist: String,
+    pub album: String,
+    pub year: Option<u32>,
+    pub track_number: Option<u32>,
+    pub disc_number: u32,
+    /// Length in milliseconds.
+    pub length: u32,
+    pub genre: Option<String>,
+    /// Where netstream plays the track from, without credentials.
+    pub stream_url: String,
+}
+
+/// A fresh Subsonic salt and the token it makes with `password`.
+fn subsonic_token(password: &str) -> (String, String) {
+    let salt = Uuid::new_v4().simple().to_string();
+    (rockbox_navidrome::compute_token(password, &salt), salt)
+}
+
+/// Sign in to Jellyfin: a username signs in with the password; without one
+/// the password is taken to be an API key. Returns the access token and
+/// user id.
+async fn jellyfin_session(
+    url: &str,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> Result<(String, String)> {
+    match username {
+        Some(user) => rockbox_jellyfin::authenticate(url, user, password.unwrap_or_default()).await,
+        None => {
+            let key = password.ok_or_else(|| anyhow!("Jellyfin sources need an API key"))?;
+            rockbox_jellyfin::authenticate_with_api_key(url, key).await
+        }
+    }
+    .ok_or_else(|| anyhow!("Jellyfin authentication failed"))
+}
+
+/// Sign in to the source's server and remember the credentials its
+/// stream URLs need, for [`crate::credentials::authenticate`].
+pub async fn sign_in(source: &RemoteSource) -> Result<()> {
+    let url = source.url.trim_end_matches('/');
+    let username = source.username.as_deref().filter(|u| !u.is_empty());
+    let password = source.password.as_deref().filter(|p| !p.is_empty());
+    let credentials = match source.kind.as_str() {
+        kind::SUBSONIC => {
+            let user = username.ok_or_else(|| anyhow!("Subsonic sources need a username"))?;
+            let (token, salt) = subsonic_token(password.unwrap_or_default());
+            Some(Credentials::Query(rockbox_navidrome::auth_params(
+                user, &token, &salt,
+            )))
+        }
+        kind::JELLYFIN => {
+            let (token, _) = jellyfin_session(url, username, password).await?;
+            Some(Credentials::Query(format!("api_key={}", token)))
+        }
+        // The password holds the X-Plex-Token.
+        kind::PLEX => password.map(|token| Credentials::Query(format!("X-Plex-Token={}", token))),
+        kind::KODI => username
+            .zip(password)
+            .map(|(user, pass)| Credentials::UserInfo {
+                user: user.to_string(),
+                pass: pass.to_string(),
+            }),
+        kind::UPNP => None,
+        other => return Err(anyhow!("unknown source kind: {}", other)),
+    };
+    credentials::set(&source.id, url, credentials);
+    Ok(())
+}
+
+/// List every track on the source's server.
+pub async fn fetch(source: &RemoteSource) -> Result<Vec<RemoteTrack>> {
+    let url = source.url.trim_end_matches('/');
+    let username = source.username.as_deref().filter(|u| !u.is_empty());
+    let password = source.password.as_deref().filter(|p| !p.is_empty());
+    match source.kind.as_str() {
+        kind::SUBSONIC => {
+            let user = username.ok_or_else(|| anyhow!("Subsonic sources need a username"))?;
+            let (token, salt) = subsonic_token(password.unwrap_or_default());
+            let songs = rockbox_navidrome::list_all_songs(url, user, &token, &salt).await?;
+            Ok(songs
+                .into_iter()
+                .map(|song| RemoteTrack {
+                    stream_url: format!("{}/rest/stream.view?id={}", url, song.id),
+                    remote_id: song.id,
+                    title: song.title,
+                    album_artist: song.artist.clone(),
+                    artist: song.artist,
+                    album: song.album,
+                    year: song.year,
+                    track_number: song.track,
+                    disc_number: 0,
+                    length: song.duration * 1000,
+                    genre: None,
+                })
+                .collect())
+        }
+        kind::JELLYFIN => {
+            let (token, user_id) = jellyfin_session(url, username, password).await?;
+            let items = rockbox_jellyfin::list_audio_items(url, &token, &user_id).await?;
+            Ok(items
+                .into_iter()
+                .map(|item| RemoteTrack {
+                    remote_id: item.id,
+                    title: item.title,
+                    artist: item.artist,
+                    album_artist: item.album_artist,
+                    album: item.album,
+                    year: item.year,
+                    track_number: item.track_number,
+                    disc_number: item.disc_number.unwrap_or(0),
+                    length: item.duration,
+                    genre: item.genre,
+                    stream_url: without_query(&item.stream_url),
+                })
+                .collect())
+        }
+        kind::PLEX => {
+            // The password holds the X-Plex-Token.
+            let tracks = rockbox_plex::list_tracks(url, password).await?;
+            Ok(tracks
+                .into_iter()
+                .map(|track| RemoteTrack {
+                    remote_id: track.rating_key,
+                    title: track.title,
+                    artist: track.artist,
+                    album_artist: track.album_artist,
+                    album: track.album,
+                    year: track.year,
+                    track_number: track.track_number,
+                    disc_number: track.disc_number.unwrap_or(0),
+                    length: track.duration,
+                    genre: None,
+                    stream_url: without_query(&track.stream_url),
+                })
+                .collect())
+        }
+        kind::KODI => {
+            let songs = rockbox_kodi::list_songs(url, username, password).await?;
+            Ok(songs
+                .into_iter()
+                .map(|song| RemoteTrack {
+                    remote_id: song.song_id.to_string(),
+                    title: song.title,
+                    artist: song.artist,
+                    album_artist: song.album_artist,
+                    album: song.album,
+                    year: song.year,
+                    track_number: song.track_number,
+                    disc_number: song.disc_number.unwrap_or(0),
+                    length: song.duration,
+                    genre: song.genre,
+                    stream_url: without_userinfo(&song.stream_url),
+                })
+                .collect())
+        }
+        kind::UPNP => {
+            // The URL is the ContentDirectory control URL.
+            let items = rockbox_upnp::control_point::list_audio_items(&source.url).await?;
+            Ok(items
+                .into_iter()
+                .filter_map(|item| {
+                    let artist = item.artist.unwrap_or_default();
+                    Some(RemoteTrack {
+                        stream_url: item.uri?,
+                        remote_id: item.id,
+                        title: item.title,
+                        album_artist: artist.clone(),
+                        artist,
+                        album: item.album.unwrap_or_default(),
+                        year: None,
+                        track_number: item.track_number,
+                        disc_number: 0,
+                        length: item.duration.unwrap_or(0),
+                        genre: None,
+                    })
+                })
+                .collect())
+        }
+        other => Err(anyhow!("unknown source kind: {}", other)),
+    }
+}
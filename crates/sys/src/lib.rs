@@ -1093,6 +1093,7 @@ extern "C" {
     fn rb_playlist_insert_track(filename: *const u8, position: i32, queue: bool, sync: bool)
         -> i32;
     fn rb_playlist_delete_track(index: i32) -> i32;
+    fn rb_playlist_move_track(index: i32, new_index: i32) -> i32;
     fn rb_playlist_insert_directory(
         dir: *const c_char,
         position: i32,
@@ -1164,6 +1165,10 @@ extern "C" {
     fn pcm_cmaf_set_http_port(port: c_ushort);
     fn pcm_cmaf_set_bitrate(bps: c_uint);
     fn pcm_cmaf_set_segment_dir(path: *const c_char);
+    fn pcm_cmaf_clear_renditions();
+    fn pcm_cmaf_add_rendition(spec: *const c_char);
+    fn pcm_cmaf_set_dvr_window(secs: c_uint);
+    fn pcm_cmaf_set_metadata(title: *const c_char, artist: *const c_char);
     fn pcm_cmaf_start() -> c_int;
     fn beep_play(frequency: c_uint, duration: c_uint, amplitude: c_uint);
     fn dsp_set_crossfeed_type(r#type: c_int);
@@ -1176,6 +1181,8 @@ extern "C" {
     fn dsp_afr_enable(var: c_int);
     fn dsp_pbe_enable(var: c_int);
     fn dsp_pbe_precut(var: c_int);
+    fn dsp_set_compressor(settings: *const CompressorSettings);
+    fn dsp_replaygain_set_settings(settings: *const ReplaygainSettings);
     fn dsp_get_timestretch() -> c_int;
     fn dsp_set_timestretch(percent: c_int);
     fn dsp_timestretch_enable(enabled: c_uchar);
@@ -1322,7 +1329,7 @@ extern "C" {
     fn semaphore_release();
     fn reset_poweroff_timer();
     fn set_sleeptimer_duration(minutes: c_int);
-    fn get_sleep_timer();
+    fn get_sleep_timer() -> c_int;
 
     // Menu
     fn root_menu_get_options();
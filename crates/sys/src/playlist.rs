@@ -125,6 +125,10 @@ pub fn delete_track(index: i32) -> i32 {
     unsafe { crate::rb_playlist_delete_track(index) }
 }
 
+pub fn move_track(index: i32, new_index: i32) -> i32 {
+    unsafe { crate::rb_playlist_move_track(index, new_index) }
+}
+
 pub fn index() -> i32 {
     unsafe { crate::rb_playlist_index() }
 }
@@ -1,4 +1,4 @@
-use crate::{DspBuffer, DspConfig, EqBandSetting};
+use crate::{CompressorSettings, DspBuffer, DspConfig, EqBandSetting, ReplaygainSettings};
 use std::ffi::c_long;
 
 pub fn set_crossfeed_type(r#type: i32) {
@@ -49,6 +49,14 @@ pub fn pbe_precut(var: i32) {
     unsafe { crate::dsp_pbe_precut(var) }
 }
 
+pub fn set_compressor(settings: &CompressorSettings) {
+    unsafe { crate::dsp_set_compressor(settings as *const CompressorSettings) }
+}
+
+pub fn replaygain_set_settings(settings: &ReplaygainSettings) {
+    unsafe { crate::dsp_replaygain_set_settings(settings as *const ReplaygainSettings) }
+}
+
 pub fn get_timestretch() -> i32 {
     unsafe { crate::dsp_get_timestretch() }
 }
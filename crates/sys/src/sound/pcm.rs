@@ -181,3 +181,27 @@ pub fn cmaf_set_segment_dir(dir: Option<&str>) {
         _ => unsafe { crate::pcm_cmaf_set_segment_dir(std::ptr::null()) },
     }
 }
+
+/// Replace the CMAF renditions (`"aac-<kbps>"` or `"flac"`). Takes effect
+/// on the next `cmaf_start`; an empty list means a single AAC rendition at
+/// the `cmaf_set_bitrate` bitrate.
+pub fn cmaf_set_renditions(renditions: &[String]) {
+    unsafe { crate::pcm_cmaf_clear_renditions() }
+    for spec in renditions {
+        let cspec = CString::new(spec.as_str()).expect("rendition must not contain null bytes");
+        unsafe { crate::pcm_cmaf_add_rendition(cspec.as_ptr()) }
+    }
+}
+
+/// How far back, in seconds, CMAF listeners can rewind. Applies live.
+pub fn cmaf_set_dvr_window(secs: u32) {
+    unsafe { crate::pcm_cmaf_set_dvr_window(secs) }
+}
+
+/// Title / artist carried as timed ID3 in the CMAF stream. The sink copies
+/// both strings, so nothing is leaked per track change.
+pub fn cmaf_set_metadata(title: &str, artist: &str) {
+    let ctitle = CString::new(title.replace('\0', "")).unwrap_or_default();
+    let cartist = CString::new(artist.replace('\0', "")).unwrap_or_default();
+    unsafe { crate::pcm_cmaf_set_metadata(ctitle.as_ptr(), cartist.as_ptr()) }
+}
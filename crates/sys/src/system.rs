@@ -122,8 +122,8 @@ pub fn set_sleeptimer_duration(minutes: i32) {
     }
 }
 
-pub fn get_sleep_timer() {
-    unsafe {
-        crate::get_sleep_timer();
-    }
+/// Seconds left on the firmware sleep timer, 0 when it is off. rockboxd
+/// keeps it off (it powers the device down); see `rockbox-scheduler`.
+pub fn get_sleep_timer() -> i32 {
+    unsafe { crate::get_sleep_timer() }
 }
@@ -737,6 +737,31 @@ pub struct NewGlobalSettings {
     pub snapcast_tcp_host: Option<String>,
     /// TCP port for the Snapcast source (default: 4953)
     pub snapcast_tcp_port: Option<u16>,
+    /// Hardware device the alsa sink opens in exclusive mode (default:
+    /// "hw:0,0").
+    pub alsa_device: Option<String>,
+    /// Open `alsa_device` directly at the source rate and the widest sample
+    /// format it takes, instead of the shared "default" PCM (default: false).
+    pub alsa_exclusive: Option<bool>,
+    /// Turn off the firmware DSP, ReplayGain and software volume while the
+    /// alsa sink is active, so samples reach the device unchanged (default:
+    /// false).
+    pub alsa_bit_perfect: Option<bool>,
+    /// Play DSF / DFF files as DoP (DSD over PCM). Needs `alsa_exclusive`
+    /// and a DoP-capable DAC (default: false).
+    pub alsa_dop: Option<bool>,
+    /// Where the cpal and alsa sinks apply the volume: "software" (default)
+    /// scales the samples, "hardware" sets an ALSA mixer element, "fixed"
+    /// leaves the level to an external amp.
+    pub mixer: Option<String>,
+    /// ALSA card the hardware mixer uses (default: the card of
+    /// `alsa_device` when the alsa sink is selected, else "default").
+    pub mixer_device: Option<String>,
+    /// Simple-mixer element the hardware mixer sets (default: "PCM").
+    pub mixer_control: Option<String>,
+    /// Dither samples the software mixer or ReplayGain scales
+    /// (default: true).
+    pub mixer_dither: Option<bool>,
     /// Subsonic/Navidrome-compatible API server username (for connecting clients).
     pub subsonic_username: Option<String>,
     /// Subsonic/Navidrome-compatible API server password.
@@ -751,11 +776,20 @@ pub struct NewGlobalSettings {
     pub cmaf_http_port: Option<u16>,
     /// AAC-LC bitrate for the CMAF sink, in bits/sec. Default: 128000.
     pub cmaf_bitrate: Option<u32>,
-    /// Optional directory the CMAF sink mirrors `init.mp4`, `seg/N.m4s`,
-    /// and the HLS/DASH manifests into. When absent, segments stay in
-    /// memory only. Useful for serving the same artefacts from an external
-    /// HTTP server (nginx, Caddy, a CDN origin).
+    /// Optional directory the CMAF sink mirrors `{rendition}/init.mp4`,
+    /// `{rendition}/seg/N.m4s`, and the HLS/DASH manifests into. When
+    /// absent, segments stay in memory only (unless `cmaf_dvr_window` needs
+    /// disk). Useful for serving the same artefacts from an external HTTP
+    /// server (nginx, Caddy, a CDN origin).
     pub cmaf_segment_dir: Option<String>,
+    /// Renditions the CMAF sink publishes side by side for adaptive
+    /// bitrate, e.g. `["aac-64", "aac-128", "aac-256", "flac"]`. Default: a
+    /// single AAC rendition at `cmaf_bitrate`.
+    pub cmaf_renditions: Option<Vec<String>>,
+    /// How far back CMAF listeners can rewind, in seconds. Default: the
+    /// ~12 s live window. Longer windows are kept on disk, in
+    /// `cmaf_segment_dir` or `~/.cache/rockbox/cmaf`.
+    pub cmaf_dvr_window: Option<u32>,
     /// Enable the S3-compatible HTTP API (default: false). Requires
     /// `s3_access_key` and `s3_secret_key` to be set.
     pub s3_enabled: Option<bool>,
@@ -779,6 +813,71 @@ pub struct NewGlobalSettings {
     /// the local library. Absent → we fall back to Last.fm's name-only
     /// output.
     pub musicbrainz_user_agent: Option<String>,
+    /// MQTT broker to publish playback, library and device events to
+    /// (e.g. the Home Assistant Mosquitto add-on). Absent → no MQTT.
+    pub mqtt_host: Option<String>,
+    /// MQTT broker port (default: 1883).
+    pub mqtt_port: Option<u16>,
+    pub mqtt_username: Option<String>,
+    pub mqtt_password: Option<String>,
+    /// Topic prefix events are published under (default: "rockbox").
+    pub mqtt_topic: Option<String>,
+    /// MQTT client id (default: "rockboxd").
+    pub mqtt_client_id: Option<String>,
+    /// Require API tokens: `false` disables checks, `true` enforces them even
+    /// before a token exists; unset enforces once one has been created.
+    pub auth_enabled: Option<bool>,
+    /// Let clients on 127.0.0.1 / ::1 in without a token (default: false).
+    /// Unsafe when a reverse proxy on the same host forwards outside traffic.
+    pub auth_trust_localhost: Option<bool>,
+    /// PEM certificate chain for the TLS listeners. TLS is on when this and
+    /// `tls_key` are set, or when `tls_self_signed` is true. Both files are
+    /// re-read on SIGHUP.
+    pub tls_cert: Option<String>,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1) matching `tls_cert`.
+    pub tls_key: Option<String>,
+    /// Generate a self-signed certificate in `~/.config/rockbox.org/tls` at
+    /// first start when no `tls_cert` is configured (default: false).
+    pub tls_self_signed: Option<bool>,
+    /// Each TLS listener binds the plain port plus this (default: 1000,
+    /// e.g. REST 6063 → 7063, gRPC 6061 → 7061).
+    pub tls_port_offset: Option<u16>,
+    /// Bind the plain HTTP/TCP listeners to 127.0.0.1 only, so remote
+    /// clients have to use TLS (default: false).
+    pub tls_only: Option<bool>,
+    /// Keep appending tracks when the queue is about to run out (default:
+    /// false).
+    pub auto_queue: Option<bool>,
+    /// Where auto-queued tracks come from: "shuffle" (default),
+    /// "instant_mix" or "smart_playlist".
+    pub auto_queue_source: Option<String>,
+    /// Smart playlist id for the "smart_playlist" source.
+    pub auto_queue_smart_playlist: Option<String>,
+    /// Seed track id for the "instant_mix" source. Absent → the tracks at
+    /// the end of the queue.
+    pub auto_queue_seed: Option<String>,
+    /// How shuffle orders the queue: "tracks" (default), "album",
+    /// "artist_spread" or "smart".
+    pub shuffle_mode: Option<String>,
+    /// Folder of playlist files kept in sync with the saved playlists.
+    /// Unset → no sync.
+    pub playlists_dir: Option<String>,
+    /// Seconds consecutive HLS / DASH streams crossfade over; 0 joins them
+    /// gapless. Unset → the firmware crossfade's fade-out duration while
+    /// crossfade is on, else 0.
+    pub stream_crossfade_secs: Option<f32>,
+    /// Gain curve of that crossfade: "equal_power" (default), "linear" or
+    /// "s_curve".
+    pub stream_crossfade_curve: Option<String>,
+    /// Loudness in dBFS MixRamp lines streams up on (default: 0).
+    pub mixramp_db: Option<f32>,
+    /// Seconds taken off the MixRamp overlap. Negative (default) turns
+    /// MixRamp off in favour of the plain crossfade.
+    pub mixramp_delay: Option<f32>,
+    /// Seconds of live HLS / DASH streams kept on disk, so they can be
+    /// paused and rewound (default: 1800). 0 turns the time-shift buffer
+    /// off.
+    pub stream_timeshift_window: Option<u32>,
 }
 
 impl From<UserSettings> for NewGlobalSettings {
@@ -843,6 +942,14 @@ impl From<UserSettings> for NewGlobalSettings {
             chromecast_http_port: None,
             snapcast_tcp_host: None,
             snapcast_tcp_port: None,
+            alsa_device: None,
+            alsa_exclusive: None,
+            alsa_bit_perfect: None,
+            alsa_dop: None,
+            mixer: None,
+            mixer_device: None,
+            mixer_control: None,
+            mixer_dither: None,
             subsonic_username: None,
             subsonic_password: None,
             subsonic_port: None,
@@ -850,6 +957,8 @@ impl From<UserSettings> for NewGlobalSettings {
             cmaf_http_port: None,
             cmaf_bitrate: None,
             cmaf_segment_dir: None,
+            cmaf_renditions: None,
+            cmaf_dvr_window: None,
             s3_enabled: None,
             s3_host: None,
             s3_port: None,
@@ -857,6 +966,30 @@ impl From<UserSettings> for NewGlobalSettings {
             s3_secret_key: None,
             lastfm_api_key: None,
             musicbrainz_user_agent: None,
+            mqtt_host: None,
+            mqtt_port: None,
+            mqtt_username: None,
+            mqtt_password: None,
+            mqtt_topic: None,
+            mqtt_client_id: None,
+            auth_enabled: None,
+            auth_trust_localhost: None,
+            tls_cert: None,
+            tls_key: None,
+            tls_self_signed: None,
+            tls_port_offset: None,
+            tls_only: None,
+            auto_queue: None,
+            auto_queue_source: None,
+            auto_queue_smart_playlist: None,
+            auto_queue_seed: None,
+            shuffle_mode: None,
+            playlists_dir: None,
+            stream_crossfade_secs: None,
+            stream_crossfade_curve: None,
+            mixramp_db: None,
+            mixramp_delay: None,
+            stream_timeshift_window: None,
         }
     }
 }
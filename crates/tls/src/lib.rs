@@ -0,0 +1,380 @@
+//! Optional rustls termination for every rockboxd listener.
+//!
+//! TLS is configured once in `settings.toml` (`tls_cert`/`tls_key`, or
+//! `tls_self_signed`) and shared by the REST, GraphQL, gRPC, Subsonic,
+//! Jellyfin, S3 and CMAF servers. Each of them keeps its plain listener —
+//! the in-process clients talk to each other over 127.0.0.1 — and adds a
+//! TLS one on the plain port plus `tls_port_offset`.
+//!
+//! The certificate and key are re-read on SIGHUP; connections made after
+//! the signal get the new certificate, open ones keep the old one.
+
+use std::{
+    fs::{self, File},
+    io::{BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, RwLock},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Error};
+use rustls::{
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    ServerConfig,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+use tracing::{debug, error, info, warn};
+
+pub use tokio_rustls;
+
+/// Default distance between a plain port and its TLS twin.
+pub const DEFAULT_PORT_OFFSET: u16 = 1000;
+
+/// ALPN ids for servers that speak HTTP/2 (gRPC) as well as HTTP/1.1.
+pub const ALPN_H2_HTTP1: &[&[u8]] = &[b"h2", b"http/1.1"];
+
+/// ALPN ids for HTTP/1.1-only servers.
+pub const ALPN_HTTP1: &[&[u8]] = &[b"http/1.1"];
+
+/// A client that connects but never finishes its handshake is dropped
+/// after this long.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+static TLS: OnceLock<Option<Tls>> = OnceLock::new();
+
+/// The process-wide TLS setup, loaded from `settings.toml` on first use.
+/// `None` when TLS is off or the certificate could not be loaded.
+pub fn tls() -> Option<&'static Tls> {
+    TLS.get_or_init(|| match Tls::from_settings() {
+        Ok(tls) => tls,
+        Err(e) => {
+            error!("tls: {:#}; TLS listeners disabled", e);
+            None
+        }
+    })
+    .as_ref()
+}
+
+/// Address a plain listener should bind: `addr` itself, or its loopback
+/// equivalent when `tls_only` is set.
+pub fn plain_addr(addr: &str) -> String {
+    match tls() {
+        Some(tls) if tls.only => loopback_addr(addr),
+        _ => addr.to_string(),
+    }
+}
+
+/// Path of the certificate rockboxd serves, for clients on the same machine
+/// that want to trust a self-signed one.
+pub fn cert_path() -> Option<PathBuf> {
+    let settings = rockbox_settings::read_settings().ok()?;
+    match settings.tls_cert {
+        Some(cert) => Some(expand_home(&cert)),
+        None if settings.tls_self_signed.unwrap_or(false) => {
+            self_signed_paths().ok().map(|(cert, _)| cert)
+        }
+        None => None,
+    }
+}
+
+pub struct Tls {
+    resolver: Arc<Resolver>,
+    config: ServerConfig,
+    port_offset: u16,
+    only: bool,
+}
+
+impl Tls {
+    fn from_settings() -> Result<Option<Self>, Error> {
+        let settings = rockbox_settings::read_settings().unwrap_or_default();
+        let (cert, key) = match (settings.tls_cert, settings.tls_key) {
+            (Some(cert), Some(key)) => (expand_home(&cert), expand_home(&key)),
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(anyhow!("tls_cert and tls_key have to be set together"))
+            }
+            (None, None) if settings.tls_self_signed.unwrap_or(false) => {
+                let (cert, key) = self_signed_paths()?;
+                if !cert.exists() || !key.exists() {
+                    generate_self_signed(&cert, &key)?;
+                    info!(
+                        "tls: generated a self-signed certificate in {}",
+                        cert.display()
+                    );
+                }
+                (cert, key)
+            }
+            (None, None) => return Ok(None),
+        };
+
+        let resolver = Arc::new(Resolver::load(cert, key)?);
+        let config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()?
+                .with_no_client_auth()
+                .with_cert_resolver(resolver.clone());
+
+        #[cfg(unix)]
+        watch_sighup(resolver.clone());
+
+        Ok(Some(Self {
+            resolver,
+            config,
+            port_offset: settings.tls_port_offset.unwrap_or(DEFAULT_PORT_OFFSET),
+            only: settings.tls_only.unwrap_or(false),
+        }))
+    }
+
+    /// A rustls config serving the current certificate, for servers that
+    /// set their own ALPN ids (actix-web's `bind_rustls_0_23`).
+    pub fn server_config(&self) -> ServerConfig {
+        self.config.clone()
+    }
+
+    pub fn acceptor(&self, alpn: &[&[u8]]) -> TlsAcceptor {
+        let mut config = self.config.clone();
+        config.alpn_protocols = alpn.iter().map(|id| id.to_vec()).collect();
+        TlsAcceptor::from(Arc::new(config))
+    }
+
+    /// TLS port paired with the plain port `plain`.
+    pub fn port(&self, plain: u16) -> Result<u16, Error> {
+        plain
+            .checked_add(self.port_offset)
+            .ok_or_else(|| anyhow!("tls: port {} + {} is out of range", plain, self.port_offset))
+    }
+
+    /// TLS address paired with the plain `host:port` address `addr`.
+    pub fn addr(&self, addr: &str) -> Result<String, Error> {
+        offset_addr(addr, self.port_offset)
+            .ok_or_else(|| anyhow!("tls: cannot offset the port of {}", addr))
+    }
+
+    /// Re-read the certificate and key. On failure the current pair stays.
+    pub fn reload(&self) -> Result<(), Error> {
+        self.resolver.reload()
+    }
+
+    /// Accepts TCP connections on `listener` and hands out the ones that
+    /// complete a TLS handshake. Handshakes run concurrently so one slow
+    /// client does not hold up the others. Must be called inside a Tokio
+    /// runtime.
+    pub fn incoming(
+        &self,
+        listener: TcpListener,
+        alpn: &[&[u8]],
+    ) -> mpsc::Receiver<TlsStream<TcpStream>> {
+        let acceptor = self.acceptor(alpn);
+        let (tx, rx) = mpsc::channel(64);
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        warn!("tls: accept failed: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                let acceptor = acceptor.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = tx.send(stream).await;
+                        }
+                        Ok(Err(e)) => debug!("tls: handshake with {} failed: {}", peer, e),
+                        Err(_) => debug!("tls: handshake with {} timed out", peer),
+                    }
+                });
+            }
+        });
+        rx
+    }
+}
+
+/// Serves whatever certificate was loaded last.
+#[derive(Debug)]
+struct Resolver {
+    cert: PathBuf,
+    key: PathBuf,
+    current: RwLock<Arc<CertifiedKey>>,
+}
+
+impl Resolver {
+    fn load(cert: PathBuf, key: PathBuf) -> Result<Self, Error> {
+        let current = RwLock::new(Arc::new(load_certified_key(&cert, &key)?));
+        Ok(Self { cert, key, current })
+    }
+
+    fn reload(&self) -> Result<(), Error> {
+        let certified_key = load_certified_key(&self.cert, &self.key)?;
+        *self.current.write().unwrap() = Arc::new(certified_key);
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for Resolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().unwrap().clone())
+    }
+}
+
+fn load_certified_key(cert: &Path, key: &Path) -> Result<CertifiedKey, Error> {
+    let file = File::open(cert).with_context(|| format!("open {}", cert.display()))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("read {}", cert.display()))?;
+    if certs.is_empty() {
+        return Err(anyhow!("no certificate found in {}", cert.display()));
+    }
+
+    let file = File::open(key).with_context(|| format!("open {}", key.display()))?;
+    let private_key = rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("read {}", key.display()))?
+        .ok_or_else(|| anyhow!("no private key found in {}", key.display()))?;
+    let signing_key = rustls::crypto::ring::sign::any_supported_type(&private_key)
+        .map_err(|e| anyhow!("unsupported private key in {}: {}", key.display(), e))?;
+
+    Ok(CertifiedKey::new(certs, signing_key))
+}
+
+#[cfg(unix)]
+fn watch_sighup(resolver: Arc<Resolver>) {
+    let spawned = std::thread::Builder::new()
+        .name("tls-reload".to_string())
+        .spawn(move || {
+            let rt = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(e) => {
+                    error!("tls: build tokio runtime: {}", e);
+                    return;
+                }
+            };
+            rt.block_on(async move {
+                use tokio::signal::unix::{signal, SignalKind};
+
+                let mut hangup = match signal(SignalKind::hangup()) {
+                    Ok(hangup) => hangup,
+                    Err(e) => {
+                        warn!("tls: cannot listen for SIGHUP, reload disabled: {}", e);
+                        return;
+                    }
+                };
+                while hangup.recv().await.is_some() {
+                    match resolver.reload() {
+                        Ok(()) => info!("tls: reloaded {}", resolver.cert.display()),
+                        Err(e) => {
+                            warn!(
+                                "tls: reload failed, keeping the current certificate: {:#}",
+                                e
+                            )
+                        }
+                    }
+                }
+            });
+        });
+    if let Err(e) = spawned {
+        warn!("tls: cannot spawn the SIGHUP watcher: {}", e);
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match std::env::var("HOME") {
+        Ok(home) => PathBuf::from(path.replace("$HOME", &home)),
+        Err(_) => PathBuf::from(path),
+    }
+}
+
+fn self_signed_paths() -> Result<(PathBuf, PathBuf), Error> {
+    let home = std::env::var("HOME")?;
+    let dir = PathBuf::from(format!("{}/.config/rockbox.org/tls", home));
+    Ok((dir.join("cert.pem"), dir.join("key.pem")))
+}
+
+/// Names the self-signed certificate is valid for: loopback, plus the
+/// machine's hostname and its mDNS `.local` name when known.
+fn self_signed_names() -> Vec<String> {
+    let mut names = vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+        "::1".to_string(),
+    ];
+    if let Ok(hostname) = fs::read_to_string("/etc/hostname") {
+        let hostname = hostname.trim();
+        if !hostname.is_empty() && hostname != "localhost" {
+            names.push(hostname.to_string());
+            if !hostname.ends_with(".local") {
+                names.push(format!("{}.local", hostname));
+            }
+        }
+    }
+    names
+}
+
+fn generate_self_signed(cert: &Path, key: &Path) -> Result<(), Error> {
+    let rcgen::CertifiedKey {
+        cert: certificate,
+        key_pair,
+    } = rcgen::generate_simple_self_signed(self_signed_names())?;
+
+    if let Some(dir) = cert.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(cert, certificate.pem())?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(key)?
+        .write_all(key_pair.serialize_pem().as_bytes())?;
+    Ok(())
+}
+
+/// `host:port` with `offset` added to the port.
+fn offset_addr(addr: &str, offset: u16) -> Option<String> {
+    let (host, port) = addr.rsplit_once(':')?;
+    let port = port.parse::<u16>().ok()?.checked_add(offset)?;
+    Some(format!("{}:{}", host, port))
+}
+
+/// `host:port` with a wildcard or external host replaced by loopback.
+fn loopback_addr(addr: &str) -> String {
+    match addr.rsplit_once(':') {
+        Some((host, port)) if host.starts_with('[') => format!("[::1]:{}", port),
+        Some((_, port)) => format!("127.0.0.1:{}", port),
+        None => addr.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tls_addresses_are_offset() {
+        assert_eq!(
+            offset_addr("0.0.0.0:6063", 1000).as_deref(),
+            Some("0.0.0.0:7063")
+        );
+        assert_eq!(offset_addr("[::]:6061", 1).as_deref(), Some("[::]:6062"));
+        assert_eq!(offset_addr("0.0.0.0:65000", 1000), None);
+        assert_eq!(offset_addr("localhost", 1000), None);
+    }
+
+    #[test]
+    fn plain_addresses_fall_back_to_loopback() {
+        assert_eq!(loopback_addr("0.0.0.0:6063"), "127.0.0.1:6063");
+        assert_eq!(loopback_addr("192.168.1.2:9000"), "127.0.0.1:9000");
+        assert_eq!(loopback_addr("[::]:6061"), "[::1]:6061");
+    }
+}
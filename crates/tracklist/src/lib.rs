@@ -1,3 +1,5 @@
+pub mod shuffle;
+
 use rand::seq::SliceRandom;
 
 use rockbox_traits::types::track::Track;
@@ -0,0 +1,288 @@
+//! Shuffle orders beyond the firmware's uniform shuffle. Every order is
+//! drawn from the `rng` it is given, so a seeded rng gives the same order
+//! every time.
+
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::{anyhow, Error};
+use rand::{seq::SliceRandom, Rng};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShuffleMode {
+    /// Every track in a uniformly random position.
+    #[default]
+    Tracks,
+    /// Albums in random order, each keeping its track order.
+    Album,
+    /// Tracks in random order, never two by the same artist in a row
+    /// when that can be avoided.
+    ArtistSpread,
+    /// Favourite and often-played tracks early, recently played ones late.
+    Smart,
+}
+
+impl ShuffleMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShuffleMode::Tracks => "tracks",
+            ShuffleMode::Album => "album",
+            ShuffleMode::ArtistSpread => "artist_spread",
+            ShuffleMode::Smart => "smart",
+        }
+    }
+}
+
+impl FromStr for ShuffleMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tracks" => Ok(ShuffleMode::Tracks),
+            "album" => Ok(ShuffleMode::Album),
+            "artist_spread" => Ok(ShuffleMode::ArtistSpread),
+            "smart" => Ok(ShuffleMode::Smart),
+            _ => Err(anyhow!(
+                "unknown shuffle mode: {} (expected tracks, album, artist_spread or smart)",
+                s
+            )),
+        }
+    }
+}
+
+/// What a shuffle needs to know about a queued track.
+#[derive(Debug, Clone, Default)]
+pub struct ShuffleItem {
+    pub album: String,
+    pub artist: String,
+    pub disc_number: u32,
+    pub track_number: u32,
+    /// Relative chance of coming up early in a smart shuffle.
+    pub weight: f64,
+}
+
+const DAY_SECS: f64 = 86400.0;
+
+/// Smart-shuffle weight of a track: favourites count double and play
+/// count adds logarithmically; a track played within the last day is held
+/// back in proportion.
+pub fn smart_weight(play_count: i64, liked: bool, last_played: Option<i64>, now: i64) -> f64 {
+    let mut weight = 1.0 + (play_count.max(0) as f64).ln_1p();
+    if liked {
+        weight *= 2.0;
+    }
+    if let Some(at) = last_played {
+        weight *= ((now - at).max(0) as f64 / DAY_SECS).clamp(0.1, 1.0);
+    }
+    weight
+}
+
+/// A shuffled order of `items`, as indices into it.
+pub fn shuffle_order<R: Rng>(items: &[ShuffleItem], mode: ShuffleMode, rng: &mut R) -> Vec<usize> {
+    match mode {
+        ShuffleMode::Tracks => {
+            let mut order: Vec<usize> = (0..items.len()).collect();
+            order.shuffle(rng);
+            order
+        }
+        ShuffleMode::Album => album_order(items, rng),
+        ShuffleMode::ArtistSpread => artist_spread_order(items, rng),
+        ShuffleMode::Smart => smart_order(items, rng),
+    }
+}
+
+fn album_order<R: Rng>(items: &[ShuffleItem], rng: &mut R) -> Vec<usize> {
+    let mut albums: Vec<Vec<usize>> = Vec::new();
+    let mut by_album: HashMap<&str, usize> = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        let album = *by_album.entry(item.album.as_str()).or_insert_with(|| {
+            albums.push(Vec::new());
+            albums.len() - 1
+        });
+        albums[album].push(i);
+    }
+    for album in albums.iter_mut() {
+        album.sort_by_key(|&i| (items[i].disc_number, items[i].track_number));
+    }
+    albums.shuffle(rng);
+    albums.into_iter().flatten().collect()
+}
+
+/// Each artist's tracks are spread evenly over the list from a random
+/// offset, then any two neighbours that still share an artist are pulled
+/// apart by bringing forward the next track by someone else.
+fn artist_spread_order<R: Rng>(items: &[ShuffleItem], rng: &mut R) -> Vec<usize> {
+    let mut artists: Vec<Vec<usize>> = Vec::new();
+    let mut by_artist: HashMap<&str, usize> = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        let artist = *by_artist.entry(item.artist.as_str()).or_insert_with(|| {
+            artists.push(Vec::new());
+            artists.len() - 1
+        });
+        artists[artist].push(i);
+    }
+
+    let mut placed: Vec<(f64, usize)> = Vec::with_capacity(items.len());
+    for mut tracks in artists {
+        tracks.shuffle(rng);
+        let gap = 1.0 / tracks.len() as f64;
+        let offset = rng.gen::<f64>() * gap;
+        for (n, i) in tracks.into_iter().enumerate() {
+            let jitter = (rng.gen::<f64>() - 0.5) * gap * 0.2;
+            placed.push((offset + n as f64 * gap + jitter, i));
+        }
+    }
+    placed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut order: Vec<usize> = placed.into_iter().map(|(_, i)| i).collect();
+
+    let artist = |i: usize| items[i].artist.as_str();
+    for pos in 1..order.len() {
+        let previous = artist(order[pos - 1]);
+        if artist(order[pos]) != previous {
+            continue;
+        }
+        match (pos + 1..order.len()).find(|&j| artist(order[j]) != previous) {
+            Some(j) => {
+                let item = order.remove(j);
+                order.insert(pos, item);
+            }
+            // Only this artist is left: tuck the rest of its tracks into
+            // earlier gaps between two other artists.
+            None => {
+                let tail = order.split_off(pos);
+                for item in tail {
+                    let gap = (0..=order.len()).find(|&p| {
+                        (p == 0 || artist(order[p - 1]) != previous)
+                            && (p == order.len() || artist(order[p]) != previous)
+                    });
+                    order.insert(gap.unwrap_or(order.len()), item);
+                }
+                break;
+            }
+        }
+    }
+    order
+}
+
+fn smart_order<R: Rng>(items: &[ShuffleItem], rng: &mut R) -> Vec<usize> {
+    // Efraimidis–Spirakis: sorting on u^(1/w) draws each next track with a
+    // chance proportional to its weight.
+    let mut keyed: Vec<(f64, usize)> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (rng.gen::<f64>().powf(1.0 / item.weight.max(1e-3)), i))
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn item(album: &str, artist: &str, track_number: u32) -> ShuffleItem {
+        ShuffleItem {
+            album: album.to_string(),
+            artist: artist.to_string(),
+            disc_number: 1,
+            track_number,
+            weight: 1.0,
+        }
+    }
+
+    fn library() -> Vec<ShuffleItem> {
+        let mut items = Vec::new();
+        for (album, artist) in [("a", "x"), ("b", "x"), ("c", "y"), ("d", "z")] {
+            for n in (1..=4).rev() {
+                items.push(item(album, artist, n));
+            }
+        }
+        items
+    }
+
+    fn is_permutation(order: &[usize], len: usize) -> bool {
+        let mut sorted = order.to_vec();
+        sorted.sort();
+        sorted == (0..len).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn seeded_shuffles_repeat() {
+        let items = library();
+        for mode in [
+            ShuffleMode::Tracks,
+            ShuffleMode::Album,
+            ShuffleMode::ArtistSpread,
+            ShuffleMode::Smart,
+        ] {
+            let first = shuffle_order(&items, mode, &mut StdRng::seed_from_u64(3));
+            let again = shuffle_order(&items, mode, &mut StdRng::seed_from_u64(3));
+            assert_eq!(first, again, "{}", mode.as_str());
+            assert!(is_permutation(&first, items.len()), "{}", mode.as_str());
+        }
+    }
+
+    #[test]
+    fn album_shuffle_keeps_albums_in_track_order() {
+        let items = library();
+        let order = shuffle_order(&items, ShuffleMode::Album, &mut StdRng::seed_from_u64(9));
+        for chunk in order.chunks(4) {
+            let album = &items[chunk[0]].album;
+            assert!(chunk.iter().all(|&i| &items[i].album == album));
+            let numbers: Vec<u32> = chunk.iter().map(|&i| items[i].track_number).collect();
+            assert_eq!(numbers, [1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn artist_spread_avoids_repeats() {
+        let items = library();
+        for seed in 0..50 {
+            let order = shuffle_order(
+                &items,
+                ShuffleMode::ArtistSpread,
+                &mut StdRng::seed_from_u64(seed),
+            );
+            for pair in order.windows(2) {
+                assert_ne!(
+                    items[pair[0]].artist, items[pair[1]].artist,
+                    "seed {}",
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn smart_shuffle_plays_heavy_tracks_first() {
+        let mut items = vec![item("a", "x", 1), item("a", "x", 2)];
+        items[0].weight = 0.1;
+        items[1].weight = 4.0;
+        let mut rng = StdRng::seed_from_u64(1);
+        let heavy_first = (0..1000)
+            .filter(|_| shuffle_order(&items, ShuffleMode::Smart, &mut rng)[0] == 1)
+            .count();
+        assert!(heavy_first > 900, "heavy first {} times", heavy_first);
+    }
+
+    #[test]
+    fn smart_weight_prefers_favourites_and_holds_back_recent_plays() {
+        let now = 1_000_000_000;
+        assert!(smart_weight(0, true, None, now) > smart_weight(0, false, None, now));
+        assert!(smart_weight(20, false, None, now) > smart_weight(0, false, None, now));
+        assert!(smart_weight(5, false, Some(now - 600), now) < smart_weight(5, false, None, now));
+    }
+
+    #[test]
+    fn modes_round_trip() {
+        for mode in [
+            ShuffleMode::Tracks,
+            ShuffleMode::Album,
+            ShuffleMode::ArtistSpread,
+            ShuffleMode::Smart,
+        ] {
+            assert_eq!(mode.as_str().parse::<ShuffleMode>().unwrap(), mode);
+        }
+        assert!("random".parse::<ShuffleMode>().is_err());
+    }
+}
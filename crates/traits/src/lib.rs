@@ -22,6 +22,8 @@ pub trait Player {
     async fn play_track_at(&self, position: u32) -> Result<(), Error>;
     async fn remove_track_at(&self, position: u32) -> Result<(), Error>;
     async fn disconnect(&self) -> Result<(), Error>;
+    async fn next_chapter(&mut self) -> Result<(), Error>;
+    async fn previous_chapter(&mut self) -> Result<(), Error>;
 }
 
 #[async_trait]
@@ -0,0 +1,37 @@
+/// Skipping back within this many milliseconds of a chapter's start goes to
+/// the previous chapter instead of restarting the current one.
+pub const PREVIOUS_CHAPTER_THRESHOLD_MS: u64 = 3000;
+
+#[derive(Debug, Clone, Default)]
+pub struct Chapter {
+    pub title: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Index of the chapter containing `position_ms`. Chapters must be sorted
+/// by start time.
+pub fn chapter_at(chapters: &[Chapter], position_ms: u64) -> Option<usize> {
+    chapters.iter().rposition(|c| c.start_ms <= position_ms)
+}
+
+/// Where "next chapter" should seek to, or `None` when playing the last
+/// chapter.
+pub fn next_chapter_start(chapters: &[Chapter], position_ms: u64) -> Option<u64> {
+    chapters
+        .iter()
+        .find(|c| c.start_ms > position_ms)
+        .map(|c| c.start_ms)
+}
+
+/// Where "previous chapter" should seek to: the start of the current
+/// chapter, or of the one before it when the current chapter has only just
+/// started. `None` when there are no chapters.
+pub fn previous_chapter_start(chapters: &[Chapter], position_ms: u64) -> Option<u64> {
+    let current = chapter_at(chapters, position_ms)?;
+    let start = chapters[current].start_ms;
+    if position_ms - start > PREVIOUS_CHAPTER_THRESHOLD_MS || current == 0 {
+        return Some(start);
+    }
+    Some(chapters[current - 1].start_ms)
+}
@@ -1,2 +1,3 @@
+pub mod chapter;
 pub mod playback;
 pub mod track;
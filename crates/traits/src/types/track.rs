@@ -1,3 +1,5 @@
+use crate::types::chapter::Chapter;
+
 #[derive(Debug, Clone, Default)]
 pub struct Artist {
     pub id: String,
@@ -26,4 +28,5 @@ pub struct Track {
     pub album_cover: Option<String>,
     pub album_id: Option<String>,
     pub artist_id: Option<String>,
+    pub chapters: Vec<Chapter>,
 }
@@ -18,6 +18,11 @@ pub struct ContentEntry {
     pub is_container: bool,
     pub uri: Option<String>,
     pub album_art_uri: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub track_number: Option<u32>,
+    /// Length in milliseconds, from the `<res duration>` attribute.
+    pub duration: Option<u32>,
 }
 
 pub async fn discover_media_servers() -> Vec<DiscoveredDevice> {
@@ -101,13 +106,43 @@ async fn fetch_device_info(client: &reqwest::Client, location: &str) -> Option<D
 }
 
 pub async fn browse_content_directory(control_url: &str, object_id: &str) -> Vec<ContentEntry> {
-    let client = match reqwest::Client::builder()
+    match browse_children(control_url, object_id).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("UPnP browse {control_url}: {e}");
+            vec![]
+        }
+    }
+}
+
+/// Every playable item below the root container, walking the whole tree,
+/// for mirroring a media server's catalogue. Fails instead of returning an
+/// empty list, so a server that is down is not mistaken for an empty one.
+/// Items reachable through several containers are listed once.
+pub async fn list_audio_items(control_url: &str) -> anyhow::Result<Vec<ContentEntry>> {
+    let mut items = vec![];
+    let mut seen_items: HashSet<String> = HashSet::new();
+    let mut seen_containers: HashSet<String> = HashSet::new();
+    let mut pending = vec!["0".to_string()];
+    while let Some(object_id) = pending.pop() {
+        if !seen_containers.insert(object_id.clone()) {
+            continue;
+        }
+        for entry in browse_children(control_url, &object_id).await? {
+            if entry.is_container {
+                pending.push(entry.id);
+            } else if entry.uri.is_some() && seen_items.insert(entry.id.clone()) {
+                items.push(entry);
+            }
+        }
+    }
+    Ok(items)
+}
+
+async fn browse_children(control_url: &str, object_id: &str) -> anyhow::Result<Vec<ContentEntry>> {
+    let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(10))
-        .build()
-    {
-        Ok(c) => c,
-        Err(_) => return vec![],
-    };
+        .build()?;
 
     let mut all_entries: Vec<ContentEntry> = Vec::new();
     let mut starting_index: u32 = 0;
@@ -132,7 +167,7 @@ pub async fn browse_content_directory(control_url: &str, object_id: &str) -> Vec
             starting_index,
         );
 
-        let resp = match client
+        let body = client
             .post(control_url)
             .header("Content-Type", r#"text/xml; charset="utf-8""#)
             .header(
@@ -141,28 +176,16 @@ pub async fn browse_content_directory(control_url: &str, object_id: &str) -> Vec
             )
             .body(soap_body)
             .send()
-            .await
-        {
-            Ok(r) => r,
-            Err(e) => {
-                tracing::warn!("UPnP browse {control_url}: {e}");
-                break;
-            }
-        };
-
-        let body = match resp.text().await {
-            Ok(b) => b,
-            Err(_) => break,
-        };
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
 
         tracing::debug!("UPnP browse response body (start={starting_index}): {body}");
 
         let result_xml = match extract_result_tag(&body) {
             Some(r) => html_unescape(&r),
-            None => {
-                tracing::warn!("UPnP browse: no <Result> tag found in response");
-                break;
-            }
+            None => anyhow::bail!("no <Result> tag found in response"),
         };
 
         tracing::debug!("UPnP DIDL-Lite XML: {result_xml}");
@@ -198,7 +221,7 @@ pub async fn browse_content_directory(control_url: &str, object_id: &str) -> Vec
     }
 
     tracing::debug!("UPnP browse total entries collected: {}", all_entries.len());
-    all_entries
+    Ok(all_entries)
 }
 
 // ── DIDL-Lite parser ──────────────────────────────────────────────────────────
@@ -244,12 +267,19 @@ fn parse_elements(xml: &str, tag: &str, is_container: bool, out: &mut Vec<Conten
         } else {
             extract_namespaced(element, "albumArtURI")
         };
+        let artist = extract_namespaced(element, "artist")
+            .or_else(|| extract_namespaced(element, "creator"))
+            .map(|a| html_unescape(&a));
         out.push(ContentEntry {
             id,
             title,
             is_container,
             uri,
             album_art_uri,
+            artist,
+            album: extract_namespaced(element, "album").map(|a| html_unescape(&a)),
+            track_number: parse_u32_tag(element, "originalTrackNumber"),
+            duration: extract_res_duration(element),
         });
         pos = end_abs;
     }
@@ -317,6 +347,18 @@ fn extract_res_uri(element: &str) -> Option<String> {
     }
 }
 
+/// `duration="H:MM:SS[.F]"` on the first `<res>`, in milliseconds.
+fn extract_res_duration(element: &str) -> Option<u32> {
+    let res_start = element.find("<res ")?;
+    let res_end = element[res_start..].find('>')? + res_start;
+    let duration = attr_value(&element[res_start..res_end], "duration")?;
+    let mut parts = duration.split(':').rev();
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next().unwrap_or("0").parse().ok()?;
+    let hours: f64 = parts.next().unwrap_or("0").parse().ok()?;
+    Some((((hours * 60.0 + minutes) * 60.0 + seconds) * 1000.0) as u32)
+}
+
 fn extract_service_control_url(xml: &str, service_type_fragment: &str) -> Option<String> {
     let needle = format!(":{service_type_fragment}:");
     let svc_pos = xml.find(&needle)?;
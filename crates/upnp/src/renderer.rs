@@ -719,8 +719,17 @@ async fn play_url(uri: &str) {
     // The internal HTTP server closes the TCP socket after each response.
     // pool_max_idle_per_host(0) disables connection pooling so each request
     // opens a fresh connection instead of reusing a server-closed socket.
+    // The internal token keeps these calls working once API tokens are
+    // enforced.
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Ok(value) =
+        reqwest::header::HeaderValue::from_str(&rockbox_auth::internal_authorization())
+    {
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
     let client = reqwest::Client::builder()
         .pool_max_idle_per_host(0)
+        .default_headers(headers)
         .build()
         .unwrap_or_default();
 
@@ -0,0 +1,203 @@
+//! In-process event bus. Playback, library and device code calls [`emit`];
+//! webhook delivery and the MQTT publisher [`subscribe`] to it.
+
+use std::sync::OnceLock;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Events buffered per subscriber before the slowest one starts losing them.
+const CAPACITY: usize = 256;
+
+/// Every event name, in the order they are documented.
+pub const EVENT_NAMES: [&str; 13] = [
+    "track_started",
+    "track_finished",
+    "track_skipped",
+    "playback_paused",
+    "playback_resumed",
+    "playback_stopped",
+    "queue_changed",
+    "library_scan_finished",
+    "file_added",
+    "file_removed",
+    "device_connected",
+    "device_disconnected",
+    "ping",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrackInfo {
+    pub id: Option<String>,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub path: String,
+    pub length_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceInfo {
+    pub id: String,
+    pub name: String,
+    pub service: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    TrackStarted {
+        track: TrackInfo,
+    },
+    /// The previous track played to (nearly) the end.
+    TrackFinished {
+        track: TrackInfo,
+    },
+    /// The previous track was left before 90 % of it had played.
+    TrackSkipped {
+        track: TrackInfo,
+        elapsed_ms: u64,
+    },
+    PlaybackPaused {
+        track: Option<TrackInfo>,
+        elapsed_ms: u64,
+    },
+    PlaybackResumed {
+        track: Option<TrackInfo>,
+        elapsed_ms: u64,
+    },
+    PlaybackStopped,
+    QueueChanged {
+        amount: i32,
+        index: i32,
+    },
+    LibraryScanFinished {
+        path: String,
+        tracks: usize,
+    },
+    FileAdded {
+        path: String,
+        track_id: Option<String>,
+    },
+    FileRemoved {
+        path: String,
+        track_id: Option<String>,
+    },
+    DeviceConnected {
+        device: DeviceInfo,
+    },
+    DeviceDisconnected {
+        device: DeviceInfo,
+    },
+    /// Sent by `POST /webhooks/{id}/test` only.
+    Ping,
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::TrackStarted { .. } => "track_started",
+            Event::TrackFinished { .. } => "track_finished",
+            Event::TrackSkipped { .. } => "track_skipped",
+            Event::PlaybackPaused { .. } => "playback_paused",
+            Event::PlaybackResumed { .. } => "playback_resumed",
+            Event::PlaybackStopped => "playback_stopped",
+            Event::QueueChanged { .. } => "queue_changed",
+            Event::LibraryScanFinished { .. } => "library_scan_finished",
+            Event::FileAdded { .. } => "file_added",
+            Event::FileRemoved { .. } => "file_removed",
+            Event::DeviceConnected { .. } => "device_connected",
+            Event::DeviceDisconnected { .. } => "device_disconnected",
+            Event::Ping => "ping",
+        }
+    }
+}
+
+/// What is actually sent: the event plus a unique id (for receivers to
+/// de-duplicate retried deliveries) and the time it happened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    pub id: String,
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+impl Envelope {
+    pub fn new(event: Event) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now().timestamp(),
+            event,
+        }
+    }
+}
+
+fn bus() -> &'static broadcast::Sender<Envelope> {
+    static BUS: OnceLock<broadcast::Sender<Envelope>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(CAPACITY).0)
+}
+
+/// Publish `event` to every subscriber. Cheap and non-blocking, so it can be
+/// called from the broker thread; a no-op when nothing subscribed.
+pub fn emit(event: Event) {
+    let _ = bus().send(Envelope::new(event));
+}
+
+pub fn subscribe() -> broadcast::Receiver<Envelope> {
+    bus().subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_serializes_flat() {
+        let envelope = Envelope {
+            id: "1".to_string(),
+            timestamp: 42,
+            event: Event::QueueChanged {
+                amount: 3,
+                index: 0,
+            },
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "id": "1",
+                "timestamp": 42,
+                "event": "queue_changed",
+                "data": { "amount": 3, "index": 0 }
+            })
+        );
+        assert_eq!(serde_json::from_value::<Envelope>(json).unwrap(), envelope);
+    }
+
+    #[test]
+    fn names_match_serde_tags() {
+        let events = [
+            Event::PlaybackStopped,
+            Event::Ping,
+            Event::FileAdded {
+                path: "/a.mp3".to_string(),
+                track_id: None,
+            },
+        ];
+        for event in events {
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(json["event"], event.name());
+            assert!(EVENT_NAMES.contains(&event.name()));
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_emitted_events() {
+        let mut rx = subscribe();
+        emit(Event::PlaybackStopped);
+        assert_eq!(rx.recv().await.unwrap().event, Event::PlaybackStopped);
+    }
+}
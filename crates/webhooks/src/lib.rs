@@ -0,0 +1,468 @@
+pub mod event;
+pub mod mqtt;
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub use event::{emit, DeviceInfo, Envelope, Event, TrackInfo, EVENT_NAMES};
+
+/// Wait before each retry of a failed delivery; one attempt plus one per
+/// entry, so a receiver that is down gets about 12 minutes to come back.
+const RETRY_DELAYS_SECS: [u64; 4] = [5, 30, 120, 600];
+
+/// Delivery log rows kept per webhook; older ones are pruned on insert.
+const MAX_DELIVERIES: i64 = 200;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>` when the webhook
+/// has a secret.
+pub const SIGNATURE_HEADER: &str = "X-Rockbox-Signature-256";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+    /// Whether payloads are signed; the secret itself is never returned.
+    pub has_secret: bool,
+    /// Event names delivered to this webhook; empty means all of them.
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Webhook {
+    pub fn wants(&self, event: &str) -> bool {
+        match self.events.is_empty() {
+            true => event != "ping",
+            false => self.events.iter().any(|e| e == event),
+        }
+    }
+}
+
+/// One attempt at delivering an event to a webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct Delivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_id: String,
+    pub event: String,
+    pub payload: String,
+    pub attempt: i64,
+    pub status_code: Option<i64>,
+    pub error: Option<String>,
+    pub success: bool,
+    pub duration_ms: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookUpdate {
+    pub url: Option<String>,
+    /// `Some("")` removes the secret.
+    pub secret: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
+
+const WEBHOOK_COLUMNS: &str = "id, url, secret, events, enabled, created_at, updated_at";
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, event_id, event, payload, attempt, \
+     status_code, error, success, duration_ms, created_at";
+
+fn webhook_from_row(r: SqliteRow) -> Webhook {
+    let secret: Option<String> = r.get(2);
+    let events: String = r.get(3);
+    Webhook {
+        id: r.get(0),
+        url: r.get(1),
+        has_secret: secret.is_some(),
+        secret,
+        events: events
+            .split(',')
+            .filter(|e| !e.is_empty())
+            .map(str::to_string)
+            .collect(),
+        enabled: r.get::<i64, _>(4) != 0,
+        created_at: r.get(5),
+        updated_at: r.get(6),
+    }
+}
+
+fn delivery_from_row(r: SqliteRow) -> Delivery {
+    Delivery {
+        id: r.get(0),
+        webhook_id: r.get(1),
+        event_id: r.get(2),
+        event: r.get(3),
+        payload: r.get(4),
+        attempt: r.get(5),
+        status_code: r.get(6),
+        error: r.get(7),
+        success: r.get::<i64, _>(8) != 0,
+        duration_ms: r.get(9),
+        created_at: r.get(10),
+    }
+}
+
+fn validate(url: &str, events: &[String]) -> Result<()> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| anyhow!("invalid url: {}", e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(anyhow!("url must be http or https"));
+    }
+    if let Some(unknown) = events.iter().find(|e| !EVENT_NAMES.contains(&e.as_str())) {
+        return Err(anyhow!("unknown event: {}", unknown));
+    }
+    Ok(())
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `body` keyed with `secret`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether a failed attempt is worth repeating: network errors, rate
+/// limiting and server errors are; other client errors will not change.
+fn retryable(status: Option<u16>) -> bool {
+    match status {
+        None => true,
+        Some(code) => code == 429 || code >= 500,
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookStore {
+    pool: Pool<Sqlite>,
+    client: reqwest::Client,
+}
+
+impl WebhookStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("rockbox-webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { pool, client }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Webhook>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(webhook_from_row).collect())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<Webhook>> {
+        let row = sqlx::query(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(webhook_from_row))
+    }
+
+    pub async fn create(
+        &self,
+        url: &str,
+        secret: Option<&str>,
+        events: &[String],
+        enabled: bool,
+    ) -> Result<Webhook> {
+        validate(url, events)?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO webhooks (id, url, secret, events, enabled, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $6)",
+        )
+        .bind(&id)
+        .bind(url)
+        .bind(secret.filter(|s| !s.is_empty()))
+        .bind(events.join(","))
+        .bind(enabled as i64)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        self.get(&id)
+            .await?
+            .ok_or_else(|| anyhow!("webhook {} vanished", id))
+    }
+
+    pub async fn update(&self, id: &str, update: WebhookUpdate) -> Result<Option<Webhook>> {
+        let Some(mut webhook) = self.get(id).await? else {
+            return Ok(None);
+        };
+        if let Some(url) = update.url {
+            webhook.url = url;
+        }
+        if let Some(secret) = update.secret {
+            webhook.secret = Some(secret).filter(|s| !s.is_empty());
+        }
+        if let Some(events) = update.events {
+            webhook.events = events;
+        }
+        if let Some(enabled) = update.enabled {
+            webhook.enabled = enabled;
+        }
+        validate(&webhook.url, &webhook.events)?;
+        sqlx::query(
+            "UPDATE webhooks SET url = $1, secret = $2, events = $3, enabled = $4, updated_at = $5
+             WHERE id = $6",
+        )
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(webhook.events.join(","))
+        .bind(webhook.enabled as i64)
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        self.get(id).await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Most recent delivery attempts first.
+    pub async fn deliveries(&self, webhook_id: &str, limit: i64) -> Result<Vec<Delivery>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE webhook_id = $1
+             ORDER BY rowid DESC LIMIT $2"
+        ))
+        .bind(webhook_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(delivery_from_row).collect())
+    }
+
+    /// POST `envelope` to the webhook once and record the attempt.
+    pub async fn send(&self, webhook: &Webhook, envelope: &Envelope, attempt: i64) -> Delivery {
+        let payload = serde_json::to_string(envelope).unwrap_or_default();
+        let mut request = self
+            .client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("X-Rockbox-Event", envelope.event.name())
+            .header("X-Rockbox-Delivery", &envelope.id);
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, payload.as_bytes()));
+        }
+
+        let started = Instant::now();
+        let (status_code, error) = match request.body(payload.clone()).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let error = (!status.is_success()).then(|| status.to_string());
+                (Some(status.as_u16() as i64), error)
+            }
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let delivery = Delivery {
+            id: Uuid::new_v4().to_string(),
+            webhook_id: webhook.id.clone(),
+            event_id: envelope.id.clone(),
+            event: envelope.event.name().to_string(),
+            payload,
+            attempt,
+            status_code,
+            success: error.is_none(),
+            error,
+            duration_ms: started.elapsed().as_millis() as i64,
+            created_at: Utc::now().timestamp(),
+        };
+        if let Err(e) = self.log(&delivery).await {
+            warn!("webhooks: could not log delivery to {}: {}", webhook.url, e);
+        }
+        delivery
+    }
+
+    async fn log(&self, delivery: &Delivery) -> Result<()> {
+        sqlx::query(&format!(
+            "INSERT INTO webhook_deliveries ({DELIVERY_COLUMNS})
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"
+        ))
+        .bind(&delivery.id)
+        .bind(&delivery.webhook_id)
+        .bind(&delivery.event_id)
+        .bind(&delivery.event)
+        .bind(&delivery.payload)
+        .bind(delivery.attempt)
+        .bind(delivery.status_code)
+        .bind(&delivery.error)
+        .bind(delivery.success as i64)
+        .bind(delivery.duration_ms)
+        .bind(delivery.created_at)
+        .execute(&self.pool)
+        .await?;
+        sqlx::query(
+            "DELETE FROM webhook_deliveries WHERE webhook_id = $1 AND rowid NOT IN (
+                SELECT rowid FROM webhook_deliveries WHERE webhook_id = $1
+                ORDER BY rowid DESC LIMIT $2)",
+        )
+        .bind(&delivery.webhook_id)
+        .bind(MAX_DELIVERIES)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Deliver `envelope`, retrying with backoff until it is accepted, the
+    /// receiver rejects it outright, or the retries run out.
+    pub async fn deliver(&self, webhook: Webhook, envelope: Envelope) {
+        let mut attempt = 1;
+        loop {
+            let delivery = self.send(&webhook, &envelope, attempt).await;
+            if delivery.success {
+                return;
+            }
+            let status = delivery.status_code.map(|c| c as u16);
+            let delay = RETRY_DELAYS_SECS.get(attempt as usize - 1);
+            match delay {
+                Some(secs) if retryable(status) => {
+                    warn!(
+                        "webhooks: {} to {} failed ({}), retrying in {}s",
+                        envelope.event.name(),
+                        webhook.url,
+                        delivery.error.unwrap_or_default(),
+                        secs
+                    );
+                    tokio::time::sleep(Duration::from_secs(*secs)).await;
+                    attempt += 1;
+                }
+                _ => {
+                    warn!(
+                        "webhooks: giving up on {} to {} after {} attempt(s)",
+                        envelope.event.name(),
+                        webhook.url,
+                        attempt
+                    );
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Send a `ping` event to one webhook, without retries.
+    pub async fn test(&self, id: &str) -> Result<Option<Delivery>> {
+        let Some(webhook) = self.get(id).await? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.send(&webhook, &Envelope::new(Event::Ping), 1).await,
+        ))
+    }
+}
+
+/// Forward every event on the bus to the enabled webhooks subscribed to it.
+/// Each delivery runs in its own task so a slow receiver holds up nobody.
+pub fn start_dispatcher(store: WebhookStore) {
+    let mut rx = event::subscribe();
+    tokio::spawn(async move {
+        info!("webhooks: dispatcher started");
+        loop {
+            let envelope = match rx.recv().await {
+                Ok(envelope) => envelope,
+                Err(RecvError::Lagged(n)) => {
+                    warn!("webhooks: dropped {} event(s)", n);
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+            let webhooks = match store.list().await {
+                Ok(webhooks) => webhooks,
+                Err(e) => {
+                    warn!("webhooks: listing webhooks: {}", e);
+                    continue;
+                }
+            };
+            for webhook in webhooks
+                .into_iter()
+                .filter(|w| w.enabled && w.wants(envelope.event.name()))
+            {
+                let store = store.clone();
+                let envelope = envelope.clone();
+                tokio::spawn(async move { store.deliver(webhook, envelope).await });
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: &[&str]) -> Webhook {
+        Webhook {
+            id: "w".to_string(),
+            url: "http://localhost:9000/hook".to_string(),
+            secret: None,
+            has_secret: false,
+            events: events.iter().map(|e| e.to_string()).collect(),
+            enabled: true,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn filters_events() {
+        assert!(webhook(&[]).wants("track_started"));
+        assert!(!webhook(&[]).wants("ping"));
+        assert!(webhook(&["ping", "file_added"]).wants("file_added"));
+        assert!(!webhook(&["file_added"]).wants("track_started"));
+    }
+
+    #[test]
+    fn validates_url_and_events() {
+        assert!(validate("http://localhost:8123/api/webhook/x", &[]).is_ok());
+        assert!(validate("ftp://example.com", &[]).is_err());
+        assert!(validate("http://localhost", &["track_started".to_string()]).is_ok());
+        assert!(validate("http://localhost", &["nope".to_string()]).is_err());
+    }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        assert!(retryable(None));
+        assert!(retryable(Some(503)));
+        assert!(retryable(Some(429)));
+        assert!(!retryable(Some(404)));
+    }
+}
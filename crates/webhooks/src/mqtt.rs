@@ -0,0 +1,225 @@
+//! Minimal MQTT 3.1.1 publisher, so Home Assistant (or any broker
+//! subscriber) can react to events without a webhook receiver.
+//!
+//! Each event is published at QoS 0 as JSON to `<topic>/<event name>`.
+//! `<topic>/status` is a retained `online` / `offline` availability topic,
+//! the latter set by the broker through the last will when we drop off.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::broadcast::error::RecvError,
+};
+use tracing::{info, warn};
+
+use crate::event;
+
+const KEEP_ALIVE_SECS: u16 = 60;
+
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Topic prefix, `rockbox` by default.
+    pub topic: String,
+}
+
+/// MQTT's variable-length "remaining length" field.
+fn remaining_length(mut len: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if len == 0 {
+            return out;
+        }
+    }
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &[u8]) {
+    buf.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    buf.extend_from_slice(s);
+}
+
+fn packet(header: u8, body: Vec<u8>) -> Vec<u8> {
+    let mut out = vec![header];
+    out.extend(remaining_length(body.len()));
+    out.extend(body);
+    out
+}
+
+fn connect_packet(config: &MqttConfig) -> Vec<u8> {
+    // Clean session, retained QoS 0 will on the status topic.
+    let mut flags = 0x02 | 0x04 | 0x20;
+    if config.username.is_some() {
+        flags |= 0x80;
+        if config.password.is_some() {
+            flags |= 0x40;
+        }
+    }
+    let mut body = Vec::new();
+    put_str(&mut body, b"MQTT");
+    body.push(4);
+    body.push(flags);
+    body.extend_from_slice(&KEEP_ALIVE_SECS.to_be_bytes());
+    put_str(&mut body, config.client_id.as_bytes());
+    put_str(&mut body, format!("{}/status", config.topic).as_bytes());
+    put_str(&mut body, b"offline");
+    if let Some(username) = &config.username {
+        put_str(&mut body, username.as_bytes());
+        if let Some(password) = &config.password {
+            put_str(&mut body, password.as_bytes());
+        }
+    }
+    packet(0x10, body)
+}
+
+fn publish_packet(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let mut body = Vec::new();
+    put_str(&mut body, topic.as_bytes());
+    body.extend_from_slice(payload);
+    packet(0x30 | retain as u8, body)
+}
+
+const PINGREQ: [u8; 2] = [0xc0, 0x00];
+
+async fn connect(config: &MqttConfig) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect((config.host.as_str(), config.port)).await?;
+    stream.write_all(&connect_packet(config)).await?;
+    let mut connack = [0u8; 4];
+    tokio::time::timeout(Duration::from_secs(10), stream.read_exact(&mut connack)).await??;
+    if connack[0] != 0x20 || connack[3] != 0 {
+        return Err(anyhow!("connection refused (return code {})", connack[3]));
+    }
+    stream
+        .write_all(&publish_packet(
+            &format!("{}/status", config.topic),
+            b"online",
+            true,
+        ))
+        .await?;
+    Ok(stream)
+}
+
+/// Publish every event on the bus to the configured broker, reconnecting
+/// on the next event after the connection drops.
+pub fn start(config: MqttConfig) {
+    let mut rx = event::subscribe();
+    tokio::spawn(async move {
+        info!(
+            "mqtt: publishing events to {}:{} under {}/",
+            config.host, config.port, config.topic
+        );
+        let mut stream: Option<TcpStream> = None;
+        let mut ping = tokio::time::interval(Duration::from_secs(KEEP_ALIVE_SECS as u64 / 2));
+        let mut scratch = [0u8; 64];
+        loop {
+            let packet = tokio::select! {
+                received = rx.recv() => match received {
+                    Ok(envelope) => match serde_json::to_vec(&envelope) {
+                        Ok(payload) => publish_packet(
+                            &format!("{}/{}", config.topic, envelope.event.name()),
+                            &payload,
+                            false,
+                        ),
+                        Err(_) => continue,
+                    },
+                    Err(RecvError::Lagged(n)) => {
+                        warn!("mqtt: dropped {} event(s)", n);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                },
+                _ = ping.tick() => match stream {
+                    Some(_) => PINGREQ.to_vec(),
+                    None => continue,
+                },
+                // Drain CONNACK/PINGRESP traffic and notice disconnects.
+                read = async { stream.as_mut().unwrap().read(&mut scratch).await },
+                    if stream.is_some() =>
+                {
+                    if !matches!(read, Ok(n) if n > 0) {
+                        warn!("mqtt: connection to {} closed", config.host);
+                        stream = None;
+                    }
+                    continue;
+                }
+            };
+
+            if stream.is_none() {
+                match connect(&config).await {
+                    Ok(s) => stream = Some(s),
+                    Err(e) => {
+                        warn!("mqtt: connecting to {}:{}: {}", config.host, config.port, e);
+                        continue;
+                    }
+                }
+            }
+            if let Some(s) = stream.as_mut() {
+                if let Err(e) = s.write_all(&packet).await {
+                    warn!("mqtt: publish failed: {}", e);
+                    stream = None;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MqttConfig {
+        MqttConfig {
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "rb".to_string(),
+            username: None,
+            password: None,
+            topic: "rockbox".to_string(),
+        }
+    }
+
+    #[test]
+    fn encodes_remaining_length() {
+        assert_eq!(remaining_length(0), [0x00]);
+        assert_eq!(remaining_length(127), [0x7f]);
+        assert_eq!(remaining_length(128), [0x80, 0x01]);
+        assert_eq!(remaining_length(16_383), [0xff, 0x7f]);
+        assert_eq!(remaining_length(16_384), [0x80, 0x80, 0x01]);
+    }
+
+    #[test]
+    fn encodes_publish() {
+        assert_eq!(
+            publish_packet("a/b", b"hi", false),
+            [0x30, 7, 0, 3, b'a', b'/', b'b', b'h', b'i']
+        );
+        assert_eq!(publish_packet("a", b"", true)[0], 0x31);
+    }
+
+    #[test]
+    fn connect_sets_credential_flags() {
+        let plain = connect_packet(&config());
+        // header, length, "MQTT" (6), level, flags
+        assert_eq!(plain[9], 0x26);
+
+        let with_auth = connect_packet(&MqttConfig {
+            username: Some("u".to_string()),
+            password: Some("p".to_string()),
+            ..config()
+        });
+        assert_eq!(with_auth[9], 0xe6);
+        assert!(with_auth.ends_with(&[0, 1, b'u', 0, 1, b'p']));
+    }
+}
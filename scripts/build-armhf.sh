@@ -187,11 +187,11 @@ echo "==> Step 3: Build S3 admin web UI bundle"
 echo "==> Step 4: Build Rust crates with cross (fts5 + alsa-sink, no typesense subprocess)"
 cross build --release \
     --target "$RUST_TARGET" \
-    --features fts5,alsa-sink \
+    --features fts5 \
     -p rockbox-cli
 cross build --release \
     --target "$RUST_TARGET" \
-    --features fts5 \
+    --features fts5,alsa-sink \
     -p rockbox-server
 
 echo "==> Step 5: Link rockboxd with Zig for arm-linux-gnueabihf"
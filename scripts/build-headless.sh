@@ -174,8 +174,8 @@ rm -rf "$TMP_LIBOPUS"
 echo "    Done — ${#OGG_SYMS[@]} ogg_* symbols namespaced in libopus.a + opus.o"
 
 echo "==> Step 3: Build Rust crates (features: cpal-sink)"
-cargo build $CARGO_FLAG --features cpal-sink -p rockbox-cli
-cargo build $CARGO_FLAG -p rockbox-server
+cargo build $CARGO_FLAG -p rockbox-cli
+cargo build $CARGO_FLAG --features cpal-sink -p rockbox-server
 
 echo "==> Step 4: Link rockboxd with Zig (headless)"
 ZIG_EXTRA_ARGS=""
@@ -0,0 +1,47 @@
+const STORAGE_KEY = "rockbox_api_token";
+
+// A link such as `http://music.local:6062/?access_token=rbx_…` hands the UI
+// its token once; it is kept in localStorage and dropped from the URL.
+const fromUrl = new URLSearchParams(location.search).get("access_token");
+if (fromUrl) {
+  localStorage.setItem(STORAGE_KEY, fromUrl);
+  const url = new URL(location.href);
+  url.searchParams.delete("access_token");
+  history.replaceState(history.state, "", url);
+}
+
+export function getApiToken(): string | null {
+  return localStorage.getItem(STORAGE_KEY);
+}
+
+/** `Authorization` header for the stored token, if any. */
+export function authHeaders(): Record<string, string> {
+  const token = getApiToken();
+  return token ? { Authorization: `Bearer ${token}` } : {};
+}
+
+/**
+ * Called when rockboxd refuses a request: ask for a token (created with
+ * `rockboxd token create`) and reload with it. Asks at most once per page
+ * load so a batch of failing queries doesn't open a prompt each.
+ */
+let asked = false;
+export function requestApiToken(): void {
+  if (asked) {
+    return;
+  }
+  asked = true;
+  const token = window.prompt(
+    "This Rockbox server requires an API token. Paste one created with `rockboxd token create`:",
+    getApiToken() ?? ""
+  );
+  if (token === null) {
+    return;
+  }
+  if (token.trim()) {
+    localStorage.setItem(STORAGE_KEY, token.trim());
+  } else {
+    localStorage.removeItem(STORAGE_KEY);
+  }
+  location.reload();
+}
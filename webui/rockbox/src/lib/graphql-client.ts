@@ -1,11 +1,22 @@
-import { GraphQLClient } from "graphql-request";
+import { ClientError, GraphQLClient } from "graphql-request";
+import { authHeaders, requestApiToken } from "./api-token";
 
 const endpoint =
   process.env.NODE_ENV === "development"
     ? import.meta.env.VITE_APP_API_URL || "http://localhost:6062/graphql"
     : `${origin}/graphql`;
 
-export const graphqlClient = new GraphQLClient(endpoint);
+export const graphqlClient = new GraphQLClient(endpoint, {
+  headers: authHeaders,
+  responseMiddleware: (response) => {
+    if (
+      response instanceof ClientError &&
+      [401, 403].includes(response.response.status)
+    ) {
+      requestApiToken();
+    }
+  },
+});
 
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 export class TypedDocumentString<TResult, TVariables extends Record<string, unknown> = Record<string, never>> extends String {
@@ -55,9 +55,11 @@ export class HlsAudioController {
 
     if (Hls.isSupported()) {
       this.hls = new Hls({
-        // Try to stay close to the live edge.
-        liveSyncDuration: 4,
-        liveMaxLatencyDuration: 8,
+        // Stay close to the live edge: with LL-HLS parts the target
+        // latency comes from the playlist's PART-HOLD-BACK (~1.5 s), and
+        // playback speeds up slightly to catch up after a stall.
+        lowLatencyMode: true,
+        maxLiveSyncPlaybackRate: 1.05,
         // Don't burn CPU on backfill — we want to hear "now".
         backBufferLength: 30,
       });
@@ -1,11 +1,19 @@
 import { useEffect, useState } from "react";
 import { SubscriptionClient } from "subscriptions-transport-ws";
+import { getApiToken } from "./api-token";
 
-const wsEndpoint = (
-  process.env.NODE_ENV === "development"
+const wsUrl = new URL(
+  (process.env.NODE_ENV === "development"
     ? import.meta.env.VITE_APP_API_URL || "http://localhost:6062/graphql"
     : `${origin}/graphql`
-).replace(/^http/, "ws");
+  ).replace(/^http/, "ws")
+);
+// Browsers can't set headers on a WebSocket.
+const token = getApiToken();
+if (token) {
+  wsUrl.searchParams.set("access_token", token);
+}
+const wsEndpoint = wsUrl.toString();
 
 export const subscriptionClient = new SubscriptionClient(wsEndpoint, {
   reconnect: true,
@@ -40,7 +40,7 @@ pub fn build(b: *std.Build) void {
     // -Dheadless=true: link against build-headless/ (no SDL, cpal PCM sink,
     // statically-linked codecs). Requires:
     //   cd build-headless && make lib
-    //   cargo build --release --features cpal-sink -p rockbox-cli
+    //   cargo build --release --features cpal-sink -p rockbox-server
     const headless = b.option(bool, "headless", "Build headless target (no SDL, cpal PCM)") orelse false;
     // -Dfw-dir=../build-armhf: override the firmware build directory.
     // Defaults to build-headless (headless=true) or build-lib (headless=false).
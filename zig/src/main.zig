@@ -71,6 +71,10 @@ export fn rb_playlist_delete_track(index: c_int) c_int {
     return playlist.delete_track(index);
 }
 
+export fn rb_playlist_move_track(index: c_int, new_index: c_int) c_int {
+    return playlist.move_track(index, new_index);
+}
+
 export fn rb_playlist_insert_directory(dir: [*]const u8, position: c_int, queue: bool, recurse: bool) c_int {
     return playlist.insert_directory(dir, position, queue, recurse);
 }
@@ -55,6 +55,7 @@ extern fn playlist_insert_context_create(playlist: *PlaylistInfo, context: *Play
 extern fn playlist_insert_context_release(context: *PlaylistInsertContext) void;
 extern fn playlist_insert_context_add(context: *PlaylistInsertContext, filename: [*]const u8) c_int;
 extern fn playlist_delete(playlist: *PlaylistInfo, index: c_int) c_int;
+extern fn playlist_move(playlist: *PlaylistInfo, index: c_int, new_index: c_int) c_int;
 extern fn playlist_insert_track(playlist: *PlaylistInfo, filename: [*]const u8, position: c_int, queue: bool, sync: bool) c_int;
 extern fn playlist_insert_directory(playlist: *PlaylistInfo, dir: [*]const u8, position: c_int, queue: bool, recurse: bool) c_int;
 extern fn playlist_remove_all_tracks(playlist: *PlaylistInfo) c_int;
@@ -119,6 +120,11 @@ pub fn delete_track(index: c_int) c_int {
     return playlist_delete(playlist, index);
 }
 
+pub fn move_track(index: c_int, new_index: c_int) c_int {
+    const playlist = playlist_get_current();
+    return playlist_move(playlist, index, new_index);
+}
+
 pub fn insert_directory(dir: [*]const u8, position: c_int, queue: bool, recurse: bool) c_int {
     const playlist = playlist_get_current();
     return playlist_insert_directory(playlist, dir, position, queue, recurse);